@@ -201,6 +201,10 @@ pub struct AppState {
     /// running. This prevents a slow/stale workspace's load from clobbering a
     /// newer workspace that started loading mid-flight.
     pub plugin_load_generation: Arc<AtomicU64>,
+    /// Cancels the scheduled plugin run in flight for the current load.
+    /// Taken and flagged when a newer load supersedes it.
+    #[cfg(feature = "extism-plugins")]
+    pub plugin_scheduler: Mutex<Option<diaryx_extism::ScheduleCanceller>>,
     /// Active native watcher for external workspace filesystem edits.
     pub workspace_watcher: Mutex<Option<RecommendedWatcher>>,
     /// Active Apple security-scoped workspace access, when needed.
@@ -216,6 +220,8 @@ impl AppState {
             plugins_loaded_at: Mutex::new(None),
             plugins_ready: Arc::new(PluginsReady::new()),
             plugin_load_generation: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "extism-plugins")]
+            plugin_scheduler: Mutex::new(None),
             workspace_watcher: Mutex::new(None),
             #[cfg(any(target_os = "macos", target_os = "ios"))]
             workspace_access: Mutex::new(None),
@@ -1126,6 +1132,17 @@ fn workspace_plugins_dir<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
     Some(ws_path.join(".diaryx").join("plugins"))
 }
 
+/// Publisher trust store for installed plugins: pinned publisher keys and the
+/// plugins the user allowed to load unsigned. Lives in the app data dir so it
+/// is shared by every workspace.
+#[cfg(feature = "extism-plugins")]
+fn plugin_trust_store_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("trusted-publishers.json"))
+}
+
 #[cfg(feature = "extism-plugins")]
 fn make_plugin_storage(workspace_root: Option<PathBuf>) -> Arc<dyn diaryx_extism::PluginStorage> {
     match workspace_root {
@@ -1220,6 +1237,42 @@ fn make_plugin_secret_store<R: Runtime>(
     })
 }
 
+/// Deliver scheduled events to the plugins of a freshly loaded instance on a
+/// background thread, until `load_gen` moves past `my_gen` or the returned
+/// handle is cancelled.
+///
+/// Returns the handle that stops the thread and cancels the run in flight,
+/// or `None` if no loaded plugin declares a schedule.
+#[cfg(feature = "extism-plugins")]
+fn spawn_plugin_scheduler(
+    diaryx: Arc<Diaryx<TauriBaseFs>>,
+    workspace_root: &Path,
+    load_gen: Arc<AtomicU64>,
+    my_gen: u64,
+) -> Option<diaryx_extism::ScheduleCanceller> {
+    let manifests = diaryx.plugin_registry().get_all_manifests();
+    let (mut runner, errors) = diaryx_extism::ScheduleRunner::new(workspace_root, &manifests);
+    for err in &errors {
+        log::warn!("Skipping plugin schedule: {err}");
+    }
+    if runner.is_empty() {
+        return None;
+    }
+
+    let canceller = runner.canceller();
+    std::thread::spawn(move || {
+        runner.run(
+            || load_gen.load(Ordering::Acquire) == my_gen,
+            |plugin_id, event| {
+                futures_lite::future::block_on(
+                    diaryx.plugin_registry().emit_scheduled(plugin_id, event),
+                );
+            },
+        );
+    });
+    Some(canceller)
+}
+
 /// Load and register any third-party Extism WASM plugins from the workspace-local
 /// plugin directory (`{workspace_root}/.diaryx/plugins/`).
 ///
@@ -1258,9 +1311,10 @@ fn register_extism_plugins<R: Runtime, FS: diaryx_core::fs::AsyncFileSystem + 's
     let file_provider: Arc<dyn diaryx_extism::FileProvider> =
         app.state::<PluginAdapters>().file_provider.clone();
     let ws_bridge = Arc::new(diaryx_extism::TokioWebSocketBridge::new());
+    let storage = make_plugin_storage(workspace_root_opt.clone());
     let host_ctx = Arc::new(diaryx_extism::HostContext {
         fs,
-        storage: make_plugin_storage(workspace_root_opt.clone()),
+        storage: storage.clone(),
         secret_store: make_plugin_secret_store(app),
         event_emitter,
         plugin_id: String::new(),
@@ -1273,6 +1327,16 @@ fn register_extism_plugins<R: Runtime, FS: diaryx_core::fs::AsyncFileSystem + 's
         namespace_provider: Arc::new(TauriNamespaceProvider { app: app.clone() }),
         plugin_command_depth: 0,
         storage_quota_bytes: diaryx_extism::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: diaryx_extism::ResourceLimitPolicy::default(),
+        // Plugins installed through the app don't carry a signature sidecar
+        // yet; each one loads only if the user allowed it unsigned at install.
+        plugin_trust: diaryx_extism::PluginTrustPolicy {
+            trust_store: plugin_trust_store_path(app),
+            ..Default::default()
+        },
+        http_client: Arc::new(diaryx_extism::NetworkHttpClient),
+        clock: Arc::new(diaryx_extism::SystemClock),
+        access_log: Arc::new(diaryx_extism::AccessLogWriter::new(storage)),
     });
     let mut adapters = Vec::new();
     let t_load = std::time::Instant::now();
@@ -1881,6 +1945,15 @@ impl PluginAdapters {
             file_provider: Arc::new(TauriRequestFileProvider::new()),
         }
     }
+
+    /// Write the access records every loaded plugin still has buffered.
+    pub fn flush_access_logs(&self) {
+        if let Ok(adapters) = self.adapters.lock() {
+            for adapter in adapters.values() {
+                adapter.flush_access_log();
+            }
+        }
+    }
 }
 
 #[cfg(feature = "extism-plugins")]
@@ -1938,12 +2011,23 @@ pub async fn inspect_user_plugin<R: Runtime>(
 /// to extract the manifest, then clears the cached Diaryx instance so the
 /// plugin is picked up on the next `execute()` call.
 ///
+/// Registry installs pass the artifact's `publisher_key` and `signature`: the
+/// signature must cover these bytes under the guest manifest's ID and version,
+/// and the key must match the one pinned for the plugin (it is pinned on first
+/// install). The signature is stored next to the module so every later load
+/// is verified too. Without a signature the plugin is only installed if the
+/// user agreed to run it unsigned: `allow_unsigned` records that override for
+/// this plugin in the trust store, and installing without it fails.
+///
 /// Returns the plugin manifest as a JSON string.
 #[cfg(feature = "extism-plugins")]
 #[tauri::command]
 pub async fn install_user_plugin<R: Runtime>(
     app: AppHandle<R>,
     wasm_bytes: Vec<u8>,
+    allow_unsigned: bool,
+    publisher_key: Option<String>,
+    signature: Option<String>,
 ) -> Result<String, SerializableError> {
     use diaryx_core::plugin::Plugin;
 
@@ -2003,6 +2087,13 @@ pub async fn install_user_plugin<R: Runtime>(
         namespace_provider: Arc::new(diaryx_extism::NoopNamespaceProvider),
         plugin_command_depth: 0,
         storage_quota_bytes: diaryx_extism::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: diaryx_extism::ResourceLimitPolicy::default(),
+        plugin_trust: diaryx_extism::PluginTrustPolicy::default(),
+        http_client: Arc::new(diaryx_extism::NetworkHttpClient),
+        clock: Arc::new(diaryx_extism::SystemClock),
+        access_log: Arc::new(diaryx_extism::AccessLogWriter::new(Arc::new(
+            diaryx_extism::NoopStorage,
+        ))),
     });
 
     log::info!(
@@ -2041,6 +2132,34 @@ pub async fn install_user_plugin<R: Runtime>(
             })
         })?;
 
+    let signed = match (publisher_key, signature) {
+        (Some(publisher_key), Some(signature)) => {
+            let signed = diaryx_extism::ArtifactSignature {
+                plugin_id: plugin_id.clone(),
+                version: manifest.version.clone(),
+                sha256: diaryx_extism::signing::sha256_hex(&wasm_bytes),
+                publisher_key,
+                signature,
+            };
+            if let Err(e) = verify_publisher_signature(&app, &signed, &wasm_bytes) {
+                let _ = std::fs::remove_dir_all(&tmp_dir);
+                return Err(log_plugin_install_error(e));
+            }
+            Some(signed)
+        }
+        _ if allow_unsigned => None,
+        _ => {
+            let _ = std::fs::remove_dir_all(&tmp_dir);
+            return Err(log_plugin_install_error(SerializableError {
+                kind: "PluginError".to_string(),
+                message: format!(
+                    "Plugin '{plugin_id}' is not signed by its publisher and was not allowed to load unsigned"
+                ),
+                path: None,
+            }));
+        }
+    };
+
     // Persist WASM to {workspace_root}/.diaryx/plugins/{plugin_id}/plugin.wasm
     let base_dir = workspace_plugins_dir(&app).ok_or_else(|| {
         log_plugin_install_error(SerializableError {
@@ -2086,6 +2205,25 @@ pub async fn install_user_plugin<R: Runtime>(
         })?;
     let _ = std::fs::remove_dir_all(&tmp_dir);
 
+    // A signed install replaces any earlier unsigned override, so a later
+    // unsigned copy of this plugin is refused again.
+    match &signed {
+        Some(signed) => {
+            signed.write_to_dir(&plugins_dir).map_err(|e| {
+                log_plugin_install_error(SerializableError {
+                    kind: "IoError".to_string(),
+                    message: e.to_string(),
+                    path: Some(plugins_dir.clone()),
+                })
+            })?;
+            set_unsigned_override(&app, &plugin_id, false).map_err(log_plugin_install_error)?;
+        }
+        None => {
+            let _ = std::fs::remove_file(plugins_dir.join(diaryx_extism::signing::SIGNATURE_FILE));
+            set_unsigned_override(&app, &plugin_id, true).map_err(log_plugin_install_error)?;
+        }
+    }
+
     // Remove stale manifest.json cache so that the next plugin load re-reads
     // the manifest from the updated WASM binary instead of the old cache.
     let cached_manifest = plugins_dir.join("manifest.json");
@@ -2140,6 +2278,64 @@ pub async fn install_user_plugin<R: Runtime>(
     Ok(manifest_json)
 }
 
+/// Verify a registry signature over `wasm_bytes` and check its publisher key
+/// against the trust store, pinning it on first use.
+#[cfg(feature = "extism-plugins")]
+fn verify_publisher_signature<R: Runtime>(
+    app: &AppHandle<R>,
+    signed: &diaryx_extism::ArtifactSignature,
+    wasm_bytes: &[u8],
+) -> Result<(), SerializableError> {
+    let trust_error = |e: diaryx_extism::TrustError| SerializableError {
+        kind: "PluginError".to_string(),
+        message: e.to_string(),
+        path: None,
+    };
+    signed.verify(wasm_bytes).map_err(trust_error)?;
+    let policy = diaryx_extism::PluginTrustPolicy {
+        trust_store: plugin_trust_store_path(app),
+        ..Default::default()
+    };
+    // A key other than the pinned one is refused rather than silently re-pinned.
+    if let diaryx_extism::signing::TrustDecision::FirstUse =
+        policy.check_publisher(signed).map_err(trust_error)?
+    {
+        log::info!(
+            "[install_user_plugin] Pinned publisher key {} for '{}'",
+            signed.publisher_key,
+            signed.plugin_id
+        );
+    }
+    Ok(())
+}
+
+/// Record or withdraw the user's override letting `plugin_id` load unsigned.
+#[cfg(feature = "extism-plugins")]
+fn set_unsigned_override<R: Runtime>(
+    app: &AppHandle<R>,
+    plugin_id: &str,
+    allowed: bool,
+) -> Result<(), SerializableError> {
+    let path = plugin_trust_store_path(app).ok_or_else(|| SerializableError {
+        kind: "IoError".to_string(),
+        message: "App data directory unavailable — cannot update the plugin trust store"
+            .to_string(),
+        path: None,
+    })?;
+    let trust_error = |e: diaryx_extism::TrustError| SerializableError {
+        kind: "IoError".to_string(),
+        message: e.to_string(),
+        path: Some(path.clone()),
+    };
+    let mut store = diaryx_extism::TrustStore::load(&path).map_err(trust_error)?;
+    if allowed {
+        store.allow_unsigned(plugin_id);
+    } else if !store.revoke_unsigned(plugin_id) {
+        return Ok(());
+    }
+    store.save(&path).map_err(trust_error)
+}
+
 /// Uninstall a user plugin by ID.
 ///
 /// Deletes `{workspace_root}/.diaryx/plugins/{plugin_id}/` and clears the cached Diaryx instance.
@@ -2165,6 +2361,8 @@ pub async fn uninstall_user_plugin<R: Runtime>(
             path: Some(plugins_dir.clone()),
         })?;
     }
+    // A reinstall has to be allowed unsigned again.
+    set_unsigned_override(&app, &plugin_id, false)?;
 
     // Clear cached Diaryx so the plugin is no longer registered.
     let app_state = app.state::<AppState>();
@@ -2356,6 +2554,9 @@ pub async fn get_plugin_component_html<R: Runtime>(
 pub async fn install_user_plugin<R: Runtime>(
     _app: AppHandle<R>,
     _wasm_bytes: Vec<u8>,
+    _allow_unsigned: bool,
+    _publisher_key: Option<String>,
+    _signature: Option<String>,
 ) -> Result<String, SerializableError> {
     Err(SerializableError {
         kind: "Unsupported".to_string(),
@@ -2618,6 +2819,11 @@ async fn get_or_init_tauri_diaryx<R: Runtime>(
         .plugin_load_generation
         .fetch_add(1, Ordering::AcqRel)
         + 1;
+    // Stop the previous load's scheduler thread and its in-flight run.
+    #[cfg(feature = "extism-plugins")]
+    if let Some(scheduler) = acquire_lock(&app_state.plugin_scheduler)?.take() {
+        scheduler.cancel();
+    }
     log::info!(
         "[get_or_init] Basic instance cached: {:?} (gen={})",
         t0.elapsed(),
@@ -2746,7 +2952,7 @@ async fn get_or_init_tauri_diaryx<R: Runtime>(
 
             match acquire_lock(&app_state.diaryx) {
                 Ok(mut guard) => {
-                    *guard = Some(full_diaryx);
+                    *guard = Some(Arc::clone(&full_diaryx));
                 }
                 Err(e) => {
                     log::error!("[get_or_init:bg] Failed to cache full instance: {e:?}");
@@ -2756,6 +2962,13 @@ async fn get_or_init_tauri_diaryx<R: Runtime>(
             if let Ok(mut loaded_guard) = acquire_lock(&app_state.plugins_loaded_at) {
                 *loaded_guard = Some(SystemTime::now());
             }
+            if let Some(ref ws_path) = workspace_path {
+                let scheduler =
+                    spawn_plugin_scheduler(full_diaryx, ws_path, Arc::clone(&load_gen), my_gen);
+                if let Ok(mut scheduler_guard) = acquire_lock(&app_state.plugin_scheduler) {
+                    *scheduler_guard = scheduler;
+                }
+            }
             log::info!(
                 "[get_or_init:bg] Plugin loading complete: {:?} (gen={})",
                 t1.elapsed(),
@@ -6483,6 +6696,8 @@ mod tests {
                     ..PluginPermissions::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         )]);
         let defaults = PluginPermissions {
@@ -6540,6 +6755,8 @@ mod tests {
                     ..PluginPermissions::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         )]);
         let defaults = PluginPermissions {
@@ -6578,6 +6795,8 @@ mod tests {
                     ..PluginPermissions::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         )]);
 
@@ -6615,6 +6834,8 @@ mod tests {
                     ..PluginPermissions::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         )]);
 
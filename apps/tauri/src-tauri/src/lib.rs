@@ -242,6 +242,14 @@ pub fn run() {
             publish_commands::preview_to_namespace,
             publish_commands::unpublish_namespace,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, _event| {
+            // Tauri exits the process without dropping managed state, so
+            // buffered plugin access records would otherwise be lost.
+            #[cfg(feature = "extism-plugins")]
+            if let tauri::RunEvent::Exit = _event {
+                _app.state::<PluginAdapters>().flush_access_logs();
+            }
+        });
 }
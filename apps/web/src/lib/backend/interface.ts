@@ -138,6 +138,15 @@ export interface PluginInspection {
   requestedPermissions?: unknown;
 }
 
+export interface PluginInstallOptions {
+  /** Install even though the plugin has no publisher signature. */
+  allowUnsigned?: boolean;
+  /** Base64 ed25519 publisher key from the registry artifact. */
+  publisherKey?: string | null;
+  /** Base64 ed25519 signature from the registry artifact. */
+  signature?: string | null;
+}
+
 // ============================================================================
 // Backend Events
 // ============================================================================
@@ -489,8 +498,16 @@ export interface Backend {
   // Plugin Management (Tauri only)
   // =========================================================================
 
-  /** Install a user plugin from WASM bytes. Returns the manifest JSON string. */
-  installPlugin?(wasmBytes: Uint8Array): Promise<string>;
+  /**
+   * Install a user plugin from WASM bytes. Returns the manifest JSON string.
+   * Tauri verifies `signature` from the registry artifact against
+   * `publisherKey` and pins the key; plugins without a signature are refused
+   * unless `allowUnsigned` records the user's override for this plugin.
+   */
+  installPlugin?(
+    wasmBytes: Uint8Array,
+    options?: PluginInstallOptions,
+  ): Promise<string>;
   /** Inspect a user plugin from WASM bytes without installing it. */
   inspectPlugin?(wasmBytes: Uint8Array): Promise<PluginInspection>;
   /** Uninstall a user plugin by ID. */
@@ -13,6 +13,7 @@ import type {
   FileSystemEvent,
   FileSystemEventCallback,
   PluginInspection,
+  PluginInstallOptions,
 } from "./interface";
 
 import { BackendError } from "./interface";
@@ -814,7 +815,10 @@ export class TauriBackend implements Backend {
   // Plugin Management
   // =========================================================================
 
-  async installPlugin(wasmBytes: Uint8Array): Promise<string> {
+  async installPlugin(
+    wasmBytes: Uint8Array,
+    options?: PluginInstallOptions,
+  ): Promise<string> {
     const invoke = this.getInvoke();
     console.info("[TauriBackend] install_user_plugin invoked", {
       bytes: wasmBytes.byteLength,
@@ -822,6 +826,9 @@ export class TauriBackend implements Backend {
     try {
       const manifestJson = await invoke<string>("install_user_plugin", {
         wasmBytes: Array.from(wasmBytes),
+        allowUnsigned: options?.allowUnsigned ?? false,
+        publisherKey: options?.publisherKey ?? null,
+        signature: options?.signature ?? null,
       });
       console.info("[TauriBackend] install_user_plugin completed", {
         bytes: wasmBytes.byteLength,
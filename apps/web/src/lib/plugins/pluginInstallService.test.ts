@@ -385,9 +385,21 @@ describe("pluginInstallService", () => {
 
     await service.installLocalPlugin(new ArrayBuffer(4), "Spoiler");
 
-    expect(confirmSpy).toHaveBeenCalledTimes(1);
+    // Once for the permissions, once to allow the plugin to load unsigned.
+    expect(confirmSpy).toHaveBeenCalledTimes(2);
     expect(backend.inspectPlugin).toHaveBeenCalledTimes(1);
     expect(backend.installPlugin).toHaveBeenCalledTimes(1);
+    expect(backend.installPlugin.mock.calls[0]?.[1]).toEqual({ allowUnsigned: true });
+  });
+
+  it("does not install on Tauri when the unsigned load is declined", async () => {
+    const { backend, confirmSpy, service } = await loadPluginInstallService();
+    confirmSpy.mockReturnValueOnce(true).mockReturnValueOnce(false);
+
+    await service.installLocalPlugin(new ArrayBuffer(4), "Spoiler");
+
+    expect(confirmSpy).toHaveBeenCalledTimes(2);
+    expect(backend.installPlugin).not.toHaveBeenCalled();
   });
 
   it("skips browser install confirm when workspace frontmatter already grants permissions", async () => {
@@ -468,6 +480,42 @@ describe("pluginInstallService", () => {
     expect(backend.installPlugin).toHaveBeenCalledTimes(1);
   });
 
+  it("passes a signed registry artifact to Tauri without an unsigned override", async () => {
+    const { backend, confirmSpy, service } = await loadPluginInstallService();
+
+    await service.installRegistryPlugin({
+      id: "diaryx.spoiler",
+      name: "Spoiler",
+      version: "1.0.0",
+      summary: "Spoilers",
+      description: "Spoilers",
+      author: "Diaryx",
+      license: "MIT",
+      repository: null,
+      categories: [],
+      tags: [],
+      icon: null,
+      screenshots: [],
+      capabilities: [],
+      requested_permissions: null,
+      artifact: {
+        url: "https://app.diaryx.org/cdn/plugins/artifacts/diaryx.spoiler/1.0.0/plugin.wasm",
+        sha256: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+        size: 4,
+        published_at: "2026-03-14T00:00:00Z",
+        publisher_key: "publisher-key",
+        signature: "signature",
+      },
+    });
+
+    // Only the permission review; the signature replaces the unsigned prompt.
+    expect(confirmSpy).toHaveBeenCalledTimes(1);
+    expect(backend.installPlugin.mock.calls[0]?.[1]).toEqual({
+      publisherKey: "publisher-key",
+      signature: "signature",
+    });
+  });
+
   it("reports the failing Tauri install stage for registry plugins", async () => {
     const { backend, service } = await loadPluginInstallService();
     const consoleError = vi.spyOn(console, "error").mockImplementation(() => {});
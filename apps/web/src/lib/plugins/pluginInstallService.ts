@@ -18,7 +18,7 @@ import {
   type PluginPermissions,
 } from "@/models/stores/permissionStore.svelte";
 import { getPluginStore } from "@/models/stores/pluginStore.svelte";
-import type { RegistryPlugin } from "$lib/plugins/pluginRegistry";
+import type { PluginArtifact, RegistryPlugin } from "$lib/plugins/pluginRegistry";
 import { workspaceStore } from "@/models/stores/workspaceStore.svelte";
 import {
   clearInstalledPluginSource,
@@ -31,6 +31,10 @@ import {
   setPluginMetadata,
 } from "$lib/storage/localWorkspaceRegistry.svelte";
 import { deleteNamespace } from "$lib/namespace/namespaceService";
+import {
+  artifactSignature,
+  confirmUnsignedPluginLoad,
+} from "$lib/plugins/unsignedPlugins";
 
 const PERMISSION_LABELS: Record<PermissionType, string> = {
   read_files: "Read files",
@@ -130,19 +134,38 @@ async function platformInstall(
   bytes: ArrayBuffer,
   name?: string,
   expectedPluginId?: string,
+  artifact?: PluginArtifact,
 ): Promise<string | null> {
   if (isTauri()) {
     const backend: Backend = await getBackend();
     if (backend.installPlugin) {
+      // Signed registry artifacts are verified by the backend; only ask to
+      // override the signature check when there is nothing to verify.
+      const signed = artifactSignature(artifact);
+      if (
+        !signed &&
+        !confirmUnsignedPluginLoad(name ?? expectedPluginId ?? "This plugin")
+      ) {
+        console.info("[pluginInstallService] Unsigned plugin load declined", {
+          expectedPluginId: expectedPluginId ?? null,
+          fallbackName: name ?? null,
+        });
+        return null;
+      }
       const installContext = {
         expectedPluginId: expectedPluginId ?? null,
         fallbackName: name ?? null,
         bytes: bytes.byteLength,
+        signed: !!signed,
       };
       const manifestJson = await runPluginInstallStage(
         "install plugin through Tauri backend",
         installContext,
-        async () => await backend.installPlugin!(new Uint8Array(bytes)),
+        async () =>
+          await backend.installPlugin!(
+            new Uint8Array(bytes),
+            signed ?? { allowUnsigned: true },
+          ),
       );
       let installedId: string | null = null;
       try {
@@ -416,6 +439,7 @@ async function reviewAndInstall(
   bytes: ArrayBuffer,
   fallbackName?: string,
   expectedPluginId?: string,
+  artifact?: PluginArtifact,
 ): Promise<string | null> {
   const inspected = await inspectPluginForInstall(bytes);
   const pluginId = inspected.pluginId;
@@ -447,7 +471,12 @@ async function reviewAndInstall(
       pluginId,
       pluginName,
     });
-    return await platformInstall(bytes, fallbackName ?? pluginName, expectedPluginId);
+    return await platformInstall(
+      bytes,
+      fallbackName ?? pluginName,
+      expectedPluginId,
+      artifact,
+    );
   }
 
   const defaults = requested?.defaults ?? {};
@@ -496,6 +525,7 @@ async function reviewAndInstall(
     bytes,
     fallbackName ?? pluginName,
     expectedPluginId,
+    artifact,
   );
   console.info("[pluginInstallService] Plugin install finished", {
     ...installContext,
@@ -548,7 +578,12 @@ export async function installRegistryPlugin(
     },
     async () => await verifyRegistryArtifact(bytes, plugin.artifact.sha256),
   );
-  const installedPluginId = await reviewAndInstall(bytes, plugin.name, plugin.id);
+  const installedPluginId = await reviewAndInstall(
+    bytes,
+    plugin.name,
+    plugin.id,
+    plugin.artifact,
+  );
   setInstalledPluginSource(installedPluginId ?? plugin.id, "registry");
   await bootstrapLinkedWorkspaceSyncState().catch((error) => {
     console.warn("[pluginInstallService] Failed to bootstrap linked workspace sync state:", error);
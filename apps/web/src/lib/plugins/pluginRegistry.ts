@@ -15,6 +15,8 @@ export interface PluginArtifact {
   sha256: string;
   size: number;
   published_at: string | null;
+  publisher_key?: string | null;
+  signature?: string | null;
 }
 
 export interface RegistryUiEntry {
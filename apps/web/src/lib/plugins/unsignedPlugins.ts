@@ -0,0 +1,30 @@
+import type { PluginArtifact } from "$lib/plugins/pluginRegistry";
+
+/** Publisher signature carried by a registry artifact. */
+export interface ArtifactSignature {
+  publisherKey: string;
+  signature: string;
+}
+
+/**
+ * The artifact's publisher key and signature, or `null` when it has no
+ * complete signature (local plugin files never have one).
+ */
+export function artifactSignature(
+  artifact?: PluginArtifact | null,
+): ArtifactSignature | null {
+  if (!artifact?.publisher_key || !artifact.signature) return null;
+  return { publisherKey: artifact.publisher_key, signature: artifact.signature };
+}
+
+/**
+ * Ask the user to let a plugin load without a publisher signature. The
+ * desktop app refuses unsigned plugins, so local plugin files and registry
+ * artifacts without a signature each need this explicit per-plugin override.
+ */
+export function confirmUnsignedPluginLoad(name: string): boolean {
+  return window.confirm(
+    `"${name}" is not signed by its publisher, so Diaryx can't confirm who built it.\n\n` +
+      `Allow it to load unsigned?`,
+  );
+}
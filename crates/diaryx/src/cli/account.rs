@@ -1,10 +1,11 @@
-//! CLI handlers for account management (login, logout, whoami).
+//! CLI handlers for account management (login, logout, whoami, devices,
+//! access tokens).
 
 use std::io::{self, Write};
 
-use diaryx_core::auth::{AuthError, AuthService, Device};
+use diaryx_core::auth::{AccessToken, AuthError, AuthService, CreateAccessTokenRequest, Device};
 
-use super::args::DeviceCommands;
+use super::args::{AccountCommands, DeviceCommands, TokenCommands};
 use super::auth_client::FsAuthenticatedClient;
 use super::block_on;
 
@@ -183,23 +184,7 @@ fn handle_devices_list(service: &AuthService<FsAuthenticatedClient>, json: bool)
     match block_on(service.get_devices()) {
         Ok(devices) => {
             if json {
-                // `Device` is a fig type (not serde-serializable), so bridge
-                // through fig's JSON serializer into a `serde_json::Value` to
-                // keep `to_string_pretty` output identical.
-                let serialized = diaryx_core::fig::ToValue::to_value(&devices)
-                    .serialize_with(
-                        diaryx_core::fig::Format::Json,
-                        diaryx_core::fig::SerializeOptions::compact(),
-                    )
-                    .map_err(|e| e.to_string())
-                    .and_then(|json| {
-                        serde_json::from_str::<serde_json::Value>(json.trim_end())
-                            .map_err(|e| e.to_string())
-                    })
-                    .and_then(|value| {
-                        serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
-                    });
-                match serialized {
+                match to_pretty_json(&devices) {
                     Ok(output) => println!("{output}"),
                     Err(e) => {
                         eprintln!("✗ Failed to serialize devices: {e}");
@@ -266,6 +251,162 @@ fn handle_devices_remove(
     }
 }
 
+pub fn handle_account_command(command: AccountCommands) -> bool {
+    let Some(service) = build_service(None) else {
+        eprintln!("✗ Cannot determine config directory for auth storage");
+        return false;
+    };
+
+    match command {
+        AccountCommands::Tokens { command } => match command {
+            TokenCommands::List { json } => handle_tokens_list(&service, json),
+            TokenCommands::Create {
+                name,
+                scopes,
+                namespaces,
+                expires_in_days,
+                quiet,
+            } => {
+                let request = CreateAccessTokenRequest {
+                    name,
+                    scopes,
+                    namespace_ids: (!namespaces.is_empty()).then_some(namespaces),
+                    expires_in_days,
+                };
+                handle_tokens_create(&service, &request, quiet)
+            }
+            TokenCommands::Revoke { id } => handle_tokens_revoke(&service, &id),
+        },
+    }
+}
+
+fn handle_tokens_list(service: &AuthService<FsAuthenticatedClient>, json: bool) -> bool {
+    match block_on(service.list_access_tokens()) {
+        Ok(tokens) => {
+            if json {
+                match to_pretty_json(&tokens) {
+                    Ok(output) => println!("{output}"),
+                    Err(e) => {
+                        eprintln!("✗ Failed to serialize access tokens: {e}");
+                        return false;
+                    }
+                }
+            } else if tokens.is_empty() {
+                println!("No access tokens.");
+            } else {
+                println!("Access tokens:");
+                for token in &tokens {
+                    print_access_token(token);
+                }
+            }
+            true
+        }
+        Err(e) => {
+            print_auth_error(e);
+            false
+        }
+    }
+}
+
+fn handle_tokens_create(
+    service: &AuthService<FsAuthenticatedClient>,
+    request: &CreateAccessTokenRequest,
+    quiet: bool,
+) -> bool {
+    match block_on(service.create_access_token(request)) {
+        Ok(created) => {
+            if quiet {
+                println!("{}", created.token);
+            } else {
+                println!("✓ Created access token {}", created.access_token.id);
+                print_access_token(&created.access_token);
+                println!();
+                println!("{}", created.token);
+                println!();
+                println!("Copy this token now; it will not be shown again.");
+                println!("Set it as DIARYX_TOKEN to use it from the CLI.");
+            }
+            true
+        }
+        Err(e) => {
+            print_auth_error(e);
+            false
+        }
+    }
+}
+
+fn handle_tokens_revoke(service: &AuthService<FsAuthenticatedClient>, id: &str) -> bool {
+    match block_on(service.revoke_access_token(id)) {
+        Ok(()) => {
+            println!("✓ Revoked access token {id}");
+            true
+        }
+        Err(e) => {
+            print_auth_error(e);
+            false
+        }
+    }
+}
+
+fn print_access_token(token: &AccessToken) {
+    let namespaces = token
+        .namespace_ids
+        .as_ref()
+        .map(|ids| ids.join(", "))
+        .unwrap_or_else(|| "all namespaces".to_string());
+    println!(
+        "  {} ({}) - {} on {}; expires {}; last used {}",
+        token.name,
+        token.id,
+        token.scopes.join(", "),
+        namespaces,
+        format_timestamp(token.expires_at, "never"),
+        format_timestamp(token.last_used_at, "never"),
+    );
+}
+
+/// Describe a unix timestamp relative to now, e.g. `in 30d` or `2h ago`.
+fn format_timestamp(timestamp: Option<i64>, missing: &str) -> String {
+    let Some(timestamp) = timestamp else {
+        return missing.to_string();
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let delta = timestamp - now;
+    let secs = delta.unsigned_abs();
+    let amount = match secs {
+        0..60 => format!("{secs}s"),
+        60..3_600 => format!("{}m", secs / 60),
+        3_600..86_400 => format!("{}h", secs / 3_600),
+        _ => format!("{}d", secs / 86_400),
+    };
+    if delta >= 0 {
+        format!("in {amount}")
+    } else {
+        format!("{amount} ago")
+    }
+}
+
+/// Render a fig type as pretty JSON.
+///
+/// Auth types are fig types (not serde-serializable), so bridge through fig's
+/// JSON serializer into a `serde_json::Value` to keep `to_string_pretty`
+/// output identical.
+fn to_pretty_json<T: diaryx_core::fig::ToValue>(value: &T) -> Result<String, String> {
+    diaryx_core::fig::ToValue::to_value(value)
+        .serialize_with(
+            diaryx_core::fig::Format::Json,
+            diaryx_core::fig::SerializeOptions::compact(),
+        )
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            serde_json::from_str::<serde_json::Value>(json.trim_end()).map_err(|e| e.to_string())
+        })
+        .and_then(|value| serde_json::to_string_pretty(&value).map_err(|e| e.to_string()))
+}
+
 fn print_auth_error(error: AuthError) {
     if error.is_session_expired() {
         eprintln!("✗ Session expired. Run `diaryx login <email>` to sign in again.");
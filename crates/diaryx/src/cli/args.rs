@@ -273,6 +273,12 @@ pub enum Commands {
         command: DeviceCommands,
     },
 
+    /// Manage account credentials (personal access tokens)
+    Account {
+        #[command(subcommand)]
+        command: AccountCommands,
+    },
+
     /// Manage server namespaces (list, delete)
     #[command(aliases = ["ns"])]
     Namespace {
@@ -1071,6 +1077,56 @@ pub enum DeviceCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum AccountCommands {
+    /// Manage personal access tokens for unattended use (e.g. CI publishing)
+    #[command(alias = "token")]
+    Tokens {
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommands {
+    /// List personal access tokens
+    #[command(alias = "ls")]
+    List {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create a personal access token (the secret is shown once)
+    Create {
+        /// Label for the token
+        name: String,
+
+        /// Scope to grant: read, publish, or manage (repeatable)
+        #[arg(long = "scope", default_value = "publish")]
+        scopes: Vec<String>,
+
+        /// Restrict the token to a namespace (repeatable; default: all)
+        #[arg(long = "namespace")]
+        namespaces: Vec<String>,
+
+        /// Expire the token after this many days (default: never)
+        #[arg(long)]
+        expires_in_days: Option<u32>,
+
+        /// Print only the token secret
+        #[arg(long)]
+        quiet: bool,
+    },
+
+    /// Revoke a personal access token
+    #[command(aliases = ["delete", "rm"])]
+    Revoke {
+        /// Token ID
+        id: String,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum NamespaceObjectCommands {
     /// List objects in a namespace
@@ -1147,7 +1203,14 @@ pub enum PluginCommands {
     /// Install a plugin from the registry
     Install {
         /// Canonical plugin ID (for example: "diaryx.sync")
-        id: String,
+        #[arg(required_unless_present = "locked")]
+        id: Option<String>,
+        /// Install the exact builds pinned in the workspace lockfile (all
+        /// locked plugins, or only ID if given) instead of the latest release
+        #[arg(long)]
+        locked: bool,
+        #[command(flatten)]
+        trust: PluginTrustArgs,
     },
     /// Remove an installed plugin
     #[command(alias = "rm")]
@@ -1178,10 +1241,12 @@ pub enum PluginCommands {
         #[arg(long)]
         json: bool,
     },
-    /// Update installed plugins to latest versions
+    /// Update installed plugins to latest versions and rewrite the lockfile
     Update {
         /// Specific plugin ID to update (updates all if omitted)
         id: Option<String>,
+        #[command(flatten)]
+        trust: PluginTrustArgs,
     },
     /// Show details about an installed plugin
     Info {
@@ -1203,4 +1268,62 @@ pub enum PluginCommands {
         /// Plugin ID
         id: String,
     },
+    /// Summarize what a plugin accessed and flag unused permissions
+    ///
+    /// Requires `audit: true` under the plugin's entry in the workspace config.
+    Audit {
+        /// Plugin ID
+        id: String,
+        /// Emit JSON output
+        #[arg(long)]
+        json: bool,
+        /// Delete the plugin's access log instead of reporting it
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Deliver due scheduled plugin events once and exit
+    ///
+    /// Meant to be run every minute from cron or a systemd timer.
+    Tick,
+    /// Manage pinned plugin publisher keys
+    Trust {
+        #[command(subcommand)]
+        command: PluginTrustCommands,
+    },
+}
+
+/// Overrides for plugin signature checks.
+#[cfg(feature = "plugins")]
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+pub struct PluginTrustArgs {
+    /// Accept plugins that carry no publisher signature
+    #[arg(long, env = "DIARYX_ALLOW_UNSIGNED_PLUGINS")]
+    pub allow_unsigned: bool,
+    /// Accept a publisher key that differs from the pinned one, and re-pin it
+    #[arg(long, env = "DIARYX_ALLOW_REKEYED_PLUGINS")]
+    pub allow_rekeyed: bool,
+}
+
+#[cfg(feature = "plugins")]
+#[derive(Subcommand)]
+pub enum PluginTrustCommands {
+    /// List pinned publisher keys
+    #[command(alias = "ls")]
+    List {
+        /// Emit JSON output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Pin a publisher key for a plugin, replacing any existing pin
+    Pin {
+        /// Canonical plugin ID
+        id: String,
+        /// Base64 ed25519 publisher public key
+        key: String,
+    },
+    /// Forget the pinned publisher key for a plugin
+    Forget {
+        /// Canonical plugin ID
+        id: String,
+    },
 }
@@ -5,6 +5,9 @@
 //! private to this module — the only way out is [`FsAuthenticatedClient::export_bearer_token`],
 //! which exists solely for handoff to guest plugin runtimes that need to make
 //! their own authenticated HTTP calls.
+//!
+//! For unattended use (CI), a personal access token in `DIARYX_TOKEN` takes
+//! precedence over the stored session and is never written to disk.
 
 use std::io::{Error, ErrorKind};
 use std::path::{Path, PathBuf};
@@ -18,6 +21,12 @@ use diaryx_core::fig;
 use diaryx_core::frontmatter::{parse_typed, serialize_typed};
 use diaryx_native::NativeConfigExt;
 
+/// Environment variable holding a personal access token for unattended use.
+pub const TOKEN_ENV: &str = "DIARYX_TOKEN";
+
+/// Environment variable overriding the server URL when no `--server` is given.
+pub const SERVER_ENV: &str = "DIARYX_SERVER";
+
 /// On-disk auth file format (private to this module).
 #[derive(Debug, Clone, diaryx_core::fig::ToValue, diaryx_core::fig::FromValue)]
 struct AuthFile {
@@ -52,6 +61,8 @@ pub struct FsAuthenticatedClient {
     auth_path: PathBuf,
     agent: ureq::Agent,
     state: Mutex<AuthFile>,
+    /// Personal access token overriding the stored session (not persisted).
+    access_token: Option<String>,
 }
 
 impl FsAuthenticatedClient {
@@ -81,18 +92,31 @@ impl FsAuthenticatedClient {
             auth_path,
             agent,
             state: Mutex::new(state),
+            access_token: None,
         }
     }
 
+    /// Authenticate with a personal access token instead of the stored
+    /// session. The token is only held in memory.
+    pub fn with_access_token(mut self, token: impl Into<String>) -> Self {
+        self.access_token = Some(token.into());
+        self
+    }
+
     /// Construct a client at the default auth path (`~/.config/diaryx/auth.md`).
     ///
-    /// The server URL is resolved from `server_override`, then from stored
-    /// state on disk, then from [`DEFAULT_SYNC_SERVER`].
+    /// The server URL is resolved from `server_override`, then `DIARYX_SERVER`,
+    /// then from stored state on disk, then from [`DEFAULT_SYNC_SERVER`]. A
+    /// non-empty `DIARYX_TOKEN` is used in place of the stored session.
     pub fn from_default_path(server_override: Option<&str>) -> Option<Self> {
         let auth_path = Config::config_path()?.with_file_name("auth.md");
 
         let stored = Self::read_file(&auth_path);
+        let server_env = std::env::var(SERVER_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty());
         let server_url = server_override
+            .or(server_env.as_deref())
             .map(|s| s.trim_end_matches('/').to_string())
             .or_else(|| {
                 stored
@@ -101,7 +125,11 @@ impl FsAuthenticatedClient {
             })
             .unwrap_or_else(|| DEFAULT_SYNC_SERVER.to_string());
 
-        Some(Self::new(server_url, auth_path))
+        let client = Self::new(server_url, auth_path);
+        match std::env::var(TOKEN_ENV) {
+            Ok(token) if !token.trim().is_empty() => Some(client.with_access_token(token.trim())),
+            _ => Some(client),
+        }
     }
 
     /// Path to the auth file this client persists to.
@@ -116,6 +144,14 @@ impl FsAuthenticatedClient {
     /// or WASM sandbox boundary). Returns `None` when no session is active.
     #[allow(dead_code)] // only used when plugins feature is enabled
     pub fn export_bearer_token(&self) -> Option<String> {
+        self.active_token()
+    }
+
+    /// The bearer token to send: the access token if set, else the session.
+    fn active_token(&self) -> Option<String> {
+        if let Some(token) = &self.access_token {
+            return Some(token.clone());
+        }
         self.state.lock().ok()?.session_token.clone()
     }
 
@@ -176,8 +212,7 @@ impl FsAuthenticatedClient {
     }
 
     fn bearer_header(&self) -> Option<String> {
-        let state = self.state.lock().ok()?;
-        state.session_token.as_ref().map(|t| format!("Bearer {t}"))
+        self.active_token().map(|t| format!("Bearer {t}"))
     }
 
     fn finish(
@@ -254,11 +289,7 @@ impl AuthenticatedClient for FsAuthenticatedClient {
     }
 
     async fn has_session(&self) -> bool {
-        self.state
-            .lock()
-            .ok()
-            .and_then(|s| s.session_token.clone())
-            .is_some()
+        self.active_token().is_some()
     }
 
     async fn load_metadata(&self) -> Option<AuthMetadata> {
@@ -370,6 +401,24 @@ mod tests {
         assert_eq!(reopened.export_bearer_token(), None);
     }
 
+    #[test]
+    fn access_token_overrides_stored_session_without_persisting() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("auth.md");
+        std::fs::write(&path, sample_auth_md()).unwrap();
+
+        let client = FsAuthenticatedClient::new("https://sync.example.com".into(), path.clone())
+            .with_access_token("dxp_ci");
+        assert!(futures_lite::future::block_on(client.has_session()));
+        assert_eq!(client.export_bearer_token().as_deref(), Some("dxp_ci"));
+        assert_eq!(client.bearer_header().as_deref(), Some("Bearer dxp_ci"));
+
+        futures_lite::future::block_on(client.clear_session());
+        assert_eq!(client.export_bearer_token().as_deref(), Some("dxp_ci"));
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("dxp_ci"));
+    }
+
     #[test]
     fn save_and_load_metadata() {
         let dir = tempdir().unwrap();
@@ -20,6 +20,11 @@ pub async fn handle_edit(workspace_root: &Path, url: Option<String>, port: Optio
         eprintln!("[edit-server] Plugin '{}' failed to init: {}", id.0, err);
     }
 
+    // Deliver scheduled plugin events while the server is up.
+    #[cfg(feature = "plugins")]
+    let scheduler =
+        super::plugin_scheduler::spawn_edit_server_scheduler(diaryx.clone(), workspace_root);
+
     // Bind to the requested port (or auto-select)
     let addr = format!("127.0.0.1:{}", port.unwrap_or(0));
     let listener = match TcpListener::bind(&addr).await {
@@ -68,6 +73,16 @@ pub async fn handle_edit(workspace_root: &Path, url: Option<String>, port: Optio
         .await
         .unwrap();
 
+    #[cfg(feature = "plugins")]
+    if let Some(scheduler) = scheduler {
+        scheduler.cancel();
+    }
+
+    // Lets each plugin wind down and writes out its buffered access records.
+    if let Err(e) = diaryx.plugin_registry().shutdown_all().await {
+        eprintln!("[edit-server] Plugin shutdown failed: {e}");
+    }
+
     println!("\nLocal edit server stopped.");
     true
 }
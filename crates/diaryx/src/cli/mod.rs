@@ -73,6 +73,10 @@ mod plugin_manager;
 #[cfg(feature = "plugins")]
 mod plugin_dispatch;
 
+/// Scheduled plugin events (`plugin tick` and the edit-server loop)
+#[cfg(feature = "plugins")]
+mod plugin_scheduler;
+
 /// Shared CLI utilities
 mod util;
 
@@ -311,6 +315,7 @@ fn dispatch_core_command(cli: Cli) -> bool {
         Commands::Logout => account::handle_logout(),
         Commands::Whoami => account::handle_whoami(),
         Commands::Devices { command } => account::handle_devices_command(command),
+        Commands::Account { command } => account::handle_account_command(command),
 
         Commands::Namespace { command } => namespace::handle_namespace_command(command),
 
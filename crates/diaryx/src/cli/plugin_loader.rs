@@ -554,11 +554,14 @@ fn build_runtime_context_from_sources(
 /// - Explicit frontmatter allow/deny is always respected.
 /// - Unconfigured permissions trigger a one-time interactive prompt.
 /// - Decisions are cached for the process lifetime.
+/// - Cross-plugin calls (`execute_commands`) are prompted for, and cached,
+///   per target plugin.
 pub struct CliPermissionChecker {
     inner: FrontmatterPermissionChecker,
     /// Session cache: (plugin_id, perm_key) → allowed.
     /// Keyed by permission type only (not target) so one "allow read_files"
-    /// covers all paths for the rest of the process.
+    /// covers all paths for the rest of the process. `execute_commands` is
+    /// keyed by the called plugin too (see [`Self::cache_scope`]).
     cache: Mutex<HashMap<(String, String), bool>>,
 }
 
@@ -570,18 +573,38 @@ impl CliPermissionChecker {
         }
     }
 
+    /// What a session decision covers: the permission type, plus the called
+    /// plugin for `execute_commands` (whose target is `plugin_id:command`).
+    fn cache_scope(permission_type: PermissionType, target: &str) -> String {
+        match permission_type {
+            PermissionType::ExecuteCommands => {
+                let called = target.split_once(':').map_or(target, |(plugin, _)| plugin);
+                format!("{}:{called}", permission_type.key())
+            }
+            _ => permission_type.key().to_string(),
+        }
+    }
+
     /// Prompt the user on a TTY, or deny silently if stdin is not interactive.
-    fn prompt(plugin_id: &str, permission_type: PermissionType) -> bool {
+    fn prompt(plugin_id: &str, permission_type: PermissionType, target: &str) -> bool {
         use std::io::IsTerminal;
         if !std::io::stdin().is_terminal() {
             return false;
         }
 
-        eprint!(
-            "Plugin \x1b[1m{}\x1b[0m requests \x1b[1m{}\x1b[0m permission. Allow? [Y/n] ",
-            plugin_id,
-            permission_type.key(),
-        );
+        if permission_type == PermissionType::ExecuteCommands {
+            let (called, command) = target.split_once(':').unwrap_or((target, ""));
+            eprint!(
+                "Plugin \x1b[1m{}\x1b[0m wants to call \x1b[1m{}\x1b[0m on plugin \x1b[1m{}\x1b[0m. Allow? [Y/n] ",
+                plugin_id, command, called,
+            );
+        } else {
+            eprint!(
+                "Plugin \x1b[1m{}\x1b[0m requests \x1b[1m{}\x1b[0m permission. Allow? [Y/n] ",
+                plugin_id,
+                permission_type.key(),
+            );
+        }
         let _ = std::io::stderr().flush();
 
         let mut input = String::new();
@@ -618,7 +641,8 @@ impl PermissionChecker for CliPermissionChecker {
         }
 
         // Check session cache.
-        let cache_key = (plugin_id.to_string(), permission_type.key().to_string());
+        let scope = Self::cache_scope(permission_type, target);
+        let cache_key = (plugin_id.to_string(), scope.clone());
         {
             let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
             if let Some(&allowed) = cache.get(&cache_key) {
@@ -627,15 +651,14 @@ impl PermissionChecker for CliPermissionChecker {
                 } else {
                     Err(format!(
                         "Permission denied (session) for plugin '{}': {}",
-                        plugin_id,
-                        permission_type.key()
+                        plugin_id, scope
                     ))
                 };
             }
         }
 
         // Interactive prompt.
-        let allowed = Self::prompt(plugin_id, permission_type);
+        let allowed = Self::prompt(plugin_id, permission_type, target);
         {
             let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
             cache.insert(cache_key, allowed);
@@ -646,11 +669,32 @@ impl PermissionChecker for CliPermissionChecker {
         } else {
             Err(format!(
                 "Permission denied by user for plugin '{}': {}",
-                plugin_id,
-                permission_type.key()
+                plugin_id, scope
             ))
         }
     }
+
+    fn resource_limits(
+        &self,
+        plugin_id: &str,
+    ) -> Option<diaryx_core::plugin::limits::PluginResourceLimits> {
+        self.inner.resource_limits(plugin_id)
+    }
+
+    fn plugin_lock(&self, plugin_id: &str) -> Option<diaryx_core::plugin::lockfile::LockedPlugin> {
+        self.inner.plugin_lock(plugin_id)
+    }
+
+    fn granted_permissions(
+        &self,
+        plugin_id: &str,
+    ) -> Option<diaryx_core::plugin::permissions::PluginPermissions> {
+        self.inner.granted_permissions(plugin_id)
+    }
+
+    fn access_log_enabled(&self, plugin_id: &str) -> bool {
+        self.inner.access_log_enabled(plugin_id)
+    }
 }
 
 /// CLI event emitter — logs plugin events to stderr.
@@ -1031,7 +1075,7 @@ fn create_host_context(
 
     Arc::new(HostContext {
         fs: Arc::new(fs),
-        storage,
+        storage: storage.clone(),
         secret_store: Arc::new(diaryx_extism::FilePluginSecretStore::new(
             workspace_root.join(".diaryx").join("plugin-secrets"),
         )),
@@ -1048,6 +1092,13 @@ fn create_host_context(
         namespace_provider: Arc::new(CliNamespaceProvider::new()),
         plugin_command_depth: 0,
         storage_quota_bytes: diaryx_extism::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: diaryx_extism::ResourceLimitPolicy::default(),
+        plugin_trust: super::plugin_manager::plugin_trust_policy(
+            super::plugin_manager::trust_args_from_env(),
+        ),
+        http_client: Arc::new(diaryx_extism::NetworkHttpClient),
+        clock: Arc::new(diaryx_extism::SystemClock),
+        access_log: Arc::new(diaryx_extism::AccessLogWriter::new(storage)),
     })
 }
 
@@ -1057,10 +1108,36 @@ fn load_plugin(workspace_root: &Path, plugin_id: &str) -> Result<Arc<ExtismPlugi
     let ws_bridge = Arc::new(TokioWebSocketBridge::new());
     let host_context = create_host_context(workspace_root, plugin_id, ws_bridge.clone());
 
+    let plugin_dir = wasm_path.parent().unwrap_or(Path::new("."));
+    let signature = host_context
+        .plugin_trust
+        .verify_installed(plugin_dir, plugin_id)
+        .map_err(|e| {
+            format!(
+                "Refusing to load plugin '{plugin_id}': {e}\n\
+                 Set DIARYX_ALLOW_UNSIGNED_PLUGINS=1 or DIARYX_ALLOW_REKEYED_PLUGINS=1 to override."
+            )
+        })?;
+
     let plugin = Arc::new(
-        load_plugin_from_wasm(&wasm_path, host_context, None)
+        load_plugin_from_wasm(&wasm_path, host_context.clone(), None)
             .map_err(|e| format!("Failed to load plugin '{}': {}", plugin_id, e))?,
     );
+    // `load_plugin_from_wasm` has already compared the module with the
+    // workspace lockfile and warned about any drift.
+    let manifest = plugin.manifest();
+    if let Some(signature) = signature {
+        if signature.plugin_id != manifest.id.0 || signature.version != manifest.version {
+            return Err(format!(
+                "Refusing to load plugin '{plugin_id}': its signature covers {}@{} but the module is {}@{}",
+                signature.plugin_id, signature.version, manifest.id, manifest.version
+            ));
+        }
+        host_context
+            .plugin_trust
+            .check_publisher(&signature)
+            .map_err(|e| format!("Refusing to load plugin '{plugin_id}': {e}"))?;
+    }
 
     let plugin_bridge: Arc<dyn diaryx_extism::SyncGuestBridge> = plugin.clone();
     ws_bridge.set_guest_bridge(Arc::downgrade(&plugin_bridge));
@@ -1109,9 +1186,10 @@ pub(super) fn register_edit_server_plugins<FS: diaryx_core::fs::AsyncFileSystem
     let fs: Arc<dyn diaryx_core::fs::AsyncFileSystem> =
         Arc::new(SyncToAsyncFs::new(RealFileSystem));
     let ws_bridge = Arc::new(TokioWebSocketBridge::new());
+    let storage = Arc::new(CliPluginStorage::new(workspace_root));
     let host_ctx = Arc::new(HostContext {
         fs,
-        storage: Arc::new(CliPluginStorage::new(workspace_root)),
+        storage: storage.clone(),
         secret_store: Arc::new(diaryx_extism::FilePluginSecretStore::new(
             workspace_root.join(".diaryx").join("plugin-secrets"),
         )),
@@ -1128,6 +1206,13 @@ pub(super) fn register_edit_server_plugins<FS: diaryx_core::fs::AsyncFileSystem
         namespace_provider: Arc::new(CliNamespaceProvider::new()),
         plugin_command_depth: 0,
         storage_quota_bytes: diaryx_extism::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: diaryx_extism::ResourceLimitPolicy::default(),
+        plugin_trust: super::plugin_manager::plugin_trust_policy(
+            super::plugin_manager::trust_args_from_env(),
+        ),
+        http_client: Arc::new(diaryx_extism::NetworkHttpClient),
+        clock: Arc::new(diaryx_extism::SystemClock),
+        access_log: Arc::new(diaryx_extism::AccessLogWriter::new(storage)),
     });
 
     let mut result = HashMap::new();
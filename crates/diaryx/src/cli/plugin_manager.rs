@@ -1,4 +1,4 @@
-//! Plugin management commands — install, remove, list, search, update, info.
+//! Plugin management commands — install, remove, list, search, update, info, audit, tick.
 //!
 //! Downloads plugins from the Diaryx CDN `registry.md` and manages the
 //! local plugin directory at `~/.diaryx/plugins/`.
@@ -8,12 +8,25 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use diaryx_core::fs::SyncToAsyncFs;
+use diaryx_core::plugin::lockfile::{LockDrift, LockedPlugin, PluginLockfile};
 use diaryx_core::plugin::manifest::{MarketplaceEntry, MarketplaceRegistry};
-use diaryx_extism::{HostContext, inspect_plugin_wasm_manifest, load_plugin_from_wasm};
+use diaryx_core::plugin::permissions::{PermissionCheck, check_command_permission};
+use diaryx_core::plugin::services::{
+    ServiceProvision, ServiceRequirement, ServiceResolution, best_provider,
+};
+use diaryx_core::plugin::{PluginCapability, PluginId, PluginManifest};
+use diaryx_core::workspace::Workspace;
+use diaryx_extism::protocol::GuestManifest;
+use diaryx_extism::signing::{
+    ArtifactSignature, PinOrigin, PluginTrustPolicy, SIGNATURE_FILE, TrustDecision, TrustError,
+    TrustStore,
+};
+use diaryx_extism::{AccessLog, HostContext, inspect_plugin_wasm_manifest, load_plugin_from_wasm};
 use diaryx_native::RealFileSystem;
 use sha2::{Digest, Sha256};
 
-use crate::cli::args::PluginCommands;
+use super::plugin_storage::CliPluginStorage;
+use crate::cli::args::{PluginCommands, PluginTrustArgs, PluginTrustCommands};
 
 const REGISTRY_URL: &str = "https://app.diaryx.org/cdn/plugins/registry.md";
 
@@ -68,7 +81,13 @@ pub fn handle_plugin_command(command: PluginCommands) {
             },
             json,
         ),
-        PluginCommands::Install { id } => handle_install(&id),
+        PluginCommands::Install { id, locked, trust } => {
+            if locked {
+                handle_install_locked(id.as_deref(), trust);
+            } else if let Some(id) = id {
+                handle_install(&id, trust);
+            }
+        }
         PluginCommands::Remove { id, yes } => handle_remove(&id, yes),
         PluginCommands::Search {
             query,
@@ -87,10 +106,44 @@ pub fn handle_plugin_command(command: PluginCommands) {
             },
             json,
         ),
-        PluginCommands::Update { id } => handle_update(id.as_deref()),
+        PluginCommands::Update { id, trust } => handle_update(id.as_deref(), trust),
         PluginCommands::Info { id, json } => handle_info(&id, json),
         PluginCommands::Dev { id, wasm_path } => handle_dev(&id, &wasm_path),
         PluginCommands::Undev { id } => handle_undev(&id),
+        PluginCommands::Audit { id, json, clear } => handle_audit(&id, json, clear),
+        PluginCommands::Tick => super::plugin_scheduler::handle_tick(),
+        PluginCommands::Trust { command } => handle_trust(command),
+    }
+}
+
+/// Path of the publisher trust store, shared by every workspace on this machine.
+fn trust_store_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("diaryx").join("trusted-publishers.json"))
+}
+
+/// Read the signature overrides from the environment, for code paths (such as
+/// plugin-provided commands) that don't take the `--allow-*` flags.
+pub(crate) fn trust_args_from_env() -> PluginTrustArgs {
+    let enabled = |name: &str| {
+        std::env::var(name).is_ok_and(|v| {
+            !matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "" | "0" | "false" | "no" | "off"
+            )
+        })
+    };
+    PluginTrustArgs {
+        allow_unsigned: enabled("DIARYX_ALLOW_UNSIGNED_PLUGINS"),
+        allow_rekeyed: enabled("DIARYX_ALLOW_REKEYED_PLUGINS"),
+    }
+}
+
+/// The trust policy installed plugins are checked against.
+pub(crate) fn plugin_trust_policy(args: PluginTrustArgs) -> PluginTrustPolicy {
+    PluginTrustPolicy {
+        trust_store: trust_store_path(),
+        allow_unsigned: args.allow_unsigned,
+        allow_rekeyed: args.allow_rekeyed,
     }
 }
 
@@ -430,7 +483,7 @@ fn handle_list(filters: DiscoveryFilters, json: bool) {
 }
 
 /// Install a plugin from the registry.
-fn handle_install(id: &str) {
+fn handle_install(id: &str, trust: PluginTrustArgs) {
     if id == "--defaults" {
         eprintln!(
             "'--defaults' was removed. Install plugins by canonical ID (for example: diaryx.sync)."
@@ -460,13 +513,363 @@ fn handle_install(id: &str) {
         return;
     };
 
-    if let Err(err) = install_plugin(plugin) {
+    let policy = plugin_trust_policy(trust);
+    if let Err(err) = install_plugin(plugin, &policy) {
         eprintln!("Failed to install '{}': {err}", plugin.id);
+        return;
+    }
+
+    let installed = install_service_providers(plugin, &registry, &policy);
+    grant_service_permissions(&installed);
+
+    update_lockfile(|lockfile, root_index| {
+        for plugin in &installed {
+            lockfile.lock(&plugin.id, lock_entry(plugin, root_index));
+        }
+    });
+}
+
+/// Offer to install registry plugins that provide the services a freshly
+/// installed plugin requires, recursing into their own requirements.
+/// Returns every plugin installed, `plugin` first.
+fn install_service_providers<'r>(
+    plugin: &'r MarketplaceEntry,
+    registry: &'r MarketplaceRegistry,
+    policy: &PluginTrustPolicy,
+) -> Vec<&'r MarketplaceEntry> {
+    let mut installed = vec![plugin];
+    let mut skipped: HashSet<(String, String)> = HashSet::new();
+
+    loop {
+        let resolution = ServiceResolution::resolve(&installed_service_manifests());
+        let Some(missing) = resolution.missing.iter().find(|missing| {
+            installed.iter().any(|p| p.id == missing.consumer.0)
+                && !skipped.contains(&(
+                    missing.consumer.0.clone(),
+                    missing.requirement.interface.clone(),
+                ))
+        }) else {
+            break;
+        };
+        skipped.insert((
+            missing.consumer.0.clone(),
+            missing.requirement.interface.clone(),
+        ));
+
+        eprintln!("Note: {missing}.");
+        let Some(provider) = registry_provider(registry, &missing.requirement) else {
+            eprintln!("No plugin in the registry provides it.");
+            continue;
+        };
+        let question = format!(
+            "Install {} ({}) v{} to provide '{}'?",
+            provider.name, provider.id, provider.version, missing.requirement.interface
+        );
+        if !confirm(&question, !missing.requirement.optional) {
+            eprintln!(
+                "Install it later with: diaryx plugin install {}",
+                provider.id
+            );
+            continue;
+        }
+        match install_plugin(provider, policy) {
+            Ok(()) => installed.push(provider),
+            Err(err) => eprintln!("Failed to install '{}': {err}", provider.id),
+        }
+    }
+
+    installed
+}
+
+/// The registry plugin offering the highest version of the interface that
+/// satisfies `requirement`.
+fn registry_provider<'r>(
+    registry: &'r MarketplaceRegistry,
+    requirement: &ServiceRequirement,
+) -> Option<&'r MarketplaceEntry> {
+    let candidates = registry
+        .plugins
+        .iter()
+        .map(|plugin| (plugin, plugin.provides.as_slice()));
+    best_provider(candidates, requirement).map(|(plugin, _)| plugin)
+}
+
+/// Ask to grant each newly installed plugin the `execute_commands` scopes it
+/// needs to call its bound providers, when the workspace doesn't already.
+fn grant_service_permissions(installed: &[&MarketplaceEntry]) {
+    let Some(root_index) = workspace_root_index() else {
+        return;
+    };
+    let resolution = ServiceResolution::resolve(&installed_service_manifests());
+
+    for plugin in installed {
+        let mut permissions = granted_permissions(&root_index, &plugin.id).unwrap_or_default();
+        let rule = permissions
+            .execute_commands
+            .get_or_insert_with(Default::default);
+        let needed: Vec<String> = resolution
+            .permission_scopes(&PluginId(plugin.id.clone()))
+            .into_iter()
+            .filter(|scope| check_command_permission(rule, scope) == PermissionCheck::NotConfigured)
+            .collect();
+        if needed.is_empty() {
+            continue;
+        }
+
+        let question = format!("Allow {} to call {}?", plugin.id, needed.join(", "));
+        if !confirm(&question, true) {
+            continue;
+        }
+        rule.include.extend(needed);
+        let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+        if let Err(err) = futures_lite::future::block_on(
+            workspace.set_workspace_plugin_permissions(&root_index, &plugin.id, &permissions),
+        ) {
+            eprintln!("Failed to update permissions for '{}': {err}", plugin.id);
+        }
+    }
+}
+
+/// Service contracts of the installed plugins, read from their cached
+/// `manifest.json`.
+fn installed_service_manifests() -> Vec<PluginManifest> {
+    read_installed_plugins()
+        .iter()
+        .filter_map(|plugin| {
+            let json = std::fs::read_to_string(plugin.manifest_path.as_ref()?).ok()?;
+            let guest: GuestManifest = serde_json::from_str(&json).ok()?;
+            Some(service_manifest(
+                &guest.id,
+                guest.provides.iter().map(Into::into).collect(),
+                guest.requires.iter().map(Into::into).collect(),
+            ))
+        })
+        .collect()
+}
+
+/// A manifest carrying only a plugin's service contracts, for resolution.
+fn service_manifest(
+    id: &str,
+    provides: Vec<ServiceProvision>,
+    requires: Vec<ServiceRequirement>,
+) -> PluginManifest {
+    PluginManifest {
+        id: PluginId(id.to_string()),
+        name: id.to_string(),
+        version: String::new(),
+        description: String::new(),
+        capabilities: vec![PluginCapability::Services { provides, requires }],
+        ui: vec![],
+        cli: vec![],
+    }
+}
+
+/// Ask a yes/no question on a TTY. Outside a terminal nothing is installed
+/// or granted.
+fn confirm(question: &str, default_yes: bool) -> bool {
+    use std::io::{IsTerminal, Write};
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+
+    print!(
+        "{question} {} ",
+        if default_yes { "[Y/n]" } else { "[y/N]" }
+    );
+    std::io::stdout().flush().ok();
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    match input.trim().to_ascii_lowercase().as_str() {
+        "" => default_yes,
+        answer => answer == "y" || answer == "yes",
+    }
+}
+
+/// Install the builds pinned in the workspace lockfile.
+fn handle_install_locked(id: Option<&str>, trust: PluginTrustArgs) {
+    let Some(root_index) = workspace_root_index() else {
+        eprintln!("No workspace found; --locked installs from the workspace's plugin lockfile.");
+        return;
+    };
+    let lockfile = match read_lockfile(&root_index) {
+        Ok(lockfile) => lockfile,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+
+    let locked: Vec<(&String, &LockedPlugin)> = lockfile
+        .plugins
+        .iter()
+        .filter(|(locked_id, _)| id.is_none_or(|id| id == locked_id.as_str()))
+        .collect();
+    if locked.is_empty() {
+        match id {
+            Some(id) => eprintln!("Plugin '{id}' is not in the workspace lockfile."),
+            None => println!("The workspace lockfile pins no plugins."),
+        }
+        return;
+    }
+
+    let registry = match fetch_registry() {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("Failed to fetch plugin registry: {err}");
+            return;
+        }
+    };
+    let policy = plugin_trust_policy(trust);
+
+    for (locked_id, entry) in locked {
+        let granted = granted_permissions(&root_index, locked_id);
+        let outcome = install_locked_entry(
+            locked_id,
+            entry,
+            &plugin_dir(locked_id),
+            &registry,
+            granted.as_ref(),
+            |listing| install_plugin(listing, &policy),
+        );
+        let permissions_drift = match outcome {
+            LockedInstall::AlreadyInstalled { permissions_drift } => {
+                println!("{locked_id} {} is already installed.", entry.version);
+                permissions_drift
+            }
+            LockedInstall::Installed { permissions_drift } => permissions_drift,
+            LockedInstall::NotInRegistry => {
+                eprintln!("Skipping {locked_id}: not found in registry");
+                continue;
+            }
+            LockedInstall::Failed(err) => {
+                eprintln!("Failed to install '{locked_id}': {err}");
+                continue;
+            }
+        };
+        if permissions_drift {
+            eprintln!(
+                "Warning: the workspace grants {locked_id} different permissions than the lockfile records."
+            );
+        }
+    }
+}
+
+/// What `install --locked` did with one lockfile entry.
+#[derive(Debug, PartialEq, Eq)]
+enum LockedInstall {
+    AlreadyInstalled { permissions_drift: bool },
+    Installed { permissions_drift: bool },
+    NotInRegistry,
+    Failed(String),
+}
+
+/// Install the build `entry` pins into `dest`, unless the module already
+/// there has the locked hash. `install` is only called when a download is
+/// needed. Either way, report whether `granted` differs from the locked
+/// permissions.
+fn install_locked_entry(
+    locked_id: &str,
+    entry: &LockedPlugin,
+    dest: &Path,
+    registry: &MarketplaceRegistry,
+    granted: Option<&diaryx_core::plugin::permissions::PluginPermissions>,
+    install: impl FnOnce(&MarketplaceEntry) -> Result<(), String>,
+) -> LockedInstall {
+    let installed_sha = std::fs::read(dest.join("plugin.wasm"))
+        .ok()
+        .map(|bytes| sha256_hex(&bytes));
+    let already_installed = installed_sha.as_deref() == Some(entry.sha256.as_str());
+    if !already_installed {
+        let Some(listing) = registry.plugins.iter().find(|p| p.id == locked_id) else {
+            return LockedInstall::NotInRegistry;
+        };
+        if let Err(err) = install(&locked_listing(listing, entry)) {
+            return LockedInstall::Failed(err);
+        }
+    }
+
+    let permissions_drift = entry
+        .drift(&entry.version, &entry.sha256, granted)
+        .contains(&LockDrift::Permissions);
+    if already_installed {
+        LockedInstall::AlreadyInstalled { permissions_drift }
+    } else {
+        LockedInstall::Installed { permissions_drift }
+    }
+}
+
+/// A registry listing re-pointed at the artifact pinned in the lockfile.
+fn locked_listing(listing: &MarketplaceEntry, locked: &LockedPlugin) -> MarketplaceEntry {
+    let mut entry = listing.clone();
+    entry.version = locked.version.clone();
+    entry.artifact.url = locked.url.clone();
+    entry.artifact.sha256 = locked.sha256.clone();
+    entry.artifact.size = locked.size;
+    entry.artifact.publisher_key = locked.publisher_key.clone();
+    entry.artifact.signature = locked.signature.clone();
+    entry
+}
+
+/// Root index of the CLI's active workspace, which anchors the plugin lockfile.
+fn workspace_root_index() -> Option<PathBuf> {
+    let root = super::plugin_loader::resolve_cli_workspace_root()?;
+    let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+    futures_lite::future::block_on(workspace.find_root_index_in_dir(&root))
+        .ok()
+        .flatten()
+}
+
+fn read_lockfile(root_index: &Path) -> Result<PluginLockfile, String> {
+    let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+    futures_lite::future::block_on(workspace.read_plugin_lockfile(root_index))
+        .map_err(|err| format!("Failed to read plugin lockfile: {err}"))
+}
+
+/// Permissions the workspace currently grants a plugin.
+fn granted_permissions(
+    root_index: &Path,
+    id: &str,
+) -> Option<diaryx_core::plugin::permissions::PluginPermissions> {
+    let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+    futures_lite::future::block_on(workspace.get_workspace_plugin_permissions(root_index, id))
+        .ok()
+        .flatten()
+}
+
+/// Lockfile entry for a freshly installed registry listing.
+fn lock_entry(plugin: &MarketplaceEntry, root_index: &Path) -> LockedPlugin {
+    LockedPlugin::from_entry(
+        plugin,
+        granted_permissions(root_index, &plugin.id).unwrap_or_default(),
+    )
+}
+
+/// Read-modify-write the workspace plugin lockfile. A no-op outside a workspace.
+fn update_lockfile(edit: impl FnOnce(&mut PluginLockfile, &Path)) {
+    let Some(root_index) = workspace_root_index() else {
+        return;
+    };
+    let mut lockfile = match read_lockfile(&root_index) {
+        Ok(lockfile) => lockfile,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    edit(&mut lockfile, &root_index);
+
+    let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+    if let Err(err) =
+        futures_lite::future::block_on(workspace.write_plugin_lockfile(&root_index, &lockfile))
+    {
+        eprintln!("Failed to write plugin lockfile: {err}");
     }
 }
 
 /// Download and install a single plugin.
-fn install_plugin(plugin: &MarketplaceEntry) -> Result<(), String> {
+fn install_plugin(plugin: &MarketplaceEntry, policy: &PluginTrustPolicy) -> Result<(), String> {
     let dest = plugin_dir(&plugin.id);
     let existed = dest.exists();
 
@@ -485,6 +888,7 @@ fn install_plugin(plugin: &MarketplaceEntry) -> Result<(), String> {
 
         let bytes = download_bytes(&plugin.artifact.url)?;
         verify_download_integrity(plugin, &bytes)?;
+        let signature = verify_artifact_signature(plugin, &bytes, policy)?;
 
         let wasm_path = dest.join("plugin.wasm");
         std::fs::write(&wasm_path, &bytes).map_err(|err| {
@@ -498,6 +902,15 @@ fn install_plugin(plugin: &MarketplaceEntry) -> Result<(), String> {
             .map_err(|err| format!("Failed to inspect plugin manifest from WASM: {err}"))?;
         verify_inspected_manifest(plugin, &inspected)?;
 
+        match &signature {
+            Some(signature) => signature
+                .write_to_dir(&dest)
+                .map_err(|err| err.to_string())?,
+            None => {
+                let _ = std::fs::remove_file(dest.join(SIGNATURE_FILE));
+            }
+        }
+
         cache_manifest_from_wasm(&wasm_path)?;
 
         println!("Installed to {}", dest.display());
@@ -540,6 +953,67 @@ fn verify_download_integrity(plugin: &MarketplaceEntry, bytes: &[u8]) -> Result<
     Ok(())
 }
 
+/// Verify the registry's publisher signature over the downloaded artifact and
+/// check its key against the trust store, pinning it on first use.
+///
+/// Returns the signature to store next to the installed module, or `None` when
+/// an unsigned artifact was allowed through.
+fn verify_artifact_signature(
+    plugin: &MarketplaceEntry,
+    bytes: &[u8],
+    policy: &PluginTrustPolicy,
+) -> Result<Option<ArtifactSignature>, String> {
+    let (Some(publisher_key), Some(signature)) =
+        (&plugin.artifact.publisher_key, &plugin.artifact.signature)
+    else {
+        if policy.allow_unsigned {
+            eprintln!(
+                "Warning: {} is not signed by its publisher; installing anyway.",
+                plugin.id
+            );
+            return Ok(None);
+        }
+        return Err(format!(
+            "{} is not signed by its publisher. Pass --allow-unsigned to install it anyway.",
+            plugin.id
+        ));
+    };
+
+    let signed = ArtifactSignature {
+        plugin_id: plugin.id.clone(),
+        version: plugin.version.clone(),
+        sha256: sha256_hex(bytes),
+        publisher_key: publisher_key.clone(),
+        signature: signature.clone(),
+    };
+    signed.verify(bytes).map_err(|err| err.to_string())?;
+
+    match policy.check_publisher(&signed) {
+        Ok(TrustDecision::Pinned) => {}
+        Ok(TrustDecision::FirstUse) => {
+            if policy.trust_store.is_some() {
+                println!("Pinned publisher key {publisher_key} for {}.", plugin.id);
+            }
+        }
+        Ok(TrustDecision::Rekeyed { previous }) => {
+            eprintln!(
+                "Warning: publisher key for {} changed from {previous} to {publisher_key}; pin replaced.",
+                plugin.id
+            );
+        }
+        Err(err @ TrustError::Rekeyed { .. }) => {
+            return Err(format!(
+                "{err}. If the publisher rotated their key, pass --allow-rekeyed or run: \
+                 diaryx plugin trust pin {} <key>",
+                plugin.id
+            ));
+        }
+        Err(err) => return Err(err.to_string()),
+    }
+
+    Ok(Some(signed))
+}
+
 fn verify_inspected_manifest(
     registry_plugin: &MarketplaceEntry,
     inspected: &diaryx_extism::protocol::GuestManifest,
@@ -608,7 +1082,12 @@ fn handle_remove(id: &str, yes: bool) {
     }
 
     match std::fs::remove_dir_all(&dest) {
-        Ok(()) => println!("Removed plugin '{id}'."),
+        Ok(()) => {
+            println!("Removed plugin '{id}'.");
+            update_lockfile(|lockfile, _| {
+                lockfile.unlock(id);
+            });
+        }
         Err(err) => eprintln!("Failed to remove plugin '{id}': {err}"),
     }
 }
@@ -808,7 +1287,7 @@ fn handle_search(filters: DiscoveryFilters, json: bool) {
 }
 
 /// Update installed plugins.
-fn handle_update(specific_id: Option<&str>) {
+fn handle_update(specific_id: Option<&str>, trust: PluginTrustArgs) {
     let registry = match fetch_registry() {
         Ok(registry) => registry,
         Err(err) => {
@@ -823,8 +1302,10 @@ fn handle_update(specific_id: Option<&str>) {
         return;
     }
 
+    let policy = plugin_trust_policy(trust);
     let mut updated = 0usize;
     let mut checked = 0usize;
+    let mut to_lock = Vec::new();
 
     for local in &installed {
         if let Some(target_id) = specific_id
@@ -843,6 +1324,14 @@ fn handle_update(specific_id: Option<&str>) {
         let needs_update = local.version.as_deref() != Some(registry_plugin.version.as_str());
 
         if !needs_update {
+            // Lock up-to-date plugins too, but only if the installed module
+            // really is the registry build (not, say, a dev link).
+            let installed_sha = std::fs::read(local.path.join("plugin.wasm"))
+                .ok()
+                .map(|bytes| sha256_hex(&bytes));
+            if installed_sha == Some(normalize_sha256(&registry_plugin.artifact.sha256)) {
+                to_lock.push(registry_plugin);
+            }
             continue;
         }
 
@@ -853,12 +1342,21 @@ fn handle_update(specific_id: Option<&str>) {
             registry_plugin.version
         );
 
-        if let Err(err) = install_plugin(registry_plugin) {
+        if let Err(err) = install_plugin(registry_plugin, &policy) {
             eprintln!("Failed to update {}: {err}", local.id);
             continue;
         }
 
         updated += 1;
+        to_lock.push(registry_plugin);
+    }
+
+    if !to_lock.is_empty() {
+        update_lockfile(|lockfile, root_index| {
+            for plugin in to_lock {
+                lockfile.lock(&plugin.id, lock_entry(plugin, root_index));
+            }
+        });
     }
 
     if let Some(target_id) = specific_id
@@ -932,6 +1430,10 @@ fn handle_info(id: &str, json: bool) {
         );
         println!("Artifact Size: {} bytes", plugin.artifact.size);
         println!("Published At: {}", plugin.artifact.published_at);
+        match &plugin.artifact.publisher_key {
+            Some(key) if plugin.artifact.signature.is_some() => println!("Publisher Key: {key}"),
+            _ => println!("Signed: no"),
+        }
         if !plugin.categories.is_empty() {
             println!("Categories: {}", plugin.categories.join(", "));
         }
@@ -941,6 +1443,17 @@ fn handle_info(id: &str, json: bool) {
         if !plugin.capabilities.is_empty() {
             println!("Capabilities: {}", plugin.capabilities.join(", "));
         }
+        for service in &plugin.provides {
+            println!("Provides: {} {}", service.interface, service.version);
+        }
+        for service in &plugin.requires {
+            println!(
+                "Requires: {} {}{}",
+                service.interface,
+                service.version,
+                if service.optional { " (optional)" } else { "" }
+            );
+        }
         if let Some(requested) = &plugin.requested_permissions {
             let requested_text = serde_json::to_string_pretty(requested)
                 .unwrap_or_else(|_| "<invalid requested_permissions>".to_string());
@@ -959,6 +1472,128 @@ fn handle_info(id: &str, json: bool) {
     }
 }
 
+/// Handle `diaryx plugin audit <id>`.
+fn handle_audit(id: &str, json: bool, clear: bool) {
+    let Some(workspace_root) = super::plugin_loader::resolve_cli_workspace_root() else {
+        eprintln!("No workspace found; plugin access logs are stored per workspace.");
+        return;
+    };
+    let storage = CliPluginStorage::new(&workspace_root);
+
+    if clear {
+        AccessLog::clear(&storage, id);
+        println!("Cleared access log for {id}.");
+        return;
+    }
+
+    let granted =
+        workspace_root_index().and_then(|root_index| granted_permissions(&root_index, id));
+    let report = AccessLog::load(&storage, id).report(id, granted.as_ref());
+
+    if json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(text) => println!("{text}"),
+            Err(err) => eprintln!("Failed to render JSON output: {err}"),
+        }
+        return;
+    }
+
+    if report.records == 0 {
+        println!("No recorded accesses for {id}.");
+        println!("Set `audit: true` under plugins.{id} in the workspace config to record them.");
+        return;
+    }
+
+    println!(
+        "{id}: {} recorded accesses ({} to {})",
+        report.records,
+        report.first_timestamp.as_deref().unwrap_or("?"),
+        report.last_timestamp.as_deref().unwrap_or("?"),
+    );
+    for usage in &report.permissions {
+        println!();
+        println!(
+            "{}: {} allowed, {} denied",
+            usage.permission, usage.allowed, usage.denied
+        );
+        for (target, count) in &usage.targets {
+            println!("  {count:>5}  {target}");
+        }
+    }
+
+    println!();
+    if report.unused.is_empty() {
+        println!("Every granted permission has been used.");
+    } else {
+        println!("Granted but never used (consider tightening):");
+        for grant in &report.unused {
+            match &grant.scope {
+                Some(scope) => println!("  {}: {scope}", grant.permission),
+                None => println!("  {}", grant.permission),
+            }
+        }
+    }
+}
+
+/// Handle `diaryx plugin trust <subcommand>`.
+fn handle_trust(command: PluginTrustCommands) {
+    let Some(path) = trust_store_path() else {
+        eprintln!("Could not determine the config directory for the publisher trust store.");
+        return;
+    };
+    let mut store = match TrustStore::load(&path) {
+        Ok(store) => store,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+
+    match command {
+        PluginTrustCommands::List { json } => {
+            if json {
+                match serde_json::to_string_pretty(&store) {
+                    Ok(text) => println!("{text}"),
+                    Err(err) => eprintln!("Failed to render JSON output: {err}"),
+                }
+                return;
+            }
+            if store.pins.is_empty() {
+                println!("No publisher keys pinned.");
+                return;
+            }
+            for (id, pin) in &store.pins {
+                let origin = match pin.origin {
+                    PinOrigin::FirstUse => "first use",
+                    PinOrigin::Manual => "manual",
+                };
+                println!("{id}  {}  ({origin}, {})", pin.publisher_key, pin.pinned_at);
+            }
+        }
+        PluginTrustCommands::Pin { id, key } => {
+            if !is_canonical_plugin_id(&id) {
+                eprintln!("Invalid plugin ID '{id}'.");
+                return;
+            }
+            store.pin(&id, key.trim(), PinOrigin::Manual);
+            match store.save(&path) {
+                Ok(()) => println!("Pinned publisher key for {id}."),
+                Err(err) => eprintln!("{err}"),
+            }
+        }
+        PluginTrustCommands::Forget { id } => {
+            if !store.forget(&id) {
+                println!("No publisher key pinned for {id}.");
+                return;
+            }
+            match store.save(&path) {
+                Ok(()) => println!("Forgot publisher key for {id}."),
+                Err(err) => eprintln!("{err}"),
+            }
+        }
+    }
+}
+
 /// Fetch and parse the plugin registry.
 fn fetch_registry() -> Result<MarketplaceRegistry, String> {
     let agent = build_http_agent(std::time::Duration::from_secs(30));
@@ -994,6 +1629,7 @@ fn download_bytes(url: &str) -> Result<Vec<u8>, String> {
 mod tests {
     use super::*;
     use diaryx_core::plugin::manifest::PluginArtifact;
+    use diaryx_core::plugin::permissions::{PermissionRule, PluginPermissions};
 
     fn sample_plugin() -> MarketplaceEntry {
         MarketplaceEntry {
@@ -1010,6 +1646,8 @@ mod tests {
                 sha256: "abc".into(),
                 size: 42,
                 published_at: "2026-03-03T00:00:00Z".into(),
+                publisher_key: None,
+                signature: None,
             },
             repository: Some("https://github.com/diaryx-org/diaryx".into()),
             categories: vec!["sync".into()],
@@ -1019,6 +1657,8 @@ mod tests {
             capabilities: vec!["sync_transport".into()],
             requested_permissions: None,
             protocol_version: Some(1),
+            provides: vec![],
+            requires: vec![],
         }
     }
 
@@ -1109,4 +1749,202 @@ plugins:
             Some(&installed)
         ));
     }
+
+    #[test]
+    fn registry_provider_picks_highest_satisfying_version() {
+        let provider = |id: &str, version: &str| MarketplaceEntry {
+            id: id.into(),
+            provides: vec![ServiceProvision {
+                interface: "templating".into(),
+                version: version.into(),
+                commands: vec![],
+            }],
+            ..sample_plugin()
+        };
+        let registry = MarketplaceRegistry {
+            schema_version: 2,
+            generated_at: "2026-03-03T00:00:00Z".into(),
+            plugins: vec![
+                sample_plugin(),
+                provider("acme.templates", "1.1.0"),
+                provider("diaryx.templating", "1.3.0"),
+                provider("acme.templates-next", "2.0.0"),
+            ],
+            body: String::new(),
+        };
+        let requirement = |version: &str| ServiceRequirement {
+            interface: "templating".into(),
+            version: version.into(),
+            optional: false,
+        };
+
+        assert_eq!(
+            registry_provider(&registry, &requirement("^1")).map(|p| p.id.as_str()),
+            Some("diaryx.templating")
+        );
+        assert_eq!(
+            registry_provider(&registry, &requirement("^3")).map(|p| p.id.as_str()),
+            None
+        );
+    }
+
+    #[test]
+    fn unsigned_artifact_requires_override() {
+        let plugin = sample_plugin();
+        let strict = PluginTrustPolicy::default();
+        let err = verify_artifact_signature(&plugin, b"wasm", &strict).unwrap_err();
+        assert!(err.contains("--allow-unsigned"), "got: {err}");
+
+        let lenient = PluginTrustPolicy {
+            allow_unsigned: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            verify_artifact_signature(&plugin, b"wasm", &lenient),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn forged_artifact_signature_is_rejected_even_when_unsigned_is_allowed() {
+        let mut plugin = sample_plugin();
+        plugin.artifact.publisher_key = Some("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".into());
+        plugin.artifact.signature = Some("Zm9yZ2Vk".into());
+        let policy = PluginTrustPolicy {
+            allow_unsigned: true,
+            allow_rekeyed: true,
+            trust_store: None,
+        };
+        assert!(verify_artifact_signature(&plugin, b"wasm", &policy).is_err());
+    }
+
+    fn locked_dir(name: &str, wasm: Option<&[u8]>) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "diaryx-install-locked-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        if let Some(wasm) = wasm {
+            std::fs::write(dir.join("plugin.wasm"), wasm).unwrap();
+        }
+        dir
+    }
+
+    fn locked_registry() -> MarketplaceRegistry {
+        MarketplaceRegistry {
+            schema_version: 2,
+            generated_at: "2026-03-03T00:00:00Z".into(),
+            plugins: vec![sample_plugin()],
+            body: String::new(),
+        }
+    }
+
+    fn http_grant(domain: &str) -> PluginPermissions {
+        PluginPermissions {
+            http_requests: Some(PermissionRule {
+                include: vec![domain.into()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn install_locked_skips_a_matching_module() {
+        let dir = locked_dir("matching", Some(b"wasm"));
+        let mut entry = LockedPlugin::from_entry(&sample_plugin(), http_grant("api.test"));
+        entry.sha256 = sha256_hex(b"wasm");
+        let install = |_: &MarketplaceEntry| -> Result<(), String> {
+            panic!("an installed module with the locked hash must not be re-downloaded")
+        };
+
+        assert_eq!(
+            install_locked_entry(
+                "diaryx.sync",
+                &entry,
+                &dir,
+                &locked_registry(),
+                Some(&http_grant("api.test")),
+                install,
+            ),
+            LockedInstall::AlreadyInstalled {
+                permissions_drift: false
+            }
+        );
+        assert_eq!(
+            install_locked_entry(
+                "diaryx.sync",
+                &entry,
+                &dir,
+                &locked_registry(),
+                Some(&http_grant("evil.test")),
+                install,
+            ),
+            LockedInstall::AlreadyInstalled {
+                permissions_drift: true
+            }
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn install_locked_fetches_the_pinned_artifact() {
+        let dir = locked_dir("stale", Some(b"old wasm"));
+        let mut entry = LockedPlugin::from_entry(&sample_plugin(), http_grant("api.test"));
+        entry.version = "1.0.0".into();
+        entry.url =
+            "https://app.diaryx.org/cdn/plugins/artifacts/diaryx.sync/1.0.0/def.wasm".into();
+        entry.sha256 = "def".into();
+        let mut requested = None;
+
+        let outcome = install_locked_entry(
+            "diaryx.sync",
+            &entry,
+            &dir,
+            &locked_registry(),
+            None,
+            |listing| {
+                requested = Some(listing.clone());
+                Ok(())
+            },
+        );
+
+        assert_eq!(
+            outcome,
+            LockedInstall::Installed {
+                permissions_drift: true
+            }
+        );
+        let requested = requested.expect("a stale module should be replaced");
+        assert_eq!(requested.version, "1.0.0");
+        assert_eq!(requested.artifact.url, entry.url);
+        assert_eq!(requested.artifact.sha256, "def");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn install_locked_reports_missing_and_failed_installs() {
+        let dir = locked_dir("missing", None);
+        let entry = LockedPlugin::from_entry(&sample_plugin(), PluginPermissions::default());
+
+        assert_eq!(
+            install_locked_entry("acme.gone", &entry, &dir, &locked_registry(), None, |_| Ok(
+                ()
+            )),
+            LockedInstall::NotInRegistry
+        );
+        assert_eq!(
+            install_locked_entry(
+                "diaryx.sync",
+                &entry,
+                &dir,
+                &locked_registry(),
+                None,
+                |_| Err("hash mismatch".into())
+            ),
+            LockedInstall::Failed("hash mismatch".into())
+        );
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -0,0 +1,113 @@
+//! Scheduled plugin events for the CLI.
+//!
+//! `diaryx plugin tick` delivers whatever is due once and exits, for use
+//! from cron or a systemd timer. The `diaryx edit` server runs the same
+//! scheduler on a background thread for as long as it is up. Both share the
+//! workspace's `.diaryx/plugin-schedules.json`, re-reading it under a lock
+//! on every tick, so missed runs are coalesced no matter which host was
+//! running and no occurrence is delivered twice.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use diaryx_core::diaryx::Diaryx;
+use diaryx_core::error::DiaryxError;
+use diaryx_core::fs::AsyncFileSystem;
+use diaryx_core::plugin::schedule::manifest_schedules;
+use diaryx_core::plugin::{PluginManifest, WorkspacePlugin};
+use diaryx_extism::{ExtismPluginAdapter, ScheduleCanceller, ScheduleRunner};
+
+use super::plugin_loader::{
+    discover_plugin_manifests, load_and_init_plugin, resolve_cli_workspace_root,
+};
+
+fn report_errors(prefix: &str, errors: &[DiaryxError]) {
+    for err in errors {
+        eprintln!("{prefix}Skipping plugin schedule: {err}");
+    }
+}
+
+/// Handle `diaryx plugin tick`.
+pub fn handle_tick() {
+    let Some(workspace_root) = resolve_cli_workspace_root() else {
+        eprintln!("No workspace found; plugin schedules are tracked per workspace.");
+        return;
+    };
+
+    let manifests: Vec<PluginManifest> = discover_plugin_manifests()
+        .into_iter()
+        .map(|(_, manifest)| manifest)
+        .filter(|manifest| !manifest_schedules(manifest).is_empty())
+        .collect();
+    let (mut runner, errors) = ScheduleRunner::new(&workspace_root, &manifests);
+    report_errors("", &errors);
+    if runner.is_empty() {
+        println!("No installed plugin declares a schedule.");
+        return;
+    }
+
+    // Plugins are loaded lazily so a tick with nothing due stays cheap.
+    let mut plugins: HashMap<String, Option<Arc<ExtismPluginAdapter>>> = HashMap::new();
+    let delivered = runner.tick_now(|plugin_id, event| {
+        let plugin = plugins.entry(plugin_id.0.clone()).or_insert_with(|| {
+            load_and_init_plugin(&workspace_root, &plugin_id.0)
+                .map_err(|err| eprintln!("Failed to load plugin {plugin_id}: {err}"))
+                .ok()
+        });
+        if let Some(plugin) = plugin {
+            futures_lite::future::block_on(WorkspacePlugin::on_scheduled(plugin.as_ref(), event));
+        }
+    });
+
+    if delivered.is_empty() {
+        println!("No plugin schedules are due.");
+    }
+    for (plugin_id, event) in &delivered {
+        if event.missed > 0 {
+            println!(
+                "{plugin_id}: ran '{}' for {} ({} missed run(s) coalesced)",
+                event.schedule_id, event.scheduled_at, event.missed
+            );
+        } else {
+            println!(
+                "{plugin_id}: ran '{}' for {}",
+                event.schedule_id, event.scheduled_at
+            );
+        }
+    }
+}
+
+/// Start delivering scheduled events to the plugins registered with the
+/// edit server's `Diaryx` instance.
+///
+/// Returns a handle that stops the scheduler thread and cancels the run in
+/// flight when the server shuts down, or `None` if no loaded plugin declares
+/// a schedule.
+pub(super) fn spawn_edit_server_scheduler<FS>(
+    diaryx: Arc<Diaryx<FS>>,
+    workspace_root: &Path,
+) -> Option<ScheduleCanceller>
+where
+    FS: AsyncFileSystem + Send + Sync + 'static,
+{
+    let manifests = diaryx.plugin_registry().get_all_manifests();
+    let (mut runner, errors) = ScheduleRunner::new(workspace_root, &manifests);
+    report_errors("[edit-server] ", &errors);
+    if runner.is_empty() {
+        return None;
+    }
+
+    let canceller = runner.canceller();
+    std::thread::spawn(move || {
+        runner.run(
+            || true,
+            |plugin_id, event| {
+                futures_lite::future::block_on(
+                    diaryx.plugin_registry().emit_scheduled(plugin_id, event),
+                );
+            },
+        );
+    });
+    Some(canceller)
+}
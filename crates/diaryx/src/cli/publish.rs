@@ -15,7 +15,7 @@ use std::time::Duration;
 
 use diaryx_core::auth::AuthenticatedClient;
 use diaryx_core::fs::SyncToAsyncFs;
-use diaryx_core::publish::{NamespaceProvider, ObjectMeta, PublishService};
+use diaryx_core::publish::{NamespaceProvider, ObjectMeta, PublishService, PublishedComment};
 use diaryx_core::workspace::Workspace;
 use diaryx_core::{fig, namespace, yaml};
 use diaryx_native::RealFileSystem;
@@ -264,6 +264,36 @@ impl NamespaceProvider for HttpNamespaceProvider {
         let (status, body) = self.with_body(WithBody::Post, &url, "application/json", &[], &[])?;
         ok_or(status, body, "build_namespace").map(|_| ())
     }
+
+    async fn list_approved_comments(&self, ns_id: &str) -> Result<Vec<PublishedComment>, String> {
+        let url = self.url(&format!(
+            "/namespaces/{}/comments?status=approved&limit=500",
+            Self::enc(ns_id)
+        ));
+        let (status, body) = self.no_body(NoBody::Get, &url)?;
+        let body = ok_or(status, body, "list_comments")?;
+        #[derive(serde::Deserialize)]
+        struct Row {
+            #[serde(default)]
+            source_key: Option<String>,
+            author_name: String,
+            body: String,
+            created_at: i64,
+        }
+        let rows: Vec<Row> =
+            serde_json::from_str(&body).map_err(|e| format!("list_comments decode: {e}"))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| {
+                Some(PublishedComment {
+                    source_key: r.source_key?,
+                    author_name: r.author_name,
+                    body: r.body,
+                    created_at: r.created_at,
+                })
+            })
+            .collect())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -276,7 +306,9 @@ fn load_client(server: Option<&str>) -> Result<FsAuthenticatedClient, String> {
     let client = FsAuthenticatedClient::from_default_path(server)
         .ok_or("Cannot determine config directory for auth storage")?;
     if !block_on(client.has_session()) {
-        return Err("Not logged in. Run `diaryx login <email>` first.".to_string());
+        return Err(
+            "Not logged in. Run `diaryx login <email>` first or set DIARYX_TOKEN.".to_string(),
+        );
     }
     Ok(client)
 }
@@ -423,6 +455,7 @@ pub fn handle_publish(
                     "bytes_uploaded": outcome.bytes_uploaded,
                     "audiences_deleted": outcome.audiences_deleted,
                     "built": outcome.built,
+                    "comment_files": outcome.comment_files,
                     "permalink": format!("https://diaryx.org/ark/{ns_id}/index"),
                 });
                 println!("{}", serde_json::to_string_pretty(&out).unwrap_or_default());
@@ -437,6 +470,12 @@ pub fn handle_publish(
                 if !outcome.built {
                     eprintln!("⚠ Server-side render did not complete.");
                 }
+                if outcome.comment_files > 0 {
+                    println!(
+                        "  Pulled reader comments into {} .comments.md file(s).",
+                        outcome.comment_files
+                    );
+                }
                 println!("  Permalink: https://diaryx.org/ark/{ns_id}/index");
             }
             true
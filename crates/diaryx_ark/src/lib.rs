@@ -1,11 +1,14 @@
 //! `diaryx_ark` — opaque ARK-style identifier minting and validation.
 //!
-//! ARK form: `ark:99999/dx<6 betanum><check>/<5 betanum><check>`
+//! ARK form: `ark:99999/dx<6 betanum><check>/<5 betanum><check>[.<variant>]`
 //!
 //! - `99999` — NAAN placeholder (until Diaryx registers a real one).
 //! - `dx` — the "shoulder", reserved so the ID format can change later.
 //! - workspace blade: `dx` + 6 random betanumeric chars + 1 check char (9 total).
 //! - file blade: 5 random betanumeric chars + 1 check char (6 total).
+//! - variant: optional `.<FILE>` suffix on the file blade naming a specific
+//!   version of the file (e.g. a content-hash prefix). Not check-protected —
+//!   it is resolved against the versions the server actually retains.
 //!
 //! Minting is **random** (opaque for free — no sequence to hide), with
 //! uniqueness enforced by the caller via rejection (`*_unique`). Entropy is
@@ -42,6 +45,11 @@ pub const WORKSPACE_BLADE_LEN: usize = SHOULDER.len() + WORKSPACE_RANDOM_LEN + 1
 /// Total length of a file blade (5 random + 1 check).
 pub const FILE_BLADE_LEN: usize = FILE_RANDOM_LEN + 1;
 
+/// Separator between a file blade and its `.<FILE>` variant.
+pub const VARIANT_SEPARATOR: char = '.';
+/// Upper bound on a variant's length (a full SHA-256 hex digest).
+pub const MAX_VARIANT_LEN: usize = 64;
+
 /// Errors produced while validating or parsing ARK components.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArkError {
@@ -203,12 +211,41 @@ pub fn validate_workspace_blade(s: &str) -> Result<(), ArkError> {
     Ok(())
 }
 
+/// Validates a `.<FILE>` variant: 1..=[`MAX_VARIANT_LEN`] characters of ASCII
+/// alphanumerics, `-` or `_`. Case is preserved; resolvers decide how to match.
+pub fn validate_variant(s: &str) -> Result<(), ArkError> {
+    if s.is_empty() || s.len() > MAX_VARIANT_LEN {
+        return Err(ArkError::BadLength);
+    }
+    if !s
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ArkError::BadChar);
+    }
+    Ok(())
+}
+
+/// Splits a file segment into its blade and optional `.<FILE>` variant.
+///
+/// Performs no validation: `"bcdfgr.3f9a"` → `("bcdfgr", Some("3f9a"))`,
+/// `"bcdfgr"` → `("bcdfgr", None)`. A trailing separator yields `Some("")`,
+/// which [`validate_variant`] rejects.
+pub fn split_variant(file_segment: &str) -> (&str, Option<&str>) {
+    match file_segment.split_once(VARIANT_SEPARATOR) {
+        Some((blade, variant)) => (blade, Some(variant)),
+        None => (file_segment, None),
+    }
+}
+
 /// A parsed ARK, borrowing from the source string.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Ark<'a> {
     pub naan: &'a str,
     pub workspace_blade: &'a str,
     pub file_blade: &'a str,
+    /// The `.<FILE>` version selector, if present.
+    pub variant: Option<&'a str>,
 }
 
 /// Composes a full ARK string from validated-or-unvalidated blades.
@@ -216,29 +253,42 @@ pub fn format_ark(workspace_blade: &str, file_blade: &str) -> String {
     format!("ark:{NAAN}/{workspace_blade}/{file_blade}")
 }
 
-/// Parses a core ARK string (`ark:<naan>/<workspace>/<file>`).
+/// Composes a full ARK string addressing one `.<FILE>` variant of a file.
+pub fn format_ark_variant(workspace_blade: &str, file_blade: &str, variant: &str) -> String {
+    format!("ark:{NAAN}/{workspace_blade}/{file_blade}{VARIANT_SEPARATOR}{variant}")
+}
+
+/// Parses an ARK string (`ark:<naan>/<workspace>/<file>[.<variant>]`).
 ///
-/// Layer 1 parses only the three core segments and validates both blades;
-/// `.FILE` / `?query` / `#callout` suffixes are a later layer and are rejected
-/// here as [`ArkError::BadFormat`].
+/// Validates both blades and, when present, the `.<FILE>` variant. `?query`
+/// and `#callout` suffixes belong to the resolver (query string / fragment),
+/// not the identifier, and are rejected here as [`ArkError::BadFormat`].
 pub fn parse_ark(s: &str) -> Result<Ark<'_>, ArkError> {
     let rest = s.strip_prefix("ark:").ok_or(ArkError::BadFormat)?;
     let mut parts = rest.split('/');
     let naan = parts.next().ok_or(ArkError::BadFormat)?;
     let workspace_blade = parts.next().ok_or(ArkError::BadFormat)?;
-    let file_blade = parts.next().ok_or(ArkError::BadFormat)?;
+    let file_segment = parts.next().ok_or(ArkError::BadFormat)?;
     if parts.next().is_some() {
         return Err(ArkError::BadFormat);
     }
     if naan.is_empty() {
         return Err(ArkError::BadFormat);
     }
+    if file_segment.contains(['?', '#']) {
+        return Err(ArkError::BadFormat);
+    }
+    let (file_blade, variant) = split_variant(file_segment);
     validate_workspace_blade(workspace_blade)?;
     validate_file_blade(file_blade)?;
+    if let Some(v) = variant {
+        validate_variant(v)?;
+    }
     Ok(Ark {
         naan,
         workspace_blade,
         file_blade,
+        variant,
     })
 }
 
@@ -371,17 +421,66 @@ mod tests {
         assert_eq!(parsed.naan, NAAN);
         assert_eq!(parsed.workspace_blade, ws);
         assert_eq!(parsed.file_blade, file);
+        assert_eq!(parsed.variant, None);
+    }
+
+    #[test]
+    fn parse_accepts_file_variant() {
+        let s = format_ark_variant("dxbcdfgh6", "bcdfgr", "3f9a0c1d");
+        assert_eq!(s, "ark:99999/dxbcdfgh6/bcdfgr.3f9a0c1d");
+
+        let parsed = parse_ark(&s).expect("should parse");
+        assert_eq!(parsed.workspace_blade, "dxbcdfgh6");
+        assert_eq!(parsed.file_blade, "bcdfgr");
+        assert_eq!(parsed.variant, Some("3f9a0c1d"));
+    }
+
+    #[test]
+    fn parse_rejects_bad_variants() {
+        // Trailing separator with nothing after it.
+        assert_eq!(
+            parse_ark("ark:99999/dxbcdfgh6/bcdfgr."),
+            Err(ArkError::BadLength)
+        );
+        // Only one variant component; a second '.' is not a valid character.
+        assert_eq!(
+            parse_ark("ark:99999/dxbcdfgh6/bcdfgr.abc.def"),
+            Err(ArkError::BadChar)
+        );
+        // The blade is still check-validated when a variant is attached.
+        assert_eq!(
+            parse_ark("ark:99999/dxbcdfgh6/bcdfgb.abc"),
+            Err(ArkError::BadCheck)
+        );
+        let too_long = "a".repeat(MAX_VARIANT_LEN + 1);
+        assert_eq!(validate_variant(&too_long), Err(ArkError::BadLength));
+    }
+
+    #[test]
+    fn split_variant_separates_blade() {
+        assert_eq!(split_variant("bcdfgr"), ("bcdfgr", None));
+        assert_eq!(split_variant("bcdfgr.v2"), ("bcdfgr", Some("v2")));
+        assert_eq!(split_variant("bcdfgr."), ("bcdfgr", Some("")));
     }
 
     #[test]
     fn parse_rejects_malformed() {
         assert_eq!(parse_ark("notanark"), Err(ArkError::BadFormat));
         assert_eq!(parse_ark("ark:99999/dxbcdfgh6"), Err(ArkError::BadFormat)); // missing file
-        // Extra segment / suffix not supported in Layer 1:
+        // Extra segment:
         assert_eq!(
             parse_ark("ark:99999/dxbcdfgh6/bcdfgr/extra"),
             Err(ArkError::BadFormat)
         );
+        // Query / callout suffixes are resolver concerns, not part of the id:
+        assert_eq!(
+            parse_ark("ark:99999/dxbcdfgh6/bcdfgr?info"),
+            Err(ArkError::BadFormat)
+        );
+        assert_eq!(
+            parse_ark("ark:99999/dxbcdfgh6/bcdfgr#intro"),
+            Err(ArkError::BadFormat)
+        );
     }
 
     #[test]
@@ -0,0 +1,19 @@
+-- `.<FILE>` variants: every distinct markdown source a file ARK has been
+-- published with, keyed by the source's SHA-256 content hash. The bytes live
+-- in an immutable, owner-only version object (`object_key`) that references
+-- the same content-addressed blob the source used, so the blob survives later
+-- republishes of the file.
+--
+-- Rows are insert-only: republishing identical content is a no-op, and
+-- `created_at` records when that content was first published.
+
+CREATE TABLE IF NOT EXISTS ark_versions (
+    workspace_ark TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    file_ark      TEXT NOT NULL,
+    version       TEXT NOT NULL,
+    object_key    TEXT NOT NULL,
+    created_at    INTEGER NOT NULL,
+    PRIMARY KEY (workspace_ark, file_ark, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ark_versions_file ON ark_versions(workspace_ark, file_ark);
@@ -0,0 +1,41 @@
+-- ActivityPub actors for namespaces with a public audience.
+--
+-- `activitypub_actor_keys` holds each actor's RSA key pair (PEM), created
+-- the first time the actor is fetched or signs a request.
+-- `activitypub_followers` lists the fediverse accounts following a
+-- namespace; deliveries go to `shared_inbox` when the follower's server has
+-- one. `activitypub_outbox` mirrors the feed pages of the namespace's public
+-- audiences as of its last build; `id` names the page's `Note` and never
+-- changes, and `published_at` is the page's `created` date or its first
+-- build.
+
+CREATE TABLE IF NOT EXISTS activitypub_actor_keys (
+    namespace_id    TEXT PRIMARY KEY REFERENCES namespaces(id) ON DELETE CASCADE,
+    public_key_pem  TEXT NOT NULL,
+    private_key_pem TEXT NOT NULL,
+    created_at      INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS activitypub_followers (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    actor        TEXT NOT NULL,
+    inbox        TEXT NOT NULL,
+    shared_inbox TEXT,
+    created_at   INTEGER NOT NULL,
+    PRIMARY KEY (namespace_id, actor)
+);
+
+CREATE TABLE IF NOT EXISTS activitypub_outbox (
+    id           TEXT NOT NULL,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience     TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    url          TEXT NOT NULL,
+    title        TEXT NOT NULL,
+    summary      TEXT NOT NULL,
+    published_at INTEGER NOT NULL,
+    updated_at   INTEGER NOT NULL,
+    PRIMARY KEY (namespace_id, object_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activitypub_outbox_published ON activitypub_outbox(namespace_id, published_at);
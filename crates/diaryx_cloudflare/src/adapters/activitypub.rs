@@ -0,0 +1,74 @@
+//! ActivityPub fetches and deliveries using the Workers Fetch API.
+//!
+//! Deliveries run on [`InlineJobSink`](super::webhooks::InlineJobSink)
+//! before the request that raised them returns: the inbox request for an
+//! `Accept`, the build for new pages.
+
+use async_trait::async_trait;
+use diaryx_server::ports::{ActivityPubClient, ServerCoreError};
+use worker::{Fetch, Headers, Method, Request, RequestInit};
+
+fn e(err: impl std::fmt::Display) -> ServerCoreError {
+    ServerCoreError::internal(err.to_string())
+}
+
+fn request(
+    url: &str,
+    method: Method,
+    headers: &[(String, String)],
+    body: Option<&[u8]>,
+) -> Result<Request, ServerCoreError> {
+    let request_headers = Headers::new();
+    for (name, value) in headers {
+        request_headers.set(name, value).map_err(e)?;
+    }
+
+    let mut init = RequestInit::new();
+    init.with_method(method);
+    init.with_headers(request_headers);
+    if let Some(body) = body {
+        init.with_body(Some(js_sys::Uint8Array::from(body).into()));
+    }
+    Request::new_with_init(url, &init)
+        .map_err(|err| ServerCoreError::invalid_input(err.to_string()))
+}
+
+/// [`ActivityPubClient`] over `worker::Fetch`.
+pub struct FetchActivityPubClient;
+
+#[async_trait(?Send)]
+impl ActivityPubClient for FetchActivityPubClient {
+    async fn get(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        max_bytes: usize,
+    ) -> Result<(u16, String), ServerCoreError> {
+        let req = request(url, Method::Get, headers, None)?;
+        let mut resp = Fetch::Request(req)
+            .send()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        let status = resp.status_code();
+        let mut body = resp
+            .bytes()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        body.truncate(max_bytes);
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError> {
+        let req = request(url, Method::Post, headers, Some(body))?;
+        let resp = Fetch::Request(req)
+            .send()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        Ok(resp.status_code())
+    }
+}
@@ -399,6 +399,1102 @@ impl NamespaceStore for D1NamespaceStore {
     }
 }
 
+// ---------------------------------------------------------------------------
+// NamespaceMemberStore
+// ---------------------------------------------------------------------------
+
+const MEMBER_SELECT: &str = "SELECT m.namespace_id, m.user_id, u.email, m.role, m.created_at \
+     FROM namespace_members m LEFT JOIN users u ON u.id = m.user_id";
+
+const INVITE_COLUMNS: &str = "id, namespace_id, email, role, invited_by, created_at, expires_at";
+
+/// Deserialize a D1 row into a `NamespaceMemberInfo`. Unknown roles decode
+/// to `viewer` so a corrupt row grants the least access.
+fn row_to_member(row: serde_json::Value) -> NamespaceMemberInfo {
+    NamespaceMemberInfo {
+        namespace_id: row["namespace_id"].as_str().unwrap_or_default().to_string(),
+        user_id: row["user_id"].as_str().unwrap_or_default().to_string(),
+        email: row["email"].as_str().map(String::from),
+        role: NamespaceRole::parse(row["role"].as_str().unwrap_or_default())
+            .unwrap_or(NamespaceRole::Viewer),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+    }
+}
+
+fn row_to_invite(row: serde_json::Value) -> NamespaceInviteInfo {
+    NamespaceInviteInfo {
+        id: row["id"].as_str().unwrap_or_default().to_string(),
+        namespace_id: row["namespace_id"].as_str().unwrap_or_default().to_string(),
+        email: row["email"].as_str().unwrap_or_default().to_string(),
+        role: NamespaceRole::parse(row["role"].as_str().unwrap_or_default())
+            .unwrap_or(NamespaceRole::Viewer),
+        invited_by: row["invited_by"].as_str().unwrap_or_default().to_string(),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+        expires_at: row["expires_at"].as_i64().unwrap_or_default(),
+    }
+}
+
+pub struct D1NamespaceMemberStore {
+    db: D1Database,
+}
+
+impl D1NamespaceMemberStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl NamespaceMemberStore for D1NamespaceMemberStore {
+    async fn get_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<NamespaceMemberInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "{MEMBER_SELECT} WHERE m.namespace_id = ?1 AND m.user_id = ?2"
+            ))
+            .bind(&[namespace_id.into(), user_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.map(row_to_member))
+    }
+
+    async fn list_members(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "{MEMBER_SELECT} WHERE m.namespace_id = ?1 ORDER BY m.created_at, m.user_id"
+            ))
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().map(row_to_member).collect())
+    }
+
+    async fn list_user_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "{MEMBER_SELECT} WHERE m.user_id = ?1 ORDER BY m.created_at DESC, m.namespace_id"
+            ))
+            .bind(&[user_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().map(row_to_member).collect())
+    }
+
+    async fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare(
+                "INSERT INTO namespace_members (namespace_id, user_id, role, created_at) \
+                 VALUES (?1, ?2, ?3, ?4) \
+                 ON CONFLICT (namespace_id, user_id) DO UPDATE SET role = excluded.role",
+            )
+            .bind(&[
+                member.namespace_id.as_str().into(),
+                member.user_id.as_str().into(),
+                member.role.as_str().into(),
+                ts(member.created_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn remove_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare(
+                "DELETE FROM namespace_members WHERE namespace_id = ?1 AND user_id = ?2 \
+                 RETURNING user_id",
+            )
+            .bind(&[namespace_id.into(), user_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+
+    async fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare(
+                "INSERT INTO namespace_invites \
+                 (id, namespace_id, email, role, token_hash, invited_by, created_at, expires_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .bind(&[
+                invite.id.as_str().into(),
+                invite.namespace_id.as_str().into(),
+                invite.email.as_str().into(),
+                invite.role.as_str().into(),
+                token_hash.into(),
+                invite.invited_by.as_str().into(),
+                ts(invite.created_at),
+                ts(invite.expires_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_invite_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<NamespaceInviteInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "SELECT {INVITE_COLUMNS} FROM namespace_invites WHERE token_hash = ?1"
+            ))
+            .bind(&[token_hash.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.map(row_to_invite))
+    }
+
+    async fn list_invites(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {INVITE_COLUMNS} FROM namespace_invites \
+                 WHERE namespace_id = ?1 ORDER BY created_at DESC, id"
+            ))
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().map(row_to_invite).collect())
+    }
+
+    async fn delete_invite(
+        &self,
+        namespace_id: &str,
+        invite_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare(
+                "DELETE FROM namespace_invites WHERE namespace_id = ?1 AND id = ?2 RETURNING id",
+            )
+            .bind(&[namespace_id.into(), invite_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WebhookStore
+// ---------------------------------------------------------------------------
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, namespace_id, event, payload, status, attempts, \
+     next_attempt_at, response_status, last_error, created_at, updated_at";
+
+fn row_to_webhook(row: serde_json::Value) -> WebhookInfo {
+    WebhookInfo {
+        id: row["id"].as_str().unwrap_or_default().to_string(),
+        namespace_id: row["namespace_id"].as_str().unwrap_or_default().to_string(),
+        url: row["url"].as_str().unwrap_or_default().to_string(),
+        events: row["events"]
+            .as_str()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        created_by: row["created_by"].as_str().unwrap_or_default().to_string(),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+    }
+}
+
+/// Rows with an unknown event or status are skipped rather than guessed at.
+fn row_to_delivery(row: serde_json::Value) -> Option<WebhookDeliveryInfo> {
+    Some(WebhookDeliveryInfo {
+        id: row["id"].as_str()?.to_string(),
+        webhook_id: row["webhook_id"].as_str()?.to_string(),
+        namespace_id: row["namespace_id"].as_str()?.to_string(),
+        event: WebhookEvent::parse(row["event"].as_str()?)?,
+        payload: row["payload"].as_str().unwrap_or_default().to_string(),
+        status: WebhookDeliveryStatus::parse(row["status"].as_str()?)?,
+        attempts: row["attempts"].as_u64().unwrap_or_default() as u32,
+        next_attempt_at: row["next_attempt_at"].as_i64(),
+        response_status: row["response_status"].as_u64().map(|s| s as u16),
+        last_error: row["last_error"].as_str().map(String::from),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+        updated_at: row["updated_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+pub struct D1WebhookStore {
+    db: D1Database,
+}
+
+impl D1WebhookStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl WebhookStore for D1WebhookStore {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookInfo,
+        secret: &str,
+    ) -> Result<(), ServerCoreError> {
+        let events = serde_json::to_string(&webhook.events).map_err(e)?;
+        self.db
+            .prepare(
+                "INSERT INTO namespace_webhooks \
+                 (id, namespace_id, url, events, secret, created_by, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .bind(&[
+                webhook.id.as_str().into(),
+                webhook.namespace_id.as_str().into(),
+                webhook.url.as_str().into(),
+                events.into(),
+                secret.into(),
+                webhook.created_by.as_str().into(),
+                ts(webhook.created_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_webhook(&self, webhook_id: &str) -> Result<Option<WebhookInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(
+                "SELECT id, namespace_id, url, events, created_by, created_at \
+                 FROM namespace_webhooks WHERE id = ?1",
+            )
+            .bind(&[webhook_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.map(row_to_webhook))
+    }
+
+    async fn get_webhook_secret(
+        &self,
+        webhook_id: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare("SELECT secret FROM namespace_webhooks WHERE id = ?1")
+            .bind(&[webhook_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(|row| row["secret"].as_str().map(String::from)))
+    }
+
+    async fn list_webhooks(&self, namespace_id: &str) -> Result<Vec<WebhookInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(
+                "SELECT id, namespace_id, url, events, created_by, created_at \
+                 FROM namespace_webhooks WHERE namespace_id = ?1 ORDER BY created_at, id",
+            )
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().map(row_to_webhook).collect())
+    }
+
+    /// Deliveries go with the webhook via `ON DELETE CASCADE`.
+    async fn delete_webhook(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare(
+                "DELETE FROM namespace_webhooks WHERE namespace_id = ?1 AND id = ?2 RETURNING id",
+            )
+            .bind(&[namespace_id.into(), webhook_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+
+    async fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), ServerCoreError> {
+        let null = worker::wasm_bindgen::JsValue::NULL;
+        self.db
+            .prepare(
+                "INSERT INTO webhook_deliveries \
+                 (id, webhook_id, namespace_id, event, payload, status, attempts, \
+                  next_attempt_at, response_status, last_error, created_at, updated_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12) \
+                 ON CONFLICT (id) DO UPDATE SET \
+                   status = excluded.status, attempts = excluded.attempts, \
+                   next_attempt_at = excluded.next_attempt_at, \
+                   response_status = excluded.response_status, \
+                   last_error = excluded.last_error, updated_at = excluded.updated_at",
+            )
+            .bind(&[
+                delivery.id.as_str().into(),
+                delivery.webhook_id.as_str().into(),
+                delivery.namespace_id.as_str().into(),
+                delivery.event.as_str().into(),
+                delivery.payload.as_str().into(),
+                delivery.status.as_str().into(),
+                ts(delivery.attempts as i64),
+                delivery.next_attempt_at.map(ts).unwrap_or(null.clone()),
+                delivery
+                    .response_status
+                    .map(|s| ts(s as i64))
+                    .unwrap_or(null.clone()),
+                delivery
+                    .last_error
+                    .as_deref()
+                    .map(|s| s.into())
+                    .unwrap_or(null),
+                ts(delivery.created_at),
+                ts(delivery.updated_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_delivery(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE id = ?1"
+            ))
+            .bind(&[delivery_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(row_to_delivery))
+    }
+
+    async fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries \
+                 WHERE namespace_id = ?1 AND (?2 IS NULL OR webhook_id = ?2) \
+                 ORDER BY created_at DESC, id DESC LIMIT ?3"
+            ))
+            .bind(&[
+                namespace_id.into(),
+                webhook_id
+                    .map(|s| s.into())
+                    .unwrap_or(worker::wasm_bindgen::JsValue::NULL),
+                ts(limit as i64),
+            ])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_delivery).collect())
+    }
+
+    async fn list_due_deliveries(
+        &self,
+        now: i64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries \
+                 WHERE status = 'pending' AND next_attempt_at <= ?1 \
+                 ORDER BY next_attempt_at, id LIMIT ?2"
+            ))
+            .bind(&[ts(now), ts(limit as i64)])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_delivery).collect())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WebmentionStore
+// ---------------------------------------------------------------------------
+
+const WEBMENTION_COLUMNS: &str =
+    "id, namespace_id, source, target, object_key, status, source_title, created_at, updated_at";
+
+/// Rows with an unknown status are skipped rather than guessed at.
+fn row_to_webmention(row: serde_json::Value) -> Option<WebmentionInfo> {
+    Some(WebmentionInfo {
+        id: row["id"].as_str()?.to_string(),
+        namespace_id: row["namespace_id"].as_str()?.to_string(),
+        source: row["source"].as_str()?.to_string(),
+        target: row["target"].as_str()?.to_string(),
+        object_key: row["object_key"].as_str()?.to_string(),
+        status: WebmentionStatus::parse(row["status"].as_str()?)?,
+        source_title: row["source_title"].as_str().map(String::from),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+        updated_at: row["updated_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+pub struct D1WebmentionStore {
+    db: D1Database,
+}
+
+impl D1WebmentionStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl WebmentionStore for D1WebmentionStore {
+    async fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), ServerCoreError> {
+        let null = worker::wasm_bindgen::JsValue::NULL;
+        self.db
+            .prepare(format!(
+                "INSERT INTO webmentions ({WEBMENTION_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) \
+                 ON CONFLICT(id) DO UPDATE SET \
+                    object_key = excluded.object_key, \
+                    status = excluded.status, \
+                    source_title = excluded.source_title, \
+                    updated_at = excluded.updated_at"
+            ))
+            .bind(&[
+                mention.id.as_str().into(),
+                mention.namespace_id.as_str().into(),
+                mention.source.as_str().into(),
+                mention.target.as_str().into(),
+                mention.object_key.as_str().into(),
+                mention.status.as_str().into(),
+                mention
+                    .source_title
+                    .as_deref()
+                    .map(|t| t.into())
+                    .unwrap_or(null),
+                ts(mention.created_at),
+                ts(mention.updated_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_webmention(&self, id: &str) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "SELECT {WEBMENTION_COLUMNS} FROM webmentions WHERE id = ?1"
+            ))
+            .bind(&[id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(row_to_webmention))
+    }
+
+    async fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "SELECT {WEBMENTION_COLUMNS} FROM webmentions \
+                 WHERE namespace_id = ?1 AND source = ?2 AND target = ?3"
+            ))
+            .bind(&[namespace_id.into(), source.into(), target.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(row_to_webmention))
+    }
+
+    async fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError> {
+        let status = status
+            .map(|s| s.as_str().into())
+            .unwrap_or(worker::wasm_bindgen::JsValue::NULL);
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {WEBMENTION_COLUMNS} FROM webmentions \
+                 WHERE namespace_id = ?1 AND (?2 IS NULL OR status = ?2) \
+                 ORDER BY created_at DESC, id DESC LIMIT ?3"
+            ))
+            .bind(&[namespace_id.into(), status, ts(limit as i64)])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_webmention).collect())
+    }
+
+    async fn delete_webmention(
+        &self,
+        namespace_id: &str,
+        id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare("DELETE FROM webmentions WHERE namespace_id = ?1 AND id = ?2 RETURNING id")
+            .bind(&[namespace_id.into(), id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CommentStore
+// ---------------------------------------------------------------------------
+
+const COMMENT_COLUMNS: &str = "id, namespace_id, audience, object_key, source_key, author_name, \
+     body, status, token_id, created_at, updated_at";
+
+/// Rows with an unknown status are skipped rather than guessed at.
+fn row_to_comment(row: serde_json::Value) -> Option<CommentInfo> {
+    Some(CommentInfo {
+        id: row["id"].as_str()?.to_string(),
+        namespace_id: row["namespace_id"].as_str()?.to_string(),
+        audience: row["audience"].as_str()?.to_string(),
+        object_key: row["object_key"].as_str()?.to_string(),
+        source_key: row["source_key"].as_str().map(String::from),
+        author_name: row["author_name"].as_str()?.to_string(),
+        body: row["body"].as_str()?.to_string(),
+        status: CommentStatus::parse(row["status"].as_str()?)?,
+        token_id: row["token_id"].as_str()?.to_string(),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+        updated_at: row["updated_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+pub struct D1CommentStore {
+    db: D1Database,
+}
+
+impl D1CommentStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl CommentStore for D1CommentStore {
+    async fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), ServerCoreError> {
+        let null = worker::wasm_bindgen::JsValue::NULL;
+        self.db
+            .prepare(format!(
+                "INSERT INTO comments ({COMMENT_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11) \
+                 ON CONFLICT(id) DO UPDATE SET \
+                    status = excluded.status, \
+                    updated_at = excluded.updated_at"
+            ))
+            .bind(&[
+                comment.id.as_str().into(),
+                comment.namespace_id.as_str().into(),
+                comment.audience.as_str().into(),
+                comment.object_key.as_str().into(),
+                comment
+                    .source_key
+                    .as_deref()
+                    .map(|k| k.into())
+                    .unwrap_or(null),
+                comment.author_name.as_str().into(),
+                comment.body.as_str().into(),
+                comment.status.as_str().into(),
+                comment.token_id.as_str().into(),
+                ts(comment.created_at),
+                ts(comment.updated_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_comment(&self, id: &str) -> Result<Option<CommentInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(format!(
+                "SELECT {COMMENT_COLUMNS} FROM comments WHERE id = ?1"
+            ))
+            .bind(&[id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(row_to_comment))
+    }
+
+    async fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError> {
+        let object_key = object_key
+            .map(|k| k.into())
+            .unwrap_or(worker::wasm_bindgen::JsValue::NULL);
+        let status = status
+            .map(|s| s.as_str().into())
+            .unwrap_or(worker::wasm_bindgen::JsValue::NULL);
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {COMMENT_COLUMNS} FROM comments \
+                 WHERE namespace_id = ?1 \
+                   AND (?2 IS NULL OR object_key = ?2) \
+                   AND (?3 IS NULL OR status = ?3) \
+                 ORDER BY created_at DESC, id DESC LIMIT ?4"
+            ))
+            .bind(&[namespace_id.into(), object_key, status, ts(limit as i64)])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_comment).collect())
+    }
+
+    async fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare("DELETE FROM comments WHERE namespace_id = ?1 AND id = ?2 RETURNING id")
+            .bind(&[namespace_id.into(), id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ActivityPubStore
+// ---------------------------------------------------------------------------
+
+const FOLLOWER_COLUMNS: &str = "namespace_id, actor, inbox, shared_inbox, created_at";
+const OUTBOX_COLUMNS: &str =
+    "id, namespace_id, audience, object_key, url, title, summary, published_at, updated_at";
+
+fn row_to_follower(row: serde_json::Value) -> Option<FollowerInfo> {
+    Some(FollowerInfo {
+        namespace_id: row["namespace_id"].as_str()?.to_string(),
+        actor: row["actor"].as_str()?.to_string(),
+        inbox: row["inbox"].as_str()?.to_string(),
+        shared_inbox: row["shared_inbox"].as_str().map(String::from),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+fn row_to_outbox_item(row: serde_json::Value) -> Option<OutboxItem> {
+    Some(OutboxItem {
+        id: row["id"].as_str()?.to_string(),
+        namespace_id: row["namespace_id"].as_str()?.to_string(),
+        audience: row["audience"].as_str()?.to_string(),
+        object_key: row["object_key"].as_str()?.to_string(),
+        url: row["url"].as_str()?.to_string(),
+        title: row["title"].as_str().unwrap_or_default().to_string(),
+        summary: row["summary"].as_str().unwrap_or_default().to_string(),
+        published_at: row["published_at"].as_i64().unwrap_or_default(),
+        updated_at: row["updated_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+pub struct D1ActivityPubStore {
+    db: D1Database,
+}
+
+impl D1ActivityPubStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl ActivityPubStore for D1ActivityPubStore {
+    async fn get_actor_key(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<ActorKeyPair>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(
+                "SELECT namespace_id, public_key_pem, private_key_pem, created_at \
+                 FROM activitypub_actor_keys WHERE namespace_id = ?1",
+            )
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.and_then(|row| {
+            Some(ActorKeyPair {
+                namespace_id: row["namespace_id"].as_str()?.to_string(),
+                public_key_pem: row["public_key_pem"].as_str()?.to_string(),
+                private_key_pem: row["private_key_pem"].as_str()?.to_string(),
+                created_at: row["created_at"].as_i64().unwrap_or_default(),
+            })
+        }))
+    }
+
+    async fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare(
+                "INSERT INTO activitypub_actor_keys \
+                    (namespace_id, public_key_pem, private_key_pem, created_at) \
+                 VALUES (?1, ?2, ?3, ?4) \
+                 ON CONFLICT(namespace_id) DO NOTHING",
+            )
+            .bind(&[
+                key.namespace_id.as_str().into(),
+                key.public_key_pem.as_str().into(),
+                key.private_key_pem.as_str().into(),
+                ts(key.created_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare(format!(
+                "INSERT INTO activitypub_followers ({FOLLOWER_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5) \
+                 ON CONFLICT(namespace_id, actor) DO UPDATE SET \
+                    inbox = excluded.inbox, \
+                    shared_inbox = excluded.shared_inbox"
+            ))
+            .bind(&[
+                follower.namespace_id.as_str().into(),
+                follower.actor.as_str().into(),
+                follower.inbox.as_str().into(),
+                follower
+                    .shared_inbox
+                    .as_deref()
+                    .map(|s| s.into())
+                    .unwrap_or(worker::wasm_bindgen::JsValue::NULL),
+                ts(follower.created_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn delete_follower(
+        &self,
+        namespace_id: &str,
+        actor: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare(
+                "DELETE FROM activitypub_followers \
+                 WHERE namespace_id = ?1 AND actor = ?2 RETURNING actor",
+            )
+            .bind(&[namespace_id.into(), actor.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+
+    async fn list_followers(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<FollowerInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {FOLLOWER_COLUMNS} FROM activitypub_followers \
+                 WHERE namespace_id = ?1 ORDER BY created_at, actor"
+            ))
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_follower).collect())
+    }
+
+    async fn count_followers(&self, namespace_id: &str) -> Result<u64, ServerCoreError> {
+        let result = self
+            .db
+            .prepare("SELECT COUNT(*) AS n FROM activitypub_followers WHERE namespace_id = ?1")
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result
+            .and_then(|row| row["n"].as_u64())
+            .unwrap_or_default())
+    }
+
+    async fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare(format!(
+                "INSERT INTO activitypub_outbox ({OUTBOX_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) \
+                 ON CONFLICT(namespace_id, object_key) DO UPDATE SET \
+                    audience = excluded.audience, \
+                    url = excluded.url, \
+                    title = excluded.title, \
+                    summary = excluded.summary, \
+                    updated_at = excluded.updated_at"
+            ))
+            .bind(&[
+                item.id.as_str().into(),
+                item.namespace_id.as_str().into(),
+                item.audience.as_str().into(),
+                item.object_key.as_str().into(),
+                item.url.as_str().into(),
+                item.title.as_str().into(),
+                item.summary.as_str().into(),
+                ts(item.published_at),
+                ts(item.updated_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn list_outbox_items(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<OutboxItem>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {OUTBOX_COLUMNS} FROM activitypub_outbox \
+                 WHERE namespace_id = ?1 ORDER BY published_at DESC, object_key"
+            ))
+            .bind(&[namespace_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_outbox_item).collect())
+    }
+
+    async fn delete_outbox_item(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare(
+                "DELETE FROM activitypub_outbox \
+                 WHERE namespace_id = ?1 AND object_key = ?2 RETURNING object_key",
+            )
+            .bind(&[namespace_id.into(), object_key.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuditLogStore
+// ---------------------------------------------------------------------------
+
+const AUDIT_COLUMNS: &str =
+    "id, action, actor_user_id, user_id, namespace_id, target, details, created_at";
+
+/// Rows with an unknown action are skipped rather than guessed at.
+fn row_to_audit_event(row: serde_json::Value) -> Option<AuditEvent> {
+    Some(AuditEvent {
+        id: row["id"].as_str()?.to_string(),
+        action: AuditAction::parse(row["action"].as_str()?)?,
+        actor_user_id: row["actor_user_id"].as_str().map(String::from),
+        user_id: row["user_id"].as_str().map(String::from),
+        namespace_id: row["namespace_id"].as_str().map(String::from),
+        target: row["target"].as_str().map(String::from),
+        details: row["details"]
+            .as_str()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+    })
+}
+
+pub struct D1AuditLogStore {
+    db: D1Database,
+}
+
+impl D1AuditLogStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl AuditLogStore for D1AuditLogStore {
+    async fn append_audit_event(&self, event: &AuditEvent) -> Result<(), ServerCoreError> {
+        let null = worker::wasm_bindgen::JsValue::NULL;
+        let opt = |v: &Option<String>| v.as_deref().map(|s| s.into()).unwrap_or(null.clone());
+        let details = if event.details.is_null() {
+            null.clone()
+        } else {
+            event.details.to_string().into()
+        };
+        self.db
+            .prepare(format!(
+                "INSERT INTO audit_events ({AUDIT_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+            ))
+            .bind(&[
+                event.id.as_str().into(),
+                event.action.as_str().into(),
+                opt(&event.actor_user_id),
+                opt(&event.user_id),
+                opt(&event.namespace_id),
+                opt(&event.target),
+                details,
+                ts(event.created_at),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn list_audit_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        let (column, id) = match scope {
+            AuditScope::User(id) => ("user_id", id),
+            AuditScope::Namespace(id) => ("namespace_id", id),
+        };
+        let results = self
+            .db
+            .prepare(format!(
+                "SELECT {AUDIT_COLUMNS} FROM audit_events \
+                 WHERE {column} = ?1 AND created_at < ?2 \
+                 ORDER BY created_at DESC, rowid DESC LIMIT ?3"
+            ))
+            .bind(&[
+                id.as_str().into(),
+                ts(before.unwrap_or(i64::MAX)),
+                ts(limit as i64),
+            ])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().filter_map(row_to_audit_event).collect())
+    }
+
+    async fn prune_audit_events(&self, cutoff: i64) -> Result<u64, ServerCoreError> {
+        let results = self
+            .db
+            .prepare("DELETE FROM audit_events WHERE created_at < ?1 RETURNING id")
+            .bind(&[ts(cutoff)])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.len() as u64)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // AuthSessionStore
 // ---------------------------------------------------------------------------
@@ -615,6 +1711,150 @@ impl AuthStore for D1AuthStore {
     }
 }
 
+// ---------------------------------------------------------------------------
+// AccessTokenStore
+// ---------------------------------------------------------------------------
+
+/// Deserialize a D1 row into an `AccessTokenInfo`. Malformed scope or
+/// namespace JSON decodes to an empty list so a corrupt row grants nothing.
+fn row_to_access_token(row: serde_json::Value) -> AccessTokenInfo {
+    let scopes = serde_json::from_str(row["scopes"].as_str().unwrap_or("[]")).unwrap_or_default();
+    let namespace_ids = row["namespace_ids"]
+        .as_str()
+        .map(|json| serde_json::from_str(json).unwrap_or_default());
+    AccessTokenInfo {
+        id: row["id"].as_str().unwrap_or_default().to_string(),
+        user_id: row["user_id"].as_str().unwrap_or_default().to_string(),
+        name: row["name"].as_str().unwrap_or_default().to_string(),
+        scopes,
+        namespace_ids,
+        created_at: row["created_at"].as_i64().unwrap_or_default(),
+        expires_at: row["expires_at"].as_i64(),
+        last_used_at: row["last_used_at"].as_i64(),
+    }
+}
+
+pub struct D1AccessTokenStore {
+    db: D1Database,
+}
+
+impl D1AccessTokenStore {
+    pub fn new(db: D1Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait(?Send)]
+impl AccessTokenStore for D1AccessTokenStore {
+    async fn create_access_token(
+        &self,
+        token: &AccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        let scopes = serde_json::to_string(&token.scopes).map_err(e)?;
+        let namespace_ids = token
+            .namespace_ids
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(e)?;
+        self.db
+            .prepare(
+                "INSERT INTO access_tokens \
+                 (id, user_id, name, token_hash, scopes, namespace_ids, created_at, expires_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )
+            .bind(&[
+                token.id.as_str().into(),
+                token.user_id.as_str().into(),
+                token.name.as_str().into(),
+                token_hash.into(),
+                scopes.as_str().into(),
+                namespace_ids
+                    .map(|s| s.into())
+                    .unwrap_or(worker::wasm_bindgen::JsValue::NULL),
+                ts(token.created_at),
+                token
+                    .expires_at
+                    .map(ts)
+                    .unwrap_or(worker::wasm_bindgen::JsValue::NULL),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AccessTokenInfo>, ServerCoreError> {
+        let result = self
+            .db
+            .prepare(
+                "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at, last_used_at \
+                 FROM access_tokens WHERE token_hash = ?1",
+            )
+            .bind(&[token_hash.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(result.map(row_to_access_token))
+    }
+
+    async fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenInfo>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(
+                "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at, last_used_at \
+                 FROM access_tokens WHERE user_id = ?1 ORDER BY created_at DESC, id",
+            )
+            .bind(&[user_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows.into_iter().map(row_to_access_token).collect())
+    }
+
+    async fn delete_access_token(
+        &self,
+        user_id: &str,
+        token_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let deleted = self
+            .db
+            .prepare("DELETE FROM access_tokens WHERE id = ?1 AND user_id = ?2 RETURNING id")
+            .bind(&[token_id.into(), user_id.into()])
+            .map_err(e)?
+            .first::<serde_json::Value>(None)
+            .await
+            .map_err(e)?;
+        Ok(deleted.is_some())
+    }
+
+    async fn touch_access_token(
+        &self,
+        token_id: &str,
+        used_at: i64,
+    ) -> Result<(), ServerCoreError> {
+        self.db
+            .prepare("UPDATE access_tokens SET last_used_at = ?1 WHERE id = ?2")
+            .bind(&[ts(used_at), token_id.into()])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ObjectMetaStore
 // ---------------------------------------------------------------------------
@@ -859,6 +2099,34 @@ impl ObjectMetaStore for D1ObjectMetaStore {
         Ok(())
     }
 
+    async fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(
+                "SELECT event_type, amount, namespace_id, recorded_at FROM usage_events \
+                 WHERE user_id = ?1 ORDER BY recorded_at, id",
+            )
+            .bind(&[user_id.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| UsageEvent {
+                event_type: row["event_type"].as_str().unwrap_or_default().to_string(),
+                amount: row["amount"].as_u64().unwrap_or(0),
+                // Rows recorded without a namespace store an empty string.
+                namespace_id: row["namespace_id"]
+                    .as_str()
+                    .filter(|id| !id.is_empty())
+                    .map(String::from),
+                recorded_at: row["recorded_at"].as_i64().unwrap_or(0),
+            })
+            .collect())
+    }
+
     async fn get_usage_totals(&self, user_id: &str) -> Result<UsageTotals, ServerCoreError> {
         let q = "SELECT COALESCE(SUM(amount), 0) as total FROM usage_events \
                  WHERE user_id = ?1 AND event_type = ?2";
@@ -1853,4 +3121,62 @@ impl ArkIndexStore for D1ArkIndexStore {
             })
             .collect())
     }
+
+    async fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError> {
+        let now = chrono::Utc::now().timestamp();
+        self.db
+            .prepare(
+                "INSERT OR IGNORE INTO ark_versions (workspace_ark, file_ark, version, object_key, created_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .bind(&[
+                workspace_ark.into(),
+                file_ark.into(),
+                version.into(),
+                object_key.into(),
+                ts(now),
+            ])
+            .map_err(e)?
+            .run()
+            .await
+            .map_err(e)?;
+        Ok(())
+    }
+
+    async fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<ArkVersionEntry>, ServerCoreError> {
+        let results = self
+            .db
+            .prepare(
+                "SELECT workspace_ark, file_ark, version, object_key, created_at \
+                 FROM ark_versions WHERE workspace_ark = ?1 AND file_ark = ?2 \
+                 ORDER BY created_at DESC, version",
+            )
+            .bind(&[workspace_ark.into(), file_ark.into()])
+            .map_err(e)?
+            .all()
+            .await
+            .map_err(e)?;
+        let rows: Vec<serde_json::Value> = results.results().map_err(e)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ArkVersionEntry {
+                workspace_ark: row["workspace_ark"].as_str().unwrap_or_default().to_string(),
+                file_ark: row["file_ark"].as_str().unwrap_or_default().to_string(),
+                version: row["version"].as_str().unwrap_or_default().to_string(),
+                object_key: row["object_key"].as_str().unwrap_or_default().to_string(),
+                created_at: row["created_at"].as_i64().unwrap_or_default(),
+            })
+            .collect())
+    }
 }
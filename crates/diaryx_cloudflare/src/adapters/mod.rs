@@ -1,4 +1,7 @@
+pub mod activitypub;
 pub mod d1;
 pub mod kv;
 pub mod r2;
 pub mod resend;
+pub mod webhooks;
+pub mod webmentions;
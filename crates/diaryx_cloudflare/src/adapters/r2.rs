@@ -1,7 +1,7 @@
 //! R2 adapter for the BlobStore trait.
 
 use async_trait::async_trait;
-use diaryx_server::ports::{BlobStore, MultipartCompletedPart, ServerCoreError};
+use diaryx_server::ports::{BlobEntry, BlobStore, MultipartCompletedPart, ServerCoreError};
 use std::collections::HashMap;
 use worker::Bucket;
 
@@ -148,20 +148,39 @@ impl BlobStore for R2BlobStore {
     }
 
     async fn list_by_prefix(&self, prefix: &str) -> Result<Vec<String>, ServerCoreError> {
-        let list = self
-            .bucket
-            .list()
-            .prefix(prefix.to_string())
-            .execute()
-            .await
-            .map_err(e)?;
-        Ok(list
-            .objects()
+        Ok(self
+            .list_entries_by_prefix(prefix)
+            .await?
             .into_iter()
-            .map(|obj| obj.key().to_string())
+            .map(|entry| entry.key)
             .collect())
     }
 
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        let mut entries = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut list = self.bucket.list().prefix(prefix.to_string());
+            if let Some(cursor) = cursor.take() {
+                list = list.cursor(cursor);
+            }
+            let page = list.execute().await.map_err(e)?;
+            entries.extend(page.objects().into_iter().map(|obj| BlobEntry {
+                key: obj.key(),
+                size_bytes: Some(obj.size()),
+                last_modified: Some((obj.uploaded().as_millis() / 1000) as i64),
+            }));
+            match page.cursor() {
+                Some(next) if page.truncated() => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(entries)
+    }
+
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError> {
         let keys = self.list_by_prefix(prefix).await?;
         let count = keys.len();
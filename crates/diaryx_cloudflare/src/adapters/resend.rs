@@ -4,6 +4,7 @@
 //! instead of `reqwest` since reqwest doesn't work in Workers.
 
 use async_trait::async_trait;
+use diaryx_server::domain::NamespaceRole;
 use diaryx_server::ports::{Mailer, ServerCoreError};
 use serde::Serialize;
 use worker::{Fetch, Headers, Method, Request, RequestInit};
@@ -95,19 +96,18 @@ struct ResendRequest {
     html: String,
 }
 
-#[async_trait(?Send)]
-impl Mailer for ResendMailer {
-    async fn send_magic_link(
+impl ResendMailer {
+    async fn send(
         &self,
         to_email: &str,
-        magic_link_url: &str,
-        verification_code: &str,
+        subject: &str,
+        html: String,
     ) -> Result<(), ServerCoreError> {
         let body = ResendRequest {
             from: format!("{} <{}>", self.from_name, self.from_email),
             to: vec![to_email.to_string()],
-            subject: "Sign in to Diaryx".to_string(),
-            html: self.build_email_body(magic_link_url, verification_code),
+            subject: subject.to_string(),
+            html,
         };
 
         let json = serde_json::to_string(&body).map_err(e)?;
@@ -138,3 +138,220 @@ impl Mailer for ResendMailer {
         Ok(())
     }
 }
+
+fn build_invite_email_body(
+    inviter_email: &str,
+    namespace_name: &str,
+    role: NamespaceRole,
+    accept_url: &str,
+) -> String {
+    let (as_role, can) = match role {
+        NamespaceRole::Viewer => ("a viewer", "read everything in it, including private pages"),
+        NamespaceRole::Editor => ("an editor", "read, publish and edit pages in it"),
+        NamespaceRole::Owner => ("an owner", "manage it with the same rights as its owner"),
+    };
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>You're invited to {name}</title>
+</head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="text-align: center; margin-bottom: 30px;">
+        <h1 style="color: #1a1a1a; margin-bottom: 10px;">Diaryx</h1>
+    </div>
+
+    <div style="background-color: #f9f9f9; border-radius: 8px; padding: 30px; margin-bottom: 20px;">
+        <h2 style="margin-top: 0; color: #1a1a1a;">You're invited to {name}</h2>
+        <p>{inviter} added you as {role} of <strong>{name}</strong>. You'll be able to {can}.</p>
+        <p>Sign in with this email address to accept. This invite expires in {days} days.</p>
+
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{link}" style="display: inline-block; background-color: #0066cc; color: white; text-decoration: none; padding: 14px 28px; border-radius: 6px; font-weight: 500;">
+                Accept invite
+            </a>
+        </div>
+
+        <p style="color: #666; font-size: 14px;">
+            If the button doesn't work, copy and paste this link into your browser:
+        </p>
+        <p style="word-break: break-all; color: #0066cc; font-size: 14px;">
+            <a href="{link}" style="color: #0066cc;">{link}</a>
+        </p>
+    </div>
+
+    <div style="text-align: center; color: #999; font-size: 12px;">
+        <p>If you weren't expecting this invite, you can safely ignore it.</p>
+        <p>&copy; Diaryx</p>
+    </div>
+</body>
+</html>"#,
+        name = escape_html(namespace_name),
+        inviter = escape_html(inviter_email),
+        role = as_role,
+        can = can,
+        days = diaryx_server::use_cases::members::INVITE_EXPIRY_DAYS,
+        link = accept_url,
+    )
+}
+
+fn build_account_deletion_email_body(confirm_url: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Confirm account deletion</title>
+</head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="text-align: center; margin-bottom: 30px;">
+        <h1 style="color: #1a1a1a; margin-bottom: 10px;">Diaryx</h1>
+    </div>
+
+    <div style="background-color: #f9f9f9; border-radius: 8px; padding: 30px; margin-bottom: 20px;">
+        <h2 style="margin-top: 0; color: #1a1a1a;">Confirm account deletion</h2>
+        <p>Someone signed in to your account asked to delete it. Deleting removes your namespaces, published pages, stored files and devices, and cannot be undone.</p>
+        <p>Consider downloading an export first. This link expires in {minutes} minutes.</p>
+
+        <div style="text-align: center; margin: 30px 0;">
+            <a href="{link}" style="display: inline-block; background-color: #cc3300; color: white; text-decoration: none; padding: 14px 28px; border-radius: 6px; font-weight: 500;">
+                Delete my account
+            </a>
+        </div>
+
+        <p style="color: #666; font-size: 14px;">
+            If the button doesn't work, copy and paste this link into your browser:
+        </p>
+        <p style="word-break: break-all; color: #0066cc; font-size: 14px;">
+            <a href="{link}" style="color: #0066cc;">{link}</a>
+        </p>
+    </div>
+
+    <div style="text-align: center; color: #999; font-size: 12px;">
+        <p>If you didn't ask for this, ignore this email and your account stays as it is.</p>
+        <p>&copy; Diaryx</p>
+    </div>
+</body>
+</html>"#,
+        minutes = diaryx_server::use_cases::current_user::ACCOUNT_DELETION_EXPIRY_MINUTES,
+        link = confirm_url,
+    )
+}
+
+fn build_comment_email_body(
+    namespace_name: &str,
+    page: &str,
+    author_name: &str,
+    body: &str,
+) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>New comment on {name}</title>
+</head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <div style="text-align: center; margin-bottom: 30px;">
+        <h1 style="color: #1a1a1a; margin-bottom: 10px;">Diaryx</h1>
+    </div>
+
+    <div style="background-color: #f9f9f9; border-radius: 8px; padding: 30px; margin-bottom: 20px;">
+        <h2 style="margin-top: 0; color: #1a1a1a;">New comment on {name}</h2>
+        <p><strong>{author}</strong> commented on <strong>{page}</strong>:</p>
+        <blockquote style="margin: 20px 0; padding: 12px 20px; border-left: 4px solid #0066cc; background-color: #fff; white-space: pre-wrap;">{body}</blockquote>
+        <p>It stays hidden until you approve it. Approved comments are added next to the entry in your workspace on your next publish.</p>
+    </div>
+
+    <div style="text-align: center; color: #999; font-size: 12px;">
+        <p>You're getting this because you own {name}.</p>
+        <p>&copy; Diaryx</p>
+    </div>
+</body>
+</html>"#,
+        name = escape_html(namespace_name),
+        page = escape_html(page),
+        author = escape_html(author_name),
+        body = escape_html(body),
+    )
+}
+
+/// The namespace name comes from client-set metadata, so escape it (and the
+/// inviter's address) before putting it in the HTML body. Comments are
+/// written by readers and are escaped the same way.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[async_trait(?Send)]
+impl Mailer for ResendMailer {
+    async fn send_magic_link(
+        &self,
+        to_email: &str,
+        magic_link_url: &str,
+        verification_code: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.send(
+            to_email,
+            "Sign in to Diaryx",
+            self.build_email_body(magic_link_url, verification_code),
+        )
+        .await
+    }
+
+    async fn send_namespace_invite(
+        &self,
+        to_email: &str,
+        inviter_email: &str,
+        namespace_name: &str,
+        role: NamespaceRole,
+        accept_url: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.send(
+            to_email,
+            &format!(
+                "{} invited you to {} on Diaryx",
+                inviter_email, namespace_name
+            ),
+            build_invite_email_body(inviter_email, namespace_name, role, accept_url),
+        )
+        .await
+    }
+
+    async fn send_account_deletion_confirmation(
+        &self,
+        to_email: &str,
+        confirm_url: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.send(
+            to_email,
+            "Confirm deleting your Diaryx account",
+            build_account_deletion_email_body(confirm_url),
+        )
+        .await
+    }
+
+    async fn send_comment_notification(
+        &self,
+        to_email: &str,
+        namespace_name: &str,
+        page: &str,
+        author_name: &str,
+        body: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.send(
+            to_email,
+            &format!("{} commented on {}", author_name, namespace_name),
+            build_comment_email_body(namespace_name, page, author_name, body),
+        )
+        .await
+    }
+}
@@ -0,0 +1,131 @@
+//! Outbound webhook adapters using the Workers Fetch API.
+//!
+//! Workers have no background task pool, so [`InlineJobSink`] makes the first
+//! delivery attempt before the request that raised the event returns.
+//! Retries are picked up by the cron sweep (`handlers::retry_webhooks`).
+//! Webmention verification runs the same way, inside the receiving request,
+//! as do ActivityPub deliveries (attempted once, never retried).
+
+use super::activitypub::FetchActivityPubClient;
+use super::d1::{D1ActivityPubStore, D1WebhookStore, D1WebmentionStore};
+use super::webmentions::FetchWebmentionFetcher;
+use async_trait::async_trait;
+use diaryx_server::ports::{JobSink, ServerCoreError, WebhookTransport};
+use diaryx_server::use_cases::activitypub::{
+    ACTIVITYPUB_DELIVERY_JOB, ActivityPubDelivery, ActivityPubDeliveryJob,
+};
+use diaryx_server::use_cases::webhooks::{
+    WEBHOOK_DELIVERY_JOB, WebhookDeliveryJob, WebhookDeliveryService,
+};
+use diaryx_server::use_cases::webmentions::{
+    WEBMENTION_VERIFY_JOB, WebmentionVerifier, WebmentionVerifyJob,
+};
+use serde_json::Value;
+use worker::{Fetch, Headers, Method, Request, RequestInit};
+
+fn e(err: impl std::fmt::Display) -> ServerCoreError {
+    ServerCoreError::internal(err.to_string())
+}
+
+/// [`WebhookTransport`] over `worker::Fetch`.
+pub struct FetchWebhookTransport;
+
+#[async_trait(?Send)]
+impl WebhookTransport for FetchWebhookTransport {
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError> {
+        let request_headers = Headers::new();
+        for (name, value) in headers {
+            request_headers.set(name, value).map_err(e)?;
+        }
+
+        let mut init = RequestInit::new();
+        init.with_method(Method::Post);
+        init.with_headers(request_headers);
+        init.with_body(Some(js_sys::Uint8Array::from(body).into()));
+
+        let req = Request::new_with_init(url, &init).map_err(e)?;
+        let resp = Fetch::Request(req)
+            .send()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        Ok(resp.status_code())
+    }
+}
+
+/// [`JobSink`] that runs each job before `enqueue` returns.
+pub struct InlineJobSink {
+    webhook_store: D1WebhookStore,
+    webmention_store: Option<D1WebmentionStore>,
+    /// Actor keys, and the API base URL actor ids are built from.
+    activitypub: Option<(D1ActivityPubStore, String)>,
+}
+
+impl InlineJobSink {
+    pub fn new(webhook_store: D1WebhookStore) -> Self {
+        Self {
+            webhook_store,
+            webmention_store: None,
+            activitypub: None,
+        }
+    }
+
+    /// Run Webmention verification jobs. Without this they are refused.
+    pub fn with_webmentions(mut self, webmention_store: D1WebmentionStore) -> Self {
+        self.webmention_store = Some(webmention_store);
+        self
+    }
+
+    /// Run ActivityPub delivery jobs. Without this they are refused.
+    pub fn with_activitypub(
+        mut self,
+        activitypub_store: D1ActivityPubStore,
+        api_base_url: String,
+    ) -> Self {
+        self.activitypub = Some((activitypub_store, api_base_url));
+        self
+    }
+}
+
+#[async_trait(?Send)]
+impl JobSink for InlineJobSink {
+    async fn enqueue(&self, kind: &str, payload: Value) -> Result<(), ServerCoreError> {
+        match kind {
+            WEBHOOK_DELIVERY_JOB => {
+                let job = WebhookDeliveryJob::from_payload(&payload)?;
+                let now = (js_sys::Date::now() / 1000.0) as i64;
+                WebhookDeliveryService::new(&self.webhook_store, &FetchWebhookTransport)
+                    .deliver(&job.delivery_id, now)
+                    .await?;
+                Ok(())
+            }
+            WEBMENTION_VERIFY_JOB => {
+                let job = WebmentionVerifyJob::from_payload(&payload)?;
+                let store = self.webmention_store.as_ref().ok_or_else(|| {
+                    ServerCoreError::unavailable("Webmention verification is not configured")
+                })?;
+                let now = (js_sys::Date::now() / 1000.0) as i64;
+                WebmentionVerifier::new(store, &FetchWebmentionFetcher)
+                    .verify(&job.webmention_id, now)
+                    .await
+            }
+            ACTIVITYPUB_DELIVERY_JOB => {
+                let job = ActivityPubDeliveryJob::from_payload(&payload)?;
+                let (store, api_base_url) = self.activitypub.as_ref().ok_or_else(|| {
+                    ServerCoreError::unavailable("ActivityPub delivery is not configured")
+                })?;
+                let now = (js_sys::Date::now() / 1000.0) as i64;
+                ActivityPubDelivery::new(store, &FetchActivityPubClient, api_base_url)
+                    .deliver(&job, now)
+                    .await
+            }
+            other => Err(ServerCoreError::invalid_input(format!(
+                "Unknown job kind: {other}"
+            ))),
+        }
+    }
+}
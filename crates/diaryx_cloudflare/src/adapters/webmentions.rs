@@ -0,0 +1,30 @@
+//! Webmention source fetching using the Workers Fetch API.
+//!
+//! Verification runs on [`InlineJobSink`](super::webhooks::InlineJobSink)
+//! before the receiving request returns.
+
+use async_trait::async_trait;
+use diaryx_server::ports::{ServerCoreError, WebmentionFetcher};
+use worker::{Fetch, Method, Request};
+
+/// [`WebmentionFetcher`] over `worker::Fetch`.
+pub struct FetchWebmentionFetcher;
+
+#[async_trait(?Send)]
+impl WebmentionFetcher for FetchWebmentionFetcher {
+    async fn fetch(&self, url: &str, max_bytes: usize) -> Result<(u16, String), ServerCoreError> {
+        let req = Request::new(url, Method::Get)
+            .map_err(|err| ServerCoreError::invalid_input(err.to_string()))?;
+        let mut resp = Fetch::Request(req)
+            .send()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        let status = resp.status_code();
+        let mut body = resp
+            .bytes()
+            .await
+            .map_err(|err| ServerCoreError::unavailable(err.to_string()))?;
+        body.truncate(max_bytes);
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
+    }
+}
@@ -1,6 +1,7 @@
 //! Configuration from Worker environment variables / secrets.
 
 use crate::adapters::resend::ResendMailer;
+use diaryx_server::use_cases::audit::DEFAULT_AUDIT_RETENTION_DAYS;
 use diaryx_server::use_cases::auth::AuthConfig;
 use worker::Env;
 
@@ -152,3 +153,21 @@ pub fn cors_origins(env: &Env) -> Vec<String> {
         })
         .unwrap_or_else(|_| vec!["https://app.diaryx.org".to_string()])
 }
+
+/// Age in hours an unreferenced blob must reach before the scheduled
+/// reconciliation removes it.
+pub fn storage_reconcile_grace_hours(env: &Env) -> i64 {
+    env.var("STORAGE_RECONCILE_GRACE_HOURS")
+        .ok()
+        .and_then(|v| v.to_string().parse().ok())
+        .unwrap_or(24)
+}
+
+/// Days audit events are kept before the daily cron prunes them; `0` keeps
+/// them forever.
+pub fn audit_log_retention_days(env: &Env) -> u32 {
+    env.var("AUDIT_LOG_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.to_string().parse().ok())
+        .unwrap_or(DEFAULT_AUDIT_RETENTION_DAYS)
+}
@@ -3,8 +3,10 @@
 use crate::adapters::d1::*;
 use crate::adapters::kv::KvDomainMappingCache;
 use crate::adapters::r2::R2BlobStore;
+use crate::adapters::webhooks::InlineJobSink;
 use crate::config;
-use diaryx_server::audience_token::validate_audience_token;
+use diaryx_server::audience_token::{AudienceTokenClaims, validate_audience_token};
+use diaryx_server::domain::{AccountExportRecord, CommentStatus, NamespaceRole, WebmentionStatus};
 use diaryx_server::api::billing::{
     AppleRestoreResponse, AppleVerifyReceiptResponse, StripeConfigResponse, UrlResponse,
 };
@@ -16,20 +18,45 @@ use diaryx_server::api::passkeys::{
     PasskeyRegisterFinishRequest, PasskeyRegisterFinishResponse, PasskeyRegisterStartResponse,
 };
 use diaryx_server::ports::{
-    AuthStore, BlobStore, Mailer, NamespaceStore, ServerCoreError, UserStore,
+    AccountExportSink, AuthStore, BlobStore, Mailer, NamespaceStore, ServerCoreError, UserStore,
+};
+use diaryx_server::use_cases::access_tokens::{
+    AccessTokenService, CreateAccessTokenRequest, authorize_request,
 };
 use diaryx_server::use_cases::auth::{
     AuthConfig, AuthError, AuthenticationService, SessionValidationService, extract_token,
 };
 use diaryx_server::use_cases::billing::BillingService;
 use diaryx_server::use_cases::{
-    ark::{ARK_WORKSPACE_INDEX, ArkService, Inflection, inflection_json},
+    activitypub::{
+        ACTIVITY_JSON, ActivityPubInbox, ActivityPubPublisher, ActivityPubService, InboxRequest,
+        JRD_JSON,
+    },
+    ark::{
+        ARK_WORKSPACE_INDEX, ArkService, ErcKernel, Inflection, canonical_ark, inflection_json,
+        info_wants_json_ld, split_file_variant, versions_json,
+    },
     audiences::AudienceService,
+    comments::{CommentListQuery, CommentReader, CommentService, PostCommentRequest},
+    audit::{AuditLogQuery, AuditLogService, AuditRecorder},
+    current_user::{
+        AccountDeletionService, AccountExportService, ConfirmAccountDeletionRequest,
+        encode_account_export_record,
+    },
     domains::DomainService,
+    members::{
+        AcceptInviteRequest, InviteMemberRequest, InviteResponse, NamespaceMemberService,
+        UpdateMemberRoleRequest, require_namespace_role,
+    },
     namespaces::NamespaceService,
     objects::ObjectService,
     render::RenderService,
     sessions::SessionService,
+    storage::{ReconcileOptions, StorageReconcileService},
+    webhooks::{CreateWebhookRequest, DeliveryLogQuery, WebhookDispatcher, WebhookService},
+    webmentions::{
+        ReceiveWebmentionRequest, WebmentionListQuery, WebmentionReceiver, WebmentionService,
+    },
 };
 use serde::Deserialize;
 use worker::*;
@@ -170,9 +197,21 @@ async fn authenticate_context(
         }
     };
 
-    let service = SessionValidationService::new(&auth_store, &session_store);
+    let access_token_store = D1AccessTokenStore::new(db(ctx)?);
+    let service = SessionValidationService::new(&auth_store, &session_store)
+        .with_access_tokens(&access_token_store);
     match service.validate(&token).await {
-        Ok(auth_ctx) => Ok(Ok(auth_ctx)),
+        Ok(auth_ctx) => {
+            // Personal access tokens are limited to the routes and namespaces
+            // their scopes cover; device sessions reach everything.
+            if let Some(access_token) = &auth_ctx.access_token {
+                let method = req.method().to_string();
+                if let Err(e) = authorize_request(access_token, &method, &req.path()) {
+                    return Ok(Err(error_response(e)?));
+                }
+            }
+            Ok(Ok(auth_ctx))
+        }
         // A present-but-invalid/expired session (or a vanished user) surfaces as
         // `NotFound`. At the HTTP boundary that's 401, not 404 — the client keys
         // off 401 to clear its stale session and log out, and would otherwise
@@ -331,7 +370,9 @@ pub async fn list_objects(req: Request, ctx: RouteContext<()>) -> Result<Respons
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store);
 
     match service.list(&ns_id, limit, offset, &user_id).await {
         Ok(objects) => {
@@ -403,7 +444,9 @@ pub async fn put_object(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store);
 
     let result = match service
         .put(
@@ -447,6 +490,24 @@ pub async fn put_object(mut req: Request, ctx: RouteContext<()>) -> Result<Respo
         {
             return error_response(e);
         }
+        // Retain the source as an immutable `.<FILE>` version so earlier
+        // publishes stay addressable after this one replaces the source.
+        if let Some(source_key) = source_key.as_deref() {
+            let retained = match service
+                .retain_version(&ns_id, source_key, file_ark, &user_id)
+                .await
+            {
+                Ok(retained) => retained,
+                Err(e) => return error_response(e),
+            };
+            if let Some(v) = retained
+                && let Err(e) = ark_service
+                    .record_version(&ns_id, file_ark, &v.content_hash, &v.key)
+                    .await
+            {
+                return error_response(e);
+            }
+        }
     }
 
     Response::from_json(&serde_json::json!({ "key": result.key, "size_bytes": result.size_bytes }))
@@ -471,7 +532,22 @@ pub async fn build_namespace(req: Request, ctx: RouteContext<()>) -> Result<Resp
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
     let ark_store = D1ArkIndexStore::new(db(&ctx)?);
-    let service = RenderService::new(&ns_store, &obj_store, &blob_store, &ark_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let webmention_store = D1WebmentionStore::new(db(&ctx)?);
+    let activitypub_store = D1ActivityPubStore::new(db(&ctx)?);
+    let api_base_url = format!("{}/api", req.url()?.origin().ascii_serialization());
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?))
+        .with_activitypub(D1ActivityPubStore::new(db(&ctx)?), api_base_url.clone());
+    let service = RenderService::new(&ns_store, &obj_store, &blob_store, &ark_store)
+        .with_members(&member_store)
+        .with_webhooks(WebhookDispatcher::new(&webhook_store, &job_sink))
+        .with_webmentions(&webmention_store, &api_base_url)
+        .with_activitypub(ActivityPubPublisher::new(
+            &activitypub_store,
+            &job_sink,
+            &api_base_url,
+        ));
 
     match service
         .build_namespace(&ns_id, &user_id, base_url.as_deref())
@@ -494,7 +570,9 @@ pub async fn get_object(req: Request, ctx: RouteContext<()>) -> Result<Response>
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store);
 
     match service.get(&ns_id, &key, &user_id).await {
         Ok(result) => {
@@ -514,7 +592,12 @@ pub async fn delete_object(req: Request, ctx: RouteContext<()>) -> Result<Respon
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?));
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store)
+        .with_webhooks(WebhookDispatcher::new(&webhook_store, &job_sink));
 
     match service.delete(&ns_id, &key, &user_id).await {
         Ok(()) => Response::empty().map(|r| r.with_status(204)),
@@ -542,7 +625,9 @@ pub async fn batch_get_objects(mut req: Request, ctx: RouteContext<()>) -> Resul
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store);
 
     match service.get_batch(&ns_id, &body.keys, &user_id).await {
         Ok(result) => {
@@ -618,7 +703,9 @@ pub async fn batch_get_objects_multipart(
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = ObjectService::new(&ns_store, &obj_store, &blob_store)
+        .with_members(&member_store);
 
     let result = match service.get_batch(&ns_id, &body.keys, &user_id).await {
         Ok(r) => r,
@@ -748,7 +835,9 @@ fn ark_inflection_from_url(url: &worker::Url) -> (Inflection, Option<String>) {
             "audience_token" => token = Some(v.to_string()),
             "content" => inflection = Inflection::Content,
             "json" => inflection = Inflection::Json,
-            "info" => inflection = Inflection::Info,
+            // `??` arrives as a bare `?` query key.
+            "info" | "?" => inflection = Inflection::Info,
+            "versions" => inflection = Inflection::Versions,
             "meta" => inflection = Inflection::Meta(v.to_string()),
             other if other.starts_with('.') => {
                 inflection = Inflection::Meta(other[1..].to_string());
@@ -805,6 +894,12 @@ async fn do_resolve_ark(
     let (inflection, token) = ark_inflection_from_url(&url);
     let key_bytes = signing_key(ctx);
 
+    // `bcdfgr.3f9a0c1` selects a retained `.<FILE>` source version.
+    let (file, variant) = match split_file_variant(file) {
+        Ok(parts) => parts,
+        Err(e) => return error_response(e),
+    };
+
     let ark_store = D1ArkIndexStore::new(db(ctx)?);
     let ark_service = ArkService::new(&ark_store);
     let entry = match ark_service.resolve(ws, file).await {
@@ -826,36 +921,57 @@ async fn do_resolve_ark(
         return Response::empty().map(|r| r.with_status(403));
     }
 
-    if inflection == Inflection::Default {
-        return match service
-            .fetch_blob(ws, &entry.object_key, access.meta.blob_key.as_deref())
-            .await
-        {
-            Ok(result) => {
-                let mut resp = Response::from_bytes(result.bytes)?;
-                resp.headers_mut().set("content-type", &result.mime_type)?;
-                Ok(resp)
-            }
+    if inflection == Inflection::Versions {
+        return match ark_service.list_versions(ws, file).await {
+            Ok(versions) => Response::from_json(&versions_json(&versions)),
             Err(e) => error_response(e),
         };
     }
 
-    let Some(source_key) = entry.source_key.as_deref() else {
-        return Response::error("no source for this ARK", 404);
-    };
-    let src_access = match service.resolve_public_access(ws, source_key).await {
-        Ok(a) => a,
-        Err(e) => return error_response(e),
-    };
-    let obj = match service
-        .fetch_blob(ws, source_key, src_access.meta.blob_key.as_deref())
-        .await
-    {
-        Ok(o) => o,
-        Err(e) => return error_response(e),
+    // `version` is the full version a `.<FILE>` variant resolved to, so the
+    // canonical ARK is the same however short a prefix the request used.
+    let (obj, version) = if let Some(variant) = variant {
+        // Versioned: the retained source stands in for both renditions.
+        let version = match ark_service.resolve_version(ws, file, variant).await {
+            Ok(v) => v,
+            Err(e) => return error_response(e),
+        };
+        match service.fetch_object(ws, &version.object_key).await {
+            Ok(o) => (o, Some(version.version)),
+            Err(e) => return error_response(e),
+        }
+    } else {
+        if inflection == Inflection::Default {
+            return match service
+                .fetch_blob(ws, &entry.object_key, access.meta.blob_key.as_deref())
+                .await
+            {
+                Ok(result) => {
+                    let mut resp = Response::from_bytes(result.bytes)?;
+                    resp.headers_mut().set("content-type", &result.mime_type)?;
+                    Ok(resp)
+                }
+                Err(e) => error_response(e),
+            };
+        }
+
+        let Some(source_key) = entry.source_key.as_deref() else {
+            return Response::error("no source for this ARK", 404);
+        };
+        let src_access = match service.resolve_public_access(ws, source_key).await {
+            Ok(a) => a,
+            Err(e) => return error_response(e),
+        };
+        match service
+            .fetch_blob(ws, source_key, src_access.meta.blob_key.as_deref())
+            .await
+        {
+            Ok(o) => (o, None),
+            Err(e) => return error_response(e),
+        }
     };
 
-    if inflection == Inflection::Content {
+    if matches!(inflection, Inflection::Default | Inflection::Content) {
         let mut resp = Response::from_bytes(obj.bytes)?;
         resp.headers_mut()
             .set("content-type", "text/markdown; charset=utf-8")?;
@@ -863,6 +979,22 @@ async fn do_resolve_ark(
     }
 
     let src = String::from_utf8_lossy(&obj.bytes);
+    if inflection == Inflection::Info {
+        let erc = match ErcKernel::from_source(&src, &canonical_ark(ws, file, version.as_deref())) {
+            Ok(erc) => erc,
+            Err(e) => return error_response(e),
+        };
+        let accept = req.headers().get("accept")?;
+        let (body, content_type) = if info_wants_json_ld(accept.as_deref()) {
+            (erc.to_json_ld().to_string(), "application/ld+json")
+        } else {
+            (erc.to_erc(), "text/plain; charset=utf-8")
+        };
+        let mut resp = Response::ok(body)?;
+        resp.headers_mut().set("content-type", content_type)?;
+        return Ok(resp);
+    }
+
     match inflection_json(&src, &inflection) {
         Ok(json) => Response::from_json(&json),
         Err(e) => error_response(e),
@@ -888,7 +1020,14 @@ pub async fn set_audience(mut req: Request, ctx: RouteContext<()>) -> Result<Res
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?));
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store)
+        .with_members(&member_store)
+        .with_webhooks(WebhookDispatcher::new(&webhook_store, &job_sink))
+        .with_audit(AuditRecorder::new(&audit_store));
 
     match service.set(&ns_id, &name, body.gates, &user_id).await {
         Ok(info) => Response::from_json(&AudienceResponse::from(info)),
@@ -902,7 +1041,8 @@ pub async fn list_audiences(req: Request, ctx: RouteContext<()>) -> Result<Respo
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store).with_members(&member_store);
 
     match service.list(&ns_id, &user_id).await {
         Ok(list) => {
@@ -924,7 +1064,14 @@ pub async fn delete_audience(req: Request, ctx: RouteContext<()>) -> Result<Resp
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?));
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store)
+        .with_members(&member_store)
+        .with_webhooks(WebhookDispatcher::new(&webhook_store, &job_sink))
+        .with_audit(AuditRecorder::new(&audit_store));
 
     match service.delete(&ns_id, &name, &user_id).await {
         Ok(()) => Response::empty().map(|r| r.with_status(204)),
@@ -947,7 +1094,8 @@ pub async fn get_audience_link_token(
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store).with_members(&member_store);
     let key_bytes = signing_key(&ctx);
 
     match service
@@ -974,7 +1122,11 @@ pub async fn unlock_audience(
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store)
+        .with_members(&member_store)
+        .with_audit(AuditRecorder::new(&audit_store));
     let key_bytes = signing_key(&ctx);
 
     match service
@@ -1003,7 +1155,14 @@ pub async fn rotate_audience_password(
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let blob_store = R2BlobStore::new(bucket(&ctx)?);
-    let service = AudienceService::new(&ns_store, &blob_store);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?));
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AudienceService::new(&ns_store, &blob_store)
+        .with_members(&member_store)
+        .with_webhooks(WebhookDispatcher::new(&webhook_store, &job_sink))
+        .with_audit(AuditRecorder::new(&audit_store));
     let key_bytes = signing_key(&ctx);
 
     match service
@@ -1028,11 +1187,15 @@ pub async fn rotate_audience_password(
 // ---------------------------------------------------------------------------
 
 pub async fn list_domains(req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    let _user_id = require_auth!(&req, &ctx);
+    let user_id = require_auth!(&req, &ctx);
     let ns_id = require_decoded_param(&ctx, "ns_id")?;
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
-    match ns_store.list_custom_domains(&ns_id).await {
+    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = DomainService::new(&ns_store, &domain_cache).with_members(&member_store);
+
+    match service.list_domains(&ns_id, &user_id).await {
         Ok(domains) => {
             let response: Vec<serde_json::Value> = domains
                 .into_iter()
@@ -1058,7 +1221,7 @@ struct RegisterDomainBody {
 }
 
 pub async fn register_domain(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    let _user_id = require_auth!(&req, &ctx);
+    let user_id = require_auth!(&req, &ctx);
     let ns_id = require_decoded_param(&ctx, "ns_id")?;
     let domain = ctx
         .param("domain")
@@ -1068,10 +1231,14 @@ pub async fn register_domain(mut req: Request, ctx: RouteContext<()>) -> Result<
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
     let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
-    let service = DomainService::new(&ns_store, &domain_cache);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = DomainService::new(&ns_store, &domain_cache)
+        .with_members(&member_store)
+        .with_audit(AuditRecorder::new(&audit_store));
 
     let info = match service
-        .register_domain(&ns_id, &domain, &body.audience_name)
+        .register_domain(&ns_id, &domain, &body.audience_name, &user_id)
         .await
     {
         Ok(info) => info,
@@ -1118,121 +1285,799 @@ pub async fn register_domain(mut req: Request, ctx: RouteContext<()>) -> Result<
         }
     }
 
-    Response::from_json(&serde_json::json!({
-        "domain": info.domain,
-        "namespace_id": info.namespace_id,
-        "audience_name": info.audience_name,
-        "created_at": info.created_at,
-        "verified": info.verified,
-    }))
+    Response::from_json(&serde_json::json!({
+        "domain": info.domain,
+        "namespace_id": info.namespace_id,
+        "audience_name": info.audience_name,
+        "created_at": info.created_at,
+        "verified": info.verified,
+    }))
+}
+
+pub async fn remove_domain(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let domain = ctx
+        .param("domain")
+        .ok_or_else(|| Error::from("missing domain"))?
+        .to_string();
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = DomainService::new(&ns_store, &domain_cache)
+        .with_members(&member_store)
+        .with_audit(AuditRecorder::new(&audit_store));
+
+    // Only the owner may detach a domain; check before touching Cloudflare.
+    if let Err(e) = require_namespace_role(
+        &ns_store,
+        Some(&member_store),
+        &ns_id,
+        &user_id,
+        NamespaceRole::Owner,
+    )
+    .await
+    {
+        return error_response(e);
+    }
+
+    // Find and delete the Cloudflare custom hostname first.
+    if let (Some(zone_id), Some(api_token)) =
+        (config::cf_zone_id(&ctx.env), config::cf_api_token(&ctx.env))
+    {
+        // Look up the custom hostname ID by hostname.
+        let list_url = format!(
+            "https://api.cloudflare.com/client/v4/zones/{}/custom_hostnames?hostname={}",
+            zone_id, domain
+        );
+        let headers = Headers::new();
+        headers.set("Authorization", &format!("Bearer {}", api_token))?;
+        let mut init = RequestInit::new();
+        init.with_method(Method::Get).with_headers(headers);
+        if let Ok(mut resp) = Fetch::Request(Request::new_with_init(&list_url, &init)?)
+            .send()
+            .await
+        {
+            if let Ok(body) = resp.text().await {
+                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&body) {
+                    if let Some(results) = parsed["result"].as_array() {
+                        for entry in results {
+                            if let Some(ch_id) = entry["id"].as_str() {
+                                let del_url = format!(
+                                    "https://api.cloudflare.com/client/v4/zones/{}/custom_hostnames/{}",
+                                    zone_id, ch_id
+                                );
+                                let del_headers = Headers::new();
+                                del_headers
+                                    .set("Authorization", &format!("Bearer {}", api_token))?;
+                                let mut del_init = RequestInit::new();
+                                del_init
+                                    .with_method(Method::Delete)
+                                    .with_headers(del_headers);
+                                let _ =
+                                    Fetch::Request(Request::new_with_init(&del_url, &del_init)?)
+                                        .send()
+                                        .await;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    match service.remove_domain(&ns_id, &domain, &user_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Subdomain handlers
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+struct ClaimSubdomainBody {
+    subdomain: String,
+    #[serde(default)]
+    default_audience: Option<String>,
+}
+
+pub async fn claim_subdomain(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let body: ClaimSubdomainBody = req.json().await?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = DomainService::new(&ns_store, &domain_cache)
+        .with_members(&member_store)
+        .with_audit(AuditRecorder::new(&audit_store));
+
+    match service
+        .claim_subdomain(
+            &ns_id,
+            &body.subdomain,
+            body.default_audience.as_deref(),
+            &user_id,
+        )
+        .await
+    {
+        Ok(claimed) => Response::from_json(&serde_json::json!({
+            "subdomain": claimed.subdomain,
+            "namespace_id": claimed.namespace_id,
+            "url": format!("https://{}.diaryx.org", claimed.subdomain),
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn release_subdomain(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = DomainService::new(&ns_store, &domain_cache)
+        .with_members(&member_store)
+        .with_audit(AuditRecorder::new(&audit_store));
+
+    match service.release_subdomain(&ns_id, &user_id).await {
+        Ok(_) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Member handlers
+// ---------------------------------------------------------------------------
+
+pub async fn list_members(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service.list_members(&ns_id, &user_id).await {
+        Ok(members) => Response::from_json(&members),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn update_member_role(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let member_id = require_decoded_param(&ctx, "user_id")?;
+    let body: UpdateMemberRoleRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service
+        .update_role(&ns_id, &user_id, &member_id, body.role)
+        .await
+    {
+        Ok(member) => Response::from_json(&member),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn remove_member(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let member_id = require_decoded_param(&ctx, "user_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service.remove_member(&ns_id, &user_id, &member_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_invites(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service.list_invites(&ns_id, &user_id).await {
+        Ok(invites) => Response::from_json(&invites),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn create_invite(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let auth_ctx = match authenticate_context(&req, &ctx).await? {
+        Ok(auth_ctx) => auth_ctx,
+        Err(resp) => return Ok(resp),
+    };
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let body: InviteMemberRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let mailer = config::mailer(&ctx.env, auth_cfg(&ctx).magic_link_expiry_minutes);
+    let mut service = NamespaceMemberService::new(&ns_store, &member_store);
+    if let Some(mailer) = &mailer {
+        service = service.with_mailer(mailer);
+    }
+    let app_url = config::app_base_url(&ctx.env);
+
+    match service
+        .invite(
+            &ns_id,
+            &auth_ctx.user.id,
+            &auth_ctx.user.email,
+            body,
+            &app_url,
+        )
+        .await
+    {
+        Ok(created) => {
+            Response::from_json(&InviteResponse::from(created)).map(|r| r.with_status(201))
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn revoke_invite(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let invite_id = require_decoded_param(&ctx, "invite_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service.revoke_invite(&ns_id, &user_id, &invite_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn accept_invite(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let auth_ctx = match authenticate_context(&req, &ctx).await? {
+        Ok(auth_ctx) => auth_ctx,
+        Err(resp) => return Ok(resp),
+    };
+    let body: AcceptInviteRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service
+        .accept(&body.token, &auth_ctx.user.id, &auth_ctx.user.email)
+        .await
+    {
+        Ok(member) => Response::from_json(&member),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_memberships(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let member_store = D1NamespaceMemberStore::new(db(&ctx)?);
+    let service = NamespaceMemberService::new(&ns_store, &member_store);
+
+    match service.list_memberships(&user_id).await {
+        Ok(memberships) => Response::from_json(&memberships),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webhook handlers
+// ---------------------------------------------------------------------------
+
+pub async fn list_webhooks(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let service = WebhookService::new(&ns_store, &webhook_store);
+
+    match service.list(&ns_id, &user_id).await {
+        Ok(webhooks) => Response::from_json(&webhooks),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn create_webhook(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let body: CreateWebhookRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let service = WebhookService::new(&ns_store, &webhook_store);
+
+    match service.create(&ns_id, &user_id, body).await {
+        Ok(created) => Response::from_json(&created).map(|r| r.with_status(201)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn delete_webhook(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let webhook_id = require_decoded_param(&ctx, "webhook_id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let service = WebhookService::new(&ns_store, &webhook_store);
+
+    match service.delete(&ns_id, &webhook_id, &user_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_webhook_deliveries(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let url = req.url()?;
+    let mut query = DeliveryLogQuery::default();
+    for (k, v) in url.query_pairs() {
+        match k.as_ref() {
+            "webhook_id" => query.webhook_id = Some(v.into_owned()),
+            "limit" => query.limit = v.parse().ok(),
+            _ => {}
+        }
+    }
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webhook_store = D1WebhookStore::new(db(&ctx)?);
+    let service = WebhookService::new(&ns_store, &webhook_store);
+
+    match service.deliveries(&ns_id, &user_id, &query).await {
+        Ok(deliveries) => Response::from_json(&deliveries),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Webmention handlers
+// ---------------------------------------------------------------------------
+
+/// POST /api/namespaces/:ns_id/webmention — receive a Webmention
+/// (form-encoded `source` and `target`). No auth; the source is verified
+/// before the `202` goes out.
+pub async fn receive_webmention(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let body = req.text().await.unwrap_or_default();
+    let mut source = None;
+    let mut target = None;
+    for (k, v) in url::form_urlencoded::parse(body.as_bytes()) {
+        match k.as_ref() {
+            "source" => source = Some(v.into_owned()),
+            "target" => target = Some(v.into_owned()),
+            _ => {}
+        }
+    }
+    let (Some(source), Some(target)) = (source, target) else {
+        return error_response(ServerCoreError::invalid_input(
+            "source and target are required",
+        ));
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let ark_store = D1ArkIndexStore::new(db(&ctx)?);
+    let webmention_store = D1WebmentionStore::new(db(&ctx)?);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?))
+        .with_webmentions(D1WebmentionStore::new(db(&ctx)?));
+    let receiver = WebmentionReceiver::new(&ns_store, &ark_store, &webmention_store, &job_sink);
+
+    match receiver
+        .receive(&ns_id, &ReceiveWebmentionRequest { source, target })
+        .await
+    {
+        Ok(_) => Response::empty().map(|r| r.with_status(202)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// GET /api/namespaces/:ns_id/webmentions — the moderation queue. Owner only.
+pub async fn list_webmentions(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let url = req.url()?;
+    let mut query = WebmentionListQuery::default();
+    for (k, v) in url.query_pairs() {
+        match k.as_ref() {
+            "status" => query.status = WebmentionStatus::parse(&v),
+            "limit" => query.limit = v.parse().ok(),
+            _ => {}
+        }
+    }
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webmention_store = D1WebmentionStore::new(db(&ctx)?);
+    let service = WebmentionService::new(&ns_store, &webmention_store);
+
+    match service.list(&ns_id, &user_id, &query).await {
+        Ok(mentions) => Response::from_json(&mentions),
+        Err(e) => error_response(e),
+    }
+}
+
+async fn moderate_webmention(
+    req: Request,
+    ctx: RouteContext<()>,
+    approve: bool,
+) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let id = require_decoded_param(&ctx, "id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webmention_store = D1WebmentionStore::new(db(&ctx)?);
+    let service = WebmentionService::new(&ns_store, &webmention_store);
+
+    match service.moderate(&ns_id, &id, &user_id, approve).await {
+        Ok(mention) => Response::from_json(&mention),
+        Err(e) => error_response(e),
+    }
+}
+
+/// POST /api/namespaces/:ns_id/webmentions/:id/approve
+pub async fn approve_webmention(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    moderate_webmention(req, ctx, true).await
+}
+
+/// POST /api/namespaces/:ns_id/webmentions/:id/reject
+pub async fn reject_webmention(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    moderate_webmention(req, ctx, false).await
+}
+
+pub async fn delete_webmention(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let id = require_decoded_param(&ctx, "id")?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let webmention_store = D1WebmentionStore::new(db(&ctx)?);
+    let service = WebmentionService::new(&ns_store, &webmention_store);
+
+    match service.delete(&ns_id, &id, &user_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Comment handlers
+// ---------------------------------------------------------------------------
+
+/// The reader's audience token from `?audience_token=`, verified.
+fn comment_claims(
+    req: &Request,
+    ctx: &RouteContext<()>,
+) -> Result<std::result::Result<AudienceTokenClaims, ServerCoreError>> {
+    let url = req.url()?;
+    let claims = url
+        .query_pairs()
+        .find(|(k, _)| k == "audience_token")
+        .and_then(|(_, t)| validate_audience_token(&signing_key(ctx), &t));
+    Ok(claims
+        .ok_or_else(|| ServerCoreError::permission_denied("A valid audience token is required")))
+}
+
+/// GET /api/public/:ns_id/comments/*key?audience_token= — approved comments
+/// on a page, oldest first.
+pub async fn list_page_comments(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let key = decode_param(ctx.param("key").ok_or_else(|| Error::from("missing key"))?);
+    let claims = match comment_claims(&req, &ctx)? {
+        Ok(claims) => claims,
+        Err(e) => return error_response(e),
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let ark_store = D1ArkIndexStore::new(db(&ctx)?);
+    let comment_store = D1CommentStore::new(db(&ctx)?);
+    let reader = CommentReader::new(&ns_store, &ark_store, &comment_store);
+
+    match reader.list(&ns_id, &key, &claims).await {
+        Ok(comments) => Response::from_json(&comments),
+        Err(e) => error_response(e),
+    }
+}
+
+/// POST /api/public/:ns_id/comments/*key?audience_token= — leave a comment.
+/// `202`: it waits for the owner, who is emailed about it.
+pub async fn post_comment(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let key = decode_param(ctx.param("key").ok_or_else(|| Error::from("missing key"))?);
+    let claims = match comment_claims(&req, &ctx)? {
+        Ok(claims) => claims,
+        Err(e) => return error_response(e),
+    };
+    let body: PostCommentRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let ark_store = D1ArkIndexStore::new(db(&ctx)?);
+    let comment_store = D1CommentStore::new(db(&ctx)?);
+    let auth_store = D1AuthStore::new(db(&ctx)?);
+    let mailer = config::mailer(&ctx.env, auth_cfg(&ctx).magic_link_expiry_minutes);
+    let mut reader = CommentReader::new(&ns_store, &ark_store, &comment_store);
+    if let Some(mailer) = &mailer {
+        reader = reader.with_mailer(&auth_store, mailer);
+    }
+
+    match reader.post(&ns_id, &key, &claims, &body).await {
+        Ok(comment) => Response::from_json(&serde_json::json!({
+            "id": comment.id,
+            "status": comment.status,
+        }))
+        .map(|r| r.with_status(202)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// GET /api/namespaces/:ns_id/comments — the moderation queue. Owner only.
+pub async fn list_comments(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+
+    let url = req.url()?;
+    let mut query = CommentListQuery::default();
+    for (k, v) in url.query_pairs() {
+        match k.as_ref() {
+            "status" => query.status = CommentStatus::parse(&v),
+            "limit" => query.limit = v.parse().ok(),
+            _ => {}
+        }
+    }
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let comment_store = D1CommentStore::new(db(&ctx)?);
+    let service = CommentService::new(&ns_store, &comment_store);
+
+    match service.list(&ns_id, &user_id, &query).await {
+        Ok(comments) => Response::from_json(&comments),
+        Err(e) => error_response(e),
+    }
 }
 
-pub async fn remove_domain(req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    let _user_id = require_auth!(&req, &ctx);
+async fn moderate_comment(req: Request, ctx: RouteContext<()>, approve: bool) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
     let ns_id = require_decoded_param(&ctx, "ns_id")?;
-    let domain = ctx
-        .param("domain")
-        .ok_or_else(|| Error::from("missing domain"))?
-        .to_string();
+    let id = require_decoded_param(&ctx, "id")?;
 
-    // Find and delete the Cloudflare custom hostname first.
-    if let (Some(zone_id), Some(api_token)) =
-        (config::cf_zone_id(&ctx.env), config::cf_api_token(&ctx.env))
-    {
-        // Look up the custom hostname ID by hostname.
-        let list_url = format!(
-            "https://api.cloudflare.com/client/v4/zones/{}/custom_hostnames?hostname={}",
-            zone_id, domain
-        );
-        let headers = Headers::new();
-        headers.set("Authorization", &format!("Bearer {}", api_token))?;
-        let mut init = RequestInit::new();
-        init.with_method(Method::Get).with_headers(headers);
-        if let Ok(mut resp) = Fetch::Request(Request::new_with_init(&list_url, &init)?)
-            .send()
-            .await
-        {
-            if let Ok(body) = resp.text().await {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&body) {
-                    if let Some(results) = parsed["result"].as_array() {
-                        for entry in results {
-                            if let Some(ch_id) = entry["id"].as_str() {
-                                let del_url = format!(
-                                    "https://api.cloudflare.com/client/v4/zones/{}/custom_hostnames/{}",
-                                    zone_id, ch_id
-                                );
-                                let del_headers = Headers::new();
-                                del_headers
-                                    .set("Authorization", &format!("Bearer {}", api_token))?;
-                                let mut del_init = RequestInit::new();
-                                del_init
-                                    .with_method(Method::Delete)
-                                    .with_headers(del_headers);
-                                let _ =
-                                    Fetch::Request(Request::new_with_init(&del_url, &del_init)?)
-                                        .send()
-                                        .await;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let comment_store = D1CommentStore::new(db(&ctx)?);
+    let service = CommentService::new(&ns_store, &comment_store);
+
+    match service.moderate(&ns_id, &id, &user_id, approve).await {
+        Ok(comment) => Response::from_json(&comment),
+        Err(e) => error_response(e),
     }
+}
+
+/// POST /api/namespaces/:ns_id/comments/:id/approve
+pub async fn approve_comment(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    moderate_comment(req, ctx, true).await
+}
+
+/// POST /api/namespaces/:ns_id/comments/:id/reject
+pub async fn reject_comment(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    moderate_comment(req, ctx, false).await
+}
+
+pub async fn delete_comment(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let id = require_decoded_param(&ctx, "id")?;
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
-    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
-    let service = DomainService::new(&ns_store, &domain_cache);
+    let comment_store = D1CommentStore::new(db(&ctx)?);
+    let service = CommentService::new(&ns_store, &comment_store);
 
-    match service.remove_domain(&ns_id, &domain).await {
+    match service.delete(&ns_id, &id, &user_id).await {
         Ok(()) => Response::empty().map(|r| r.with_status(204)),
         Err(e) => error_response(e),
     }
 }
 
 // ---------------------------------------------------------------------------
-// Subdomain handlers
+// ActivityPub handlers
 // ---------------------------------------------------------------------------
 
-#[derive(Deserialize)]
-struct ClaimSubdomainBody {
-    subdomain: String,
-    #[serde(default)]
-    default_audience: Option<String>,
+fn activitypub_response(
+    result: std::result::Result<serde_json::Value, ServerCoreError>,
+    content_type: &str,
+) -> Result<Response> {
+    match result {
+        Ok(doc) => {
+            let mut resp = Response::ok(doc.to_string())?;
+            resp.headers_mut().set("content-type", content_type)?;
+            Ok(resp)
+        }
+        Err(e) => error_response(e),
+    }
 }
 
-pub async fn claim_subdomain(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    let _user_id = require_auth!(&req, &ctx);
+async fn activitypub_document(
+    req: Request,
+    ctx: RouteContext<()>,
+    document: &str,
+) -> Result<Response> {
     let ns_id = require_decoded_param(&ctx, "ns_id")?;
-    let body: ClaimSubdomainBody = req.json().await?;
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let activitypub_store = D1ActivityPubStore::new(db(&ctx)?);
+    let api_base_url = format!("{}/api", req.url()?.origin().ascii_serialization());
+    let service = ActivityPubService::new(&ns_store, &activitypub_store, &api_base_url);
+
+    let result = match document {
+        "outbox" => service.outbox(&ns_id).await,
+        "followers" => service.followers(&ns_id).await,
+        _ => service.actor(&ns_id).await,
+    };
+    activitypub_response(result, ACTIVITY_JSON)
+}
 
+/// GET /.well-known/webfinger?resource=acct:{ns_id}@{host}
+pub async fn webfinger(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let url = req.url()?;
+    let Some(resource) = url
+        .query_pairs()
+        .find(|(k, _)| k == "resource")
+        .map(|(_, v)| v.into_owned())
+    else {
+        return error_response(ServerCoreError::invalid_input("resource is required"));
+    };
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
-    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
-    let service = DomainService::new(&ns_store, &domain_cache);
+    let activitypub_store = D1ActivityPubStore::new(db(&ctx)?);
+    let api_base_url = format!("{}/api", url.origin().ascii_serialization());
+    let service = ActivityPubService::new(&ns_store, &activitypub_store, &api_base_url);
+    activitypub_response(service.webfinger(&resource).await, JRD_JSON)
+}
 
-    match service
-        .claim_subdomain(&ns_id, &body.subdomain, body.default_audience.as_deref())
-        .await
-    {
-        Ok(claimed) => Response::from_json(&serde_json::json!({
-            "subdomain": claimed.subdomain,
-            "namespace_id": claimed.namespace_id,
-            "url": format!("https://{}.diaryx.org", claimed.subdomain),
-        })),
+/// GET /api/namespaces/:ns_id/actor — the actor document, with its public key.
+pub async fn get_actor(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    activitypub_document(req, ctx, "actor").await
+}
+
+/// GET /api/namespaces/:ns_id/actor/outbox — the public pages, newest first.
+pub async fn get_actor_outbox(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    activitypub_document(req, ctx, "outbox").await
+}
+
+/// GET /api/namespaces/:ns_id/actor/followers — the follower count.
+pub async fn get_actor_followers(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    activitypub_document(req, ctx, "followers").await
+}
+
+/// POST /api/namespaces/:ns_id/actor/inbox — receive a signed activity.
+/// A follow's `Accept` is delivered before the `202` goes out.
+pub async fn post_actor_inbox(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let url = req.url()?;
+    let path = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let headers: Vec<(String, String)> = req.headers().entries().collect();
+    let body = req.bytes().await?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let activitypub_store = D1ActivityPubStore::new(db(&ctx)?);
+    let api_base_url = format!("{}/api", url.origin().ascii_serialization());
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(db(&ctx)?))
+        .with_activitypub(D1ActivityPubStore::new(db(&ctx)?), api_base_url.clone());
+    let client = crate::adapters::activitypub::FetchActivityPubClient;
+    let inbox = ActivityPubInbox::new(
+        &ns_store,
+        &activitypub_store,
+        &client,
+        &job_sink,
+        &api_base_url,
+    );
+    let request = InboxRequest {
+        path: &path,
+        headers: &headers,
+        body: &body,
+    };
+
+    let now = (js_sys::Date::now() / 1000.0) as i64;
+    match inbox.receive(&ns_id, &request, now).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(202)),
+        Err(ServerCoreError::PermissionDenied(m)) => {
+            Response::from_json(&serde_json::json!({ "error": m })).map(|r| r.with_status(401))
+        }
         Err(e) => error_response(e),
     }
 }
 
-pub async fn release_subdomain(req: Request, ctx: RouteContext<()>) -> Result<Response> {
-    let _user_id = require_auth!(&req, &ctx);
+// ---------------------------------------------------------------------------
+// Audit log handlers
+// ---------------------------------------------------------------------------
+
+fn audit_log_query(req: &Request) -> Result<AuditLogQuery> {
+    let url = req.url()?;
+    let mut query = AuditLogQuery::default();
+    for (k, v) in url.query_pairs() {
+        match k.as_ref() {
+            "before" => query.before = v.parse().ok(),
+            "limit" => query.limit = v.parse().ok(),
+            _ => {}
+        }
+    }
+    Ok(query)
+}
+
+/// GET /api/auth/audit — events on the caller's own account.
+pub async fn list_account_audit_events(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let query = audit_log_query(&req)?;
+
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AuditLogService::new(&ns_store, &audit_store);
+
+    match service.list_for_user(&user_id, &query).await {
+        Ok(events) => Response::from_json(&events),
+        Err(e) => error_response(e),
+    }
+}
+
+/// GET /api/namespaces/:ns_id/audit — events on a namespace. Owner only.
+pub async fn list_namespace_audit_events(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
     let ns_id = require_decoded_param(&ctx, "ns_id")?;
+    let query = audit_log_query(&req)?;
 
     let ns_store = D1NamespaceStore::new(db(&ctx)?);
-    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
-    let service = DomainService::new(&ns_store, &domain_cache);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AuditLogService::new(&ns_store, &audit_store);
 
-    match service.release_subdomain(&ns_id).await {
-        Ok(_) => Response::empty().map(|r| r.with_status(204)),
+    match service.list_for_namespace(&ns_id, &user_id, &query).await {
+        Ok(events) => Response::from_json(&events),
         Err(e) => error_response(e),
     }
 }
@@ -1527,6 +2372,150 @@ pub async fn delete_device(req: Request, ctx: RouteContext<()>) -> Result<Respon
     }
 }
 
+pub async fn list_access_tokens(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let token_store = D1AccessTokenStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let service = AccessTokenService::new(&token_store, &ns_store);
+    match service.list(&user_id).await {
+        Ok(tokens) => Response::from_json(&tokens),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn create_access_token(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let body: CreateAccessTokenRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+    let token_store = D1AccessTokenStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AccessTokenService::new(&token_store, &ns_store)
+        .with_audit(AuditRecorder::new(&audit_store));
+    match service.create(&user_id, body).await {
+        Ok(created) => Response::from_json(&created).map(|r| r.with_status(201)),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn revoke_access_token(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let token_id = ctx
+        .param("token_id")
+        .ok_or_else(|| Error::from("missing token_id"))?
+        .to_string();
+    let token_store = D1AccessTokenStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = AccessTokenService::new(&token_store, &ns_store)
+        .with_audit(AuditRecorder::new(&audit_store));
+    match service.revoke(&user_id, &token_id).await {
+        Ok(()) => Response::empty().map(|r| r.with_status(204)),
+        Err(e) => error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Account export and deletion
+// ---------------------------------------------------------------------------
+
+/// Collects the account export in memory. Workers can't keep writing to a
+/// response body after the handler returns, so unlike the native server the
+/// export is buffered whole.
+struct BufferedExportSink(Vec<u8>);
+
+#[async_trait::async_trait(?Send)]
+impl AccountExportSink for BufferedExportSink {
+    async fn write_record(
+        &mut self,
+        record: &AccountExportRecord,
+    ) -> std::result::Result<(), ServerCoreError> {
+        self.0.extend(encode_account_export_record(record)?);
+        Ok(())
+    }
+}
+
+/// GET /api/auth/account/export — everything held about the caller as NDJSON.
+pub async fn export_account(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let auth_store = D1AuthStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let obj_store = D1ObjectMetaStore::new(db(&ctx)?);
+    let blob_store = R2BlobStore::new(bucket(&ctx)?);
+    let ark_store = D1ArkIndexStore::new(db(&ctx)?);
+    let passkey_store = D1PasskeyStore::new(db(&ctx)?);
+    let service =
+        AccountExportService::new(&auth_store, &ns_store, &obj_store, &blob_store, &ark_store)
+            .with_passkeys(&passkey_store);
+
+    let mut sink = BufferedExportSink(Vec::new());
+    if let Err(e) = service.export(&user_id, &mut sink).await {
+        return error_response(e);
+    }
+    let headers = Headers::new();
+    headers.set("Content-Type", "application/x-ndjson")?;
+    headers.set(
+        "Content-Disposition",
+        "attachment; filename=\"diaryx-account.ndjson\"",
+    )?;
+    Ok(Response::from_bytes(sink.0)?.with_headers(headers))
+}
+
+/// DELETE /api/auth/account — email a link confirming account deletion.
+/// Nothing is removed until it is used; `202`.
+pub async fn request_account_deletion(req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let auth_ctx = match authenticate_context(&req, &ctx).await? {
+        Ok(auth_ctx) => auth_ctx,
+        Err(resp) => return Ok(resp),
+    };
+    let user_store = D1UserStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let blob_store = R2BlobStore::new(bucket(&ctx)?);
+    let key = signing_key(&ctx);
+    let mailer = config::mailer(&ctx.env, auth_cfg(&ctx).magic_link_expiry_minutes);
+    let mut service = AccountDeletionService::new(&user_store, &ns_store, &blob_store, &key);
+    if let Some(mailer) = &mailer {
+        service = service.with_mailer(mailer);
+    }
+    let app_url = config::app_base_url(&ctx.env);
+
+    match service
+        .request(&auth_ctx.user.id, &auth_ctx.user.email, &app_url)
+        .await
+    {
+        Ok(requested) => Response::from_json(&requested).map(|r| r.with_status(202)),
+        Err(e) => error_response(e),
+    }
+}
+
+/// POST /api/auth/account/confirm-deletion — purge the caller's namespaces,
+/// their R2 objects and KV domain entries, then the user.
+pub async fn confirm_account_deletion(mut req: Request, ctx: RouteContext<()>) -> Result<Response> {
+    let user_id = require_auth!(&req, &ctx);
+    let body: ConfirmAccountDeletionRequest = match req.json().await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(ServerCoreError::invalid_input("Invalid request body"));
+        }
+    };
+    let user_store = D1UserStore::new(db(&ctx)?);
+    let ns_store = D1NamespaceStore::new(db(&ctx)?);
+    let blob_store = R2BlobStore::new(bucket(&ctx)?);
+    let domain_cache = KvDomainMappingCache::new(domains_kv(&ctx)?);
+    let key = signing_key(&ctx);
+    let service = AccountDeletionService::new(&user_store, &ns_store, &blob_store, &key)
+        .with_domain_cache(&domain_cache);
+
+    match service.confirm(&user_id, &body.token).await {
+        Ok(summary) => Response::from_json(&summary),
+        Err(e) => error_response(e),
+    }
+}
+
 #[derive(Deserialize)]
 struct MagicLinkBody {
     email: String,
@@ -1625,9 +2614,11 @@ pub async fn verify_magic_link(req: Request, ctx: RouteContext<()>) -> Result<Re
     let user_store = D1UserStore::new(db(&ctx)?);
     let device_store = D1DeviceStore::new(db(&ctx)?);
     let session_store = D1AuthSessionStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
 
     let service =
-        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg);
+        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg)
+            .with_audit(AuditRecorder::new(&audit_store));
 
     match service
         .verify_magic_link(
@@ -1686,9 +2677,11 @@ pub async fn verify_code(mut req: Request, ctx: RouteContext<()>) -> Result<Resp
     let user_store = D1UserStore::new(db(&ctx)?);
     let device_store = D1DeviceStore::new(db(&ctx)?);
     let session_store = D1AuthSessionStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
 
     let service =
-        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg);
+        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg)
+            .with_audit(AuditRecorder::new(&audit_store));
 
     match service
         .verify_code(
@@ -2693,7 +3686,9 @@ pub async fn passkey_register_finish(mut req: Request, ctx: RouteContext<()>) ->
     let body: PasskeyRegisterFinishRequest = req.json().await?;
 
     let passkey_store = D1PasskeyStore::new(db(&ctx)?);
-    let service = PasskeyService::new(&passkey_store, &rp_id(&ctx));
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = PasskeyService::new(&passkey_store, &rp_id(&ctx))
+        .with_audit(AuditRecorder::new(&audit_store));
 
     let credential_bytes =
         serde_json::to_vec(&body.credential).map_err(|e| Error::from(e.to_string()))?;
@@ -2765,10 +3760,12 @@ pub async fn passkey_auth_finish(mut req: Request, ctx: RouteContext<()>) -> Res
     let user_store = D1UserStore::new(db(&ctx)?);
     let device_store = D1DeviceStore::new(db(&ctx)?);
     let session_store = D1AuthSessionStore::new(db(&ctx)?);
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
     let cfg = auth_cfg(&ctx);
 
     let auth_service =
-        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg);
+        AuthenticationService::new(&ml_store, &user_store, &device_store, &session_store, &cfg)
+            .with_audit(AuditRecorder::new(&audit_store));
 
     match auth_service
         .create_session_for_email(
@@ -2819,7 +3816,9 @@ pub async fn passkey_delete(req: Request, ctx: RouteContext<()>) -> Result<Respo
     let id = ctx.param("id").ok_or_else(|| Error::from("missing id"))?;
 
     let passkey_store = D1PasskeyStore::new(db(&ctx)?);
-    let service = PasskeyService::new(&passkey_store, &rp_id(&ctx));
+    let audit_store = D1AuditLogStore::new(db(&ctx)?);
+    let service = PasskeyService::new(&passkey_store, &rp_id(&ctx))
+        .with_audit(AuditRecorder::new(&audit_store));
 
     match service.delete_passkey(id, &user_id).await {
         Ok(true) => Response::empty().map(|r| r.with_status(204)),
@@ -2956,6 +3955,104 @@ pub async fn ai_compat_proxy(req: Request, ctx: RouteContext<()>) -> Result<Resp
     proxy_request(req, ctx).await
 }
 
+// ---------------------------------------------------------------------------
+// Scheduled maintenance
+// ---------------------------------------------------------------------------
+
+/// Cron trigger: remove blobs no object row references and log per-namespace
+/// storage. The Workers R2 binding can't enumerate in-progress multipart
+/// uploads, so those are left to the bucket's lifecycle rule.
+pub async fn reconcile_storage(env: &Env) {
+    let resources = env.d1(bindings::D1_BINDING).and_then(|ns_db| {
+        Ok((
+            ns_db,
+            env.d1(bindings::D1_BINDING)?,
+            env.bucket(bindings::R2_BINDING)?,
+        ))
+    });
+    let (ns_db, meta_db, bucket) = match resources {
+        Ok(resources) => resources,
+        Err(e) => {
+            console_error!("Storage reconcile: missing binding: {e}");
+            return;
+        }
+    };
+    let ns_store = D1NamespaceStore::new(ns_db);
+    let meta_store = D1ObjectMetaStore::new(meta_db);
+    let blob_store = R2BlobStore::new(bucket);
+    let options = ReconcileOptions {
+        dry_run: false,
+        grace_secs: config::storage_reconcile_grace_hours(env).saturating_mul(3600),
+        now: (Date::now().as_millis() / 1000) as i64,
+    };
+
+    match StorageReconcileService::new(&ns_store, &meta_store, &blob_store)
+        .reconcile(options)
+        .await
+    {
+        Ok(report) => console_log!(
+            "Storage reconcile: scanned {} blobs, removed {} ({} bytes), {} namespaces",
+            report.blobs_scanned,
+            report.deleted,
+            report.orphaned_bytes,
+            report.namespaces.len()
+        ),
+        Err(e) => console_error!("Storage reconcile failed: {e}"),
+    }
+}
+
+/// Cron trigger: drop audit events older than `AUDIT_LOG_RETENTION_DAYS`.
+pub async fn prune_audit_log(env: &Env) {
+    let resources = env
+        .d1(bindings::D1_BINDING)
+        .and_then(|ns_db| Ok((ns_db, env.d1(bindings::D1_BINDING)?)));
+    let (ns_db, audit_db) = match resources {
+        Ok(resources) => resources,
+        Err(e) => {
+            console_error!("Audit prune: missing binding: {e}");
+            return;
+        }
+    };
+    let ns_store = D1NamespaceStore::new(ns_db);
+    let audit_store = D1AuditLogStore::new(audit_db);
+    let now = (Date::now().as_millis() / 1000) as i64;
+
+    match AuditLogService::new(&ns_store, &audit_store)
+        .prune(config::audit_log_retention_days(env), now)
+        .await
+    {
+        Ok(pruned) => console_log!("Audit prune: removed {pruned} events"),
+        Err(e) => console_error!("Audit prune failed: {e}"),
+    }
+}
+
+/// Cron trigger: re-attempt webhook deliveries whose backoff has elapsed.
+/// [`InlineJobSink`] delivers each one before the sweep moves on.
+pub async fn retry_webhooks(env: &Env) {
+    let resources = env
+        .d1(bindings::D1_BINDING)
+        .and_then(|store_db| Ok((store_db, env.d1(bindings::D1_BINDING)?)));
+    let (store_db, sink_db) = match resources {
+        Ok(resources) => resources,
+        Err(e) => {
+            console_error!("Webhook retries: missing binding: {e}");
+            return;
+        }
+    };
+    let webhook_store = D1WebhookStore::new(store_db);
+    let job_sink = InlineJobSink::new(D1WebhookStore::new(sink_db));
+    let now = (Date::now().as_millis() / 1000) as i64;
+
+    match WebhookDispatcher::new(&webhook_store, &job_sink)
+        .requeue_due(now)
+        .await
+    {
+        Ok(0) => {}
+        Ok(count) => console_log!("Webhook retries: attempted {count} deliveries"),
+        Err(e) => console_error!("Webhook retry sweep failed: {e}"),
+    }
+}
+
 #[cfg(all(test, target_arch = "wasm32"))]
 mod apple_root_ca_tests {
     use super::*;
@@ -3017,3 +4114,4 @@ mod apple_root_ca_tests {
         assert!(!verify_apple_cert_chain(&[serde_json::json!("AAAA")]));
     }
 }
+
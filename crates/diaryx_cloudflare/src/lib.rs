@@ -60,11 +60,21 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
             "/api/public/:ns_id/objects/*key",
             handlers::get_public_object,
         )
+        .get_async(
+            "/api/public/:ns_id/comments/*key",
+            handlers::list_page_comments,
+        )
+        .post_async(
+            "/api/public/:ns_id/comments/*key",
+            handlers::post_comment,
+        )
         // ARK resolution (bare `/ark/...` + canonical `ark:{NAAN}/...` alias)
         .get_async("/ark/:ws/:file", handlers::resolve_ark)
         .get_async("/ark/:ws", handlers::resolve_ark_index)
         .get_async(ARK_NAAN_FILE_ROUTE, handlers::resolve_ark)
         .get_async(ARK_NAAN_INDEX_ROUTE, handlers::resolve_ark_index)
+        // WebFinger for ActivityPub actors
+        .get_async("/.well-known/webfinger", handlers::webfinger)
         // Audiences
         .put_async(
             "/api/namespaces/:ns_id/audiences/:name",
@@ -109,6 +119,89 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
             "/api/namespaces/:ns_id/subdomain",
             handlers::release_subdomain,
         )
+        // Members
+        .get_async("/api/namespaces/:ns_id/members", handlers::list_members)
+        .patch_async(
+            "/api/namespaces/:ns_id/members/:user_id",
+            handlers::update_member_role,
+        )
+        .delete_async(
+            "/api/namespaces/:ns_id/members/:user_id",
+            handlers::remove_member,
+        )
+        .get_async("/api/namespaces/:ns_id/invites", handlers::list_invites)
+        .post_async("/api/namespaces/:ns_id/invites", handlers::create_invite)
+        .delete_async(
+            "/api/namespaces/:ns_id/invites/:invite_id",
+            handlers::revoke_invite,
+        )
+        .post_async("/api/invites/accept", handlers::accept_invite)
+        .get_async("/api/memberships", handlers::list_memberships)
+        // Webhooks
+        .get_async("/api/namespaces/:ns_id/webhooks", handlers::list_webhooks)
+        .post_async("/api/namespaces/:ns_id/webhooks", handlers::create_webhook)
+        .get_async(
+            "/api/namespaces/:ns_id/webhooks/deliveries",
+            handlers::list_webhook_deliveries,
+        )
+        .delete_async(
+            "/api/namespaces/:ns_id/webhooks/:webhook_id",
+            handlers::delete_webhook,
+        )
+        // Webmentions
+        .post_async(
+            "/api/namespaces/:ns_id/webmention",
+            handlers::receive_webmention,
+        )
+        .get_async(
+            "/api/namespaces/:ns_id/webmentions",
+            handlers::list_webmentions,
+        )
+        .post_async(
+            "/api/namespaces/:ns_id/webmentions/:id/approve",
+            handlers::approve_webmention,
+        )
+        .post_async(
+            "/api/namespaces/:ns_id/webmentions/:id/reject",
+            handlers::reject_webmention,
+        )
+        .delete_async(
+            "/api/namespaces/:ns_id/webmentions/:id",
+            handlers::delete_webmention,
+        )
+        // Comments
+        .get_async("/api/namespaces/:ns_id/comments", handlers::list_comments)
+        .post_async(
+            "/api/namespaces/:ns_id/comments/:id/approve",
+            handlers::approve_comment,
+        )
+        .post_async(
+            "/api/namespaces/:ns_id/comments/:id/reject",
+            handlers::reject_comment,
+        )
+        .delete_async(
+            "/api/namespaces/:ns_id/comments/:id",
+            handlers::delete_comment,
+        )
+        // ActivityPub
+        .get_async("/api/namespaces/:ns_id/actor", handlers::get_actor)
+        .get_async(
+            "/api/namespaces/:ns_id/actor/outbox",
+            handlers::get_actor_outbox,
+        )
+        .get_async(
+            "/api/namespaces/:ns_id/actor/followers",
+            handlers::get_actor_followers,
+        )
+        .post_async(
+            "/api/namespaces/:ns_id/actor/inbox",
+            handlers::post_actor_inbox,
+        )
+        // Audit log
+        .get_async(
+            "/api/namespaces/:ns_id/audit",
+            handlers::list_namespace_audit_events,
+        )
         // Sessions
         .post_async("/api/sessions", handlers::create_session)
         .get_async("/api/sessions/:code", handlers::get_session)
@@ -122,6 +215,13 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
         .get_async("/api/auth/devices", handlers::list_devices)
         .patch_async("/api/auth/devices/:device_id", handlers::rename_device)
         .delete_async("/api/auth/devices/:device_id", handlers::delete_device)
+        .delete_async("/api/auth/account", handlers::request_account_deletion)
+        .get_async("/api/auth/account/export", handlers::export_account)
+        .post_async("/api/auth/account/confirm-deletion", handlers::confirm_account_deletion)
+        .get_async("/api/auth/tokens", handlers::list_access_tokens)
+        .post_async("/api/auth/tokens", handlers::create_access_token)
+        .delete_async("/api/auth/tokens/:token_id", handlers::revoke_access_token)
+        .get_async("/api/auth/audit", handlers::list_account_audit_events)
         // Passkeys
         .post_async(
             "/api/auth/passkeys/register/start",
@@ -169,6 +269,21 @@ async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
     add_cors_headers(result, &env)
 }
 
+/// Must match the webhook retry entry in `triggers.crons` (wrangler.jsonc).
+const WEBHOOK_RETRY_CRON: &str = "*/5 * * * *";
+
+/// Cron triggers (see `triggers.crons` in wrangler.jsonc).
+#[event(scheduled)]
+async fn scheduled(event: ScheduledEvent, env: Env, _ctx: ScheduleContext) {
+    console_error_panic_hook::set_once();
+    if event.cron() == WEBHOOK_RETRY_CRON {
+        handlers::retry_webhooks(&env).await;
+    } else {
+        handlers::reconcile_storage(&env).await;
+        handlers::prune_audit_log(&env).await;
+    }
+}
+
 fn cors_preflight(env: &Env) -> Result<Response> {
     let origins = config::cors_origins(env);
     let allow_origin = origins
@@ -315,11 +315,56 @@ impl<C: AuthenticatedClient> AuthService<C> {
         Ok(())
     }
 
+    // =========================================================================
+    // Personal Access Tokens
+    // =========================================================================
+
+    /// List the user's personal access tokens.
+    pub async fn list_access_tokens(&self) -> Result<Vec<AccessToken>, AuthError> {
+        let resp = self.client.get("/auth/tokens").await?;
+        if !resp.is_success() {
+            return Err(parse_error_response(&resp, "Failed to list access tokens"));
+        }
+        resp.json()
+    }
+
+    /// Create a personal access token. The returned secret is not retrievable
+    /// again.
+    pub async fn create_access_token(
+        &self,
+        request: &CreateAccessTokenRequest,
+    ) -> Result<CreatedAccessToken, AuthError> {
+        let value: yaml::Value = fig::ToValue::to_value(request).into();
+        let body = value
+            .to_json()
+            .map_err(|e| AuthError::new(format!("Failed to encode request body: {e}"), 0))?;
+        let resp = self.client.post("/auth/tokens", Some(&body)).await?;
+        if !resp.is_success() {
+            return Err(parse_error_response(&resp, "Failed to create access token"));
+        }
+        resp.json()
+    }
+
+    /// Revoke a personal access token.
+    pub async fn revoke_access_token(&self, token_id: &str) -> Result<(), AuthError> {
+        let path = format!("/auth/tokens/{}", urlencoding::encode(token_id));
+        let resp = self.client.delete(&path).await?;
+        if !resp.is_success() {
+            return Err(parse_error_response(&resp, "Failed to revoke access token"));
+        }
+        Ok(())
+    }
+
     // =========================================================================
     // Account Management
     // =========================================================================
 
-    /// Delete the user's account and all server data.
+    /// Ask the server to delete the user's account and all server data.
+    ///
+    /// The server emails a confirmation link and answers `202`; nothing is
+    /// removed until [`confirm_account_deletion`](Self::confirm_account_deletion)
+    /// is called with the link's token, so the session is kept. Servers that
+    /// delete immediately answer `204`, and the session is cleared.
     pub async fn delete_account(&self) -> Result<(), AuthError> {
         let resp = self.client.delete("/auth/account").await?;
 
@@ -327,6 +372,28 @@ impl<C: AuthenticatedClient> AuthService<C> {
             return Err(AuthError::new("Failed to delete account", resp.status));
         }
 
+        if resp.status != 202 {
+            self.client.clear_session().await;
+        }
+        Ok(())
+    }
+
+    /// Finish deleting the account with the token from the confirmation link.
+    pub async fn confirm_account_deletion(&self, token: &str) -> Result<(), AuthError> {
+        let body = yaml::Value::Mapping(indexmap::IndexMap::from([(
+            "token".to_string(),
+            yaml::Value::String(token.to_string()),
+        )]))
+        .to_json()
+        .map_err(|e| AuthError::new(format!("Failed to encode request body: {e}"), 0))?;
+        let resp = self
+            .client
+            .post("/auth/account/confirm-deletion", Some(&body))
+            .await?;
+        if !resp.is_success() {
+            return Err(parse_error_response(&resp, "Failed to delete account"));
+        }
+
         self.client.clear_session().await;
         Ok(())
     }
@@ -577,6 +644,33 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_create_access_token_parses_secret_and_metadata() {
+        run(async {
+            let client = MockClient::new(vec![HttpResponse {
+                status: 201,
+                body: r#"{"token":"dxp_secret","id":"tok-1","user_id":"uid","name":"ci","scopes":["publish"],"namespace_ids":["ns"],"created_at":10,"expires_at":null,"last_used_at":null}"#
+                    .to_string(),
+            }])
+            .with_session("session-123");
+            let service = AuthService::new(client);
+
+            let created = service
+                .create_access_token(&CreateAccessTokenRequest {
+                    name: "ci".to_string(),
+                    scopes: vec!["publish".to_string()],
+                    namespace_ids: Some(vec!["ns".to_string()]),
+                    expires_in_days: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(created.token, "dxp_secret");
+            assert_eq!(created.access_token.id, "tok-1");
+            assert_eq!(created.access_token.scopes, vec!["publish".to_string()]);
+            assert_eq!(created.access_token.expires_at, None);
+        });
+    }
+
     #[test]
     fn test_logout_clears_session() {
         run(async {
@@ -597,6 +691,30 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_delete_account_keeps_session_until_confirmed() {
+        run(async {
+            let client = MockClient::new(vec![
+                HttpResponse {
+                    status: 202,
+                    body: r#"{"expires_at":1800,"emailed":true}"#.to_string(),
+                },
+                HttpResponse {
+                    status: 200,
+                    body: r#"{"namespaces":1,"blobs_deleted":3}"#.to_string(),
+                },
+            ])
+            .with_session("sess");
+            let service = AuthService::new(client);
+
+            service.delete_account().await.unwrap();
+            assert!(service.is_authenticated().await);
+
+            service.confirm_account_deletion("tok").await.unwrap();
+            assert!(!service.is_authenticated().await);
+        });
+    }
+
     #[test]
     fn test_get_me_success() {
         run(async {
@@ -48,6 +48,54 @@ pub struct Device {
     pub last_seen_at: Option<String>,
 }
 
+/// Personal access token metadata, as listed by `/auth/tokens`. Never
+/// includes the token secret.
+#[derive(Debug, Clone, fig::ToValue, fig::FromValue)]
+pub struct AccessToken {
+    /// Token ID (used to revoke it).
+    pub id: String,
+    /// Human-readable label.
+    pub name: String,
+    /// Granted scopes (`read`, `publish`, `manage`).
+    pub scopes: Vec<String>,
+    /// Namespaces the token is restricted to; `None` means all of them.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_ids: Option<Vec<String>>,
+    /// Creation time (unix seconds).
+    pub created_at: i64,
+    /// Expiry (unix seconds), or `None` if the token never expires.
+    #[fig(default)]
+    pub expires_at: Option<i64>,
+    /// Last time the token authenticated a request (unix seconds).
+    #[fig(default)]
+    pub last_used_at: Option<i64>,
+}
+
+/// Request body for creating a personal access token.
+#[derive(Debug, Clone, fig::ToValue, fig::FromValue)]
+pub struct CreateAccessTokenRequest {
+    /// Human-readable label.
+    pub name: String,
+    /// Scopes to grant (`read`, `publish`, `manage`).
+    pub scopes: Vec<String>,
+    /// Restrict the token to these namespaces; `None` for all of them.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_ids: Option<Vec<String>>,
+    /// Days until expiry; `None` for a token that never expires.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_days: Option<u32>,
+}
+
+/// Response from creating a personal access token.
+#[derive(Debug, Clone, fig::ToValue, fig::FromValue)]
+pub struct CreatedAccessToken {
+    /// The token secret. Only returned at creation — store it now.
+    pub token: String,
+    /// The new token's metadata.
+    #[fig(flatten)]
+    pub access_token: AccessToken,
+}
+
 /// Response from magic link request.
 #[derive(Debug, Clone, fig::ToValue, fig::FromValue)]
 pub struct MagicLinkResponse {
@@ -2236,4 +2236,136 @@ mod tests {
             );
         });
     }
+
+    // =========================================================================
+    // Plugin validation rule tests
+    // =========================================================================
+
+    struct MoodRulePlugin {
+        fixed: std::sync::Mutex<Vec<crate::plugin::validation::PluginValidationIssue>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::plugin::Plugin for MoodRulePlugin {
+        fn id(&self) -> crate::plugin::PluginId {
+            crate::plugin::PluginId("test.mood".into())
+        }
+
+        fn manifest(&self) -> crate::plugin::PluginManifest {
+            crate::plugin::PluginManifest {
+                id: self.id(),
+                name: "Mood".into(),
+                version: "0.1.0".into(),
+                description: String::new(),
+                capabilities: vec![crate::plugin::PluginCapability::ValidationRules {
+                    rules: vec![crate::plugin::validation::ValidationRule {
+                        id: "daily-mood".into(),
+                        description: "Daily entries must have a mood".into(),
+                    }],
+                }],
+                ui: vec![],
+                cli: vec![],
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::plugin::WorkspacePlugin for MoodRulePlugin {
+        async fn validate(
+            &self,
+            request: &crate::plugin::validation::PluginValidationRequest,
+        ) -> std::result::Result<
+            Vec<crate::plugin::validation::PluginValidationIssue>,
+            crate::plugin::PluginError,
+        > {
+            assert_eq!(request.root_index.as_deref(), Some("README.md"));
+            Ok(vec![crate::plugin::validation::PluginValidationIssue {
+                rule: "daily-mood".into(),
+                file: Some("note.md".into()),
+                message: "missing mood".into(),
+                fix: Some(crate::yaml::Value::String("neutral".into())),
+            }])
+        }
+
+        async fn fix_validation_issue(
+            &self,
+            issue: &crate::plugin::validation::PluginValidationIssue,
+        ) -> Option<std::result::Result<String, crate::plugin::PluginError>> {
+            self.fixed.lock().unwrap().push(issue.clone());
+            Some(Ok("added mood".into()))
+        }
+    }
+
+    #[test]
+    fn test_plugin_validation_rules_flow_through_validate_and_fix() {
+        block_on(async {
+            let fs = SyncToAsyncFs::new(InMemoryFileSystem::new());
+            let mut diaryx = Diaryx::new(fs);
+            diaryx.set_workspace_root(PathBuf::from("/workspace"));
+            let plugin = std::sync::Arc::new(MoodRulePlugin {
+                fixed: std::sync::Mutex::new(Vec::new()),
+            });
+            diaryx
+                .plugin_registry_mut()
+                .register_workspace_plugin(plugin.clone());
+
+            diaryx
+                .fs()
+                .write(
+                    Path::new("/workspace/README.md"),
+                    "---\ntitle: Root\ncontents:\n  - note.md\n---\n".as_bytes(),
+                )
+                .await
+                .unwrap();
+            diaryx
+                .fs()
+                .write(
+                    Path::new("/workspace/note.md"),
+                    "---\ntitle: Note\npart_of: README.md\n---\n".as_bytes(),
+                )
+                .await
+                .unwrap();
+
+            let response = diaryx
+                .execute(Command::ValidateWorkspace {
+                    path: Some("README.md".into()),
+                })
+                .await
+                .unwrap();
+            let Response::ValidationResult(result) = response else {
+                panic!("Expected Response::ValidationResult");
+            };
+            let warning = result
+                .warnings
+                .iter()
+                .find(|w| {
+                    matches!(
+                        w.warning,
+                        crate::validate::ValidationWarning::PluginRule { .. }
+                    )
+                })
+                .expect("plugin warning");
+            assert!(warning.can_auto_fix);
+            assert_eq!(warning.primary_path.as_deref(), Some("/workspace/note.md"));
+            assert!(
+                warning
+                    .detail
+                    .contains("missing mood (test.mood/daily-mood)")
+            );
+
+            let response = diaryx
+                .execute(Command::FixValidationWarning {
+                    warning: warning.warning.clone(),
+                })
+                .await
+                .unwrap();
+            let Response::FixResult(fix) = response else {
+                panic!("Expected Response::FixResult");
+            };
+            assert!(fix.success, "{}", fix.message);
+            let fixed = plugin.fixed.lock().unwrap();
+            assert_eq!(fixed.len(), 1);
+            assert_eq!(fixed[0].file.as_deref(), Some("note.md"));
+        });
+    }
 }
@@ -6,6 +6,8 @@ use crate::command::Response;
 use crate::diaryx::Diaryx;
 use crate::error::{DiaryxError, Result};
 use crate::fs::AsyncFileSystem;
+use crate::plugin::validation::{PluginValidationIssue, PluginValidationRequest};
+use crate::validate::{FixResult, ValidationResult, ValidationWarning};
 
 impl<FS: AsyncFileSystem + Clone> Diaryx<FS> {
     pub(crate) async fn cmd_validate_workspace(&self, path: Option<String>) -> Result<Response> {
@@ -14,25 +16,101 @@ impl<FS: AsyncFileSystem + Clone> Diaryx<FS> {
             message: "ValidateWorkspace requires a root index path".to_string(),
         })?;
         let resolved_root_path = self.resolve_fs_path(&root_path);
-        let result = self
+        let mut result = self
             .validate()
             .validate_workspace(&resolved_root_path, Some(2))
             .await?;
+        self.append_plugin_warnings(&mut result, Some(&root_path), None)
+            .await;
         Ok(Response::ValidationResult(result.with_metadata()))
     }
 
     pub(crate) async fn cmd_validate_file(&self, path: String) -> Result<Response> {
         let resolved_path = self.resolve_fs_path(&path);
-        let result = self.validate().validate_file(&resolved_path).await?;
+        let mut result = self.validate().validate_file(&resolved_path).await?;
+        self.append_plugin_warnings(&mut result, None, Some(&path))
+            .await;
         Ok(Response::ValidationResult(result.with_metadata()))
     }
 
+    /// Run plugin-contributed lint rules and append their issues to `result`
+    /// as [`ValidationWarning::PluginRule`]s.
+    async fn append_plugin_warnings(
+        &self,
+        result: &mut ValidationResult,
+        root_index: Option<&str>,
+        file: Option<&str>,
+    ) {
+        let request = PluginValidationRequest {
+            workspace_root: self.workspace_root().unwrap_or_default(),
+            root_index: root_index.map(|r| self.to_workspace_relative(r)),
+            file: file.map(|f| self.to_workspace_relative(f)),
+        };
+        let issues = self
+            .plugin_registry()
+            .collect_validation_issues(&request)
+            .await;
+        result
+            .warnings
+            .extend(
+                issues
+                    .into_iter()
+                    .map(|(plugin_id, issue)| ValidationWarning::PluginRule {
+                        plugin_id: plugin_id.0,
+                        rule: issue.rule,
+                        file: issue.file.map(|f| self.resolve_fs_path(f)),
+                        message: issue.message,
+                        fix: issue.fix,
+                    }),
+            );
+    }
+
+    /// Hand a [`ValidationWarning::PluginRule`] back to the plugin that
+    /// raised it. Returns `None` for any other warning.
+    async fn fix_plugin_warning(&self, warning: &ValidationWarning) -> Option<FixResult> {
+        let ValidationWarning::PluginRule {
+            plugin_id,
+            rule,
+            file,
+            message,
+            fix,
+        } = warning
+        else {
+            return None;
+        };
+        let issue = PluginValidationIssue {
+            rule: rule.clone(),
+            file: file
+                .as_ref()
+                .map(|f| self.to_workspace_relative(&f.to_string_lossy())),
+            message: message.clone(),
+            fix: fix.clone(),
+        };
+        let result = match self
+            .plugin_registry()
+            .fix_validation_issue(plugin_id, &issue)
+            .await
+        {
+            Some(Ok(message)) => FixResult::success(message),
+            Some(Err(e)) => FixResult::failure(format!("Plugin '{plugin_id}' failed to fix: {e}")),
+            None => FixResult::failure(format!("Plugin '{plugin_id}' can't fix '{rule}' issues")),
+        };
+        Some(result)
+    }
+
     pub(crate) async fn cmd_fix_all(
         &self,
-        validation_result: crate::validate::ValidationResult,
+        validation_result: ValidationResult,
     ) -> Result<Response> {
         let fixer = self.validate().fixer();
-        let (error_fixes, warning_fixes) = fixer.fix_all(&validation_result).await;
+        let (error_fixes, mut warning_fixes) = fixer.fix_all(&validation_result).await;
+        for warning in &validation_result.warnings {
+            if warning.can_auto_fix()
+                && let Some(fix) = self.fix_plugin_warning(warning).await
+            {
+                warning_fixes.push(fix);
+            }
+        }
 
         let total_fixed = error_fixes.iter().filter(|r| r.success).count()
             + warning_fixes.iter().filter(|r| r.success).count();
@@ -57,12 +135,16 @@ impl<FS: AsyncFileSystem + Clone> Diaryx<FS> {
     /// auto-fixable.
     pub(crate) async fn cmd_fix_validation_warning(
         &self,
-        warning: crate::validate::ValidationWarning,
+        warning: ValidationWarning,
     ) -> Result<Response> {
         let fixer = self.validate().fixer();
-        let result = match fixer.fix_warning(&warning).await {
+        let fixed = match self.fix_plugin_warning(&warning).await {
+            Some(r) => Some(r),
+            None => fixer.fix_warning(&warning).await,
+        };
+        let result = match fixed {
             Some(r) => r,
-            None => crate::validate::FixResult::failure(format!(
+            None => FixResult::failure(format!(
                 "Warning '{}' is not auto-fixable",
                 warning.description()
             )),
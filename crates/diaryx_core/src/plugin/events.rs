@@ -73,3 +73,23 @@ pub struct FileMovedEvent {
     /// New workspace-relative path.
     pub new_path: String,
 }
+
+// ============================================================================
+// Scheduled Events
+// ============================================================================
+
+/// Delivered to a plugin when one of its declared
+/// [`schedules`](super::schedule) comes due.
+#[derive(Debug, Clone)]
+pub struct ScheduledEvent {
+    /// ID of the schedule from the plugin's manifest.
+    pub schedule_id: String,
+    /// Local time of the occurrence being delivered (`YYYY-MM-DDTHH:MM`).
+    pub scheduled_at: String,
+    /// Earlier occurrences coalesced into this one because the host was not
+    /// running when they came due.
+    pub missed: u32,
+    /// Token to poll with `host_is_cancelled`; the host flags it when it
+    /// needs the run to stop early.
+    pub cancel_token: String,
+}
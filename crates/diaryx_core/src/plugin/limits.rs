@@ -0,0 +1,269 @@
+//! Per-plugin resource limits.
+//!
+//! A plugin may declare the linear memory, wall-clock time and fuel it needs
+//! in its manifest. The workspace can override those values under
+//! `plugins.<id>.limits`, and the host caps whatever results at its own
+//! [`ResourceLimitPolicy`]:
+//!
+//! ```yaml
+//! plugins:
+//!   com.example.indexer:
+//!     limits:
+//!       max_memory_pages: 2048   # 64 KiB pages → 128 MiB
+//!       timeout_ms: 120000
+//!       fuel: 5000000000
+//! ```
+//!
+//! # Resolution
+//!
+//! For each limit: workspace override, else the plugin's declaration, else
+//! the policy default — then clamped to the policy ceiling. Fuel metering is
+//! off unless someone asks for it (or the policy sets a default).
+//!
+//! Calls that overrun a limit are aborted by the runtime and surface as
+//! [`PluginError::Timeout`] or [`PluginError::ResourceLimitExceeded`].
+//! [`LimitViolations`] counts them so the plugin's [`PluginHealth`] degrades
+//! and, after [`MAX_LIMIT_VIOLATIONS`], fails.
+
+use super::{PluginError, PluginHealth};
+
+/// Size of one WebAssembly linear-memory page in bytes.
+pub const WASM_PAGE_BYTES: u64 = 64 * 1024;
+
+/// Number of aborted calls after which a plugin is marked
+/// [`PluginHealth::Failed`] and no longer dispatched to.
+pub const MAX_LIMIT_VIOLATIONS: u32 = 3;
+
+/// Resource limits as declared by a plugin or overridden by the workspace.
+///
+/// Every field is optional; unset fields fall through to the next source
+/// during [`ResourceLimitPolicy::resolve`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+pub struct PluginResourceLimits {
+    /// Maximum linear memory, in 64 KiB WebAssembly pages.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_pages: Option<u32>,
+
+    /// Wall-clock budget for a single guest call, in milliseconds.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+
+    /// Fuel (roughly, executed instructions) available to a single guest call.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
+}
+
+/// The limits a plugin actually runs under, after resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedResourceLimits {
+    /// Maximum linear memory, in 64 KiB WebAssembly pages.
+    pub max_memory_pages: u32,
+    /// Wall-clock budget for a single guest call, in milliseconds.
+    pub timeout_ms: u64,
+    /// Fuel per guest call, or `None` when fuel metering is off.
+    pub fuel: Option<u64>,
+}
+
+/// Host-side defaults and ceilings for plugin resource limits.
+///
+/// Each host (CLI, `diaryx edit` server, Tauri app) may tighten these; the
+/// [`Default`] is generous enough for sync-style plugins that spend most of a
+/// call waiting on host HTTP, while still bounding a runaway guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimitPolicy {
+    /// Memory pages granted when nobody declares a value (default: 256 MiB).
+    pub default_memory_pages: u32,
+    /// Hard ceiling on memory pages (default: 1 GiB).
+    pub max_memory_pages: u32,
+    /// Per-call timeout when nobody declares a value (default: 5 minutes).
+    pub default_timeout_ms: u64,
+    /// Hard ceiling on the per-call timeout (default: 1 hour).
+    pub max_timeout_ms: u64,
+    /// Fuel granted when nobody declares a value (default: none — metering off).
+    pub default_fuel: Option<u64>,
+    /// Hard ceiling on fuel, if any (default: none).
+    pub max_fuel: Option<u64>,
+}
+
+impl Default for ResourceLimitPolicy {
+    fn default() -> Self {
+        Self {
+            default_memory_pages: 4096,
+            max_memory_pages: 16384,
+            default_timeout_ms: 5 * 60 * 1000,
+            max_timeout_ms: 60 * 60 * 1000,
+            default_fuel: None,
+            max_fuel: None,
+        }
+    }
+}
+
+impl ResourceLimitPolicy {
+    /// Resolve the limits a plugin runs under.
+    ///
+    /// `declared` comes from the plugin's manifest, `workspace` from
+    /// `plugins.<id>.limits`. The workspace wins over the plugin, and the
+    /// policy ceiling wins over both.
+    pub fn resolve(
+        &self,
+        declared: Option<&PluginResourceLimits>,
+        workspace: Option<&PluginResourceLimits>,
+    ) -> ResolvedResourceLimits {
+        let pick = |f: fn(&PluginResourceLimits) -> Option<u64>| {
+            workspace.and_then(f).or_else(|| declared.and_then(f))
+        };
+
+        let max_memory_pages = pick(|l| l.max_memory_pages.map(u64::from))
+            .map_or(self.default_memory_pages, |p| {
+                u32::try_from(p).unwrap_or(u32::MAX)
+            })
+            .clamp(1, self.max_memory_pages);
+        let timeout_ms = pick(|l| l.timeout_ms)
+            .unwrap_or(self.default_timeout_ms)
+            .clamp(1, self.max_timeout_ms);
+        let fuel = pick(|l| l.fuel)
+            .or(self.default_fuel)
+            .map(|fuel| self.max_fuel.map_or(fuel, |max| fuel.min(max)));
+
+        ResolvedResourceLimits {
+            max_memory_pages,
+            timeout_ms,
+            fuel,
+        }
+    }
+}
+
+/// Running count of calls a plugin had aborted for overrunning its limits.
+#[derive(Debug, Clone, Default)]
+pub struct LimitViolations {
+    count: u32,
+    last: Option<String>,
+}
+
+impl LimitViolations {
+    /// Record `err` if it is a limit violation. Returns whether it was.
+    pub fn record(&mut self, err: &PluginError) -> bool {
+        if !err.is_limit_violation() {
+            return false;
+        }
+        self.count = self.count.saturating_add(1);
+        self.last = Some(err.to_string());
+        true
+    }
+
+    /// Number of violations recorded so far.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Whether the plugin has overrun its limits often enough to be disabled.
+    pub fn exhausted(&self) -> bool {
+        self.count >= MAX_LIMIT_VIOLATIONS
+    }
+
+    /// Health implied by the violations so far: healthy with none, degraded
+    /// after the first, failed at [`MAX_LIMIT_VIOLATIONS`].
+    pub fn health(&self) -> PluginHealth {
+        let Some(last) = &self.last else {
+            return PluginHealth::Healthy;
+        };
+        let summary = format!(
+            "{} of {} allowed resource limit violations (last: {last})",
+            self.count, MAX_LIMIT_VIOLATIONS
+        );
+        if self.exhausted() {
+            PluginHealth::Failed(summary)
+        } else {
+            PluginHealth::Degraded(summary)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(pages: Option<u32>, timeout: Option<u64>, fuel: Option<u64>) -> PluginResourceLimits {
+        PluginResourceLimits {
+            max_memory_pages: pages,
+            timeout_ms: timeout,
+            fuel,
+        }
+    }
+
+    #[test]
+    fn undeclared_limits_use_policy_defaults() {
+        let policy = ResourceLimitPolicy::default();
+        let resolved = policy.resolve(None, None);
+        assert_eq!(resolved.max_memory_pages, policy.default_memory_pages);
+        assert_eq!(resolved.timeout_ms, policy.default_timeout_ms);
+        assert_eq!(resolved.fuel, None);
+    }
+
+    #[test]
+    fn workspace_overrides_plugin_declaration() {
+        let policy = ResourceLimitPolicy::default();
+        let declared = limits(Some(512), Some(10_000), Some(1_000));
+        let workspace = limits(Some(1024), None, Some(2_000));
+        let resolved = policy.resolve(Some(&declared), Some(&workspace));
+        assert_eq!(resolved.max_memory_pages, 1024);
+        assert_eq!(resolved.timeout_ms, 10_000);
+        assert_eq!(resolved.fuel, Some(2_000));
+    }
+
+    #[test]
+    fn policy_ceiling_caps_declared_and_overridden_limits() {
+        let policy = ResourceLimitPolicy {
+            max_fuel: Some(500),
+            ..ResourceLimitPolicy::default()
+        };
+        let declared = limits(Some(u32::MAX), None, Some(10_000));
+        let workspace = limits(None, Some(u64::MAX), None);
+        let resolved = policy.resolve(Some(&declared), Some(&workspace));
+        assert_eq!(resolved.max_memory_pages, policy.max_memory_pages);
+        assert_eq!(resolved.timeout_ms, policy.max_timeout_ms);
+        assert_eq!(resolved.fuel, Some(500));
+    }
+
+    #[test]
+    fn zero_limits_are_raised_to_the_minimum() {
+        let policy = ResourceLimitPolicy::default();
+        let resolved = policy.resolve(Some(&limits(Some(0), Some(0), None)), None);
+        assert_eq!(resolved.max_memory_pages, 1);
+        assert_eq!(resolved.timeout_ms, 1);
+    }
+
+    #[test]
+    fn repeated_violations_degrade_then_fail() {
+        let mut violations = LimitViolations::default();
+        assert!(matches!(violations.health(), PluginHealth::Healthy));
+
+        assert!(!violations.record(&PluginError::CommandError("boom".into())));
+        assert!(matches!(violations.health(), PluginHealth::Healthy));
+
+        assert!(violations.record(&PluginError::Timeout("`on_event` after 5000ms".into())));
+        assert!(matches!(violations.health(), PluginHealth::Degraded(_)));
+
+        for _ in 1..MAX_LIMIT_VIOLATIONS {
+            violations.record(&PluginError::ResourceLimitExceeded("memory".into()));
+        }
+        assert!(violations.exhausted());
+        match violations.health() {
+            PluginHealth::Failed(msg) => assert!(msg.contains("memory"), "{msg}"),
+            other => panic!("expected failed health, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn limits_parse_from_workspace_yaml() {
+        let value = fig::Document::parse(
+            b"max_memory_pages: 2048\ntimeout_ms: 120000\n",
+            fig::Format::Yaml,
+        )
+        .unwrap()
+        .to_value()
+        .unwrap();
+        let parsed = <PluginResourceLimits as fig::FromValue>::from_value(&value).unwrap();
+        assert_eq!(parsed, limits(Some(2048), Some(120_000), None));
+    }
+}
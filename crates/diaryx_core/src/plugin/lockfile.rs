@@ -0,0 +1,282 @@
+//! Workspace plugin lockfile.
+//!
+//! `plugins.<id>.download` in the workspace config says where a plugin comes
+//! from but not which build, so two people opening the same workspace can end
+//! up running different code. The lockfile pins, per plugin, the exact
+//! artifact (version, URL, SHA-256 and publisher signature) and the
+//! permissions the workspace granted when it was locked. It lives next to the
+//! settings file as [`PLUGIN_LOCKFILE_NAME`]:
+//!
+//! ```json
+//! {
+//!   "lockfile_version": 1,
+//!   "plugins": {
+//!     "diaryx.sync": {
+//!       "version": "1.2.3",
+//!       "url": "https://app.diaryx.org/cdn/plugins/artifacts/diaryx.sync/1.2.3/ab12.wasm",
+//!       "sha256": "ab12…",
+//!       "size": 2048000,
+//!       "permissions": { "http_requests": { "include": ["app.diaryx.org"] } }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Installers write it; loaders compare the installed module against it and
+//! report any [`LockDrift`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::manifest::MarketplaceEntry;
+use super::permissions::PluginPermissions;
+use crate::error::{DiaryxError, Result};
+
+/// File name of the lockfile, stored in the same directory as the workspace
+/// settings file (`Meta/` by default).
+pub const PLUGIN_LOCKFILE_NAME: &str = "plugins.lock.json";
+
+/// Lockfile format version written by this build.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// The parsed workspace plugin lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+pub struct PluginLockfile {
+    /// Format version (must be [`LOCKFILE_VERSION`]).
+    pub lockfile_version: u32,
+    /// Locked plugins keyed by canonical plugin ID.
+    #[fig(default)]
+    pub plugins: BTreeMap<String, LockedPlugin>,
+}
+
+impl Default for PluginLockfile {
+    fn default() -> Self {
+        Self {
+            lockfile_version: LOCKFILE_VERSION,
+            plugins: BTreeMap::new(),
+        }
+    }
+}
+
+/// The pinned build and permissions of one plugin.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+pub struct LockedPlugin {
+    /// SemVer version string.
+    pub version: String,
+    /// URL the artifact was downloaded from.
+    pub url: String,
+    /// Lowercase hex SHA-256 of the WASM module.
+    pub sha256: String,
+    /// File size in bytes.
+    pub size: u64,
+    /// Base64 ed25519 public key of the publisher, if the artifact was signed.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub publisher_key: Option<String>,
+    /// Base64 ed25519 publisher signature, if the artifact was signed.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Permissions the workspace granted the plugin when it was locked.
+    #[fig(default)]
+    pub permissions: PluginPermissions,
+}
+
+impl LockedPlugin {
+    /// Lock the artifact of a registry entry together with the permissions
+    /// the workspace currently grants it.
+    pub fn from_entry(entry: &MarketplaceEntry, permissions: PluginPermissions) -> Self {
+        Self {
+            version: entry.version.clone(),
+            url: entry.artifact.url.clone(),
+            sha256: normalize_sha256(&entry.artifact.sha256),
+            size: entry.artifact.size,
+            publisher_key: entry.artifact.publisher_key.clone(),
+            signature: entry.artifact.signature.clone(),
+            permissions,
+        }
+    }
+
+    /// Compare an installed build and the workspace's current grants against
+    /// this entry. `granted` is `None` when the workspace has no
+    /// `plugins.<id>.permissions`, which matches a lock with no permissions.
+    pub fn drift(
+        &self,
+        version: &str,
+        sha256: &str,
+        granted: Option<&PluginPermissions>,
+    ) -> Vec<LockDrift> {
+        let mut drift = Vec::new();
+        if version != self.version {
+            drift.push(LockDrift::Version {
+                locked: self.version.clone(),
+                installed: version.to_string(),
+            });
+        }
+        let sha256 = normalize_sha256(sha256);
+        if sha256 != self.sha256 {
+            drift.push(LockDrift::Artifact {
+                locked: self.sha256.clone(),
+                installed: sha256,
+            });
+        }
+        let default_permissions = PluginPermissions::default();
+        if granted.unwrap_or(&default_permissions) != &self.permissions {
+            drift.push(LockDrift::Permissions);
+        }
+        drift
+    }
+}
+
+/// One way an installed plugin differs from its lockfile entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockDrift {
+    /// The installed version is not the locked one.
+    Version {
+        /// Locked version.
+        locked: String,
+        /// Installed version.
+        installed: String,
+    },
+    /// The installed module's SHA-256 is not the locked one.
+    Artifact {
+        /// Locked SHA-256.
+        locked: String,
+        /// SHA-256 of the installed module.
+        installed: String,
+    },
+    /// The permissions granted in the workspace config differ from the locked ones.
+    Permissions,
+}
+
+impl fmt::Display for LockDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version { locked, installed } => {
+                write!(f, "version {installed} is installed but {locked} is locked")
+            }
+            Self::Artifact { locked, installed } => write!(
+                f,
+                "installed module has SHA-256 {installed} but {locked} is locked"
+            ),
+            Self::Permissions => f.write_str("granted permissions differ from the locked ones"),
+        }
+    }
+}
+
+impl PluginLockfile {
+    /// Parse lockfile JSON, rejecting formats this build doesn't understand.
+    pub fn parse(json: &str) -> Result<Self> {
+        let value = fig::Document::parse(json.as_bytes(), fig::Format::Json)?.to_value()?;
+        let lockfile = <Self as fig::FromValue>::from_value(&value)?;
+        if lockfile.lockfile_version != LOCKFILE_VERSION {
+            return Err(DiaryxError::Validation(format!(
+                "Unsupported plugin lockfile_version: {} (expected {LOCKFILE_VERSION})",
+                lockfile.lockfile_version
+            )));
+        }
+        Ok(lockfile)
+    }
+
+    /// Render the lockfile as pretty-printed JSON with a trailing newline.
+    pub fn to_json(&self) -> Result<String> {
+        let mut json = fig::ToValue::to_value(self)
+            .serialize_with(fig::Format::Json, fig::SerializeOptions::pretty(2))?;
+        if !json.ends_with('\n') {
+            json.push('\n');
+        }
+        Ok(json)
+    }
+
+    /// The entry for `plugin_id`, if locked.
+    pub fn get(&self, plugin_id: &str) -> Option<&LockedPlugin> {
+        self.plugins.get(plugin_id)
+    }
+
+    /// Lock `plugin_id` to `entry`, replacing any previous entry.
+    pub fn lock(&mut self, plugin_id: &str, entry: LockedPlugin) {
+        self.plugins.insert(plugin_id.to_string(), entry);
+    }
+
+    /// Drop `plugin_id` from the lockfile. Returns whether it was locked.
+    pub fn unlock(&mut self, plugin_id: &str) -> bool {
+        self.plugins.remove(plugin_id).is_some()
+    }
+}
+
+/// Lowercase a hex digest and strip an optional `sha256:` prefix.
+fn normalize_sha256(value: &str) -> String {
+    let trimmed = value.trim();
+    trimmed
+        .strip_prefix("sha256:")
+        .unwrap_or(trimmed)
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::permissions::PermissionRule;
+
+    fn locked() -> LockedPlugin {
+        LockedPlugin {
+            version: "1.2.3".into(),
+            url: "https://example.com/diaryx.sync/1.2.3/ab12.wasm".into(),
+            sha256: "ab12".into(),
+            size: 42,
+            publisher_key: Some("cHVibGlzaGVy".into()),
+            signature: None,
+            permissions: PluginPermissions {
+                http_requests: Some(PermissionRule {
+                    include: vec!["app.diaryx.org".into()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn lockfile_roundtrips_through_json() {
+        let mut lockfile = PluginLockfile::default();
+        lockfile.lock("diaryx.sync", locked());
+        let json = lockfile.to_json().unwrap();
+        assert!(json.contains("\"diaryx.sync\""), "{json}");
+        assert_eq!(PluginLockfile::parse(&json).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn unknown_lockfile_version_is_rejected() {
+        let err = PluginLockfile::parse(r#"{"lockfile_version": 99, "plugins": {}}"#).unwrap_err();
+        assert!(err.to_string().contains("lockfile_version"), "{err}");
+    }
+
+    #[test]
+    fn matching_install_has_no_drift() {
+        let entry = locked();
+        let granted = entry.permissions.clone();
+        assert!(
+            entry
+                .drift("1.2.3", "sha256:AB12", Some(&granted))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn drift_reports_version_hash_and_permissions() {
+        let entry = locked();
+        let drift = entry.drift("1.3.0", "cd34", None);
+        assert_eq!(
+            drift,
+            vec![
+                LockDrift::Version {
+                    locked: "1.2.3".into(),
+                    installed: "1.3.0".into(),
+                },
+                LockDrift::Artifact {
+                    locked: "ab12".into(),
+                    installed: "cd34".into(),
+                },
+                LockDrift::Permissions,
+            ]
+        );
+    }
+}
@@ -5,6 +5,7 @@
 //! to dynamically render settings tabs, sidebar panels, command palette items, etc.
 
 use super::PluginId;
+use super::services::{ServiceProvision, ServiceRequirement};
 use crate::error::DiaryxError;
 use crate::frontmatter;
 
@@ -66,6 +67,31 @@ pub enum PluginCapability {
     /// secrets in their config (and would leak them into a git-diffable file)
     /// must NOT declare this until those secrets are moved to `host::secrets`.
     DeclarativeConfig,
+    /// Receives [`ScheduledEvent`](super::ScheduledEvent)s on cron-like
+    /// schedules.
+    Scheduled {
+        /// Schedules the host should deliver ticks for.
+        schedules: Vec<super::schedule::PluginSchedule>,
+    },
+    /// Contributes workspace lint rules whose issues are reported alongside
+    /// the built-in validation warnings.
+    ValidationRules {
+        /// Rules the plugin checks.
+        rules: Vec<super::validation::ValidationRule>,
+    },
+    /// Expands the markdown syntax of its editor extensions into static HTML
+    /// when a site is rendered.
+    RenderHtml,
+    /// Provides and/or consumes versioned service interfaces shared with
+    /// other plugins.
+    Services {
+        /// Interfaces this plugin implements for other plugins to call.
+        #[fig(default)]
+        provides: Vec<ServiceProvision>,
+        /// Interfaces this plugin calls on other plugins.
+        #[fig(default)]
+        requires: Vec<ServiceRequirement>,
+    },
 }
 
 /// A UI extension point contributed by a plugin.
@@ -725,6 +751,12 @@ pub struct PluginArtifact {
     pub size: u64,
     /// ISO 8601 timestamp of when the artifact was published.
     pub published_at: String,
+    /// Base64 ed25519 public key of the publisher that signed the artifact.
+    #[fig(default)]
+    pub publisher_key: Option<String>,
+    /// Base64 ed25519 signature over the plugin ID, version and SHA-256.
+    #[fig(default)]
+    pub signature: Option<String>,
 }
 
 /// A single plugin listing in the marketplace registry.
@@ -770,6 +802,12 @@ pub struct MarketplaceEntry {
     /// Protocol version this plugin was built against.
     #[fig(default)]
     pub protocol_version: Option<u32>,
+    /// Service interfaces the plugin provides to other plugins.
+    #[fig(default)]
+    pub provides: Vec<ServiceProvision>,
+    /// Service interfaces the plugin calls on other plugins.
+    #[fig(default)]
+    pub requires: Vec<ServiceRequirement>,
 }
 
 /// The parsed CDN registry (`registry.md`).
@@ -829,6 +867,12 @@ pub struct PluginWorkspaceMetadata {
     /// Protocol version this plugin was built against.
     #[fig(default)]
     pub protocol_version: Option<u32>,
+    /// Service interfaces the plugin provides.
+    #[fig(default)]
+    pub provides: Vec<ServiceProvision>,
+    /// Service interfaces the plugin requires.
+    #[fig(default)]
+    pub requires: Vec<ServiceRequirement>,
     /// Markdown body after the frontmatter.
     #[fig(skip)]
     pub body: String,
@@ -958,6 +1002,17 @@ impl PluginWorkspaceMetadata {
             .and_then(|v| v.as_u64())
             .map(|v| v as u32);
 
+        let provides = match fm.get("provides") {
+            Some(value) => yaml_value_to_typed(value)
+                .map_err(|e| DiaryxError::Validation(format!("Failed to parse provides: {e}")))?,
+            None => Vec::new(),
+        };
+        let requires = match fm.get("requires") {
+            Some(value) => yaml_value_to_typed(value)
+                .map_err(|e| DiaryxError::Validation(format!("Failed to parse requires: {e}")))?,
+            None => Vec::new(),
+        };
+
         Ok(PluginWorkspaceMetadata {
             id,
             name,
@@ -974,6 +1029,8 @@ impl PluginWorkspaceMetadata {
             cli,
             requested_permissions,
             protocol_version,
+            provides,
+            requires,
             body: parsed.body,
         })
     }
@@ -997,6 +1054,8 @@ impl PluginWorkspaceMetadata {
             screenshots: Vec::new(),
             requested_permissions: self.requested_permissions.clone(),
             protocol_version: self.protocol_version,
+            provides: self.provides.clone(),
+            requires: self.requires.clone(),
         }
     }
 }
@@ -1087,6 +1146,8 @@ plugins:
       sha256: "abc123"
       size: 2048000
       published_at: "2026-03-03T00:00:00Z"
+      publisher_key: "cHVibGlzaGVyLWtleQ=="
+      signature: "c2lnbmF0dXJl"
     capabilities: ["sync_transport"]
     icon: null
     screenshots: []
@@ -1125,6 +1186,14 @@ requested_permissions:
       include: ["api.diaryx.org"]
   reasons:
     http_requests: "Connect to sync server"
+provides:
+  - interface: sync
+    version: "1.0.0"
+    commands: ["SyncNow"]
+requires:
+  - interface: templating
+    version: "^1"
+    optional: true
 ---
 # Sync Plugin
 Full description in markdown body...
@@ -1140,6 +1209,14 @@ Full description in markdown body...
         assert_eq!(registry.plugins[0].version, "1.2.3");
         assert_eq!(registry.plugins[0].author, "Diaryx Team");
         assert_eq!(registry.plugins[0].artifact.size, 2048000);
+        assert_eq!(
+            registry.plugins[0].artifact.publisher_key.as_deref(),
+            Some("cHVibGlzaGVyLWtleQ==")
+        );
+        assert_eq!(
+            registry.plugins[0].artifact.signature.as_deref(),
+            Some("c2lnbmF0dXJl")
+        );
         assert!(registry.body.contains("Browse and install"));
     }
 
@@ -1153,6 +1230,7 @@ Full description in markdown body...
         assert_eq!(meta.author.as_deref(), Some("Diaryx Team"));
         assert_eq!(meta.categories, vec!["sync", "collaboration"]);
         assert_eq!(meta.artifact.sha256, "abc123");
+        assert_eq!(meta.artifact.signature, None);
         assert!(meta.ui.is_some());
         assert!(meta.cli.is_some());
         assert!(meta.requested_permissions.is_some());
@@ -1168,6 +1246,10 @@ Full description in markdown body...
         assert_eq!(entry.author, "Diaryx Team");
         assert_eq!(entry.artifact.url, meta.artifact.url);
         assert!(entry.description.contains("Full description"));
+        assert_eq!(entry.provides[0].interface, "sync");
+        assert_eq!(entry.provides[0].commands, vec!["SyncNow"]);
+        assert_eq!(entry.requires[0].version, "^1");
+        assert!(entry.requires[0].optional);
     }
 
     #[test]
@@ -1228,12 +1310,16 @@ plugins:
                 sha256: "abc123".to_string(),
                 size: 1024,
                 published_at: "2026-03-03T00:00:00Z".to_string(),
+                publisher_key: Some("cHVibGlzaGVy".to_string()),
+                signature: Some("c2lnbmF0dXJl".to_string()),
             },
             capabilities: vec!["custom".to_string()],
             icon: None,
             screenshots: vec![],
             requested_permissions: None,
             protocol_version: Some(1),
+            provides: vec![],
+            requires: vec![],
         };
 
         let json = to_json(&entry);
@@ -7,7 +7,7 @@
 //! # Plugin Namespaces
 //!
 //! - [`Plugin`] — base trait (id, init, shutdown)
-//! - [`WorkspacePlugin`] — workspace lifecycle events + custom commands
+//! - [`WorkspacePlugin`] — workspace lifecycle events, scheduled ticks, lint rules, render hooks + custom commands
 //! - [`FilePlugin`] — per-file lifecycle events
 //!
 //! # Registry
@@ -15,11 +15,22 @@
 //! The [`PluginRegistry`] collects plugins and dispatches events/commands to them.
 //! It is stored as a field on [`Diaryx`](crate::diaryx::Diaryx) and wired into
 //! the command handler.
+//!
+//! # Services
+//!
+//! Plugins that call each other declare the versioned interfaces they
+//! provide and require; see [`services`] for how they are resolved.
 
 pub mod events;
+pub mod limits;
+pub mod lockfile;
 pub mod manifest;
 pub mod permissions;
 pub mod registry;
+pub mod render_hooks;
+pub mod schedule;
+pub mod services;
+pub mod validation;
 
 use std::fmt;
 use std::path::PathBuf;
@@ -72,6 +83,10 @@ pub enum PluginError {
     #[error("Plugin call timed out: {0}")]
     Timeout(String),
 
+    /// A guest call was aborted for exceeding its memory or fuel limit.
+    #[error("Plugin exceeded its resource limits: {0}")]
+    ResourceLimitExceeded(String),
+
     /// No plugin with the given ID is registered.
     #[error("Plugin not found: {0}")]
     PluginNotFound(String),
@@ -95,6 +110,15 @@ impl PluginError {
         matches!(self, PluginError::Timeout(_))
     }
 
+    /// Whether the runtime aborted the call for overrunning one of the
+    /// plugin's [`limits`] (wall-clock, memory or fuel).
+    pub fn is_limit_violation(&self) -> bool {
+        matches!(
+            self,
+            PluginError::Timeout(_) | PluginError::ResourceLimitExceeded(_)
+        )
+    }
+
     /// Whether this error is a permission denial.
     pub fn is_permission_error(&self) -> bool {
         matches!(self, PluginError::PermissionDenied(_))
@@ -164,6 +188,12 @@ pub trait Plugin: Send + Sync + 'static {
     async fn shutdown(&self) -> Result<(), PluginError> {
         Ok(())
     }
+
+    /// Health the plugin reports about itself, e.g. after its runtime aborted
+    /// calls for overrunning [`limits`]. `None` defers to the registry.
+    fn health(&self) -> Option<PluginHealth> {
+        None
+    }
 }
 
 /// Base plugin trait. All plugins must implement this.
@@ -188,6 +218,12 @@ pub trait Plugin: 'static {
     async fn shutdown(&self) -> Result<(), PluginError> {
         Ok(())
     }
+
+    /// Health the plugin reports about itself, e.g. after its runtime aborted
+    /// calls for overrunning [`limits`]. `None` defers to the registry.
+    fn health(&self) -> Option<PluginHealth> {
+        None
+    }
 }
 
 /// Data a plugin hands back to the host from [`WorkspacePlugin::set_config`].
@@ -288,6 +324,46 @@ pub trait WorkspacePlugin: Plugin {
         let _ = event;
     }
 
+    /// Called when one of the plugin's declared schedules comes due.
+    async fn on_scheduled(&self, event: &ScheduledEvent) {
+        let _ = event;
+    }
+
+    /// Run the plugin's lint rules. Only called for plugins that declare
+    /// [`PluginCapability::ValidationRules`].
+    async fn validate(
+        &self,
+        request: &validation::PluginValidationRequest,
+    ) -> Result<Vec<validation::PluginValidationIssue>, PluginError> {
+        let _ = request;
+        Ok(Vec::new())
+    }
+
+    /// Apply the `fix` payload of an issue this plugin raised, returning a
+    /// description of what was done.
+    ///
+    /// Returns `None` if the plugin can't fix the issue.
+    async fn fix_validation_issue(
+        &self,
+        issue: &validation::PluginValidationIssue,
+    ) -> Option<Result<String, PluginError>> {
+        let _ = issue;
+        None
+    }
+
+    /// Render one occurrence of an editor extension's syntax as static HTML.
+    /// Only called for plugins that declare [`PluginCapability::RenderHtml`].
+    ///
+    /// Must be pure (see [`render_hooks`]). Returns `None` to leave the
+    /// syntax as written.
+    fn render_html(
+        &self,
+        request: &render_hooks::RenderHtmlRequest,
+    ) -> Option<Result<String, PluginError>> {
+        let _ = request;
+        None
+    }
+
     /// Handle a plugin-specific command.
     ///
     /// Returns `None` if the command is not recognized by this plugin.
@@ -34,10 +34,20 @@ pub struct PluginConfig {
     /// `host::storage`, which holds opaque per-plugin state/blobs.
     #[fig(default, skip_serializing_if = "Option::is_none")]
     pub config: Option<crate::yaml::Value>,
+
+    /// Resource limit overrides for this plugin. Still capped by the host's
+    /// [`ResourceLimitPolicy`](super::limits::ResourceLimitPolicy).
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<super::limits::PluginResourceLimits>,
+
+    /// Record every permission check this plugin triggers in its access
+    /// ledger, for `diaryx plugin audit`. Off by default.
+    #[fig(default, skip_serializing_if = "std::ops::Not::not")]
+    pub audit: bool,
 }
 
 /// All permission categories for a plugin.
-#[derive(Debug, Clone, Default, fig::ToValue, fig::FromValue)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, fig::ToValue, fig::FromValue)]
 pub struct PluginPermissions {
     /// Read files: `host_read_file`, `host_list_files`, `host_file_exists`.
     #[fig(default, skip_serializing_if = "Option::is_none")]
@@ -64,7 +74,8 @@ pub struct PluginPermissions {
     #[fig(default, skip_serializing_if = "Option::is_none")]
     pub http_requests: Option<PermissionRule>,
 
-    /// Command execution: `host_execute_command` (future).
+    /// Cross-plugin command calls: `host_plugin_command`.
+    /// Scope values are plugin IDs, `<plugin_id>:<command>` pairs, or `all`.
     #[fig(default, skip_serializing_if = "Option::is_none")]
     pub execute_commands: Option<PermissionRule>,
 
@@ -74,7 +85,7 @@ pub struct PluginPermissions {
 }
 
 /// A single permission rule with include/exclude lists.
-#[derive(Debug, Clone, Default, fig::ToValue, fig::FromValue)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, fig::ToValue, fig::FromValue)]
 pub struct PermissionRule {
     /// Scope values that grant access. Can be:
     /// - `"all"` — allow everything
@@ -82,6 +93,7 @@ pub struct PermissionRule {
     /// - Plain paths: `journal/daily/` — folder and descendants
     /// - Audience tags: `work`, `personal` — files with that audience
     /// - Domain patterns: `openrouter.ai` — for HTTP permissions
+    /// - Plugin IDs or `plugin_id:command` — for command permissions
     #[fig(default)]
     pub include: Vec<String>,
 
@@ -114,13 +126,25 @@ pub enum PermissionType {
     MoveFiles,
     /// Make HTTP requests (`host_http_request`).
     HttpRequests,
-    /// Execute host commands (`host_execute_command`, future).
+    /// Call other plugins' commands (`host_plugin_command`).
     ExecuteCommands,
     /// Access plugin persistent storage (`host_storage_get`, `host_storage_set`).
     PluginStorage,
 }
 
 impl PermissionType {
+    /// Every permission type, in declaration order.
+    pub const ALL: [PermissionType; 8] = [
+        Self::ReadFiles,
+        Self::EditFiles,
+        Self::CreateFiles,
+        Self::DeleteFiles,
+        Self::MoveFiles,
+        Self::HttpRequests,
+        Self::ExecuteCommands,
+        Self::PluginStorage,
+    ];
+
     /// Human-readable label for UI display.
     pub fn label(&self) -> &'static str {
         match self {
@@ -285,6 +309,28 @@ pub fn check_http_permission(rule: &PermissionRule, url: &str) -> PermissionChec
     PermissionCheck::NotConfigured
 }
 
+/// Check if a cross-plugin command call is allowed by a permission rule.
+///
+/// The target is `<plugin_id>:<command>`. Scope values are `all`, a plugin
+/// ID (any of its commands), or `<plugin_id>:<command>`.
+pub fn check_command_permission(rule: &PermissionRule, target: &str) -> PermissionCheck {
+    let plugin_id = target.split_once(':').map_or(target, |(plugin, _)| plugin);
+    let matches =
+        |scope: &str| scope.eq_ignore_ascii_case("all") || scope == target || scope == plugin_id;
+
+    // Check excludes first
+    if rule.exclude.iter().any(|scope| matches(scope.trim())) {
+        return PermissionCheck::Denied;
+    }
+
+    // Check includes
+    if rule.include.iter().any(|scope| matches(scope.trim())) {
+        return PermissionCheck::Allowed;
+    }
+
+    PermissionCheck::NotConfigured
+}
+
 /// Check if plugin storage access is allowed.
 pub fn check_storage_permission(rule: &PermissionRule) -> PermissionCheck {
     // Check excludes first
@@ -356,6 +402,7 @@ pub fn check_permission(
 
     match permission_type {
         PermissionType::HttpRequests => check_http_permission(rule, target),
+        PermissionType::ExecuteCommands => check_command_permission(rule, target),
         PermissionType::PluginStorage => check_storage_permission(rule),
         _ => check_file_permission(rule, target),
     }
@@ -585,6 +632,8 @@ mod tests {
                 download: None,
                 permissions: PluginPermissions::default(),
                 config: None,
+                limits: None,
+                audit: false,
             },
         );
         assert_eq!(
@@ -619,6 +668,8 @@ mod tests {
                     ..Default::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         );
 
@@ -694,6 +745,36 @@ mod tests {
         assert!(domain_matches("openrouter.ai", "openrouter.ai"));
     }
 
+    #[test]
+    fn test_command_permission_scopes() {
+        let rule = PermissionRule {
+            include: vec!["diaryx.templating:RenderBody".into(), "diaryx.math".into()],
+            exclude: vec!["diaryx.math:Delete".into()],
+            quota_bytes: None,
+        };
+
+        assert_eq!(
+            check_command_permission(&rule, "diaryx.templating:RenderBody"),
+            PermissionCheck::Allowed
+        );
+        assert_eq!(
+            check_command_permission(&rule, "diaryx.templating:HasTemplates"),
+            PermissionCheck::NotConfigured
+        );
+        assert_eq!(
+            check_command_permission(&rule, "diaryx.math:Render"),
+            PermissionCheck::Allowed
+        );
+        assert_eq!(
+            check_command_permission(&rule, "diaryx.math:Delete"),
+            PermissionCheck::Denied
+        );
+        assert_eq!(
+            check_command_permission(&rule, "diaryx.mathematics:Render"),
+            PermissionCheck::NotConfigured
+        );
+    }
+
     #[test]
     fn plugin_storage_defaults_to_allowed_when_not_configured() {
         let config = HashMap::new();
@@ -735,6 +816,8 @@ mod tests {
                 ..Default::default()
             },
             config: None,
+            limits: None,
+            audit: false,
         };
 
         let yaml = fig::ToValue::to_value(&config)
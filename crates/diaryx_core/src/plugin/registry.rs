@@ -9,7 +9,9 @@ use std::sync::{Arc, Mutex};
 use crate::yaml::Value as YamlValue;
 
 use super::events::*;
-use super::manifest::{PluginManifest, UiContribution};
+use super::manifest::{PluginCapability, PluginManifest, UiContribution};
+use super::services::ServiceResolution;
+use super::validation::{PluginValidationIssue, PluginValidationRequest};
 use super::{
     FilePlugin, Plugin, PluginContext, PluginError, PluginHealth, PluginId, WorkspacePlugin,
 };
@@ -24,8 +26,9 @@ struct PluginHealthTracker {
 /// Plugins are registered by namespace (workspace, file) and the registry
 /// dispatches events and commands to the appropriate plugins.
 ///
-/// The registry tracks plugin health — plugins that fail to initialize are
-/// marked as [`PluginHealth::Failed`] and skipped for subsequent dispatches.
+/// The registry tracks plugin health — plugins that fail to initialize, or
+/// that report themselves failed via [`Plugin::health`], are treated as
+/// [`PluginHealth::Failed`] and skipped for subsequent dispatches.
 pub struct PluginRegistry {
     plugins: Vec<Arc<dyn Plugin>>,
     workspace_plugins: Vec<Arc<dyn WorkspacePlugin>>,
@@ -91,25 +94,31 @@ impl PluginRegistry {
     }
 
     fn is_plugin_healthy(&self, id: &PluginId) -> bool {
-        if let Ok(tracker) = self.health.lock() {
-            !matches!(tracker.health.get(id), Some(PluginHealth::Failed(_)))
-        } else {
-            // If lock is poisoned, assume healthy to avoid blocking everything.
-            true
-        }
+        !matches!(self.get_plugin_health(id), PluginHealth::Failed(_))
     }
 
     /// Get the health status of a specific plugin.
+    ///
+    /// A failure recorded by the registry (e.g. at init) wins; otherwise the
+    /// plugin's own [`Plugin::health`] report (e.g. repeated resource limit
+    /// violations) is used when it has one.
     pub fn get_plugin_health(&self, plugin_id: &PluginId) -> PluginHealth {
-        if let Ok(tracker) = self.health.lock() {
-            tracker
-                .health
-                .get(plugin_id)
-                .cloned()
-                .unwrap_or(PluginHealth::Healthy)
-        } else {
-            PluginHealth::Healthy
+        // If the lock is poisoned, assume healthy to avoid blocking everything.
+        let tracked = self
+            .health
+            .lock()
+            .ok()
+            .and_then(|tracker| tracker.health.get(plugin_id).cloned())
+            .unwrap_or(PluginHealth::Healthy);
+        if matches!(tracked, PluginHealth::Failed(_)) {
+            return tracked;
         }
+        self.plugins
+            .iter()
+            .find(|p| &p.id() == plugin_id)
+            .and_then(|p| p.health())
+            .filter(|reported| !matches!(reported, PluginHealth::Healthy))
+            .unwrap_or(tracked)
     }
 
     /// Get health status of all registered plugins.
@@ -133,6 +142,12 @@ impl PluginRegistry {
         self.plugins.iter().map(|p| p.manifest()).collect()
     }
 
+    /// Match the service requirements of all registered plugins against
+    /// what the others provide.
+    pub fn service_resolution(&self) -> ServiceResolution {
+        ServiceResolution::resolve(&self.get_all_manifests())
+    }
+
     /// Get UI contributions from all registered plugins, tagged with plugin ID.
     pub fn get_all_ui_contributions(&self) -> Vec<(PluginId, Vec<UiContribution>)> {
         self.plugins
@@ -281,6 +296,99 @@ impl PluginRegistry {
         }
     }
 
+    /// Deliver a scheduled event to the plugin that declared the schedule.
+    ///
+    /// Does nothing if the plugin is not registered or not healthy.
+    pub async fn emit_scheduled(&self, plugin_id: &PluginId, event: &ScheduledEvent) {
+        if !self.is_plugin_healthy(plugin_id) {
+            return;
+        }
+        if let Some(plugin) = self
+            .workspace_plugins
+            .iter()
+            .find(|plugin| &plugin.id() == plugin_id)
+        {
+            plugin.on_scheduled(event).await;
+        }
+    }
+
+    // ========================================================================
+    // Validation Rules
+    // ========================================================================
+
+    /// Run the lint rules of every healthy plugin that declares
+    /// [`PluginCapability::ValidationRules`].
+    ///
+    /// A plugin whose rules fail is logged and skipped so one broken plugin
+    /// doesn't hide the built-in warnings.
+    pub async fn collect_validation_issues(
+        &self,
+        request: &PluginValidationRequest,
+    ) -> Vec<(PluginId, PluginValidationIssue)> {
+        let mut issues = Vec::new();
+        for plugin in &self.workspace_plugins {
+            let id = plugin.id();
+            if !self.is_plugin_healthy(&id) {
+                continue;
+            }
+            let declares_rules = plugin
+                .manifest()
+                .capabilities
+                .iter()
+                .any(|c| matches!(c, PluginCapability::ValidationRules { .. }));
+            if !declares_rules {
+                continue;
+            }
+            match plugin.validate(request).await {
+                Ok(found) => issues.extend(found.into_iter().map(|issue| (id.clone(), issue))),
+                Err(e) => log::warn!("[plugin-registry] Validation rules of {} failed: {}", id, e),
+            }
+        }
+        issues
+    }
+
+    /// Hand an issue back to the plugin that raised it to apply its fix.
+    ///
+    /// Returns `None` if the plugin is not registered or can't fix the issue.
+    pub async fn fix_validation_issue(
+        &self,
+        plugin_id: &str,
+        issue: &PluginValidationIssue,
+    ) -> Option<Result<String, PluginError>> {
+        let plugin = self
+            .workspace_plugins
+            .iter()
+            .find(|plugin| plugin.id().0 == plugin_id)?;
+        if !self.is_plugin_healthy(&plugin.id()) {
+            return Some(Err(PluginError::Other(format!(
+                "Plugin '{}' is in failed state",
+                plugin_id
+            ))));
+        }
+        plugin.fix_validation_issue(issue).await
+    }
+
+    // ========================================================================
+    // Render Hooks
+    // ========================================================================
+
+    /// Healthy plugins that declare [`PluginCapability::RenderHtml`], for a
+    /// site renderer to expand their editor syntax with.
+    pub fn render_hook_plugins(&self) -> Vec<Arc<dyn WorkspacePlugin>> {
+        self.workspace_plugins
+            .iter()
+            .filter(|plugin| {
+                self.is_plugin_healthy(&plugin.id())
+                    && plugin
+                        .manifest()
+                        .capabilities
+                        .iter()
+                        .any(|c| matches!(c, PluginCapability::RenderHtml))
+            })
+            .cloned()
+            .collect()
+    }
+
     // ========================================================================
     // File Events
     // ========================================================================
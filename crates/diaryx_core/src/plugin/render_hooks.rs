@@ -0,0 +1,92 @@
+//! Render-time expansion of plugin editor syntax.
+//!
+//! A plugin that contributes an
+//! [`UiContribution::EditorExtension`](super::UiContribution::EditorExtension)
+//! and also declares [`PluginCapability::RenderHtml`](super::PluginCapability::RenderHtml)
+//! can turn its markdown syntax into static HTML for published sites. The
+//! renderer finds each occurrence of the extension's delimiters, hands the
+//! text between them to [`WorkspacePlugin::render_html`](super::WorkspacePlugin::render_html),
+//! and splices the result into the page. The extension's `css` is added to
+//! the site stylesheet.
+//!
+//! `render_html` must be pure: the same request always yields the same HTML,
+//! with no file, network or storage access, so it can run anywhere a site is
+//! built.
+
+use super::{
+    EditorNodeType, MarkdownLevel, PluginCapability, PluginId, PluginManifest, UiContribution,
+};
+
+/// One piece of editor syntax a plugin expands at render time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderHook {
+    /// Plugin that renders the syntax.
+    pub plugin_id: PluginId,
+    /// The editor extension the syntax belongs to (e.g. `mathBlock`).
+    pub extension_id: String,
+    /// Whether the syntax is block-level (must start a line).
+    pub block: bool,
+    /// Opening delimiter (e.g. `$$`).
+    pub open: String,
+    /// Closing delimiter (e.g. `$$`).
+    pub close: String,
+    /// Whether a block's delimiters must be on the same line.
+    pub single_line: bool,
+    /// Stylesheet the extension ships for its rendered output.
+    pub css: Option<String>,
+}
+
+/// What a plugin is asked to render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderHtmlRequest {
+    /// The editor extension whose syntax matched.
+    pub extension_id: String,
+    /// The text between the delimiters.
+    pub source: String,
+    /// Whether the syntax matched as a block rather than inline.
+    pub display_mode: bool,
+}
+
+/// The syntax a manifest asks to have expanded at render time: every
+/// delimiter-based editor extension, if the manifest declares
+/// [`PluginCapability::RenderHtml`]. Builtin extensions and extensions
+/// without delimiters are skipped.
+pub fn manifest_render_hooks(manifest: &PluginManifest) -> Vec<RenderHook> {
+    let declares = manifest
+        .capabilities
+        .iter()
+        .any(|c| matches!(c, PluginCapability::RenderHtml));
+    if !declares {
+        return Vec::new();
+    }
+    manifest
+        .ui
+        .iter()
+        .filter_map(|contribution| match contribution {
+            UiContribution::EditorExtension {
+                extension_id,
+                node_type,
+                markdown,
+                css,
+                ..
+            } => {
+                if matches!(node_type, EditorNodeType::Builtin { .. })
+                    || markdown.open.is_empty()
+                    || markdown.close.is_empty()
+                {
+                    return None;
+                }
+                Some(RenderHook {
+                    plugin_id: manifest.id.clone(),
+                    extension_id: extension_id.clone(),
+                    block: matches!(markdown.level, MarkdownLevel::Block),
+                    open: markdown.open.clone(),
+                    close: markdown.close.clone(),
+                    single_line: markdown.single_line,
+                    css: css.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
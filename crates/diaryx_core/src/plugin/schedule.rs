@@ -0,0 +1,552 @@
+//! Scheduled plugin events.
+//!
+//! A plugin that declares the [`PluginCapability::Scheduled`] capability lists
+//! named, cron-like schedules in its manifest:
+//!
+//! ```yaml
+//! schedules:
+//!   - id: morning-reminder
+//!     cron: "0 9 * * 1-5"     # 09:00 on weekdays
+//!   - id: backup
+//!     cron: "@hourly"
+//! ```
+//!
+//! Hosts (`diaryx plugin tick`, the `diaryx edit` server, the Tauri app) keep
+//! a [`PluginScheduler`], ask it which schedules are [`due`](PluginScheduler::due)
+//! and deliver each one to its plugin as a [`ScheduledEvent`]. Schedules are
+//! evaluated against local wall-clock time at minute resolution.
+//!
+//! # Missed runs
+//!
+//! The scheduler remembers the last occurrence it delivered for each schedule
+//! in a [`ScheduleState`] that hosts persist between runs. When the host was
+//! not running (laptop asleep, app closed) across several occurrences, they
+//! are coalesced into a single event for the latest one, with
+//! [`ScheduledEvent::missed`] counting the skipped occurrences. A schedule
+//! seen for the first time starts counting from now rather than firing
+//! immediately.
+//!
+//! # Cancellation
+//!
+//! Every event carries a [`ScheduledEvent::cancel_token`]. Hosts flag it
+//! through the plugin runtime's cancellation registry when they shut down
+//! mid-run, and long-running plugins poll it with `host_is_cancelled`.
+//!
+//! [`PluginCapability::Scheduled`]: super::PluginCapability::Scheduled
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use super::{PluginCapability, PluginId, PluginManifest, ScheduledEvent};
+use crate::error::{DiaryxError, Result};
+
+/// File name of the persisted [`ScheduleState`], stored under the
+/// workspace's `.diaryx/` directory.
+pub const SCHEDULE_STATE_FILE: &str = "plugin-schedules.json";
+
+/// Format of the timestamps in [`ScheduleState`] and [`ScheduledEvent`].
+pub const SCHEDULE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// A named schedule declared in a plugin manifest.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(export, export_to = "bindings/"))]
+pub struct PluginSchedule {
+    /// Identifier passed back in [`ScheduledEvent::schedule_id`].
+    pub id: String,
+    /// Five-field cron expression (`minute hour day-of-month month
+    /// day-of-week`) or one of `@hourly`, `@daily`, `@weekly`, `@monthly`,
+    /// `@yearly`.
+    pub cron: String,
+    /// Human-readable description shown in plugin details.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Error parsing a cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronParseError {
+    /// The expression that failed to parse.
+    pub expression: String,
+    /// What was wrong with it.
+    pub reason: String,
+}
+
+impl fmt::Display for CronParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid cron expression '{}': {}",
+            self.expression, self.reason
+        )
+    }
+}
+
+impl std::error::Error for CronParseError {}
+
+/// A parsed cron expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    /// Whether day-of-month was restricted (not `*`). When both day fields
+    /// are restricted, cron matches either of them.
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field cron expression or an `@` alias.
+    ///
+    /// Each field accepts `*`, numbers, ranges (`1-5`), steps (`*/15`,
+    /// `0-30/10`) and comma-separated lists. Day-of-week runs from 0
+    /// (Sunday) to 6; 7 is also accepted as Sunday.
+    pub fn parse(expression: &str) -> std::result::Result<Self, CronParseError> {
+        let err = |reason: String| CronParseError {
+            expression: expression.to_string(),
+            reason,
+        };
+        let trimmed = expression.trim();
+        let expanded = match trimmed {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(err(format!("unknown alias '{other}'")));
+            }
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields.as_slice() else {
+            return Err(err(format!("expected 5 fields, found {}", fields.len())));
+        };
+
+        let minutes = parse_field(minute, 0, 59).map_err(&err)?;
+        let hours = parse_field(hour, 0, 23).map_err(&err)?;
+        let days_of_month = parse_field(dom, 1, 31).map_err(&err)?;
+        let months = parse_field(month, 1, 12).map_err(&err)?;
+        let mut days_of_week = parse_field(dow, 0, 7).map_err(&err)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: days_of_week as u8,
+            dom_restricted: !dom.starts_with('*'),
+            dow_restricted: !dow.starts_with('*'),
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// Whether the schedule fires in the minute containing `time`.
+    pub fn matches(&self, time: NaiveDateTime) -> bool {
+        self.months & (1 << time.month()) != 0
+            && self.matches_day(time.date())
+            && self.hours & (1 << time.hour()) != 0
+            && self.minutes & (1 << time.minute()) != 0
+    }
+
+    /// The first minute strictly after `after` at which the schedule fires,
+    /// or `None` if it never fires in the next five years (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = time + Duration::days(5 * 366);
+        while time <= limit {
+            if self.months & (1 << time.month()) == 0 {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_time(NaiveTime::MIN);
+                continue;
+            }
+            if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_time(NaiveTime::MIN);
+                continue;
+            }
+            if self.hours & (1 << time.hour()) == 0 {
+                time = time.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << time.minute()) == 0 {
+                time += Duration::minutes(1);
+                continue;
+            }
+            return Some(time);
+        }
+        None
+    }
+}
+
+/// Parse one cron field into a bitmask of allowed values.
+fn parse_field(field: &str, min: u32, max: u32) -> std::result::Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{step}' in '{field}'"))?;
+                if step == 0 {
+                    return Err(format!("step must be positive in '{field}'"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, field)?, parse_value(end, field)?)
+        } else {
+            let value = parse_value(range, field)?;
+            // `5/10` means "from 5 to the end, every 10".
+            (value, if step > 1 { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("'{part}' is outside {min}-{max} in '{field}'"));
+        }
+        let mut value = start;
+        while value <= end {
+            mask |= 1u64 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, field: &str) -> std::result::Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' in '{field}'"))
+}
+
+/// Cancellation token for a schedule's runs, as passed to `host_is_cancelled`.
+pub fn schedule_cancel_token(schedule_id: &str) -> String {
+    format!("schedule:{schedule_id}")
+}
+
+/// The schedules a manifest declares, if it has the `Scheduled` capability.
+pub fn manifest_schedules(manifest: &PluginManifest) -> &[PluginSchedule] {
+    manifest
+        .capabilities
+        .iter()
+        .find_map(|capability| match capability {
+            PluginCapability::Scheduled { schedules } => Some(schedules.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+/// When each schedule last fired, persisted by hosts between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+pub struct ScheduleState {
+    /// Last delivered occurrence ([`SCHEDULE_TIME_FORMAT`]) keyed by
+    /// `<plugin_id>/<schedule_id>`.
+    #[fig(default)]
+    pub last_runs: BTreeMap<String, String>,
+}
+
+impl ScheduleState {
+    /// Parse persisted state JSON.
+    pub fn parse(json: &str) -> Result<Self> {
+        let value = fig::Document::parse(json.as_bytes(), fig::Format::Json)?.to_value()?;
+        Ok(<Self as fig::FromValue>::from_value(&value)?)
+    }
+
+    /// Render the state as pretty-printed JSON with a trailing newline.
+    pub fn to_json(&self) -> Result<String> {
+        let mut json = fig::ToValue::to_value(self)
+            .serialize_with(fig::Format::Json, fig::SerializeOptions::pretty(2))?;
+        if !json.ends_with('\n') {
+            json.push('\n');
+        }
+        Ok(json)
+    }
+
+    fn key(plugin_id: &PluginId, schedule_id: &str) -> String {
+        format!("{plugin_id}/{schedule_id}")
+    }
+
+    fn last_run(&self, key: &str) -> Option<NaiveDateTime> {
+        let raw = self.last_runs.get(key)?;
+        NaiveDateTime::parse_from_str(raw, SCHEDULE_TIME_FORMAT).ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ScheduleEntry {
+    plugin_id: PluginId,
+    schedule_id: String,
+    cron: CronSchedule,
+}
+
+/// Decides which plugin schedules are due and coalesces missed runs.
+///
+/// The scheduler holds no timers; hosts call [`due`](Self::due) periodically
+/// (sleeping until [`next_due`](Self::next_due) in between) and persist
+/// [`state`](Self::state) afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct PluginScheduler {
+    entries: Vec<ScheduleEntry>,
+    state: ScheduleState,
+}
+
+impl PluginScheduler {
+    /// Create a scheduler resuming from persisted state.
+    pub fn new(state: ScheduleState) -> Self {
+        Self {
+            entries: Vec::new(),
+            state,
+        }
+    }
+
+    /// Register (or re-register) a plugin's schedules.
+    ///
+    /// Schedules whose cron expression fails to parse are skipped and
+    /// returned as errors for the host to report.
+    pub fn register(
+        &mut self,
+        plugin_id: &PluginId,
+        schedules: &[PluginSchedule],
+    ) -> Vec<DiaryxError> {
+        self.unregister(plugin_id);
+        let mut errors = Vec::new();
+        for schedule in schedules {
+            match CronSchedule::parse(&schedule.cron) {
+                Ok(cron) => self.entries.push(ScheduleEntry {
+                    plugin_id: plugin_id.clone(),
+                    schedule_id: schedule.id.clone(),
+                    cron,
+                }),
+                Err(e) => errors.push(DiaryxError::Validation(format!(
+                    "Plugin {plugin_id} schedule '{}': {e}",
+                    schedule.id
+                ))),
+            }
+        }
+        errors
+    }
+
+    /// Register the schedules of every manifest with the `Scheduled`
+    /// capability.
+    pub fn register_manifests(&mut self, manifests: &[PluginManifest]) -> Vec<DiaryxError> {
+        manifests
+            .iter()
+            .flat_map(|manifest| self.register(&manifest.id, manifest_schedules(manifest)))
+            .collect()
+    }
+
+    /// Drop a plugin's schedules. Its persisted last runs are kept so a
+    /// reinstall resumes where it left off.
+    pub fn unregister(&mut self, plugin_id: &PluginId) {
+        self.entries.retain(|entry| &entry.plugin_id != plugin_id);
+    }
+
+    /// Whether any schedules are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The persisted state, updated by [`due`](Self::due).
+    pub fn state(&self) -> &ScheduleState {
+        &self.state
+    }
+
+    /// Replace the state, e.g. with a fresh copy another host has written
+    /// since this scheduler was created.
+    pub fn set_state(&mut self, state: ScheduleState) {
+        self.state = state;
+    }
+
+    /// Collect the events due at `now` (local wall-clock time) and mark them
+    /// as delivered.
+    pub fn due(&mut self, now: NaiveDateTime) -> Vec<(PluginId, ScheduledEvent)> {
+        let mut events = Vec::new();
+        for entry in &self.entries {
+            let key = ScheduleState::key(&entry.plugin_id, &entry.schedule_id);
+            let Some(last) = self.state.last_run(&key) else {
+                self.state
+                    .last_runs
+                    .insert(key, now.format(SCHEDULE_TIME_FORMAT).to_string());
+                continue;
+            };
+
+            let mut latest = None;
+            let mut occurrences = 0u32;
+            let mut cursor = last;
+            while let Some(next) = entry.cron.next_after(cursor) {
+                if next > now {
+                    break;
+                }
+                latest = Some(next);
+                occurrences = occurrences.saturating_add(1);
+                cursor = next;
+            }
+            let Some(latest) = latest else {
+                continue;
+            };
+
+            let scheduled_at = latest.format(SCHEDULE_TIME_FORMAT).to_string();
+            self.state.last_runs.insert(key, scheduled_at.clone());
+            events.push((
+                entry.plugin_id.clone(),
+                ScheduledEvent {
+                    schedule_id: entry.schedule_id.clone(),
+                    scheduled_at,
+                    missed: occurrences - 1,
+                    cancel_token: schedule_cancel_token(&entry.schedule_id),
+                },
+            ));
+        }
+        events
+    }
+
+    /// The earliest upcoming occurrence across all schedules after `now`.
+    pub fn next_due(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let key = ScheduleState::key(&entry.plugin_id, &entry.schedule_id);
+                let from = self.state.last_run(&key).unwrap_or(now);
+                entry.cron.next_after(from)
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, SCHEDULE_TIME_FORMAT).unwrap()
+    }
+
+    fn schedule(id: &str, cron: &str) -> PluginSchedule {
+        PluginSchedule {
+            id: id.into(),
+            cron: cron.into(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn parses_fields_and_aliases() {
+        let weekdays = CronSchedule::parse("0 9 * * 1-5").unwrap();
+        // 2026-03-02 is a Monday.
+        assert!(weekdays.matches(at("2026-03-02T09:00")));
+        assert!(!weekdays.matches(at("2026-03-01T09:00")));
+        assert!(!weekdays.matches(at("2026-03-02T09:01")));
+
+        let quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert!(quarter.matches(at("2026-03-02T10:45")));
+        assert!(!quarter.matches(at("2026-03-02T10:50")));
+
+        assert_eq!(
+            CronSchedule::parse("@daily").unwrap(),
+            CronSchedule::parse("0 0 * * *").unwrap()
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("0 0 * * 0").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for bad in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "@fortnightly",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::parse(bad).is_err(), "{bad:?} should fail");
+        }
+    }
+
+    #[test]
+    fn next_after_skips_to_matching_minute() {
+        let cron = CronSchedule::parse("30 6 1 * *").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-03-01T06:30")),
+            Some(at("2026-04-01T06:30"))
+        );
+        let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(never.next_after(at("2026-01-01T00:00")), None);
+    }
+
+    #[test]
+    fn first_registration_does_not_fire_immediately() {
+        let mut scheduler = PluginScheduler::default();
+        scheduler.register(&"diaryx.daily".into(), &[schedule("tick", "* * * * *")]);
+
+        assert!(scheduler.due(at("2026-03-02T09:00")).is_empty());
+        let events = scheduler.due(at("2026-03-02T09:01"));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1.missed, 0);
+        assert_eq!(events[0].1.cancel_token, "schedule:tick");
+    }
+
+    #[test]
+    fn missed_runs_are_coalesced() {
+        let mut state = ScheduleState::default();
+        state
+            .last_runs
+            .insert("diaryx.backup/hourly".into(), "2026-03-02T09:00".into());
+        let mut scheduler = PluginScheduler::new(state);
+        scheduler.register(&"diaryx.backup".into(), &[schedule("hourly", "@hourly")]);
+
+        let events = scheduler.due(at("2026-03-02T12:30"));
+        assert_eq!(events.len(), 1);
+        let (plugin_id, event) = &events[0];
+        assert_eq!(plugin_id.0, "diaryx.backup");
+        assert_eq!(event.scheduled_at, "2026-03-02T12:00");
+        assert_eq!(event.missed, 2);
+
+        assert!(scheduler.due(at("2026-03-02T12:59")).is_empty());
+        assert_eq!(
+            scheduler.next_due(at("2026-03-02T12:59")),
+            Some(at("2026-03-02T13:00"))
+        );
+
+        let json = scheduler.state().to_json().unwrap();
+        assert_eq!(ScheduleState::parse(&json).unwrap(), *scheduler.state());
+    }
+
+    #[test]
+    fn invalid_schedules_are_reported_and_skipped() {
+        let mut scheduler = PluginScheduler::default();
+        let errors = scheduler.register(
+            &"diaryx.daily".into(),
+            &[schedule("bad", "not cron"), schedule("ok", "@daily")],
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("bad"), "{}", errors[0]);
+        assert!(!scheduler.is_empty());
+    }
+}
@@ -0,0 +1,551 @@
+//! Plugin-to-plugin service contracts.
+//!
+//! Plugins call each other through `host_plugin_command`. Rather than naming
+//! another plugin and hoping it is installed, a plugin declares the
+//! [`PluginCapability::Services`] capability: the versioned interfaces it
+//! `provides`, and the ones it `requires` along with a version range:
+//!
+//! ```yaml
+//! # diaryx.templating
+//! provides:
+//!   - interface: templating
+//!     version: 1.2.0
+//!     commands: [RenderBody, HasTemplates]
+//!
+//! # diaryx.pandoc
+//! requires:
+//!   - interface: templating
+//!     version: ^1.0
+//!     optional: true
+//! ```
+//!
+//! [`ServiceResolution::resolve`] matches every requirement against the
+//! loaded plugins, picking the highest satisfying provider. Loaders use the
+//! resulting [`order`](ServiceResolution::order) so providers are initialized
+//! before the plugins that call them, and report
+//! [`missing`](ServiceResolution::missing) providers instead of letting the
+//! first cross-plugin call fail. `diaryx plugin install` uses the same
+//! resolution to offer installing missing providers and to ask for the
+//! `execute_commands` permission each binding needs
+//! ([`ServiceResolution::permission_scopes`]).
+//!
+//! # Version ranges
+//!
+//! Ranges follow Cargo's syntax: comma-separated comparators (`^1.2`, `~1.2.3`,
+//! `=1`, `>=1.0, <3`, `1.x`, `*`), all of which must match. A bare version is
+//! a caret requirement. Pre-release and build suffixes on versions are
+//! ignored.
+//!
+//! [`PluginCapability::Services`]: super::PluginCapability::Services
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{PluginCapability, PluginId, PluginManifest};
+
+/// A service interface a plugin implements.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(export, export_to = "bindings/"))]
+pub struct ServiceProvision {
+    /// Interface name, e.g. `templating`.
+    pub interface: String,
+    /// Version of the interface implemented (`major.minor.patch`).
+    pub version: String,
+    /// Commands that make up the interface. Empty means callers may invoke
+    /// any of the plugin's commands.
+    #[fig(default)]
+    pub commands: Vec<String>,
+}
+
+/// A service interface a plugin calls on another plugin.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(export, export_to = "bindings/"))]
+pub struct ServiceRequirement {
+    /// Interface name, e.g. `templating`.
+    pub interface: String,
+    /// Accepted versions of the interface (see the module docs).
+    pub version: String,
+    /// Whether the plugin still works, with reduced functionality, when no
+    /// provider is installed.
+    #[fig(default)]
+    pub optional: bool,
+}
+
+/// Service interfaces a plugin provides, from its
+/// [`PluginCapability::Services`] capability.
+pub fn manifest_provides(manifest: &PluginManifest) -> &[ServiceProvision] {
+    manifest
+        .capabilities
+        .iter()
+        .find_map(|capability| match capability {
+            PluginCapability::Services { provides, .. } => Some(provides.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+/// Service interfaces a plugin requires, from its
+/// [`PluginCapability::Services`] capability.
+pub fn manifest_requires(manifest: &PluginManifest) -> &[ServiceRequirement] {
+    manifest
+        .capabilities
+        .iter()
+        .find_map(|capability| match capability {
+            PluginCapability::Services { requires, .. } => Some(requires.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
+
+// ============================================================================
+// Versions
+// ============================================================================
+
+type Version = (u64, u64, u64);
+
+fn parse_version(version: &str) -> Option<Version> {
+    let core = version.trim().split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// The versions a partial version (`1`, `1.2`, `1.2.3`, `1.x`) stands for,
+/// as a half-open `[low, high)` interval. `high` is `None` when unbounded.
+fn partial_bounds(parts: &[u64]) -> (Version, Option<Version>) {
+    let get = |i: usize| parts.get(i).copied().unwrap_or(0);
+    let low = (get(0), get(1), get(2));
+    let high = match parts.len() {
+        0 => None,
+        n => Some(bump(parts, n - 1)),
+    };
+    (low, high)
+}
+
+/// The smallest version above every version sharing `parts[..=index]`.
+fn bump(parts: &[u64], index: usize) -> Version {
+    let get = |i: usize| if i < index { parts[i] } else { 0 };
+    let mut version = [get(0), get(1), get(2)];
+    version[index] = parts[index] + 1;
+    (version[0], version[1], version[2])
+}
+
+fn comparator_matches(comparator: &str, version: Version) -> Option<bool> {
+    let comparator = comparator.trim();
+    let (op, rest) = ["<=", ">=", "<", ">", "=", "^", "~"]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("^", comparator));
+
+    let rest = rest.trim().split(['-', '+']).next()?;
+    let mut parts = Vec::new();
+    if rest != "*" {
+        for part in rest.split('.') {
+            match part {
+                "x" | "X" | "*" => break,
+                _ => parts.push(part.parse::<u64>().ok()?),
+            }
+        }
+        if parts.is_empty() || parts.len() > 3 {
+            return None;
+        }
+    }
+
+    let (low, high) = partial_bounds(&parts);
+    let below = |bound: Option<Version>| bound.is_none_or(|b| version < b);
+    Some(match op {
+        "=" => version >= low && below(high),
+        ">=" => version >= low,
+        ">" => high.is_some_and(|h| version >= h),
+        "<" => version < low,
+        "<=" => below(high),
+        "~" => {
+            let high = match parts.len() {
+                0 => None,
+                1 => Some(bump(&parts, 0)),
+                _ => Some(bump(&parts, 1)),
+            };
+            version >= low && below(high)
+        }
+        _ => {
+            // `^1.2` stops below 2.0.0, `^0.2` below 0.3.0, `^0.0.3` below 0.0.4.
+            let high = (!parts.is_empty()).then(|| {
+                let first_nonzero = parts.iter().position(|&p| p != 0);
+                bump(&parts, first_nonzero.unwrap_or(parts.len() - 1))
+            });
+            version >= low && below(high)
+        }
+    })
+}
+
+/// Whether `version` satisfies the version `range`. Malformed ranges or
+/// versions never match.
+pub fn version_satisfies(range: &str, version: &str) -> bool {
+    let Some(version) = parse_version(version) else {
+        return false;
+    };
+    let range = range.trim();
+    if range.is_empty() {
+        return true;
+    }
+    range
+        .split(',')
+        .all(|comparator| comparator_matches(comparator, version) == Some(true))
+}
+
+// ============================================================================
+// Resolution
+// ============================================================================
+
+/// Pick the candidate providing the highest version of the interface that
+/// satisfies `requirement`. Ties go to the candidate listed first.
+///
+/// Candidates pair any key (a manifest, a registry listing) with the
+/// interfaces it provides.
+pub fn best_provider<'a, K: Copy>(
+    candidates: impl IntoIterator<Item = (K, &'a [ServiceProvision])>,
+    requirement: &ServiceRequirement,
+) -> Option<(K, &'a ServiceProvision)> {
+    let mut best: Option<(K, &ServiceProvision, Version)> = None;
+    for (candidate, provides) in candidates {
+        for provision in provides
+            .iter()
+            .filter(|p| p.interface == requirement.interface)
+        {
+            let Some(version) = parse_version(&provision.version) else {
+                continue;
+            };
+            if version_satisfies(&requirement.version, &provision.version)
+                && best.as_ref().is_none_or(|(_, _, v)| version > *v)
+            {
+                best = Some((candidate, provision, version));
+            }
+        }
+    }
+    best.map(|(candidate, provision, _)| (candidate, provision))
+}
+
+/// A requirement matched to the plugin that provides it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceBinding {
+    /// Plugin that requires the interface.
+    pub consumer: PluginId,
+    /// Interface name.
+    pub interface: String,
+    /// Plugin chosen to provide it.
+    pub provider: PluginId,
+    /// Version of the interface the provider implements.
+    pub version: String,
+    /// Commands the consumer may call (empty means any).
+    pub commands: Vec<String>,
+}
+
+/// A requirement no loaded plugin satisfies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingService {
+    /// Plugin that requires the interface.
+    pub consumer: PluginId,
+    /// The unmet requirement.
+    pub requirement: ServiceRequirement,
+    /// Versions of the interface that are provided, but outside the range.
+    pub available_versions: Vec<String>,
+}
+
+impl fmt::Display for MissingService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "plugin '{}' {} service '{}' {}",
+            self.consumer,
+            if self.requirement.optional {
+                "can use"
+            } else {
+                "requires"
+            },
+            self.requirement.interface,
+            self.requirement.version,
+        )?;
+        if self.available_versions.is_empty() {
+            write!(f, ", but no installed plugin provides it")
+        } else {
+            write!(
+                f,
+                ", but only version(s) {} are installed",
+                self.available_versions.join(", ")
+            )
+        }
+    }
+}
+
+/// The outcome of matching every plugin's service requirements against the
+/// other plugins' provisions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServiceResolution {
+    /// Plugin IDs ordered so every provider comes before its consumers.
+    /// Plugins caught in a dependency cycle come last, in input order.
+    pub order: Vec<PluginId>,
+    /// Satisfied requirements.
+    pub bindings: Vec<ServiceBinding>,
+    /// Unsatisfied requirements, optional ones included.
+    pub missing: Vec<MissingService>,
+    /// Plugins whose requirements form a cycle.
+    pub cyclic: Vec<PluginId>,
+}
+
+impl ServiceResolution {
+    /// Resolve the service requirements of `manifests`.
+    ///
+    /// Each requirement binds to the provider with the highest satisfying
+    /// version; ties go to the plugin listed first. A plugin never satisfies
+    /// its own requirements.
+    pub fn resolve(manifests: &[PluginManifest]) -> Self {
+        let mut resolution = Self::default();
+
+        for consumer in manifests {
+            for requirement in manifest_requires(consumer) {
+                let candidates = manifests
+                    .iter()
+                    .filter(|m| m.id != consumer.id)
+                    .map(|m| (m, manifest_provides(m)));
+                match best_provider(candidates.clone(), requirement) {
+                    Some((provider, provision)) => resolution.bindings.push(ServiceBinding {
+                        consumer: consumer.id.clone(),
+                        interface: requirement.interface.clone(),
+                        provider: provider.id.clone(),
+                        version: provision.version.clone(),
+                        commands: provision.commands.clone(),
+                    }),
+                    None => resolution.missing.push(MissingService {
+                        consumer: consumer.id.clone(),
+                        requirement: requirement.clone(),
+                        available_versions: candidates
+                            .flat_map(|(_, provides)| provides)
+                            .filter(|p| p.interface == requirement.interface)
+                            .map(|p| p.version.clone())
+                            .collect(),
+                    }),
+                }
+            }
+        }
+
+        // Stable topological sort: repeatedly take the first plugin, in
+        // input order, whose providers have all been placed.
+        let mut dependencies: HashMap<&PluginId, HashSet<&PluginId>> = HashMap::new();
+        for binding in &resolution.bindings {
+            dependencies
+                .entry(&binding.consumer)
+                .or_default()
+                .insert(&binding.provider);
+        }
+        let mut placed: HashSet<&PluginId> = HashSet::new();
+        let mut remaining: Vec<&PluginId> = manifests.iter().map(|m| &m.id).collect();
+        while let Some(index) = remaining.iter().position(|id| {
+            dependencies
+                .get(id)
+                .is_none_or(|deps| deps.iter().all(|dep| placed.contains(dep)))
+        }) {
+            let id = remaining.remove(index);
+            placed.insert(id);
+            resolution.order.push(id.clone());
+        }
+        for id in remaining {
+            resolution.cyclic.push(id.clone());
+            resolution.order.push(id.clone());
+        }
+
+        resolution
+    }
+
+    /// The provider bound to `consumer`'s requirement on `interface`.
+    pub fn provider(&self, consumer: &PluginId, interface: &str) -> Option<&ServiceBinding> {
+        self.bindings
+            .iter()
+            .find(|b| &b.consumer == consumer && b.interface == interface)
+    }
+
+    /// Requirements of `consumer` that are not satisfied.
+    pub fn missing_for<'a>(
+        &'a self,
+        consumer: &'a PluginId,
+    ) -> impl Iterator<Item = &'a MissingService> + 'a {
+        self.missing.iter().filter(move |m| &m.consumer == consumer)
+    }
+
+    /// `execute_commands` permission scopes `consumer` needs to call its
+    /// bound providers: `<provider>:<command>` for each interface command,
+    /// or `<provider>` for interfaces that don't list their commands.
+    pub fn permission_scopes(&self, consumer: &PluginId) -> Vec<String> {
+        let mut scopes = Vec::new();
+        for binding in self.bindings.iter().filter(|b| &b.consumer == consumer) {
+            let binding_scopes = if binding.commands.is_empty() {
+                vec![binding.provider.0.clone()]
+            } else {
+                binding
+                    .commands
+                    .iter()
+                    .map(|command| format!("{}:{}", binding.provider, command))
+                    .collect()
+            };
+            for scope in binding_scopes {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+        }
+        scopes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(
+        id: &str,
+        provides: &[(&str, &str)],
+        requires: &[(&str, &str, bool)],
+    ) -> PluginManifest {
+        PluginManifest {
+            id: PluginId(id.into()),
+            name: id.into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            capabilities: vec![PluginCapability::Services {
+                provides: provides
+                    .iter()
+                    .map(|(interface, version)| ServiceProvision {
+                        interface: (*interface).into(),
+                        version: (*version).into(),
+                        commands: vec!["Render".into()],
+                    })
+                    .collect(),
+                requires: requires
+                    .iter()
+                    .map(|(interface, version, optional)| ServiceRequirement {
+                        interface: (*interface).into(),
+                        version: (*version).into(),
+                        optional: *optional,
+                    })
+                    .collect(),
+            }],
+            ui: vec![],
+            cli: vec![],
+        }
+    }
+
+    fn ids(ids: &[PluginId]) -> Vec<&str> {
+        ids.iter().map(|id| id.0.as_str()).collect()
+    }
+
+    #[test]
+    fn version_ranges_follow_cargo_semantics() {
+        for (range, version, expected) in [
+            ("^1.2", "1.9.0", true),
+            ("^1.2", "2.0.0", false),
+            ("^1.2", "1.1.9", false),
+            ("1.2", "1.4.0", true),
+            ("^0.2.3", "0.2.9", true),
+            ("^0.2.3", "0.3.0", false),
+            ("^0.0.3", "0.0.4", false),
+            ("~1.2.3", "1.2.9", true),
+            ("~1.2.3", "1.3.0", false),
+            ("~1", "1.9.0", true),
+            ("=1.2", "1.2.7", true),
+            ("=1.2.3", "1.2.4", false),
+            (">=1.0, <3", "2.5.0", true),
+            (">=1.0, <3", "3.0.0", false),
+            (">1.2", "1.2.9", false),
+            (">1.2", "1.3.0", true),
+            ("<=1.2", "1.2.9", true),
+            ("1.x", "1.7.1", true),
+            ("*", "0.0.1", true),
+            ("", "5.0.0", true),
+            ("^1", "1.0.0-beta.1", true),
+            ("^1", "not-a-version", false),
+            ("^one", "1.0.0", false),
+        ] {
+            assert_eq!(
+                version_satisfies(range, version),
+                expected,
+                "{range:?} vs {version:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn binds_highest_satisfying_provider_and_orders_providers_first() {
+        let manifests = [
+            manifest("export", &[], &[("templating", "^1", false)]),
+            manifest("templating.old", &[("templating", "1.1.0")], &[]),
+            manifest("templating.new", &[("templating", "1.4.0")], &[]),
+            manifest("templating.next", &[("templating", "2.0.0")], &[]),
+        ];
+        let resolution = ServiceResolution::resolve(&manifests);
+
+        let binding = resolution
+            .provider(&PluginId("export".into()), "templating")
+            .unwrap();
+        assert_eq!(binding.provider.0, "templating.new");
+        assert!(resolution.missing.is_empty());
+        assert_eq!(
+            ids(&resolution.order),
+            [
+                "templating.old",
+                "templating.new",
+                "export",
+                "templating.next"
+            ]
+        );
+        assert_eq!(
+            resolution.permission_scopes(&PluginId("export".into())),
+            ["templating.new:Render"]
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_out_of_range_providers() {
+        let manifests = [
+            manifest(
+                "export",
+                &[],
+                &[("templating", "^2", false), ("spellcheck", "*", true)],
+            ),
+            manifest("templating", &[("templating", "1.4.0")], &[]),
+        ];
+        let resolution = ServiceResolution::resolve(&manifests);
+
+        assert!(resolution.bindings.is_empty());
+        let missing: Vec<String> = resolution.missing.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            missing,
+            [
+                "plugin 'export' requires service 'templating' ^2, but only version(s) 1.4.0 are installed",
+                "plugin 'export' can use service 'spellcheck' *, but no installed plugin provides it",
+            ]
+        );
+        assert_eq!(ids(&resolution.order), ["export", "templating"]);
+    }
+
+    #[test]
+    fn cycles_are_reported_and_placed_last() {
+        let manifests = [
+            manifest("a", &[("alpha", "1.0.0")], &[("beta", "^1", false)]),
+            manifest("b", &[("beta", "1.0.0")], &[("alpha", "^1", false)]),
+            manifest("c", &[], &[]),
+            manifest("self", &[("own", "1.0.0")], &[("own", "^1", false)]),
+        ];
+        let resolution = ServiceResolution::resolve(&manifests);
+
+        assert_eq!(ids(&resolution.cyclic), ["a", "b"]);
+        assert_eq!(ids(&resolution.order), ["c", "self", "a", "b"]);
+        assert_eq!(resolution.missing.len(), 1);
+        assert_eq!(resolution.missing[0].consumer.0, "self");
+    }
+}
@@ -0,0 +1,68 @@
+//! Plugin-contributed workspace lint rules.
+//!
+//! A plugin that declares
+//! [`PluginCapability::ValidationRules`](super::PluginCapability::ValidationRules)
+//! is asked for issues whenever the workspace (or a single file) is
+//! validated. Each [`PluginValidationIssue`] it returns becomes a
+//! [`ValidationWarning::PluginRule`](crate::validate::ValidationWarning::PluginRule)
+//! next to the built-in warnings, and flows through `ValidateWorkspace`,
+//! `ValidationResultWithMeta` and `FixValidationWarning` the same way. An
+//! issue that carries a `fix` payload is auto-fixable: the host hands the
+//! issue back to the plugin that raised it, which applies the fix.
+
+use std::path::PathBuf;
+
+use crate::yaml::Value as YamlValue;
+
+/// A lint rule a plugin contributes, as declared in its manifest.
+#[derive(Debug, Clone, PartialEq, Eq, fig::ToValue, fig::FromValue)]
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[cfg_attr(feature = "typescript", ts(export, export_to = "bindings/"))]
+pub struct ValidationRule {
+    /// Rule identifier, unique within the plugin (e.g. `daily-mood`).
+    pub id: String,
+    /// Human-readable summary of what the rule checks.
+    pub description: String,
+}
+
+/// What a plugin is asked to validate.
+#[derive(Debug, Clone, Default)]
+pub struct PluginValidationRequest {
+    /// Root directory of the workspace.
+    pub workspace_root: PathBuf,
+    /// Workspace-relative path of the root index, for a whole-workspace
+    /// validation.
+    pub root_index: Option<String>,
+    /// Workspace-relative path of the single file being validated, or `None`
+    /// for a whole-workspace validation.
+    pub file: Option<String>,
+}
+
+/// One issue raised by a plugin rule.
+#[derive(Debug, Clone, PartialEq, fig::ToValue, fig::FromValue)]
+pub struct PluginValidationIssue {
+    /// ID of the rule that raised the issue.
+    pub rule: String,
+    /// Workspace-relative path of the offending file, if any.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// Plugin-defined payload describing how to fix the issue. Issues with a
+    /// payload are auto-fixable; the payload is handed back unchanged.
+    #[fig(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<YamlValue>,
+}
+
+/// The lint rules declared in a manifest, if it has the `ValidationRules`
+/// capability.
+pub fn manifest_validation_rules(manifest: &super::PluginManifest) -> &[ValidationRule] {
+    manifest
+        .capabilities
+        .iter()
+        .find_map(|capability| match capability {
+            super::PluginCapability::ValidationRules { rules } => Some(rules.as_slice()),
+            _ => None,
+        })
+        .unwrap_or(&[])
+}
@@ -15,6 +15,6 @@ pub mod service;
 pub mod source;
 
 pub use collect::{CollectedAudience, collect_audience};
-pub use provider::{NamespaceProvider, ObjectMeta};
+pub use provider::{NamespaceProvider, ObjectMeta, PublishedComment};
 pub use service::{PublishOutcome, PublishService};
 pub use source::{Attachment, AudienceInput, SourceFile};
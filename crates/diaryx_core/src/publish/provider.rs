@@ -18,6 +18,19 @@ pub struct ObjectMeta {
     pub content_hash: Option<String>,
 }
 
+/// An approved reader comment, as publish files it beside its entry.
+#[derive(Debug, Clone)]
+pub struct PublishedComment {
+    /// `"{audience}/{source_rel_path}"` of the page it was left on.
+    pub source_key: String,
+    /// The name the reader signed with.
+    pub author_name: String,
+    /// The comment text, as the reader wrote it.
+    pub body: String,
+    /// Unix seconds.
+    pub created_at: i64,
+}
+
 // Server operations the publish pipeline performs within a namespace. Defined
 // twice (the core async-trait convention): `Send + Sync` on native so
 // `&dyn NamespaceProvider` crosses threads; `?Send` on wasm32 where the host
@@ -61,6 +74,11 @@ pub trait NamespaceProvider: Send + Sync {
     /// Trigger the server-side render of the namespace's stored sources into HTML
     /// (ARK Layer 3). `base_url` is forwarded for canonical/sitemap/feeds.
     async fn build_namespace(&self, ns_id: &str, base_url: Option<&str>) -> Result<(), String>;
+    /// List the namespace's approved reader comments. Providers whose server
+    /// has no comments keep the default: none.
+    async fn list_approved_comments(&self, _ns_id: &str) -> Result<Vec<PublishedComment>, String> {
+        Ok(Vec::new())
+    }
 }
 
 /// Server operations the publish pipeline performs within a namespace.
@@ -101,4 +119,9 @@ pub trait NamespaceProvider {
     /// Trigger the server-side render of the namespace's stored sources into HTML
     /// (ARK Layer 3). `base_url` is forwarded for canonical/sitemap/feeds.
     async fn build_namespace(&self, ns_id: &str, base_url: Option<&str>) -> Result<(), String>;
+    /// List the namespace's approved reader comments. Providers whose server
+    /// has no comments keep the default: none.
+    async fn list_approved_comments(&self, _ns_id: &str) -> Result<Vec<PublishedComment>, String> {
+        Ok(Vec::new())
+    }
 }
@@ -5,13 +5,13 @@
 //! `compute_publish_plan` + apply. It is transport-agnostic (works over the
 //! port) and unit-testable with a fake provider.
 
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path};
 
 use crate::fs::AsyncFileSystem;
 use crate::publish::collect::collect_audience;
 use crate::publish::plan::{self, AudiencePlan, PublishPlan};
-use crate::publish::provider::NamespaceProvider;
+use crate::publish::provider::{NamespaceProvider, PublishedComment};
 use crate::publish::source::AudienceInput;
 use crate::workspace::{Gate, Workspace};
 
@@ -23,6 +23,8 @@ pub struct PublishOutcome {
     pub deleted: usize,
     pub audiences_deleted: Vec<String>,
     pub built: bool,
+    /// Comment sidecars written into the workspace after the publish.
+    pub comment_files: usize,
 }
 
 /// Orchestrates publish against a [`NamespaceProvider`].
@@ -280,12 +282,65 @@ impl<'p> PublishService<'p> {
         FS: AsyncFileSystem + Clone,
     {
         let plan = self
-            .plan_workspace(fs, root_index_path, namespace_id)
+            .plan_workspace(fs.clone(), root_index_path, namespace_id)
             .await?;
-        let outcome = self.apply(namespace_id, &plan, base_url).await?;
+        let mut outcome = self.apply(namespace_id, &plan, base_url).await?;
+        // Best-effort: the site is already live, and the next publish retries.
+        outcome.comment_files = self
+            .pull_comments(fs, root_index_path, namespace_id)
+            .await
+            .unwrap_or(0);
         Ok((plan, outcome))
     }
 
+    /// Pull the namespace's approved reader comments into the workspace: each
+    /// commented entry gets a `{stem}.comments.md` sidecar beside it, rewritten
+    /// on every pull. Comments whose entry is no longer in the workspace are
+    /// skipped. Returns the number of sidecars written.
+    pub async fn pull_comments<FS>(
+        &self,
+        fs: FS,
+        root_index_path: &Path,
+        namespace_id: &str,
+    ) -> Result<usize, String>
+    where
+        FS: AsyncFileSystem,
+    {
+        let comments = self.provider.list_approved_comments(namespace_id).await?;
+
+        // The same entry can be published to several audiences; its comments
+        // share one sidecar.
+        let mut by_entry: BTreeMap<&str, Vec<&PublishedComment>> = BTreeMap::new();
+        for comment in &comments {
+            let Some((_audience, rel)) = comment.source_key.split_once('/') else {
+                continue;
+            };
+            if Path::new(rel)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                by_entry.entry(rel).or_default().push(comment);
+            }
+        }
+
+        let workspace_dir = root_index_path.parent().unwrap_or(root_index_path);
+        let mut written = 0;
+        for (rel, mut entry_comments) in by_entry {
+            let entry = workspace_dir.join(rel);
+            if !matches!(fs.try_exists(&entry).await, Ok(true)) {
+                continue;
+            }
+            entry_comments.sort_by_key(|c| c.created_at);
+            let sidecar = entry.with_extension("comments.md");
+            let content = comments_sidecar(&entry, &entry_comments);
+            fs.write(&sidecar, content.as_bytes())
+                .await
+                .map_err(|e| format!("failed to write {}: {e}", sidecar.display()))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
     /// Compute the publish plan for a workspace WITHOUT applying it (the
     /// preview path). Reads the file-declared audiences, collects each
     /// audience's sources, lists the namespace's current objects/audiences, and
@@ -359,6 +414,27 @@ impl<'p> PublishService<'p> {
     }
 }
 
+/// Render an entry's comment sidecar, oldest comment first. Plain markdown
+/// without frontmatter, so it never becomes a workspace entry of its own.
+fn comments_sidecar(entry: &Path, comments: &[&PublishedComment]) -> String {
+    let stem = entry
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut out = format!("# Comments on {stem}\n");
+    for comment in comments {
+        let when = chrono::DateTime::from_timestamp(comment.created_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "\n## {} — {when}\n\n{}\n",
+            comment.author_name,
+            comment.body.trim_end()
+        ));
+    }
+    out
+}
+
 /// Convert a workspace audience [`Gate`] to the server's gate value
 /// (`{ kind: "link" | "password" }`), as a [`crate::yaml::Value`].
 fn gate_to_yaml(gate: &Gate) -> crate::yaml::Value {
@@ -389,6 +465,7 @@ mod tests {
         deletes: Mutex<Vec<String>>,
         synced: Mutex<Vec<String>>,
         built: Mutex<Vec<Option<String>>>,
+        comments: Vec<PublishedComment>,
     }
 
     #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
@@ -444,6 +521,12 @@ mod tests {
             self.built.lock().unwrap().push(base_url.map(String::from));
             Ok(())
         }
+        async fn list_approved_comments(
+            &self,
+            _ns_id: &str,
+        ) -> Result<Vec<PublishedComment>, String> {
+            Ok(self.comments.clone())
+        }
     }
 
     fn public_audience(sources: Vec<SourceFile>, attachments: Vec<Attachment>) -> AudienceInput {
@@ -632,4 +715,44 @@ mod tests {
         assert!(deletes.contains(&"public/index.html".to_string()));
         assert!(deletes.contains(&"family/index.html".to_string()));
     }
+
+    #[test]
+    fn pull_comments_writes_one_sidecar_per_entry() {
+        use crate::fs::{InMemoryFileSystem, SyncToAsyncFs, block_on_test};
+        let comment = |source_key: &str, author: &str, created_at: i64| PublishedComment {
+            source_key: source_key.into(),
+            author_name: author.into(),
+            body: format!("From {author}"),
+            created_at,
+        };
+        let provider = FakeProvider {
+            comments: vec![
+                comment("friends/notes/post.md", "Ben", 1_700_000_100),
+                comment("family/notes/post.md", "May", 1_700_000_000),
+                comment("family/gone.md", "Sam", 1_700_000_000),
+                comment("family/../escape.md", "Eve", 1_700_000_000),
+            ],
+            ..Default::default()
+        };
+
+        let fs = SyncToAsyncFs::new(InMemoryFileSystem::new());
+        block_on_test(fs.write(Path::new("/ws/index.md"), b"# Home")).unwrap();
+        block_on_test(fs.write(Path::new("/ws/notes/post.md"), b"# Post")).unwrap();
+
+        let service = PublishService::new(&provider);
+        let written =
+            block_on_test(service.pull_comments(fs.clone(), Path::new("/ws/index.md"), "ns1"))
+                .unwrap();
+
+        assert_eq!(written, 1);
+        let sidecar =
+            block_on_test(fs.read_to_string(Path::new("/ws/notes/post.comments.md"))).unwrap();
+        assert_eq!(
+            sidecar,
+            "# Comments on post\n\
+             \n## May — 2023-11-14 22:13 UTC\n\nFrom May\n\
+             \n## Ben — 2023-11-14 22:15 UTC\n\nFrom Ben\n"
+        );
+        assert!(!block_on_test(fs.try_exists(Path::new("/escape.comments.md"))).unwrap());
+    }
 }
@@ -203,6 +203,16 @@ pub fn warning_detail(warning: &ValidationWarning) -> String {
             reason,
             suggested_filename
         ),
+        ValidationWarning::PluginRule {
+            plugin_id,
+            rule,
+            file,
+            message,
+            ..
+        } => match file {
+            Some(file) => format!("{}: {} ({}/{})", display(file), message, plugin_id, rule),
+            None => format!("{} ({}/{})", message, plugin_id, rule),
+        },
     }
 }
 
@@ -1250,6 +1250,9 @@ impl<FS: AsyncFileSystem> ValidationFixer<FS> {
             // These cannot be auto-fixed
             ValidationWarning::MultipleIndexes { .. } => None,
             ValidationWarning::InvalidContentsRef { .. } => None,
+            // Fixed by the plugin that raised it, through the plugin registry
+            // (see `Diaryx::execute` with `Command::FixValidationWarning`).
+            ValidationWarning::PluginRule { .. } => None,
         }
     }
 
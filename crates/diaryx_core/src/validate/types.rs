@@ -211,6 +211,21 @@ pub enum ValidationWarning {
         /// Suggested sanitized filename
         suggested_filename: String,
     },
+    /// An issue raised by a plugin-contributed lint rule
+    /// (see [`crate::plugin::validation`]).
+    PluginRule {
+        /// ID of the plugin that raised the issue
+        plugin_id: String,
+        /// ID of the rule within the plugin
+        rule: String,
+        /// The offending file, if the issue is tied to one
+        file: Option<PathBuf>,
+        /// Human-readable description of the problem
+        message: String,
+        /// Plugin-defined fix payload, handed back to the plugin when the
+        /// warning is fixed
+        fix: Option<crate::yaml::Value>,
+    },
 }
 
 /// Structured classification of why an `attachments` entry is rejected.
@@ -258,6 +273,7 @@ impl ValidationWarning {
             Self::MissingAttachmentBacklink { .. } => "Missing attachment backlink",
             Self::StaleAttachmentBacklink { .. } => "Stale attachment backlink",
             Self::NonPortableFilename { .. } => "Non-portable filename",
+            Self::PluginRule { .. } => "Plugin rule",
         }
     }
 
@@ -303,6 +319,7 @@ impl ValidationWarning {
             Self::MissingAttachmentBacklink { .. } => true,
             Self::StaleAttachmentBacklink { .. } => true,
             Self::NonPortableFilename { .. } => true,
+            Self::PluginRule { fix, .. } => fix.is_some(),
         }
     }
 
@@ -325,6 +342,7 @@ impl ValidationWarning {
             Self::MissingAttachmentBacklink { file, .. } => Some(file),
             Self::StaleAttachmentBacklink { file, .. } => Some(file),
             Self::NonPortableFilename { file, .. } => Some(file),
+            Self::PluginRule { file, .. } => file.as_deref(),
         }
     }
 
@@ -7,6 +7,8 @@ use crate::config::Config;
 use crate::error::{DiaryxError, Result};
 use crate::fs::AsyncFileSystem;
 use crate::link_parser::{self, LinkFormat};
+use crate::plugin::lockfile::{PLUGIN_LOCKFILE_NAME, PluginLockfile};
+use crate::plugin::permissions::PluginPermissions;
 use crate::yaml;
 
 use super::*;
@@ -690,6 +692,97 @@ and is intentionally kept out of the content hierarchy.\n";
         .await
     }
 
+    /// Read the permissions the workspace grants a plugin
+    /// (`plugins.<id>.permissions`). Returns `None` if none are configured.
+    pub async fn get_workspace_plugin_permissions(
+        &self,
+        root_index_path: &Path,
+        plugin_id: &str,
+    ) -> Result<Option<PluginPermissions>> {
+        let (source, _) = self.resolve_config_source(root_index_path).await?;
+        let Some(permissions) = source
+            .get("plugins")
+            .and_then(|plugins| plugins.get(plugin_id))
+            .and_then(|entry| entry.get("permissions"))
+        else {
+            return Ok(None);
+        };
+        let value = fig::Value::from(permissions);
+        Ok(Some(<PluginPermissions as fig::FromValue>::from_value(
+            &value,
+        )?))
+    }
+
+    /// Replace the permissions the workspace grants a plugin
+    /// (`plugins.<id>.permissions`), keeping the rest of its entry.
+    pub async fn set_workspace_plugin_permissions(
+        &self,
+        root_index_path: &Path,
+        plugin_id: &str,
+        permissions: &PluginPermissions,
+    ) -> Result<()> {
+        let (source, _) = self.resolve_config_source(root_index_path).await?;
+        let mut plugins = match source.get("plugins") {
+            Some(yaml::Value::Mapping(m)) => m.clone(),
+            _ => yaml::Mapping::new(),
+        };
+        let mut entry = match plugins.get(plugin_id) {
+            Some(yaml::Value::Mapping(m)) => m.clone(),
+            _ => yaml::Mapping::new(),
+        };
+        entry.insert(
+            "permissions".to_string(),
+            yaml::Value::from(fig::ToValue::to_value(permissions)),
+        );
+        plugins.insert(plugin_id.to_string(), yaml::Value::Mapping(entry));
+        self.set_workspace_config_field_value(
+            root_index_path,
+            "plugins",
+            yaml::Value::Mapping(plugins),
+        )
+        .await
+    }
+
+    /// Path of the plugin lockfile: next to the linked settings file, or where
+    /// the settings file would be created for workspaces that keep their
+    /// config inline.
+    pub async fn plugin_lockfile_path(&self, root_index_path: &Path) -> Result<PathBuf> {
+        let (_, config_path) = self.resolve_config_source(root_index_path).await?;
+        let config_path =
+            config_path.unwrap_or_else(|| self.default_config_file_path(root_index_path));
+        Ok(config_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(PLUGIN_LOCKFILE_NAME))
+    }
+
+    /// Read the workspace's plugin lockfile. A missing lockfile is empty.
+    pub async fn read_plugin_lockfile(&self, root_index_path: &Path) -> Result<PluginLockfile> {
+        let path = self.plugin_lockfile_path(root_index_path).await?;
+        match self.fs.read_to_string(&path).await {
+            Ok(json) => PluginLockfile::parse(&json),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(PluginLockfile::default()),
+            Err(e) => Err(DiaryxError::FileRead { path, source: e }),
+        }
+    }
+
+    /// Write the workspace's plugin lockfile, creating its directory.
+    pub async fn write_plugin_lockfile(
+        &self,
+        root_index_path: &Path,
+        lockfile: &PluginLockfile,
+    ) -> Result<()> {
+        let path = self.plugin_lockfile_path(root_index_path).await?;
+        if let Some(parent) = path.parent() {
+            self.fs.create_dir_all(parent).await?;
+        }
+        let json = lockfile.to_json()?;
+        self.fs
+            .write(&path, json.as_bytes())
+            .await
+            .map_err(|e| DiaryxError::FileWrite { path, source: e })
+    }
+
     /// Get the link format configuration from a workspace root index.
     pub async fn get_link_format(&self, root_index_path: &Path) -> Result<LinkFormat> {
         let config = self.get_workspace_config(root_index_path).await?;
@@ -1815,3 +1815,90 @@ fn test_migrate_sweeps_lingering_plugins_from_already_linked_root() {
     let again = block_on_test(ws.migrate_workspace_config_to_file(Path::new("README.md"))).unwrap();
     assert!(!again, "second sweep should be a no-op");
 }
+
+#[test]
+fn test_set_workspace_plugin_permissions_keeps_config() {
+    use crate::plugin::permissions::{PermissionRule, PluginPermissions};
+
+    let fs = InMemoryFileSystem::new();
+    fs.write(
+        Path::new("README.md"),
+        "---\ntitle: Root\ncontents: []\n---\n".as_bytes(),
+    )
+    .unwrap();
+    let ws = Workspace::new(SyncToAsyncFs::new(fs));
+    let root = Path::new("README.md");
+
+    let plugins_json = r#"{"diaryx.pandoc":{"config":{"format":"pdf"}}}"#;
+    block_on_test(ws.set_workspace_config_field(root, "plugins", plugins_json)).unwrap();
+
+    let permissions = PluginPermissions {
+        execute_commands: Some(PermissionRule {
+            include: vec!["diaryx.templating:RenderBody".into()],
+            exclude: vec![],
+            quota_bytes: None,
+        }),
+        ..Default::default()
+    };
+    block_on_test(ws.set_workspace_plugin_permissions(root, "diaryx.pandoc", &permissions))
+        .unwrap();
+
+    let granted = block_on_test(ws.get_workspace_plugin_permissions(root, "diaryx.pandoc"))
+        .unwrap()
+        .expect("permissions configured");
+    assert_eq!(granted, permissions);
+    let config = block_on_test(ws.get_workspace_plugin_config(root, "diaryx.pandoc")).unwrap();
+    assert!(config.is_some(), "config entry should be preserved");
+}
+
+#[test]
+fn test_plugin_lockfile_lives_next_to_settings_file() {
+    use crate::plugin::lockfile::{LockedPlugin, PluginLockfile};
+
+    let fs = InMemoryFileSystem::new();
+    fs.write(
+        Path::new("README.md"),
+        "---\ntitle: Root\ncontents: []\n---\n".as_bytes(),
+    )
+    .unwrap();
+    let ws = Workspace::new(SyncToAsyncFs::new(fs));
+    let root = Path::new("README.md");
+
+    let plugins_json = r#"{"diaryx.sync":{"permissions":{"http_requests":{"include":["app.diaryx.org"],"exclude":[]}}}}"#;
+    block_on_test(ws.set_workspace_config_field(root, "plugins", plugins_json)).unwrap();
+
+    // No lockfile yet → empty.
+    let empty = block_on_test(ws.read_plugin_lockfile(root)).unwrap();
+    assert!(empty.plugins.is_empty());
+
+    let granted = block_on_test(ws.get_workspace_plugin_permissions(root, "diaryx.sync"))
+        .unwrap()
+        .expect("permissions configured");
+    let mut lockfile = PluginLockfile::default();
+    lockfile.lock(
+        "diaryx.sync",
+        LockedPlugin {
+            version: "1.2.3".into(),
+            url: "https://example.com/sync.wasm".into(),
+            sha256: "ab12".into(),
+            size: 42,
+            publisher_key: None,
+            signature: None,
+            permissions: granted.clone(),
+        },
+    );
+    block_on_test(ws.write_plugin_lockfile(root, &lockfile)).unwrap();
+
+    assert_eq!(
+        block_on_test(ws.plugin_lockfile_path(root)).unwrap(),
+        Path::new("Meta/plugins.lock.json")
+    );
+    let read = block_on_test(ws.read_plugin_lockfile(root)).unwrap();
+    assert_eq!(read, lockfile);
+    assert!(
+        read.get("diaryx.sync")
+            .unwrap()
+            .drift("1.2.3", "ab12", Some(&granted))
+            .is_empty()
+    );
+}
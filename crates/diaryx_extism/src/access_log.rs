@@ -0,0 +1,486 @@
+//! Per-plugin access ledger.
+//!
+//! The permission checker decides whether a plugin may read a file or call a
+//! domain, but nothing else remembers what it actually did. When a workspace
+//! sets `plugins.<id>.audit: true`, [`HostContext`](crate::HostContext)
+//! appends every permission check the plugin triggers — permission type,
+//! target, decision and timestamp — to a ledger in host storage under
+//! [`AccessLog::storage_key`]. That key sits outside the plugin's own
+//! `<plugin_id>:` keyspace, so guests can't reach it and it doesn't count
+//! against their storage.
+//!
+//! Checks are buffered in an [`AccessLogWriter`] and written in batches, so a
+//! plugin making thousands of host calls doesn't rewrite the ledger on each.
+//!
+//! [`AccessLog::report`] summarizes the ledger and flags granted permissions
+//! (and individual `include` scopes) the plugin has never used, so they can
+//! be tightened.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use diaryx_core::plugin::permissions::{
+    PermissionCheck, PermissionRule, PermissionType, PluginPermissions, check_file_permission,
+    check_http_permission, get_permission_rule,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::host_fns::PluginStorage;
+
+/// Prefix of plugin storage keys reserved for the host. Guest storage calls
+/// with a key under this prefix are denied.
+pub const RESERVED_STORAGE_PREFIX: &str = "__diaryx/";
+
+/// Prefix of the host storage keys holding access ledgers, one per plugin.
+pub const ACCESS_LOG_STORAGE_KEY: &str = "__diaryx/access-log";
+
+/// Maximum number of records kept per plugin; the oldest are dropped first.
+pub const MAX_ACCESS_LOG_RECORDS: usize = 2000;
+
+/// Pending records per plugin that trigger a write of its ledger.
+pub const ACCESS_LOG_FLUSH_RECORDS: usize = 64;
+
+/// Longest time a record waits in an [`AccessLogWriter`] before the next
+/// check writes it out.
+pub const ACCESS_LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// One permission check made on behalf of a plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessRecord {
+    /// RFC 3339 UTC timestamp.
+    pub timestamp: String,
+    /// Permission key (`read_files`, `http_requests`, ...).
+    pub permission: String,
+    /// Path, URL, command or storage key the plugin asked for.
+    pub target: String,
+    /// Whether the check was allowed.
+    pub allowed: bool,
+}
+
+/// The stored ledger of a plugin's permission checks, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessLog {
+    /// Recorded checks.
+    pub records: Vec<AccessRecord>,
+}
+
+impl AccessLog {
+    /// Host storage key of a plugin's ledger. Guest storage keys are all
+    /// `<plugin_id>:<key>`, so this one is never in a plugin's keyspace.
+    pub fn storage_key(plugin_id: &str) -> String {
+        format!("{ACCESS_LOG_STORAGE_KEY}/{plugin_id}")
+    }
+
+    /// Load a plugin's ledger. A missing or unreadable ledger is empty.
+    pub fn load(storage: &dyn PluginStorage, plugin_id: &str) -> Self {
+        storage
+            .get(&Self::storage_key(plugin_id))
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist a plugin's ledger.
+    pub fn save(&self, storage: &dyn PluginStorage, plugin_id: &str) {
+        match serde_json::to_vec(self) {
+            Ok(bytes) => storage.set(&Self::storage_key(plugin_id), &bytes),
+            Err(e) => log::warn!("Failed to serialize access log for {plugin_id}: {e}"),
+        }
+    }
+
+    /// Delete a plugin's ledger.
+    pub fn clear(storage: &dyn PluginStorage, plugin_id: &str) {
+        storage.delete(&Self::storage_key(plugin_id));
+    }
+
+    /// Append records to a plugin's stored ledger in one load and save.
+    pub fn append(
+        storage: &dyn PluginStorage,
+        plugin_id: &str,
+        records: impl IntoIterator<Item = AccessRecord>,
+    ) {
+        let mut log = Self::load(storage, plugin_id);
+        for record in records {
+            log.push(record);
+        }
+        log.save(storage, plugin_id);
+    }
+
+    /// Append a record, dropping the oldest ones past
+    /// [`MAX_ACCESS_LOG_RECORDS`].
+    pub fn push(&mut self, record: AccessRecord) {
+        self.records.push(record);
+        if self.records.len() > MAX_ACCESS_LOG_RECORDS {
+            let excess = self.records.len() - MAX_ACCESS_LOG_RECORDS;
+            self.records.drain(..excess);
+        }
+    }
+
+    /// Summarize the ledger. `granted` is the plugin's current
+    /// `plugins.<id>.permissions`, used to find grants that were never used.
+    pub fn report(&self, plugin_id: &str, granted: Option<&PluginPermissions>) -> AccessReport {
+        let mut usage: BTreeMap<String, PermissionUsage> = BTreeMap::new();
+        for record in &self.records {
+            let entry = usage
+                .entry(record.permission.clone())
+                .or_insert_with(|| PermissionUsage {
+                    permission: record.permission.clone(),
+                    ..Default::default()
+                });
+            if record.allowed {
+                entry.allowed += 1;
+                *entry.targets.entry(record.target.clone()).or_default() += 1;
+            } else {
+                entry.denied += 1;
+            }
+        }
+
+        let mut unused = Vec::new();
+        if let Some(granted) = granted {
+            for permission_type in PermissionType::ALL {
+                let Some(rule) = get_permission_rule(granted, permission_type) else {
+                    continue;
+                };
+                if rule.include.is_empty() {
+                    continue;
+                }
+                let used: Vec<&str> = usage
+                    .get(permission_type.key())
+                    .map(|u| u.targets.keys().map(String::as_str).collect())
+                    .unwrap_or_default();
+                if used.is_empty() {
+                    unused.push(UnusedGrant {
+                        permission: permission_type.key().to_string(),
+                        scope: None,
+                    });
+                    continue;
+                }
+                for scope in &rule.include {
+                    if scope.trim().eq_ignore_ascii_case("all") {
+                        continue;
+                    }
+                    if !used
+                        .iter()
+                        .any(|target| scope_matches(permission_type, scope, target))
+                    {
+                        unused.push(UnusedGrant {
+                            permission: permission_type.key().to_string(),
+                            scope: Some(scope.clone()),
+                        });
+                    }
+                }
+            }
+        }
+
+        AccessReport {
+            plugin_id: plugin_id.to_string(),
+            records: self.records.len(),
+            first_timestamp: self.records.first().map(|r| r.timestamp.clone()),
+            last_timestamp: self.records.last().map(|r| r.timestamp.clone()),
+            permissions: usage.into_values().collect(),
+            unused,
+        }
+    }
+}
+
+/// Buffers access records in memory and appends them to the stored ledgers
+/// in batches.
+///
+/// A plugin's pending records are written once [`ACCESS_LOG_FLUSH_RECORDS`]
+/// have built up or [`ACCESS_LOG_FLUSH_INTERVAL`] has passed since its last
+/// write, on [`flush`](Self::flush), and when the writer is dropped.
+pub struct AccessLogWriter {
+    storage: Arc<dyn PluginStorage>,
+    pending: Mutex<BTreeMap<String, PendingRecords>>,
+}
+
+struct PendingRecords {
+    records: Vec<AccessRecord>,
+    since: Instant,
+}
+
+impl AccessLogWriter {
+    /// Create a writer over the host storage the ledgers live in.
+    pub fn new(storage: Arc<dyn PluginStorage>) -> Self {
+        Self {
+            storage,
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record one permission check made on behalf of `plugin_id`.
+    pub fn record(
+        &self,
+        plugin_id: &str,
+        permission_type: PermissionType,
+        target: &str,
+        allowed: bool,
+    ) {
+        let record = AccessRecord {
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            permission: permission_type.key().to_string(),
+            target: target.to_string(),
+            allowed,
+        };
+        let due = {
+            let Ok(mut pending) = self.pending.lock() else {
+                return;
+            };
+            let entry = pending
+                .entry(plugin_id.to_string())
+                .or_insert_with(|| PendingRecords {
+                    records: Vec::new(),
+                    since: Instant::now(),
+                });
+            entry.records.push(record);
+            if entry.records.len() >= ACCESS_LOG_FLUSH_RECORDS
+                || entry.since.elapsed() >= ACCESS_LOG_FLUSH_INTERVAL
+            {
+                pending.remove(plugin_id).map(|entry| entry.records)
+            } else {
+                None
+            }
+        };
+        if let Some(records) = due {
+            AccessLog::append(self.storage.as_ref(), plugin_id, records);
+        }
+    }
+
+    /// Write every plugin's pending records to storage.
+    pub fn flush(&self) {
+        let pending = match self.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return,
+        };
+        for (plugin_id, entry) in pending {
+            AccessLog::append(self.storage.as_ref(), &plugin_id, entry.records);
+        }
+    }
+}
+
+impl Drop for AccessLogWriter {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Whether an allowed `target` falls under a single `include` scope.
+fn scope_matches(permission_type: PermissionType, scope: &str, target: &str) -> bool {
+    let rule = PermissionRule {
+        include: vec![scope.to_string()],
+        ..Default::default()
+    };
+    let check = match permission_type {
+        PermissionType::ReadFiles
+        | PermissionType::EditFiles
+        | PermissionType::CreateFiles
+        | PermissionType::DeleteFiles
+        | PermissionType::MoveFiles => check_file_permission(&rule, target),
+        PermissionType::HttpRequests => check_http_permission(&rule, target),
+        PermissionType::ExecuteCommands | PermissionType::PluginStorage => {
+            return scope.trim() == target;
+        }
+    };
+    check == PermissionCheck::Allowed
+}
+
+/// Summary of a plugin's access ledger.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AccessReport {
+    /// Plugin the ledger belongs to.
+    pub plugin_id: String,
+    /// Number of recorded checks.
+    pub records: usize,
+    /// Timestamp of the oldest record.
+    pub first_timestamp: Option<String>,
+    /// Timestamp of the newest record.
+    pub last_timestamp: Option<String>,
+    /// Per-permission usage, sorted by permission key.
+    pub permissions: Vec<PermissionUsage>,
+    /// Granted permissions or scopes with no allowed access in the ledger.
+    pub unused: Vec<UnusedGrant>,
+}
+
+/// How a plugin used one permission type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PermissionUsage {
+    /// Permission key.
+    pub permission: String,
+    /// Allowed checks.
+    pub allowed: u64,
+    /// Denied checks.
+    pub denied: u64,
+    /// Allowed targets and how often each was accessed.
+    pub targets: BTreeMap<String, u64>,
+}
+
+/// A granted permission the plugin never exercised.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnusedGrant {
+    /// Permission key.
+    pub permission: String,
+    /// The unused `include` scope, or `None` if the whole permission is unused.
+    pub scope: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStorage(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl PluginStorage for MemoryStorage {
+        fn get(&self, key: &str) -> Option<Vec<u8>> {
+            self.0.lock().unwrap().get(key).cloned()
+        }
+
+        fn set(&self, key: &str, data: &[u8]) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), data.to_vec());
+        }
+
+        fn delete(&self, key: &str) {
+            self.0.lock().unwrap().remove(key);
+        }
+    }
+
+    fn rule(include: &[&str]) -> Option<PermissionRule> {
+        Some(PermissionRule {
+            include: include.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn records_persist_outside_the_plugin_keyspace() {
+        let storage = Arc::new(MemoryStorage::default());
+        let writer = AccessLogWriter::new(storage.clone());
+        writer.record("diaryx.sync", PermissionType::ReadFiles, "notes/a.md", true);
+        writer.record(
+            "diaryx.sync",
+            PermissionType::HttpRequests,
+            "https://evil.example/x",
+            false,
+        );
+        writer.flush();
+
+        let log = AccessLog::load(storage.as_ref(), "diaryx.sync");
+        assert_eq!(log.records.len(), 2);
+        assert_eq!(log.records[0].permission, "read_files");
+        assert!(!log.records[1].allowed);
+        assert!(
+            AccessLog::load(storage.as_ref(), "diaryx.other")
+                .records
+                .is_empty()
+        );
+        assert!(
+            storage
+                .0
+                .lock()
+                .unwrap()
+                .keys()
+                .all(|key| !key.starts_with("diaryx.sync:"))
+        );
+
+        AccessLog::clear(storage.as_ref(), "diaryx.sync");
+        assert!(
+            AccessLog::load(storage.as_ref(), "diaryx.sync")
+                .records
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn writer_batches_records() {
+        let storage = Arc::new(MemoryStorage::default());
+        let writer = AccessLogWriter::new(storage.clone());
+        for _ in 0..ACCESS_LOG_FLUSH_RECORDS - 1 {
+            writer.record("diaryx.sync", PermissionType::ReadFiles, "a.md", true);
+        }
+        assert!(
+            AccessLog::load(storage.as_ref(), "diaryx.sync")
+                .records
+                .is_empty()
+        );
+
+        writer.record("diaryx.sync", PermissionType::ReadFiles, "a.md", true);
+        assert_eq!(
+            AccessLog::load(storage.as_ref(), "diaryx.sync")
+                .records
+                .len(),
+            ACCESS_LOG_FLUSH_RECORDS
+        );
+
+        writer.record("diaryx.sync", PermissionType::ReadFiles, "b.md", true);
+        drop(writer);
+        let log = AccessLog::load(storage.as_ref(), "diaryx.sync");
+        assert_eq!(log.records.len(), ACCESS_LOG_FLUSH_RECORDS + 1);
+        assert_eq!(log.records.last().unwrap().target, "b.md");
+    }
+
+    #[test]
+    fn ledger_is_capped() {
+        let mut log = AccessLog::default();
+        for i in 0..MAX_ACCESS_LOG_RECORDS + 5 {
+            log.push(AccessRecord {
+                timestamp: i.to_string(),
+                permission: "read_files".into(),
+                target: "a.md".into(),
+                allowed: true,
+            });
+        }
+        assert_eq!(log.records.len(), MAX_ACCESS_LOG_RECORDS);
+        assert_eq!(log.records[0].timestamp, "5");
+    }
+
+    #[test]
+    fn report_flags_unused_grants_and_scopes() {
+        let mut log = AccessLog::default();
+        for (permission, target, allowed) in [
+            ("read_files", "journal/2026-01-01.md", true),
+            ("read_files", "journal/2026-01-01.md", true),
+            ("http_requests", "https://api.example.com/v1", true),
+            ("http_requests", "https://other.example.org/", false),
+        ] {
+            log.push(AccessRecord {
+                timestamp: String::new(),
+                permission: permission.into(),
+                target: target.into(),
+                allowed,
+            });
+        }
+        let granted = PluginPermissions {
+            read_files: rule(&["[Journal](/journal/index.md)", "[Drafts](/drafts/index.md)"]),
+            http_requests: rule(&["api.example.com"]),
+            delete_files: rule(&["all"]),
+            ..Default::default()
+        };
+
+        let report = log.report("diaryx.sync", Some(&granted));
+        assert_eq!(report.records, 4);
+        let http = &report.permissions[0];
+        assert_eq!(
+            (http.permission.as_str(), http.allowed, http.denied),
+            ("http_requests", 1, 1)
+        );
+        assert_eq!(report.permissions[1].targets["journal/2026-01-01.md"], 2);
+        assert_eq!(
+            report.unused,
+            vec![
+                UnusedGrant {
+                    permission: "read_files".into(),
+                    scope: Some("[Drafts](/drafts/index.md)".into()),
+                },
+                UnusedGrant {
+                    permission: "delete_files".into(),
+                    scope: None,
+                },
+            ]
+        );
+    }
+}
@@ -5,19 +5,33 @@
 //! calling the guest's exported functions with JSON payloads.
 
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use serde_json::Value as JsonValue;
 
+use diaryx_core::plugin::limits::{
+    LimitViolations, MAX_LIMIT_VIOLATIONS, ResolvedResourceLimits, WASM_PAGE_BYTES,
+};
+use diaryx_core::plugin::render_hooks::RenderHtmlRequest;
+use diaryx_core::plugin::schedule::PluginSchedule;
+use diaryx_core::plugin::validation::{
+    PluginValidationIssue, PluginValidationRequest, ValidationRule,
+};
 use diaryx_core::plugin::{
     CliCommand, ConfigReconcile, FileCreatedEvent, FileDeletedEvent, FileMovedEvent, FilePlugin,
-    FileSavedEvent, Plugin, PluginCapability, PluginContext, PluginError, PluginId, PluginManifest,
-    UiContribution, WorkspaceChangedEvent, WorkspaceClosedEvent, WorkspaceCommittedEvent,
-    WorkspaceOpenedEvent, WorkspacePlugin,
+    FileSavedEvent, Plugin, PluginCapability, PluginContext, PluginError, PluginHealth, PluginId,
+    PluginManifest, ScheduledEvent, UiContribution, WorkspaceChangedEvent, WorkspaceClosedEvent,
+    WorkspaceCommittedEvent, WorkspaceOpenedEvent, WorkspacePlugin,
 };
 
-use crate::protocol::{CommandRequest, CommandResponse, GuestEvent, GuestManifest};
+use crate::access_log::AccessLogWriter;
+use crate::host_fns::clear_plugin_operation_cancellation;
+use crate::protocol::{
+    CommandRequest, CommandResponse, GuestEvent, GuestManifest, GuestValidationIssue,
+    RenderHtmlInput, RenderHtmlOutput, ValidationFixResponse, ValidationRequest,
+    ValidationResponse,
+};
 
 /// Wraps an `extism::Plugin` and implements the diaryx_core plugin traits.
 ///
@@ -32,6 +46,12 @@ pub struct ExtismPluginAdapter {
     config: Mutex<JsonValue>,
     /// Path to the config file on disk.
     config_path: PathBuf,
+    /// Resource limits the plugin was built under, for error messages.
+    limits: Option<ResolvedResourceLimits>,
+    /// Calls the runtime aborted for overrunning `limits`.
+    violations: Mutex<LimitViolations>,
+    /// Ledger the guest's host calls are recorded to, flushed after each call.
+    access_log: Option<Arc<AccessLogWriter>>,
 }
 
 // SAFETY: extism::Plugin is !Send because it contains raw pointers to the WASM
@@ -58,33 +78,117 @@ impl ExtismPluginAdapter {
             manifest,
             config: Mutex::new(config),
             config_path,
+            limits: None,
+            violations: Mutex::new(LimitViolations::default()),
+            access_log: None,
+        }
+    }
+
+    /// Record the resource limits the underlying Extism plugin was built
+    /// under, so aborted calls can name the limit they hit.
+    pub fn with_limits(mut self, limits: ResolvedResourceLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Flush `access_log` at the end of every guest call, so a host that
+    /// keeps the plugin loaded for its whole lifetime doesn't hold the
+    /// ledger in memory until it exits.
+    pub fn with_access_log(mut self, access_log: Arc<AccessLogWriter>) -> Self {
+        self.access_log = Some(access_log);
+        self
+    }
+
+    /// Write any access records still buffered for this plugin's host.
+    pub fn flush_access_log(&self) {
+        if let Some(access_log) = &self.access_log {
+            access_log.flush();
+        }
+    }
+
+    /// Refuse calls once the plugin has overrun its limits too often.
+    fn ensure_within_violation_budget(&self) -> Result<(), PluginError> {
+        match self.violations.lock() {
+            Ok(violations) if violations.exhausted() => {
+                Err(PluginError::ResourceLimitExceeded(format!(
+                    "plugin {} disabled after {} aborted calls",
+                    self.manifest.id,
+                    violations.count()
+                )))
+            }
+            _ => Ok(()),
         }
     }
 
+    /// Translate an Extism call error, recognising calls the runtime aborted
+    /// for overrunning the plugin's limits and counting them against its
+    /// health.
+    fn call_error(&self, func: &str, e: extism::Error) -> PluginError {
+        let err = match e.root_cause().to_string().as_str() {
+            "timeout" => PluginError::Timeout(match self.limits {
+                Some(limits) => format!("`{func}` exceeded {}ms", limits.timeout_ms),
+                None => format!("`{func}` exceeded its time limit"),
+            }),
+            "oom" => PluginError::ResourceLimitExceeded(match self.limits {
+                Some(limits) => format!(
+                    "`{func}` exceeded {} memory pages ({} MiB)",
+                    limits.max_memory_pages,
+                    u64::from(limits.max_memory_pages) * WASM_PAGE_BYTES / (1024 * 1024)
+                ),
+                None => format!("`{func}` exceeded its memory limit"),
+            }),
+            "plugin ran out of fuel" => PluginError::ResourceLimitExceeded(
+                match self.limits.and_then(|limits| limits.fuel) {
+                    Some(fuel) => format!("`{func}` used all {fuel} units of fuel"),
+                    None => format!("`{func}` ran out of fuel"),
+                },
+            ),
+            _ => return PluginError::Other(format!("Extism call `{func}` failed: {e}")),
+        };
+        if let Ok(mut violations) = self.violations.lock()
+            && violations.record(&err)
+        {
+            log::warn!(
+                "Extism plugin {}: {err} ({} of {MAX_LIMIT_VIOLATIONS} allowed)",
+                self.manifest.id,
+                violations.count()
+            );
+        }
+        err
+    }
+
     /// Call a guest-exported function with a JSON input, returning the output string.
     pub fn call_guest(&self, func: &str, input: &str) -> Result<String, PluginError> {
+        self.ensure_within_violation_budget()?;
         let mut plugin = self
             .inner
             .lock()
             .map_err(|e| PluginError::Other(format!("Failed to lock extism plugin: {e}")))?;
         let output = plugin
             .call::<&str, &[u8]>(func, input)
-            .map_err(|e| PluginError::Other(format!("Extism call `{func}` failed: {e}")))?;
-        Ok(String::from_utf8_lossy(output).into_owned())
+            .map(|output| String::from_utf8_lossy(output).into_owned())
+            .map_err(|e| self.call_error(func, e));
+        drop(plugin);
+        self.flush_access_log();
+        output
     }
 
     /// Call a guest-exported function with binary input, returning raw bytes.
     ///
     /// Used for hot-path binary exports (sync messages, CRDT updates).
     pub fn call_guest_binary(&self, func: &str, input: &[u8]) -> Result<Vec<u8>, PluginError> {
+        self.ensure_within_violation_budget()?;
         let mut plugin = self
             .inner
             .lock()
             .map_err(|e| PluginError::Other(format!("Failed to lock extism plugin: {e}")))?;
         let output = plugin
             .call::<&[u8], &[u8]>(func, input)
-            .map_err(|e| PluginError::Other(format!("Extism call `{func}` failed: {e}")))?;
-        Ok(output.to_vec())
+            .map(<[u8]>::to_vec)
+            .map_err(|e| self.call_error(func, e));
+        drop(plugin);
+        self.flush_access_log();
+        output
     }
 
     /// Call a guest function, ignoring the output. Logs errors but doesn't propagate.
@@ -189,6 +293,13 @@ impl Plugin for ExtismPluginAdapter {
         let _ = self.call_guest("shutdown", "{}");
         Ok(())
     }
+
+    fn health(&self) -> Option<PluginHealth> {
+        self.violations
+            .lock()
+            .ok()
+            .map(|violations| violations.health())
+    }
 }
 
 // ============================================================================
@@ -234,6 +345,117 @@ impl WorkspacePlugin for ExtismPluginAdapter {
         });
     }
 
+    async fn on_scheduled(&self, event: &ScheduledEvent) {
+        // A flag left over from cancelling an earlier run must not stop this one.
+        let plugin_id = &self.manifest.id.0;
+        clear_plugin_operation_cancellation(plugin_id, &event.cancel_token);
+        self.send_event(&GuestEvent {
+            event_type: "scheduled".into(),
+            payload: serde_json::json!({
+                "schedule_id": event.schedule_id,
+                "scheduled_at": event.scheduled_at,
+                "missed": event.missed,
+                "cancel_token": event.cancel_token,
+            }),
+        });
+        clear_plugin_operation_cancellation(plugin_id, &event.cancel_token);
+    }
+
+    async fn validate(
+        &self,
+        request: &PluginValidationRequest,
+    ) -> Result<Vec<PluginValidationIssue>, PluginError> {
+        let input = serde_json::to_string(&ValidationRequest {
+            workspace_root: request.workspace_root.to_string_lossy().into_owned(),
+            root_index: request.root_index.clone(),
+            file: request.file.clone(),
+        })
+        .map_err(|e| PluginError::Other(format!("Failed to serialize validation request: {e}")))?;
+        let output = self.call_guest("validate", &input)?;
+        let response: ValidationResponse = serde_json::from_str(&output)
+            .map_err(|e| PluginError::Other(format!("Failed to parse validation response: {e}")))?;
+        Ok(response
+            .issues
+            .into_iter()
+            .map(|issue| PluginValidationIssue {
+                rule: issue.rule,
+                file: issue.file,
+                message: issue.message,
+                fix: issue.fix.map(Into::into),
+            })
+            .collect())
+    }
+
+    async fn fix_validation_issue(
+        &self,
+        issue: &PluginValidationIssue,
+    ) -> Option<Result<String, PluginError>> {
+        issue.fix.as_ref()?;
+        let input = match serde_json::to_string(&GuestValidationIssue {
+            rule: issue.rule.clone(),
+            file: issue.file.clone(),
+            message: issue.message.clone(),
+            fix: issue.fix.clone().map(Into::into),
+        }) {
+            Ok(json) => json,
+            Err(e) => {
+                return Some(Err(PluginError::Other(format!(
+                    "Failed to serialize validation issue: {e}"
+                ))));
+            }
+        };
+        let result = self
+            .call_guest("fix_validation_issue", &input)
+            .and_then(|output| {
+                serde_json::from_str::<ValidationFixResponse>(&output).map_err(|e| {
+                    PluginError::Other(format!("Failed to parse validation fix response: {e}"))
+                })
+            })
+            .and_then(|response| {
+                if response.success {
+                    Ok(response.message)
+                } else {
+                    Err(PluginError::CommandError(response.message))
+                }
+            });
+        Some(result)
+    }
+
+    fn render_html(&self, request: &RenderHtmlRequest) -> Option<Result<String, PluginError>> {
+        let declares = self
+            .manifest
+            .capabilities
+            .iter()
+            .any(|c| matches!(c, PluginCapability::RenderHtml));
+        if !declares {
+            return None;
+        }
+        let input = match serde_json::to_string(&RenderHtmlInput {
+            extension_id: request.extension_id.clone(),
+            source: request.source.clone(),
+            display_mode: request.display_mode,
+        }) {
+            Ok(json) => json,
+            Err(e) => {
+                return Some(Err(PluginError::Other(format!(
+                    "Failed to serialize render request: {e}"
+                ))));
+            }
+        };
+        let output = match self.call_guest("render_html", &input).and_then(|output| {
+            serde_json::from_str::<RenderHtmlOutput>(&output)
+                .map_err(|e| PluginError::Other(format!("Failed to parse render output: {e}")))
+        }) {
+            Ok(output) => output,
+            Err(e) => return Some(Err(e)),
+        };
+        match (output.html, output.error) {
+            (Some(html), _) => Some(Ok(html)),
+            (None, Some(error)) => Some(Err(PluginError::CommandError(error))),
+            (None, None) => None,
+        }
+    }
+
     async fn handle_command(
         &self,
         cmd: &str,
@@ -384,6 +606,32 @@ fn convert_guest_manifest(guest: &GuestManifest) -> PluginManifest {
                 conversions: guest.conversions.clone(),
             }),
             "declarative_config" => Some(PluginCapability::DeclarativeConfig),
+            "scheduled" => Some(PluginCapability::Scheduled {
+                schedules: guest
+                    .schedules
+                    .iter()
+                    .map(|schedule| PluginSchedule {
+                        id: schedule.id.clone(),
+                        cron: schedule.cron.clone(),
+                        description: schedule.description.clone(),
+                    })
+                    .collect(),
+            }),
+            "validation_rules" => Some(PluginCapability::ValidationRules {
+                rules: guest
+                    .validation_rules
+                    .iter()
+                    .map(|rule| ValidationRule {
+                        id: rule.id.clone(),
+                        description: rule.description.clone(),
+                    })
+                    .collect(),
+            }),
+            "render_html" => Some(PluginCapability::RenderHtml),
+            "services" => Some(PluginCapability::Services {
+                provides: guest.provides.iter().map(Into::into).collect(),
+                requires: guest.requires.iter().map(Into::into).collect(),
+            }),
             other => {
                 log::warn!("Unknown capability: {other}");
                 None
@@ -7,12 +7,16 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use chrono::{Local, SecondsFormat};
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
 use diaryx_core::fs::AsyncFileSystem;
-use diaryx_core::plugin::permissions::PermissionType;
+use diaryx_core::plugin::limits::{PluginResourceLimits, ResourceLimitPolicy};
+use diaryx_core::plugin::lockfile::LockedPlugin;
+use diaryx_core::plugin::permissions::{PermissionType, PluginPermissions};
 use extism::{CurrentPlugin, Error as ExtismError, UserData, Val, ValType};
 
+use crate::access_log::{AccessLogWriter, RESERVED_STORAGE_PREFIX};
 use crate::permission_checker::DenyAllPermissionChecker;
+use crate::signing::PluginTrustPolicy;
 
 /// Trait for persisting plugin state (CRDT snapshots, config, etc.).
 ///
@@ -66,6 +70,46 @@ pub trait RuntimeContextProvider: Send + Sync {
     fn get_context(&self, plugin_id: &str) -> serde_json::Value;
 }
 
+/// An outgoing `host_http_request`, after permission and header checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostHttpRequest {
+    /// HTTP method (`GET`, `POST`, ...).
+    pub method: String,
+    /// Request URL.
+    pub url: String,
+    /// Request headers.
+    pub headers: std::collections::HashMap<String, String>,
+    /// Request body, if any.
+    pub body: Option<Vec<u8>>,
+    /// Timeout requested by the plugin, already clamped by the host.
+    pub timeout: Option<std::time::Duration>,
+}
+
+/// The response handed back to a plugin for a [`HostHttpRequest`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostHttpResponse {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response headers.
+    pub headers: std::collections::HashMap<String, String>,
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+/// Trait for performing the HTTP requests plugins make via `host_http_request`.
+pub trait HttpClient: Send + Sync {
+    /// Send a request. `Err` is a transport failure (no response at all);
+    /// HTTP error statuses are returned as responses.
+    fn send(&self, request: &HostHttpRequest) -> Result<HostHttpResponse, String>;
+}
+
+/// Trait for the wall clock plugins see via `host_get_now` and
+/// `host_get_timestamp`.
+pub trait HostClock: Send + Sync {
+    /// The current local time.
+    fn now(&self) -> DateTime<FixedOffset>;
+}
+
 /// Metadata for a single object in a namespace.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NamespaceObjectMeta {
@@ -621,6 +665,78 @@ impl RuntimeContextProvider for NoopRuntimeContextProvider {
     }
 }
 
+/// [`HostClock`] backed by the system clock.
+pub struct SystemClock;
+
+impl HostClock for SystemClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        Local::now().fixed_offset()
+    }
+}
+
+/// [`HttpClient`] that performs real network requests. Requires the `http`
+/// feature; without it every request fails.
+pub struct NetworkHttpClient;
+
+#[cfg(feature = "http")]
+impl HttpClient for NetworkHttpClient {
+    fn send(&self, request: &HostHttpRequest) -> Result<HostHttpResponse, String> {
+        use ureq::http::Request;
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(request.timeout)
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        let mut builder = Request::builder()
+            .method(request.method.as_str())
+            .uri(request.url.as_str());
+        for (key, value) in &request.headers {
+            builder = builder.header(key, value);
+        }
+        let response = match &request.body {
+            Some(body) => builder
+                .body(body.clone())
+                .map_err(|e| format!("invalid request: {e}"))
+                .and_then(|r| agent.run(r).map_err(|e| e.to_string())),
+            None => builder
+                .body(())
+                .map_err(|e| format!("invalid request: {e}"))
+                .and_then(|r| agent.run(r).map_err(|e| e.to_string())),
+        };
+        let mut response = response?;
+
+        let status = response.status().as_u16();
+        let mut headers = std::collections::HashMap::new();
+        for (name, value) in response.headers() {
+            if let Ok(value) = value.to_str() {
+                headers.insert(name.to_string(), value.to_string());
+            }
+        }
+        // Raise the default 10 MB body limit so plugins can download large WASM
+        // binaries (e.g. pandoc.wasm ~58 MB).
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(128 * 1024 * 1024)
+            .read_to_vec()
+            .map_err(|e| format!("read body: {e}"))?;
+        Ok(HostHttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+#[cfg(not(feature = "http"))]
+impl HttpClient for NetworkHttpClient {
+    fn send(&self, _request: &HostHttpRequest) -> Result<HostHttpResponse, String> {
+        Err("http feature not enabled".to_string())
+    }
+}
+
 /// No-op namespace provider for hosts that don't support namespace operations.
 pub struct NoopNamespaceProvider;
 
@@ -706,6 +822,40 @@ pub trait PermissionChecker: Send + Sync {
         let _ = plugin_id;
         None
     }
+
+    /// Return the workspace's resource limit overrides for a plugin
+    /// (`plugins.<id>.limits`).
+    ///
+    /// `None` means "no override". The loader still caps the result at
+    /// [`HostContext::resource_limit_policy`].
+    fn resource_limits(&self, plugin_id: &str) -> Option<PluginResourceLimits> {
+        let _ = plugin_id;
+        None
+    }
+
+    /// Return the workspace lockfile entry for a plugin, if it is locked.
+    ///
+    /// The loader compares the installed module against it and warns about
+    /// any drift.
+    fn plugin_lock(&self, plugin_id: &str) -> Option<LockedPlugin> {
+        let _ = plugin_id;
+        None
+    }
+
+    /// Return the permissions the workspace currently grants a plugin
+    /// (`plugins.<id>.permissions`), for comparison with its lockfile entry.
+    fn granted_permissions(&self, plugin_id: &str) -> Option<PluginPermissions> {
+        let _ = plugin_id;
+        None
+    }
+
+    /// Whether the workspace opted this plugin into the access ledger
+    /// (`plugins.<id>.audit`). When `true`, every permission check is
+    /// recorded in the plugin's [`AccessLog`].
+    fn access_log_enabled(&self, plugin_id: &str) -> bool {
+        let _ = plugin_id;
+        false
+    }
 }
 
 /// Context shared with host functions via Extism's `UserData` mechanism.
@@ -741,6 +891,17 @@ pub struct HostContext {
     pub plugin_command_depth: u32,
     /// Maximum storage bytes per plugin (0 = unlimited). Default: 1 MiB.
     pub storage_quota_bytes: u64,
+    /// Defaults and ceilings for the memory, time and fuel a plugin may use.
+    pub resource_limit_policy: ResourceLimitPolicy,
+    /// Signature and publisher-key checks applied to installed plugins
+    /// before they are loaded.
+    pub plugin_trust: PluginTrustPolicy,
+    /// Performs the plugin's `host_http_request` calls.
+    pub http_client: Arc<dyn HttpClient>,
+    /// Clock behind `host_get_now` and `host_get_timestamp`.
+    pub clock: Arc<dyn HostClock>,
+    /// Buffered ledger of permission checks for plugins opted into auditing.
+    pub access_log: Arc<AccessLogWriter>,
 }
 
 /// Default plugin storage quota: 1 MiB.
@@ -825,20 +986,40 @@ impl HostContext {
             namespace_provider: Arc::new(NoopNamespaceProvider),
             plugin_command_depth: 0,
             storage_quota_bytes: DEFAULT_STORAGE_QUOTA_BYTES,
+            resource_limit_policy: ResourceLimitPolicy::default(),
+            plugin_trust: PluginTrustPolicy::default(),
+            http_client: Arc::new(NetworkHttpClient),
+            clock: Arc::new(SystemClock),
+            access_log: Arc::new(AccessLogWriter::new(Arc::new(NoopStorage))),
         }
     }
 
     /// Check a permission, returning an Extism error if denied.
+    ///
+    /// Storage keys under [`RESERVED_STORAGE_PREFIX`] belong to the host and
+    /// are always denied. If the plugin is opted into auditing, the decision
+    /// is recorded in its [`AccessLog`](crate::AccessLog).
     fn check_perm(&self, perm: PermissionType, target: &str) -> Result<(), ExtismError> {
-        if let Some(checker) = &self.permission_checker {
-            checker
-                .check_permission(&self.plugin_id, perm, target)
-                .map_err(|msg| ExtismError::msg(msg))
-        } else {
-            Err(ExtismError::msg(
+        let Some(checker) = &self.permission_checker else {
+            return Err(ExtismError::msg(
                 "Permission checker is not configured for this plugin host context",
+            ));
+        };
+        let decision = if perm == PermissionType::PluginStorage
+            && target.starts_with(RESERVED_STORAGE_PREFIX)
+        {
+            Err(format!(
+                "Storage key '{target}' is reserved for the host (plugin '{}')",
+                self.plugin_id
             ))
+        } else {
+            checker.check_permission(&self.plugin_id, perm, target)
+        };
+        if !self.plugin_id.is_empty() && checker.access_log_enabled(&self.plugin_id) {
+            self.access_log
+                .record(&self.plugin_id, perm, target, decision.is_ok());
         }
+        decision.map_err(ExtismError::msg)
     }
 
     /// Validate HTTP header names and values to prevent header injection.
@@ -2148,12 +2329,15 @@ fn host_get_timestamp(
     plugin: &mut CurrentPlugin,
     _inputs: &[Val],
     outputs: &mut [Val],
-    _user_data: UserData<HostContext>,
+    user_data: UserData<HostContext>,
 ) -> Result<(), ExtismError> {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
+    let now = {
+        let ctx = user_data.get()?;
+        let ctx = ctx
+            .lock()
+            .map_err(|e| ExtismError::msg(format!("host_get_timestamp: lock: {e}")))?;
+        ctx.clock.now().timestamp_millis().max(0) as u64
+    };
 
     plugin.memory_set_val(&mut outputs[0], now.to_string().as_str())?;
     Ok(())
@@ -2164,9 +2348,15 @@ fn host_get_now(
     plugin: &mut CurrentPlugin,
     _inputs: &[Val],
     outputs: &mut [Val],
-    _user_data: UserData<HostContext>,
+    user_data: UserData<HostContext>,
 ) -> Result<(), ExtismError> {
-    let now = Local::now().to_rfc3339_opts(SecondsFormat::Secs, false);
+    let now = {
+        let ctx = user_data.get()?;
+        let ctx = ctx
+            .lock()
+            .map_err(|e| ExtismError::msg(format!("host_get_now: lock: {e}")))?;
+        ctx.clock.now().to_rfc3339_opts(SecondsFormat::Secs, false)
+    };
     plugin.memory_set_val(&mut outputs[0], now.as_str())?;
     Ok(())
 }
@@ -2577,10 +2767,10 @@ fn host_proxy_request(
 
 /// Host function: `host_http_request(input: {url, method, headers, body?, timeout_ms?}) -> {status, headers, body}`
 ///
-/// Performs an HTTP request and returns the response. Only available when
-/// the `http` feature is enabled (native builds). On WASM the browser
-/// host functions provide the equivalent via `fetch()`.
-#[cfg(feature = "http")]
+/// Performs an HTTP request through the context's [`HttpClient`] and returns
+/// the response. On native hosts that is [`NetworkHttpClient`] (which needs
+/// the `http` feature); on WASM the browser host functions provide the
+/// equivalent via `fetch()`.
 fn host_http_request(
     plugin: &mut CurrentPlugin,
     inputs: &[Val],
@@ -2588,7 +2778,6 @@ fn host_http_request(
     user_data: UserData<HostContext>,
 ) -> Result<(), ExtismError> {
     use base64::Engine as _;
-    use ureq::http::Request;
 
     let input: String = plugin.memory_get_val(&inputs[0])?;
 
@@ -2640,7 +2829,7 @@ fn host_http_request(
         return Ok(());
     }
 
-    {
+    let (client, plugin_id) = {
         let ctx = user_data.get()?;
         let ctx = ctx
             .lock()
@@ -2650,130 +2839,57 @@ fn host_http_request(
             plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
             return Ok(());
         }
-    }
+        (ctx.http_client.clone(), ctx.plugin_id.clone())
+    };
 
     const MIN_HTTP_TIMEOUT_MS: u64 = 1_000;
     const MAX_HTTP_TIMEOUT_MS: u64 = 300_000;
 
-    let timeout = parsed
-        .timeout_ms
-        .map(|value| value.clamp(MIN_HTTP_TIMEOUT_MS, MAX_HTTP_TIMEOUT_MS))
-        .map(std::time::Duration::from_millis);
-    let agent: ureq::Agent = ureq::Agent::config_builder()
-        .timeout_global(timeout)
-        .http_status_as_error(false)
-        .build()
-        .into();
-
-    let mut request_builder = Request::builder()
-        .method(parsed.method.as_str())
-        .uri(parsed.url.as_str());
-    for (key, value) in &parsed.headers {
-        request_builder = request_builder.header(key, value);
-    }
-
-    let response = if let Some(b64) = &parsed.body_base64 {
-        let bytes = match base64::engine::general_purpose::STANDARD.decode(b64) {
-            Ok(bytes) => bytes,
+    let body = match (&parsed.body_base64, parsed.body) {
+        (Some(b64), _) => match base64::engine::general_purpose::STANDARD.decode(b64) {
+            Ok(bytes) => Some(bytes),
             Err(e) => {
                 let msg = format!("host_http_request: base64 decode: {e}");
                 plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
                 return Ok(());
             }
-        };
-        match request_builder.body(bytes) {
-            Ok(request) => match agent.run(request) {
-                Ok(r) => r,
-                Err(e) => {
-                    let msg = format!("host_http_request: {e}");
-                    plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                    return Ok(());
-                }
-            },
-            Err(e) => {
-                let msg = format!("host_http_request: invalid request: {e}");
-                plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                return Ok(());
-            }
-        }
-    } else if let Some(body) = &parsed.body {
-        match request_builder.body(body.clone()) {
-            Ok(request) => match agent.run(request) {
-                Ok(r) => r,
-                Err(e) => {
-                    let msg = format!("host_http_request: {e}");
-                    plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                    return Ok(());
-                }
-            },
-            Err(e) => {
-                let msg = format!("host_http_request: invalid request: {e}");
-                plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                return Ok(());
-            }
-        }
-    } else {
-        match request_builder.body(()) {
-            Ok(request) => match agent.run(request) {
-                Ok(r) => r,
-                Err(e) => {
-                    let msg = format!("host_http_request: {e}");
-                    plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                    return Ok(());
-                }
-            },
-            Err(e) => {
-                let msg = format!("host_http_request: invalid request: {e}");
-                plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
-                return Ok(());
-            }
-        }
+        },
+        (None, body) => body.map(String::into_bytes),
+    };
+    let request = HostHttpRequest {
+        method: parsed.method,
+        url: parsed.url,
+        headers: parsed.headers,
+        body,
+        timeout: parsed
+            .timeout_ms
+            .map(|value| value.clamp(MIN_HTTP_TIMEOUT_MS, MAX_HTTP_TIMEOUT_MS))
+            .map(std::time::Duration::from_millis),
     };
 
-    let status = response.status().as_u16();
-    if status >= 400 {
-        log::warn!(
-            "host_http_request: {} {} → {} (plugin={})",
-            parsed.method,
-            parsed.url,
-            status,
-            {
-                let ctx = user_data.get().ok();
-                ctx.and_then(|c| c.lock().ok().map(|g| g.plugin_id.clone()))
-                    .unwrap_or_default()
-            },
-        );
-    }
-    let mut resp_headers = std::collections::HashMap::new();
-    for (name, value) in response.headers() {
-        if let Ok(value) = value.to_str() {
-            resp_headers.insert(name.to_string(), value.to_string());
-        }
-    }
-    let mut response = response;
-    // Raise the default 10 MB body limit so plugins can download large WASM
-    // binaries (e.g. pandoc.wasm ~58 MB).
-    let body_bytes = match response
-        .body_mut()
-        .with_config()
-        .limit(128 * 1024 * 1024)
-        .read_to_vec()
-    {
-        Ok(bytes) => bytes,
+    let response = match client.send(&request) {
+        Ok(response) => response,
         Err(e) => {
-            let msg = format!("host_http_request: read body: {e}");
+            let msg = format!("host_http_request: {e}");
             plugin.memory_set_val(&mut outputs[0], err_envelope(&msg).as_str())?;
             return Ok(());
         }
     };
-    let body = String::from_utf8_lossy(&body_bytes).to_string();
-    let body_base64 = Some(base64::engine::general_purpose::STANDARD.encode(&body_bytes));
 
+    if response.status >= 400 {
+        log::warn!(
+            "host_http_request: {} {} → {} (plugin={})",
+            request.method,
+            request.url,
+            response.status,
+            plugin_id,
+        );
+    }
     let output = HttpOutput {
-        status,
-        headers: resp_headers,
-        body,
-        body_base64,
+        status: response.status,
+        headers: response.headers,
+        body: String::from_utf8_lossy(&response.body).to_string(),
+        body_base64: Some(base64::engine::general_purpose::STANDARD.encode(&response.body)),
     };
 
     let json = serde_json::to_string(&output)
@@ -2783,23 +2899,6 @@ fn host_http_request(
     Ok(())
 }
 
-/// Stub for `host_http_request` when the `http` feature is not enabled.
-#[cfg(not(feature = "http"))]
-fn host_http_request(
-    plugin: &mut CurrentPlugin,
-    _inputs: &[Val],
-    outputs: &mut [Val],
-    _user_data: UserData<HostContext>,
-) -> Result<(), ExtismError> {
-    let error = serde_json::json!({
-        "status": 0,
-        "headers": {},
-        "body": "host_http_request: http feature not enabled"
-    });
-    plugin.memory_set_val(&mut outputs[0], error.to_string().as_str())?;
-    Ok(())
-}
-
 #[cfg(not(feature = "http"))]
 fn host_proxy_request(
     plugin: &mut CurrentPlugin,
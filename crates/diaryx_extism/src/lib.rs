@@ -67,6 +67,7 @@ pub(crate) fn platform_wasmtime_config() -> Option<wasmtime::Config> {
     None
 }
 
+pub mod access_log;
 pub mod adapter;
 pub mod binary_protocol;
 pub mod host_fns;
@@ -76,6 +77,8 @@ pub mod loader;
 pub mod permission_checker;
 pub mod plugin_fs;
 pub mod protocol;
+pub mod scheduler;
+pub mod signing;
 #[cfg(feature = "testing")]
 pub mod testing;
 #[cfg(feature = "wasi-runner")]
@@ -86,22 +89,28 @@ pub mod ws_transport;
 #[cfg(feature = "http")]
 pub use http_namespace_provider::HttpNamespaceProvider;
 
+pub use access_log::{AccessLog, AccessLogWriter, AccessReport};
 pub use adapter::ExtismPluginAdapter;
+pub use diaryx_core::plugin::limits::ResourceLimitPolicy;
 pub use host_fns::{
     BatchGetEntry, BatchGetResult, DEFAULT_STORAGE_QUOTA_BYTES, EventEmitter,
-    FilePluginSecretStore, FilePluginStorage, FileProvider, HostContext, MapFileProvider,
-    NamespaceEntry, NamespaceObjectMeta, NamespaceProvider, NoopEventEmitter, NoopFileProvider,
+    FilePluginSecretStore, FilePluginStorage, FileProvider, HostClock, HostContext,
+    HostHttpRequest, HostHttpResponse, HttpClient, MapFileProvider, NamespaceEntry,
+    NamespaceObjectMeta, NamespaceProvider, NetworkHttpClient, NoopEventEmitter, NoopFileProvider,
     NoopNamespaceProvider, NoopPluginCommandBridge, NoopRuntimeContextProvider, NoopSecretStore,
     NoopStorage, NoopWebSocketBridge, PermissionChecker, PluginCommandBridge, PluginSecretStore,
-    PluginStorage, RuntimeContextProvider, WebSocketBridge, cancel_plugin_operation,
+    PluginStorage, RuntimeContextProvider, SystemClock, WebSocketBridge, cancel_plugin_operation,
     clear_plugin_operation_cancellation, parse_multipart_batch,
 };
 pub use loader::{
     ExtismLoadError, inspect_plugin_wasm_manifest, load_plugin_from_wasm, load_plugins_from_dir,
+    lock_drift,
 };
 pub use permission_checker::{
     AllowAllPermissionChecker, DenyAllPermissionChecker, FrontmatterPermissionChecker,
 };
+pub use scheduler::{ScheduleCanceller, ScheduleRunner};
+pub use signing::{ArtifactSignature, PluginTrustPolicy, TrustError, TrustStore};
 
 #[cfg(test)]
 mod tests {
@@ -7,13 +7,22 @@
 //!     plugin.wasm      # The WASM module
 //!     manifest.json    # Optional cached manifest (skip calling guest fn)
 //!     config.json      # Plugin config (created/updated at runtime)
+//!     signature.json   # Publisher signature (see [`crate::signing`])
 //! ```
+//!
+//! Plugins found by [`load_plugins_from_dir`] must pass the host's
+//! [`PluginTrustPolicy`](crate::signing::PluginTrustPolicy) before any guest
+//! code runs.
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use diaryx_core::fs::SyncToAsyncFs;
 use diaryx_core::plugin::Plugin;
+use diaryx_core::plugin::limits::{ResolvedResourceLimits, ResourceLimitPolicy};
+use diaryx_core::plugin::lockfile::LockDrift;
+use diaryx_core::plugin::services::ServiceResolution;
 use diaryx_native::RealFileSystem;
 use extism::{Manifest as ExtismManifest, PluginBuilder, UserData, Wasm};
 use thiserror::Error;
@@ -22,6 +31,7 @@ use crate::adapter::ExtismPluginAdapter;
 use crate::host_fns::{self, HostContext};
 use crate::platform_wasmtime_config;
 use crate::protocol::{CURRENT_PROTOCOL_VERSION, GuestManifest, MIN_SUPPORTED_PROTOCOL_VERSION};
+use crate::signing::{ArtifactSignature, TrustError, sha256_hex};
 
 /// Errors that can occur during plugin loading.
 #[derive(Debug, Error)]
@@ -73,6 +83,13 @@ pub enum ExtismLoadError {
         required: String,
         running: String,
     },
+
+    /// The plugin failed signature or publisher-key verification.
+    #[error("Refusing to load plugin '{plugin_name}': {source}")]
+    Untrusted {
+        plugin_name: String,
+        source: TrustError,
+    },
 }
 
 /// Check that the guest's protocol version is within the range this host supports.
@@ -92,6 +109,31 @@ fn validate_protocol_version(
     Ok(())
 }
 
+/// Check that a verified signature was made for the plugin the guest says
+/// it is, so a signed module cannot be reused under another plugin's ID.
+fn validate_signed_identity(
+    signature: Option<&ArtifactSignature>,
+    manifest: &GuestManifest,
+    plugin_name: &str,
+) -> Result<(), ExtismLoadError> {
+    let Some(signature) = signature else {
+        return Ok(());
+    };
+    if signature.plugin_id == manifest.id && signature.version == manifest.version {
+        return Ok(());
+    }
+    Err(ExtismLoadError::Untrusted {
+        plugin_name: plugin_name.to_string(),
+        source: TrustError::InvalidSignature {
+            plugin_id: signature.plugin_id.clone(),
+            reason: format!(
+                "signature covers {}@{} but the module is {}@{}",
+                signature.plugin_id, signature.version, manifest.id, manifest.version
+            ),
+        },
+    })
+}
+
 /// Parse a `"major.minor.patch"` version string into a comparable tuple.
 fn parse_version(v: &str) -> Option<(u32, u32, u32)> {
     let mut parts = v.split('.');
@@ -121,6 +163,97 @@ fn validate_app_version(
     }
 }
 
+/// Build an Extism plugin for `wasm_path` with host functions registered and
+/// the given resource limits applied.
+///
+/// Memory is capped per instance, and the timeout and fuel budget apply to
+/// each guest call; a call that overruns them is aborted by the runtime.
+fn build_plugin(
+    wasm_path: &Path,
+    plugin_name: &str,
+    limits: &ResolvedResourceLimits,
+    user_data: UserData<HostContext>,
+) -> Result<extism::Plugin, ExtismLoadError> {
+    let extism_manifest = ExtismManifest::new([Wasm::file(wasm_path)])
+        .with_memory_max(limits.max_memory_pages)
+        .with_timeout(Duration::from_millis(limits.timeout_ms));
+    let mut builder = PluginBuilder::new(extism_manifest).with_wasi(true);
+    if let Some(fuel) = limits.fuel {
+        builder = builder.with_fuel_limit(fuel);
+    }
+    if let Some(config) = platform_wasmtime_config() {
+        builder = builder.with_wasmtime_config(config);
+    }
+    let builder = host_fns::register_host_functions(builder, user_data);
+    builder.build().map_err(|e| ExtismLoadError::PluginCreate {
+        plugin_name: plugin_name.to_string(),
+        source: e,
+    })
+}
+
+/// Resolve the limits a plugin runs under: its manifest declaration,
+/// overridden by the workspace's `plugins.<id>.limits`, capped by the host's
+/// [`ResourceLimitPolicy`].
+fn resolve_plugin_limits(
+    host_context: &HostContext,
+    manifest: &GuestManifest,
+) -> ResolvedResourceLimits {
+    let workspace = host_context
+        .permission_checker
+        .as_ref()
+        .and_then(|checker| checker.resource_limits(&manifest.id));
+    let limits = host_context
+        .resource_limit_policy
+        .resolve(manifest.resource_limits.as_ref(), workspace.as_ref());
+    log::debug!(
+        "Plugin {} resource limits: {} memory pages, {}ms timeout, fuel {:?}",
+        manifest.id,
+        limits.max_memory_pages,
+        limits.timeout_ms,
+        limits.fuel
+    );
+    limits
+}
+
+/// Compare an installed plugin (`plugin_id` at `version`, loaded from
+/// `wasm_path`) against its entry in the workspace lockfile.
+///
+/// Returns no drift when the workspace has no lockfile entry for the plugin
+/// or no permission checker to read one through. The module is only hashed
+/// when there is an entry to compare with.
+pub fn lock_drift(
+    host_context: &HostContext,
+    plugin_id: &str,
+    version: &str,
+    wasm_path: &Path,
+) -> Vec<LockDrift> {
+    let Some(checker) = host_context.permission_checker.as_ref() else {
+        return Vec::new();
+    };
+    let Some(locked) = checker.plugin_lock(plugin_id) else {
+        return Vec::new();
+    };
+    let sha256 = match std::fs::read(wasm_path) {
+        Ok(bytes) => sha256_hex(&bytes),
+        Err(e) => {
+            log::debug!("Could not hash {} for lock check: {e}", wasm_path.display());
+            return Vec::new();
+        }
+    };
+    let granted = checker.granted_permissions(plugin_id);
+    locked.drift(version, &sha256, granted.as_ref())
+}
+
+/// Log a warning for each way a plugin has drifted from the workspace lockfile.
+fn warn_on_lock_drift(host_context: &HostContext, manifest: &GuestManifest, wasm_path: &Path) {
+    for drift in lock_drift(host_context, &manifest.id, &manifest.version, wasm_path) {
+        log::warn!(
+            "Plugin {} drifted from the workspace lockfile: {drift}",
+            manifest.id
+        );
+    }
+}
+
 fn parse_guest_manifest(
     plugin: &mut extism::Plugin,
     plugin_name: &str,
@@ -149,8 +282,6 @@ pub fn inspect_plugin_wasm_manifest(wasm_path: &Path) -> Result<GuestManifest, E
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| "unknown".into());
 
-    let wasm = Wasm::file(wasm_path);
-    let extism_manifest = ExtismManifest::new([wasm]);
     // Register host imports so plugins with host_* functions can still be
     // instantiated for CI manifest inspection.
     let fs = Arc::new(SyncToAsyncFs::new(RealFileSystem));
@@ -159,15 +290,8 @@ pub fn inspect_plugin_wasm_manifest(wasm_path: &Path) -> Result<GuestManifest, E
         plugin_id_locked: false,
         ..HostContext::with_fs(fs)
     });
-    let mut builder = PluginBuilder::new(extism_manifest).with_wasi(true);
-    if let Some(config) = platform_wasmtime_config() {
-        builder = builder.with_wasmtime_config(config);
-    }
-    let builder = host_fns::register_host_functions(builder, user_data);
-    let mut plugin = builder.build().map_err(|e| ExtismLoadError::PluginCreate {
-        plugin_name: plugin_name.clone(),
-        source: e,
-    })?;
+    let limits = ResourceLimitPolicy::default().resolve(None, None);
+    let mut plugin = build_plugin(wasm_path, &plugin_name, &limits, user_data)?;
 
     let manifest = parse_guest_manifest(&mut plugin, &plugin_name)?;
 
@@ -187,10 +311,15 @@ pub fn inspect_plugin_wasm_manifest(wasm_path: &Path) -> Result<GuestManifest, E
 ///
 /// Scans `plugins_dir` for subdirectories containing a `plugin.wasm` file.
 /// For each valid plugin:
-/// 1. Creates an Extism plugin with registered host functions
-/// 2. Calls the guest's `manifest` export (or reads `manifest.json` cache)
-/// 3. Loads `config.json` if present
-/// 4. Returns an [`ExtismPluginAdapter`] ready for registration
+/// 1. Verifies its publisher signature against the host's trust policy
+/// 2. Creates an Extism plugin with registered host functions
+/// 3. Calls the guest's `manifest` export (or reads `manifest.json` cache)
+/// 4. Loads `config.json` if present
+/// 5. Returns an [`ExtismPluginAdapter`] ready for registration
+///
+/// Adapters are returned in service dependency order, providers before the
+/// plugins that require them (see [`ServiceResolution`]); unmet requirements
+/// and cycles are logged.
 ///
 /// Plugins that fail to load are logged and skipped (not fatal).
 pub fn load_plugins_from_dir(
@@ -232,15 +361,47 @@ pub fn load_plugins_from_dir(
         }
     }
 
+    order_by_services(&mut adapters);
     Ok(adapters)
 }
 
+/// Sort `adapters` so every service provider precedes its consumers, and log
+/// requirements no loaded plugin satisfies.
+fn order_by_services(adapters: &mut [ExtismPluginAdapter]) {
+    let manifests: Vec<_> = adapters.iter().map(|adapter| adapter.manifest()).collect();
+    let resolution = ServiceResolution::resolve(&manifests);
+
+    for missing in &resolution.missing {
+        if missing.requirement.optional {
+            log::info!("{missing}");
+        } else {
+            log::warn!("{missing}");
+        }
+    }
+    if !resolution.cyclic.is_empty() {
+        let cyclic: Vec<_> = resolution.cyclic.iter().map(|id| id.0.as_str()).collect();
+        log::warn!(
+            "Plugin service requirements form a cycle: {}",
+            cyclic.join(", ")
+        );
+    }
+
+    adapters.sort_by_cached_key(|adapter| {
+        let id = adapter.id();
+        resolution.order.iter().position(|ordered| *ordered == id)
+    });
+}
+
 /// Load a single WASM plugin from a file path with a given host context.
 ///
 /// This is a lower-level API for loading a specific plugin (e.g. the sync plugin)
 /// rather than scanning a directory. The caller provides the WASM file path,
 /// host context, and an optional config JSON sidecar path.
 ///
+/// No signature check is made here: callers loading an installed plugin
+/// should run [`PluginTrustPolicy::verify_installed`](crate::signing::PluginTrustPolicy::verify_installed)
+/// first.
+///
 /// # Arguments
 /// * `wasm_path` — Path to the `.wasm` file
 /// * `host_context` — Host functions context (filesystem, storage, events)
@@ -256,8 +417,6 @@ pub fn load_plugin_from_wasm(
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| "unknown".into());
 
-    let wasm = Wasm::file(wasm_path);
-    let extism_manifest = ExtismManifest::new([wasm]);
     let user_data = UserData::new(HostContext {
         fs: host_context.fs.clone(),
         storage: host_context.storage.clone(),
@@ -273,22 +432,28 @@ pub fn load_plugin_from_wasm(
         namespace_provider: host_context.namespace_provider.clone(),
         plugin_command_depth: 0,
         storage_quota_bytes: crate::host_fns::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: host_context.resource_limit_policy,
+        plugin_trust: host_context.plugin_trust.clone(),
+        http_client: host_context.http_client.clone(),
+        clock: host_context.clock.clone(),
+        access_log: host_context.access_log.clone(),
     });
 
-    let mut builder = PluginBuilder::new(extism_manifest).with_wasi(true);
-    if let Some(config) = platform_wasmtime_config() {
-        builder = builder.with_wasmtime_config(config);
-    }
-    let builder = host_fns::register_host_functions(builder, user_data.clone());
-    let mut plugin = builder.build().map_err(|e| ExtismLoadError::PluginCreate {
-        plugin_name: plugin_name.clone(),
-        source: e,
-    })?;
-
-    // Call the guest's manifest export.
-    let guest_manifest = parse_guest_manifest(&mut plugin, &plugin_name)?;
+    // Call the guest's manifest export on a probe built under the host's
+    // default limits; the plugin's own limits are only known afterwards.
+    let probe_limits = host_context.resource_limit_policy.resolve(None, None);
+    let mut probe = build_plugin(wasm_path, &plugin_name, &probe_limits, user_data.clone())?;
+    let guest_manifest = parse_guest_manifest(&mut probe, &plugin_name)?;
     validate_protocol_version(&guest_manifest, &plugin_name)?;
     validate_app_version(&guest_manifest, &plugin_name)?;
+    warn_on_lock_drift(&host_context, &guest_manifest, wasm_path);
+
+    let limits = resolve_plugin_limits(&host_context, &guest_manifest);
+    let plugin = if limits == probe_limits {
+        probe
+    } else {
+        build_plugin(wasm_path, &plugin_name, &limits, user_data.clone())?
+    };
 
     // Set the plugin ID from the guest manifest exactly once, then lock it.
     if let Ok(ctx) = user_data.get()
@@ -320,12 +485,11 @@ pub fn load_plugin_from_wasm(
         serde_json::Value::Object(Default::default())
     };
 
-    Ok(ExtismPluginAdapter::new(
-        plugin,
-        guest_manifest,
-        config,
-        cfg_path,
-    ))
+    Ok(
+        ExtismPluginAdapter::new(plugin, guest_manifest, config, cfg_path)
+            .with_limits(limits)
+            .with_access_log(host_context.access_log.clone()),
+    )
 }
 
 /// Load a single plugin from its directory.
@@ -335,8 +499,14 @@ fn load_single_plugin(
     plugin_name: &str,
     host_context: &Arc<HostContext>,
 ) -> Result<ExtismPluginAdapter, ExtismLoadError> {
-    let wasm = Wasm::file(wasm_path);
-    let extism_manifest = ExtismManifest::new([wasm]);
+    let signature = host_context
+        .plugin_trust
+        .verify_installed(plugin_dir, plugin_name)
+        .map_err(|source| ExtismLoadError::Untrusted {
+            plugin_name: plugin_name.to_string(),
+            source,
+        })?;
+
     let user_data = UserData::new(HostContext {
         fs: host_context.fs.clone(),
         storage: host_context.storage.clone(),
@@ -352,23 +522,20 @@ fn load_single_plugin(
         namespace_provider: host_context.namespace_provider.clone(),
         plugin_command_depth: 0,
         storage_quota_bytes: crate::host_fns::DEFAULT_STORAGE_QUOTA_BYTES,
+        resource_limit_policy: host_context.resource_limit_policy,
+        plugin_trust: host_context.plugin_trust.clone(),
+        http_client: host_context.http_client.clone(),
+        clock: host_context.clock.clone(),
+        access_log: host_context.access_log.clone(),
     });
 
-    let mut builder = PluginBuilder::new(extism_manifest).with_wasi(true);
-    if let Some(config) = platform_wasmtime_config() {
-        builder = builder.with_wasmtime_config(config);
-    }
-    let builder = host_fns::register_host_functions(builder, user_data.clone());
-    let mut plugin = builder.build().map_err(|e| ExtismLoadError::PluginCreate {
-        plugin_name: plugin_name.into(),
-        source: e,
-    })?;
-
     // Try to read a cached manifest.json first; fall back to calling the guest.
     // Invalidate the cache when plugin.wasm is newer than manifest.json (e.g.
-    // after an update that replaced the WASM binary).
+    // after an update that replaced the WASM binary). A signed plugin always
+    // asks the guest: the signature covers the module, not the cache, and
+    // its identity is checked against what the module itself declares.
     let manifest_path = plugin_dir.join("manifest.json");
-    let cache_is_fresh = manifest_path.exists() && {
+    let cache_is_fresh = signature.is_none() && manifest_path.exists() && {
         let wasm_mtime = std::fs::metadata(wasm_path).and_then(|m| m.modified()).ok();
         let cache_mtime = std::fs::metadata(&manifest_path)
             .and_then(|m| m.modified())
@@ -378,6 +545,8 @@ fn load_single_plugin(
             _ => false, // If we can't compare, treat the cache as stale.
         }
     };
+    let probe_limits = host_context.resource_limit_policy.resolve(None, None);
+    let mut probe = None;
     let guest_manifest = if cache_is_fresh {
         let json = std::fs::read_to_string(&manifest_path).map_err(ExtismLoadError::ReadDir)?;
         serde_json::from_str::<GuestManifest>(&json).map_err(|e| {
@@ -387,13 +556,25 @@ fn load_single_plugin(
             }
         })?
     } else {
+        // Call the guest's manifest export on a probe built under the host's
+        // default limits; the plugin's own limits are only known afterwards.
+        let mut plugin = build_plugin(wasm_path, plugin_name, &probe_limits, user_data.clone())?;
         let gm = parse_guest_manifest(&mut plugin, plugin_name)?;
         // Cache the manifest for fast discovery on next startup.
         cache_manifest(&manifest_path, &gm);
+        probe = Some(plugin);
         gm
     };
+    validate_signed_identity(signature.as_ref(), &guest_manifest, plugin_name)?;
     validate_protocol_version(&guest_manifest, plugin_name)?;
     validate_app_version(&guest_manifest, plugin_name)?;
+    warn_on_lock_drift(host_context, &guest_manifest, wasm_path);
+
+    let limits = resolve_plugin_limits(host_context, &guest_manifest);
+    let plugin = match probe {
+        Some(plugin) if limits == probe_limits => plugin,
+        _ => build_plugin(wasm_path, plugin_name, &limits, user_data.clone())?,
+    };
 
     // Set the plugin ID from the guest manifest exactly once, then lock it.
     if let Ok(ctx) = user_data.get()
@@ -413,12 +594,23 @@ fn load_single_plugin(
         serde_json::Value::Object(Default::default())
     };
 
-    Ok(ExtismPluginAdapter::new(
-        plugin,
-        guest_manifest,
-        config,
-        config_path,
-    ))
+    // Only now that the module loaded as the plugin its signature names is
+    // its publisher key worth pinning.
+    if let Some(signature) = &signature {
+        host_context
+            .plugin_trust
+            .check_publisher(signature)
+            .map_err(|source| ExtismLoadError::Untrusted {
+                plugin_name: plugin_name.to_string(),
+                source,
+            })?;
+    }
+
+    Ok(
+        ExtismPluginAdapter::new(plugin, guest_manifest, config, config_path)
+            .with_limits(limits)
+            .with_access_log(host_context.access_log.clone()),
+    )
 }
 
 /// Write the guest manifest as a JSON sidecar so the CLI can discover
@@ -5,8 +5,10 @@ use std::path::{Path, PathBuf};
 
 use diaryx_core::fs::SyncToAsyncFs;
 use diaryx_core::path_utils::{normalize_sync_path, strip_workspace_root_prefix};
+use diaryx_core::plugin::limits::PluginResourceLimits;
+use diaryx_core::plugin::lockfile::LockedPlugin;
 use diaryx_core::plugin::permissions::{
-    PermissionCheck, PermissionType, PluginConfig, check_permission,
+    PermissionCheck, PermissionType, PluginConfig, PluginPermissions, check_permission,
 };
 use diaryx_core::workspace::Workspace;
 use diaryx_native::RealFileSystem;
@@ -155,6 +157,43 @@ impl PermissionChecker for FrontmatterPermissionChecker {
             .as_ref()?
             .quota_bytes
     }
+
+    fn resource_limits(&self, plugin_id: &str) -> Option<PluginResourceLimits> {
+        self.load_plugins_config()
+            .ok()?
+            .get(plugin_id)?
+            .limits
+            .clone()
+    }
+
+    fn plugin_lock(&self, plugin_id: &str) -> Option<LockedPlugin> {
+        let root_path = self.root_index_path.as_ref()?;
+        let workspace = Workspace::new(SyncToAsyncFs::new(RealFileSystem));
+        match futures_lite::future::block_on(workspace.read_plugin_lockfile(root_path)) {
+            Ok(lockfile) => lockfile.get(plugin_id).cloned(),
+            Err(e) => {
+                log::warn!("Failed to read plugin lockfile: {e}");
+                None
+            }
+        }
+    }
+
+    fn granted_permissions(&self, plugin_id: &str) -> Option<PluginPermissions> {
+        Some(
+            self.load_plugins_config()
+                .ok()?
+                .get(plugin_id)?
+                .permissions
+                .clone(),
+        )
+    }
+
+    fn access_log_enabled(&self, plugin_id: &str) -> bool {
+        self.load_plugins_config()
+            .ok()
+            .and_then(|plugins| plugins.get(plugin_id).map(|config| config.audit))
+            .unwrap_or(false)
+    }
 }
 
 fn normalize_workspace_file_target(root_index_path: Option<&Path>, target: &str) -> String {
@@ -255,6 +294,8 @@ mod tests {
                     ..PluginPermissions::default()
                 },
                 config: None,
+                limits: None,
+                audit: false,
             },
         );
 
@@ -6,7 +6,9 @@
 
 use std::collections::HashMap;
 
+use diaryx_core::plugin::limits::PluginResourceLimits;
 use diaryx_core::plugin::permissions::PluginPermissions;
+use diaryx_core::plugin::services::{ServiceProvision, ServiceRequirement};
 use serde::{Deserialize, Serialize};
 
 /// The current protocol version supported by this host.
@@ -75,6 +77,51 @@ mod fig_permissions {
     }
 }
 
+/// serde bridge for optional fig types such as [`PluginResourceLimits`], with
+/// the same wire format as [`fig_permissions`].
+mod fig_option {
+    use diaryx_core::fig::{Document, Format, FromValue, SerializeOptions, ToValue};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ToValue,
+        S: Serializer,
+    {
+        let json_value = match value {
+            Some(value) => {
+                let json = value
+                    .to_value()
+                    .serialize_with(Format::Json, SerializeOptions::compact())
+                    .map_err(serde::ser::Error::custom)?;
+                Some(
+                    serde_json::from_str::<serde_json::Value>(&json)
+                        .map_err(serde::ser::Error::custom)?,
+                )
+            }
+            None => None,
+        };
+        json_value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromValue,
+        D: Deserializer<'de>,
+    {
+        let Some(json_value) = Option::<serde_json::Value>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let json = serde_json::to_string(&json_value).map_err(serde::de::Error::custom)?;
+        let fig_value = Document::parse(json.as_bytes(), Format::Json)
+            .and_then(|doc| doc.to_value())
+            .map_err(serde::de::Error::custom)?;
+        T::from_value(&fig_value)
+            .map(Some)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// Manifest returned by the guest's exported `manifest` function.
 ///
 /// The host calls `manifest("")` at load time and caches the result.
@@ -95,7 +142,8 @@ pub struct GuestManifest {
     pub description: String,
     /// Capability strings this plugin requests.
     ///
-    /// Known values: `"file_events"`, `"workspace_events"`, `"custom_commands"`.
+    /// Known values: `"file_events"`, `"workspace_events"`, `"custom_commands"`,
+    /// `"scheduled"`, `"validation_rules"`, `"render_html"`, `"services"`.
     pub capabilities: Vec<String>,
     /// Serialized [`UiContribution`](diaryx_core::plugin::UiContribution) values.
     ///
@@ -127,6 +175,170 @@ pub struct GuestManifest {
     /// calls them. Used for documentation, validation, and future marketplace tooling.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub server_functions: Vec<ServerFunctionDecl>,
+    /// Memory, time and fuel the plugin needs per call.
+    ///
+    /// Overridable under `plugins.<id>.limits` and capped by the host's
+    /// [`ResourceLimitPolicy`](diaryx_core::plugin::limits::ResourceLimitPolicy)
+    /// at load time. `None` takes the host's defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fig_option")]
+    pub resource_limits: Option<PluginResourceLimits>,
+    /// Cron-like schedules for the `scheduled` capability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<GuestSchedule>,
+    /// Lint rules for the `validation_rules` capability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation_rules: Vec<GuestValidationRule>,
+    /// Service interfaces provided to other plugins (`services` capability).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provides: Vec<GuestServiceProvision>,
+    /// Service interfaces called on other plugins (`services` capability).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<GuestServiceRequirement>,
+}
+
+/// A schedule declared by a guest with the `scheduled` capability.
+///
+/// Mirrors [`PluginSchedule`](diaryx_core::plugin::schedule::PluginSchedule).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestSchedule {
+    /// Identifier passed back in the `scheduled` event payload.
+    pub id: String,
+    /// Five-field cron expression or `@hourly`/`@daily`/`@weekly`/`@monthly`/`@yearly`.
+    pub cron: String,
+    /// Human-readable description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A lint rule declared by a guest with the `validation_rules` capability.
+///
+/// Mirrors [`ValidationRule`](diaryx_core::plugin::validation::ValidationRule).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestValidationRule {
+    /// Rule identifier, reported back in each issue's `rule`.
+    pub id: String,
+    /// Human-readable summary of what the rule checks.
+    pub description: String,
+}
+
+/// A service interface declared by a guest with the `services` capability.
+///
+/// Mirrors [`ServiceProvision`](diaryx_core::plugin::services::ServiceProvision).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestServiceProvision {
+    /// Interface name.
+    pub interface: String,
+    /// Version of the interface implemented.
+    pub version: String,
+    /// Commands that make up the interface (empty means any).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+}
+
+/// A service interface a guest with the `services` capability calls.
+///
+/// Mirrors [`ServiceRequirement`](diaryx_core::plugin::services::ServiceRequirement).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestServiceRequirement {
+    /// Interface name.
+    pub interface: String,
+    /// Accepted version range, e.g. `^1.0`.
+    pub version: String,
+    /// Whether the guest works without a provider.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl From<&GuestServiceProvision> for ServiceProvision {
+    fn from(provision: &GuestServiceProvision) -> Self {
+        Self {
+            interface: provision.interface.clone(),
+            version: provision.version.clone(),
+            commands: provision.commands.clone(),
+        }
+    }
+}
+
+impl From<&GuestServiceRequirement> for ServiceRequirement {
+    fn from(requirement: &GuestServiceRequirement) -> Self {
+        Self {
+            interface: requirement.interface.clone(),
+            version: requirement.version.clone(),
+            optional: requirement.optional,
+        }
+    }
+}
+
+/// Input to the guest's `validate` export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationRequest {
+    /// Workspace root directory.
+    pub workspace_root: String,
+    /// Workspace-relative root index, for a whole-workspace validation.
+    #[serde(default)]
+    pub root_index: Option<String>,
+    /// Workspace-relative file, when a single file is validated.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+/// Output of the guest's `validate` export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationResponse {
+    /// Issues found by the guest's rules.
+    #[serde(default)]
+    pub issues: Vec<GuestValidationIssue>,
+}
+
+/// An issue raised by a guest lint rule. Also the input to the guest's
+/// `fix_validation_issue` export, which receives it back unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestValidationIssue {
+    /// ID of the rule that raised the issue.
+    pub rule: String,
+    /// Workspace-relative path of the offending file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// Guest-defined fix payload; issues with one are auto-fixable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<serde_json::Value>,
+}
+
+/// Output of the guest's `fix_validation_issue` export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationFixResponse {
+    /// Whether the fix was applied.
+    pub success: bool,
+    /// What was done, or why it failed.
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Input to the guest's `render_html` export, for the `render_html`
+/// capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderHtmlInput {
+    /// Editor extension whose syntax matched.
+    pub extension_id: String,
+    /// Text between the extension's delimiters.
+    pub source: String,
+    /// Whether the syntax matched as a block.
+    #[serde(default)]
+    pub display_mode: bool,
+}
+
+/// Output of the guest's `render_html` export. Without `html`, the syntax is
+/// left as written.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RenderHtmlOutput {
+    /// Static HTML replacing the syntax.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// Why the syntax couldn't be rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Declares a server-side API endpoint this plugin interacts with.
@@ -154,6 +366,7 @@ pub struct GuestEvent {
     /// Known values:
     /// - `"workspace_opened"`, `"workspace_closed"`, `"workspace_changed"`, `"workspace_committed"`
     /// - `"file_saved"`, `"file_created"`, `"file_deleted"`, `"file_moved"`
+    /// - `"scheduled"` (payload: `schedule_id`, `scheduled_at`, `missed`, `cancel_token`)
     pub event_type: String,
     /// Event-specific payload (varies by event type).
     pub payload: serde_json::Value,
@@ -204,6 +417,11 @@ mod tests {
             conversions: vec![],
             min_app_version: None,
             server_functions: vec![],
+            resource_limits: None,
+            schedules: vec![],
+            validation_rules: vec![],
+            provides: vec![],
+            requires: vec![],
         };
         let json = serde_json::to_string(&manifest).unwrap();
         let parsed: GuestManifest = serde_json::from_str(&json).unwrap();
@@ -228,6 +446,66 @@ mod tests {
         assert_eq!(m.protocol_version, 2);
     }
 
+    #[test]
+    fn guest_manifest_resource_limits_roundtrip() {
+        let json = r#"{"id":"test","name":"T","version":"1.0","description":"d","capabilities":[],"resource_limits":{"max_memory_pages":512,"timeout_ms":2000}}"#;
+        let m: GuestManifest = serde_json::from_str(json).unwrap();
+        let limits = m.resource_limits.as_ref().unwrap();
+        assert_eq!(limits.max_memory_pages, Some(512));
+        assert_eq!(limits.timeout_ms, Some(2000));
+        assert_eq!(limits.fuel, None);
+
+        let reparsed: GuestManifest =
+            serde_json::from_str(&serde_json::to_string(&m).unwrap()).unwrap();
+        assert_eq!(reparsed.resource_limits, m.resource_limits);
+
+        let bare =
+            r#"{"id":"test","name":"T","version":"1.0","description":"d","capabilities":[]}"#;
+        let m: GuestManifest = serde_json::from_str(bare).unwrap();
+        assert!(m.resource_limits.is_none());
+        assert!(
+            !serde_json::to_string(&m)
+                .unwrap()
+                .contains("resource_limits")
+        );
+    }
+
+    #[test]
+    fn guest_manifest_schedules_default_empty() {
+        let json = r#"{"id":"test","name":"T","version":"1.0","description":"d","capabilities":["scheduled"],"schedules":[{"id":"daily","cron":"@daily"}]}"#;
+        let m: GuestManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(m.schedules[0].cron, "@daily");
+        assert!(m.schedules[0].description.is_none());
+
+        let bare =
+            r#"{"id":"test","name":"T","version":"1.0","description":"d","capabilities":[]}"#;
+        let m: GuestManifest = serde_json::from_str(bare).unwrap();
+        assert!(m.schedules.is_empty());
+    }
+
+    #[test]
+    fn guest_manifest_services() {
+        let json = r#"{"id":"test","name":"T","version":"1.0","description":"d","capabilities":["services"],"provides":[{"interface":"templating","version":"1.2.0","commands":["RenderBody"]}],"requires":[{"interface":"export","version":"^1"}]}"#;
+        let m: GuestManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(m.provides[0].commands, vec!["RenderBody"]);
+        assert!(!m.requires[0].optional);
+        assert!(!serde_json::to_string(&m).unwrap().contains("optional"));
+    }
+
+    #[test]
+    fn validation_response_issues_default_fix_to_none() {
+        let json = r#"{"issues":[{"rule":"daily-mood","file":"journal/today.md","message":"missing mood"}]}"#;
+        let resp: ValidationResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.issues[0].rule, "daily-mood");
+        assert!(resp.issues[0].fix.is_none());
+        assert!(
+            serde_json::from_str::<ValidationResponse>("{}")
+                .unwrap()
+                .issues
+                .is_empty()
+        );
+    }
+
     #[test]
     fn command_response_roundtrip() {
         let resp = CommandResponse {
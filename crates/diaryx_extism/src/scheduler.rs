@@ -0,0 +1,366 @@
+//! Host-side driver for scheduled plugin events.
+//!
+//! [`ScheduleRunner`] wraps a core [`PluginScheduler`] with what every native
+//! host needs: persisting the [`ScheduleState`] under the workspace's
+//! `.diaryx/` directory, tracking the run in flight, and flagging its cancel
+//! token through the [cancellation registry](crate::cancel_plugin_operation)
+//! when the host shuts down. Hosts call [`run`](ScheduleRunner::run) on a
+//! background thread, or [`tick_now`](ScheduleRunner::tick_now) once, and
+//! deliver each due event with
+//! [`PluginRegistry::emit_scheduled`](diaryx_core::plugin::PluginRegistry::emit_scheduled)
+//! or [`WorkspacePlugin::on_scheduled`](diaryx_core::plugin::WorkspacePlugin::on_scheduled).
+//!
+//! Several hosts may drive the same workspace at once (a cron
+//! `diaryx plugin tick` next to a running edit server or desktop app). Each
+//! tick therefore re-reads the state file and marks its events delivered
+//! while holding [`SCHEDULE_LOCK_FILE`], so an occurrence is claimed by
+//! exactly one host.
+
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, Timelike};
+use diaryx_core::error::DiaryxError;
+use diaryx_core::plugin::schedule::{PluginScheduler, SCHEDULE_STATE_FILE, ScheduleState};
+use diaryx_core::plugin::{PluginId, PluginManifest, ScheduledEvent};
+
+use crate::host_fns::cancel_plugin_operation;
+
+/// File name of the lock held while a host claims due events, next to the
+/// schedule state under `.diaryx/`.
+pub const SCHEDULE_LOCK_FILE: &str = "plugin-schedules.lock";
+
+/// How long a host waits for another host's lock before skipping a tick.
+const LOCK_WAIT: Duration = Duration::from_secs(2);
+
+/// Age past which a lock is assumed to be left over from a crashed host.
+/// Locks are only held while the state file is read and written.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+/// Where a workspace's schedule state is persisted.
+pub fn schedule_state_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".diaryx").join(SCHEDULE_STATE_FILE)
+}
+
+/// Time to sleep until the start of the next local minute.
+pub fn until_next_minute() -> Duration {
+    let second = chrono::Local::now().second().min(59);
+    Duration::from_secs(u64::from(60 - second))
+}
+
+/// Stops a [`ScheduleRunner`] and flags the cancel token of its run in
+/// flight, if any.
+///
+/// Cloneable so a shutdown path can hold one while the runner ticks
+/// elsewhere.
+#[derive(Clone, Default)]
+pub struct ScheduleCanceller {
+    in_flight: Arc<Mutex<Option<(String, String)>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl ScheduleCanceller {
+    /// Stop the runner and ask the plugin currently running a scheduled
+    /// event to stop. The plugin sees this on its next `host_is_cancelled`
+    /// poll; the runner delivers nothing further.
+    pub fn cancel(&self) {
+        self.stopped.store(true, Ordering::Release);
+        if let Ok(in_flight) = self.in_flight.lock()
+            && let Some((plugin_id, token)) = in_flight.as_ref()
+        {
+            cancel_plugin_operation(plugin_id, token);
+        }
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+
+    fn set(&self, run: Option<(String, String)>) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            *in_flight = run;
+        }
+    }
+}
+
+/// Exclusive hold on a workspace's [`SCHEDULE_LOCK_FILE`], released on drop.
+struct StateLock {
+    path: PathBuf,
+}
+
+impl StateLock {
+    /// Take the lock, waiting up to [`LOCK_WAIT`] for another host to
+    /// release it. Returns `None` if it stays held.
+    fn acquire(path: &Path) -> Option<Self> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let deadline = Instant::now() + LOCK_WAIT;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => {
+                    return Some(Self {
+                        path: path.to_path_buf(),
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = std::fs::metadata(path)
+                        .and_then(|meta| meta.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                    if stale {
+                        log::warn!("Removing stale {}", path.display());
+                        let _ = std::fs::remove_file(path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return None;
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => {
+                    log::warn!("Failed to create {}: {e}", path.display());
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Persistent scheduler for one workspace.
+pub struct ScheduleRunner {
+    state_path: PathBuf,
+    lock_path: PathBuf,
+    scheduler: PluginScheduler,
+    canceller: ScheduleCanceller,
+}
+
+impl ScheduleRunner {
+    /// Register the schedules of `manifests` for the workspace. Invalid
+    /// schedules are skipped and returned for the host to report.
+    pub fn new(workspace_root: &Path, manifests: &[PluginManifest]) -> (Self, Vec<DiaryxError>) {
+        let state_path = schedule_state_path(workspace_root);
+        let lock_path = state_path.with_file_name(SCHEDULE_LOCK_FILE);
+        let mut scheduler = PluginScheduler::new(ScheduleState::default());
+        let errors = scheduler.register_manifests(manifests);
+        let runner = Self {
+            state_path,
+            lock_path,
+            scheduler,
+            canceller: ScheduleCanceller::default(),
+        };
+        (runner, errors)
+    }
+
+    /// Whether any schedules are registered.
+    pub fn is_empty(&self) -> bool {
+        self.scheduler.is_empty()
+    }
+
+    /// A handle that stops the runner and cancels the run in flight.
+    pub fn canceller(&self) -> ScheduleCanceller {
+        self.canceller.clone()
+    }
+
+    /// Deliver every event due at `now` through `deliver`. Returns the events
+    /// delivered.
+    ///
+    /// The events are claimed first: under the state lock, the latest state
+    /// is reloaded, the due events marked and the state written back. If the
+    /// lock can't be taken, nothing is delivered; the next tick coalesces
+    /// what was missed. Events not yet delivered when the runner is
+    /// cancelled are dropped.
+    pub fn tick(
+        &mut self,
+        now: NaiveDateTime,
+        mut deliver: impl FnMut(&PluginId, &ScheduledEvent),
+    ) -> Vec<(PluginId, ScheduledEvent)> {
+        if self.canceller.is_cancelled() {
+            return Vec::new();
+        }
+        let due = {
+            let Some(_lock) = StateLock::acquire(&self.lock_path) else {
+                log::warn!(
+                    "{} is held by another host; skipping this tick",
+                    self.lock_path.display()
+                );
+                return Vec::new();
+            };
+            self.scheduler.set_state(self.load());
+            let due = self.scheduler.due(now);
+            self.save();
+            due
+        };
+
+        let mut delivered = Vec::with_capacity(due.len());
+        for (plugin_id, event) in due {
+            if self.canceller.is_cancelled() {
+                break;
+            }
+            self.canceller
+                .set(Some((plugin_id.0.clone(), event.cancel_token.clone())));
+            deliver(&plugin_id, &event);
+            self.canceller.set(None);
+            delivered.push((plugin_id, event));
+        }
+        delivered
+    }
+
+    /// [`tick`](Self::tick) at the current local time. Schedules are
+    /// evaluated in the host's local time zone.
+    pub fn tick_now(
+        &mut self,
+        deliver: impl FnMut(&PluginId, &ScheduledEvent),
+    ) -> Vec<(PluginId, ScheduledEvent)> {
+        self.tick(chrono::Local::now().naive_local(), deliver)
+    }
+
+    /// Tick at the start of every minute until the runner is cancelled or
+    /// `keep_going` returns `false`.
+    pub fn run(
+        mut self,
+        mut keep_going: impl FnMut() -> bool,
+        mut deliver: impl FnMut(&PluginId, &ScheduledEvent),
+    ) {
+        while !self.canceller.is_cancelled() && keep_going() {
+            self.tick_now(&mut deliver);
+            let wake = Instant::now() + until_next_minute();
+            while !self.canceller.is_cancelled() {
+                let left = wake.saturating_duration_since(Instant::now());
+                if left.is_zero() {
+                    break;
+                }
+                std::thread::sleep(left.min(Duration::from_secs(1)));
+            }
+        }
+    }
+
+    fn load(&self) -> ScheduleState {
+        match std::fs::read_to_string(&self.state_path) {
+            Ok(json) => ScheduleState::parse(&json).unwrap_or_else(|e| {
+                log::warn!("Ignoring unreadable {}: {e}", self.state_path.display());
+                ScheduleState::default()
+            }),
+            Err(_) => ScheduleState::default(),
+        }
+    }
+
+    fn save(&self) {
+        let json = match self.scheduler.state().to_json() {
+            Ok(json) => json,
+            Err(e) => {
+                log::warn!("Failed to serialize plugin schedule state: {e}");
+                return;
+            }
+        };
+        if let Some(parent) = self.state_path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(&self.state_path, json) {
+            log::warn!("Failed to write {}: {e}", self.state_path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diaryx_core::plugin::PluginCapability;
+    use diaryx_core::plugin::schedule::{PluginSchedule, SCHEDULE_TIME_FORMAT};
+
+    use super::*;
+
+    fn manifest() -> PluginManifest {
+        PluginManifest {
+            id: PluginId("diaryx.digest".into()),
+            name: "Digest".into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            capabilities: vec![PluginCapability::Scheduled {
+                schedules: vec![PluginSchedule {
+                    id: "hourly".into(),
+                    cron: "@hourly".into(),
+                    description: None,
+                }],
+            }],
+            ui: Vec::new(),
+            cli: Vec::new(),
+        }
+    }
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, SCHEDULE_TIME_FORMAT).unwrap()
+    }
+
+    fn workspace(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("diaryx-scheduler-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn concurrent_runners_deliver_each_occurrence_once() {
+        let root = workspace("shared");
+        let (mut server, _) = ScheduleRunner::new(&root, &[manifest()]);
+        let (mut cli, _) = ScheduleRunner::new(&root, &[manifest()]);
+
+        // The first tick only records a starting point.
+        assert!(server.tick(at("2026-03-02T09:30"), |_, _| {}).is_empty());
+        assert_eq!(cli.tick(at("2026-03-02T10:00"), |_, _| {}).len(), 1);
+        // The server picks up the state the CLI tick wrote instead of
+        // delivering 10:00 again.
+        assert!(server.tick(at("2026-03-02T10:01"), |_, _| {}).is_empty());
+        assert_eq!(server.tick(at("2026-03-02T11:00"), |_, _| {}).len(), 1);
+        assert!(cli.tick(at("2026-03-02T11:00"), |_, _| {}).is_empty());
+        assert!(!root.join(".diaryx").join(SCHEDULE_LOCK_FILE).exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn held_lock_skips_the_tick() {
+        let root = workspace("locked");
+        let (mut runner, _) = ScheduleRunner::new(&root, &[manifest()]);
+        runner.tick(at("2026-03-02T09:30"), |_, _| {});
+
+        let lock = StateLock::acquire(&runner.lock_path).unwrap();
+        assert!(runner.tick(at("2026-03-02T10:00"), |_, _| {}).is_empty());
+        drop(lock);
+        assert_eq!(runner.tick(at("2026-03-02T10:00"), |_, _| {}).len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn cancelled_runner_stops_ticking() {
+        let root = workspace("cancelled");
+        let (runner, _) = ScheduleRunner::new(&root, &[manifest()]);
+        let canceller = runner.canceller();
+        canceller.cancel();
+        assert!(canceller.is_cancelled());
+
+        let mut ticks = 0;
+        runner.run(
+            || {
+                ticks += 1;
+                true
+            },
+            |_, _| {},
+        );
+        assert_eq!(ticks, 0);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,577 @@
+//! Publisher signatures on plugin artifacts and the local trust store.
+//!
+//! A registry `sha256` only proves the download matches the registry; if the
+//! registry itself is compromised it proves nothing. Publishers therefore
+//! sign each artifact with an ed25519 key over a message binding the plugin
+//! ID, version and WASM digest (see [`signing_message`]). Installers verify
+//! that signature, write it next to the module as [`SIGNATURE_FILE`], and the
+//! loader re-verifies it on every load.
+//!
+//! Which key may sign which plugin is decided by the [`TrustStore`]: the first
+//! key seen for a plugin is pinned (trust on first use), a user can pin a key
+//! up front, and a later artifact signed by a different key is refused as
+//! *re-keyed* unless the [`PluginTrustPolicy`] overrides it. The store also
+//! records the plugins a user has explicitly allowed to load without a
+//! signature.
+//!
+//! ```text
+//! ~/.diaryx/plugins/
+//!   my-plugin/
+//!     plugin.wasm
+//!     signature.json   # ArtifactSignature written at install time
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// File name of the signature sidecar stored next to `plugin.wasm`.
+pub const SIGNATURE_FILE: &str = "signature.json";
+
+/// Domain separator for the signed message, versioned so the format can
+/// change without old signatures verifying under new rules.
+const SIGNING_CONTEXT: &str = "diaryx-plugin-artifact:v1";
+
+/// Errors raised while verifying a plugin artifact against its signature and
+/// the trust store.
+#[derive(Debug, Error)]
+pub enum TrustError {
+    /// The artifact carries no publisher signature.
+    #[error("plugin '{plugin_id}' is not signed by its publisher")]
+    Unsigned { plugin_id: String },
+
+    /// The signature, key or digest is malformed or does not verify.
+    #[error("invalid signature for plugin '{plugin_id}': {reason}")]
+    InvalidSignature { plugin_id: String, reason: String },
+
+    /// The artifact is signed by a different key than the one pinned for it.
+    #[error(
+        "plugin '{plugin_id}' is signed by {presented}, but {pinned} is pinned for it; \
+         the publisher key changed"
+    )]
+    Rekeyed {
+        plugin_id: String,
+        pinned: String,
+        presented: String,
+    },
+
+    /// The trust store or signature sidecar could not be read or written.
+    #[error("trust store error: {0}")]
+    Store(String),
+}
+
+/// An ed25519 publisher signature over a plugin artifact.
+///
+/// Stored as [`SIGNATURE_FILE`] next to an installed `plugin.wasm`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactSignature {
+    /// Canonical plugin ID the signature covers.
+    pub plugin_id: String,
+    /// Plugin version the signature covers.
+    pub version: String,
+    /// Lowercase hex SHA-256 of the WASM module.
+    pub sha256: String,
+    /// Base64 ed25519 public key of the publisher.
+    pub publisher_key: String,
+    /// Base64 ed25519 signature over [`signing_message`].
+    pub signature: String,
+}
+
+/// The exact bytes a publisher signs for an artifact.
+pub fn signing_message(plugin_id: &str, version: &str, sha256: &str) -> String {
+    format!(
+        "{SIGNING_CONTEXT}\n{plugin_id}\n{version}\n{}",
+        sha256.to_ascii_lowercase()
+    )
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+impl ArtifactSignature {
+    /// Sign `wasm` as `plugin_id`@`version` with a publisher's secret key.
+    pub fn sign(signing_key: &SigningKey, plugin_id: &str, version: &str, wasm: &[u8]) -> Self {
+        let sha256 = sha256_hex(wasm);
+        let signature = signing_key.sign(signing_message(plugin_id, version, &sha256).as_bytes());
+        Self {
+            plugin_id: plugin_id.to_string(),
+            version: version.to_string(),
+            sha256,
+            publisher_key: BASE64.encode(signing_key.verifying_key().as_bytes()),
+            signature: BASE64.encode(signature.to_bytes()),
+        }
+    }
+
+    /// Verify that this signature is well-formed, covers `wasm`, and was made
+    /// by `publisher_key`. Says nothing about whether that key is trusted —
+    /// see [`TrustStore::check`].
+    pub fn verify(&self, wasm: &[u8]) -> Result<(), TrustError> {
+        let invalid = |reason: String| TrustError::InvalidSignature {
+            plugin_id: self.plugin_id.clone(),
+            reason,
+        };
+
+        let actual = sha256_hex(wasm);
+        if !actual.eq_ignore_ascii_case(&self.sha256) {
+            return Err(invalid(format!(
+                "signed digest {} does not match module digest {actual}",
+                self.sha256
+            )));
+        }
+
+        let key_bytes: [u8; 32] = BASE64
+            .decode(&self.publisher_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("publisher key is not a base64 ed25519 key".into()))?;
+        let key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|e| invalid(format!("publisher key is not a valid ed25519 key: {e}")))?;
+        let sig_bytes: [u8; 64] = BASE64
+            .decode(&self.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("signature is not a base64 ed25519 signature".into()))?;
+
+        key.verify(
+            signing_message(&self.plugin_id, &self.version, &self.sha256).as_bytes(),
+            &Signature::from_bytes(&sig_bytes),
+        )
+        .map_err(|_| invalid("signature does not verify".into()))
+    }
+
+    /// Read the signature sidecar from a plugin directory, if present.
+    pub fn read_from_dir(plugin_dir: &Path) -> Result<Option<Self>, TrustError> {
+        let path = plugin_dir.join(SIGNATURE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| TrustError::Store(format!("failed to read {}: {e}", path.display())))?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| TrustError::Store(format!("failed to parse {}: {e}", path.display())))
+    }
+
+    /// Write this signature as the sidecar of a plugin directory.
+    pub fn write_to_dir(&self, plugin_dir: &Path) -> Result<(), TrustError> {
+        let path = plugin_dir.join(SIGNATURE_FILE);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| TrustError::Store(format!("failed to serialize signature: {e}")))?;
+        std::fs::write(&path, json)
+            .map_err(|e| TrustError::Store(format!("failed to write {}: {e}", path.display())))
+    }
+}
+
+/// How a publisher key came to be pinned for a plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinOrigin {
+    /// Pinned automatically the first time a signed artifact was seen.
+    FirstUse,
+    /// Pinned explicitly by the user.
+    Manual,
+}
+
+/// The publisher key pinned for one plugin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublisherPin {
+    /// Base64 ed25519 public key.
+    pub publisher_key: String,
+    /// How the pin was created.
+    pub origin: PinOrigin,
+    /// RFC 3339 timestamp of when the pin was created.
+    pub pinned_at: String,
+}
+
+/// Outcome of checking a signed artifact against the trust store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustDecision {
+    /// The key matches the one pinned for the plugin.
+    Pinned,
+    /// No key was pinned; this one has now been (trust on first use).
+    FirstUse,
+    /// The key differs from the pinned one and the policy allowed it anyway;
+    /// the new key replaced the pin.
+    Rekeyed {
+        /// The key that was previously pinned.
+        previous: String,
+    },
+}
+
+/// Local store of publisher keys pinned per plugin ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    /// Pinned publisher keys keyed by canonical plugin ID.
+    #[serde(default)]
+    pub pins: BTreeMap<String, PublisherPin>,
+    /// Plugin IDs the user has allowed to load without a publisher signature.
+    #[serde(default)]
+    pub unsigned: BTreeSet<String>,
+}
+
+impl TrustStore {
+    /// Load the trust store at `path`; a missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self, TrustError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| TrustError::Store(format!("failed to read {}: {e}", path.display())))?;
+        serde_json::from_str(&json)
+            .map_err(|e| TrustError::Store(format!("failed to parse {}: {e}", path.display())))
+    }
+
+    /// Persist the trust store to `path`, creating parent directories.
+    pub fn save(&self, path: &Path) -> Result<(), TrustError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                TrustError::Store(format!("failed to create {}: {e}", parent.display()))
+            })?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| TrustError::Store(format!("failed to serialize trust store: {e}")))?;
+        std::fs::write(path, json)
+            .map_err(|e| TrustError::Store(format!("failed to write {}: {e}", path.display())))
+    }
+
+    /// Pin `publisher_key` for `plugin_id`, replacing any existing pin.
+    pub fn pin(&mut self, plugin_id: &str, publisher_key: &str, origin: PinOrigin) {
+        self.pins.insert(
+            plugin_id.to_string(),
+            PublisherPin {
+                publisher_key: publisher_key.to_string(),
+                origin,
+                pinned_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+    }
+
+    /// Forget the pin for `plugin_id`. Returns whether one existed.
+    pub fn forget(&mut self, plugin_id: &str) -> bool {
+        self.pins.remove(plugin_id).is_some()
+    }
+
+    /// Allow `plugin_id` to load without a publisher signature.
+    pub fn allow_unsigned(&mut self, plugin_id: &str) {
+        self.unsigned.insert(plugin_id.to_string());
+    }
+
+    /// Withdraw an unsigned-load override. Returns whether one existed.
+    pub fn revoke_unsigned(&mut self, plugin_id: &str) -> bool {
+        self.unsigned.remove(plugin_id)
+    }
+
+    /// Check a verified signature's key against the pin for its plugin,
+    /// pinning it on first use. A different key is refused unless
+    /// `allow_rekeyed`, in which case it replaces the pin.
+    pub fn check(
+        &mut self,
+        signature: &ArtifactSignature,
+        allow_rekeyed: bool,
+    ) -> Result<TrustDecision, TrustError> {
+        let plugin_id = &signature.plugin_id;
+        let presented = &signature.publisher_key;
+        match self.pins.get(plugin_id) {
+            Some(pin) if &pin.publisher_key == presented => Ok(TrustDecision::Pinned),
+            Some(pin) if !allow_rekeyed => Err(TrustError::Rekeyed {
+                plugin_id: plugin_id.clone(),
+                pinned: pin.publisher_key.clone(),
+                presented: presented.clone(),
+            }),
+            Some(pin) => {
+                let previous = pin.publisher_key.clone();
+                self.pin(plugin_id, presented, PinOrigin::Manual);
+                Ok(TrustDecision::Rekeyed { previous })
+            }
+            None => {
+                self.pin(plugin_id, presented, PinOrigin::FirstUse);
+                Ok(TrustDecision::FirstUse)
+            }
+        }
+    }
+}
+
+/// How strictly installed plugins are checked before they are loaded.
+///
+/// The default refuses unsigned and re-keyed plugins. Hosts without a trust
+/// store on disk still verify signatures but cannot pin keys across runs.
+#[derive(Debug, Clone, Default)]
+pub struct PluginTrustPolicy {
+    /// Trust store file; `None` skips pinning.
+    pub trust_store: Option<PathBuf>,
+    /// Load every plugin that has no signature sidecar. Per-plugin overrides
+    /// live in the trust store instead; see [`TrustStore::allow_unsigned`].
+    pub allow_unsigned: bool,
+    /// Load plugins signed by a different key than the pinned one (and
+    /// re-pin to the new key).
+    pub allow_rekeyed: bool,
+}
+
+impl PluginTrustPolicy {
+    /// Check an installed plugin's signature and publisher key before it is
+    /// loaded.
+    ///
+    /// `plugin_dir` holds `plugin.wasm` and, for signed plugins, the
+    /// [`SIGNATURE_FILE`] sidecar. A symlinked `plugin.wasm` is a local build
+    /// linked with `diaryx plugin dev` and is loaded without a signature.
+    /// Returns the verified signature, or `None` if the plugin was allowed
+    /// through unsigned.
+    ///
+    /// A key the trust store hasn't seen is not pinned yet: the signature's
+    /// `plugin_id` is only a claim until the guest manifest confirms it.
+    /// Call [`check_publisher`](Self::check_publisher) once the plugin has
+    /// loaded to save the pin.
+    pub fn verify_installed(
+        &self,
+        plugin_dir: &Path,
+        plugin_name: &str,
+    ) -> Result<Option<ArtifactSignature>, TrustError> {
+        let wasm_path = plugin_dir.join("plugin.wasm");
+        if wasm_path.is_symlink() {
+            log::debug!("Plugin {plugin_name} is a linked dev build; skipping signature check");
+            return Ok(None);
+        }
+
+        let Some(signature) = ArtifactSignature::read_from_dir(plugin_dir)? else {
+            if self.allow_unsigned || self.unsigned_allowed(plugin_name)? {
+                log::warn!("Loading unsigned plugin {plugin_name}");
+                return Ok(None);
+            }
+            return Err(TrustError::Unsigned {
+                plugin_id: plugin_name.to_string(),
+            });
+        };
+
+        let wasm = std::fs::read(&wasm_path).map_err(|e| {
+            TrustError::Store(format!("failed to read {}: {e}", wasm_path.display()))
+        })?;
+        signature.verify(&wasm)?;
+        if let Some(path) = &self.trust_store {
+            TrustStore::load(path)?.check(&signature, self.allow_rekeyed)?;
+        }
+        Ok(Some(signature))
+    }
+
+    /// Whether the trust store lets `plugin_id` load without a signature.
+    fn unsigned_allowed(&self, plugin_id: &str) -> Result<bool, TrustError> {
+        match &self.trust_store {
+            Some(path) => Ok(TrustStore::load(path)?.unsigned.contains(plugin_id)),
+            None => Ok(false),
+        }
+    }
+
+    /// Check a verified signature's publisher key against the trust store,
+    /// pinning it on first use and persisting any change.
+    pub fn check_publisher(
+        &self,
+        signature: &ArtifactSignature,
+    ) -> Result<TrustDecision, TrustError> {
+        let Some(path) = &self.trust_store else {
+            return Ok(TrustDecision::FirstUse);
+        };
+        let mut store = TrustStore::load(path)?;
+        let decision = store.check(signature, self.allow_rekeyed)?;
+        match &decision {
+            TrustDecision::Pinned => {}
+            TrustDecision::FirstUse => {
+                log::info!(
+                    "Pinned publisher key {} for plugin {}",
+                    signature.publisher_key,
+                    signature.plugin_id
+                );
+                store.save(path)?;
+            }
+            TrustDecision::Rekeyed { previous } => {
+                log::warn!(
+                    "Plugin {} re-keyed from {previous} to {}; pin replaced",
+                    signature.plugin_id,
+                    signature.publisher_key
+                );
+                store.save(path)?;
+            }
+        }
+        Ok(decision)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[test]
+    fn signature_roundtrip_verifies() {
+        let sig = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.2.3", b"wasm bytes");
+        assert_eq!(sig.sha256, sha256_hex(b"wasm bytes"));
+        sig.verify(b"wasm bytes").unwrap();
+    }
+
+    #[test]
+    fn tampered_module_is_rejected() {
+        let sig = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.2.3", b"wasm bytes");
+        let err = sig.verify(b"evil bytes").unwrap_err();
+        assert!(matches!(err, TrustError::InvalidSignature { .. }), "{err}");
+    }
+
+    #[test]
+    fn signature_is_bound_to_id_and_version() {
+        let mut sig = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.2.3", b"wasm");
+        sig.version = "9.9.9".into();
+        assert!(sig.verify(b"wasm").is_err());
+
+        let mut sig = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.2.3", b"wasm");
+        sig.plugin_id = "diaryx.other".into();
+        assert!(sig.verify(b"wasm").is_err());
+    }
+
+    #[test]
+    fn substituted_publisher_key_is_rejected() {
+        let mut sig = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.2.3", b"wasm");
+        sig.publisher_key = BASE64.encode(key(2).verifying_key().as_bytes());
+        assert!(sig.verify(b"wasm").is_err());
+    }
+
+    #[test]
+    fn first_key_is_pinned_and_rekeys_are_refused() {
+        let mut store = TrustStore::default();
+        let first = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.0.0", b"v1");
+        assert_eq!(store.check(&first, false).unwrap(), TrustDecision::FirstUse);
+        assert_eq!(store.pins["diaryx.sync"].origin, PinOrigin::FirstUse);
+
+        let update = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.1.0", b"v2");
+        assert_eq!(store.check(&update, false).unwrap(), TrustDecision::Pinned);
+
+        let rekeyed = ArtifactSignature::sign(&key(2), "diaryx.sync", "1.2.0", b"v3");
+        let err = store.check(&rekeyed, false).unwrap_err();
+        assert!(matches!(err, TrustError::Rekeyed { .. }), "{err}");
+        assert_eq!(store.pins["diaryx.sync"].publisher_key, first.publisher_key);
+    }
+
+    #[test]
+    fn allowed_rekey_replaces_the_pin() {
+        let mut store = TrustStore::default();
+        let first = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.0.0", b"v1");
+        store.check(&first, false).unwrap();
+
+        let rekeyed = ArtifactSignature::sign(&key(2), "diaryx.sync", "1.2.0", b"v3");
+        assert_eq!(
+            store.check(&rekeyed, true).unwrap(),
+            TrustDecision::Rekeyed {
+                previous: first.publisher_key.clone()
+            }
+        );
+        assert_eq!(
+            store.pins["diaryx.sync"].publisher_key,
+            rekeyed.publisher_key
+        );
+        assert_eq!(store.check(&rekeyed, false).unwrap(), TrustDecision::Pinned);
+    }
+
+    #[test]
+    fn manual_pin_rejects_first_artifact_from_another_key() {
+        let mut store = TrustStore::default();
+        let expected = BASE64.encode(key(1).verifying_key().as_bytes());
+        store.pin("diaryx.sync", &expected, PinOrigin::Manual);
+
+        let imposter = ArtifactSignature::sign(&key(2), "diaryx.sync", "1.0.0", b"v1");
+        assert!(store.check(&imposter, false).is_err());
+    }
+
+    #[test]
+    fn verifying_an_install_pins_nothing_until_the_load_succeeds() {
+        let root =
+            std::env::temp_dir().join(format!("diaryx-signing-deferred-{}", std::process::id()));
+        let plugin_dir = root.join("diaryx.sync");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("plugin.wasm"), b"wasm").unwrap();
+        let signature = ArtifactSignature::sign(&key(1), "diaryx.sync", "1.0.0", b"wasm");
+        signature.write_to_dir(&plugin_dir).unwrap();
+        let store_path = root.join("trusted-publishers.json");
+        let policy = PluginTrustPolicy {
+            trust_store: Some(store_path.clone()),
+            ..Default::default()
+        };
+
+        let verified = policy
+            .verify_installed(&plugin_dir, "diaryx.sync")
+            .unwrap()
+            .unwrap();
+        assert!(TrustStore::load(&store_path).unwrap().pins.is_empty());
+
+        assert_eq!(
+            policy.check_publisher(&verified).unwrap(),
+            TrustDecision::FirstUse
+        );
+        let store = TrustStore::load(&store_path).unwrap();
+        assert_eq!(
+            store.pins["diaryx.sync"].publisher_key,
+            signature.publisher_key
+        );
+
+        // A pinned key still gates verification.
+        let imposter = ArtifactSignature::sign(&key(2), "diaryx.sync", "1.0.0", b"wasm");
+        imposter.write_to_dir(&plugin_dir).unwrap();
+        let err = policy
+            .verify_installed(&plugin_dir, "diaryx.sync")
+            .unwrap_err();
+        assert!(matches!(err, TrustError::Rekeyed { .. }), "{err}");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn unsigned_plugins_need_a_per_plugin_override() {
+        let root =
+            std::env::temp_dir().join(format!("diaryx-signing-unsigned-{}", std::process::id()));
+        let plugin_dir = root.join("diaryx.local");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::write(plugin_dir.join("plugin.wasm"), b"wasm").unwrap();
+        let store_path = root.join("trusted-publishers.json");
+        let policy = PluginTrustPolicy {
+            trust_store: Some(store_path.clone()),
+            ..Default::default()
+        };
+
+        let err = policy
+            .verify_installed(&plugin_dir, "diaryx.local")
+            .unwrap_err();
+        assert!(matches!(err, TrustError::Unsigned { .. }), "{err}");
+
+        let mut store = TrustStore::default();
+        store.allow_unsigned("diaryx.local");
+        store.save(&store_path).unwrap();
+        assert!(
+            policy
+                .verify_installed(&plugin_dir, "diaryx.local")
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            policy
+                .verify_installed(&plugin_dir, "diaryx.other")
+                .is_err()
+        );
+
+        store.revoke_unsigned("diaryx.local");
+        store.save(&store_path).unwrap();
+        assert!(
+            policy
+                .verify_installed(&plugin_dir, "diaryx.local")
+                .is_err()
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
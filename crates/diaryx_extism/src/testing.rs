@@ -1,37 +1,64 @@
 //! Test harness for integration-testing Extism guest plugins.
 //!
 //! Feature-gated behind `testing`. Provides [`PluginTestHarness`] for loading
-//! a `.wasm` plugin with mock host functions and exercising its exports.
+//! a `.wasm` plugin with mock host functions and exercising its exports, so
+//! plugin crates can test their compiled module with plain `cargo test`.
+//!
+//! The fakes cover the host functions that would otherwise reach outside the
+//! test:
+//!
+//! - workspace files: an [`InMemoryFileSystem`] ([`PluginTestHarnessBuilder::with_file`])
+//! - `host_http_request`: a [`ScriptedHttpClient`] that answers from canned
+//!   responses and records what was sent
+//! - `host_get_now` / `host_get_timestamp`: a [`FakeClock`] the test moves by hand
+//! - `host_emit_event`: a [`RecordingEventEmitter`] (the default)
 //!
 //! # Example
 //!
 //! ```rust,ignore
-//! use diaryx_extism::testing::PluginTestHarness;
+//! use diaryx_extism::testing::{FakeClock, PluginTestHarnessBuilder, ScriptedHttpClient};
+//! use diaryx_extism::HostHttpResponse;
+//! use serde_json::json;
 //!
 //! #[tokio::test]
-//! async fn test_manifest() {
-//!     let harness = PluginTestHarness::load("target/wasm32-wasip1/release/my_plugin.wasm")
+//! async fn imports_feed() {
+//!     let http = Arc::new(ScriptedHttpClient::new().respond(
+//!         "GET",
+//!         "https://example.com/feed.json",
+//!         HostHttpResponse::json(200, &json!({ "items": [{ "title": "Hi" }] })),
+//!     ));
+//!     let harness = PluginTestHarnessBuilder::new("target/wasm32-unknown-unknown/release/my_plugin.wasm")
+//!         .with_file("README.md", "---\ntitle: Home\n---\n")
+//!         .with_http_client(http.clone())
+//!         .with_clock(Arc::new(FakeClock::at("2026-01-02T09:00:00+00:00")))
+//!         .build()
 //!         .expect("Failed to load plugin");
-//!     let manifest = harness.manifest();
-//!     assert_eq!(manifest.id.0, "diaryx.myplugin");
+//!
+//!     harness.command_ok("ImportFeed", json!({ "url": "https://example.com/feed.json" })).await;
+//!     harness.assert_file_contains("2026/01/02.md", "Hi");
+//!     harness.assert_event_emitted(&json!({ "type": "import_finished" }));
+//!     assert_eq!(http.requests().len(), 1);
 //! }
 //! ```
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+use chrono::{DateTime, Duration, FixedOffset};
 use serde_json::Value as JsonValue;
 use serde_json::json;
 
-use diaryx_core::fs::SyncToAsyncFs;
+use diaryx_core::fs::{AsyncFileSystem, InMemoryFileSystem, SyncToAsyncFs};
 use diaryx_core::plugin::permissions::PermissionType;
 use diaryx_core::plugin::{
     FileCreatedEvent, FileDeletedEvent, FileMovedEvent, FilePlugin, FileSavedEvent, Plugin,
-    PluginContext, PluginError, PluginId, PluginManifest, WorkspaceOpenedEvent, WorkspacePlugin,
+    PluginContext, PluginError, PluginHealth, PluginId, PluginManifest, WorkspaceOpenedEvent,
+    WorkspacePlugin,
 };
 use diaryx_native::RealFileSystem;
 
+use crate::access_log::AccessLogWriter;
 use crate::host_fns::*;
 use crate::loader::load_plugin_from_wasm;
 
@@ -180,6 +207,175 @@ impl PermissionChecker for AllowAllPermissionChecker {
     }
 }
 
+// ============================================================================
+// Scripted HTTP and a controllable clock
+// ============================================================================
+
+impl HostHttpResponse {
+    /// A response with a plain-text body.
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HashMap::from([("content-type".to_string(), "text/plain".to_string())]),
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// A response with a JSON body.
+    pub fn json(status: u16, body: &JsonValue) -> Self {
+        Self {
+            status,
+            headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+            body: body.to_string().into_bytes(),
+        }
+    }
+}
+
+struct ScriptedRoute {
+    method: String,
+    url: String,
+    response: HostHttpResponse,
+    once: bool,
+}
+
+impl ScriptedRoute {
+    fn matches(&self, request: &HostHttpRequest) -> bool {
+        if !self.method.eq_ignore_ascii_case(&request.method) {
+            return false;
+        }
+        match self.url.strip_suffix('*') {
+            Some(prefix) => request.url.starts_with(prefix),
+            None => self.url == request.url,
+        }
+    }
+}
+
+/// An [`HttpClient`] that answers from canned responses and records every
+/// request it receives.
+///
+/// Routes match on method (case-insensitive) and URL; a URL ending in `*`
+/// matches by prefix. One-shot routes ([`respond_once`](Self::respond_once))
+/// are used up in the order they were added and take precedence over
+/// standing ones. A request nothing matches fails like a network error.
+#[derive(Default)]
+pub struct ScriptedHttpClient {
+    routes: Mutex<Vec<ScriptedRoute>>,
+    requests: Mutex<Vec<HostHttpRequest>>,
+}
+
+impl ScriptedHttpClient {
+    /// A client with no routes: every request fails.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer every matching request with `response` (builder pattern).
+    pub fn respond(self, method: &str, url: &str, response: HostHttpResponse) -> Self {
+        self.push(method, url, response, false);
+        self
+    }
+
+    /// Answer the next matching request with `response` (builder pattern).
+    pub fn respond_once(self, method: &str, url: &str, response: HostHttpResponse) -> Self {
+        self.push(method, url, response, true);
+        self
+    }
+
+    /// Add a standing route after the harness is built.
+    pub fn add_response(&self, method: &str, url: &str, response: HostHttpResponse) {
+        self.push(method, url, response, false);
+    }
+
+    /// Every request received so far, in order.
+    pub fn requests(&self) -> Vec<HostHttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn push(&self, method: &str, url: &str, response: HostHttpResponse, once: bool) {
+        self.routes.lock().unwrap().push(ScriptedRoute {
+            method: method.to_string(),
+            url: url.to_string(),
+            response,
+            once,
+        });
+    }
+}
+
+impl HttpClient for ScriptedHttpClient {
+    fn send(&self, request: &HostHttpRequest) -> Result<HostHttpResponse, String> {
+        self.requests.lock().unwrap().push(request.clone());
+        let mut routes = self.routes.lock().unwrap();
+        if let Some(index) = routes.iter().position(|r| r.once && r.matches(request)) {
+            return Ok(routes.remove(index).response);
+        }
+        routes
+            .iter()
+            .find(|r| !r.once && r.matches(request))
+            .map(|r| r.response.clone())
+            .ok_or_else(|| {
+                format!(
+                    "no scripted response for {} {}",
+                    request.method, request.url
+                )
+            })
+    }
+}
+
+/// A [`HostClock`] that only moves when the test moves it.
+pub struct FakeClock {
+    now: Mutex<DateTime<FixedOffset>>,
+}
+
+impl FakeClock {
+    /// A clock stopped at `now`.
+    pub fn new(now: DateTime<FixedOffset>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// A clock stopped at an RFC 3339 timestamp.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timestamp` isn't valid RFC 3339.
+    pub fn at(timestamp: &str) -> Self {
+        Self::new(
+            DateTime::parse_from_rfc3339(timestamp)
+                .unwrap_or_else(|e| panic!("invalid FakeClock timestamp {timestamp:?}: {e}")),
+        )
+    }
+
+    /// Jump to `now`.
+    pub fn set(&self, now: DateTime<FixedOffset>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// Move the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += by;
+    }
+}
+
+impl HostClock for FakeClock {
+    fn now(&self) -> DateTime<FixedOffset> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Whether `actual` contains everything in `expected`: objects match when
+/// every key of `expected` matches in `actual` (extra keys are fine), other
+/// values must be equal.
+fn json_contains(actual: &JsonValue, expected: &JsonValue) -> bool {
+    match (actual, expected) {
+        (JsonValue::Object(actual), JsonValue::Object(expected)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| json_contains(a, value))),
+        _ => actual == expected,
+    }
+}
+
 // ============================================================================
 // Test harness builder and struct
 // ============================================================================
@@ -207,6 +403,10 @@ pub struct PluginTestHarnessBuilder {
     workspace_root: Option<PathBuf>,
     runtime_context_provider: Option<Arc<dyn RuntimeContextProvider>>,
     namespace_provider: Option<Arc<dyn NamespaceProvider>>,
+    memory_fs: Option<InMemoryFileSystem>,
+    files: Vec<(PathBuf, Vec<u8>)>,
+    http_client: Option<Arc<dyn HttpClient>>,
+    clock: Option<Arc<dyn HostClock>>,
 }
 
 struct StaticRuntimeContextProvider {
@@ -236,6 +436,10 @@ impl PluginTestHarnessBuilder {
             workspace_root: None,
             runtime_context_provider: None,
             namespace_provider: None,
+            memory_fs: None,
+            files: Vec::new(),
+            http_client: None,
+            clock: None,
         }
     }
 
@@ -245,7 +449,8 @@ impl PluginTestHarnessBuilder {
         self
     }
 
-    /// Use a custom event emitter (e.g., [`RecordingEventEmitter`]).
+    /// Use a custom event emitter. By default events are captured by a
+    /// [`RecordingEventEmitter`] and exposed via [`PluginTestHarness::events`].
     pub fn with_event_emitter(mut self, emitter: Arc<dyn EventEmitter>) -> Self {
         self.event_emitter = Some(emitter);
         self
@@ -284,9 +489,53 @@ impl PluginTestHarnessBuilder {
         self
     }
 
+    /// Back the workspace with an in-memory filesystem instead of the real
+    /// one. Keep a clone to inspect it from the test.
+    pub fn with_memory_fs(mut self, fs: InMemoryFileSystem) -> Self {
+        self.memory_fs = Some(fs);
+        self
+    }
+
+    /// Seed a workspace file. Implies an in-memory filesystem.
+    pub fn with_file(mut self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) -> Self {
+        self.files.push((path.into(), contents.into()));
+        self
+    }
+
+    /// Use a custom HTTP client for `host_http_request` (e.g.,
+    /// [`ScriptedHttpClient`]). By default requests go to the real network.
+    pub fn with_http_client(mut self, client: Arc<dyn HttpClient>) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Use a custom clock for `host_get_now` and `host_get_timestamp`
+    /// (e.g., [`FakeClock`]).
+    pub fn with_clock(mut self, clock: Arc<dyn HostClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
     /// Build the test harness, loading the WASM plugin.
     pub fn build(self) -> Result<PluginTestHarness, String> {
-        let fs = Arc::new(SyncToAsyncFs::new(RealFileSystem));
+        let memory_fs = if self.memory_fs.is_some() || !self.files.is_empty() {
+            let fs: Arc<dyn AsyncFileSystem> =
+                Arc::new(SyncToAsyncFs::new(self.memory_fs.unwrap_or_default()));
+            for (path, contents) in &self.files {
+                futures_lite::future::block_on(fs.write(path, contents))
+                    .map_err(|e| format!("Failed to seed {}: {e}", path.display()))?;
+            }
+            Some(fs)
+        } else {
+            None
+        };
+        let fs = memory_fs
+            .clone()
+            .unwrap_or_else(|| Arc::new(SyncToAsyncFs::new(RealFileSystem)));
+        let recorded_events = match &self.event_emitter {
+            Some(_) => None,
+            None => Some(Arc::new(RecordingEventEmitter::new())),
+        };
         let runtime_context_provider = self.runtime_context_provider.unwrap_or_else(|| {
             if let Some(root) = &self.workspace_root {
                 Arc::new(StaticRuntimeContextProvider::new(json!({
@@ -298,13 +547,16 @@ impl PluginTestHarnessBuilder {
                 Arc::new(NoopRuntimeContextProvider)
             }
         });
+        let storage = self.storage.unwrap_or_else(|| Arc::new(NoopStorage));
         let host_context = Arc::new(HostContext {
             fs,
-            storage: self.storage.unwrap_or_else(|| Arc::new(NoopStorage)),
+            storage: storage.clone(),
             secret_store: Arc::new(NoopSecretStore),
-            event_emitter: self
-                .event_emitter
-                .unwrap_or_else(|| Arc::new(NoopEventEmitter)),
+            event_emitter: match (self.event_emitter, &recorded_events) {
+                (Some(emitter), _) => emitter,
+                (None, Some(recorder)) => recorder.clone(),
+                (None, None) => Arc::new(NoopEventEmitter),
+            },
             plugin_id: String::new(),
             plugin_id_locked: false,
             permission_checker: Some(
@@ -320,6 +572,13 @@ impl PluginTestHarnessBuilder {
                 .unwrap_or_else(|| Arc::new(crate::host_fns::NoopNamespaceProvider)),
             plugin_command_depth: 0,
             storage_quota_bytes: crate::host_fns::DEFAULT_STORAGE_QUOTA_BYTES,
+            resource_limit_policy: Default::default(),
+            plugin_trust: Default::default(),
+            http_client: self
+                .http_client
+                .unwrap_or_else(|| Arc::new(NetworkHttpClient)),
+            clock: self.clock.unwrap_or_else(|| Arc::new(SystemClock)),
+            access_log: Arc::new(AccessLogWriter::new(storage)),
         });
 
         let adapter = load_plugin_from_wasm(&self.wasm_path, host_context, None)
@@ -328,6 +587,8 @@ impl PluginTestHarnessBuilder {
         Ok(PluginTestHarness {
             adapter: Arc::new(adapter),
             workspace_root: self.workspace_root,
+            memory_fs,
+            recorded_events,
         })
     }
 }
@@ -340,6 +601,8 @@ impl PluginTestHarnessBuilder {
 pub struct PluginTestHarness {
     adapter: Arc<crate::adapter::ExtismPluginAdapter>,
     workspace_root: Option<PathBuf>,
+    memory_fs: Option<Arc<dyn AsyncFileSystem>>,
+    recorded_events: Option<Arc<RecordingEventEmitter>>,
 }
 
 impl PluginTestHarness {
@@ -359,6 +622,12 @@ impl PluginTestHarness {
         self.adapter.id()
     }
 
+    /// Health the plugin reports, e.g. after calls aborted for overrunning
+    /// its resource limits.
+    pub fn health(&self) -> Option<PluginHealth> {
+        self.adapter.health()
+    }
+
     /// Initialize the plugin with a test context.
     pub async fn init(&self) -> Result<(), PluginError> {
         let ctx = PluginContext::new(
@@ -438,4 +707,235 @@ impl PluginTestHarness {
     pub fn call_raw(&self, func: &str, input: &str) -> Result<String, PluginError> {
         self.adapter.call_guest(func, input)
     }
+
+    // ------------------------------------------------------------------------
+    // Assertion helpers
+    // ------------------------------------------------------------------------
+
+    /// Send a command that must succeed, returning its data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin doesn't handle the command or it fails.
+    pub async fn command_ok(&self, cmd: &str, params: JsonValue) -> JsonValue {
+        match self.command(cmd, params).await {
+            Some(Ok(data)) => data,
+            Some(Err(e)) => panic!("command {cmd} failed: {e}"),
+            None => panic!("command {cmd} is not handled by the plugin"),
+        }
+    }
+
+    /// Send a command that must fail, returning the error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin doesn't handle the command or it succeeds.
+    pub async fn command_err(&self, cmd: &str, params: JsonValue) -> PluginError {
+        match self.command(cmd, params).await {
+            Some(Err(e)) => e,
+            Some(Ok(data)) => panic!("command {cmd} unexpectedly succeeded with {data}"),
+            None => panic!("command {cmd} is not handled by the plugin"),
+        }
+    }
+
+    /// Read a workspace file from the in-memory filesystem. `None` if it
+    /// doesn't exist or the harness uses the real filesystem.
+    pub fn read_file(&self, path: &str) -> Option<String> {
+        let fs = self.memory_fs.as_ref()?;
+        futures_lite::future::block_on(fs.read_to_string(Path::new(path))).ok()
+    }
+
+    /// Assert that a workspace file exists and contains `needle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file is missing or doesn't contain `needle`.
+    pub fn assert_file_contains(&self, path: &str, needle: &str) {
+        match self.read_file(path) {
+            Some(contents) => assert!(
+                contents.contains(needle),
+                "{path} does not contain {needle:?}:\n{contents}"
+            ),
+            None => panic!("{path} does not exist in the workspace"),
+        }
+    }
+
+    /// Assert that a workspace file doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the file exists.
+    pub fn assert_no_file(&self, path: &str) {
+        assert!(
+            self.read_file(path).is_none(),
+            "{path} unexpectedly exists in the workspace"
+        );
+    }
+
+    /// Events the plugin has emitted, parsed as JSON. Empty when the harness
+    /// was built with a custom event emitter.
+    pub fn events(&self) -> Vec<JsonValue> {
+        self.recorded_events
+            .as_ref()
+            .map(|recorder| recorder.events_json())
+            .unwrap_or_default()
+    }
+
+    /// Assert that some emitted event contains every field of `expected`
+    /// (extra fields are ignored).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no emitted event matches.
+    pub fn assert_event_emitted(&self, expected: &JsonValue) {
+        let events = self.events();
+        assert!(
+            events.iter().any(|event| json_contains(event, expected)),
+            "no emitted event matches {expected}; emitted: {events:?}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, url: &str) -> HostHttpRequest {
+        HostHttpRequest {
+            method: method.into(),
+            url: url.into(),
+            headers: HashMap::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn scripted_http_matches_routes_and_records_requests() {
+        let client = ScriptedHttpClient::new()
+            .respond(
+                "GET",
+                "https://api.test/items*",
+                HostHttpResponse::text(200, "all"),
+            )
+            .respond_once(
+                "get",
+                "https://api.test/items/1",
+                HostHttpResponse::text(404, ""),
+            );
+
+        let first = client
+            .send(&request("GET", "https://api.test/items/1"))
+            .unwrap();
+        assert_eq!(first.status, 404);
+        let second = client
+            .send(&request("GET", "https://api.test/items/1"))
+            .unwrap();
+        assert_eq!(second.body, b"all");
+        assert!(
+            client
+                .send(&request("POST", "https://api.test/items"))
+                .is_err()
+        );
+
+        let methods: Vec<_> = client.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, ["GET", "GET", "POST"]);
+    }
+
+    #[test]
+    fn scripted_http_one_shot_routes_run_out_in_order() {
+        let client = ScriptedHttpClient::new()
+            .respond_once(
+                "POST",
+                "https://api.test/upload",
+                HostHttpResponse::text(503, ""),
+            )
+            .respond_once(
+                "POST",
+                "https://api.test/upload",
+                HostHttpResponse::text(201, "done"),
+            );
+        let upload = request("POST", "https://api.test/upload");
+
+        assert_eq!(client.send(&upload).unwrap().status, 503);
+        assert_eq!(client.send(&upload).unwrap().status, 201);
+        assert_eq!(
+            client.send(&upload).unwrap_err(),
+            "no scripted response for POST https://api.test/upload"
+        );
+        assert_eq!(client.requests().len(), 3);
+    }
+
+    #[test]
+    fn scripted_http_urls_match_exactly_without_a_wildcard() {
+        let client = ScriptedHttpClient::new().respond(
+            "GET",
+            "https://api.test/items",
+            HostHttpResponse::text(200, "exact"),
+        );
+
+        assert!(
+            client
+                .send(&request("GET", "https://api.test/items"))
+                .is_ok()
+        );
+        assert!(
+            client
+                .send(&request("GET", "https://api.test/items/1"))
+                .is_err()
+        );
+
+        client.add_response(
+            "GET",
+            "https://api.test/items/*",
+            HostHttpResponse::json(200, &json!({ "id": 1 })),
+        );
+        let response = client
+            .send(&request("GET", "https://api.test/items/1"))
+            .unwrap();
+        assert_eq!(response.body, br#"{"id":1}"#);
+        assert_eq!(
+            response.headers.get("content-type").map(String::as_str),
+            Some("application/json")
+        );
+    }
+
+    #[test]
+    fn fake_clock_moves_only_when_told() {
+        let clock = FakeClock::at("2026-03-01T08:00:00+01:00");
+        assert_eq!(clock.now().to_rfc3339(), "2026-03-01T08:00:00+01:00");
+        clock.advance(Duration::minutes(90));
+        assert_eq!(clock.now().to_rfc3339(), "2026-03-01T09:30:00+01:00");
+        clock.advance(Duration::days(1));
+        assert_eq!(clock.now().to_rfc3339(), "2026-03-02T09:30:00+01:00");
+
+        clock.set(DateTime::parse_from_rfc3339("2025-12-31T23:59:59+00:00").unwrap());
+        assert_eq!(clock.now().timestamp(), 1_767_225_599);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid FakeClock timestamp")]
+    fn fake_clock_rejects_bad_timestamps() {
+        FakeClock::at("tomorrow");
+    }
+
+    #[test]
+    fn event_matching_ignores_extra_fields() {
+        let event = json!({ "type": "synced", "detail": { "files": 2, "ok": true } });
+        assert!(json_contains(&event, &json!({ "detail": { "files": 2 } })));
+        assert!(json_contains(&event, &json!({})));
+        assert!(!json_contains(&event, &json!({ "type": "failed" })));
+        assert!(!json_contains(
+            &event,
+            &json!({ "detail": { "errors": 0 } })
+        ));
+        assert!(!json_contains(&event, &json!("synced")));
+    }
+
+    #[test]
+    fn event_matching_compares_arrays_whole() {
+        let event = json!({ "paths": ["a.md", "b.md"] });
+        assert!(json_contains(&event, &json!({ "paths": ["a.md", "b.md"] })));
+        assert!(!json_contains(&event, &json!({ "paths": ["a.md"] })));
+    }
 }
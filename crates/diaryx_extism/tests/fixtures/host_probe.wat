@@ -0,0 +1,111 @@
+;; Test fixture for the plugin test harness: a guest written directly in
+;; WAT so the harness tests don't need a wasm toolchain.
+;;
+;; `manifest` declares one command, `Probe`. `handle_command` ignores its
+;; params, calls `host_get_now`, `host_get_timestamp`, `host_http_request`
+;; (POST https://api.test/probe), `host_emit_event` ({"type":"probe_ran"}) and
+;; `host_write_file` (probe.md), and answers
+;; {"now": <now>, "timestamp": <timestamp>, "http": <http response>}.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "length" (func $length (param i64) (result i64)))
+  (import "extism:host/env" "load_u8" (func $load_u8 (param i64) (result i32)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+  (import "extism:host/user" "host_get_now" (func $host_get_now (param i64) (result i64)))
+  (import "extism:host/user" "host_get_timestamp" (func $host_get_timestamp (param i64) (result i64)))
+  (import "extism:host/user" "host_http_request" (func $host_http_request (param i64) (result i64)))
+  (import "extism:host/user" "host_emit_event" (func $host_emit_event (param i64) (result i64)))
+  (import "extism:host/user" "host_write_file" (func $host_write_file (param i64) (result i64)))
+
+  (memory (export "memory") 1)
+
+  ;; manifest
+  (data (i32.const 16) "{\"id\":\"test.host-probe\",\"name\":\"Host Probe\",\"version\":\"0.1.0\",\"description\":\"Calls host functions and reports what they returned\",\"capabilities\":[\"custom_commands\"],\"commands\":[\"Probe\"]}")
+  ;; empty
+  (data (i32.const 208) "{}")
+  ;; http_request
+  (data (i32.const 224) "{\"url\":\"https://api.test/probe\",\"method\":\"POST\",\"headers\":{\"content-type\":\"text/plain\"},\"body\":\"ping\"}")
+  ;; event
+  (data (i32.const 336) "{\"type\":\"probe_ran\"}")
+  ;; write
+  (data (i32.const 368) "{\"path\":\"probe.md\",\"content\":\"probed\"}")
+  ;; prefix
+  (data (i32.const 416) "{\"success\":true,\"data\":{\"now\":\"")
+  ;; timestamp
+  (data (i32.const 448) "\",\"timestamp\":")
+  ;; http
+  (data (i32.const 464) ",\"http\":")
+  ;; suffix
+  (data (i32.const 480) "}}")
+
+  ;; Copy `len` bytes at `src` in this module's memory to `dst + pos` in
+  ;; Extism memory. Returns the position after them.
+  (func $write (param $dst i64) (param $pos i64) (param $src i32) (param $len i32) (result i64)
+    (local $i i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $dst) (i64.add (local.get $pos) (i64.extend_i32_u (local.get $i))))
+          (i32.load8_u (i32.add (local.get $src) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (i64.add (local.get $pos) (i64.extend_i32_u (local.get $len))))
+
+  ;; Copy the Extism memory block `src` to `dst + pos`. Returns the position
+  ;; after it.
+  (func $copy (param $dst i64) (param $pos i64) (param $src i64) (result i64)
+    (local $i i64)
+    (local $len i64)
+    (local.set $len (call $length (local.get $src)))
+    (block $done
+      (loop $next
+        (br_if $done (i64.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $dst) (i64.add (local.get $pos) (local.get $i)))
+          (call $load_u8 (i64.add (local.get $src) (local.get $i))))
+        (local.set $i (i64.add (local.get $i) (i64.const 1)))
+        (br $next)))
+    (i64.add (local.get $pos) (local.get $len)))
+
+  ;; A new Extism memory block holding `len` bytes from `src`.
+  (func $const (param $src i32) (param $len i32) (result i64)
+    (local $block i64)
+    (local.set $block (call $alloc (i64.extend_i32_u (local.get $len))))
+    (drop (call $write (local.get $block) (i64.const 0) (local.get $src) (local.get $len)))
+    (local.get $block))
+
+  (func (export "manifest") (result i32)
+    (call $output_set (call $const (i32.const 16) (i32.const 186)) (i64.const 186))
+    (i32.const 0))
+
+  (func (export "handle_command") (result i32)
+    (local $now i64)
+    (local $timestamp i64)
+    (local $http i64)
+    (local $out i64)
+    (local $pos i64)
+    (local.set $now (call $host_get_now (call $const (i32.const 208) (i32.const 2))))
+    (local.set $timestamp (call $host_get_timestamp (call $const (i32.const 208) (i32.const 2))))
+    (local.set $http (call $host_http_request (call $const (i32.const 224) (i32.const 102))))
+    (drop (call $host_emit_event (call $const (i32.const 336) (i32.const 20))))
+    (drop (call $host_write_file (call $const (i32.const 368) (i32.const 38))))
+
+    (local.set $out
+      (call $alloc
+        (i64.add
+          (i64.const 55)
+          (i64.add
+            (call $length (local.get $now))
+            (i64.add (call $length (local.get $timestamp)) (call $length (local.get $http)))))))
+    (local.set $pos (call $write (local.get $out) (i64.const 0) (i32.const 416) (i32.const 31)))
+    (local.set $pos (call $copy (local.get $out) (local.get $pos) (local.get $now)))
+    (local.set $pos (call $write (local.get $out) (local.get $pos) (i32.const 448) (i32.const 14)))
+    (local.set $pos (call $copy (local.get $out) (local.get $pos) (local.get $timestamp)))
+    (local.set $pos (call $write (local.get $out) (local.get $pos) (i32.const 464) (i32.const 8)))
+    (local.set $pos (call $copy (local.get $out) (local.get $pos) (local.get $http)))
+    (local.set $pos (call $write (local.get $out) (local.get $pos) (i32.const 480) (i32.const 2)))
+    (call $output_set (local.get $out) (local.get $pos))
+    (i32.const 0))
+)
@@ -0,0 +1,35 @@
+;; Test fixture: a guest that runs out of fuel.
+;;
+;; `manifest` declares one command, `Burn`, and the resource limits
+;; {"fuel": 100000}; `handle_command` counts to 2^31, which takes far more fuel than it
+;; is given.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 16) "{\"id\":\"test.burn\",\"name\":\"Burn\",\"version\":\"0.1.0\",\"description\":\"Counts far past its fuel\",\"capabilities\":[\"custom_commands\"],\"commands\":[\"Burn\"],\"resource_limits\":{\"fuel\":100000}}")
+
+  (func (export "manifest") (result i32)
+    (local $block i64)
+    (local $i i32)
+    (local.set $block (call $alloc (i64.const 180)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.const 180)))
+        (call $store_u8
+          (i64.add (local.get $block) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (i32.const 16) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $output_set (local.get $block) (i64.const 180))
+    (i32.const 0))
+
+  (func (export "handle_command") (result i32)
+    (local $i i32)
+    (loop $count
+      (local.set $i (i32.add (local.get $i) (i32.const 1)))
+      (br_if $count (i32.lt_u (local.get $i) (i32.const 0x7fffffff))))
+    (i32.const 0)))
@@ -0,0 +1,34 @@
+;; Test fixture: a guest that allocates until memory runs out.
+;;
+;; `manifest` declares one command, `Grow`, and the resource limits
+;; {"max_memory_pages": 4}; `handle_command` grows its memory a page at a time and, like a
+;; Rust guest's allocation error handler, traps with `unreachable` once
+;; `memory.grow` fails.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 16) "{\"id\":\"test.grow\",\"name\":\"Grow\",\"version\":\"0.1.0\",\"description\":\"Grows its memory until it fails\",\"capabilities\":[\"custom_commands\"],\"commands\":[\"Grow\"],\"resource_limits\":{\"max_memory_pages\":4}}")
+
+  (func (export "manifest") (result i32)
+    (local $block i64)
+    (local $i i32)
+    (local.set $block (call $alloc (i64.const 194)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.const 194)))
+        (call $store_u8
+          (i64.add (local.get $block) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (i32.const 16) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $output_set (local.get $block) (i64.const 194))
+    (i32.const 0))
+
+  (func (export "handle_command") (result i32)
+    (loop $grow
+      (br_if $grow (i32.ne (memory.grow (i32.const 1)) (i32.const -1))))
+    unreachable))
@@ -0,0 +1,32 @@
+;; Test fixture: a guest that never returns from a call.
+;;
+;; `manifest` declares one command, `Spin`, and the resource limits
+;; {"timeout_ms": 50}; `handle_command` spins forever, so only the timeout ends it.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+
+  (memory (export "memory") 1)
+
+  (data (i32.const 16) "{\"id\":\"test.spin\",\"name\":\"Spin\",\"version\":\"0.1.0\",\"description\":\"Loops forever\",\"capabilities\":[\"custom_commands\"],\"commands\":[\"Spin\"],\"resource_limits\":{\"timeout_ms\":50}}")
+
+  (func (export "manifest") (result i32)
+    (local $block i64)
+    (local $i i32)
+    (local.set $block (call $alloc (i64.const 171)))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (i32.const 171)))
+        (call $store_u8
+          (i64.add (local.get $block) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (i32.const 16) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $output_set (local.get $block) (i64.const 171))
+    (i32.const 0))
+
+  (func (export "handle_command") (result i32)
+    (loop $forever
+      (br $forever))
+    (i32.const 0)))
@@ -0,0 +1,193 @@
+//! Drives a real guest through [`PluginTestHarnessBuilder`], checking that
+//! the host functions reach the harness fakes rather than the network or the
+//! system clock.
+//!
+//! The guest is `fixtures/host_probe.wat`, a hand-written module (Wasmtime
+//! compiles WAT directly), so no wasm toolchain is needed:
+//!
+//!     cargo test -p diaryx_extism --features testing --test plugin_harness
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Duration;
+use diaryx_core::plugin::permissions::PermissionType;
+use diaryx_extism::testing::{
+    FakeClock, PluginTestHarness, PluginTestHarnessBuilder, RecordingStorage, ScriptedHttpClient,
+};
+use diaryx_extism::{AccessLog, HostClock, HostHttpResponse, PermissionChecker};
+use serde_json::json;
+
+const PROBE_URL: &str = "https://api.test/probe";
+
+/// Copy the fixture into its own directory: the loader caches
+/// `manifest.json` next to the module, which shouldn't land in the source
+/// tree.
+fn probe_wasm(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("diaryx-harness-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("plugin.wasm");
+    std::fs::copy(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/host_probe.wat"),
+        &path,
+    )
+    .unwrap();
+    path
+}
+
+fn probe(
+    name: &str,
+    build: impl FnOnce(PluginTestHarnessBuilder) -> PluginTestHarnessBuilder,
+) -> PluginTestHarness {
+    build(PluginTestHarnessBuilder::new(probe_wasm(name)).with_file("README.md", "# Home\n"))
+        .build()
+        .expect("probe fixture should load")
+}
+
+struct DenyHttp;
+
+impl PermissionChecker for DenyHttp {
+    fn check_permission(
+        &self,
+        _plugin_id: &str,
+        permission_type: PermissionType,
+        target: &str,
+    ) -> Result<(), String> {
+        match permission_type {
+            PermissionType::HttpRequests => Err(format!("http denied for {target}")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Allows everything and has the access ledger turned on.
+struct Audited;
+
+impl PermissionChecker for Audited {
+    fn check_permission(
+        &self,
+        _plugin_id: &str,
+        _permission_type: PermissionType,
+        _target: &str,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn access_log_enabled(&self, _plugin_id: &str) -> bool {
+        true
+    }
+}
+
+#[test]
+fn host_functions_use_the_harness_fakes() {
+    let clock = Arc::new(FakeClock::at("2026-03-01T08:00:00+01:00"));
+    let http = Arc::new(ScriptedHttpClient::new().respond(
+        "POST",
+        PROBE_URL,
+        HostHttpResponse::text(201, "pong"),
+    ));
+    let harness = probe("fakes", |builder| {
+        builder
+            .with_clock(clock.clone())
+            .with_http_client(http.clone())
+    });
+    assert_eq!(harness.plugin_id().0, "test.host-probe");
+
+    clock.advance(Duration::minutes(90));
+    let data = futures_lite::future::block_on(harness.command_ok("Probe", json!({})));
+
+    assert_eq!(data["now"], "2026-03-01T09:30:00+01:00");
+    assert_eq!(data["timestamp"], clock.now().timestamp_millis());
+    assert_eq!(data["http"]["status"], 201);
+    assert_eq!(data["http"]["body"], "pong");
+
+    let requests = http.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].url, PROBE_URL);
+    assert_eq!(requests[0].body.as_deref(), Some(&b"ping"[..]));
+    assert_eq!(
+        requests[0].headers.get("content-type").map(String::as_str),
+        Some("text/plain")
+    );
+
+    harness.assert_event_emitted(&json!({ "type": "probe_ran" }));
+    harness.assert_file_contains("probe.md", "probed");
+    harness.assert_file_contains("README.md", "Home");
+}
+
+#[test]
+fn unscripted_requests_fail_like_network_errors() {
+    let harness = probe("unscripted", |builder| {
+        builder.with_http_client(Arc::new(ScriptedHttpClient::new()))
+    });
+
+    let data = futures_lite::future::block_on(harness.command_ok("Probe", json!({})));
+
+    assert_eq!(data["http"]["status"], 0);
+    let error = data["http"]["error"].as_str().unwrap();
+    assert!(
+        error.contains("no scripted response for POST https://api.test/probe"),
+        "{error}"
+    );
+}
+
+#[test]
+fn denied_requests_never_reach_the_client() {
+    let http = Arc::new(ScriptedHttpClient::new().respond(
+        "POST",
+        PROBE_URL,
+        HostHttpResponse::text(200, "pong"),
+    ));
+    let harness = probe("denied", |builder| {
+        builder
+            .with_http_client(http.clone())
+            .with_permission_checker(Arc::new(DenyHttp))
+    });
+
+    let data = futures_lite::future::block_on(harness.command_ok("Probe", json!({})));
+
+    assert_eq!(data["http"]["status"], 0);
+    assert!(
+        data["http"]["error"]
+            .as_str()
+            .is_some_and(|error| error.contains("http denied")),
+        "{data}"
+    );
+    assert!(http.requests().is_empty());
+    // Other host calls are unaffected.
+    harness.assert_file_contains("probe.md", "probed");
+}
+
+#[test]
+fn access_ledger_is_written_when_the_call_returns() {
+    let storage = Arc::new(RecordingStorage::new());
+    let harness = probe("ledger", |builder| {
+        builder
+            .with_storage(storage.clone())
+            .with_permission_checker(Arc::new(Audited))
+            .with_http_client(Arc::new(ScriptedHttpClient::new().respond(
+                "POST",
+                PROBE_URL,
+                HostHttpResponse::text(200, "pong"),
+            )))
+    });
+
+    futures_lite::future::block_on(harness.command_ok("Probe", json!({})));
+
+    // The harness (and the writer buffering the ledger) is still alive, so
+    // these records can only have come from the flush at the end of the call.
+    let log = AccessLog::load(storage.as_ref(), "test.host-probe");
+    assert!(
+        log.records
+            .iter()
+            .any(|r| r.permission == "http_requests" && r.target.contains("api.test") && r.allowed),
+        "{log:?}"
+    );
+    assert!(
+        log.records
+            .iter()
+            .any(|r| r.target.ends_with("probe.md") && r.allowed),
+        "{log:?}"
+    );
+}
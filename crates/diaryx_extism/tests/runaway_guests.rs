@@ -0,0 +1,87 @@
+//! Runs guests that overrun their resource limits and checks that the
+//! adapter reports each abort as a limit violation, degrades the plugin on
+//! the first and fails it once [`MAX_LIMIT_VIOLATIONS`] have piled up.
+//!
+//! Each guest is a WAT fixture declaring tiny limits in its manifest:
+//!
+//!     cargo test -p diaryx_extism --features testing --test runaway_guests
+
+use std::path::PathBuf;
+
+use diaryx_core::plugin::limits::MAX_LIMIT_VIOLATIONS;
+use diaryx_core::plugin::{PluginError, PluginHealth};
+use diaryx_extism::testing::PluginTestHarness;
+use serde_json::json;
+
+/// Copy a fixture into its own directory, away from the manifest cache the
+/// loader writes next to the module.
+fn fixture(name: &str) -> PluginTestHarness {
+    let dir = std::env::temp_dir().join(format!("diaryx-runaway-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path: PathBuf = dir.join("plugin.wasm");
+    std::fs::copy(
+        format!(
+            "{}/tests/fixtures/runaway_{name}.wat",
+            env!("CARGO_MANIFEST_DIR")
+        ),
+        &path,
+    )
+    .unwrap();
+    PluginTestHarness::load(path).expect("fixture should load")
+}
+
+/// Call `command` until the plugin is disabled, checking each abort with
+/// `is_violation` and the health it leaves behind.
+fn run_until_failed(
+    harness: &PluginTestHarness,
+    command: &str,
+    is_violation: fn(&PluginError) -> bool,
+) {
+    assert!(matches!(harness.health(), Some(PluginHealth::Healthy)));
+    for call in 1..=MAX_LIMIT_VIOLATIONS {
+        let err = futures_lite::future::block_on(harness.command_err(command, json!({})));
+        assert!(is_violation(&err), "call {call}: {err:?}");
+        match harness.health() {
+            Some(PluginHealth::Degraded(_)) if call < MAX_LIMIT_VIOLATIONS => {}
+            Some(PluginHealth::Failed(_)) if call == MAX_LIMIT_VIOLATIONS => {}
+            health => panic!("call {call}: unexpected health {health:?}"),
+        }
+    }
+
+    // A failed plugin is refused without running the guest again.
+    let err = futures_lite::future::block_on(harness.command_err(command, json!({})));
+    assert!(
+        matches!(&err, PluginError::ResourceLimitExceeded(msg) if msg.contains("disabled")),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn infinite_loop_times_out() {
+    let harness = fixture("spin");
+    run_until_failed(
+        &harness,
+        "Spin",
+        |err| matches!(err, PluginError::Timeout(msg) if msg.contains("50ms")),
+    );
+}
+
+#[test]
+fn unbounded_memory_growth_hits_the_page_limit() {
+    let harness = fixture("grow");
+    run_until_failed(
+        &harness,
+        "Grow",
+        |err| matches!(err, PluginError::ResourceLimitExceeded(msg) if msg.contains("4 memory pages")),
+    );
+}
+
+#[test]
+fn long_computation_runs_out_of_fuel() {
+    let harness = fixture("burn");
+    run_until_failed(
+        &harness,
+        "Burn",
+        |err| matches!(err, PluginError::ResourceLimitExceeded(msg) if msg.contains("100000 units of fuel")),
+    );
+}
@@ -0,0 +1,297 @@
+//! Plugin render hooks: editor-extension syntax expanded into static HTML.
+//!
+//! Highlights, spoilers and HTML embeds are built into
+//! [`preprocess_custom_syntax`](crate::preprocess_custom_syntax). Everything
+//! else a plugin adds to the editor (math, drawings, ...) reaches the renderer
+//! through [`RenderHooks`]: the plugins that declare
+//! [`PluginCapability::RenderHtml`](diaryx_core::plugin::PluginCapability::RenderHtml)
+//! are asked to render each occurrence of their syntax via
+//! [`WorkspacePlugin::render_html`]. Native hosts pass in Extism plugins
+//! (`diaryx_extism`); any other [`WorkspacePlugin`] works the same way, which
+//! keeps this crate free of a plugin runtime.
+
+use std::sync::Arc;
+
+use diaryx_core::plugin::render_hooks::{RenderHook, RenderHtmlRequest, manifest_render_hooks};
+use diaryx_core::plugin::{PluginRegistry, WorkspacePlugin};
+
+use crate::page::html_escape;
+
+/// The plugin syntax a render expands, and the plugins that expand it.
+#[derive(Clone, Default)]
+pub struct RenderHooks {
+    /// Hooks ordered longest opening delimiter first, so `$$` wins over `$`.
+    hooks: Vec<(RenderHook, Arc<dyn WorkspacePlugin>)>,
+}
+
+impl RenderHooks {
+    /// No hooks: only the built-in syntax is expanded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hooks of every healthy plugin in `registry` that declares
+    /// [`PluginCapability::RenderHtml`](diaryx_core::plugin::PluginCapability::RenderHtml).
+    pub fn from_registry(registry: &PluginRegistry) -> Self {
+        let mut hooks = Self::new();
+        for plugin in registry.render_hook_plugins() {
+            hooks.add_plugin(plugin);
+        }
+        hooks
+    }
+
+    /// Add the render hooks a plugin's manifest declares. Plugins without
+    /// the capability contribute nothing.
+    pub fn add_plugin(&mut self, plugin: Arc<dyn WorkspacePlugin>) {
+        for hook in manifest_render_hooks(&plugin.manifest()) {
+            self.hooks.push((hook, Arc::clone(&plugin)));
+        }
+        self.hooks
+            .sort_by_key(|(hook, _)| std::cmp::Reverse(hook.open.len()));
+    }
+
+    /// Whether no plugin syntax will be expanded.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// The syntax that will be expanded.
+    pub fn hooks(&self) -> impl Iterator<Item = &RenderHook> {
+        self.hooks.iter().map(|(hook, _)| hook)
+    }
+
+    /// CSS the hooked extensions ship, to append to the site stylesheet.
+    pub fn css(&self) -> String {
+        let mut css = String::new();
+        for (hook, _) in &self.hooks {
+            if let Some(extension_css) = hook.css.as_deref().filter(|c| !c.trim().is_empty()) {
+                css.push_str(&format!(
+                    "\n/* {} {} */\n{}\n",
+                    hook.plugin_id, hook.extension_id, extension_css
+                ));
+            }
+        }
+        css
+    }
+
+    /// Try to expand plugin syntax at the start of `s`. Returns
+    /// `(html, bytes_consumed)`.
+    ///
+    /// `prev` is the character before `s`: block syntax only matches at the
+    /// start of a line, and nothing matches after a backslash or in the
+    /// middle of a longer delimiter run (`$$x$$` mid-line isn't inline `$`).
+    /// Syntax whose plugin declines or fails to render it is left as written.
+    pub(crate) fn expand(&self, s: &str, prev: Option<char>) -> Option<(String, usize)> {
+        if prev == Some('\\') {
+            return None;
+        }
+        let line_start = prev.is_none_or(|c| c == '\n');
+        self.hooks.iter().find_map(|(hook, plugin)| {
+            if !s.starts_with(&hook.open) || (hook.block && !line_start) {
+                return None;
+            }
+            if !hook.block
+                && (prev.is_some_and(|c| hook.open.ends_with(c))
+                    || s[hook.open.len()..].starts_with(&hook.open))
+            {
+                return None;
+            }
+            let (source, consumed) = match_delimiters(hook, s)?;
+            let request = RenderHtmlRequest {
+                extension_id: hook.extension_id.clone(),
+                source: source.to_string(),
+                display_mode: hook.block,
+            };
+            let html = plugin.render_html(&request)?.ok()?;
+            let extension = html_escape(&hook.extension_id);
+            if hook.block {
+                Some((
+                    format!(
+                        "<div class=\"diaryx-render-hook\" data-extension=\"{extension}\">{html}</div>\n\n"
+                    ),
+                    consumed,
+                ))
+            } else {
+                Some((
+                    format!(
+                        "<span class=\"diaryx-render-hook\" data-extension=\"{extension}\">{html}</span>"
+                    ),
+                    consumed,
+                ))
+            }
+        })
+    }
+}
+
+/// Match `hook`'s delimiters at the start of `s`. Returns the source between
+/// them and the bytes consumed.
+///
+/// Inline and single-line syntax must close on the same line; inline source
+/// can't start or end with whitespace, so `$5 and $10` stays text. A block
+/// must be the only thing on its closing line, whose newline is consumed.
+fn match_delimiters<'s>(hook: &RenderHook, s: &'s str) -> Option<(&'s str, usize)> {
+    let after_open = &s[hook.open.len()..];
+    let (source, close) = if hook.block && !hook.single_line {
+        let close = after_open.find(&hook.close)?;
+        (after_open[..close].trim_matches(['\n', '\r']), close)
+    } else {
+        let line = &after_open[..after_open.find('\n').unwrap_or(after_open.len())];
+        let close = line.find(&hook.close)?;
+        (&line[..close], close)
+    };
+    if source.trim().is_empty() {
+        return None;
+    }
+    if !hook.block
+        && (source.starts_with(char::is_whitespace) || source.ends_with(char::is_whitespace))
+    {
+        return None;
+    }
+
+    let consumed = hook.open.len() + close + hook.close.len();
+    if !hook.block {
+        return Some((source, consumed));
+    }
+    let rest = &s[consumed..];
+    let line_end = rest.find('\n').unwrap_or(rest.len());
+    if !rest[..line_end].trim().is_empty() {
+        return None;
+    }
+    Some((source, consumed + (line_end + 1).min(rest.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use diaryx_core::plugin::{
+        EditorNodeType, MarkdownLevel, MarkdownSyntax, Plugin, PluginCapability, PluginError,
+        PluginId, PluginManifest, UiContribution,
+    };
+
+    use super::*;
+    use crate::{markdown_to_html, preprocess_custom_syntax_with_hooks};
+
+    struct MathPlugin {
+        capabilities: Vec<PluginCapability>,
+    }
+
+    fn math_extension(id: &str, level: MarkdownLevel, delimiter: &str) -> UiContribution {
+        UiContribution::EditorExtension {
+            extension_id: id.into(),
+            node_type: EditorNodeType::InlineAtom,
+            markdown: Box::new(MarkdownSyntax {
+                level,
+                open: delimiter.into(),
+                close: delimiter.into(),
+                attribute_syntax: None,
+                single_line: false,
+            }),
+            render_export: None,
+            edit_mode: None,
+            iframe_component_id: None,
+            css: Some(".math { font-style: italic; }".into()),
+            insert_command: Box::new(None),
+            keyboard_shortcut: None,
+            click_behavior: None,
+            html_tag: None,
+            base_css_class: None,
+            attributes: None,
+            toolbar: Box::new(None),
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Plugin for MathPlugin {
+        fn id(&self) -> PluginId {
+            PluginId("test.math".into())
+        }
+
+        fn manifest(&self) -> PluginManifest {
+            PluginManifest {
+                id: self.id(),
+                name: "Math".into(),
+                version: "0.1.0".into(),
+                description: String::new(),
+                capabilities: self.capabilities.clone(),
+                ui: vec![
+                    math_extension("mathInline", MarkdownLevel::Inline, "$"),
+                    math_extension("mathBlock", MarkdownLevel::Block, "$$"),
+                ],
+                cli: vec![],
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl WorkspacePlugin for MathPlugin {
+        fn render_html(&self, request: &RenderHtmlRequest) -> Option<Result<String, PluginError>> {
+            if request.source == "fail" {
+                return Some(Err(PluginError::Other("bad input".into())));
+            }
+            let tag = if request.display_mode { "mblock" } else { "m" };
+            Some(Ok(format!("<{tag}>{}</{tag}>", request.source)))
+        }
+    }
+
+    fn hooks() -> RenderHooks {
+        let mut hooks = RenderHooks::new();
+        hooks.add_plugin(Arc::new(MathPlugin {
+            capabilities: vec![
+                PluginCapability::EditorExtension,
+                PluginCapability::RenderHtml,
+            ],
+        }));
+        hooks
+    }
+
+    #[test]
+    fn inline_syntax_is_rendered() {
+        let out = preprocess_custom_syntax_with_hooks("so $x^2$ and ==hi==", &hooks());
+        assert!(out.starts_with(
+            r#"so <span class="diaryx-render-hook" data-extension="mathInline"><m>x^2</m></span> and <mark"#
+        ));
+    }
+
+    #[test]
+    fn block_syntax_is_rendered_on_its_own_lines() {
+        let html = markdown_to_html(&preprocess_custom_syntax_with_hooks(
+            "$$\nE = mc^2\n$$\nAfter.",
+            &hooks(),
+        ));
+        assert!(html.contains(
+            r#"<div class="diaryx-render-hook" data-extension="mathBlock"><mblock>E = mc^2</mblock></div>"#
+        ));
+        assert!(html.contains("<p>After.</p>"));
+    }
+
+    #[test]
+    fn unmatched_escaped_and_code_syntax_is_left_alone() {
+        let hooks = hooks();
+        for input in [
+            "costs $5 and $10",
+            r"an escaped \$x$",
+            "`$x$`",
+            "```\n$$\nx\n$$\n```",
+            "text $$x$$ mid-line",
+            "$fail$",
+        ] {
+            let out = preprocess_custom_syntax_with_hooks(input, &hooks);
+            assert!(!out.contains("diaryx-render-hook"), "{input:?} -> {out}");
+        }
+    }
+
+    #[test]
+    fn only_plugins_declaring_the_capability_contribute() {
+        let mut hooks = RenderHooks::new();
+        hooks.add_plugin(Arc::new(MathPlugin {
+            capabilities: vec![PluginCapability::EditorExtension],
+        }));
+        assert!(hooks.is_empty());
+
+        let hooks = self::hooks();
+        assert_eq!(
+            hooks.hooks().map(|h| h.open.as_str()).collect::<Vec<_>>(),
+            ["$$", "$"]
+        );
+        assert_eq!(hooks.css().matches(".math").count(), 2);
+    }
+}
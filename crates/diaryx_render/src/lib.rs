@@ -9,6 +9,7 @@
 //! target): no Extism, no host functions, no filesystem, no entropy/clock.
 
 pub mod appearance;
+pub mod hooks;
 pub mod html;
 mod links;
 mod markdown;
@@ -23,7 +24,10 @@ pub mod types;
 pub use appearance::{
     ColorPalette, ContentWidth, FaviconAsset, FontFamily, ThemeAppearance, TypographySettings,
 };
+pub use hooks::RenderHooks;
 pub use html::{HtmlRenderer, SiteStyle};
 pub use links::{percent_decode, root_prefix, transform_links};
-pub use markdown::{markdown_to_html, preprocess_custom_syntax};
+pub use markdown::{
+    markdown_to_html, preprocess_custom_syntax, preprocess_custom_syntax_with_hooks,
+};
 pub use nav::{build_site_nav_tree, nav_for_page};
@@ -3,9 +3,13 @@
 //! Two stages run in order:
 //! 1. [`preprocess_custom_syntax`] rewrites Diaryx-specific syntax (highlights,
 //!    spoilers, HTML embeds) into raw HTML, skipping fenced/inline code.
+//!    [`preprocess_custom_syntax_with_hooks`] also expands plugin syntax
+//!    through [`RenderHooks`].
 //! 2. [`markdown_to_html`] runs comrak over the preprocessed markdown with
 //!    `unsafe` rendering on, so the injected raw HTML passes through.
 
+use crate::hooks::RenderHooks;
+
 /// Convert preprocessed markdown to HTML via comrak.
 ///
 /// Call [`preprocess_custom_syntax`] first to expand Diaryx custom syntax.
@@ -26,6 +30,12 @@ pub fn markdown_to_html(preprocessed_markdown: &str) -> String {
 /// Pre-process custom markdown syntax (highlights, spoilers, HTML embeds) into
 /// raw HTML before passing to comrak. Skips fenced code blocks and inline code.
 pub fn preprocess_custom_syntax(markdown: &str) -> String {
+    preprocess_custom_syntax_with_hooks(markdown, &RenderHooks::new())
+}
+
+/// [`preprocess_custom_syntax`], also expanding the plugin syntax in `hooks`.
+/// Built-in syntax takes precedence.
+pub fn preprocess_custom_syntax_with_hooks(markdown: &str, hooks: &RenderHooks) -> String {
     let bytes = markdown.as_bytes();
     let len = bytes.len();
     let mut out = String::with_capacity(len);
@@ -103,6 +113,16 @@ pub fn preprocess_custom_syntax(markdown: &str) -> String {
             }
         }
 
+        // Try plugin syntax (math, drawings, ...)
+        if !hooks.is_empty() {
+            let prev = markdown[..i].chars().next_back();
+            if let Some((html, consumed)) = hooks.expand(&markdown[i..], prev) {
+                out.push_str(&html);
+                i += consumed;
+                continue;
+            }
+        }
+
         out.push(markdown[i..].chars().next().unwrap());
         i += markdown[i..].chars().next().unwrap().len_utf8();
     }
@@ -1,5 +1,6 @@
 //! Page-shell helpers: navigation, breadcrumbs, SEO meta, feed/sitemap/robots
-//! generation, and small HTML/XML escaping utilities.
+//! generation, Webmention discovery and display, and small HTML/XML escaping
+//! utilities.
 //!
 //! These are pure functions over the value types in [`crate::types`]. The page
 //! *assembly* (full `<html>` document, theme/CSS/favicon) still lives in the
@@ -9,7 +10,7 @@ use diaryx_core::entry::slugify;
 use diaryx_core::link_parser;
 
 use crate::links::root_prefix;
-use crate::types::{NavLink, PublishedPage, SiteNavNode, SiteNavigation};
+use crate::types::{FeedEntry, NavLink, PageMention, PublishedPage, SiteNavNode, SiteNavigation};
 
 /// Escape HTML special characters.
 pub fn html_escape(s: &str) -> String {
@@ -265,6 +266,41 @@ pub fn generate_feed_link_tags(root_prefix: &str) -> String {
     )
 }
 
+/// Generate the `<link>` tag advertising a page's Webmention endpoint.
+pub fn generate_webmention_link_tag(endpoint: &str) -> String {
+    format!(
+        r#"<link rel="webmention" href="{}">"#,
+        html_escape(endpoint)
+    )
+}
+
+/// Render the list of mentions shown under a page's content. Empty when
+/// there are none.
+pub fn render_webmentions(mentions: &[PageMention]) -> String {
+    if mentions.is_empty() {
+        return String::new();
+    }
+    let items: Vec<String> = mentions
+        .iter()
+        .map(|m| {
+            let label = m
+                .title
+                .as_deref()
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or(&m.source);
+            format!(
+                r#"<li><a href="{}" rel="nofollow ugc">{}</a></li>"#,
+                html_escape(&m.source),
+                html_escape(label)
+            )
+        })
+        .collect();
+    format!(
+        r#"<section class="webmentions" aria-label="Mentions"><h2>Mentions</h2><ul>{}</ul></section>"#,
+        items.join("")
+    )
+}
+
 /// Generate a sitemap.xml from published pages.
 pub fn generate_sitemap(pages: &[PublishedPage], base_url: &str) -> String {
     let base = base_url.trim_end_matches('/');
@@ -314,29 +350,47 @@ pub fn generate_robots_txt(base_url: &str, is_public: bool) -> String {
     }
 }
 
-/// Generate an Atom 1.0 feed.
-pub fn generate_atom_feed(
-    pages: &[PublishedPage],
-    site_title: &str,
-    base_url: &str,
-    site_description: &str,
-    site_author: &str,
-) -> String {
-    let base = base_url.trim_end_matches('/');
-
-    // Feed items: non-root leaf pages, not hidden from feed
+/// Feed items: non-root leaf pages not hidden from feed, newest first by
+/// updated/created date.
+fn feed_items(pages: &[PublishedPage]) -> Vec<&PublishedPage> {
     let mut items: Vec<&PublishedPage> = pages
         .iter()
         .filter(|p| !p.is_root && p.contents_links.is_empty() && !p.hide_from_feed)
         .collect();
-
-    // Sort by created/updated descending
     items.sort_by(|a, b| {
         let date_a = a.updated.as_deref().or(a.created.as_deref()).unwrap_or("");
         let date_b = b.updated.as_deref().or(b.created.as_deref()).unwrap_or("");
         date_b.cmp(date_a)
     });
+    items
+}
+
+/// Every page the feeds would list, newest first, for consumers that
+/// syndicate the site some other way.
+pub fn feed_entries(pages: &[PublishedPage]) -> Vec<FeedEntry> {
+    feed_items(pages)
+        .into_iter()
+        .map(|page| FeedEntry {
+            dest_filename: page.dest_filename.clone(),
+            title: page.title.clone(),
+            summary: strip_html_truncate(&page.rendered_body, 280),
+            published: page.created.clone(),
+            updated: page.updated.clone(),
+        })
+        .collect()
+}
+
+/// Generate an Atom 1.0 feed.
+pub fn generate_atom_feed(
+    pages: &[PublishedPage],
+    site_title: &str,
+    base_url: &str,
+    site_description: &str,
+    site_author: &str,
+) -> String {
+    let base = base_url.trim_end_matches('/');
 
+    let mut items = feed_items(pages);
     items.truncate(50);
 
     let feed_updated = items
@@ -424,17 +478,7 @@ pub fn generate_rss_feed(
 ) -> String {
     let base = base_url.trim_end_matches('/');
 
-    let mut items: Vec<&PublishedPage> = pages
-        .iter()
-        .filter(|p| !p.is_root && p.contents_links.is_empty() && !p.hide_from_feed)
-        .collect();
-
-    items.sort_by(|a, b| {
-        let date_a = a.updated.as_deref().or(a.created.as_deref()).unwrap_or("");
-        let date_b = b.updated.as_deref().or(b.created.as_deref()).unwrap_or("");
-        date_b.cmp(date_a)
-    });
-
+    let mut items = feed_items(pages);
     items.truncate(50);
 
     let last_build = items
@@ -670,6 +714,29 @@ mod tests {
         assert!(!atom.contains("<title>Hidden</title>"));
     }
 
+    #[test]
+    fn test_feed_entries_newest_first() {
+        let root = make_page("index.html", "Home", true);
+        let mut old = make_page("old.html", "Old", false);
+        old.created = Some("2024-01-01T00:00:00Z".into());
+        let mut new = make_page("new.html", "New", false);
+        new.created = Some("2024-06-01T00:00:00Z".into());
+        new.rendered_body = "<p>Second <strong>post</strong></p>".into();
+        let mut hidden = make_page("hidden.html", "Hidden", false);
+        hidden.hide_from_feed = true;
+
+        let entries = feed_entries(&[root, old, hidden, new]);
+
+        let dests: Vec<_> = entries.iter().map(|e| e.dest_filename.as_str()).collect();
+        assert_eq!(dests, ["new.html", "old.html"]);
+        assert_eq!(entries[0].title, "New");
+        assert_eq!(entries[0].summary, "Second post");
+        assert_eq!(
+            entries[0].published.as_deref(),
+            Some("2024-06-01T00:00:00Z")
+        );
+    }
+
     #[test]
     fn test_rss_feed_structure() {
         let root = make_page("index.html", "Home", true);
@@ -691,6 +758,33 @@ mod tests {
         assert!(rss.contains("<pubDate>2024-01-15</pubDate>"));
     }
 
+    #[test]
+    fn test_webmention_link_and_list() {
+        assert_eq!(
+            generate_webmention_link_tag("https://sync.example.com/api/namespaces/n1/webmention"),
+            r#"<link rel="webmention" href="https://sync.example.com/api/namespaces/n1/webmention">"#
+        );
+        assert_eq!(render_webmentions(&[]), "");
+
+        let html = render_webmentions(&[
+            PageMention {
+                source: "https://a.example/reply".to_string(),
+                title: Some("A <reply>".to_string()),
+            },
+            PageMention {
+                source: "https://b.example/post".to_string(),
+                title: None,
+            },
+        ]);
+        assert!(html.contains(r#"class="webmentions""#));
+        assert!(
+            html.contains(
+                r#"href="https://a.example/reply" rel="nofollow ugc">A &lt;reply&gt;</a>"#
+            )
+        );
+        assert!(html.contains(">https://b.example/post</a>"));
+    }
+
     #[test]
     fn test_feed_links() {
         let links = generate_feed_link_tags("");
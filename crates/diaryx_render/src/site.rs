@@ -4,8 +4,9 @@
 //! whole site, mirroring the publish plugin's page-derivation rules so the
 //! server can render-on-write. The stored sources are already audience-scoped
 //! and visibility-filtered (Layer 2), but pre-template — so the per-page
-//! pipeline here is: parse → template → preprocess → comrak → transform_links →
-//! page assembly. Gated behind the `templating` feature.
+//! pipeline here is: parse → template → preprocess (built-in syntax plus
+//! plugin [`RenderHooks`]) → comrak → transform_links → page assembly. Gated
+//! behind the `templating` feature.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -15,9 +16,10 @@ use diaryx_core::link_parser;
 use diaryx_core::yaml::Value as YamlValue;
 use indexmap::IndexMap;
 
+use crate::hooks::RenderHooks;
 use crate::html::{HtmlRenderer, SiteStyle};
 use crate::nav::{build_site_nav_tree, nav_for_page};
-use crate::types::{NavLink, PublishedPage};
+use crate::types::{FeedEntry, NavLink, PageMention, PublishedPage};
 use crate::{links, markdown, page, template};
 
 /// A stored markdown source to render.
@@ -56,6 +58,14 @@ pub struct SiteOptions {
     pub generate_feeds: bool,
     /// Caller-supplied appearance (theme/custom CSS/custom favicon).
     pub style: SiteStyle,
+    /// Webmention endpoint advertised by every page; `None` for sites that
+    /// don't accept mentions.
+    pub webmention_endpoint: Option<String>,
+    /// Approved mentions to show under each page, keyed by dest filename.
+    pub mentions: HashMap<String, Vec<PageMention>>,
+    /// Plugin syntax to expand into HTML; their CSS is appended to
+    /// `style.css`.
+    pub render_hooks: RenderHooks,
 }
 
 impl Default for SiteOptions {
@@ -67,6 +77,9 @@ impl Default for SiteOptions {
             generate_seo: true,
             generate_feeds: true,
             style: SiteStyle::default(),
+            webmention_endpoint: None,
+            mentions: HashMap::new(),
+            render_hooks: RenderHooks::new(),
         }
     }
 }
@@ -78,11 +91,23 @@ pub struct SiteRender {
     pub pages: Vec<RenderedPage>,
     /// `(filename, bytes)` assets to write alongside the pages.
     pub assets: Vec<(String, Vec<u8>)>,
+    /// The pages the feeds list, newest first. Filled in even when no feed
+    /// files were written.
+    pub entries: Vec<FeedEntry>,
 }
 
 /// Reconstruct [`PublishedPage`]s from stored sources, fully rendering each
 /// page's `rendered_body` (template → preprocess → comrak → link rewrite).
 pub fn build_pages(sources: &[SourceDoc], audience: Option<&str>) -> Vec<PublishedPage> {
+    build_pages_with_hooks(sources, audience, &RenderHooks::new())
+}
+
+/// [`build_pages`], expanding plugin syntax through `hooks`.
+pub fn build_pages_with_hooks(
+    sources: &[SourceDoc],
+    audience: Option<&str>,
+    hooks: &RenderHooks,
+) -> Vec<PublishedPage> {
     // Map sanitized canonical `.md` path → output `.html` filename (root →
     // index.html). Sources are keyed by their workspace-relative path; we
     // sanitize keys so that frontmatter links (which may carry unsanitized
@@ -113,13 +138,13 @@ pub fn build_pages(sources: &[SourceDoc], audience: Option<&str>) -> Vec<Publish
 
     sources
         .iter()
-        .map(|s| build_page(s, audience, &path_to_filename, &title_map))
+        .map(|s| build_page(s, audience, &path_to_filename, &title_map, hooks))
         .collect()
 }
 
 /// Reconstruct and render a whole site from stored sources.
 pub fn render_site(sources: &[SourceDoc], opts: &SiteOptions) -> SiteRender {
-    let pages = build_pages(sources, opts.audience.as_deref());
+    let pages = build_pages_with_hooks(sources, opts.audience.as_deref(), &opts.render_hooks);
 
     let renderer = HtmlRenderer::with_style(opts.style.clone());
     let nav_tree = build_site_nav_tree(&pages);
@@ -139,12 +164,34 @@ pub fn render_site(sources: &[SourceDoc], opts: &SiteOptions) -> SiteRender {
         } else {
             String::new()
         };
-        let feeds = if opts.generate_feeds {
+        let mut head_links = if opts.generate_feeds {
             page::generate_feed_link_tags(&links::root_prefix(&p.dest_filename))
         } else {
             String::new()
         };
-        let html = renderer.render_page_with_context(p, &site_title, false, &nav, &seo, &feeds);
+        if let Some(endpoint) = &opts.webmention_endpoint {
+            if !head_links.is_empty() {
+                head_links.push_str("\n    ");
+            }
+            head_links.push_str(&page::generate_webmention_link_tag(endpoint));
+        }
+        let html = match opts.mentions.get(&p.dest_filename) {
+            Some(mentions) if !mentions.is_empty() => {
+                let mut with_mentions = p.clone();
+                with_mentions
+                    .rendered_body
+                    .push_str(&page::render_webmentions(mentions));
+                renderer.render_page_with_context(
+                    &with_mentions,
+                    &site_title,
+                    false,
+                    &nav,
+                    &seo,
+                    &head_links,
+                )
+            }
+            _ => renderer.render_page_with_context(p, &site_title, false, &nav, &seo, &head_links),
+        };
         out_pages.push(RenderedPage {
             dest_filename: p.dest_filename.clone(),
             html,
@@ -154,6 +201,12 @@ pub fn render_site(sources: &[SourceDoc], opts: &SiteOptions) -> SiteRender {
 
     // Static assets (style.css + favicon) always; supplementary files need a base URL.
     let mut assets = renderer.static_assets();
+    let hook_css = opts.render_hooks.css();
+    if !hook_css.is_empty()
+        && let Some((_, css)) = assets.iter_mut().find(|(name, _)| name == "style.css")
+    {
+        css.extend_from_slice(hook_css.as_bytes());
+    }
     if !base_url.is_empty() {
         if opts.generate_seo {
             assets.push((
@@ -183,6 +236,7 @@ pub fn render_site(sources: &[SourceDoc], opts: &SiteOptions) -> SiteRender {
     SiteRender {
         pages: out_pages,
         assets,
+        entries: page::feed_entries(&pages),
     }
 }
 
@@ -193,6 +247,7 @@ fn build_page(
     audience: Option<&str>,
     path_to_filename: &HashMap<PathBuf, String>,
     title_map: &HashMap<PathBuf, String>,
+    hooks: &RenderHooks,
 ) -> PublishedPage {
     let parsed = frontmatter::parse_or_empty(&s.markdown).unwrap_or(frontmatter::ParsedFile {
         frontmatter: IndexMap::new(),
@@ -240,7 +295,7 @@ fn build_page(
 
     // Markdown → HTML, then rewrite internal `.md` links. The empty workspace
     // dir means canonical paths are used directly as `path_to_filename` keys.
-    let preprocessed = markdown::preprocess_custom_syntax(&rendered_body);
+    let preprocessed = markdown::preprocess_custom_syntax_with_hooks(&rendered_body, hooks);
     let converted = markdown::markdown_to_html(&preprocessed);
     let final_html = links::transform_links(
         &converted,
@@ -490,6 +545,46 @@ mod tests {
         assert!(orphan_page.parent_link.is_none());
     }
 
+    #[test]
+    fn render_site_advertises_webmention_endpoint_and_lists_mentions() {
+        let index = "---\ntitle: Home\n---\nHi.\n";
+        let post = "---\ntitle: Post\npart_of: \"/index.md\"\n---\nBody.\n";
+        let sources = vec![src("index.md", index, true), src("post.md", post, false)];
+
+        let mut opts = SiteOptions {
+            webmention_endpoint: Some(
+                "https://sync.example.com/api/namespaces/n1/webmention".into(),
+            ),
+            ..SiteOptions::default()
+        };
+        opts.mentions.insert(
+            "post.html".to_string(),
+            vec![PageMention {
+                source: "https://elsewhere.example/reply".to_string(),
+                title: Some("A reply".to_string()),
+            }],
+        );
+        let out = render_site(&sources, &opts);
+
+        let html = |dest: &str| {
+            out.pages
+                .iter()
+                .find(|p| p.dest_filename == dest)
+                .unwrap()
+                .html
+                .clone()
+        };
+        let post_html = html("post.html");
+        assert!(post_html.contains(r#"<link rel="webmention" href="https://sync.example.com/api/namespaces/n1/webmention">"#));
+        assert!(post_html.contains("https://elsewhere.example/reply"));
+        let home_html = html("index.html");
+        assert!(home_html.contains(r#"rel="webmention""#));
+        assert!(!home_html.contains("class=\"webmentions\""));
+
+        let plain = render_site(&sources, &SiteOptions::default());
+        assert!(!plain.pages[0].html.contains(r#"rel="webmention""#));
+    }
+
     #[test]
     fn render_site_produces_pages_nav_and_assets() {
         let index = "---\ntitle: Home\ncontents:\n  - \"[Child](/child.md)\"\n---\nHi.\n";
@@ -511,5 +606,10 @@ mod tests {
 
         // assets include the stylesheet
         assert!(out.assets.iter().any(|(n, _)| n == "style.css"));
+
+        // the child is a feed entry even though no feed files were written
+        assert!(!out.assets.iter().any(|(n, _)| n == "feed.xml"));
+        assert_eq!(out.entries.len(), 1);
+        assert_eq!(out.entries[0].dest_filename, "child.html");
     }
 }
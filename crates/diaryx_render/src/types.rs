@@ -130,6 +130,30 @@ pub struct SiteNavigation {
     pub breadcrumbs: Vec<NavLink>,
 }
 
+/// A page as the site's feeds list it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedEntry {
+    /// Output filename, relative to the site root
+    pub dest_filename: String,
+    /// Page title
+    pub title: String,
+    /// Plain-text excerpt of the body
+    pub summary: String,
+    /// Frontmatter `created` date, as written
+    pub published: Option<String>,
+    /// Frontmatter `updated` date, as written
+    pub updated: Option<String>,
+}
+
+/// An approved Webmention shown under a page: another site that links to it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PageMention {
+    /// URL of the page that links here
+    pub source: String,
+    /// Title of the linking page, when it has one
+    pub title: Option<String>,
+}
+
 /// Result of a publishing operation.
 #[derive(Debug, Serialize)]
 pub struct PublishResult {
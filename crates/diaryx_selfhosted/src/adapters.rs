@@ -1,18 +1,22 @@
-use crate::db::{AuthRepo, NamespaceRepo};
+use crate::db::{ActivityPubRepo, AuditRepo, AuthRepo, CommentRepo, NamespaceRepo, WebmentionRepo};
 use async_trait::async_trait;
 use diaryx_server::domain::{
-    ArkIndexEntry as CoreArkIndexEntry, AudienceInfo as CoreAudienceInfo,
-    AuthSessionInfo as CoreAuthSessionInfo, CustomDomainInfo as CoreCustomDomainInfo,
-    DeviceInfo as CoreDeviceInfo, NamespaceInfo as CoreNamespaceInfo,
-    NamespaceSessionInfo as CoreNamespaceSessionInfo, ObjectMeta as CoreObjectMeta,
+    AccessTokenInfo as CoreAccessTokenInfo, ActorKeyPair, ArkIndexEntry as CoreArkIndexEntry,
+    ArkVersionEntry as CoreArkVersionEntry, AudienceInfo as CoreAudienceInfo, AuditEvent,
+    AuditScope, AuthSessionInfo as CoreAuthSessionInfo, CommentInfo, CommentStatus,
+    CustomDomainInfo as CoreCustomDomainInfo, DeviceInfo as CoreDeviceInfo, FollowerInfo,
+    NamespaceInfo as CoreNamespaceInfo, NamespaceInviteInfo, NamespaceMemberInfo,
+    NamespaceSessionInfo as CoreNamespaceSessionInfo, ObjectMeta as CoreObjectMeta, OutboxItem,
     PasskeyChallengeInfo as CorePasskeyChallengeInfo,
-    PasskeyCredentialInfo as CorePasskeyCredentialInfo, UsageTotals as CoreUsageTotals,
-    UserInfo as CoreUserInfo, UserTier as CoreUserTier,
+    PasskeyCredentialInfo as CorePasskeyCredentialInfo, UsageEvent, UsageTotals as CoreUsageTotals,
+    UserInfo as CoreUserInfo, UserTier as CoreUserTier, WebhookDeliveryInfo, WebhookInfo,
+    WebmentionInfo, WebmentionStatus,
 };
 use diaryx_server::ports::{
-    ArkIndexStore, AuthSessionStore, AuthStore, BillingStore, DeviceStore, DomainMappingCache,
-    MagicLinkStore, NamespaceStore, ObjectMetaStore, PasskeyStore, ServerCoreError, SessionStore,
-    UserStore,
+    AccessTokenStore, ActivityPubStore, ArkIndexStore, AuditLogStore, AuthSessionStore, AuthStore,
+    BillingStore, CommentStore, DeviceStore, DomainMappingCache, MagicLinkStore,
+    NamespaceMemberStore, NamespaceStore, ObjectMetaStore, PasskeyStore, ServerCoreError,
+    SessionStore, UserStore, WebhookStore, WebmentionStore,
 };
 use serde_json::json;
 use std::sync::Arc;
@@ -414,6 +418,68 @@ impl PasskeyStore for NativePasskeyStore {
     }
 }
 
+#[derive(Clone)]
+pub struct NativeAccessTokenStore {
+    repo: Arc<AuthRepo>,
+}
+
+impl NativeAccessTokenStore {
+    pub fn new(repo: Arc<AuthRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl AccessTokenStore for NativeAccessTokenStore {
+    async fn create_access_token(
+        &self,
+        token: &CoreAccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.repo
+            .create_access_token(token, token_hash)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))
+    }
+
+    async fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<CoreAccessTokenInfo>, ServerCoreError> {
+        self.repo
+            .get_access_token_by_hash(token_hash)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))
+    }
+
+    async fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<CoreAccessTokenInfo>, ServerCoreError> {
+        self.repo
+            .list_access_tokens(user_id)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))
+    }
+
+    async fn delete_access_token(
+        &self,
+        user_id: &str,
+        token_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_access_token(user_id, token_id)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))
+    }
+
+    async fn touch_access_token(
+        &self,
+        token_id: &str,
+        used_at: i64,
+    ) -> Result<(), ServerCoreError> {
+        self.repo
+            .touch_access_token(token_id, used_at)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))
+    }
+}
+
 impl From<crate::db::PasskeyCredentialInfo> for CorePasskeyCredentialInfo {
     fn from(value: crate::db::PasskeyCredentialInfo) -> Self {
         Self {
@@ -655,6 +721,375 @@ impl NamespaceStore for NativeNamespaceStore {
     }
 }
 
+#[derive(Clone)]
+pub struct NativeNamespaceMemberStore {
+    repo: Arc<NamespaceRepo>,
+}
+
+impl NativeNamespaceMemberStore {
+    pub fn new(repo: Arc<NamespaceRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl NamespaceMemberStore for NativeNamespaceMemberStore {
+    async fn get_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<NamespaceMemberInfo>, ServerCoreError> {
+        Ok(self.repo.get_member(namespace_id, user_id))
+    }
+
+    async fn list_members(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        Ok(self.repo.list_members(namespace_id))
+    }
+
+    async fn list_user_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        Ok(self.repo.list_user_memberships(user_id))
+    }
+
+    async fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_member(member)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn remove_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .remove_member(namespace_id, user_id)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.repo
+            .create_invite(invite, token_hash)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn get_invite_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<NamespaceInviteInfo>, ServerCoreError> {
+        Ok(self.repo.get_invite_by_hash(token_hash))
+    }
+
+    async fn list_invites(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError> {
+        Ok(self.repo.list_invites(namespace_id))
+    }
+
+    async fn delete_invite(
+        &self,
+        namespace_id: &str,
+        invite_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_invite(namespace_id, invite_id)
+            .map_err(ServerCoreError::from)
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeWebhookStore {
+    repo: Arc<NamespaceRepo>,
+}
+
+impl NativeWebhookStore {
+    pub fn new(repo: Arc<NamespaceRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl WebhookStore for NativeWebhookStore {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookInfo,
+        secret: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.repo
+            .create_webhook(webhook, secret)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn get_webhook(&self, webhook_id: &str) -> Result<Option<WebhookInfo>, ServerCoreError> {
+        Ok(self.repo.get_webhook(webhook_id))
+    }
+
+    async fn get_webhook_secret(
+        &self,
+        webhook_id: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        Ok(self.repo.get_webhook_secret(webhook_id))
+    }
+
+    async fn list_webhooks(&self, namespace_id: &str) -> Result<Vec<WebhookInfo>, ServerCoreError> {
+        Ok(self.repo.list_webhooks(namespace_id))
+    }
+
+    async fn delete_webhook(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_webhook(namespace_id, webhook_id)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_delivery(delivery)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn get_delivery(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError> {
+        Ok(self.repo.get_delivery(delivery_id))
+    }
+
+    async fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        Ok(self.repo.list_deliveries(namespace_id, webhook_id, limit))
+    }
+
+    async fn list_due_deliveries(
+        &self,
+        now: i64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        Ok(self.repo.list_due_deliveries(now, limit))
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeAuditLogStore {
+    repo: Arc<AuditRepo>,
+}
+
+impl NativeAuditLogStore {
+    pub fn new(repo: Arc<AuditRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl AuditLogStore for NativeAuditLogStore {
+    async fn append_audit_event(&self, event: &AuditEvent) -> Result<(), ServerCoreError> {
+        self.repo.append_event(event).map_err(ServerCoreError::from)
+    }
+
+    async fn list_audit_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        Ok(self.repo.list_events(scope, before, limit))
+    }
+
+    async fn prune_audit_events(&self, cutoff: i64) -> Result<u64, ServerCoreError> {
+        self.repo
+            .prune_events(cutoff)
+            .map_err(ServerCoreError::from)
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeWebmentionStore {
+    repo: Arc<WebmentionRepo>,
+}
+
+impl NativeWebmentionStore {
+    pub fn new(repo: Arc<WebmentionRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl WebmentionStore for NativeWebmentionStore {
+    async fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_webmention(mention)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn get_webmention(&self, id: &str) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        Ok(self.repo.get_webmention(id))
+    }
+
+    async fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        Ok(self.repo.find_webmention(namespace_id, source, target))
+    }
+
+    async fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError> {
+        Ok(self.repo.list_webmentions(namespace_id, status, limit))
+    }
+
+    async fn delete_webmention(
+        &self,
+        namespace_id: &str,
+        id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_webmention(namespace_id, id)
+            .map_err(ServerCoreError::from)
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeCommentStore {
+    repo: Arc<CommentRepo>,
+}
+
+impl NativeCommentStore {
+    pub fn new(repo: Arc<CommentRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl CommentStore for NativeCommentStore {
+    async fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_comment(comment)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn get_comment(&self, id: &str) -> Result<Option<CommentInfo>, ServerCoreError> {
+        Ok(self.repo.get_comment(id))
+    }
+
+    async fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError> {
+        Ok(self
+            .repo
+            .list_comments(namespace_id, object_key, status, limit))
+    }
+
+    async fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_comment(namespace_id, id)
+            .map_err(ServerCoreError::from)
+    }
+}
+
+#[derive(Clone)]
+pub struct NativeActivityPubStore {
+    repo: Arc<ActivityPubRepo>,
+}
+
+impl NativeActivityPubStore {
+    pub fn new(repo: Arc<ActivityPubRepo>) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl ActivityPubStore for NativeActivityPubStore {
+    async fn get_actor_key(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<ActorKeyPair>, ServerCoreError> {
+        Ok(self.repo.get_actor_key(namespace_id))
+    }
+
+    async fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), ServerCoreError> {
+        self.repo
+            .insert_actor_key(key)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_follower(follower)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn delete_follower(
+        &self,
+        namespace_id: &str,
+        actor: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_follower(namespace_id, actor)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn list_followers(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<FollowerInfo>, ServerCoreError> {
+        Ok(self.repo.list_followers(namespace_id))
+    }
+
+    async fn count_followers(&self, namespace_id: &str) -> Result<u64, ServerCoreError> {
+        Ok(self.repo.count_followers(namespace_id))
+    }
+
+    async fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), ServerCoreError> {
+        self.repo
+            .upsert_outbox_item(item)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn list_outbox_items(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<OutboxItem>, ServerCoreError> {
+        Ok(self.repo.list_outbox_items(namespace_id))
+    }
+
+    async fn delete_outbox_item(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+    ) -> Result<bool, ServerCoreError> {
+        self.repo
+            .delete_outbox_item(namespace_id, object_key)
+            .map_err(ServerCoreError::from)
+    }
+}
+
 #[derive(Clone)]
 pub struct NativeSessionStore {
     repo: Arc<NamespaceRepo>,
@@ -963,6 +1398,12 @@ impl ObjectMetaStore for NativeObjectMetaStore {
         Ok(self.repo.get_usage_totals(user_id).into())
     }
 
+    async fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, ServerCoreError> {
+        self.repo
+            .list_usage_events(user_id)
+            .map_err(ServerCoreError::from)
+    }
+
     async fn get_namespace_usage_totals(
         &self,
         user_id: &str,
@@ -1161,6 +1602,39 @@ impl ArkIndexStore for NativeArkIndexStore {
             )
             .collect())
     }
+
+    async fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.repo
+            .record_ark_version(workspace_ark, file_ark, version, object_key)
+            .map_err(ServerCoreError::from)
+    }
+
+    async fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<CoreArkVersionEntry>, ServerCoreError> {
+        Ok(self
+            .repo
+            .list_ark_versions(workspace_ark, file_ark)
+            .into_iter()
+            .map(
+                |(workspace_ark, file_ark, version, object_key, created_at)| CoreArkVersionEntry {
+                    workspace_ark,
+                    file_ark,
+                    version,
+                    object_key,
+                    created_at,
+                },
+            )
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -1232,13 +1706,13 @@ mod tests {
         let service = DomainService::new(&namespace_store, &cache);
 
         let domain = service
-            .register_domain("workspace:test", "blog.example.com", "public")
+            .register_domain("workspace:test", "blog.example.com", "public", &user_id)
             .await
             .expect("domain registered");
         assert_eq!(domain.audience_name, "public");
 
         service
-            .remove_domain("workspace:test", "blog.example.com")
+            .remove_domain("workspace:test", "blog.example.com", &user_id)
             .await
             .expect("domain removed");
     }
@@ -0,0 +1,337 @@
+//! `diaryx_selfhosted admin ...` — a command-line client for the operator
+//! admin API (`handlers::admin`).
+//!
+//! The server must be running with `ADMIN_SECRET` set; the CLI reads the same
+//! variable and sends it as a bearer token. `--url` (or `DIARYX_ADMIN_URL`)
+//! points it at the instance, defaulting to `http://127.0.0.1:$PORT`. Every
+//! command prints the JSON response.
+
+use reqwest::Method;
+use serde_json::Value;
+
+/// Name of the admin subcommand.
+pub const ADMIN_COMMAND: &str = "admin";
+
+/// Usage text printed when the arguments don't parse.
+pub const ADMIN_USAGE: &str = "usage: diaryx_selfhosted admin [--url URL] <command>
+
+commands:
+  health
+  users [--query Q] [--limit N] [--offset N]
+  user <user_id>
+  set-tier <user_id> <free|plus>
+  set-limits <user_id> [--devices N] [--attachment-bytes N] [--workspaces N] [--sites N]
+  revoke-sessions <user_id>
+  revoke-device <user_id> <device_id>
+  namespaces [--owner USER_ID] [--limit N] [--offset N]
+  takedown <namespace_id> [--reason TEXT]";
+
+/// One admin API call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    Health,
+    Users {
+        query: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    },
+    User {
+        user_id: String,
+    },
+    SetTier {
+        user_id: String,
+        tier: String,
+    },
+    SetLimits {
+        user_id: String,
+        device_limit: Option<u32>,
+        attachment_limit_bytes: Option<u64>,
+        workspace_limit: Option<u32>,
+        published_site_limit: Option<u32>,
+    },
+    RevokeSessions {
+        user_id: String,
+    },
+    RevokeDevice {
+        user_id: String,
+        device_id: String,
+    },
+    Namespaces {
+        owner: Option<String>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    },
+    Takedown {
+        namespace_id: String,
+        reason: Option<String>,
+    },
+}
+
+/// Arguments accepted by `admin`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminArgs {
+    /// Base URL of the server; `None` uses [`default_admin_url`].
+    pub url: Option<String>,
+    pub command: AdminCommand,
+}
+
+impl AdminArgs {
+    /// Parse the arguments following the subcommand name.
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut url = None;
+        let mut positional = Vec::new();
+        let mut flags: Vec<(String, String)> = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("--{name} needs a value"))?
+                    .as_ref()
+                    .to_string();
+                if name == "url" {
+                    url = Some(value);
+                } else {
+                    flags.push((name.to_string(), value));
+                }
+            } else {
+                positional.push(arg.to_string());
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let name = positional
+            .next()
+            .ok_or_else(|| "missing admin command".to_string())?;
+        let mut next_arg = |what: &str| {
+            positional
+                .next()
+                .ok_or_else(|| format!("{name} needs a {what}"))
+        };
+        let command = match name.as_str() {
+            "health" => AdminCommand::Health,
+            "users" => AdminCommand::Users {
+                query: take_flag(&mut flags, "query"),
+                limit: parse_flag(&mut flags, "limit")?,
+                offset: parse_flag(&mut flags, "offset")?,
+            },
+            "user" => AdminCommand::User {
+                user_id: next_arg("user ID")?,
+            },
+            "set-tier" => AdminCommand::SetTier {
+                user_id: next_arg("user ID")?,
+                tier: next_arg("tier")?,
+            },
+            "set-limits" => AdminCommand::SetLimits {
+                user_id: next_arg("user ID")?,
+                device_limit: parse_flag(&mut flags, "devices")?,
+                attachment_limit_bytes: parse_flag(&mut flags, "attachment-bytes")?,
+                workspace_limit: parse_flag(&mut flags, "workspaces")?,
+                published_site_limit: parse_flag(&mut flags, "sites")?,
+            },
+            "revoke-sessions" => AdminCommand::RevokeSessions {
+                user_id: next_arg("user ID")?,
+            },
+            "revoke-device" => AdminCommand::RevokeDevice {
+                user_id: next_arg("user ID")?,
+                device_id: next_arg("device ID")?,
+            },
+            "namespaces" => AdminCommand::Namespaces {
+                owner: take_flag(&mut flags, "owner"),
+                limit: parse_flag(&mut flags, "limit")?,
+                offset: parse_flag(&mut flags, "offset")?,
+            },
+            "takedown" => AdminCommand::Takedown {
+                namespace_id: next_arg("namespace ID")?,
+                reason: take_flag(&mut flags, "reason"),
+            },
+            other => return Err(format!("unknown admin command: {other}")),
+        };
+
+        if let Some(extra) = positional.next() {
+            return Err(format!("unexpected argument: {extra}"));
+        }
+        if let Some((flag, _)) = flags.first() {
+            return Err(format!("unknown argument: --{flag}"));
+        }
+        Ok(Self { url, command })
+    }
+}
+
+fn take_flag(flags: &mut Vec<(String, String)>, name: &str) -> Option<String> {
+    let pos = flags.iter().position(|(flag, _)| flag == name)?;
+    Some(flags.remove(pos).1)
+}
+
+fn parse_flag<T: std::str::FromStr>(
+    flags: &mut Vec<(String, String)>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    take_flag(flags, name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("invalid --{name}: {value}"))
+        })
+        .transpose()
+}
+
+/// `DIARYX_ADMIN_URL`, else the local server on `PORT` (default 3030).
+pub fn default_admin_url() -> String {
+    std::env::var("DIARYX_ADMIN_URL").unwrap_or_else(|_| {
+        let port = std::env::var("PORT").unwrap_or_else(|_| "3030".to_string());
+        format!("http://127.0.0.1:{port}")
+    })
+}
+
+/// Send `command` to the admin API at `base_url` and return the response
+/// body (`null` for empty responses). Non-2xx responses become errors.
+pub async fn run_admin_command(
+    client: &reqwest::Client,
+    base_url: &str,
+    admin_secret: &str,
+    command: &AdminCommand,
+) -> Result<Value, String> {
+    let mut url = reqwest::Url::parse(base_url).map_err(|e| format!("invalid --url: {e}"))?;
+    let mut query: Vec<(&str, String)> = Vec::new();
+    let mut body = None;
+    let (method, segments): (Method, Vec<&str>) = match command {
+        AdminCommand::Health => (Method::GET, vec!["health"]),
+        AdminCommand::Users {
+            query: q,
+            limit,
+            offset,
+        } => {
+            push_query(&mut query, "q", q);
+            push_query(&mut query, "limit", limit);
+            push_query(&mut query, "offset", offset);
+            (Method::GET, vec!["users"])
+        }
+        AdminCommand::User { user_id } => (Method::GET, vec!["users", user_id]),
+        AdminCommand::SetTier { user_id, tier } => {
+            body = Some(serde_json::json!({ "tier": tier }));
+            (Method::PUT, vec!["users", user_id, "tier"])
+        }
+        AdminCommand::SetLimits {
+            user_id,
+            device_limit,
+            attachment_limit_bytes,
+            workspace_limit,
+            published_site_limit,
+        } => {
+            body = Some(serde_json::json!({
+                "device_limit": device_limit,
+                "attachment_limit_bytes": attachment_limit_bytes,
+                "workspace_limit": workspace_limit,
+                "published_site_limit": published_site_limit,
+            }));
+            (Method::PUT, vec!["users", user_id, "limits"])
+        }
+        AdminCommand::RevokeSessions { user_id } => {
+            (Method::DELETE, vec!["users", user_id, "sessions"])
+        }
+        AdminCommand::RevokeDevice { user_id, device_id } => {
+            (Method::DELETE, vec!["users", user_id, "devices", device_id])
+        }
+        AdminCommand::Namespaces {
+            owner,
+            limit,
+            offset,
+        } => {
+            push_query(&mut query, "owner", owner);
+            push_query(&mut query, "limit", limit);
+            push_query(&mut query, "offset", offset);
+            (Method::GET, vec!["namespaces"])
+        }
+        AdminCommand::Takedown {
+            namespace_id,
+            reason,
+        } => {
+            push_query(&mut query, "reason", reason);
+            (Method::DELETE, vec!["namespaces", namespace_id])
+        }
+    };
+
+    url.path_segments_mut()
+        .map_err(|_| "invalid --url: not a base URL".to_string())?
+        .pop_if_empty()
+        .extend(["api", "admin"])
+        .extend(segments);
+
+    let mut request = client
+        .request(method, url)
+        .bearer_auth(admin_secret)
+        .query(&query);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{status}: {text}"));
+    }
+    if text.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&text).map_err(|e| format!("invalid response: {e}"))
+}
+
+fn push_query<T: ToString>(query: &mut Vec<(&str, String)>, name: &'static str, value: &Option<T>) {
+    if let Some(value) = value {
+        query.push((name, value.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AdminArgs, AdminCommand};
+
+    #[test]
+    fn parses_admin_commands() {
+        let args =
+            AdminArgs::parse(["--url", "http://sync:3030", "users", "--query", "bob"]).unwrap();
+        assert_eq!(args.url.as_deref(), Some("http://sync:3030"));
+        assert_eq!(
+            args.command,
+            AdminCommand::Users {
+                query: Some("bob".to_string()),
+                limit: None,
+                offset: None,
+            }
+        );
+
+        let args = AdminArgs::parse(["takedown", "ns-1", "--reason", "abuse"]).unwrap();
+        assert_eq!(
+            args.command,
+            AdminCommand::Takedown {
+                namespace_id: "ns-1".to_string(),
+                reason: Some("abuse".to_string()),
+            }
+        );
+
+        let args = AdminArgs::parse(["set-limits", "u1", "--devices", "5"]).unwrap();
+        assert_eq!(
+            args.command,
+            AdminCommand::SetLimits {
+                user_id: "u1".to_string(),
+                device_limit: Some(5),
+                attachment_limit_bytes: None,
+                workspace_limit: None,
+                published_site_limit: None,
+            }
+        );
+
+        assert!(AdminArgs::parse(Vec::<String>::new()).is_err());
+        assert!(AdminArgs::parse(["user"]).is_err());
+        assert!(AdminArgs::parse(["users", "--limit", "many"]).is_err());
+        assert!(AdminArgs::parse(["health", "--force", "1"]).is_err());
+        assert!(AdminArgs::parse(["health", "extra"]).is_err());
+        assert!(AdminArgs::parse(["reboot"]).is_err());
+    }
+}
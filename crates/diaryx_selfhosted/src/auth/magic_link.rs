@@ -3,6 +3,10 @@ use crate::adapters::{
 };
 use crate::config::Config;
 use crate::db::AuthRepo;
+use diaryx_server::ports::{
+    AuditLogStore, AuthSessionStore, DeviceStore, MagicLinkStore, UserStore,
+};
+use diaryx_server::use_cases::audit::AuditRecorder;
 use diaryx_server::use_cases::auth::{AuthConfig, AuthError, AuthenticationService};
 
 // Re-export core types so they're accessible via `crate::auth::*`
@@ -12,12 +16,13 @@ use std::sync::Arc;
 /// Magic link authentication service.
 ///
 /// Thin wrapper around the portable `AuthenticationService` that provides
-/// the native adapters and config.
+/// the storage adapters and config.
 pub struct MagicLinkService {
-    magic_link_store: Arc<NativeMagicLinkStore>,
-    user_store: Arc<NativeUserStore>,
-    device_store: Arc<NativeDeviceStore>,
-    session_store: Arc<NativeAuthSessionStore>,
+    magic_link_store: Arc<dyn MagicLinkStore>,
+    user_store: Arc<dyn UserStore>,
+    device_store: Arc<dyn DeviceStore>,
+    session_store: Arc<dyn AuthSessionStore>,
+    audit_store: Option<Arc<dyn AuditLogStore>>,
     auth_config: AuthConfig,
     app_base_url: String,
 }
@@ -76,11 +81,24 @@ impl From<AuthError> for MagicLinkError {
 
 impl MagicLinkService {
     pub fn new(repo: Arc<AuthRepo>, config: Arc<Config>) -> Self {
-        let magic_link_store = Arc::new(NativeMagicLinkStore::new(repo.clone()));
-        let user_store = Arc::new(NativeUserStore::new(repo.clone()));
-        let device_store = Arc::new(NativeDeviceStore::new(repo.clone()));
-        let session_store = Arc::new(NativeAuthSessionStore::new(repo));
+        Self::with_stores(
+            Arc::new(NativeMagicLinkStore::new(repo.clone())),
+            Arc::new(NativeUserStore::new(repo.clone())),
+            Arc::new(NativeDeviceStore::new(repo.clone())),
+            Arc::new(NativeAuthSessionStore::new(repo)),
+            &config,
+        )
+    }
 
+    /// Build the service over arbitrary store implementations (e.g. the
+    /// [`crate::postgres`] stores).
+    pub fn with_stores(
+        magic_link_store: Arc<dyn MagicLinkStore>,
+        user_store: Arc<dyn UserStore>,
+        device_store: Arc<dyn DeviceStore>,
+        session_store: Arc<dyn AuthSessionStore>,
+        config: &Config,
+    ) -> Self {
         let auth_config = AuthConfig {
             magic_link_expiry_minutes: config.magic_link_expiry_minutes,
             session_expiry_days: config.session_expiry_days,
@@ -92,19 +110,30 @@ impl MagicLinkService {
             user_store,
             device_store,
             session_store,
+            audit_store: None,
             auth_config,
             app_base_url: config.app_base_url.clone(),
         }
     }
 
+    /// Record sign-ins (by link, code or passkey) in the audit log.
+    pub fn with_audit(mut self, audit_store: Arc<dyn AuditLogStore>) -> Self {
+        self.audit_store = Some(audit_store);
+        self
+    }
+
     fn service(&self) -> AuthenticationService<'_> {
-        AuthenticationService::new(
+        let service = AuthenticationService::new(
             self.magic_link_store.as_ref(),
             self.user_store.as_ref(),
             self.device_store.as_ref(),
             self.session_store.as_ref(),
             &self.auth_config,
-        )
+        );
+        match &self.audit_store {
+            Some(store) => service.with_audit(AuditRecorder::new(store.as_ref())),
+            None => service,
+        }
     }
 
     /// Request a magic link for the given email.
@@ -1,9 +1,10 @@
 use diaryx_server::domain::{AuthContext, AuthSessionInfo, UserInfo};
-use diaryx_server::ports::{AuthSessionStore, AuthStore};
+use diaryx_server::ports::{AccessTokenStore, AuthSessionStore, AuthStore};
+use diaryx_server::use_cases::access_tokens::authorize_request;
 use diaryx_server::use_cases::auth::{SessionValidationService, extract_token};
 
 use axum::{
-    extract::FromRequestParts,
+    extract::{FromRequestParts, OriginalUri},
     http::{StatusCode, request::Parts},
 };
 use std::sync::Arc;
@@ -30,6 +31,7 @@ impl From<AuthContext> for AuthUser {
 pub struct AuthExtractor {
     auth_store: Arc<dyn AuthStore>,
     session_store: Arc<dyn AuthSessionStore>,
+    access_token_store: Option<Arc<dyn AccessTokenStore>>,
 }
 
 /// Extractor for optional authentication
@@ -49,11 +51,26 @@ impl AuthExtractor {
         Self {
             auth_store,
             session_store,
+            access_token_store: None,
         }
     }
 
+    /// Also accept personal access tokens, restricted to the routes and
+    /// namespaces their scopes allow.
+    pub fn with_access_tokens(mut self, access_token_store: Arc<dyn AccessTokenStore>) -> Self {
+        self.access_token_store = Some(access_token_store);
+        self
+    }
+
     /// Extract authentication from request headers, cookies, or query parameters.
-    pub async fn extract_auth(&self, parts: &Parts) -> Option<AuthUser> {
+    ///
+    /// Returns `Ok(None)` when the request carries no valid credentials, and
+    /// `Err` (403) when it carries an access token that doesn't cover the
+    /// requested route.
+    pub async fn extract_auth(
+        &self,
+        parts: &Parts,
+    ) -> Result<Option<AuthUser>, (StatusCode, &'static str)> {
         let authorization = parts
             .headers
             .get("Authorization")
@@ -76,11 +93,34 @@ impl AuthExtractor {
 
         let query = parts.uri.query();
 
-        let token = extract_token(authorization, cookie_header.as_deref(), query)?;
+        let Some(token) = extract_token(authorization, cookie_header.as_deref(), query) else {
+            return Ok(None);
+        };
 
-        let service =
+        let mut service =
             SessionValidationService::new(self.auth_store.as_ref(), self.session_store.as_ref());
-        service.validate(&token).await.ok().map(AuthUser::from)
+        if let Some(access_token_store) = &self.access_token_store {
+            service = service.with_access_tokens(access_token_store.as_ref());
+        }
+        let Ok(ctx) = service.validate(&token).await else {
+            return Ok(None);
+        };
+
+        if let Some(access_token) = &ctx.access_token {
+            // Nested routers see a stripped URI; the policy needs the full path.
+            let path = parts
+                .extensions
+                .get::<OriginalUri>()
+                .map_or_else(|| parts.uri.path(), |uri| uri.0.path());
+            if authorize_request(access_token, parts.method.as_str(), path).is_err() {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    "Access token not permitted for this request",
+                ));
+            }
+        }
+
+        Ok(Some(AuthUser::from(ctx)))
     }
 }
 
@@ -97,7 +137,7 @@ where
             .cloned()
             .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Auth not configured"))?;
 
-        Ok(OptionalAuth(extractor.extract_auth(parts).await))
+        Ok(OptionalAuth(extractor.extract_auth(parts).await?))
     }
 }
 
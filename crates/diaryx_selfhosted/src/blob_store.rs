@@ -1,65 +1,110 @@
-use crate::config::R2Config;
+use crate::config::{BlobStoreBackend, R2Config, S3Config};
 use async_trait::async_trait;
 use aws_config::BehaviorVersion;
 use aws_sdk_s3::Client;
+use aws_sdk_s3::config::http::HttpResponse;
 use aws_sdk_s3::config::{RequestChecksumCalculation, ResponseChecksumValidation};
-use aws_sdk_s3::error::ProvideErrorMetadata;
+use aws_sdk_s3::error::{ProvideErrorMetadata, SdkError};
 use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart, Delete, ObjectIdentifier};
 use aws_smithy_types::byte_stream::ByteStream;
 use diaryx_server::ports::ServerCoreError;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-pub use diaryx_server::ports::{BlobStore, MultipartCompletedPart};
+pub use diaryx_server::ports::{
+    BlobEntry, BlobStore, MultipartCompletedPart, PendingMultipartUpload,
+};
 
 fn internal_error(message: impl Into<String>) -> ServerCoreError {
     ServerCoreError::internal(message.into())
 }
 
+/// S3-compatible blob store (AWS S3, Cloudflare R2, MinIO, Garage, ...),
+/// signed with SigV4 through the AWS SDK.
 #[derive(Clone)]
-pub struct R2BlobStore {
+pub struct S3BlobStore {
     client: Client,
     bucket: String,
     prefix: String,
+    /// Backend name used in error messages ("R2" or "S3").
+    service: &'static str,
 }
 
-impl R2BlobStore {
-    pub async fn new(config: &R2Config) -> Result<Self, ServerCoreError> {
+impl S3BlobStore {
+    /// Connect to a generic S3-compatible endpoint.
+    pub async fn new(config: &S3Config) -> Result<Self, ServerCoreError> {
+        Ok(Self::connect("S3", config).await)
+    }
+
+    /// Connect to Cloudflare R2, deriving the endpoint from the account ID
+    /// unless one is given explicitly.
+    pub async fn r2(config: &R2Config) -> Result<Self, ServerCoreError> {
         let endpoint = config
             .endpoint
             .clone()
             .unwrap_or_else(|| format!("https://{}.r2.cloudflarestorage.com", config.account_id));
+        let s3 = S3Config {
+            endpoint,
+            region: "auto".to_string(),
+            bucket: config.bucket.clone(),
+            access_key_id: config.access_key_id.clone(),
+            secret_access_key: config.secret_access_key.clone(),
+            prefix: config.prefix.clone(),
+            force_path_style: true,
+        };
+        Ok(Self::connect("R2", &s3).await)
+    }
 
+    async fn connect(service: &'static str, config: &S3Config) -> Self {
         let sdk_config = aws_config::defaults(BehaviorVersion::latest())
-            .region(aws_sdk_s3::config::Region::new("auto"))
+            .region(aws_sdk_s3::config::Region::new(config.region.clone()))
             .credentials_provider(aws_sdk_s3::config::Credentials::new(
                 &config.access_key_id,
                 &config.secret_access_key,
                 None,
                 None,
-                "diaryx-r2",
+                "diaryx-s3",
             ))
-            .endpoint_url(endpoint)
+            .endpoint_url(&config.endpoint)
             .load()
             .await;
 
         let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
-            .force_path_style(true)
-            // R2 can reject optional checksum behavior used by newer S3 SDK defaults.
+            .force_path_style(config.force_path_style)
+            // R2 and most self-hosted S3 servers reject the optional checksum
+            // behavior used by newer S3 SDK defaults.
             .request_checksum_calculation(RequestChecksumCalculation::WhenRequired)
             .response_checksum_validation(ResponseChecksumValidation::WhenRequired)
             .build();
 
-        Ok(Self {
+        Self {
             client: Client::from_conf(s3_config),
             bucket: config.bucket.clone(),
             prefix: config.prefix.trim_matches('/').to_string(),
-        })
+            service,
+        }
     }
 }
 
+/// Whether an S3 error means the key does not exist. GET reports
+/// `NoSuchKey`, while HEAD only has the bare 404 status to go on.
+fn is_not_found<E: ProvideErrorMetadata>(err: &SdkError<E, HttpResponse>) -> bool {
+    if let Some(response) = err.raw_response()
+        && response.status().as_u16() == 404
+    {
+        return true;
+    }
+    let code = err.code().unwrap_or("");
+    let msg = err.to_string();
+    code == "NoSuchKey"
+        || code == "NotFound"
+        || msg.contains("NoSuchKey")
+        || msg.contains("404")
+        || msg.contains("Not Found")
+}
+
 #[async_trait]
-impl BlobStore for R2BlobStore {
+impl BlobStore for S3BlobStore {
     fn blob_key(&self, user_id: &str, hash: &str) -> String {
         if self.prefix.is_empty() {
             format!("u/{}/blobs/{}", user_id, hash)
@@ -96,7 +141,8 @@ impl BlobStore for R2BlobStore {
 
         req.send().await.map_err(|e| {
             internal_error(format!(
-                "R2 put failed for bucket={} key={}: code={} message={} raw={:?}",
+                "{} put failed for bucket={} key={}: code={} message={} raw={:?}",
+                self.service,
                 self.bucket,
                 key,
                 e.code().unwrap_or("unknown"),
@@ -119,22 +165,20 @@ impl BlobStore for R2BlobStore {
         match response {
             Ok(out) => {
                 let body = out.body.collect().await.map_err(|e| {
-                    internal_error(format!("R2 get body failed for {}: {}", key, e))
+                    internal_error(format!(
+                        "{} get body failed for {}: {}",
+                        self.service, key, e
+                    ))
                 })?;
                 Ok(Some(body.into_bytes().to_vec()))
             }
             Err(e) => {
-                let msg = e.to_string();
-                let code = e.code().unwrap_or("");
-                if code == "NoSuchKey"
-                    || msg.contains("NoSuchKey")
-                    || msg.contains("404")
-                    || msg.contains("Not Found")
-                {
+                if is_not_found(&e) {
                     Ok(None)
                 } else {
                     Err(internal_error(format!(
-                        "R2 get failed for bucket={} key={}: code={} message={} raw={:?}",
+                        "{} get failed for bucket={} key={}: code={} message={} raw={:?}",
+                        self.service,
                         self.bucket,
                         key,
                         e.code().unwrap_or("unknown"),
@@ -155,7 +199,8 @@ impl BlobStore for R2BlobStore {
             .await
             .map_err(|e| {
                 internal_error(format!(
-                    "R2 delete failed for bucket={} key={}: code={} message={} raw={:?}",
+                    "{} delete failed for bucket={} key={}: code={} message={} raw={:?}",
+                    self.service,
                     self.bucket,
                     key,
                     e.code().unwrap_or("unknown"),
@@ -178,17 +223,12 @@ impl BlobStore for R2BlobStore {
         match result {
             Ok(_) => Ok(true),
             Err(e) => {
-                let msg = e.to_string();
-                let code = e.code().unwrap_or("");
-                if code == "NoSuchKey"
-                    || msg.contains("NoSuchKey")
-                    || msg.contains("404")
-                    || msg.contains("Not Found")
-                {
+                if is_not_found(&e) {
                     Ok(false)
                 } else {
                     Err(internal_error(format!(
-                        "R2 exists failed for bucket={} key={}: code={} message={} raw={:?}",
+                        "{} exists failed for bucket={} key={}: code={} message={} raw={:?}",
+                        self.service,
                         self.bucket,
                         key,
                         e.code().unwrap_or("unknown"),
@@ -211,7 +251,8 @@ impl BlobStore for R2BlobStore {
             .await
             .map_err(|e| {
                 internal_error(format!(
-                    "R2 multipart init failed for bucket={} key={}: code={} message={} raw={:?}",
+                    "{} multipart init failed for bucket={} key={}: code={} message={} raw={:?}",
+                    self.service,
                     self.bucket,
                     key,
                     e.code().unwrap_or("unknown"),
@@ -220,9 +261,9 @@ impl BlobStore for R2BlobStore {
                 ))
             })?;
 
-        response
-            .upload_id
-            .ok_or_else(|| internal_error("R2 multipart init missing upload_id"))
+        response.upload_id.ok_or_else(|| {
+            internal_error(format!("{} multipart init missing upload_id", self.service))
+        })
     }
 
     async fn upload_part(
@@ -245,7 +286,7 @@ impl BlobStore for R2BlobStore {
             .await
             .map_err(|e| {
                 internal_error(format!(
-                    "R2 multipart part upload failed for bucket={} key={} part={}: code={} message={} raw={:?}",
+                    "{} multipart part upload failed for bucket={} key={} part={}: code={} message={} raw={:?}", self.service,
                     self.bucket,
                     key,
                     part_no,
@@ -256,7 +297,10 @@ impl BlobStore for R2BlobStore {
             })?;
 
         response.e_tag.ok_or_else(|| {
-            internal_error(format!("R2 multipart upload part {} missing etag", part_no))
+            internal_error(format!(
+                "{} multipart upload part {} missing etag",
+                self.service, part_no
+            ))
         })
     }
 
@@ -289,7 +333,7 @@ impl BlobStore for R2BlobStore {
             .await
             .map_err(|e| {
                 internal_error(format!(
-                    "R2 multipart complete failed for bucket={} key={}: code={} message={} raw={:?}",
+                    "{} multipart complete failed for bucket={} key={}: code={} message={} raw={:?}", self.service,
                     self.bucket,
                     key,
                     e.code().unwrap_or("unknown"),
@@ -310,7 +354,8 @@ impl BlobStore for R2BlobStore {
             .await
             .map_err(|e| {
                 internal_error(format!(
-                    "R2 multipart abort failed for bucket={} key={}: code={} message={} raw={:?}",
+                    "{} multipart abort failed for bucket={} key={}: code={} message={} raw={:?}",
+                    self.service,
                     self.bucket,
                     key,
                     e.code().unwrap_or("unknown"),
@@ -339,22 +384,20 @@ impl BlobStore for R2BlobStore {
         match response {
             Ok(out) => {
                 let body = out.body.collect().await.map_err(|e| {
-                    internal_error(format!("R2 get range body failed for {}: {}", key, e))
+                    internal_error(format!(
+                        "{} get range body failed for {}: {}",
+                        self.service, key, e
+                    ))
                 })?;
                 Ok(Some(body.into_bytes().to_vec()))
             }
             Err(e) => {
-                let msg = e.to_string();
-                let code = e.code().unwrap_or("");
-                if code == "NoSuchKey"
-                    || msg.contains("NoSuchKey")
-                    || msg.contains("404")
-                    || msg.contains("Not Found")
-                {
+                if is_not_found(&e) {
                     Ok(None)
                 } else {
                     Err(internal_error(format!(
-                        "R2 get range failed for bucket={} key={}: code={} message={} raw={:?}",
+                        "{} get range failed for bucket={} key={}: code={} message={} raw={:?}",
+                        self.service,
                         self.bucket,
                         key,
                         e.code().unwrap_or("unknown"),
@@ -367,7 +410,19 @@ impl BlobStore for R2BlobStore {
     }
 
     async fn list_by_prefix(&self, prefix: &str) -> Result<Vec<String>, ServerCoreError> {
-        let mut keys = Vec::new();
+        Ok(self
+            .list_entries_by_prefix(prefix)
+            .await?
+            .into_iter()
+            .map(|entry| entry.key)
+            .collect())
+    }
+
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        let mut entries = Vec::new();
         let mut continuation: Option<String> = None;
 
         loop {
@@ -382,7 +437,8 @@ impl BlobStore for R2BlobStore {
 
             let response = req.send().await.map_err(|e| {
                 internal_error(format!(
-                    "R2 list failed for bucket={} prefix={}: code={} message={} raw={:?}",
+                    "{} list failed for bucket={} prefix={}: code={} message={} raw={:?}",
+                    self.service,
                     self.bucket,
                     prefix,
                     e.code().unwrap_or("unknown"),
@@ -393,7 +449,11 @@ impl BlobStore for R2BlobStore {
 
             for object in response.contents() {
                 if let Some(key) = object.key() {
-                    keys.push(key.to_string());
+                    entries.push(BlobEntry {
+                        key: key.to_string(),
+                        size_bytes: object.size().map(|size| size.max(0) as u64),
+                        last_modified: object.last_modified().map(|t| t.secs()),
+                    });
                 }
             }
 
@@ -407,7 +467,64 @@ impl BlobStore for R2BlobStore {
             }
         }
 
-        Ok(keys)
+        Ok(entries)
+    }
+
+    async fn list_multipart_uploads(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<PendingMultipartUpload>, ServerCoreError> {
+        let mut uploads = Vec::new();
+        let mut key_marker: Option<String> = None;
+        let mut upload_id_marker: Option<String> = None;
+
+        loop {
+            let mut req = self
+                .client
+                .list_multipart_uploads()
+                .bucket(&self.bucket)
+                .prefix(prefix);
+            if let Some(marker) = &key_marker {
+                req = req.key_marker(marker);
+            }
+            if let Some(marker) = &upload_id_marker {
+                req = req.upload_id_marker(marker);
+            }
+
+            let response = req.send().await.map_err(|e| {
+                internal_error(format!(
+                    "{} multipart list failed for bucket={} prefix={}: code={} message={} raw={:?}",
+                    self.service,
+                    self.bucket,
+                    prefix,
+                    e.code().unwrap_or("unknown"),
+                    e.message().unwrap_or("unknown"),
+                    e
+                ))
+            })?;
+
+            for upload in response.uploads() {
+                if let (Some(key), Some(upload_id)) = (upload.key(), upload.upload_id()) {
+                    uploads.push(PendingMultipartUpload {
+                        key: key.to_string(),
+                        upload_id: upload_id.to_string(),
+                        initiated_at: upload.initiated().map(|t| t.secs()),
+                    });
+                }
+            }
+
+            if response.is_truncated().unwrap_or(false) {
+                key_marker = response.next_key_marker().map(str::to_string);
+                upload_id_marker = response.next_upload_id_marker().map(str::to_string);
+                if key_marker.is_none() {
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+
+        Ok(uploads)
     }
 
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError> {
@@ -421,7 +538,10 @@ impl BlobStore for R2BlobStore {
             let mut objects = Vec::with_capacity(chunk.len());
             for key in chunk {
                 let object = ObjectIdentifier::builder().key(key).build().map_err(|e| {
-                    internal_error(format!("R2 delete object identifier build failed: {}", e))
+                    internal_error(format!(
+                        "{} delete object identifier build failed: {}",
+                        self.service, e
+                    ))
                 })?;
                 objects.push(object);
             }
@@ -429,7 +549,12 @@ impl BlobStore for R2BlobStore {
             let delete = Delete::builder()
                 .set_objects(Some(objects))
                 .build()
-                .map_err(|e| internal_error(format!("R2 delete request build failed: {}", e)))?;
+                .map_err(|e| {
+                    internal_error(format!(
+                        "{} delete request build failed: {}",
+                        self.service, e
+                    ))
+                })?;
 
             self.client
                 .delete_objects()
@@ -439,7 +564,7 @@ impl BlobStore for R2BlobStore {
                 .await
                 .map_err(|e| {
                     internal_error(format!(
-                        "R2 delete-by-prefix failed for bucket={} prefix={}: code={} message={} raw={:?}",
+                        "{} delete-by-prefix failed for bucket={} prefix={}: code={} message={} raw={:?}", self.service,
                         self.bucket,
                         prefix,
                         e.code().unwrap_or("unknown"),
@@ -634,6 +759,25 @@ impl BlobStore for InMemoryBlobStore {
             .collect())
     }
 
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        let blobs = self
+            .blobs
+            .lock()
+            .map_err(|_| internal_error("Failed to lock in-memory blob store"))?;
+        Ok(blobs
+            .iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(k, blob)| BlobEntry {
+                key: k.clone(),
+                size_bytes: Some(blob.len() as u64),
+                last_modified: None,
+            })
+            .collect())
+    }
+
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError> {
         let mut blobs = self
             .blobs
@@ -652,6 +796,15 @@ impl BlobStore for InMemoryBlobStore {
     }
 }
 
+/// File inside a multipart session dir holding the key being uploaded.
+const MULTIPART_KEY_FILE: &str = "key";
+
+fn unix_secs(time: std::time::SystemTime) -> Option<i64> {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
 /// Filesystem-backed blob store that persists blobs to a local directory.
 pub struct LocalFsBlobStore {
     root: std::path::PathBuf,
@@ -752,11 +905,7 @@ impl BlobStore for LocalFsBlobStore {
         Ok(self.key_to_path(key).exists())
     }
 
-    async fn init_multipart(
-        &self,
-        _key: &str,
-        _mime_type: &str,
-    ) -> Result<String, ServerCoreError> {
+    async fn init_multipart(&self, key: &str, _mime_type: &str) -> Result<String, ServerCoreError> {
         let upload_id = uuid::Uuid::new_v4().to_string();
         let session_dir = self.multipart_session_dir(&upload_id);
         std::fs::create_dir_all(&session_dir).map_err(|e| {
@@ -765,6 +914,14 @@ impl BlobStore for LocalFsBlobStore {
                 session_dir, e
             ))
         })?;
+        // Record the target key so stale sessions can be listed by prefix.
+        let key_path = session_dir.join(MULTIPART_KEY_FILE);
+        std::fs::write(&key_path, key).map_err(|e| {
+            internal_error(format!(
+                "Failed to write multipart session key {:?}: {}",
+                key_path, e
+            ))
+        })?;
         Ok(upload_id)
     }
 
@@ -888,6 +1045,65 @@ impl BlobStore for LocalFsBlobStore {
         Ok(keys)
     }
 
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        let keys = self.list_by_prefix(prefix).await?;
+        let mut entries = Vec::with_capacity(keys.len());
+        for key in keys {
+            // A file removed between the walk and the stat is simply gone.
+            let Ok(metadata) = std::fs::metadata(self.key_to_path(&key)) else {
+                continue;
+            };
+            entries.push(BlobEntry {
+                key,
+                size_bytes: Some(metadata.len()),
+                last_modified: metadata.modified().ok().and_then(unix_secs),
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn list_multipart_uploads(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<PendingMultipartUpload>, ServerCoreError> {
+        let sessions = match std::fs::read_dir(&self.multipart_dir) {
+            Ok(sessions) => sessions,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(internal_error(format!(
+                    "Failed to read multipart dir {:?}: {}",
+                    self.multipart_dir, e
+                )));
+            }
+        };
+
+        let mut uploads = Vec::new();
+        for session in sessions.filter_map(|e| e.ok()) {
+            let path = session.path();
+            // Sessions started before the key file existed can't be matched
+            // against a prefix, so they are left for manual cleanup.
+            let Ok(key) = std::fs::read_to_string(path.join(MULTIPART_KEY_FILE)) else {
+                continue;
+            };
+            if !key.starts_with(prefix) {
+                continue;
+            }
+            uploads.push(PendingMultipartUpload {
+                key,
+                upload_id: session.file_name().to_string_lossy().to_string(),
+                initiated_at: session
+                    .metadata()
+                    .ok()
+                    .and_then(|m| m.modified().ok())
+                    .and_then(unix_secs),
+            });
+        }
+        Ok(uploads)
+    }
+
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError> {
         let keys = self.list_by_prefix(prefix).await?;
         let deleted = keys.len();
@@ -902,14 +1118,30 @@ impl BlobStore for LocalFsBlobStore {
 pub async fn build_blob_store(
     config: &crate::config::Config,
 ) -> Result<Arc<dyn BlobStore>, ServerCoreError> {
-    if config.is_r2_configured() {
-        let store = R2BlobStore::new(&config.r2).await?;
-        Ok(Arc::new(store))
-    } else if config.blob_store_in_memory {
-        Ok(Arc::new(InMemoryBlobStore::new(config.r2.prefix.clone())))
-    } else {
-        let store = LocalFsBlobStore::new(&config.blob_store_path, config.r2.prefix.clone())?;
-        Ok(Arc::new(store))
+    match config.blob_store_backend() {
+        BlobStoreBackend::R2 => {
+            if !config.is_r2_configured() {
+                return Err(internal_error(
+                    "BLOB_STORE=r2 requires R2_ACCOUNT_ID, R2_ACCESS_KEY_ID and R2_SECRET_ACCESS_KEY",
+                ));
+            }
+            Ok(Arc::new(S3BlobStore::r2(&config.r2).await?))
+        }
+        BlobStoreBackend::S3 => {
+            let s3 = config.s3.as_ref().filter(|_| config.is_s3_configured()).ok_or_else(|| {
+                internal_error(
+                    "BLOB_STORE=s3 requires S3_ENDPOINT, S3_ACCESS_KEY_ID and S3_SECRET_ACCESS_KEY",
+                )
+            })?;
+            Ok(Arc::new(S3BlobStore::new(s3).await?))
+        }
+        BlobStoreBackend::InMemory => {
+            Ok(Arc::new(InMemoryBlobStore::new(config.r2.prefix.clone())))
+        }
+        BlobStoreBackend::LocalFs => {
+            let store = LocalFsBlobStore::new(&config.blob_store_path, config.r2.prefix.clone())?;
+            Ok(Arc::new(store))
+        }
     }
 }
 
@@ -939,4 +1171,47 @@ mod tests {
         assert!(store.get("site/a/page.html").await.unwrap().is_none());
         assert!(store.get("site/b/index.html").await.unwrap().is_some());
     }
+
+    #[tokio::test]
+    async fn local_fs_lists_entries_and_pending_uploads() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalFsBlobStore::new(dir.path(), "diaryx-sync").unwrap();
+        store
+            .put("ns/a/blobs/ff", b"hello", "text/plain", None)
+            .await
+            .unwrap();
+
+        let entries = store.list_entries_by_prefix("ns/").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].size_bytes, Some(5));
+        assert!(entries[0].last_modified.is_some());
+
+        let upload_id = store
+            .init_multipart("ns/a/blobs/big", "application/octet-stream")
+            .await
+            .unwrap();
+        let uploads = store.list_multipart_uploads("ns/a/").await.unwrap();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].key, "ns/a/blobs/big");
+        assert_eq!(uploads[0].upload_id, upload_id);
+        assert!(
+            store
+                .list_multipart_uploads("ns/b/")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        store
+            .abort_multipart("ns/a/blobs/big", &upload_id)
+            .await
+            .unwrap();
+        assert!(
+            store
+                .list_multipart_uploads("ns/")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
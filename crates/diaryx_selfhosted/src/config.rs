@@ -1,3 +1,4 @@
+use diaryx_server::use_cases::audit::DEFAULT_AUDIT_RETENTION_DAYS;
 use std::env;
 use std::path::PathBuf;
 
@@ -22,9 +23,13 @@ pub struct Config {
     pub cors_origins: Vec<String>,
     /// R2 blob storage configuration
     pub r2: R2Config,
+    /// Generic S3-compatible blob storage (MinIO, Garage, ...). None if S3_ENDPOINT not set.
+    pub s3: Option<S3Config>,
+    /// Explicit blob store selection (BLOB_STORE). None picks one from what is configured.
+    pub blob_store_backend: Option<BlobStoreBackend>,
     /// Global HMAC key for audience access tokens (32 bytes)
     pub token_signing_key: Vec<u8>,
-    /// Optional admin secret for tier management endpoints
+    /// Optional bearer secret for the operator admin API (`/api/admin`)
     pub admin_secret: Option<String>,
     /// Managed AI proxy configuration.
     pub managed_ai: ManagedAiConfig,
@@ -50,6 +55,26 @@ pub struct Config {
     /// When set, subdomains are available (requires DNS wildcard + reverse proxy).
     /// When empty, sites are served at `/sites/{ns_id}/` paths only.
     pub site_domain: Option<String>,
+    /// Hours between background storage reconciliation passes. None disables
+    /// the scheduled job (STORAGE_RECONCILE_INTERVAL_HOURS unset or 0).
+    pub storage_reconcile_interval_hours: Option<u64>,
+    /// Age in hours an unreferenced blob must reach before reconciliation
+    /// removes it (default: 24)
+    pub storage_reconcile_grace_hours: u64,
+    /// Days audit log events are kept (AUDIT_LOG_RETENTION_DAYS, default:
+    /// 365). 0 keeps them forever.
+    pub audit_log_retention_days: u32,
+    /// Let webhooks, Webmention sources and ActivityPub peers reach loopback
+    /// and private network addresses (OUTBOUND_ALLOW_PRIVATE, default:
+    /// false). Only for servers whose users target their own network.
+    pub outbound_allow_private: bool,
+    /// Directory of plugins that expand editor syntax (math, drawings, ...)
+    /// when a namespace is built (RENDER_PLUGINS_DIR). None renders only the
+    /// built-in syntax.
+    pub render_plugins_dir: Option<PathBuf>,
+    /// Load render plugins that carry no publisher signature
+    /// (RENDER_PLUGINS_ALLOW_UNSIGNED, default: false).
+    pub render_plugins_allow_unsigned: bool,
 }
 
 /// Managed AI proxy configuration.
@@ -119,6 +144,63 @@ pub struct R2Config {
     pub prefix: String,
 }
 
+/// Generic S3-compatible blob storage configuration.
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    /// Service endpoint, e.g. http://minio:9000
+    pub endpoint: String,
+    /// Signing region (default: us-east-1)
+    pub region: String,
+    /// Bucket name (default: diaryx-user-data)
+    pub bucket: String,
+    /// Access key ID
+    pub access_key_id: String,
+    /// Secret access key
+    pub secret_access_key: String,
+    /// Object key prefix (default: diaryx-sync)
+    pub prefix: String,
+    /// Address buckets as `{endpoint}/{bucket}/{key}` rather than by
+    /// virtual host (default: true, which MinIO and Garage expect)
+    pub force_path_style: bool,
+}
+
+/// Which blob store backend to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobStoreBackend {
+    R2,
+    S3,
+    LocalFs,
+    InMemory,
+}
+
+impl BlobStoreBackend {
+    /// Parse a `BLOB_STORE` value (`r2`, `s3`, `local`/`fs`, `memory`).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "r2" => Some(Self::R2),
+            "s3" => Some(Self::S3),
+            "local" | "fs" | "filesystem" => Some(Self::LocalFs),
+            "memory" | "in-memory" | "in_memory" => Some(Self::InMemory),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::R2 => "r2",
+            Self::S3 => "s3",
+            Self::LocalFs => "local",
+            Self::InMemory => "memory",
+        }
+    }
+}
+
+impl S3Config {
+    fn has_credentials(&self) -> bool {
+        !self.access_key_id.is_empty() && !self.secret_access_key.is_empty()
+    }
+}
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, ConfigError> {
@@ -172,6 +254,41 @@ impl Config {
             prefix: env::var("R2_PREFIX").unwrap_or_else(|_| "diaryx-sync".to_string()),
         };
 
+        let s3 = env::var("S3_ENDPOINT")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .map(|endpoint| S3Config {
+                endpoint,
+                region: env::var("S3_REGION")
+                    .ok()
+                    .filter(|v| !v.trim().is_empty())
+                    .unwrap_or_else(|| "us-east-1".to_string()),
+                bucket: env::var("S3_BUCKET").unwrap_or_else(|_| "diaryx-user-data".to_string()),
+                access_key_id: env::var("S3_ACCESS_KEY_ID").unwrap_or_default(),
+                secret_access_key: env::var("S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+                prefix: env::var("S3_PREFIX").unwrap_or_else(|_| "diaryx-sync".to_string()),
+                force_path_style: env::var("S3_FORCE_PATH_STYLE")
+                    .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+                    .unwrap_or(true),
+            });
+
+        let blob_store_backend = match env::var("BLOB_STORE")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+        {
+            Some(raw) => {
+                Some(BlobStoreBackend::parse(&raw).ok_or(ConfigError::InvalidBlobStore(raw))?)
+            }
+            None => None,
+        };
+        if blob_store_backend == Some(BlobStoreBackend::S3)
+            && !s3.as_ref().is_some_and(S3Config::has_credentials)
+        {
+            return Err(ConfigError::MissingS3Config);
+        }
+
         let token_signing_key_raw = env::var("TOKEN_SIGNING_KEY")
             .unwrap_or_else(|_| "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=".to_string());
         let token_signing_key = base64::Engine::decode(
@@ -304,6 +421,37 @@ impl Config {
             .map(|v| v.trim().to_string())
             .filter(|v| !v.is_empty());
 
+        let storage_reconcile_interval_hours = env::var("STORAGE_RECONCILE_INTERVAL_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|hours| *hours > 0);
+
+        let storage_reconcile_grace_hours = env::var("STORAGE_RECONCILE_GRACE_HOURS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(24);
+
+        let audit_log_retention_days = env::var("AUDIT_LOG_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_AUDIT_RETENTION_DAYS);
+
+        let outbound_allow_private = env::var("OUTBOUND_ALLOW_PRIVATE")
+            .ok()
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let render_plugins_dir = env::var("RENDER_PLUGINS_DIR")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from);
+
+        let render_plugins_allow_unsigned = env::var("RENDER_PLUGINS_ALLOW_UNSIGNED")
+            .ok()
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
         Ok(Config {
             host,
             port,
@@ -314,6 +462,8 @@ impl Config {
             magic_link_expiry_minutes,
             cors_origins,
             r2,
+            s3,
+            blob_store_backend,
             token_signing_key,
             secure_cookies,
             blob_store_path,
@@ -326,6 +476,12 @@ impl Config {
             kv_namespace_id,
             site_base_url,
             site_domain,
+            storage_reconcile_interval_hours,
+            storage_reconcile_grace_hours,
+            audit_log_retention_days,
+            outbound_allow_private,
+            render_plugins_dir,
+            render_plugins_allow_unsigned,
         })
     }
 
@@ -351,6 +507,27 @@ impl Config {
             && !self.r2.secret_access_key.is_empty()
     }
 
+    /// Check if a generic S3-compatible store is configured.
+    pub fn is_s3_configured(&self) -> bool {
+        self.s3.as_ref().is_some_and(S3Config::has_credentials)
+    }
+
+    /// The blob store to build: `BLOB_STORE` when set, otherwise R2, then
+    /// S3, then in-memory or local filesystem.
+    pub fn blob_store_backend(&self) -> BlobStoreBackend {
+        if let Some(backend) = self.blob_store_backend {
+            backend
+        } else if self.is_r2_configured() {
+            BlobStoreBackend::R2
+        } else if self.is_s3_configured() {
+            BlobStoreBackend::S3
+        } else if self.blob_store_in_memory {
+            BlobStoreBackend::InMemory
+        } else {
+            BlobStoreBackend::LocalFs
+        }
+    }
+
     /// Check if Stripe billing is configured.
     pub fn is_stripe_configured(&self) -> bool {
         self.stripe.is_some()
@@ -366,6 +543,8 @@ impl Config {
 pub enum ConfigError {
     InvalidPort,
     InvalidTokenSigningKey,
+    InvalidBlobStore(String),
+    MissingS3Config,
 }
 
 impl std::fmt::Display for ConfigError {
@@ -376,6 +555,14 @@ impl std::fmt::Display for ConfigError {
                 f,
                 "Invalid TOKEN_SIGNING_KEY (expected base64-encoded 32-byte key)"
             ),
+            ConfigError::InvalidBlobStore(value) => write!(
+                f,
+                "Invalid BLOB_STORE {value:?} (expected r2, s3, local or memory)"
+            ),
+            ConfigError::MissingS3Config => write!(
+                f,
+                "BLOB_STORE=s3 requires S3_ENDPOINT, S3_ACCESS_KEY_ID and S3_SECRET_ACCESS_KEY"
+            ),
         }
     }
 }
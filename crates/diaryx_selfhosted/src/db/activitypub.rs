@@ -0,0 +1,287 @@
+//! ActivityPub repository methods.
+
+use diaryx_server::domain::{ActorKeyPair, FollowerInfo, OutboxItem};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::sync::{Arc, Mutex};
+
+const FOLLOWER_COLUMNS: &str = "namespace_id, actor, inbox, shared_inbox, created_at";
+const OUTBOX_COLUMNS: &str =
+    "id, namespace_id, audience, object_key, url, title, summary, published_at, updated_at";
+
+/// Namespace actors' signing keys, followers and outboxes
+/// (`activitypub_actor_keys`, `activitypub_followers`, `activitypub_outbox`).
+pub struct ActivityPubRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl ActivityPubRepo {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    pub fn get_actor_key(&self, namespace_id: &str) -> Option<ActorKeyPair> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT namespace_id, public_key_pem, private_key_pem, created_at
+             FROM activitypub_actor_keys WHERE namespace_id = ?1",
+            params![namespace_id],
+            |row| {
+                Ok(ActorKeyPair {
+                    namespace_id: row.get(0)?,
+                    public_key_pem: row.get(1)?,
+                    private_key_pem: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .ok()
+        .flatten()
+    }
+
+    /// Store a key unless the namespace already has one.
+    pub fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO activitypub_actor_keys
+                (namespace_id, public_key_pem, private_key_pem, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(namespace_id) DO NOTHING",
+            params![
+                key.namespace_id,
+                key.public_key_pem,
+                key.private_key_pem,
+                key.created_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// Insert a follower, or update the inboxes of an existing one.
+    pub fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT INTO activitypub_followers ({FOLLOWER_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(namespace_id, actor) DO UPDATE SET
+                    inbox = excluded.inbox,
+                    shared_inbox = excluded.shared_inbox"
+            ),
+            params![
+                follower.namespace_id,
+                follower.actor,
+                follower.inbox,
+                follower.shared_inbox,
+                follower.created_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn delete_follower(&self, namespace_id: &str, actor: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM activitypub_followers WHERE namespace_id = ?1 AND actor = ?2",
+            params![namespace_id, actor],
+        )
+        .map(|n| n > 0)
+        .map_err(|e| e.to_string())
+    }
+
+    /// List a namespace's followers, oldest first.
+    pub fn list_followers(&self, namespace_id: &str) -> Vec<FollowerInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {FOLLOWER_COLUMNS} FROM activitypub_followers
+             WHERE namespace_id = ?1
+             ORDER BY created_at, actor"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id], follower_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn count_followers(&self, namespace_id: &str) -> u64 {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM activitypub_followers WHERE namespace_id = ?1",
+            params![namespace_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .map(|n| n as u64)
+        .unwrap_or(0)
+    }
+
+    /// Insert an outbox item, or update the mutable fields of the one with
+    /// its object key.
+    pub fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT INTO activitypub_outbox ({OUTBOX_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT(namespace_id, object_key) DO UPDATE SET
+                    audience = excluded.audience,
+                    url = excluded.url,
+                    title = excluded.title,
+                    summary = excluded.summary,
+                    updated_at = excluded.updated_at"
+            ),
+            params![
+                item.id,
+                item.namespace_id,
+                item.audience,
+                item.object_key,
+                item.url,
+                item.title,
+                item.summary,
+                item.published_at,
+                item.updated_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// List a namespace's outbox, newest first.
+    pub fn list_outbox_items(&self, namespace_id: &str) -> Vec<OutboxItem> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {OUTBOX_COLUMNS} FROM activitypub_outbox
+             WHERE namespace_id = ?1
+             ORDER BY published_at DESC, object_key"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id], outbox_item_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn delete_outbox_item(&self, namespace_id: &str, object_key: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM activitypub_outbox WHERE namespace_id = ?1 AND object_key = ?2",
+            params![namespace_id, object_key],
+        )
+        .map(|n| n > 0)
+        .map_err(|e| e.to_string())
+    }
+}
+
+fn follower_from_row(row: &Row<'_>) -> rusqlite::Result<FollowerInfo> {
+    Ok(FollowerInfo {
+        namespace_id: row.get(0)?,
+        actor: row.get(1)?,
+        inbox: row.get(2)?,
+        shared_inbox: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+fn outbox_item_from_row(row: &Row<'_>) -> rusqlite::Result<OutboxItem> {
+    Ok(OutboxItem {
+        id: row.get(0)?,
+        namespace_id: row.get(1)?,
+        audience: row.get(2)?,
+        object_key: row.get(3)?,
+        url: row.get(4)?,
+        title: row.get(5)?,
+        summary: row.get(6)?,
+        published_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn item(object_key: &str, published_at: i64) -> OutboxItem {
+        OutboxItem {
+            id: format!("id-{object_key}"),
+            namespace_id: "ns1".to_string(),
+            audience: "public".to_string(),
+            object_key: object_key.to_string(),
+            url: format!("https://me.example/{object_key}"),
+            title: "Post".to_string(),
+            summary: String::new(),
+            published_at,
+            updated_at: published_at,
+        }
+    }
+
+    #[test]
+    fn followers_and_outbox_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, email, created_at, tier) VALUES ('u1', 'u1@test.com', 0, 'free');
+             INSERT INTO namespaces (id, owner_user_id, created_at) VALUES ('ns1', 'u1', 0);",
+        )
+        .unwrap();
+        let conn = Arc::new(Mutex::new(conn));
+        let repo = ActivityPubRepo::new(conn);
+
+        let key = ActorKeyPair {
+            namespace_id: "ns1".to_string(),
+            public_key_pem: "pub".to_string(),
+            private_key_pem: "priv".to_string(),
+            created_at: 1,
+        };
+        repo.insert_actor_key(&key).unwrap();
+        repo.insert_actor_key(&ActorKeyPair {
+            public_key_pem: "other".to_string(),
+            ..key.clone()
+        })
+        .unwrap();
+        assert_eq!(repo.get_actor_key("ns1"), Some(key));
+
+        let follower = FollowerInfo {
+            namespace_id: "ns1".to_string(),
+            actor: "https://social.example/users/a".to_string(),
+            inbox: "https://social.example/users/a/inbox".to_string(),
+            shared_inbox: None,
+            created_at: 10,
+        };
+        repo.upsert_follower(&follower).unwrap();
+        repo.upsert_follower(&FollowerInfo {
+            shared_inbox: Some("https://social.example/inbox".to_string()),
+            ..follower.clone()
+        })
+        .unwrap();
+        let followers = repo.list_followers("ns1");
+        assert_eq!(followers.len(), 1);
+        assert_eq!(
+            followers[0].shared_inbox.as_deref(),
+            Some("https://social.example/inbox")
+        );
+        assert_eq!(repo.count_followers("ns1"), 1);
+        assert!(repo.delete_follower("ns1", &follower.actor).unwrap());
+        assert_eq!(repo.count_followers("ns1"), 0);
+
+        repo.upsert_outbox_item(&item("public/a.html", 100))
+            .unwrap();
+        repo.upsert_outbox_item(&item("public/b.html", 200))
+            .unwrap();
+        repo.upsert_outbox_item(&OutboxItem {
+            id: "ignored".to_string(),
+            title: "Renamed".to_string(),
+            ..item("public/a.html", 100)
+        })
+        .unwrap();
+        let items = repo.list_outbox_items("ns1");
+        let keys: Vec<&str> = items.iter().map(|i| i.object_key.as_str()).collect();
+        assert_eq!(keys, ["public/b.html", "public/a.html"]);
+        assert_eq!(items[1].title, "Renamed");
+        assert_eq!(items[1].id, "id-public/a.html");
+        assert!(repo.delete_outbox_item("ns1", "public/a.html").unwrap());
+        assert!(!repo.delete_outbox_item("ns1", "public/a.html").unwrap());
+    }
+}
@@ -0,0 +1,164 @@
+//! Audit log repository methods.
+
+use diaryx_server::domain::{AuditAction, AuditEvent, AuditScope};
+use rusqlite::{Connection, Row, params};
+use std::sync::{Arc, Mutex};
+
+/// Append-only store for security-relevant events (`audit_events`).
+pub struct AuditRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AuditRepo {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    pub fn append_event(&self, event: &AuditEvent) -> Result<(), String> {
+        let details = (!event.details.is_null()).then(|| event.details.to_string());
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO audit_events (id, action, actor_user_id, user_id, namespace_id, target, details, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                event.id,
+                event.action.as_str(),
+                event.actor_user_id,
+                event.user_id,
+                event.namespace_id,
+                event.target,
+                details,
+                event.created_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// List a user's or namespace's events, newest first, created strictly
+    /// before `before` when given. Same-second events keep append order.
+    pub fn list_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Vec<AuditEvent> {
+        let (column, id) = match scope {
+            AuditScope::User(id) => ("user_id", id),
+            AuditScope::Namespace(id) => ("namespace_id", id),
+        };
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT id, action, actor_user_id, user_id, namespace_id, target, details, created_at
+             FROM audit_events
+             WHERE {column} = ?1 AND (?2 IS NULL OR created_at < ?2)
+             ORDER BY created_at DESC, rowid DESC
+             LIMIT ?3"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![id, before, limit], audit_event_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok().flatten()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// Delete events created before `cutoff`; returns how many went.
+    pub fn prune_events(&self, cutoff: i64) -> Result<u64, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM audit_events WHERE created_at < ?1",
+            params![cutoff],
+        )
+        .map(|n| n as u64)
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// `None` for rows with an unknown action.
+fn audit_event_from_row(row: &Row<'_>) -> rusqlite::Result<Option<AuditEvent>> {
+    let action: String = row.get(1)?;
+    let Some(action) = AuditAction::parse(&action) else {
+        return Ok(None);
+    };
+    let details: Option<String> = row.get(6)?;
+    Ok(Some(AuditEvent {
+        id: row.get(0)?,
+        action,
+        actor_user_id: row.get(2)?,
+        user_id: row.get(3)?,
+        namespace_id: row.get(4)?,
+        target: row.get(5)?,
+        details: details
+            .and_then(|d| serde_json::from_str(&d).ok())
+            .unwrap_or_default(),
+        created_at: row.get(7)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+    use serde_json::json;
+
+    fn event(id: &str, action: AuditAction, user: Option<&str>, created_at: i64) -> AuditEvent {
+        AuditEvent {
+            id: id.to_string(),
+            action,
+            actor_user_id: user.map(str::to_string),
+            user_id: user.map(str::to_string),
+            namespace_id: user.is_none().then(|| "ns1".to_string()),
+            target: None,
+            details: json!(null),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn audit_events_list_newest_first_and_prune() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let repo = AuditRepo::new(Arc::new(Mutex::new(conn)));
+
+        repo.append_event(&event("a", AuditAction::SignIn, Some("u1"), 100))
+            .unwrap();
+        repo.append_event(&AuditEvent {
+            details: json!({ "name": "laptop" }),
+            ..event("b", AuditAction::PasskeyRegistered, Some("u1"), 200)
+        })
+        .unwrap();
+        repo.append_event(&event(
+            "c",
+            AuditAction::AccessTokenCreated,
+            Some("u1"),
+            200,
+        ))
+        .unwrap();
+        repo.append_event(&event("d", AuditAction::AudienceUnlocked, None, 150))
+            .unwrap();
+
+        let user = AuditScope::User("u1".to_string());
+        let ids: Vec<String> = repo
+            .list_events(&user, None, 10)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+
+        let page = repo.list_events(&user, Some(200), 10);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].action, AuditAction::SignIn);
+        assert_eq!(
+            repo.list_events(&user, None, 2)[1].details["name"],
+            "laptop"
+        );
+
+        let ns = repo.list_events(&AuditScope::Namespace("ns1".to_string()), None, 10);
+        assert_eq!(ns.len(), 1);
+        assert!(ns[0].user_id.is_none());
+        assert!(ns[0].details.is_null());
+
+        assert_eq!(repo.prune_events(160).unwrap(), 2);
+        assert_eq!(repo.list_events(&user, None, 10).len(), 2);
+    }
+}
@@ -0,0 +1,194 @@
+//! Reader comment repository methods.
+
+use diaryx_server::domain::{CommentInfo, CommentStatus};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::sync::{Arc, Mutex};
+
+const COMMENT_COLUMNS: &str = "id, namespace_id, audience, object_key, source_key, author_name, \
+     body, status, token_id, created_at, updated_at";
+
+/// Reader comments on gated pages and their moderation state (`comments`).
+pub struct CommentRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CommentRepo {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Insert a comment, or update the moderation state of an existing one.
+    pub fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT INTO comments ({COMMENT_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(id) DO UPDATE SET
+                    status = excluded.status,
+                    updated_at = excluded.updated_at"
+            ),
+            params![
+                comment.id,
+                comment.namespace_id,
+                comment.audience,
+                comment.object_key,
+                comment.source_key,
+                comment.author_name,
+                comment.body,
+                comment.status.as_str(),
+                comment.token_id,
+                comment.created_at,
+                comment.updated_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn get_comment(&self, id: &str) -> Option<CommentInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {COMMENT_COLUMNS} FROM comments WHERE id = ?1"),
+            params![id],
+            comment_from_row,
+        )
+        .optional()
+        .ok()
+        .flatten()
+        .flatten()
+    }
+
+    /// List a namespace's comments, newest first, optionally on one page
+    /// and/or in one status.
+    pub fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Vec<CommentInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {COMMENT_COLUMNS} FROM comments
+             WHERE namespace_id = ?1
+               AND (?2 IS NULL OR object_key = ?2)
+               AND (?3 IS NULL OR status = ?3)
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?4"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(
+                params![namespace_id, object_key, status.map(|s| s.as_str()), limit],
+                comment_from_row,
+            )
+            .map(|rows| rows.filter_map(|r| r.ok().flatten()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM comments WHERE namespace_id = ?1 AND id = ?2",
+            params![namespace_id, id],
+        )
+        .map(|n| n > 0)
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// `None` for rows with an unknown status.
+fn comment_from_row(row: &Row<'_>) -> rusqlite::Result<Option<CommentInfo>> {
+    let status: String = row.get(7)?;
+    let Some(status) = CommentStatus::parse(&status) else {
+        return Ok(None);
+    };
+    Ok(Some(CommentInfo {
+        id: row.get(0)?,
+        namespace_id: row.get(1)?,
+        audience: row.get(2)?,
+        object_key: row.get(3)?,
+        source_key: row.get(4)?,
+        author_name: row.get(5)?,
+        body: row.get(6)?,
+        status,
+        token_id: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn comment(id: &str, object_key: &str, created_at: i64) -> CommentInfo {
+        CommentInfo {
+            id: id.to_string(),
+            namespace_id: "ns1".to_string(),
+            audience: "family".to_string(),
+            object_key: object_key.to_string(),
+            source_key: Some("family/post.md".to_string()),
+            author_name: "Grandma".to_string(),
+            body: "Lovely".to_string(),
+            status: CommentStatus::Pending,
+            token_id: "tok1".to_string(),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn comments_upsert_list_and_delete() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, email, created_at, tier) VALUES ('u1', 'u1@test.com', 0, 'free');
+             INSERT INTO namespaces (id, owner_user_id, created_at) VALUES ('ns1', 'u1', 0);",
+        )
+        .unwrap();
+        let conn = Arc::new(Mutex::new(conn));
+        let repo = CommentRepo::new(conn);
+
+        repo.upsert_comment(&comment("a", "family/post.html", 100))
+            .unwrap();
+        repo.upsert_comment(&comment("b", "family/other.html", 200))
+            .unwrap();
+        repo.upsert_comment(&CommentInfo {
+            status: CommentStatus::Approved,
+            body: "ignored".to_string(),
+            updated_at: 300,
+            ..comment("a", "family/post.html", 100)
+        })
+        .unwrap();
+
+        let a = repo.get_comment("a").unwrap();
+        assert_eq!(a.status, CommentStatus::Approved);
+        assert_eq!(a.body, "Lovely");
+        assert_eq!(a.source_key.as_deref(), Some("family/post.md"));
+
+        let ids: Vec<String> = repo
+            .list_comments("ns1", None, None, 10)
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(ids, ["b", "a"]);
+        let on_post = repo.list_comments(
+            "ns1",
+            Some("family/post.html"),
+            Some(CommentStatus::Approved),
+            10,
+        );
+        assert_eq!(on_post.len(), 1);
+        assert!(
+            repo.list_comments("ns1", None, Some(CommentStatus::Rejected), 10)
+                .is_empty()
+        );
+
+        assert!(!repo.delete_comment("other", "a").unwrap());
+        assert!(repo.delete_comment("ns1", "a").unwrap());
+        assert!(repo.get_comment("a").is_none());
+    }
+}
@@ -1,13 +1,23 @@
+mod activitypub;
+mod audit;
+mod comments;
 mod namespaces;
 mod repo;
 mod schema;
+mod webmentions;
 
+pub use activitypub::ActivityPubRepo;
+pub use audit::AuditRepo;
+pub use comments::CommentRepo;
+pub(crate) use namespaces::generate_session_code;
 pub use namespaces::{
     AudienceInfo, CustomDomainInfo, NamespaceInfo, NamespaceObjectMeta, NamespaceRepo,
-    NamespaceSessionInfo, UsageTotals,
+    NamespaceSessionInfo, NamespaceSummary, StorageCounts, UsageTotals,
 };
 pub use repo::{
-    AuthRepo, DeviceInfo, PasskeyChallengeInfo, PasskeyCredentialInfo, SessionInfo, TierDefaults,
-    UserInfo, UserTier,
+    AccountCounts, AuthRepo, DeviceInfo, LimitOverrides, PasskeyChallengeInfo,
+    PasskeyCredentialInfo, SessionInfo, TierDefaults, UserInfo, UserSummary, UserTier,
 };
+pub(crate) use repo::{generate_secure_token, generate_verification_code};
 pub use schema::init_database;
+pub use webmentions::WebmentionRepo;
@@ -2,6 +2,10 @@
 
 use chrono::Utc;
 use diaryx_server::GateRecord;
+use diaryx_server::domain::{
+    NamespaceInviteInfo, NamespaceMemberInfo, NamespaceRole, UsageEvent, WebhookDeliveryInfo,
+    WebhookDeliveryStatus, WebhookEvent, WebhookInfo,
+};
 use rusqlite::{Connection, OptionalExtension, params};
 use std::sync::{Arc, Mutex};
 
@@ -59,6 +63,29 @@ pub struct UsageTotals {
     pub relay_seconds: u64,
 }
 
+/// One row of the operator namespace listing, with the storage it holds.
+#[derive(Debug, Clone)]
+pub struct NamespaceSummary {
+    pub id: String,
+    pub owner_user_id: String,
+    pub owner_email: Option<String>,
+    pub created_at: i64,
+    pub metadata: Option<String>,
+    pub object_count: u64,
+    pub stored_bytes: u64,
+}
+
+/// Instance-wide namespace, storage and webhook counts for the operator
+/// health summary.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StorageCounts {
+    pub namespaces: u64,
+    pub objects: u64,
+    pub stored_bytes: u64,
+    pub pending_webhook_deliveries: u64,
+    pub failed_webhook_deliveries: u64,
+}
+
 /// Custom domain record mapping a domain to a namespace + audience.
 #[derive(Debug, Clone)]
 pub struct CustomDomainInfo {
@@ -141,6 +168,64 @@ impl NamespaceRepo {
         .unwrap_or_default()
     }
 
+    /// List every namespace, largest first, optionally for one owner.
+    pub fn list_namespace_summaries(
+        &self,
+        owner_user_id: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Vec<NamespaceSummary> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(
+            "SELECT n.id, n.owner_user_id, u.email, n.created_at, n.metadata,
+                    COUNT(o.key), COALESCE(SUM(o.size_bytes), 0)
+             FROM namespaces n
+             LEFT JOIN users u ON u.id = n.owner_user_id
+             LEFT JOIN namespace_objects o ON o.namespace_id = n.id
+             WHERE ?1 IS NULL OR n.owner_user_id = ?1
+             GROUP BY n.id
+             ORDER BY COALESCE(SUM(o.size_bytes), 0) DESC, n.id
+             LIMIT ?2 OFFSET ?3",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![owner_user_id, limit, offset], |row| {
+                Ok(NamespaceSummary {
+                    id: row.get(0)?,
+                    owner_user_id: row.get(1)?,
+                    owner_email: row.get(2)?,
+                    created_at: row.get(3)?,
+                    metadata: row.get(4)?,
+                    object_count: row.get::<_, i64>(5)?.max(0) as u64,
+                    stored_bytes: row.get::<_, i64>(6)?.max(0) as u64,
+                })
+            })
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// Count namespaces, objects, stored bytes and outstanding webhook
+    /// deliveries across the instance.
+    pub fn storage_counts(&self) -> Result<StorageCounts, String> {
+        let conn = self.conn.lock().unwrap();
+        let count = |sql: &str| -> Result<u64, String> {
+            conn.query_row(sql, [], |row| row.get::<_, i64>(0))
+                .map(|v| v.max(0) as u64)
+                .map_err(|e| e.to_string())
+        };
+        Ok(StorageCounts {
+            namespaces: count("SELECT COUNT(*) FROM namespaces")?,
+            objects: count("SELECT COUNT(*) FROM namespace_objects")?,
+            stored_bytes: count("SELECT COALESCE(SUM(size_bytes), 0) FROM namespace_objects")?,
+            pending_webhook_deliveries: count(
+                "SELECT COUNT(*) FROM webhook_deliveries WHERE status = 'pending'",
+            )?,
+            failed_webhook_deliveries: count(
+                "SELECT COUNT(*) FROM webhook_deliveries WHERE status = 'failed'",
+            )?,
+        })
+    }
+
     pub fn update_metadata(
         &self,
         namespace_id: &str,
@@ -157,12 +242,37 @@ impl NamespaceRepo {
 
     pub fn delete_namespace(&self, namespace_id: &str) -> Result<(), String> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM namespaces WHERE id = ?1",
-            params![namespace_id],
-        )
-        .map(|_| ())
-        .map_err(|e| e.to_string())
+        // Foreign keys aren't enforced on this connection, so drop the
+        // collaborators and webhooks explicitly: a recreated namespace with
+        // the same ID must not inherit them.
+        conn.execute_batch("BEGIN")
+            .and_then(|_| {
+                conn.execute(
+                    "DELETE FROM namespace_members WHERE namespace_id = ?1",
+                    params![namespace_id],
+                )?;
+                conn.execute(
+                    "DELETE FROM namespace_invites WHERE namespace_id = ?1",
+                    params![namespace_id],
+                )?;
+                conn.execute(
+                    "DELETE FROM webhook_deliveries WHERE namespace_id = ?1",
+                    params![namespace_id],
+                )?;
+                conn.execute(
+                    "DELETE FROM namespace_webhooks WHERE namespace_id = ?1",
+                    params![namespace_id],
+                )?;
+                conn.execute(
+                    "DELETE FROM namespaces WHERE id = ?1",
+                    params![namespace_id],
+                )?;
+                conn.execute_batch("COMMIT")
+            })
+            .map_err(|e| {
+                let _ = conn.execute_batch("ROLLBACK");
+                e.to_string()
+            })
     }
 
     // -------------------------------------------------------------------------
@@ -282,6 +392,57 @@ impl NamespaceRepo {
         }
     }
 
+    /// Record a retained source version for a file ARK. Insert-only: an
+    /// existing `(workspace_ark, file_ark, version)` row keeps its `created_at`.
+    pub fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        conn.execute(
+            "INSERT OR IGNORE INTO ark_versions (workspace_ark, file_ark, version, object_key, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![workspace_ark, file_ark, version, object_key, now],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    /// List the retained versions of a file ARK, newest first, as
+    /// `(workspace_ark, file_ark, version, object_key, created_at)`.
+    pub fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Vec<(String, String, String, String, i64)> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT workspace_ark, file_ark, version, object_key, created_at
+             FROM ark_versions WHERE workspace_ark = ?1 AND file_ark = ?2
+             ORDER BY created_at DESC, version",
+        ) {
+            Ok(s) => s,
+            Err(_) => return Vec::new(),
+        };
+        let rows = stmt.query_map(params![workspace_ark, file_ark], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        });
+        match rows {
+            Ok(iter) => iter.filter_map(|r| r.ok()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
     pub fn get_object_meta(&self, namespace_id: &str, key: &str) -> Option<NamespaceObjectMeta> {
         let conn = self.conn.lock().unwrap();
         conn.query_row(
@@ -716,6 +877,308 @@ impl NamespaceRepo {
         .map_err(|e| e.to_string())
     }
 
+    // -------------------------------------------------------------------------
+    // Members and invites
+    // -------------------------------------------------------------------------
+
+    pub fn get_member(&self, namespace_id: &str, user_id: &str) -> Option<NamespaceMemberInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT m.namespace_id, m.user_id, u.email, m.role, m.created_at
+             FROM namespace_members m LEFT JOIN users u ON u.id = m.user_id
+             WHERE m.namespace_id = ?1 AND m.user_id = ?2",
+            params![namespace_id, user_id],
+            member_from_row,
+        )
+        .optional()
+        .unwrap_or(None)
+    }
+
+    /// List a namespace's members, oldest first.
+    pub fn list_members(&self, namespace_id: &str) -> Vec<NamespaceMemberInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(
+            "SELECT m.namespace_id, m.user_id, u.email, m.role, m.created_at
+             FROM namespace_members m LEFT JOIN users u ON u.id = m.user_id
+             WHERE m.namespace_id = ?1 ORDER BY m.created_at, m.user_id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id], member_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// List the memberships a user holds, newest first.
+    pub fn list_user_memberships(&self, user_id: &str) -> Vec<NamespaceMemberInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(
+            "SELECT m.namespace_id, m.user_id, u.email, m.role, m.created_at
+             FROM namespace_members m
+             JOIN namespaces n ON n.id = m.namespace_id
+             LEFT JOIN users u ON u.id = m.user_id
+             WHERE m.user_id = ?1 ORDER BY m.created_at DESC, m.namespace_id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![user_id], member_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// Insert a membership or update its role, keeping the original `created_at`.
+    pub fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO namespace_members (namespace_id, user_id, role, created_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(namespace_id, user_id) DO UPDATE SET role = excluded.role",
+            params![
+                member.namespace_id,
+                member.user_id,
+                member.role.as_str(),
+                member.created_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn remove_member(&self, namespace_id: &str, user_id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM namespace_members WHERE namespace_id = ?1 AND user_id = ?2",
+            params![namespace_id, user_id],
+        )
+        .map(|changed| changed > 0)
+        .map_err(|e| e.to_string())
+    }
+
+    /// Store an invite under the hash of its secret.
+    pub fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO namespace_invites (id, namespace_id, email, role, token_hash, invited_by, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                invite.id,
+                invite.namespace_id,
+                invite.email,
+                invite.role.as_str(),
+                token_hash,
+                invite.invited_by,
+                invite.created_at,
+                invite.expires_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn get_invite_by_hash(&self, token_hash: &str) -> Option<NamespaceInviteInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, namespace_id, email, role, invited_by, created_at, expires_at
+             FROM namespace_invites WHERE token_hash = ?1",
+            params![token_hash],
+            invite_from_row,
+        )
+        .optional()
+        .unwrap_or(None)
+    }
+
+    /// List a namespace's invites, newest first.
+    pub fn list_invites(&self, namespace_id: &str) -> Vec<NamespaceInviteInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(
+            "SELECT id, namespace_id, email, role, invited_by, created_at, expires_at
+             FROM namespace_invites WHERE namespace_id = ?1 ORDER BY created_at DESC, id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id], invite_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn delete_invite(&self, namespace_id: &str, invite_id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM namespace_invites WHERE namespace_id = ?1 AND id = ?2",
+            params![namespace_id, invite_id],
+        )
+        .map(|changed| changed > 0)
+        .map_err(|e| e.to_string())
+    }
+
+    // -------------------------------------------------------------------------
+    // Webhooks
+    // -------------------------------------------------------------------------
+
+    pub fn create_webhook(&self, webhook: &WebhookInfo, secret: &str) -> Result<(), String> {
+        let events = serde_json::to_string(&webhook.events).map_err(|e| e.to_string())?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO namespace_webhooks (id, namespace_id, url, events, secret, created_by, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                webhook.id,
+                webhook.namespace_id,
+                webhook.url,
+                events,
+                secret,
+                webhook.created_by,
+                webhook.created_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn get_webhook(&self, webhook_id: &str) -> Option<WebhookInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, namespace_id, url, events, created_by, created_at
+             FROM namespace_webhooks WHERE id = ?1",
+            params![webhook_id],
+            webhook_from_row,
+        )
+        .optional()
+        .unwrap_or(None)
+    }
+
+    pub fn get_webhook_secret(&self, webhook_id: &str) -> Option<String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT secret FROM namespace_webhooks WHERE id = ?1",
+            params![webhook_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap_or(None)
+    }
+
+    /// List a namespace's webhooks, oldest first.
+    pub fn list_webhooks(&self, namespace_id: &str) -> Vec<WebhookInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(
+            "SELECT id, namespace_id, url, events, created_by, created_at
+             FROM namespace_webhooks WHERE namespace_id = ?1 ORDER BY created_at, id",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id], webhook_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// Delete a webhook and its deliveries.
+    pub fn delete_webhook(&self, namespace_id: &str, webhook_id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("BEGIN")
+            .and_then(|_| {
+                let changed = conn.execute(
+                    "DELETE FROM namespace_webhooks WHERE namespace_id = ?1 AND id = ?2",
+                    params![namespace_id, webhook_id],
+                )?;
+                if changed > 0 {
+                    conn.execute(
+                        "DELETE FROM webhook_deliveries WHERE webhook_id = ?1",
+                        params![webhook_id],
+                    )?;
+                }
+                conn.execute_batch("COMMIT")?;
+                Ok(changed > 0)
+            })
+            .map_err(|e| {
+                let _ = conn.execute_batch("ROLLBACK");
+                e.to_string()
+            })
+    }
+
+    /// Insert a delivery, or update the fields that change between attempts.
+    pub fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO webhook_deliveries (id, webhook_id, namespace_id, event, payload, status, attempts,
+                                             next_attempt_at, response_status, last_error, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                attempts = excluded.attempts,
+                next_attempt_at = excluded.next_attempt_at,
+                response_status = excluded.response_status,
+                last_error = excluded.last_error,
+                updated_at = excluded.updated_at",
+            params![
+                delivery.id,
+                delivery.webhook_id,
+                delivery.namespace_id,
+                delivery.event.as_str(),
+                delivery.payload,
+                delivery.status.as_str(),
+                delivery.attempts,
+                delivery.next_attempt_at,
+                delivery.response_status,
+                delivery.last_error,
+                delivery.created_at,
+                delivery.updated_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn get_delivery(&self, delivery_id: &str) -> Option<WebhookDeliveryInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE id = ?1"),
+            params![delivery_id],
+            delivery_from_row,
+        )
+        .optional()
+        .unwrap_or(None)
+    }
+
+    /// List a namespace's deliveries, newest first, optionally for one webhook.
+    pub fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Vec<WebhookDeliveryInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+             WHERE namespace_id = ?1 AND (?2 IS NULL OR webhook_id = ?2)
+             ORDER BY created_at DESC, id DESC LIMIT ?3"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![namespace_id, webhook_id, limit], delivery_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    /// List pending deliveries due at `now`, oldest due first.
+    pub fn list_due_deliveries(&self, now: i64, limit: u32) -> Vec<WebhookDeliveryInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+             WHERE status = 'pending' AND next_attempt_at <= ?1
+             ORDER BY next_attempt_at, id LIMIT ?2"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(params![now, limit], delivery_from_row)
+                .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        })
+        .unwrap_or_default()
+    }
+
     // -------------------------------------------------------------------------
     // Usage metering
     // -------------------------------------------------------------------------
@@ -739,6 +1202,28 @@ impl NamespaceRepo {
         }
     }
 
+    /// Every usage event recorded for a user, oldest first.
+    pub fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT event_type, amount, namespace_id, recorded_at FROM usage_events
+                 WHERE user_id = ?1 ORDER BY recorded_at, id",
+            )
+            .map_err(|e| e.to_string())?;
+        stmt.query_map(params![user_id], |row| {
+            Ok(UsageEvent {
+                event_type: row.get(0)?,
+                amount: row.get::<_, i64>(1)?.max(0) as u64,
+                namespace_id: row.get(2)?,
+                recorded_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+    }
+
     /// Aggregate usage totals for a user scoped to a specific namespace.
     pub fn get_namespace_usage_totals(&self, user_id: &str, namespace_id: &str) -> UsageTotals {
         let conn = self.conn.lock().unwrap();
@@ -759,8 +1244,82 @@ impl NamespaceRepo {
     }
 }
 
+/// Map a `namespace_members` row joined with the member's email. Unknown
+/// roles decode as viewer, the least privileged.
+fn member_from_row(row: &rusqlite::Row<'_>) -> Result<NamespaceMemberInfo, rusqlite::Error> {
+    let role: String = row.get(3)?;
+    Ok(NamespaceMemberInfo {
+        namespace_id: row.get(0)?,
+        user_id: row.get(1)?,
+        email: row.get(2)?,
+        role: NamespaceRole::parse(&role).unwrap_or(NamespaceRole::Viewer),
+        created_at: row.get(4)?,
+    })
+}
+
+fn invite_from_row(row: &rusqlite::Row<'_>) -> Result<NamespaceInviteInfo, rusqlite::Error> {
+    let role: String = row.get(3)?;
+    Ok(NamespaceInviteInfo {
+        id: row.get(0)?,
+        namespace_id: row.get(1)?,
+        email: row.get(2)?,
+        role: NamespaceRole::parse(&role).unwrap_or(NamespaceRole::Viewer),
+        invited_by: row.get(4)?,
+        created_at: row.get(5)?,
+        expires_at: row.get(6)?,
+    })
+}
+
+/// Map a `namespace_webhooks` row. Unknown event names are dropped.
+fn webhook_from_row(row: &rusqlite::Row<'_>) -> Result<WebhookInfo, rusqlite::Error> {
+    let events: String = row.get(3)?;
+    let events: Vec<String> = serde_json::from_str(&events).unwrap_or_default();
+    Ok(WebhookInfo {
+        id: row.get(0)?,
+        namespace_id: row.get(1)?,
+        url: row.get(2)?,
+        events: events
+            .iter()
+            .filter_map(|e| WebhookEvent::parse(e))
+            .collect(),
+        created_by: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, namespace_id, event, payload, status, attempts, \
+     next_attempt_at, response_status, last_error, created_at, updated_at";
+
+/// Map a `webhook_deliveries` row. Rows with an unknown event or status fail
+/// to decode and are skipped by the list queries.
+fn delivery_from_row(row: &rusqlite::Row<'_>) -> Result<WebhookDeliveryInfo, rusqlite::Error> {
+    let unknown = |column: usize, value: String| {
+        rusqlite::Error::FromSqlConversionFailure(
+            column,
+            rusqlite::types::Type::Text,
+            format!("unknown value {value:?}").into(),
+        )
+    };
+    let event: String = row.get(3)?;
+    let status: String = row.get(5)?;
+    Ok(WebhookDeliveryInfo {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        namespace_id: row.get(2)?,
+        event: WebhookEvent::parse(&event).ok_or_else(|| unknown(3, event.clone()))?,
+        payload: row.get(4)?,
+        status: WebhookDeliveryStatus::parse(&status).ok_or_else(|| unknown(5, status.clone()))?,
+        attempts: row.get(6)?,
+        next_attempt_at: row.get(7)?,
+        response_status: row.get(8)?,
+        last_error: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+    })
+}
+
 /// Generate a session code in XXXXXXXX-XXXXXXXX format.
-fn generate_session_code() -> String {
+pub(crate) fn generate_session_code() -> String {
     use rand::Rng;
     const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
     let mut rng = rand::thread_rng();
@@ -808,6 +1367,47 @@ mod tests {
         assert!(repo.get_namespace("workspace:abc").is_none());
     }
 
+    #[test]
+    fn namespace_summaries_report_storage() {
+        let repo = make_repo_with_schema();
+        repo.create_namespace("small", "u1", None).unwrap();
+        repo.create_namespace("large", "u1", None).unwrap();
+        repo.upsert_object(
+            "large",
+            "a.md",
+            "ns/large/a.md",
+            "text/markdown",
+            300,
+            None,
+            None,
+        )
+        .unwrap();
+        repo.upsert_object(
+            "large",
+            "b.md",
+            "ns/large/b.md",
+            "text/markdown",
+            200,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let all = repo.list_namespace_summaries(None, 10, 0);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, "large");
+        assert_eq!((all[0].object_count, all[0].stored_bytes), (2, 500));
+        assert_eq!(all[0].owner_email.as_deref(), Some("u1@test.com"));
+        assert_eq!((all[1].object_count, all[1].stored_bytes), (0, 0));
+        assert!(repo.list_namespace_summaries(Some("u2"), 10, 0).is_empty());
+
+        let counts = repo.storage_counts().unwrap();
+        assert_eq!(
+            (counts.namespaces, counts.objects, counts.stored_bytes),
+            (2, 2, 500)
+        );
+    }
+
     #[test]
     fn object_crud() {
         let repo = make_repo_with_schema();
@@ -1002,6 +1602,123 @@ mod tests {
         assert!(repo.get_session(&code).is_none());
     }
 
+    #[test]
+    fn member_and_invite_crud() {
+        let repo = make_repo_with_schema();
+        repo.conn
+            .lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO users (id, email, created_at, tier) VALUES ('u2', 'u2@test.com', 0, 'free')",
+            )
+            .unwrap();
+        repo.create_namespace("site:fam", "u1", None).unwrap();
+
+        let member = NamespaceMemberInfo {
+            namespace_id: "site:fam".to_string(),
+            user_id: "u2".to_string(),
+            email: None,
+            role: NamespaceRole::Viewer,
+            created_at: 10,
+        };
+        repo.upsert_member(&member).unwrap();
+        repo.upsert_member(&NamespaceMemberInfo {
+            role: NamespaceRole::Editor,
+            created_at: 99,
+            ..member.clone()
+        })
+        .unwrap();
+
+        let stored = repo.get_member("site:fam", "u2").unwrap();
+        assert_eq!(stored.role, NamespaceRole::Editor);
+        assert_eq!(stored.created_at, 10);
+        assert_eq!(stored.email.as_deref(), Some("u2@test.com"));
+        assert_eq!(repo.list_members("site:fam").len(), 1);
+        assert_eq!(repo.list_user_memberships("u2").len(), 1);
+
+        let invite = NamespaceInviteInfo {
+            id: "inv1".to_string(),
+            namespace_id: "site:fam".to_string(),
+            email: "u3@test.com".to_string(),
+            role: NamespaceRole::Viewer,
+            invited_by: "u1".to_string(),
+            created_at: 1,
+            expires_at: 2,
+        };
+        repo.create_invite(&invite, "hash1").unwrap();
+        assert_eq!(repo.get_invite_by_hash("hash1"), Some(invite));
+        assert_eq!(repo.list_invites("site:fam").len(), 1);
+
+        // Deleting the namespace drops its collaborators too.
+        repo.delete_namespace("site:fam").unwrap();
+        assert!(repo.get_member("site:fam", "u2").is_none());
+        assert!(repo.list_user_memberships("u2").is_empty());
+        assert!(repo.get_invite_by_hash("hash1").is_none());
+
+        repo.create_namespace("site:fam", "u1", None).unwrap();
+        repo.upsert_member(&member).unwrap();
+        assert!(repo.remove_member("site:fam", "u2").unwrap());
+        assert!(!repo.remove_member("site:fam", "u2").unwrap());
+    }
+
+    #[test]
+    fn webhook_and_delivery_crud() {
+        let repo = make_repo_with_schema();
+        repo.create_namespace("site:fam", "u1", None).unwrap();
+
+        let webhook = WebhookInfo {
+            id: "wh1".to_string(),
+            namespace_id: "site:fam".to_string(),
+            url: "https://bot.example.com/hook".to_string(),
+            events: vec![WebhookEvent::BuildCompleted, WebhookEvent::ObjectDeleted],
+            created_by: "u1".to_string(),
+            created_at: 5,
+        };
+        repo.create_webhook(&webhook, "secret").unwrap();
+        assert_eq!(repo.get_webhook("wh1"), Some(webhook.clone()));
+        assert_eq!(repo.get_webhook_secret("wh1").as_deref(), Some("secret"));
+        assert_eq!(repo.list_webhooks("site:fam"), vec![webhook]);
+
+        let delivery = WebhookDeliveryInfo {
+            id: "d1".to_string(),
+            webhook_id: "wh1".to_string(),
+            namespace_id: "site:fam".to_string(),
+            event: WebhookEvent::BuildCompleted,
+            payload: "{}".to_string(),
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Some(10),
+            response_status: None,
+            last_error: None,
+            created_at: 10,
+            updated_at: 10,
+        };
+        repo.upsert_delivery(&delivery).unwrap();
+        assert_eq!(repo.list_due_deliveries(9, 10).len(), 0);
+        assert_eq!(repo.list_due_deliveries(10, 10), vec![delivery.clone()]);
+
+        let retried = WebhookDeliveryInfo {
+            attempts: 1,
+            next_attempt_at: Some(70),
+            response_status: Some(502),
+            last_error: Some("bad gateway".to_string()),
+            updated_at: 11,
+            ..delivery.clone()
+        };
+        repo.upsert_delivery(&retried).unwrap();
+        assert_eq!(repo.get_delivery("d1"), Some(retried.clone()));
+        assert_eq!(
+            repo.list_deliveries("site:fam", Some("wh1"), 10),
+            vec![retried]
+        );
+        assert!(repo.list_deliveries("site:fam", Some("wh2"), 10).is_empty());
+
+        assert!(!repo.delete_webhook("site:other", "wh1").unwrap());
+        assert!(repo.delete_webhook("site:fam", "wh1").unwrap());
+        assert!(repo.get_webhook("wh1").is_none());
+        assert!(repo.get_delivery("d1").is_none());
+    }
+
     #[test]
     fn usage_recording_and_totals() {
         let repo = make_repo_with_schema();
@@ -1021,5 +1738,10 @@ mod tests {
         assert_eq!(ns_totals.bytes_in, 1000);
         assert_eq!(ns_totals.bytes_out, 0);
         assert_eq!(ns_totals.relay_seconds, 0);
+
+        let events = repo.list_usage_events("u1").unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].namespace_id.as_deref(), Some("workspace:abc"));
+        assert!(repo.list_usage_events("u2").unwrap().is_empty());
     }
 }
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use diaryx_server::domain::AccessTokenInfo;
 use rusqlite::{Connection, OptionalExtension, params};
 use std::sync::{Arc, Mutex};
 
@@ -86,6 +87,35 @@ pub struct SessionInfo {
     pub created_at: DateTime<Utc>,
 }
 
+/// One row of the operator user listing.
+#[derive(Debug, Clone)]
+pub struct UserSummary {
+    pub id: String,
+    pub email: String,
+    pub tier: UserTier,
+    pub created_at: i64,
+    pub last_login_at: Option<i64>,
+    pub device_count: u32,
+    pub namespace_count: u32,
+}
+
+/// Per-user limit overrides. `None` falls back to the tier default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitOverrides {
+    pub device_limit: Option<u32>,
+    pub attachment_limit_bytes: Option<u64>,
+    pub workspace_limit: Option<u32>,
+    pub published_site_limit: Option<u32>,
+}
+
+/// Instance-wide account counts for the operator health summary.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccountCounts {
+    pub users: u64,
+    pub devices: u64,
+    pub active_sessions: u64,
+}
+
 /// Passkey credential info for WebAuthn.
 #[derive(Debug, Clone)]
 pub struct PasskeyCredentialInfo {
@@ -488,11 +518,10 @@ impl AuthRepo {
         Ok(())
     }
 
-    /// Delete all sessions for a user
-    pub fn delete_user_sessions(&self, user_id: &str) -> Result<(), rusqlite::Error> {
+    /// Delete all sessions for a user. Returns how many were deleted.
+    pub fn delete_user_sessions(&self, user_id: &str) -> Result<usize, rusqlite::Error> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM auth_sessions WHERE user_id = ?", [user_id])?;
-        Ok(())
+        conn.execute("DELETE FROM auth_sessions WHERE user_id = ?", [user_id])
     }
 
     /// Clean up expired sessions
@@ -547,6 +576,102 @@ impl AuthRepo {
         Ok(updated > 0)
     }
 
+    // ===== Admin operations =====
+
+    /// List users, newest first. `query` matches an exact user ID or a
+    /// case-insensitive substring of the email address.
+    pub fn list_users(
+        &self,
+        query: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<UserSummary>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let pattern = query.map(|q| format!("%{}%", q.to_lowercase()));
+        let mut stmt = conn.prepare(
+            "SELECT u.id, u.email, u.tier, u.created_at, u.last_login_at,
+                    (SELECT COUNT(*) FROM devices d WHERE d.user_id = u.id),
+                    (SELECT COUNT(*) FROM namespaces n WHERE n.owner_user_id = u.id)
+             FROM users u
+             WHERE ?1 IS NULL OR u.id = ?2 OR LOWER(u.email) LIKE ?1
+             ORDER BY u.created_at DESC, u.id LIMIT ?3 OFFSET ?4",
+        )?;
+        stmt.query_map(params![pattern, query, limit, offset], |row| {
+            Ok(UserSummary {
+                id: row.get(0)?,
+                email: row.get(1)?,
+                tier: UserTier::from_str_lossy(&row.get::<_, String>(2)?),
+                created_at: row.get(3)?,
+                last_login_at: row.get(4)?,
+                device_count: row.get(5)?,
+                namespace_count: row.get(6)?,
+            })
+        })?
+        .collect()
+    }
+
+    /// Get a user's limit overrides, or `None` if the user doesn't exist.
+    pub fn get_limit_overrides(
+        &self,
+        user_id: &str,
+    ) -> Result<Option<LimitOverrides>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT device_limit, attachment_limit_bytes, workspace_limit, published_site_limit
+             FROM users WHERE id = ?",
+            [user_id],
+            |row| {
+                Ok(LimitOverrides {
+                    device_limit: row.get::<_, Option<i64>>(0)?.map(|v| v as u32),
+                    attachment_limit_bytes: row.get::<_, Option<i64>>(1)?.map(|v| v as u64),
+                    workspace_limit: row.get::<_, Option<i64>>(2)?.map(|v| v as u32),
+                    published_site_limit: row.get::<_, Option<i64>>(3)?.map(|v| v as u32),
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// Replace a user's limit overrides. Returns true if the row was updated.
+    pub fn set_limit_overrides(
+        &self,
+        user_id: &str,
+        overrides: LimitOverrides,
+    ) -> Result<bool, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE users SET device_limit = ?, attachment_limit_bytes = ?, workspace_limit = ?,
+                    published_site_limit = ?
+             WHERE id = ?",
+            params![
+                overrides.device_limit,
+                overrides.attachment_limit_bytes.map(|v| v as i64),
+                overrides.workspace_limit,
+                overrides.published_site_limit,
+                user_id
+            ],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Count users, devices and unexpired sessions.
+    pub fn account_counts(&self) -> Result<AccountCounts, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let now = Utc::now().timestamp();
+        let count = |sql: &str, args: &[&dyn rusqlite::ToSql]| {
+            conn.query_row(sql, args, |row| row.get::<_, i64>(0))
+                .map(|v| v.max(0) as u64)
+        };
+        Ok(AccountCounts {
+            users: count("SELECT COUNT(*) FROM users", &[])?,
+            devices: count("SELECT COUNT(*) FROM devices", &[])?,
+            active_sessions: count(
+                "SELECT COUNT(*) FROM auth_sessions WHERE expires_at >= ?",
+                &[&now],
+            )?,
+        })
+    }
+
     // ===== Stripe billing operations =====
 
     /// Set the Stripe customer ID for a user.
@@ -883,12 +1008,112 @@ impl AuthRepo {
         let deleted = conn.execute("DELETE FROM passkey_challenges WHERE expires_at < ?", [now])?;
         Ok(deleted)
     }
+
+    // ===== Access token operations =====
+
+    /// Store a personal access token under the hash of its secret.
+    pub fn create_access_token(
+        &self,
+        token: &AccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO access_tokens (id, user_id, name, token_hash, scopes, namespace_ids, created_at, expires_at, last_used_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                token.id,
+                token.user_id,
+                token.name,
+                token_hash,
+                serde_json::to_string(&token.scopes).unwrap_or_else(|_| "[]".to_string()),
+                token
+                    .namespace_ids
+                    .as_ref()
+                    .and_then(|ids| serde_json::to_string(ids).ok()),
+                token.created_at,
+                token.expires_at,
+                token.last_used_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Look up an access token by the hash of its secret.
+    pub fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AccessTokenInfo>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at, last_used_at \
+             FROM access_tokens WHERE token_hash = ?",
+            [token_hash],
+            access_token_from_row,
+        )
+        .optional()
+    }
+
+    /// List a user's access tokens, newest first.
+    pub fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenInfo>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at, last_used_at \
+             FROM access_tokens WHERE user_id = ? ORDER BY created_at DESC, id",
+        )?;
+        let rows = stmt
+            .query_map([user_id], access_token_from_row)?
+            .filter_map(Result::ok)
+            .collect();
+        Ok(rows)
+    }
+
+    /// Delete an access token (owned by user_id).
+    pub fn delete_access_token(&self, user_id: &str, id: &str) -> Result<bool, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn.execute(
+            "DELETE FROM access_tokens WHERE id = ? AND user_id = ?",
+            params![id, user_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Update an access token's last_used_at timestamp.
+    pub fn touch_access_token(&self, id: &str, used_at: i64) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE access_tokens SET last_used_at = ? WHERE id = ?",
+            params![used_at, id],
+        )?;
+        Ok(())
+    }
+}
+
+/// Map an `access_tokens` row (in the column order the queries above select)
+/// to the core type. Malformed JSON columns decode as empty lists, so a bad
+/// row grants nothing rather than failing the whole query.
+fn access_token_from_row(row: &rusqlite::Row<'_>) -> Result<AccessTokenInfo, rusqlite::Error> {
+    let scopes: String = row.get(3)?;
+    let namespace_ids: Option<String> = row.get(4)?;
+    Ok(AccessTokenInfo {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+        namespace_ids: namespace_ids.map(|ids| serde_json::from_str(&ids).unwrap_or_default()),
+        created_at: row.get(5)?,
+        expires_at: row.get(6)?,
+        last_used_at: row.get(7)?,
+    })
 }
 
 // ===== Helper functions =====
 
 /// Generate a cryptographically secure random token
-fn generate_secure_token() -> String {
+pub(crate) fn generate_secure_token() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let bytes: Vec<u8> = (0..32).map(|_| rng.r#gen()).collect();
@@ -896,7 +1121,7 @@ fn generate_secure_token() -> String {
 }
 
 /// Generate a 6-digit verification code (zero-padded)
-fn generate_verification_code() -> String {
+pub(crate) fn generate_verification_code() -> String {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     format!("{:06}", rng.gen_range(0..1_000_000u32))
@@ -1118,4 +1343,70 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_access_token_flow() {
+        use diaryx_server::domain::TokenScope;
+
+        let repo = setup_test_db();
+        let user_id = repo.get_or_create_user("ci@example.com").unwrap();
+        let token = AccessTokenInfo {
+            id: "tok-1".to_string(),
+            user_id: user_id.clone(),
+            name: "ci".to_string(),
+            scopes: vec![TokenScope::Publish],
+            namespace_ids: Some(vec!["ns-1".to_string()]),
+            created_at: 10,
+            expires_at: Some(20),
+            last_used_at: None,
+        };
+        repo.create_access_token(&token, "hash-1").unwrap();
+
+        let found = repo.get_access_token_by_hash("hash-1").unwrap().unwrap();
+        assert_eq!(found.scopes, vec![TokenScope::Publish]);
+        assert_eq!(found.namespace_ids, Some(vec!["ns-1".to_string()]));
+        assert!(repo.get_access_token_by_hash("tok-1").unwrap().is_none());
+
+        repo.touch_access_token("tok-1", 15).unwrap();
+        let listed = repo.list_access_tokens(&user_id).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].last_used_at, Some(15));
+
+        assert!(!repo.delete_access_token("someone-else", "tok-1").unwrap());
+        assert!(repo.delete_access_token(&user_id, "tok-1").unwrap());
+        assert!(repo.list_access_tokens(&user_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_admin_user_listing_and_overrides() {
+        let repo = setup_test_db();
+        let alice = repo.get_or_create_user("Alice@example.com").unwrap();
+        let bob = repo.get_or_create_user("bob@example.com").unwrap();
+        repo.create_device(&alice, Some("laptop"), None).unwrap();
+
+        let found = repo.list_users(Some("alice"), 10, 0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, alice);
+        assert_eq!(found[0].device_count, 1);
+        assert_eq!(
+            repo.list_users(Some(&bob), 10, 0).unwrap()[0].email,
+            "bob@example.com"
+        );
+        assert_eq!(repo.list_users(None, 10, 0).unwrap().len(), 2);
+        assert_eq!(repo.list_users(None, 1, 1).unwrap().len(), 1);
+
+        let counts = repo.account_counts().unwrap();
+        assert_eq!((counts.users, counts.devices), (2, 1));
+
+        let overrides = LimitOverrides {
+            device_limit: Some(5),
+            attachment_limit_bytes: Some(1 << 32),
+            ..Default::default()
+        };
+        assert!(repo.set_limit_overrides(&alice, overrides).unwrap());
+        assert_eq!(repo.get_limit_overrides(&alice).unwrap(), Some(overrides));
+        assert_eq!(repo.get_effective_device_limit(&alice).unwrap(), 5);
+        assert!(!repo.set_limit_overrides("missing", overrides).unwrap());
+        assert!(repo.get_limit_overrides("missing").unwrap().is_none());
+    }
 }
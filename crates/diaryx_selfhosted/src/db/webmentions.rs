@@ -0,0 +1,204 @@
+//! Webmention repository methods.
+
+use diaryx_server::domain::{WebmentionInfo, WebmentionStatus};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::sync::{Arc, Mutex};
+
+const WEBMENTION_COLUMNS: &str =
+    "id, namespace_id, source, target, object_key, status, source_title, created_at, updated_at";
+
+/// Received Webmentions and their moderation state (`webmentions`).
+pub struct WebmentionRepo {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl WebmentionRepo {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Insert a mention, or update the mutable fields of an existing one.
+    pub fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT INTO webmentions ({WEBMENTION_COLUMNS})
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT(id) DO UPDATE SET
+                    object_key = excluded.object_key,
+                    status = excluded.status,
+                    source_title = excluded.source_title,
+                    updated_at = excluded.updated_at"
+            ),
+            params![
+                mention.id,
+                mention.namespace_id,
+                mention.source,
+                mention.target,
+                mention.object_key,
+                mention.status.as_str(),
+                mention.source_title,
+                mention.created_at,
+                mention.updated_at
+            ],
+        )
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+    }
+
+    pub fn get_webmention(&self, id: &str) -> Option<WebmentionInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {WEBMENTION_COLUMNS} FROM webmentions WHERE id = ?1"),
+            params![id],
+            webmention_from_row,
+        )
+        .optional()
+        .ok()
+        .flatten()
+        .flatten()
+    }
+
+    pub fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Option<WebmentionInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!(
+                "SELECT {WEBMENTION_COLUMNS} FROM webmentions
+                 WHERE namespace_id = ?1 AND source = ?2 AND target = ?3"
+            ),
+            params![namespace_id, source, target],
+            webmention_from_row,
+        )
+        .optional()
+        .ok()
+        .flatten()
+        .flatten()
+    }
+
+    /// List a namespace's mentions, newest first, optionally in one status.
+    pub fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Vec<WebmentionInfo> {
+        let conn = self.conn.lock().unwrap();
+        conn.prepare(&format!(
+            "SELECT {WEBMENTION_COLUMNS} FROM webmentions
+             WHERE namespace_id = ?1 AND (?2 IS NULL OR status = ?2)
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?3"
+        ))
+        .and_then(|mut stmt| {
+            stmt.query_map(
+                params![namespace_id, status.map(|s| s.as_str()), limit],
+                webmention_from_row,
+            )
+            .map(|rows| rows.filter_map(|r| r.ok().flatten()).collect())
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn delete_webmention(&self, namespace_id: &str, id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM webmentions WHERE namespace_id = ?1 AND id = ?2",
+            params![namespace_id, id],
+        )
+        .map(|n| n > 0)
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// `None` for rows with an unknown status.
+fn webmention_from_row(row: &Row<'_>) -> rusqlite::Result<Option<WebmentionInfo>> {
+    let status: String = row.get(5)?;
+    let Some(status) = WebmentionStatus::parse(&status) else {
+        return Ok(None);
+    };
+    Ok(Some(WebmentionInfo {
+        id: row.get(0)?,
+        namespace_id: row.get(1)?,
+        source: row.get(2)?,
+        target: row.get(3)?,
+        object_key: row.get(4)?,
+        status,
+        source_title: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::schema::init_database;
+
+    fn mention(id: &str, source: &str, created_at: i64) -> WebmentionInfo {
+        WebmentionInfo {
+            id: id.to_string(),
+            namespace_id: "ns1".to_string(),
+            source: source.to_string(),
+            target: "https://me.example/post.html".to_string(),
+            object_key: "public/post.html".to_string(),
+            status: WebmentionStatus::Unverified,
+            source_title: None,
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    #[test]
+    fn webmentions_upsert_list_and_delete() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, email, created_at, tier) VALUES ('u1', 'u1@test.com', 0, 'free');
+             INSERT INTO namespaces (id, owner_user_id, created_at) VALUES ('ns1', 'u1', 0);",
+        )
+        .unwrap();
+        let conn = Arc::new(Mutex::new(conn));
+        let repo = WebmentionRepo::new(conn);
+
+        repo.upsert_webmention(&mention("a", "https://one.example/", 100))
+            .unwrap();
+        repo.upsert_webmention(&mention("b", "https://two.example/", 200))
+            .unwrap();
+        repo.upsert_webmention(&WebmentionInfo {
+            status: WebmentionStatus::Pending,
+            source_title: Some("One".to_string()),
+            updated_at: 300,
+            ..mention("a", "https://one.example/", 100)
+        })
+        .unwrap();
+
+        let found = repo
+            .find_webmention(
+                "ns1",
+                "https://one.example/",
+                "https://me.example/post.html",
+            )
+            .unwrap();
+        assert_eq!(found.status, WebmentionStatus::Pending);
+        assert_eq!(found.source_title.as_deref(), Some("One"));
+        assert_eq!(found.created_at, 100);
+
+        let ids: Vec<String> = repo
+            .list_webmentions("ns1", None, 10)
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(ids, ["b", "a"]);
+        let pending = repo.list_webmentions("ns1", Some(WebmentionStatus::Pending), 10);
+        assert_eq!(pending.len(), 1);
+
+        assert!(!repo.delete_webmention("other", "a").unwrap());
+        assert!(repo.delete_webmention("ns1", "a").unwrap());
+        assert!(repo.get_webmention("a").is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! Account data export and deletion — `GET /auth/account/export`,
+//! `DELETE /auth/account` and `POST /auth/account/confirm-deletion`.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::current_user`, shared
+//! with the Cloudflare worker adapter. Like the other `/auth` routes these
+//! need a device session; access tokens can't reach them.
+
+use crate::auth::RequireAuth;
+use crate::email::EmailService;
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
+};
+use diaryx_server::domain::AccountExportRecord;
+use diaryx_server::ports::{
+    AccountExportSink, ArkIndexStore, AuthStore, BlobStore, DomainMappingCache, NamespaceStore,
+    ObjectMetaStore, PasskeyStore, ServerCoreError, UserStore,
+};
+use diaryx_server::use_cases::current_user::{
+    AccountDeletionService, AccountExportService, ConfirmAccountDeletionRequest,
+    encode_account_export_record,
+};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Records buffered between the export task and the response body.
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+
+/// Shared state for account handlers.
+#[derive(Clone)]
+pub struct AccountState {
+    pub auth_store: Arc<dyn AuthStore>,
+    pub user_store: Arc<dyn UserStore>,
+    pub passkey_store: Arc<dyn PasskeyStore>,
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub object_meta_store: Arc<dyn ObjectMetaStore>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub ark_index_store: Arc<dyn ArkIndexStore>,
+    /// Deleted namespaces' domains are dropped from here when configured.
+    pub domain_mapping_cache: Option<Arc<dyn DomainMappingCache>>,
+    /// Sends confirmation emails when configured; otherwise the confirmation
+    /// link is returned to the caller (dev mode).
+    pub email_service: Arc<EmailService>,
+    /// Base URL of the web app; confirmation links are
+    /// `{app_base_url}?delete_account=…`.
+    pub app_base_url: String,
+    pub token_signing_key: Vec<u8>,
+}
+
+impl AccountState {
+    fn deletion_service(&self) -> AccountDeletionService<'_> {
+        let mut service = AccountDeletionService::new(
+            self.user_store.as_ref(),
+            self.namespace_store.as_ref(),
+            self.blob_store.as_ref(),
+            &self.token_signing_key,
+        );
+        if self.email_service.is_configured() {
+            service = service.with_mailer(self.email_service.as_ref());
+        }
+        if let Some(cache) = &self.domain_mapping_cache {
+            service = service.with_domain_cache(cache.as_ref());
+        }
+        service
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Router (mounted under /auth)
+// ---------------------------------------------------------------------------
+
+pub fn account_routes(state: AccountState) -> Router {
+    Router::new()
+        .route("/account", delete(request_account_deletion))
+        .route("/account/export", get(export_account))
+        .route("/account/confirm-deletion", post(confirm_account_deletion))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> Response {
+    if matches!(err, ServerCoreError::Internal(_)) {
+        error!("Account operation failed: {}", err);
+    }
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+/// Export sink that hands encoded lines to the response body.
+struct ChannelSink(mpsc::Sender<Result<Bytes, std::io::Error>>);
+
+#[async_trait::async_trait]
+impl AccountExportSink for ChannelSink {
+    async fn write_record(&mut self, record: &AccountExportRecord) -> Result<(), ServerCoreError> {
+        let line = encode_account_export_record(record)?;
+        self.0
+            .send(Ok(Bytes::from(line)))
+            .await
+            .map_err(|_| ServerCoreError::unavailable("export client disconnected"))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /auth/account/export — stream everything held about the caller as
+/// NDJSON. A missing `end` record marks an export that failed part way.
+async fn export_account(
+    State(state): State<AccountState>,
+    RequireAuth(auth): RequireAuth,
+) -> Response {
+    let (tx, rx) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    let user_id = auth.user.id.clone();
+    tokio::spawn(async move {
+        let service = AccountExportService::new(
+            state.auth_store.as_ref(),
+            state.namespace_store.as_ref(),
+            state.object_meta_store.as_ref(),
+            state.blob_store.as_ref(),
+            state.ark_index_store.as_ref(),
+        )
+        .with_passkeys(state.passkey_store.as_ref());
+        let mut sink = ChannelSink(tx.clone());
+        if let Err(e) = service.export(&user_id, &mut sink).await {
+            warn!(user_id = %user_id, "account export failed: {e}");
+            let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"diaryx-account.ndjson\"".to_string(),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// DELETE /auth/account — start deleting the caller's account. Nothing is
+/// removed until the emailed link is confirmed; `202` with the link's expiry
+/// (and, in dev mode, the link itself).
+async fn request_account_deletion(
+    State(state): State<AccountState>,
+    RequireAuth(auth): RequireAuth,
+) -> Response {
+    match state
+        .deletion_service()
+        .request(&auth.user.id, &auth.user.email, &state.app_base_url)
+        .await
+    {
+        Ok(requested) => {
+            if !requested.emailed {
+                warn!("Email not configured - returning account deletion link to caller");
+            }
+            (StatusCode::ACCEPTED, Json(requested)).into_response()
+        }
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /auth/account/confirm-deletion — delete the caller's account, its
+/// namespaces and their blobs. `403` if the token is invalid, expired, or
+/// was issued to another account.
+async fn confirm_account_deletion(
+    State(state): State<AccountState>,
+    RequireAuth(auth): RequireAuth,
+    Json(body): Json<ConfirmAccountDeletionRequest>,
+) -> Response {
+    match state
+        .deletion_service()
+        .confirm(&auth.user.id, &body.token)
+        .await
+    {
+        Ok(summary) => {
+            info!(
+                "Deleted account {} ({} namespaces, {} blobs)",
+                auth.user.id, summary.namespaces, summary.blobs_deleted
+            );
+            Json(summary).into_response()
+        }
+        Err(e) => core_error_response(e),
+    }
+}
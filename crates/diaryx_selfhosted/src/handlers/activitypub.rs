@@ -0,0 +1,185 @@
+//! ActivityPub handlers: each namespace's actor document, outbox, followers
+//! collection and inbox under `/namespaces/{id}/actor`, and the WebFinger
+//! endpoint that resolves `acct:{id}@{host}` to it.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::activitypub`, shared
+//! with the Cloudflare worker adapter. Only namespaces with a public audience
+//! have an actor. Deliveries run on the job sink (see
+//! [`crate::jobs::TokioJobSink::with_activitypub`]); new pages are announced
+//! by the build.
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{OriginalUri, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Json},
+    routing::{get, post},
+};
+use diaryx_server::ports::{
+    ActivityPubClient, ActivityPubStore, JobSink, NamespaceStore, ServerCoreError,
+};
+use diaryx_server::use_cases::activitypub::{
+    ACTIVITY_JSON, ActivityPubInbox, ActivityPubService, InboxRequest, JRD_JSON, WebFingerQuery,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Shared state for ActivityPub handlers.
+#[derive(Clone)]
+pub struct ActivityPubState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub activitypub_store: Arc<dyn ActivityPubStore>,
+    /// Fetches signing keys of remote actors.
+    pub client: Arc<dyn ActivityPubClient>,
+    /// Runs deliveries (see [`crate::jobs::TokioJobSink`]).
+    pub job_sink: Arc<dyn JobSink>,
+    /// Public API base URL (`{SITE_BASE_URL}/api`) actor ids live under.
+    pub api_base_url: String,
+}
+
+impl ActivityPubState {
+    fn service(&self) -> ActivityPubService<'_> {
+        ActivityPubService::new(
+            self.namespace_store.as_ref(),
+            self.activitypub_store.as_ref(),
+            &self.api_base_url,
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Routers
+// ---------------------------------------------------------------------------
+
+/// Actor routes, mounted under `/namespaces/{ns_id}`.
+pub fn activitypub_routes(state: ActivityPubState) -> Router {
+    Router::new()
+        .route("/actor", get(get_actor))
+        .route("/actor/outbox", get(get_outbox))
+        .route("/actor/followers", get(get_followers))
+        .route("/actor/inbox", post(post_inbox))
+        .with_state(state)
+}
+
+/// `/.well-known/webfinger`, mounted at the root rather than under `/api`.
+pub fn webfinger_routes(state: ActivityPubState) -> Router {
+    Router::new()
+        .route("/.well-known/webfinger", get(webfinger))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::UNAUTHORIZED,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+fn document_response(
+    result: Result<Value, ServerCoreError>,
+    content_type: &'static str,
+) -> axum::response::Response {
+    match result {
+        Ok(doc) => ([(header::CONTENT_TYPE, content_type)], doc.to_string()).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /.well-known/webfinger?resource=acct:{ns_id}@{host} — point at the
+/// namespace's actor.
+async fn webfinger(
+    State(state): State<ActivityPubState>,
+    Query(query): Query<WebFingerQuery>,
+) -> impl IntoResponse {
+    document_response(state.service().webfinger(&query.resource).await, JRD_JSON)
+}
+
+/// GET /namespaces/{ns_id}/actor — the actor document, with its public key.
+async fn get_actor(
+    State(state): State<ActivityPubState>,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    document_response(state.service().actor(&ns_id).await, ACTIVITY_JSON)
+}
+
+/// GET /namespaces/{ns_id}/actor/outbox — the public pages, newest first.
+async fn get_outbox(
+    State(state): State<ActivityPubState>,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    document_response(state.service().outbox(&ns_id).await, ACTIVITY_JSON)
+}
+
+/// GET /namespaces/{ns_id}/actor/followers — the follower count.
+async fn get_followers(
+    State(state): State<ActivityPubState>,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    document_response(state.service().followers(&ns_id).await, ACTIVITY_JSON)
+}
+
+/// POST /namespaces/{ns_id}/actor/inbox — receive a signed activity.
+/// Answers `202` once handled; `401` when the signature doesn't check out.
+async fn post_inbox(
+    State(state): State<ActivityPubState>,
+    Path(ns_id): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let headers: Vec<(String, String)> = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect();
+    let path = uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or(uri.path());
+    let request = InboxRequest {
+        path,
+        headers: &headers,
+        body: &body,
+    };
+    let inbox = ActivityPubInbox::new(
+        state.namespace_store.as_ref(),
+        state.activitypub_store.as_ref(),
+        state.client.as_ref(),
+        state.job_sink.as_ref(),
+        &state.api_base_url,
+    );
+    match inbox
+        .receive(&ns_id, &request, chrono::Utc::now().timestamp())
+        .await
+    {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
@@ -0,0 +1,554 @@
+//! Operator admin API under `/admin`: user and namespace listings, usage,
+//! tier and limit overrides, session/device revocation, namespace takedown
+//! and a server health summary.
+//!
+//! Mounted only when `ADMIN_SECRET` is set. Every request must carry
+//! `Authorization: Bearer <ADMIN_SECRET>`; user sessions and access tokens
+//! are not accepted. The `admin` subcommand (see [`crate::admin`]) is a thin
+//! client for these routes.
+
+use crate::config::Config;
+use crate::db::{AuthRepo, LimitOverrides, NamespaceRepo, UserTier};
+use axum::{
+    Router,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Json},
+    routing::{delete, get, put},
+};
+use diaryx_server::ports::{
+    BlobStore, DomainMappingCache, NamespaceStore, ObjectMetaStore, ServerCoreError,
+};
+use diaryx_server::use_cases::namespaces::NamespaceService;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Page size when `limit` is not given.
+const DEFAULT_PAGE_SIZE: u32 = 50;
+/// Largest page a listing returns.
+const MAX_PAGE_SIZE: u32 = 500;
+
+/// Shared state for admin handlers.
+#[derive(Clone)]
+pub struct AdminState {
+    pub admin_secret: String,
+    pub auth_repo: Arc<AuthRepo>,
+    pub ns_repo: Arc<NamespaceRepo>,
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub object_meta_store: Arc<dyn ObjectMetaStore>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub domain_mapping_cache: Option<Arc<dyn DomainMappingCache>>,
+    pub config: Arc<Config>,
+    /// Unix seconds the server started, for the uptime in `/admin/health`.
+    pub started_at: i64,
+}
+
+/// Extractor that admits only requests bearing the admin secret.
+pub struct RequireAdmin;
+
+impl FromRequestParts<AdminState> for RequireAdmin {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AdminState,
+    ) -> Result<Self, Self::Rejection> {
+        let presented = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or((StatusCode::UNAUTHORIZED, "Admin secret required"))?;
+        if constant_time_eq(presented.trim(), &state.admin_secret) {
+            Ok(RequireAdmin)
+        } else {
+            Err((StatusCode::UNAUTHORIZED, "Invalid admin secret"))
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Router (mounted under /admin)
+// ---------------------------------------------------------------------------
+
+pub fn admin_routes(state: AdminState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/users", get(list_users))
+        .route("/users/{user_id}", get(get_user))
+        .route("/users/{user_id}/tier", put(set_tier))
+        .route("/users/{user_id}/limits", put(set_limits))
+        .route("/users/{user_id}/sessions", delete(revoke_sessions))
+        .route(
+            "/users/{user_id}/devices/{device_id}",
+            delete(revoke_device),
+        )
+        .route("/namespaces", get(list_namespaces))
+        .route("/namespaces/{ns_id}", delete(take_down_namespace))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+fn db_error(err: impl std::fmt::Display) -> axum::response::Response {
+    core_error_response(ServerCoreError::internal(err.to_string()))
+}
+
+fn user_not_found() -> axum::response::Response {
+    core_error_response(ServerCoreError::not_found("User not found"))
+}
+
+/// Clamp listing `limit`/`offset` query parameters.
+fn page(limit: Option<u32>, offset: Option<u32>) -> (u32, u32) {
+    (
+        limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        offset.unwrap_or(0),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Response types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+struct HealthResponse {
+    version: &'static str,
+    uptime_seconds: i64,
+    blob_store: BlobStoreHealth,
+    users: u64,
+    devices: u64,
+    active_sessions: u64,
+    namespaces: u64,
+    objects: u64,
+    stored_bytes: u64,
+    pending_webhook_deliveries: u64,
+    failed_webhook_deliveries: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct BlobStoreHealth {
+    backend: &'static str,
+    reachable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct UserSummaryResponse {
+    id: String,
+    email: String,
+    tier: &'static str,
+    created_at: i64,
+    last_login_at: Option<i64>,
+    device_count: u32,
+    namespace_count: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct UserDetailResponse {
+    id: String,
+    email: String,
+    tier: &'static str,
+    created_at: i64,
+    last_login_at: Option<i64>,
+    limits: LimitsResponse,
+    devices: Vec<DeviceResponse>,
+    namespaces: Vec<NamespaceSummaryResponse>,
+    usage: UsageResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LimitsResponse {
+    device_limit: Option<u32>,
+    attachment_limit_bytes: Option<u64>,
+    workspace_limit: Option<u32>,
+    published_site_limit: Option<u32>,
+}
+
+impl From<LimitOverrides> for LimitsResponse {
+    fn from(o: LimitOverrides) -> Self {
+        Self {
+            device_limit: o.device_limit,
+            attachment_limit_bytes: o.attachment_limit_bytes,
+            workspace_limit: o.workspace_limit,
+            published_site_limit: o.published_site_limit,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DeviceResponse {
+    id: String,
+    name: Option<String>,
+    user_agent: Option<String>,
+    created_at: i64,
+    last_seen_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct UsageResponse {
+    bytes_in: u64,
+    bytes_out: u64,
+    relay_seconds: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct NamespaceSummaryResponse {
+    id: String,
+    owner_user_id: String,
+    owner_email: Option<String>,
+    created_at: i64,
+    metadata: Option<serde_json::Value>,
+    object_count: u64,
+    stored_bytes: u64,
+}
+
+impl From<crate::db::NamespaceSummary> for NamespaceSummaryResponse {
+    fn from(ns: crate::db::NamespaceSummary) -> Self {
+        Self {
+            id: ns.id,
+            owner_user_id: ns.owner_user_id,
+            owner_email: ns.owner_email,
+            created_at: ns.created_at,
+            metadata: ns.metadata.and_then(|m| serde_json::from_str(&m).ok()),
+            object_count: ns.object_count,
+            stored_bytes: ns.stored_bytes,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /admin/health — version, uptime, blob store reachability and
+/// instance-wide counts.
+async fn health(State(state): State<AdminState>, _admin: RequireAdmin) -> impl IntoResponse {
+    let accounts = match state.auth_repo.account_counts() {
+        Ok(counts) => counts,
+        Err(e) => return db_error(e),
+    };
+    let storage = match state.ns_repo.storage_counts() {
+        Ok(counts) => counts,
+        Err(e) => return db_error(e),
+    };
+    // Any answer from the backend, including "not found", means it's reachable.
+    let probe = state.blob_store.exists("admin-health-probe").await;
+
+    Json(HealthResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: chrono::Utc::now().timestamp() - state.started_at,
+        blob_store: BlobStoreHealth {
+            backend: state.config.blob_store_backend().as_str(),
+            reachable: probe.is_ok(),
+            error: probe.err().map(|e| e.to_string()),
+        },
+        users: accounts.users,
+        devices: accounts.devices,
+        active_sessions: accounts.active_sessions,
+        namespaces: storage.namespaces,
+        objects: storage.objects,
+        stored_bytes: storage.stored_bytes,
+        pending_webhook_deliveries: storage.pending_webhook_deliveries,
+        failed_webhook_deliveries: storage.failed_webhook_deliveries,
+    })
+    .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct UserListQuery {
+    q: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+/// GET /admin/users?q=&limit=&offset= — users, newest first. `q` matches a
+/// user ID or part of an email address.
+async fn list_users(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Query(query): Query<UserListQuery>,
+) -> impl IntoResponse {
+    let q = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let (limit, offset) = page(query.limit, query.offset);
+    match state.auth_repo.list_users(q, limit, offset) {
+        Ok(users) => Json(
+            users
+                .into_iter()
+                .map(|u| UserSummaryResponse {
+                    id: u.id,
+                    email: u.email,
+                    tier: u.tier.as_str(),
+                    created_at: u.created_at,
+                    last_login_at: u.last_login_at,
+                    device_count: u.device_count,
+                    namespace_count: u.namespace_count,
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// GET /admin/users/{user_id} — a user with their limit overrides, devices,
+/// owned namespaces and usage totals.
+async fn get_user(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let user = match state.auth_repo.get_user(&user_id) {
+        Ok(Some(user)) => user,
+        Ok(None) => return user_not_found(),
+        Err(e) => return db_error(e),
+    };
+    let limits = match state.auth_repo.get_limit_overrides(&user_id) {
+        Ok(limits) => limits.unwrap_or_default(),
+        Err(e) => return db_error(e),
+    };
+    let devices = match state.auth_repo.get_user_devices(&user_id) {
+        Ok(devices) => devices,
+        Err(e) => return db_error(e),
+    };
+    let usage = match state.object_meta_store.get_usage_totals(&user_id).await {
+        Ok(usage) => usage,
+        Err(e) => return core_error_response(e),
+    };
+    let namespaces = state
+        .ns_repo
+        .list_namespace_summaries(Some(&user_id), MAX_PAGE_SIZE, 0);
+
+    Json(UserDetailResponse {
+        id: user.id,
+        email: user.email,
+        tier: user.tier.as_str(),
+        created_at: user.created_at.timestamp(),
+        last_login_at: user.last_login_at.map(|t| t.timestamp()),
+        limits: limits.into(),
+        devices: devices
+            .into_iter()
+            .map(|d| DeviceResponse {
+                id: d.id,
+                name: d.name,
+                user_agent: d.user_agent,
+                created_at: d.created_at.timestamp(),
+                last_seen_at: d.last_seen_at.timestamp(),
+            })
+            .collect(),
+        namespaces: namespaces.into_iter().map(Into::into).collect(),
+        usage: UsageResponse {
+            bytes_in: usage.bytes_in,
+            bytes_out: usage.bytes_out,
+            relay_seconds: usage.relay_seconds,
+        },
+    })
+    .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct SetTierRequest {
+    tier: String,
+}
+
+/// PUT /admin/users/{user_id}/tier — override a user's tier (`free` or
+/// `plus`). Billing webhooks may change it again later.
+async fn set_tier(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path(user_id): Path<String>,
+    Json(req): Json<SetTierRequest>,
+) -> impl IntoResponse {
+    let tier = match req.tier.as_str() {
+        "free" => UserTier::Free,
+        "plus" => UserTier::Plus,
+        other => {
+            return core_error_response(ServerCoreError::invalid_input(format!(
+                "Unknown tier: {other}"
+            )));
+        }
+    };
+    match state.auth_repo.set_user_tier(&user_id, tier) {
+        Ok(true) => {
+            warn!(user_id, tier = tier.as_str(), "Admin set user tier");
+            Json(serde_json::json!({ "tier": tier.as_str() })).into_response()
+        }
+        Ok(false) => user_not_found(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// PUT /admin/users/{user_id}/limits — replace a user's limit overrides.
+/// Omitted or null fields fall back to the tier default.
+async fn set_limits(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path(user_id): Path<String>,
+    Json(req): Json<LimitsResponse>,
+) -> impl IntoResponse {
+    let overrides = LimitOverrides {
+        device_limit: req.device_limit,
+        attachment_limit_bytes: req.attachment_limit_bytes,
+        workspace_limit: req.workspace_limit,
+        published_site_limit: req.published_site_limit,
+    };
+    match state.auth_repo.set_limit_overrides(&user_id, overrides) {
+        Ok(true) => {
+            warn!(user_id, ?overrides, "Admin set user limit overrides");
+            Json(LimitsResponse::from(overrides)).into_response()
+        }
+        Ok(false) => user_not_found(),
+        Err(e) => db_error(e),
+    }
+}
+
+/// DELETE /admin/users/{user_id}/sessions — sign the user out everywhere.
+/// Devices and access tokens are kept.
+async fn revoke_sessions(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    match state.auth_repo.get_user(&user_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => return user_not_found(),
+        Err(e) => return db_error(e),
+    }
+    match state.auth_repo.delete_user_sessions(&user_id) {
+        Ok(revoked) => {
+            warn!(user_id, revoked, "Admin revoked user sessions");
+            Json(serde_json::json!({ "revoked": revoked })).into_response()
+        }
+        Err(e) => db_error(e),
+    }
+}
+
+/// DELETE /admin/users/{user_id}/devices/{device_id} — remove one of the
+/// user's devices along with its sessions.
+async fn revoke_device(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path((user_id, device_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let devices = match state.auth_repo.get_user_devices(&user_id) {
+        Ok(devices) => devices,
+        Err(e) => return db_error(e),
+    };
+    if !devices.iter().any(|d| d.id == device_id) {
+        return core_error_response(ServerCoreError::not_found("Device not found"));
+    }
+    match state.auth_repo.delete_device(&device_id) {
+        Ok(()) => {
+            warn!(user_id, device_id, "Admin revoked device");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => db_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NamespaceListQuery {
+    owner: Option<String>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+/// GET /admin/namespaces?owner=&limit=&offset= — namespaces with their
+/// object count and stored bytes, largest first.
+async fn list_namespaces(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Query(query): Query<NamespaceListQuery>,
+) -> impl IntoResponse {
+    let (limit, offset) = page(query.limit, query.offset);
+    let namespaces = state
+        .ns_repo
+        .list_namespace_summaries(query.owner.as_deref(), limit, offset);
+    Json(
+        namespaces
+            .into_iter()
+            .map(NamespaceSummaryResponse::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct TakedownQuery {
+    reason: Option<String>,
+}
+
+/// DELETE /admin/namespaces/{ns_id}?reason= — take a namespace down: purge
+/// its blobs, edge-cache domain entries and rows, whoever owns it.
+async fn take_down_namespace(
+    State(state): State<AdminState>,
+    _admin: RequireAdmin,
+    Path(ns_id): Path<String>,
+    Query(query): Query<TakedownQuery>,
+) -> impl IntoResponse {
+    let ns = match state.namespace_store.get_namespace(&ns_id).await {
+        Ok(Some(ns)) => ns,
+        Ok(None) => {
+            return core_error_response(ServerCoreError::not_found("Namespace not found"));
+        }
+        Err(e) => return core_error_response(e),
+    };
+
+    let blobs_deleted = match state
+        .blob_store
+        .delete_by_prefix(&format!("ns/{}/", ns.id))
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => return core_error_response(e),
+    };
+    let cache = state.domain_mapping_cache.as_deref();
+    if let Err(e) = NamespaceService::new(state.namespace_store.as_ref())
+        .delete_with_cache(&ns.id, &ns.owner_user_id, cache)
+        .await
+    {
+        return core_error_response(e);
+    }
+
+    warn!(
+        namespace_id = %ns.id,
+        owner_user_id = %ns.owner_user_id,
+        reason = query.reason.as_deref().unwrap_or(""),
+        blobs_deleted,
+        "Admin took down namespace"
+    );
+    Json(serde_json::json!({ "blobs_deleted": blobs_deleted })).into_response()
+}
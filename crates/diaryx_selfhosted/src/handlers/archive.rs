@@ -0,0 +1,210 @@
+//! Namespace export/import handlers — `GET /namespaces/{id}/export` and
+//! `POST /namespaces/import`, for moving a namespace between servers.
+//!
+//! The archive is NDJSON (`application/x-ndjson`), one
+//! [`ArchiveRecord`](diaryx_server::domain::ArchiveRecord) per line. Export
+//! streams it straight from the stores; import reads the request body
+//! incrementally, so neither side buffers the whole namespace. The decoder
+//! caps both the length of a line and the size of the whole body.
+
+use crate::auth::RequireAuth;
+use axum::{
+    Router,
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+};
+use diaryx_server::domain::ArchiveRecord;
+use diaryx_server::ports::{
+    ArchiveSink, ArchiveSource, ArkIndexStore, BlobStore, DomainMappingCache, NamespaceStore,
+    ObjectMetaStore, ServerCoreError,
+};
+use diaryx_server::use_cases::archive::{
+    ArchiveDecoder, NamespaceArchiveService, encode_archive_record,
+};
+use diaryx_server::use_cases::namespaces::NamespaceService;
+use futures::StreamExt;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Records buffered between the export task and the response body.
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+
+/// Shared state for archive handlers.
+#[derive(Clone)]
+pub struct ArchiveState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub object_meta_store: Arc<dyn ObjectMetaStore>,
+    pub blob_store: Arc<dyn BlobStore>,
+    pub ark_index_store: Arc<dyn ArkIndexStore>,
+    /// Imported custom domains are pushed here (best-effort) when configured.
+    pub domain_mapping_cache: Option<Arc<dyn DomainMappingCache>>,
+}
+
+// ---------------------------------------------------------------------------
+// Router (mounted under /namespaces)
+// ---------------------------------------------------------------------------
+
+pub fn archive_routes(state: ArchiveState) -> Router {
+    Router::new()
+        .route("/import", post(import_namespace))
+        .route("/{id}/export", get(export_namespace))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+/// Export sink that hands encoded lines to the response body.
+struct ChannelSink(mpsc::Sender<Result<Bytes, std::io::Error>>);
+
+#[async_trait::async_trait]
+impl ArchiveSink for ChannelSink {
+    async fn write_record(&mut self, record: &ArchiveRecord) -> Result<(), ServerCoreError> {
+        let line = encode_archive_record(record)?;
+        self.0
+            .send(Ok(Bytes::from(line)))
+            .await
+            .map_err(|_| ServerCoreError::unavailable("export client disconnected"))
+    }
+}
+
+/// Import source reading NDJSON lines off the request body as it arrives.
+struct BodySource {
+    body: axum::body::BodyDataStream,
+    decoder: ArchiveDecoder,
+    done: bool,
+}
+
+#[async_trait::async_trait]
+impl ArchiveSource for BodySource {
+    async fn next_record(&mut self) -> Result<Option<ArchiveRecord>, ServerCoreError> {
+        loop {
+            if let Some(record) = self.decoder.next_record()? {
+                return Ok(Some(record));
+            }
+            if self.done {
+                return self.decoder.finish();
+            }
+            match self.body.next().await {
+                Some(Ok(chunk)) => self.decoder.push(&chunk)?,
+                Some(Err(e)) => {
+                    return Err(ServerCoreError::invalid_input(format!(
+                        "failed to read archive body: {e}"
+                    )));
+                }
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /namespaces/{id}/export — stream an owned namespace as an NDJSON archive.
+///
+/// Ownership is checked up front so failures get a proper status; errors after
+/// streaming starts abort the body, and the missing `end` record tells the
+/// importer the archive is incomplete.
+async fn export_namespace(
+    State(state): State<ArchiveState>,
+    RequireAuth(auth): RequireAuth,
+    Path(id): Path<String>,
+) -> Response {
+    let namespaces = NamespaceService::new(state.namespace_store.as_ref());
+    if let Err(e) = namespaces.get(&id, &auth.user.id).await {
+        return core_error_response(e);
+    }
+
+    let (tx, rx) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    let user_id = auth.user.id.clone();
+    let ns_id = id.clone();
+    tokio::spawn(async move {
+        let service = NamespaceArchiveService::new(
+            state.namespace_store.as_ref(),
+            state.object_meta_store.as_ref(),
+            state.blob_store.as_ref(),
+            state.ark_index_store.as_ref(),
+        );
+        let mut sink = ChannelSink(tx.clone());
+        if let Err(e) = service.export(&ns_id, &user_id, &mut sink).await {
+            warn!(namespace_id = %ns_id, "namespace export failed: {e}");
+            let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+        }
+    });
+
+    let stream = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    });
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{id}.diaryx-archive.ndjson\""),
+            ),
+        ],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// POST /namespaces/import — recreate a namespace from an NDJSON archive,
+/// owned by the caller. `409` if its id or a custom domain is already taken.
+async fn import_namespace(
+    State(state): State<ArchiveState>,
+    RequireAuth(auth): RequireAuth,
+    body: Body,
+) -> Response {
+    let service = NamespaceArchiveService::new(
+        state.namespace_store.as_ref(),
+        state.object_meta_store.as_ref(),
+        state.blob_store.as_ref(),
+        state.ark_index_store.as_ref(),
+    );
+    let mut source = BodySource {
+        body: body.into_data_stream(),
+        decoder: ArchiveDecoder::new(),
+        done: false,
+    };
+
+    match service
+        .import(
+            &auth.user.id,
+            &mut source,
+            state.domain_mapping_cache.as_deref(),
+        )
+        .await
+    {
+        Ok(summary) => (StatusCode::CREATED, Json(summary)).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
@@ -5,6 +5,7 @@
 //! live in `diaryx_server::use_cases::audiences`, shared with the Cloudflare
 //! worker adapter.
 
+use super::WebhookState;
 use crate::auth::RequireAuth;
 use axum::{
     Router,
@@ -13,11 +14,14 @@ use axum::{
     response::{IntoResponse, Json},
     routing::{get, post, put},
 };
-use diaryx_server::ports::{BlobStore, NamespaceStore, ServerCoreError};
+use diaryx_server::ports::{
+    AuditLogStore, BlobStore, NamespaceMemberStore, NamespaceStore, ServerCoreError,
+};
 use diaryx_server::use_cases::audiences::{
     AudienceResponse, AudienceService, RotatePasswordRequest, SetAudienceRequest, TokenResponse,
     UnlockRequest,
 };
+use diaryx_server::use_cases::audit::AuditRecorder;
 use std::sync::Arc;
 
 /// Shared state for audience handlers.
@@ -28,6 +32,21 @@ pub struct AudienceState {
     pub token_signing_key: Vec<u8>,
     /// Blob store for writing `_audiences.json` metadata to R2.
     pub blob_store: Arc<dyn BlobStore>,
+    /// Collaborators; any member can list audiences.
+    pub member_store: Arc<dyn NamespaceMemberStore>,
+    /// Notified when audiences change.
+    pub webhooks: WebhookState,
+    /// Records audience changes and password unlocks.
+    pub audit_store: Arc<dyn AuditLogStore>,
+}
+
+impl AudienceState {
+    fn service(&self) -> AudienceService<'_> {
+        AudienceService::new(self.namespace_store.as_ref(), self.blob_store.as_ref())
+            .with_members(self.member_store.as_ref())
+            .with_webhooks(self.webhooks.dispatcher())
+            .with_audit(AuditRecorder::new(self.audit_store.as_ref()))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -86,7 +105,7 @@ async fn set_audience(
     Path((ns_id, name)): Path<(String, String)>,
     Json(req): Json<SetAudienceRequest>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service.set(&ns_id, &name, req.gates, &auth.user.id).await {
         Ok(info) => Json(AudienceResponse::from(info)).into_response(),
@@ -100,7 +119,7 @@ async fn list_audiences(
     RequireAuth(auth): RequireAuth,
     Path(ns_id): Path<String>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service.list(&ns_id, &auth.user.id).await {
         Ok(audiences) => {
@@ -119,7 +138,7 @@ async fn get_audience_link_token(
     RequireAuth(auth): RequireAuth,
     Path((ns_id, name)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service
         .issue_link_token(&state.token_signing_key, &ns_id, &name, &auth.user.id)
@@ -137,7 +156,7 @@ async fn unlock_audience(
     Path((ns_id, name)): Path<(String, String)>,
     Json(req): Json<UnlockRequest>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service
         .unlock_with_password(&state.token_signing_key, &ns_id, &name, &req.password)
@@ -157,7 +176,7 @@ async fn rotate_audience_password(
     Path((ns_id, name)): Path<(String, String)>,
     Json(req): Json<RotatePasswordRequest>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service
         .rotate_password_and_issue(
@@ -186,7 +205,7 @@ async fn delete_audience(
     RequireAuth(auth): RequireAuth,
     Path((ns_id, name)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    let service = AudienceService::new(state.namespace_store.as_ref(), state.blob_store.as_ref());
+    let service = state.service();
 
     match service.delete(&ns_id, &name, &auth.user.id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
@@ -198,10 +217,11 @@ async fn delete_audience(
 mod tests {
     use super::*;
     use crate::{
-        adapters::NativeNamespaceStore,
+        adapters::{NativeNamespaceMemberStore, NativeNamespaceStore, NativeWebhookStore},
         auth::AuthUser,
         blob_store::InMemoryBlobStore,
         db::{NamespaceRepo, init_database},
+        jobs::{OutboundClient, ReqwestWebhookTransport, TokioJobSink},
     };
     use axum::{
         body::to_bytes,
@@ -210,6 +230,7 @@ mod tests {
     use chrono::{TimeZone, Utc};
     use diaryx_server::audience_token::{GateKind, validate_audience_token};
     use diaryx_server::domain::GateInput;
+    use diaryx_server::testing::InMemoryAuditLogStore;
     use diaryx_server::{AuthSessionInfo, BlobStore, UserInfo, UserTier};
     use rusqlite::{Connection, params};
     use serde_json::{Value as JsonValue, json};
@@ -234,10 +255,26 @@ mod tests {
     }
 
     fn state(repo: Arc<NamespaceRepo>, blob_store: Arc<InMemoryBlobStore>) -> AudienceState {
+        let namespace_store: Arc<dyn NamespaceStore> =
+            Arc::new(NativeNamespaceStore::new(repo.clone()));
+        let webhook_store: Arc<dyn diaryx_server::ports::WebhookStore> =
+            Arc::new(NativeWebhookStore::new(repo.clone()));
+        let job_sink = Arc::new(TokioJobSink::new(
+            webhook_store.clone(),
+            Arc::new(ReqwestWebhookTransport::new(OutboundClient::new(false, 0))),
+        ));
         AudienceState {
-            namespace_store: Arc::new(NativeNamespaceStore::new(repo)),
+            namespace_store: namespace_store.clone(),
             token_signing_key: b"audience-signing-key".to_vec(),
             blob_store,
+            member_store: Arc::new(NativeNamespaceMemberStore::new(repo)),
+            webhooks: WebhookState {
+                namespace_store,
+                webhook_store,
+                job_sink,
+                allow_private_targets: false,
+            },
+            audit_store: Arc::new(InMemoryAuditLogStore::new()),
         }
     }
 
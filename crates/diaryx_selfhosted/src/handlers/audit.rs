@@ -0,0 +1,114 @@
+//! Audit log handlers: `GET /auth/audit` for the caller's own account and
+//! `GET /namespaces/{id}/audit` for a namespace they own.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::audit`, shared with the
+//! Cloudflare worker adapter. Events are recorded by the services the other
+//! handlers build, through their `with_audit` builders.
+
+use crate::auth::RequireAuth;
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::get,
+};
+use diaryx_server::ports::{AuditLogStore, NamespaceStore, ServerCoreError};
+use diaryx_server::use_cases::audit::{AuditLogQuery, AuditLogService, AuditRecorder};
+use std::sync::Arc;
+
+/// Shared state for audit log handlers.
+#[derive(Clone)]
+pub struct AuditState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub audit_store: Arc<dyn AuditLogStore>,
+}
+
+impl AuditState {
+    fn service(&self) -> AuditLogService<'_> {
+        AuditLogService::new(self.namespace_store.as_ref(), self.audit_store.as_ref())
+    }
+
+    /// Recorder for handlers that raise audit events themselves.
+    pub fn recorder(&self) -> AuditRecorder<'_> {
+        AuditRecorder::new(self.audit_store.as_ref())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Routers
+// ---------------------------------------------------------------------------
+
+/// Mounted under `/auth`.
+pub fn account_audit_routes(state: AuditState) -> Router {
+    Router::new()
+        .route("/audit", get(list_account_events))
+        .with_state(state)
+}
+
+/// Mounted under `/namespaces/{ns_id}`.
+pub fn namespace_audit_routes(state: AuditState) -> Router {
+    Router::new()
+        .route("/audit", get(list_namespace_events))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /auth/audit?before=&limit= — sign-ins, passkeys and access tokens on
+/// the caller's account, newest first.
+async fn list_account_events(
+    State(state): State<AuditState>,
+    RequireAuth(auth): RequireAuth,
+    Query(query): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    match state.service().list_for_user(&auth.user.id, &query).await {
+        Ok(events) => Json(events).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// GET /namespaces/{ns_id}/audit?before=&limit= — audience and domain
+/// events on a namespace, newest first. Owner only.
+async fn list_namespace_events(
+    State(state): State<AuditState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Query(query): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .list_for_namespace(&ns_id, &auth.user.id, &query)
+        .await
+    {
+        Ok(events) => Json(events).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
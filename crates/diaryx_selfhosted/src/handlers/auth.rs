@@ -7,9 +7,13 @@ use axum::{
     response::{IntoResponse, Json},
     routing::{delete, get, post},
 };
+use diaryx_server::domain::AuditAction;
 use diaryx_server::ports::{
-    AuthSessionStore, AuthStore, NamespaceStore, ServerCoreError, UserStore,
+    AccessTokenStore, AuditLogStore, AuthSessionStore, AuthStore, NamespaceStore, ServerCoreError,
+    UserStore,
 };
+use diaryx_server::use_cases::access_tokens::{AccessTokenService, CreateAccessTokenRequest};
+use diaryx_server::use_cases::audit::AuditRecorder;
 use diaryx_server::use_cases::current_user::CurrentUserService;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -24,7 +28,11 @@ pub struct AuthState {
     pub namespace_store: Arc<dyn NamespaceStore>,
     pub session_store: Arc<dyn AuthSessionStore>,
     pub user_store: Arc<dyn UserStore>,
+    pub access_token_store: Arc<dyn AccessTokenStore>,
     pub passkey_service: Arc<PasskeyService>,
+    /// Records passkey and access token changes. Sign-ins are recorded by
+    /// `magic_link_service`.
+    pub audit_store: Arc<dyn AuditLogStore>,
     /// Session expiry in days, used for cookie Max-Age.
     pub session_expiry_days: i64,
     /// Whether to set the `Secure` flag on session cookies.
@@ -171,12 +179,14 @@ pub fn auth_routes(state: AuthState) -> Router {
         .route("/verify-code", post(verify_code))
         .route("/me", get(get_current_user))
         .route("/logout", post(logout))
-        .route("/account", delete(delete_account))
         .route("/devices", get(list_devices))
         .route(
             "/devices/{device_id}",
             axum::routing::patch(rename_device).delete(delete_device),
         )
+        // Personal access tokens
+        .route("/tokens", get(list_access_tokens).post(create_access_token))
+        .route("/tokens/{id}", delete(revoke_access_token))
         // Passkey routes
         .route("/passkeys/register/start", post(passkey_register_start))
         .route("/passkeys/register/finish", post(passkey_register_finish))
@@ -631,28 +641,76 @@ async fn delete_device(
     StatusCode::NO_CONTENT.into_response()
 }
 
-/// DELETE /auth/account - Delete user account and all server data
-async fn delete_account(
+// ===== Access token handlers =====
+//
+// Access tokens can't reach these routes (see `required_access`), so token
+// management always needs a device session.
+
+fn access_token_error(error: ServerCoreError) -> axum::response::Response {
+    if matches!(error, ServerCoreError::Internal(_)) {
+        error!("Access token operation failed: {}", error);
+    }
+    (
+        status_for_core_error(&error),
+        Json(ErrorResponse::new(error.to_string())),
+    )
+        .into_response()
+}
+
+/// GET /auth/tokens - List the user's personal access tokens
+async fn list_access_tokens(
     State(state): State<AuthState>,
     RequireAuth(auth): RequireAuth,
 ) -> impl IntoResponse {
-    let user_id = &auth.user.id;
-
-    info!("Deleting account for user: {}", user_id);
-
-    // Delete user from database (CASCADE deletes namespaces, sessions, etc.)
-    if let Err(e) = state.user_store.delete_user(user_id).await {
-        error!("Failed to delete user {}: {}", user_id, e);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new("Failed to delete account")),
-        )
-            .into_response();
+    let service = AccessTokenService::new(
+        state.access_token_store.as_ref(),
+        state.namespace_store.as_ref(),
+    );
+    match service.list(&auth.user.id).await {
+        Ok(tokens) => Json(tokens).into_response(),
+        Err(e) => access_token_error(e),
     }
+}
 
-    info!("Successfully deleted account for user: {}", user_id);
+/// POST /auth/tokens - Create a personal access token. The response is the
+/// only time the token secret is returned.
+async fn create_access_token(
+    State(state): State<AuthState>,
+    RequireAuth(auth): RequireAuth,
+    Json(body): Json<CreateAccessTokenRequest>,
+) -> impl IntoResponse {
+    let service = AccessTokenService::new(
+        state.access_token_store.as_ref(),
+        state.namespace_store.as_ref(),
+    )
+    .with_audit(AuditRecorder::new(state.audit_store.as_ref()));
+    match service.create(&auth.user.id, body).await {
+        Ok(created) => {
+            info!(
+                "Access token {} created for user {}",
+                created.info.id, auth.user.email
+            );
+            (StatusCode::CREATED, Json(created)).into_response()
+        }
+        Err(e) => access_token_error(e),
+    }
+}
 
-    StatusCode::NO_CONTENT.into_response()
+/// DELETE /auth/tokens/:id - Revoke a personal access token
+async fn revoke_access_token(
+    State(state): State<AuthState>,
+    RequireAuth(auth): RequireAuth,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let service = AccessTokenService::new(
+        state.access_token_store.as_ref(),
+        state.namespace_store.as_ref(),
+    )
+    .with_audit(AuditRecorder::new(state.audit_store.as_ref()));
+    match service.revoke(&auth.user.id, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => access_token_error(e),
+    }
 }
 
 // ===== Passkey handlers =====
@@ -721,6 +779,14 @@ async fn passkey_register_finish(
                 "Passkey '{}' registered for user {}",
                 body.name, auth.user.email
             );
+            AuditRecorder::new(state.audit_store.as_ref())
+                .account(
+                    AuditAction::PasskeyRegistered,
+                    &auth.user.id,
+                    Some(&id),
+                    serde_json::json!({ "name": body.name }),
+                )
+                .await;
             (StatusCode::OK, Json(PasskeyRegisterFinishResponse { id })).into_response()
         }
         Err(crate::auth::PasskeyError::ChallengeNotFound) => (
@@ -908,6 +974,14 @@ async fn passkey_delete(
     match state.passkey_service.delete_passkey(&id, &auth.user.id) {
         Ok(true) => {
             info!("Passkey {} deleted for user {}", id, auth.user.email);
+            AuditRecorder::new(state.audit_store.as_ref())
+                .account(
+                    AuditAction::PasskeyDeleted,
+                    &auth.user.id,
+                    Some(&id),
+                    serde_json::Value::Null,
+                )
+                .await;
             StatusCode::NO_CONTENT
         }
         Ok(false) => StatusCode::NOT_FOUND,
@@ -0,0 +1,227 @@
+//! Reader comment handlers: the public endpoints readers of a gated audience
+//! post and read comments through, under `/public/{ns_id}/comments/{*key}`,
+//! and the owner's moderation queue under `/namespaces/{id}/comments`.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::comments`, shared with
+//! the Cloudflare worker adapter. Readers authenticate with the same
+//! `audience_token` query parameter public objects take; the owner is
+//! emailed about new comments when email is configured.
+
+use crate::auth::RequireAuth;
+use crate::email::EmailService;
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{delete, get, post},
+};
+use diaryx_server::audience_token::{AudienceTokenClaims, validate_audience_token};
+use diaryx_server::ports::{
+    ArkIndexStore, AuthStore, CommentStore, NamespaceStore, ServerCoreError,
+};
+use diaryx_server::use_cases::comments::{
+    CommentListQuery, CommentReader, CommentService, PostCommentRequest,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Shared state for comment handlers.
+#[derive(Clone)]
+pub struct CommentState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub ark_index_store: Arc<dyn ArkIndexStore>,
+    pub comment_store: Arc<dyn CommentStore>,
+    /// Looks up the owner's email address for notifications.
+    pub auth_store: Arc<dyn AuthStore>,
+    /// Emails the owner about new comments when configured.
+    pub email_service: Arc<EmailService>,
+    /// Verifies readers' audience tokens.
+    pub token_signing_key: Vec<u8>,
+}
+
+impl CommentState {
+    fn reader(&self) -> CommentReader<'_> {
+        let reader = CommentReader::new(
+            self.namespace_store.as_ref(),
+            self.ark_index_store.as_ref(),
+            self.comment_store.as_ref(),
+        );
+        if self.email_service.is_configured() {
+            reader.with_mailer(self.auth_store.as_ref(), self.email_service.as_ref())
+        } else {
+            reader
+        }
+    }
+
+    fn service(&self) -> CommentService<'_> {
+        CommentService::new(self.namespace_store.as_ref(), self.comment_store.as_ref())
+    }
+
+    fn claims(&self, params: &CommentTokenParams) -> Result<AudienceTokenClaims, ServerCoreError> {
+        params
+            .audience_token
+            .as_deref()
+            .and_then(|token| validate_audience_token(&self.token_signing_key, token))
+            .ok_or_else(|| ServerCoreError::permission_denied("A valid audience token is required"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentTokenParams {
+    pub audience_token: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Routers
+// ---------------------------------------------------------------------------
+
+/// Owner moderation routes, mounted under `/namespaces/{ns_id}`.
+pub fn comment_routes(state: CommentState) -> Router {
+    Router::new()
+        .route("/comments", get(list_comments))
+        .route("/comments/{id}", delete(delete_comment))
+        .route("/comments/{id}/approve", post(approve_comment))
+        .route("/comments/{id}/reject", post(reject_comment))
+        .with_state(state)
+}
+
+/// Reader routes, authenticated by audience token rather than a session.
+pub fn public_comment_routes(state: CommentState) -> Router {
+    Router::new()
+        .route(
+            "/public/{ns_id}/comments/{*key}",
+            get(list_page_comments).post(post_comment),
+        )
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Reader handlers
+// ---------------------------------------------------------------------------
+
+/// GET /public/{ns_id}/comments/{*key}?audience_token= — approved comments
+/// on a page, oldest first.
+async fn list_page_comments(
+    State(state): State<CommentState>,
+    Path((ns_id, key)): Path<(String, String)>,
+    Query(params): Query<CommentTokenParams>,
+) -> impl IntoResponse {
+    let claims = match state.claims(&params) {
+        Ok(claims) => claims,
+        Err(e) => return core_error_response(e),
+    };
+    match state.reader().list(&ns_id, &key, &claims).await {
+        Ok(comments) => Json(comments).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /public/{ns_id}/comments/{*key}?audience_token= — leave a comment
+/// (`author_name`, `body`). Answers `202`: it waits for the owner.
+async fn post_comment(
+    State(state): State<CommentState>,
+    Path((ns_id, key)): Path<(String, String)>,
+    Query(params): Query<CommentTokenParams>,
+    Json(req): Json<PostCommentRequest>,
+) -> impl IntoResponse {
+    let claims = match state.claims(&params) {
+        Ok(claims) => claims,
+        Err(e) => return core_error_response(e),
+    };
+    match state.reader().post(&ns_id, &key, &claims, &req).await {
+        Ok(comment) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "id": comment.id, "status": comment.status })),
+        )
+            .into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Owner handlers
+// ---------------------------------------------------------------------------
+
+/// GET /namespaces/{ns_id}/comments?status=&limit= — the moderation queue,
+/// newest first.
+async fn list_comments(
+    State(state): State<CommentState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Query(query): Query<CommentListQuery>,
+) -> impl IntoResponse {
+    match state.service().list(&ns_id, &auth.user.id, &query).await {
+        Ok(comments) => Json(comments).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+async fn moderate(
+    state: CommentState,
+    user_id: &str,
+    ns_id: &str,
+    id: &str,
+    approve: bool,
+) -> axum::response::Response {
+    match state.service().moderate(ns_id, id, user_id, approve).await {
+        Ok(comment) => Json(comment).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /namespaces/{ns_id}/comments/{id}/approve — show a comment to the
+/// audience.
+async fn approve_comment(
+    State(state): State<CommentState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    moderate(state, &auth.user.id, &ns_id, &id, true).await
+}
+
+/// POST /namespaces/{ns_id}/comments/{id}/reject — hide a comment.
+async fn reject_comment(
+    State(state): State<CommentState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    moderate(state, &auth.user.id, &ns_id, &id, false).await
+}
+
+/// DELETE /namespaces/{ns_id}/comments/{id} — forget a comment.
+async fn delete_comment(
+    State(state): State<CommentState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state.service().delete(&ns_id, &id, &auth.user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
@@ -5,7 +5,6 @@
 //! site-proxy worker can resolve custom domains at the edge without hitting
 //! this server.
 
-use super::require_namespace_owner;
 use crate::auth::RequireAuth;
 use crate::db::NamespaceRepo;
 use axum::{
@@ -18,7 +17,11 @@ use axum::{
 use diaryx_server::audience_token::{GateKind, validate_audience_token};
 use diaryx_server::domain::CustomDomainInfo as CoreCustomDomainInfo;
 use diaryx_server::domain::GateRecord;
-use diaryx_server::ports::{BlobStore, DomainMappingCache, NamespaceStore, ServerCoreError};
+use diaryx_server::ports::{
+    AuditLogStore, BlobStore, DomainMappingCache, NamespaceMemberStore, NamespaceStore,
+    ServerCoreError,
+};
+use diaryx_server::use_cases::audit::AuditRecorder;
 use diaryx_server::use_cases::domains::DomainService;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -30,12 +33,27 @@ pub struct DomainState {
     pub ns_repo: Arc<NamespaceRepo>,
     pub namespace_store: Arc<dyn NamespaceStore>,
     pub domain_mapping_cache: Arc<dyn DomainMappingCache>,
+    /// Collaborators; members can list domains, only owners change them.
+    pub member_store: Arc<dyn NamespaceMemberStore>,
     pub blob_store: Arc<dyn BlobStore>,
+    /// Records domain and subdomain changes.
+    pub audit_store: Arc<dyn AuditLogStore>,
     pub token_signing_key: Vec<u8>,
     /// Whether subdomain/custom-domain features are available.
     pub subdomains_available: bool,
 }
 
+impl DomainState {
+    fn service(&self) -> DomainService<'_> {
+        DomainService::new(
+            self.namespace_store.as_ref(),
+            self.domain_mapping_cache.as_ref(),
+        )
+        .with_members(self.member_store.as_ref())
+        .with_audit(AuditRecorder::new(self.audit_store.as_ref()))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Request / response types
 // ---------------------------------------------------------------------------
@@ -122,16 +140,9 @@ async fn register_domain(
     Path((ns_id, domain)): Path<(String, String)>,
     Json(req): Json<RegisterDomainRequest>,
 ) -> impl IntoResponse {
-    if let Err(resp) = require_namespace_owner(&state.ns_repo, &ns_id, &auth.user.id) {
-        return resp;
-    }
-
-    let service = DomainService::new(
-        state.namespace_store.as_ref(),
-        state.domain_mapping_cache.as_ref(),
-    );
+    let service = state.service();
     match service
-        .register_domain(&ns_id, &domain, &req.audience_name)
+        .register_domain(&ns_id, &domain, &req.audience_name, &auth.user.id)
         .await
     {
         Ok(info) => Json(DomainResponse::from(info)).into_response(),
@@ -145,11 +156,7 @@ async fn list_domains(
     RequireAuth(auth): RequireAuth,
     Path(ns_id): Path<String>,
 ) -> impl IntoResponse {
-    if let Err(resp) = require_namespace_owner(&state.ns_repo, &ns_id, &auth.user.id) {
-        return resp;
-    }
-
-    match state.namespace_store.list_custom_domains(&ns_id).await {
+    match state.service().list_domains(&ns_id, &auth.user.id).await {
         Ok(domains) => Json(
             domains
                 .into_iter()
@@ -167,15 +174,8 @@ async fn remove_domain(
     RequireAuth(auth): RequireAuth,
     Path((ns_id, domain)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    if let Err(resp) = require_namespace_owner(&state.ns_repo, &ns_id, &auth.user.id) {
-        return resp;
-    }
-
-    let service = DomainService::new(
-        state.namespace_store.as_ref(),
-        state.domain_mapping_cache.as_ref(),
-    );
-    match service.remove_domain(&ns_id, &domain).await {
+    let service = state.service();
+    match service.remove_domain(&ns_id, &domain, &auth.user.id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
         Err(error) => core_error_response(error),
     }
@@ -195,16 +195,14 @@ async fn claim_subdomain(
         )
             .into_response();
     }
-    if let Err(resp) = require_namespace_owner(&state.ns_repo, &ns_id, &auth.user.id) {
-        return resp;
-    }
-
-    let service = DomainService::new(
-        state.namespace_store.as_ref(),
-        state.domain_mapping_cache.as_ref(),
-    );
+    let service = state.service();
     match service
-        .claim_subdomain(&ns_id, &req.subdomain, req.default_audience.as_deref())
+        .claim_subdomain(
+            &ns_id,
+            &req.subdomain,
+            req.default_audience.as_deref(),
+            &auth.user.id,
+        )
         .await
     {
         Ok(claimed) => Json(SubdomainResponse {
@@ -223,15 +221,8 @@ async fn release_subdomain(
     RequireAuth(auth): RequireAuth,
     Path(ns_id): Path<String>,
 ) -> impl IntoResponse {
-    if let Err(resp) = require_namespace_owner(&state.ns_repo, &ns_id, &auth.user.id) {
-        return resp;
-    }
-
-    let service = DomainService::new(
-        state.namespace_store.as_ref(),
-        state.domain_mapping_cache.as_ref(),
-    );
-    match service.release_subdomain(&ns_id).await {
+    let service = state.service();
+    match service.release_subdomain(&ns_id, &auth.user.id).await {
         Ok(_) => StatusCode::NO_CONTENT.into_response(),
         Err(error) => core_error_response(error),
     }
@@ -393,7 +384,7 @@ async fn domain_auth(
 mod tests {
     use super::*;
     use crate::{
-        adapters::{NativeDomainMappingCache, NativeNamespaceStore},
+        adapters::{NativeDomainMappingCache, NativeNamespaceMemberStore, NativeNamespaceStore},
         auth::AuthUser,
         blob_store::InMemoryBlobStore,
         db::{NamespaceRepo, init_database},
@@ -406,6 +397,8 @@ mod tests {
     use diaryx_server::audience_token::{
         AudienceTokenClaims, GateKind as TestGateKind, create_audience_token,
     };
+    use diaryx_server::domain::{NamespaceMemberInfo, NamespaceRole};
+    use diaryx_server::testing::InMemoryAuditLogStore;
     use diaryx_server::{AuthSessionInfo, BlobStore, UserInfo, UserTier};
     use reqwest::Client;
     use rusqlite::{Connection, params};
@@ -433,7 +426,8 @@ mod tests {
     fn state(repo: Arc<NamespaceRepo>, blob_store: Arc<InMemoryBlobStore>) -> DomainState {
         DomainState {
             ns_repo: repo.clone(),
-            namespace_store: Arc::new(NativeNamespaceStore::new(repo)),
+            namespace_store: Arc::new(NativeNamespaceStore::new(repo.clone())),
+            member_store: Arc::new(NativeNamespaceMemberStore::new(repo)),
             domain_mapping_cache: Arc::new(NativeDomainMappingCache::new(
                 Client::new(),
                 "",
@@ -443,6 +437,7 @@ mod tests {
             blob_store,
             token_signing_key: b"domain-signing-key".to_vec(),
             subdomains_available: true,
+            audit_store: Arc::new(InMemoryAuditLogStore::new()),
         }
     }
 
@@ -528,6 +523,54 @@ mod tests {
         assert_eq!(json_body(empty).await, json!([]));
     }
 
+    #[tokio::test]
+    async fn domain_changes_are_owner_only_for_members() {
+        let repo = setup_repo(&["user1", "user2"]);
+        repo.create_namespace("workspace:alpha", "user1", None)
+            .expect("seed namespace");
+        repo.upsert_audience("workspace:alpha", "public", &[])
+            .expect("seed audience");
+        repo.upsert_member(&NamespaceMemberInfo {
+            namespace_id: "workspace:alpha".to_string(),
+            user_id: "user2".to_string(),
+            email: None,
+            role: NamespaceRole::Editor,
+            created_at: 1,
+        })
+        .expect("seed member");
+        let state = state(repo, Arc::new(InMemoryBlobStore::new("")));
+
+        let listed = list_domains(
+            State(state.clone()),
+            auth("user2"),
+            Path("workspace:alpha".to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(listed.status(), StatusCode::OK);
+
+        let registered = register_domain(
+            State(state.clone()),
+            auth("user2"),
+            Path(("workspace:alpha".to_string(), "example.com".to_string())),
+            Json(RegisterDomainRequest {
+                audience_name: "public".to_string(),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(registered.status(), StatusCode::FORBIDDEN);
+
+        let stranger = list_domains(
+            State(state),
+            auth("user3"),
+            Path("workspace:alpha".to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(stranger.status(), StatusCode::FORBIDDEN);
+    }
+
     #[tokio::test]
     async fn domain_routes_cover_subdomain_claim_and_release() {
         let repo = setup_repo(&["user1"]);
@@ -0,0 +1,223 @@
+//! Namespace collaborator handlers — members and invites under
+//! `/namespaces/{id}`, plus the caller-level `POST /invites/accept` and
+//! `GET /memberships`.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::members`, shared with the
+//! Cloudflare worker adapter.
+
+use crate::auth::RequireAuth;
+use crate::email::EmailService;
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{delete, get, patch, post},
+};
+use diaryx_server::ports::{NamespaceMemberStore, NamespaceStore, ServerCoreError};
+use diaryx_server::use_cases::members::{
+    AcceptInviteRequest, InviteMemberRequest, InviteResponse, NamespaceMemberService,
+    UpdateMemberRoleRequest,
+};
+use std::sync::Arc;
+
+/// Shared state for member handlers.
+#[derive(Clone)]
+pub struct MemberState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub member_store: Arc<dyn NamespaceMemberStore>,
+    /// Sends invite emails when configured; otherwise the accept link is
+    /// returned to the inviter (dev mode).
+    pub email_service: Arc<EmailService>,
+    /// Base URL of the web app; invite links are `{app_base_url}?invite=…`.
+    pub app_base_url: String,
+}
+
+impl MemberState {
+    fn service(&self) -> NamespaceMemberService<'_> {
+        let service =
+            NamespaceMemberService::new(self.namespace_store.as_ref(), self.member_store.as_ref());
+        if self.email_service.is_configured() {
+            service.with_mailer(self.email_service.as_ref())
+        } else {
+            service
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Routers
+// ---------------------------------------------------------------------------
+
+/// Member and invite management, mounted under `/namespaces/{ns_id}`.
+pub fn member_routes(state: MemberState) -> Router {
+    Router::new()
+        .route("/members", get(list_members))
+        .route(
+            "/members/{user_id}",
+            patch(update_member_role).delete(remove_member),
+        )
+        .route("/invites", get(list_invites).post(create_invite))
+        .route("/invites/{invite_id}", delete(revoke_invite))
+        .with_state(state)
+}
+
+/// Caller-level routes: accepting an invite and listing memberships.
+pub fn membership_routes(state: MemberState) -> Router {
+    Router::new()
+        .route("/invites/accept", post(accept_invite))
+        .route("/memberships", get(list_memberships))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /namespaces/{ns_id}/members — everyone with a role, owner first.
+async fn list_members(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    match state.service().list_members(&ns_id, &auth.user.id).await {
+        Ok(members) => Json(members).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// PATCH /namespaces/{ns_id}/members/{user_id} — change a member's role.
+async fn update_member_role(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, user_id)): Path<(String, String)>,
+    Json(req): Json<UpdateMemberRoleRequest>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .update_role(&ns_id, &auth.user.id, &user_id, req.role)
+        .await
+    {
+        Ok(member) => Json(member).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// DELETE /namespaces/{ns_id}/members/{user_id} — remove a member (or leave).
+async fn remove_member(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, user_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .remove_member(&ns_id, &auth.user.id, &user_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// GET /namespaces/{ns_id}/invites — pending invites.
+async fn list_invites(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    match state.service().list_invites(&ns_id, &auth.user.id).await {
+        Ok(invites) => Json(invites).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /namespaces/{ns_id}/invites — invite someone by email.
+async fn create_invite(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Json(req): Json<InviteMemberRequest>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .invite(
+            &ns_id,
+            &auth.user.id,
+            &auth.user.email,
+            req,
+            &state.app_base_url,
+        )
+        .await
+    {
+        Ok(created) => (StatusCode::CREATED, Json(InviteResponse::from(created))).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// DELETE /namespaces/{ns_id}/invites/{invite_id} — withdraw an invite.
+async fn revoke_invite(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, invite_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .revoke_invite(&ns_id, &auth.user.id, &invite_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /invites/accept — accept an invite sent to the caller's address.
+async fn accept_invite(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+    Json(req): Json<AcceptInviteRequest>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .accept(&req.token, &auth.user.id, &auth.user.email)
+        .await
+    {
+        Ok(member) => Json(member).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// GET /memberships — namespaces the caller collaborates on.
+async fn list_memberships(
+    State(state): State<MemberState>,
+    RequireAuth(auth): RequireAuth,
+) -> impl IntoResponse {
+    match state.service().list_memberships(&auth.user.id).await {
+        Ok(memberships) => Json(memberships).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
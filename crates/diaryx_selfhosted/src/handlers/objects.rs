@@ -1,5 +1,6 @@
 //! Object store handlers — `PUT/GET/DELETE/LIST /namespaces/{id}/objects`.
 
+use super::{ActivityPubState, WebhookState, WebmentionState};
 use crate::auth::RequireAuth;
 use axum::{
     Router,
@@ -9,12 +10,18 @@ use axum::{
     response::{IntoResponse, Json, Response},
     routing::{get, post, put},
 };
+use diaryx_render::RenderHooks;
 use diaryx_server::audience_token::{GateKind, validate_audience_token};
 use diaryx_server::domain::{GateRecord, ObjectMeta, UsageTotals};
 use diaryx_server::ports::{
-    ArkIndexStore, BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError,
+    ArkIndexStore, BlobStore, NamespaceMemberStore, NamespaceStore, ObjectMetaStore,
+    ServerCoreError,
+};
+use diaryx_server::use_cases::activitypub::ActivityPubPublisher;
+use diaryx_server::use_cases::ark::{
+    ARK_WORKSPACE_INDEX, ArkService, ErcKernel, Inflection, canonical_ark, inflection_json,
+    info_wants_json_ld, split_file_variant, versions_json,
 };
-use diaryx_server::use_cases::ark::{ARK_WORKSPACE_INDEX, ArkService, Inflection, inflection_json};
 use diaryx_server::use_cases::objects::ObjectService;
 use diaryx_server::use_cases::render::RenderService;
 use serde::{Deserialize, Serialize};
@@ -72,6 +79,16 @@ pub struct ObjectState {
     pub ark_index_store: Arc<dyn ArkIndexStore>,
     /// HMAC-SHA256 key for validating audience access tokens.
     pub token_signing_key: Vec<u8>,
+    /// Collaborators; editors can write objects and viewers read them.
+    pub member_store: Arc<dyn NamespaceMemberStore>,
+    /// Notified of object deletions and completed builds.
+    pub webhooks: WebhookState,
+    /// Builds list approved mentions and advertise the receiving endpoint.
+    pub webmentions: WebmentionState,
+    /// Builds keep the outbox in step and announce new public pages.
+    pub activitypub: ActivityPubState,
+    /// Plugin syntax (math, drawings, ...) builds expand into static HTML.
+    pub render_hooks: RenderHooks,
 }
 
 // ---------------------------------------------------------------------------
@@ -201,6 +218,8 @@ fn make_service(state: &ObjectState) -> ObjectService<'_> {
         state.object_meta_store.as_ref(),
         state.blob_store.as_ref(),
     )
+    .with_members(state.member_store.as_ref())
+    .with_webhooks(state.webhooks.dispatcher())
 }
 
 // ---------------------------------------------------------------------------
@@ -257,7 +276,7 @@ async fn put_object(
 
     // Register the ARK after the object is stored. A cross-device collision
     // (same provisional file blade, different object) surfaces as 409 so the
-    // client remints and republishes. The object PUT is already owner-gated.
+    // client remints and republishes. The object PUT is already editor-gated.
     if let Some(file_ark) = file_ark {
         let registered_key = dest_object_key.unwrap_or(result.key.as_str());
         let ark_service = ArkService::new(state.ark_index_store.as_ref());
@@ -275,6 +294,24 @@ async fn put_object(
         {
             return core_error_response(e);
         }
+        // Retain the source as an immutable `.<FILE>` version so earlier
+        // publishes stay addressable after this one replaces the source.
+        if let Some(source_key) = source_key {
+            let retained = match service
+                .retain_version(&ns_id, source_key, file_ark, &auth.user.id)
+                .await
+            {
+                Ok(retained) => retained,
+                Err(e) => return core_error_response(e),
+            };
+            if let Some(v) = retained
+                && let Err(e) = ark_service
+                    .record_version(&ns_id, file_ark, &v.content_hash, &v.key)
+                    .await
+            {
+                return core_error_response(e);
+            }
+        }
     }
 
     (
@@ -303,7 +340,19 @@ async fn build_namespace(
         state.object_meta_store.as_ref(),
         state.blob_store.as_ref(),
         state.ark_index_store.as_ref(),
-    );
+    )
+    .with_members(state.member_store.as_ref())
+    .with_webhooks(state.webhooks.dispatcher())
+    .with_webmentions(
+        state.webmentions.webmention_store.as_ref(),
+        &state.webmentions.api_base_url,
+    )
+    .with_activitypub(ActivityPubPublisher::new(
+        state.activitypub.activitypub_store.as_ref(),
+        state.activitypub.job_sink.as_ref(),
+        &state.activitypub.api_base_url,
+    ))
+    .with_render_hooks(state.render_hooks.clone());
     match service
         .build_namespace(&ns_id, &auth.user.id, params.base_url.as_deref())
         .await
@@ -590,8 +639,11 @@ fn inflection_from_params(params: &HashMap<String, String>) -> Inflection {
         Inflection::Content
     } else if params.contains_key("json") {
         Inflection::Json
-    } else if params.contains_key("info") {
+    } else if params.contains_key("info") || params.contains_key("?") {
+        // `??` arrives as a bare `?` query key.
         Inflection::Info
+    } else if params.contains_key("versions") {
+        Inflection::Versions
     } else if let Some(k) = params.get("meta") {
         Inflection::Meta(k.clone())
     } else if let Some(k) = params.keys().find(|k| k.starts_with('.')) {
@@ -618,31 +670,45 @@ async fn resolve_ark(
     State(state): State<ObjectState>,
     Path((ws, file)): Path<(String, String)>,
     Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    do_resolve(&state, &ws, &file, &params).await
+    do_resolve(&state, &ws, &file, &params, &headers).await
 }
 
 async fn resolve_ark_index(
     State(state): State<ObjectState>,
     Path(ws): Path<String>,
     Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    do_resolve(&state, &ws, ARK_WORKSPACE_INDEX, &params).await
+    do_resolve(&state, &ws, ARK_WORKSPACE_INDEX, &params, &headers).await
 }
 
 /// Resolve an ARK to content, honoring the query inflection and audience gates.
 /// `?content`/`?json`/`?info`/`?meta=` read the markdown source sibling; the
 /// default serves the rendered HTML. Gating is enforced on the canonical
-/// rendition (the source shares its audience).
+/// rendition (the source shares its audience). `?info` / `??` answer with ERC
+/// kernel metadata — JSON-LD when `Accept` asks for JSON, ERC text otherwise.
+///
+/// A `.<FILE>` variant on the file segment (`bcdfgr.3f9a0c1`) selects a
+/// retained source version instead: the default and `?content` serve its
+/// markdown, the JSON inflections read its frontmatter. `?versions` lists the
+/// retained versions.
 async fn do_resolve(
     state: &ObjectState,
     ws: &str,
     file: &str,
     params: &HashMap<String, String>,
+    headers: &HeaderMap,
 ) -> Response {
     let inflection = inflection_from_params(params);
     let token = params.get("audience_token").map(|s| s.as_str());
 
+    let (file, variant) = match split_file_variant(file) {
+        Ok(parts) => parts,
+        Err(e) => return core_error_response(e),
+    };
+
     let ark = ArkService::new(state.ark_index_store.as_ref());
     let entry = match ark.resolve(ws, file).await {
         Ok(e) => e,
@@ -665,41 +731,80 @@ async fn do_resolve(
         return StatusCode::FORBIDDEN.into_response();
     }
 
-    if inflection == Inflection::Default {
-        return match service
-            .fetch_blob(ws, &entry.object_key, access.meta.blob_key.as_deref())
-            .await
-        {
-            Ok(obj) => serve_blob(&obj.mime_type, obj.bytes),
+    if inflection == Inflection::Versions {
+        return match ark.list_versions(ws, file).await {
+            Ok(versions) => (StatusCode::OK, Json(versions_json(&versions))).into_response(),
             Err(e) => core_error_response(e),
         };
     }
 
-    // Source-backed inflections need the markdown sibling.
-    let Some(source_key) = entry.source_key.as_deref() else {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "no source for this ARK" })),
-        )
-            .into_response();
-    };
-    let src_access = match service.resolve_public_access(ws, source_key).await {
-        Ok(a) => a,
-        Err(e) => return core_error_response(e),
-    };
-    let obj = match service
-        .fetch_blob(ws, source_key, src_access.meta.blob_key.as_deref())
-        .await
-    {
-        Ok(o) => o,
-        Err(e) => return core_error_response(e),
+    // `version` is the full version a `.<FILE>` variant resolved to, so the
+    // canonical ARK is the same however short a prefix the request used.
+    let (obj, version) = if let Some(variant) = variant {
+        // Versioned: the retained source stands in for both renditions.
+        let version = match ark.resolve_version(ws, file, variant).await {
+            Ok(v) => v,
+            Err(e) => return core_error_response(e),
+        };
+        match service.fetch_object(ws, &version.object_key).await {
+            Ok(o) => (o, Some(version.version)),
+            Err(e) => return core_error_response(e),
+        }
+    } else {
+        if inflection == Inflection::Default {
+            return match service
+                .fetch_blob(ws, &entry.object_key, access.meta.blob_key.as_deref())
+                .await
+            {
+                Ok(obj) => serve_blob(&obj.mime_type, obj.bytes),
+                Err(e) => core_error_response(e),
+            };
+        }
+
+        // Source-backed inflections need the markdown sibling.
+        let Some(source_key) = entry.source_key.as_deref() else {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "no source for this ARK" })),
+            )
+                .into_response();
+        };
+        let src_access = match service.resolve_public_access(ws, source_key).await {
+            Ok(a) => a,
+            Err(e) => return core_error_response(e),
+        };
+        match service
+            .fetch_blob(ws, source_key, src_access.meta.blob_key.as_deref())
+            .await
+        {
+            Ok(o) => (o, None),
+            Err(e) => return core_error_response(e),
+        }
     };
 
-    if inflection == Inflection::Content {
+    if matches!(inflection, Inflection::Default | Inflection::Content) {
         return serve_blob("text/markdown; charset=utf-8", obj.bytes);
     }
 
     let src = String::from_utf8_lossy(&obj.bytes);
+    if inflection == Inflection::Info {
+        let erc = match ErcKernel::from_source(&src, &canonical_ark(ws, file, version.as_deref())) {
+            Ok(erc) => erc,
+            Err(e) => return core_error_response(e),
+        };
+        let accept = headers
+            .get(axum::http::header::ACCEPT)
+            .and_then(|v| v.to_str().ok());
+        return if info_wants_json_ld(accept) {
+            serve_blob(
+                "application/ld+json",
+                erc.to_json_ld().to_string().into_bytes(),
+            )
+        } else {
+            serve_blob("text/plain; charset=utf-8", erc.to_erc().into_bytes())
+        };
+    }
+
     match inflection_json(&src, &inflection) {
         Ok(json) => (StatusCode::OK, Json(json)).into_response(),
         Err(e) => core_error_response(e),
@@ -0,0 +1,147 @@
+//! Outbound webhook handlers under `/namespaces/{id}`: managing a namespace's
+//! webhooks and reading its delivery log.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::webhooks`, shared with
+//! the Cloudflare worker adapter. The same state carries the dispatcher the
+//! object, audience and build handlers raise events through.
+
+use crate::auth::RequireAuth;
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{delete, get},
+};
+use diaryx_server::ports::{JobSink, NamespaceStore, ServerCoreError, WebhookStore};
+use diaryx_server::use_cases::webhooks::{
+    CreateWebhookRequest, DeliveryLogQuery, WebhookDispatcher, WebhookService,
+};
+use std::sync::Arc;
+
+/// Shared state for webhook handlers.
+#[derive(Clone)]
+pub struct WebhookState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub webhook_store: Arc<dyn WebhookStore>,
+    /// Runs delivery attempts (see [`crate::jobs::TokioJobSink`]).
+    pub job_sink: Arc<dyn JobSink>,
+    /// Accept webhook URLs on loopback and private networks
+    /// ([`crate::Config::outbound_allow_private`]).
+    pub allow_private_targets: bool,
+}
+
+impl WebhookState {
+    fn service(&self) -> WebhookService<'_> {
+        WebhookService::new(self.namespace_store.as_ref(), self.webhook_store.as_ref())
+            .with_private_targets(self.allow_private_targets)
+    }
+
+    /// Dispatcher for services that raise webhook events.
+    pub fn dispatcher(&self) -> WebhookDispatcher<'_> {
+        WebhookDispatcher::new(self.webhook_store.as_ref(), self.job_sink.as_ref())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Router (mounted under /namespaces/{ns_id})
+// ---------------------------------------------------------------------------
+
+pub fn webhook_routes(state: WebhookState) -> Router {
+    Router::new()
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/deliveries", get(list_deliveries))
+        .route("/webhooks/{webhook_id}", delete(delete_webhook))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /namespaces/{ns_id}/webhooks — the namespace's webhooks, without
+/// their secrets.
+async fn list_webhooks(
+    State(state): State<WebhookState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+) -> impl IntoResponse {
+    match state.service().list(&ns_id, &auth.user.id).await {
+        Ok(webhooks) => Json(webhooks).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /namespaces/{ns_id}/webhooks — add a webhook. The response carries
+/// the signing secret; it is not shown again.
+async fn create_webhook(
+    State(state): State<WebhookState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Json(req): Json<CreateWebhookRequest>,
+) -> impl IntoResponse {
+    match state.service().create(&ns_id, &auth.user.id, req).await {
+        Ok(created) => (StatusCode::CREATED, Json(created)).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// DELETE /namespaces/{ns_id}/webhooks/{webhook_id} — remove a webhook and
+/// its delivery log.
+async fn delete_webhook(
+    State(state): State<WebhookState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, webhook_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .delete(&ns_id, &webhook_id, &auth.user.id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// GET /namespaces/{ns_id}/webhooks/deliveries?webhook_id=&limit= — the
+/// delivery log, newest first.
+async fn list_deliveries(
+    State(state): State<WebhookState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Query(query): Query<DeliveryLogQuery>,
+) -> impl IntoResponse {
+    match state
+        .service()
+        .deliveries(&ns_id, &auth.user.id, &query)
+        .await
+    {
+        Ok(deliveries) => Json(deliveries).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
@@ -0,0 +1,167 @@
+//! Webmention handlers under `/namespaces/{id}`: the public receiving
+//! endpoint published pages advertise, and the owner's moderation queue.
+//!
+//! Orchestration lives in `diaryx_server::use_cases::webmentions`, shared
+//! with the Cloudflare worker adapter. Verification runs on the job sink
+//! (see [`crate::jobs::TokioJobSink::with_webmentions`]); approved mentions
+//! appear on the next build.
+
+use crate::auth::RequireAuth;
+use axum::{
+    Form, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+    routing::{delete, get, post},
+};
+use diaryx_server::ports::{
+    ArkIndexStore, JobSink, NamespaceStore, ServerCoreError, WebmentionStore,
+};
+use diaryx_server::use_cases::webmentions::{
+    ReceiveWebmentionRequest, WebmentionListQuery, WebmentionReceiver, WebmentionService,
+};
+use std::sync::Arc;
+
+/// Shared state for Webmention handlers.
+#[derive(Clone)]
+pub struct WebmentionState {
+    pub namespace_store: Arc<dyn NamespaceStore>,
+    pub ark_index_store: Arc<dyn ArkIndexStore>,
+    pub webmention_store: Arc<dyn WebmentionStore>,
+    /// Runs source verification (see [`crate::jobs::TokioJobSink`]).
+    pub job_sink: Arc<dyn JobSink>,
+    /// Public API base URL (`{SITE_BASE_URL}/api`) pages advertise the
+    /// endpoint under.
+    pub api_base_url: String,
+}
+
+impl WebmentionState {
+    fn service(&self) -> WebmentionService<'_> {
+        WebmentionService::new(
+            self.namespace_store.as_ref(),
+            self.webmention_store.as_ref(),
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Router (mounted under /namespaces/{ns_id})
+// ---------------------------------------------------------------------------
+
+pub fn webmention_routes(state: WebmentionState) -> Router {
+    Router::new()
+        .route("/webmention", post(receive_webmention))
+        .route("/webmentions", get(list_webmentions))
+        .route("/webmentions/{id}", delete(delete_webmention))
+        .route("/webmentions/{id}/approve", post(approve_webmention))
+        .route("/webmentions/{id}/reject", post(reject_webmention))
+        .with_state(state)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn status_for_core_error(err: &ServerCoreError) -> StatusCode {
+    match err {
+        ServerCoreError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        ServerCoreError::Conflict(_) => StatusCode::CONFLICT,
+        ServerCoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        ServerCoreError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+        ServerCoreError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+        ServerCoreError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        ServerCoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn core_error_response(err: ServerCoreError) -> axum::response::Response {
+    let status = status_for_core_error(&err);
+    (
+        status,
+        Json(serde_json::json!({ "error": err.to_string() })),
+    )
+        .into_response()
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// POST /namespaces/{ns_id}/webmention — receive a Webmention
+/// (`application/x-www-form-urlencoded` `source` and `target`). No auth;
+/// answers `202` once queued for verification.
+async fn receive_webmention(
+    State(state): State<WebmentionState>,
+    Path(ns_id): Path<String>,
+    Form(req): Form<ReceiveWebmentionRequest>,
+) -> impl IntoResponse {
+    let receiver = WebmentionReceiver::new(
+        state.namespace_store.as_ref(),
+        state.ark_index_store.as_ref(),
+        state.webmention_store.as_ref(),
+        state.job_sink.as_ref(),
+    );
+    match receiver.receive(&ns_id, &req).await {
+        Ok(_) => StatusCode::ACCEPTED.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// GET /namespaces/{ns_id}/webmentions?status=&limit= — the moderation
+/// queue, newest first.
+async fn list_webmentions(
+    State(state): State<WebmentionState>,
+    RequireAuth(auth): RequireAuth,
+    Path(ns_id): Path<String>,
+    Query(query): Query<WebmentionListQuery>,
+) -> impl IntoResponse {
+    match state.service().list(&ns_id, &auth.user.id, &query).await {
+        Ok(mentions) => Json(mentions).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+async fn moderate(
+    state: WebmentionState,
+    user_id: &str,
+    ns_id: &str,
+    id: &str,
+    approve: bool,
+) -> axum::response::Response {
+    match state.service().moderate(ns_id, id, user_id, approve).await {
+        Ok(mention) => Json(mention).into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
+
+/// POST /namespaces/{ns_id}/webmentions/{id}/approve — show a verified
+/// mention from the next build on.
+async fn approve_webmention(
+    State(state): State<WebmentionState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    moderate(state, &auth.user.id, &ns_id, &id, true).await
+}
+
+/// POST /namespaces/{ns_id}/webmentions/{id}/reject — hide a mention,
+/// including when its sender re-sends it.
+async fn reject_webmention(
+    State(state): State<WebmentionState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    moderate(state, &auth.user.id, &ns_id, &id, false).await
+}
+
+/// DELETE /namespaces/{ns_id}/webmentions/{id} — forget a mention.
+async fn delete_webmention(
+    State(state): State<WebmentionState>,
+    RequireAuth(auth): RequireAuth,
+    Path((ns_id, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state.service().delete(&ns_id, &id, &auth.user.id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => core_error_response(e),
+    }
+}
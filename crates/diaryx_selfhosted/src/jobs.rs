@@ -0,0 +1,573 @@
+//! Background jobs: the tokio-backed [`JobSink`] the shared use cases enqueue
+//! onto, and the outbound webhook, Webmention and ActivityPub plumbing it
+//! drives.
+//!
+//! Jobs run on spawned tasks in this process; nothing is persisted by the
+//! sink itself. Webhook deliveries are recorded in the [`WebhookStore`]
+//! before they are enqueued, so [`spawn_webhook_retries`] re-enqueues
+//! anything a restart dropped along with the retries that come due. A
+//! Webmention whose verification a restart dropped stays unverified until
+//! its sender sends it again. ActivityPub deliveries are attempted once;
+//! followers pick up anything they missed from the outbox.
+//!
+//! Every URL these jobs reach was supplied by a user, so they go through an
+//! [`OutboundClient`], which refuses to connect to this machine or a private
+//! network however the URL gets there: as an IP literal, through DNS, or by
+//! redirect.
+
+use async_trait::async_trait;
+use diaryx_server::outbound::{is_internal_host, is_internal_ip};
+use diaryx_server::ports::{
+    ActivityPubClient, ActivityPubStore, JobSink, ServerCoreError, WebhookStore, WebhookTransport,
+    WebmentionFetcher, WebmentionStore,
+};
+use diaryx_server::use_cases::activitypub::{
+    ACTIVITYPUB_DELIVERY_JOB, ActivityPubDelivery, ActivityPubDeliveryJob,
+};
+use diaryx_server::use_cases::webhooks::{
+    WEBHOOK_DELIVERY_JOB, WebhookDeliveryJob, WebhookDeliveryService, WebhookDispatcher,
+};
+use diaryx_server::use_cases::webmentions::{
+    WEBMENTION_VERIFY_JOB, WebmentionVerifier, WebmentionVerifyJob,
+};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{Method, RequestBuilder, Url, redirect};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, warn};
+
+/// How often due webhook retries are re-enqueued.
+pub const WEBHOOK_RETRY_INTERVAL_SECS: u64 = 60;
+
+/// Per-attempt timeout for webhook requests.
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Timeout for fetching a Webmention source.
+const WEBMENTION_FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Per-request timeout for ActivityPub fetches and deliveries.
+const ACTIVITYPUB_TIMEOUT_SECS: u64 = 10;
+
+/// Redirects an [`OutboundClient`] follows before giving up.
+pub const MAX_OUTBOUND_REDIRECTS: usize = 5;
+
+/// What ActivityPub delivery jobs need: the actor key store, the client
+/// that posts to inboxes, and the API base URL actor ids are built from.
+type ActivityPubJobDeps = (
+    Arc<dyn ActivityPubStore>,
+    Arc<dyn ActivityPubClient>,
+    String,
+);
+
+/// Runs enqueued jobs on the tokio runtime.
+#[derive(Clone)]
+pub struct TokioJobSink {
+    webhook_store: Arc<dyn WebhookStore>,
+    transport: Arc<dyn WebhookTransport>,
+    webmentions: Option<(Arc<dyn WebmentionStore>, Arc<dyn WebmentionFetcher>)>,
+    activitypub: Option<ActivityPubJobDeps>,
+}
+
+impl TokioJobSink {
+    pub fn new(webhook_store: Arc<dyn WebhookStore>, transport: Arc<dyn WebhookTransport>) -> Self {
+        Self {
+            webhook_store,
+            transport,
+            webmentions: None,
+            activitypub: None,
+        }
+    }
+
+    /// Run Webmention verification jobs. Without this they are refused.
+    pub fn with_webmentions(
+        mut self,
+        webmention_store: Arc<dyn WebmentionStore>,
+        fetcher: Arc<dyn WebmentionFetcher>,
+    ) -> Self {
+        self.webmentions = Some((webmention_store, fetcher));
+        self
+    }
+
+    /// Run ActivityPub delivery jobs. Without this they are refused.
+    pub fn with_activitypub(
+        mut self,
+        activitypub_store: Arc<dyn ActivityPubStore>,
+        client: Arc<dyn ActivityPubClient>,
+        api_base_url: String,
+    ) -> Self {
+        self.activitypub = Some((activitypub_store, client, api_base_url));
+        self
+    }
+}
+
+#[async_trait]
+impl JobSink for TokioJobSink {
+    async fn enqueue(&self, kind: &str, payload: Value) -> Result<(), ServerCoreError> {
+        match kind {
+            WEBHOOK_DELIVERY_JOB => {
+                let job = WebhookDeliveryJob::from_payload(&payload)?;
+                let webhook_store = self.webhook_store.clone();
+                let transport = self.transport.clone();
+                tokio::spawn(async move {
+                    let service =
+                        WebhookDeliveryService::new(webhook_store.as_ref(), transport.as_ref());
+                    let now = chrono::Utc::now().timestamp();
+                    if let Err(e) = service.deliver(&job.delivery_id, now).await {
+                        warn!("Webhook delivery {} failed: {}", job.delivery_id, e);
+                    }
+                });
+                Ok(())
+            }
+            WEBMENTION_VERIFY_JOB => {
+                let job = WebmentionVerifyJob::from_payload(&payload)?;
+                let Some((store, fetcher)) = self.webmentions.clone() else {
+                    return Err(ServerCoreError::unavailable(
+                        "Webmention verification is not configured",
+                    ));
+                };
+                tokio::spawn(async move {
+                    let verifier = WebmentionVerifier::new(store.as_ref(), fetcher.as_ref());
+                    let now = chrono::Utc::now().timestamp();
+                    if let Err(e) = verifier.verify(&job.webmention_id, now).await {
+                        warn!(
+                            "Webmention verification {} failed: {}",
+                            job.webmention_id, e
+                        );
+                    }
+                });
+                Ok(())
+            }
+            ACTIVITYPUB_DELIVERY_JOB => {
+                let job = ActivityPubDeliveryJob::from_payload(&payload)?;
+                let Some((store, client, api_base_url)) = self.activitypub.clone() else {
+                    return Err(ServerCoreError::unavailable(
+                        "ActivityPub delivery is not configured",
+                    ));
+                };
+                tokio::spawn(async move {
+                    let delivery =
+                        ActivityPubDelivery::new(store.as_ref(), client.as_ref(), &api_base_url);
+                    let now = chrono::Utc::now().timestamp();
+                    if let Err(e) = delivery.deliver(&job, now).await {
+                        warn!("ActivityPub delivery to {} failed: {}", job.inbox, e);
+                    }
+                });
+                Ok(())
+            }
+            other => Err(ServerCoreError::invalid_input(format!(
+                "Unknown job kind: {other}"
+            ))),
+        }
+    }
+}
+
+/// [`WebhookTransport`] over an [`OutboundClient`]. Give it one that
+/// follows no redirects: a receiver that redirects has failed the delivery.
+#[derive(Clone)]
+pub struct ReqwestWebhookTransport {
+    client: OutboundClient,
+}
+
+impl ReqwestWebhookTransport {
+    pub fn new(client: OutboundClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl WebhookTransport for ReqwestWebhookTransport {
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError> {
+        let mut request = self
+            .client
+            .request(Method::POST, url)?
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+            .body(body.to_vec());
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ServerCoreError::unavailable(e.to_string()))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// A `reqwest` client for URLs users supply. Unless private targets are
+/// allowed it refuses hosts that name this machine or a private network,
+/// names that resolve to one, and redirects to either; it never goes
+/// through a proxy, which would resolve names out of its sight.
+#[derive(Clone)]
+pub struct OutboundClient {
+    client: reqwest::Client,
+    allow_private: bool,
+}
+
+impl OutboundClient {
+    /// A client following up to `max_redirects` redirects, each checked like
+    /// the URL it started from. With none, a redirect is answered as is.
+    /// `allow_private` lifts the checks, for servers whose users deliberately
+    /// target their own network.
+    pub fn new(allow_private: bool, max_redirects: usize) -> Self {
+        let client = Self::builder(allow_private, max_redirects)
+            .build()
+            .expect("outbound reqwest client");
+        Self {
+            client,
+            allow_private,
+        }
+    }
+
+    fn builder(allow_private: bool, max_redirects: usize) -> reqwest::ClientBuilder {
+        let builder = reqwest::Client::builder().no_proxy();
+        let builder = if allow_private {
+            builder
+        } else {
+            builder.dns_resolver(Arc::new(PublicResolver))
+        };
+        if max_redirects == 0 {
+            return builder.redirect(redirect::Policy::none());
+        }
+        if allow_private {
+            return builder.redirect(redirect::Policy::limited(max_redirects));
+        }
+        builder.redirect(redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > max_redirects {
+                attempt.error("too many redirects")
+            } else if is_internal_url(attempt.url()) {
+                let refused = format!("redirect to {} refused", attempt.url());
+                attempt.error(refused)
+            } else {
+                attempt.follow()
+            }
+        }))
+    }
+
+    /// Start a request, refusing URLs that name an internal host outright.
+    /// Names that only resolve to one are refused when connecting.
+    fn request(&self, method: Method, url: &str) -> Result<RequestBuilder, ServerCoreError> {
+        let parsed = Url::parse(url)
+            .map_err(|e| ServerCoreError::invalid_input(format!("Invalid URL {url}: {e}")))?;
+        if !self.allow_private && is_internal_url(&parsed) {
+            return Err(ServerCoreError::invalid_input(format!(
+                "Not a public URL: {url}"
+            )));
+        }
+        Ok(self.client.request(method, parsed))
+    }
+}
+
+/// Whether a URL is not http(s) or its host names this machine or a
+/// private network.
+fn is_internal_url(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return true;
+    }
+    match url.host() {
+        Some(url::Host::Domain(host)) => is_internal_host(host),
+        Some(url::Host::Ipv4(ip)) => is_internal_ip(ip.into()),
+        Some(url::Host::Ipv6(ip)) => is_internal_ip(ip.into()),
+        None => true,
+    }
+}
+
+/// Resolves names with the system resolver and refuses any that resolve to
+/// an internal address, so a public name can't be pointed at one.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            if let Some(addr) = addrs.iter().find(|addr| is_internal_ip(addr.ip())) {
+                return Err(format!("{host} resolves to internal address {}", addr.ip()).into());
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+/// [`WebmentionFetcher`] over an [`OutboundClient`]. Redirects are followed
+/// as long as each one stays on the public internet.
+#[derive(Clone)]
+pub struct ReqwestWebmentionFetcher {
+    client: OutboundClient,
+}
+
+impl ReqwestWebmentionFetcher {
+    pub fn new(client: OutboundClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl WebmentionFetcher for ReqwestWebmentionFetcher {
+    async fn fetch(&self, url: &str, max_bytes: usize) -> Result<(u16, String), ServerCoreError> {
+        let mut response = self
+            .client
+            .request(Method::GET, url)?
+            .timeout(Duration::from_secs(WEBMENTION_FETCH_TIMEOUT_SECS))
+            .header("Accept", "text/html, */*;q=0.5")
+            .send()
+            .await
+            .map_err(|e| ServerCoreError::unavailable(e.to_string()))?;
+        let status = response.status().as_u16();
+        let mut body = Vec::new();
+        while body.len() < max_bytes {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                Ok(None) => break,
+                Err(e) => return Err(ServerCoreError::unavailable(e.to_string())),
+            }
+        }
+        body.truncate(max_bytes);
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
+    }
+}
+
+/// [`ActivityPubClient`] over an [`OutboundClient`], so actor and inbox URLs
+/// taken from remote documents can't lead to this machine or a private
+/// network, directly or by redirect.
+#[derive(Clone)]
+pub struct ReqwestActivityPubClient {
+    client: OutboundClient,
+}
+
+impl ReqwestActivityPubClient {
+    pub fn new(client: OutboundClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ActivityPubClient for ReqwestActivityPubClient {
+    async fn get(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        max_bytes: usize,
+    ) -> Result<(u16, String), ServerCoreError> {
+        let mut request = self
+            .client
+            .request(Method::GET, url)?
+            .timeout(Duration::from_secs(ACTIVITYPUB_TIMEOUT_SECS));
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| ServerCoreError::unavailable(e.to_string()))?;
+        let status = response.status().as_u16();
+        let mut body = Vec::new();
+        while body.len() < max_bytes {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                Ok(None) => break,
+                Err(e) => return Err(ServerCoreError::unavailable(e.to_string())),
+            }
+        }
+        body.truncate(max_bytes);
+        Ok((status, String::from_utf8_lossy(&body).into_owned()))
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError> {
+        let mut request = self
+            .client
+            .request(Method::POST, url)?
+            .timeout(Duration::from_secs(ACTIVITYPUB_TIMEOUT_SECS))
+            .body(body.to_vec());
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ServerCoreError::unavailable(e.to_string()))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Re-enqueue due webhook deliveries every [`WEBHOOK_RETRY_INTERVAL_SECS`]
+/// for the life of the process.
+pub fn spawn_webhook_retries(webhook_store: Arc<dyn WebhookStore>, job_sink: Arc<dyn JobSink>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(WEBHOOK_RETRY_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let dispatcher = WebhookDispatcher::new(webhook_store.as_ref(), job_sink.as_ref());
+            if let Err(e) = dispatcher.requeue_due(chrono::Utc::now().timestamp()).await {
+                error!("Webhook retry sweep failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::{StatusCode, header};
+    use axum::routing::{any, get};
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    /// Serve `/secret`, and `/hop?to=URL` redirecting there, on loopback.
+    async fn serve_redirector() -> SocketAddr {
+        let app = Router::new()
+            .route("/secret", get(|| async { "internal" }))
+            .route(
+                "/hop",
+                any(
+                    |axum::extract::Query(q): axum::extract::Query<
+                        std::collections::HashMap<String, String>,
+                    >| async move {
+                        (StatusCode::FOUND, [(header::LOCATION, q["to"].clone())])
+                    },
+                ),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    /// A guarded client that resolves `public.example` to `addr`, standing in
+    /// for a public site that redirects.
+    fn client_via_public_name(addr: SocketAddr) -> OutboundClient {
+        OutboundClient {
+            client: OutboundClient::builder(false, MAX_OUTBOUND_REDIRECTS)
+                .resolve("public.example", addr)
+                .build()
+                .unwrap(),
+            allow_private: false,
+        }
+    }
+
+    /// GET `url` through `client`, returning the final status and body.
+    async fn get_text(client: &OutboundClient, url: &str) -> Result<(u16, String), String> {
+        let response = client
+            .request(Method::GET, url)
+            .map_err(|e| e.to_string())?
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        Ok((status, response.text().await.map_err(|e| e.to_string())?))
+    }
+
+    #[tokio::test]
+    async fn internal_urls_are_refused_before_connecting() {
+        let addr = serve_redirector().await;
+        let client = OutboundClient::new(false, MAX_OUTBOUND_REDIRECTS);
+        for url in [
+            format!("http://{addr}/secret"),
+            format!("http://localhost:{}/secret", addr.port()),
+            format!("http://[::ffff:127.0.0.1]:{}/secret", addr.port()),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+            "file:///etc/passwd".to_string(),
+        ] {
+            assert!(get_text(&client, &url).await.is_err(), "{url}");
+        }
+
+        let allowed = OutboundClient::new(true, MAX_OUTBOUND_REDIRECTS);
+        let (status, body) = get_text(&allowed, &format!("http://{addr}/secret"))
+            .await
+            .unwrap();
+        assert_eq!((status, body.as_str()), (200, "internal"));
+    }
+
+    #[tokio::test]
+    async fn names_resolving_to_internal_addresses_are_refused() {
+        let err = PublicResolver
+            .resolve(Name::from_str("localhost").unwrap())
+            .await
+            .err()
+            .expect("localhost resolves to loopback");
+        assert!(err.to_string().contains("internal address"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirects_to_internal_addresses_are_refused() {
+        let addr = serve_redirector().await;
+        let client = client_via_public_name(addr);
+        let port = addr.port();
+
+        let (status, body) = get_text(&client, &format!("http://public.example:{port}/secret"))
+            .await
+            .unwrap();
+        assert_eq!((status, body.as_str()), (200, "internal"));
+
+        for target in [
+            format!("http://127.0.0.1:{port}/secret"),
+            format!("http://localhost:{port}/secret"),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+        ] {
+            let url = format!("http://public.example:{port}/hop?to={target}");
+            assert!(get_text(&client, &url).await.is_err(), "{target}");
+        }
+        let url = format!("http://public.example:{port}/hop?to=/secret");
+        assert_eq!(get_text(&client, &url).await.unwrap().0, 200);
+    }
+
+    #[tokio::test]
+    async fn webhook_deliveries_refuse_internal_urls_and_redirects() {
+        let addr = serve_redirector().await;
+        let port = addr.port();
+        let refusing = ReqwestWebhookTransport::new(OutboundClient::new(false, 0));
+        for url in [
+            format!("http://{addr}/secret"),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+        ] {
+            assert!(refusing.post(&url, &[], b"{}").await.is_err(), "{url}");
+        }
+
+        // A receiver that redirects gets its answer recorded, not followed.
+        let transport = ReqwestWebhookTransport::new(OutboundClient::new(true, 0));
+        let url = format!("http://{addr}/hop?to=http://127.0.0.1:{port}/secret");
+        assert_eq!(transport.post(&url, &[], b"{}").await.unwrap(), 302);
+    }
+
+    #[tokio::test]
+    async fn webmention_sources_refuse_internal_urls_and_redirects() {
+        let addr = serve_redirector().await;
+        let port = addr.port();
+        let fetcher = ReqwestWebmentionFetcher::new(client_via_public_name(addr));
+
+        let source = format!("http://public.example:{port}/secret");
+        let (status, body) = fetcher.fetch(&source, 1024).await.unwrap();
+        assert_eq!((status, body.as_str()), (200, "internal"));
+        let hop = format!("http://public.example:{port}/hop?to=http://127.0.0.1:{port}/secret");
+        assert!(fetcher.fetch(&hop, 1024).await.is_err());
+        let metadata = "http://169.254.169.254/latest/meta-data/";
+        assert!(fetcher.fetch(metadata, 1024).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn activitypub_requests_refuse_internal_urls_and_redirects() {
+        let addr = serve_redirector().await;
+        let port = addr.port();
+        let client = ReqwestActivityPubClient::new(client_via_public_name(addr));
+
+        let actor = format!("http://public.example:{port}/secret");
+        assert_eq!(client.get(&actor, &[], 1024).await.unwrap().0, 200);
+        let hop = format!("http://public.example:{port}/hop?to=http://127.0.0.1:{port}/secret");
+        assert!(client.get(&hop, &[], 1024).await.is_err());
+        assert!(client.post(&hop, &[], b"{}").await.is_err());
+        let inbox = "http://169.254.169.254/inbox";
+        assert!(client.post(inbox, &[], b"{}").await.is_err());
+    }
+}
@@ -12,14 +12,19 @@
 //! - **Generic namespace API**: Namespace-scoped object store, audiences, and sessions
 
 pub mod adapters;
+pub mod admin;
 pub mod auth;
 pub mod blob_store;
 pub mod config;
 pub mod db;
 pub mod email;
 pub mod handlers;
+pub mod jobs;
+pub mod maintenance;
+pub mod postgres;
 pub mod proxy_adapters;
 pub mod rate_limit;
+pub mod render_plugins;
 pub mod testing;
 
 pub use config::Config;
@@ -6,22 +6,42 @@ use axum::{
 };
 use diaryx_selfhosted::{
     adapters::{
-        NativeArkIndexStore, NativeAuthSessionStore, NativeAuthStore, NativeDomainMappingCache,
-        NativeNamespaceStore, NativeObjectMetaStore, NativeSessionStore, NativeUserStore,
+        NativeAccessTokenStore, NativeActivityPubStore, NativeArkIndexStore, NativeAuditLogStore,
+        NativeAuthSessionStore, NativeAuthStore, NativeCommentStore, NativeDomainMappingCache,
+        NativeNamespaceMemberStore, NativeNamespaceStore, NativeObjectMetaStore,
+        NativePasskeyStore, NativeSessionStore, NativeUserStore, NativeWebhookStore,
+        NativeWebmentionStore,
     },
+    admin::{ADMIN_COMMAND, ADMIN_USAGE, AdminArgs, default_admin_url, run_admin_command},
     auth::{AuthExtractor, MagicLinkService, PasskeyService},
     blob_store::{BlobStore, build_blob_store},
-    config::Config,
+    config::{BlobStoreBackend, Config},
     db::NamespaceRepo,
-    db::{AuthRepo, init_database},
+    db::{ActivityPubRepo, AuditRepo, AuthRepo, CommentRepo, WebmentionRepo, init_database},
     email::EmailService,
     handlers::{
-        AudienceState, DomainState, NamespaceState, NsSessionState, ObjectState, ProxyState,
-        ai_routes, ark_routes, audience_routes, auth_routes, domain_auth_route, domain_routes,
-        namespace_routes, ns_session_routes, object_routes, proxy_routes, public_object_routes,
-        site_routes, usage_routes,
+        AccountState, ActivityPubState, AdminState, ArchiveState, AudienceState, AuditState,
+        CommentState, DomainState, MemberState, NamespaceState, NsSessionState, ObjectState,
+        ProxyState, WebhookState, WebmentionState, account_audit_routes, account_routes,
+        activitypub_routes, admin_routes, ai_routes, archive_routes, ark_routes, audience_routes,
+        auth_routes, comment_routes, domain_auth_route, domain_routes, member_routes,
+        membership_routes, namespace_audit_routes, namespace_routes, ns_session_routes,
+        object_routes, proxy_routes, public_comment_routes, public_object_routes, site_routes,
+        usage_routes, webfinger_routes, webhook_routes, webmention_routes,
+    },
+    jobs::{
+        MAX_OUTBOUND_REDIRECTS, OutboundClient, ReqwestActivityPubClient, ReqwestWebhookTransport,
+        ReqwestWebmentionFetcher, TokioJobSink, spawn_webhook_retries,
+    },
+    maintenance::{
+        RECONCILE_STORAGE_COMMAND, ReconcileStorageArgs, reconcile_storage, spawn_audit_prune,
+        spawn_storage_reconcile,
     },
     proxy_adapters::{NativeProxySecretResolver, NativeProxyUsageStore, StaticProxyConfigStore},
+    render_plugins::load_render_hooks,
+};
+use diaryx_server::ports::{
+    ActivityPubClient, ActivityPubStore, AuditLogStore, JobSink, WebhookStore, WebmentionStore,
 };
 use rusqlite::Connection;
 use std::sync::Arc;
@@ -45,6 +65,48 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // The admin client only talks HTTP to a running server, so it needs
+    // neither the configuration nor the database.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some(ADMIN_COMMAND) {
+        let parsed = match AdminArgs::parse(&args[1..]) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, ADMIN_USAGE);
+                std::process::exit(2);
+            }
+        };
+        let Some(admin_secret) = std::env::var("ADMIN_SECRET")
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+        else {
+            eprintln!("ADMIN_SECRET must be set to use the admin commands");
+            std::process::exit(2);
+        };
+        let url = parsed.url.unwrap_or_else(default_admin_url);
+        match run_admin_command(
+            &reqwest::Client::new(),
+            &url,
+            &admin_secret,
+            &parsed.command,
+        )
+        .await
+        {
+            Ok(body) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&body).unwrap_or_default()
+                );
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Admin request failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Load configuration
     let config = match Config::from_env() {
         Ok(c) => Arc::new(c),
@@ -56,6 +118,9 @@ async fn main() {
 
     info!("Starting Diaryx Sync Server v{}", env!("CARGO_PKG_VERSION"));
     info!("Database path: {:?}", config.database_path);
+    if std::env::var_os("DATABASE_URL").is_some() {
+        warn!("DATABASE_URL is set but the server only runs on SQLite; using DATABASE_PATH");
+    }
     info!("CORS origins: {:?}", config.cors_origins);
 
     // Initialize database
@@ -74,7 +139,12 @@ async fn main() {
 
     // Create shared state
     let repo = Arc::new(AuthRepo::new(conn));
-    let magic_link_service = Arc::new(MagicLinkService::new(repo.clone(), config.clone()));
+    let audit_store: Arc<dyn AuditLogStore> = Arc::new(NativeAuditLogStore::new(Arc::new(
+        AuditRepo::new(repo.connection()),
+    )));
+    let magic_link_service = Arc::new(
+        MagicLinkService::new(repo.clone(), config.clone()).with_audit(audit_store.clone()),
+    );
     let email_service = Arc::new(EmailService::new(config.clone()));
     let passkey_service = Arc::new(PasskeyService::new(
         repo.clone(),
@@ -85,15 +155,18 @@ async fn main() {
     let user_store = Arc::new(NativeUserStore::new(repo.clone()));
     let blob_store: Arc<dyn BlobStore> = match build_blob_store(config.as_ref()).await {
         Ok(store) => {
-            if config.is_r2_configured() {
-                info!("Blob store: R2 ({})", config.r2.bucket);
-            } else if config.blob_store_in_memory {
-                info!("Blob store: in-memory (volatile)");
-            } else {
-                info!(
+            match config.blob_store_backend() {
+                BlobStoreBackend::R2 => info!("Blob store: R2 ({})", config.r2.bucket),
+                BlobStoreBackend::S3 => {
+                    if let Some(s3) = &config.s3 {
+                        info!("Blob store: S3 ({}/{})", s3.endpoint, s3.bucket);
+                    }
+                }
+                BlobStoreBackend::InMemory => info!("Blob store: in-memory (volatile)"),
+                BlobStoreBackend::LocalFs => info!(
                     "Blob store: local filesystem ({:?})",
                     config.blob_store_path
-                );
+                ),
             }
             store
         }
@@ -117,7 +190,9 @@ async fn main() {
     // Create shared rate limiter
     let rate_limiter = diaryx_selfhosted::rate_limit::RateLimiter::new();
 
-    let auth_extractor = AuthExtractor::new(auth_store.clone(), auth_session_store.clone());
+    let access_token_store = Arc::new(NativeAccessTokenStore::new(repo.clone()));
+    let auth_extractor = AuthExtractor::new(auth_store.clone(), auth_session_store.clone())
+        .with_access_tokens(access_token_store.clone());
 
     // Create handler states
     let auth_state = diaryx_selfhosted::handlers::auth::AuthState {
@@ -127,7 +202,9 @@ async fn main() {
         namespace_store: namespace_store.clone(),
         session_store: auth_session_store,
         user_store: user_store.clone(),
+        access_token_store,
         passkey_service,
+        audit_store: audit_store.clone(),
         session_expiry_days: config.session_expiry_days,
         secure_cookies: config.secure_cookies,
     };
@@ -168,6 +245,125 @@ async fn main() {
 
     let session_store = Arc::new(NativeSessionStore::new(ns_repo.clone()));
     let object_meta_store = Arc::new(NativeObjectMetaStore::new(ns_repo.clone()));
+    let member_store = Arc::new(NativeNamespaceMemberStore::new(ns_repo.clone()));
+
+    // One-shot maintenance subcommands run against the configured stores and exit.
+    if args.first().map(String::as_str) == Some(RECONCILE_STORAGE_COMMAND) {
+        let parsed =
+            match ReconcileStorageArgs::parse(&args[1..], config.storage_reconcile_grace_hours) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    error!("{}", e);
+                    eprintln!(
+                        "usage: diaryx_selfhosted {} [--dry-run] [--grace-hours N]",
+                        RECONCILE_STORAGE_COMMAND
+                    );
+                    std::process::exit(2);
+                }
+            };
+        match reconcile_storage(
+            namespace_store.as_ref(),
+            object_meta_store.as_ref(),
+            blob_store.as_ref(),
+            parsed.dry_run,
+            parsed.grace_hours,
+        )
+        .await
+        {
+            Ok(report) => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).unwrap_or_default()
+                );
+                std::process::exit(0);
+            }
+            Err(e) => {
+                error!("Storage reconcile failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(interval_hours) = config.storage_reconcile_interval_hours {
+        info!(
+            "Storage reconcile: every {}h (grace {}h)",
+            interval_hours, config.storage_reconcile_grace_hours
+        );
+        spawn_storage_reconcile(
+            namespace_store.clone(),
+            object_meta_store.clone(),
+            blob_store.clone(),
+            interval_hours,
+            config.storage_reconcile_grace_hours,
+        );
+    }
+
+    // Outbound webhooks, Webmention verification and ActivityPub deliveries
+    // run on spawned tasks, with a periodic sweep for webhook retries and
+    // anything a restart dropped.
+    let api_base_url = format!("{}/api", config.site_base_url.trim_end_matches('/'));
+    let webhook_store: Arc<dyn WebhookStore> = Arc::new(NativeWebhookStore::new(ns_repo.clone()));
+    let webmention_store: Arc<dyn WebmentionStore> = Arc::new(NativeWebmentionStore::new(
+        Arc::new(WebmentionRepo::new(repo.connection())),
+    ));
+    let activitypub_store: Arc<dyn ActivityPubStore> = Arc::new(NativeActivityPubStore::new(
+        Arc::new(ActivityPubRepo::new(repo.connection())),
+    ));
+    let activitypub_client: Arc<dyn ActivityPubClient> = Arc::new(ReqwestActivityPubClient::new(
+        OutboundClient::new(config.outbound_allow_private, MAX_OUTBOUND_REDIRECTS),
+    ));
+    let job_sink: Arc<dyn JobSink> = Arc::new(
+        TokioJobSink::new(
+            webhook_store.clone(),
+            Arc::new(ReqwestWebhookTransport::new(OutboundClient::new(
+                config.outbound_allow_private,
+                0,
+            ))),
+        )
+        .with_webmentions(
+            webmention_store.clone(),
+            Arc::new(ReqwestWebmentionFetcher::new(OutboundClient::new(
+                config.outbound_allow_private,
+                MAX_OUTBOUND_REDIRECTS,
+            ))),
+        )
+        .with_activitypub(
+            activitypub_store.clone(),
+            activitypub_client.clone(),
+            api_base_url.clone(),
+        ),
+    );
+    spawn_webhook_retries(webhook_store.clone(), job_sink.clone());
+    let webhook_state = WebhookState {
+        namespace_store: namespace_store.clone(),
+        webhook_store,
+        job_sink: job_sink.clone(),
+        allow_private_targets: config.outbound_allow_private,
+    };
+    let ark_index_store = Arc::new(NativeArkIndexStore::new(ns_repo.clone()));
+    let webmention_state = WebmentionState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        webmention_store,
+        job_sink: job_sink.clone(),
+        api_base_url: api_base_url.clone(),
+    };
+    let comment_state = CommentState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        comment_store: Arc::new(NativeCommentStore::new(Arc::new(CommentRepo::new(
+            repo.connection(),
+        )))),
+        auth_store: auth_state.auth_store.clone(),
+        email_service: email_service.clone(),
+        token_signing_key: config.token_signing_key.clone(),
+    };
+    let activitypub_state = ActivityPubState {
+        namespace_store: namespace_store.clone(),
+        activitypub_store,
+        client: activitypub_client,
+        job_sink,
+        api_base_url,
+    };
 
     // Namespace / object / audience states
     let namespace_state = NamespaceState {
@@ -178,19 +374,87 @@ async fn main() {
         namespace_store: namespace_store.clone(),
         object_meta_store,
         blob_store: blob_store.clone(),
-        ark_index_store: Arc::new(NativeArkIndexStore::new(ns_repo.clone())),
+        ark_index_store,
+        token_signing_key: config.token_signing_key.clone(),
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        webmentions: webmention_state.clone(),
+        activitypub: activitypub_state.clone(),
+        render_hooks: load_render_hooks(&config),
+    };
+    let archive_state = ArchiveState {
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_state.object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: object_state.ark_index_store.clone(),
+        domain_mapping_cache: Some(domain_mapping_cache.clone()),
+    };
+    let account_state = AccountState {
+        auth_store: auth_state.auth_store.clone(),
+        user_store: user_store.clone(),
+        passkey_store: Arc::new(NativePasskeyStore::new(repo.clone())),
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_state.object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: object_state.ark_index_store.clone(),
+        domain_mapping_cache: Some(domain_mapping_cache.clone()),
+        email_service: email_service.clone(),
+        app_base_url: config.app_base_url.clone(),
         token_signing_key: config.token_signing_key.clone(),
     };
+    // Operator admin API (only if ADMIN_SECRET is set)
+    let admin_router = if let Some(admin_secret) = config.admin_secret.clone() {
+        info!("Admin API: enabled");
+        Some(admin_routes(AdminState {
+            admin_secret,
+            auth_repo: repo.clone(),
+            ns_repo: ns_repo.clone(),
+            namespace_store: namespace_store.clone(),
+            object_meta_store: object_state.object_meta_store.clone(),
+            blob_store: blob_store.clone(),
+            domain_mapping_cache: Some(domain_mapping_cache.clone()),
+            config: config.clone(),
+            started_at: chrono::Utc::now().timestamp(),
+        }))
+    } else {
+        info!("Admin API: disabled (ADMIN_SECRET not set)");
+        None
+    };
     let audience_state = AudienceState {
         namespace_store: namespace_store.clone(),
         token_signing_key: config.token_signing_key.clone(),
         blob_store: blob_store.clone(),
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        audit_store: audit_store.clone(),
     };
+    let member_state = MemberState {
+        namespace_store: namespace_store.clone(),
+        member_store: member_store.clone(),
+        email_service: email_service.clone(),
+        app_base_url: config.app_base_url.clone(),
+    };
+    let audit_state = AuditState {
+        namespace_store: namespace_store.clone(),
+        audit_store: audit_store.clone(),
+    };
+    // Audit log retention (AUDIT_LOG_RETENTION_DAYS=0 keeps everything)
+    info!(
+        "Audit log retention: {} days",
+        config.audit_log_retention_days
+    );
+    spawn_audit_prune(
+        namespace_store.clone(),
+        audit_store.clone(),
+        config.audit_log_retention_days,
+    );
     let domain_state = DomainState {
         ns_repo: ns_repo.clone(),
         namespace_store,
         domain_mapping_cache,
+        member_store,
         blob_store: blob_store.clone(),
+        audit_store,
         token_signing_key: config.token_signing_key.clone(),
         subdomains_available: config.subdomains_available(),
     };
@@ -269,20 +533,45 @@ async fn main() {
     let mut api = Router::new()
         // Auth routes
         .nest("/auth", auth_routes(auth_state))
+        // Account export and deletion (mounted under /auth)
+        .nest("/auth", account_routes(account_state))
+        // The caller's own audit log (mounted under /auth)
+        .nest("/auth", account_audit_routes(audit_state.clone()))
         // AI routes
         .merge(ai_routes(ai_state))
         // Generic proxy routes
         .merge(proxy_routes(proxy_state))
         // Generic namespace routes
         .nest("/namespaces", namespace_routes(namespace_state))
+        // Namespace export/import (mounted under /namespaces)
+        .nest("/namespaces", archive_routes(archive_state))
         // Object store routes (mounted under /namespaces/{ns_id})
         .nest("/namespaces/{ns_id}", object_routes(object_state.clone()))
         // Audience routes (mounted under /namespaces/{ns_id})
         .nest("/namespaces/{ns_id}", audience_routes(audience_state))
+        // Collaborator routes (mounted under /namespaces/{ns_id})
+        .nest("/namespaces/{ns_id}", member_routes(member_state.clone()))
+        // Outbound webhooks and their delivery log (mounted under /namespaces/{ns_id})
+        .nest("/namespaces/{ns_id}", webhook_routes(webhook_state))
+        // Webmentions: public receiving endpoint + owner moderation
+        .nest("/namespaces/{ns_id}", webmention_routes(webmention_state))
+        // Reader comments: owner moderation (mounted under /namespaces/{ns_id})
+        .nest("/namespaces/{ns_id}", comment_routes(comment_state.clone()))
+        // ActivityPub actor, outbox, followers and inbox (public)
+        .nest(
+            "/namespaces/{ns_id}",
+            activitypub_routes(activitypub_state.clone()),
+        )
+        // Namespace audit log, owner only (mounted under /namespaces/{ns_id})
+        .nest("/namespaces/{ns_id}", namespace_audit_routes(audit_state))
+        // Invite acceptance and the caller's memberships
+        .merge(membership_routes(member_state))
         // Domain management routes (mounted under /namespaces/{ns_id})
         .nest("/namespaces/{ns_id}", domain_routes(domain_state.clone()))
         // Public (unauthenticated) object access
         .merge(public_object_routes(object_state.clone()))
+        // Reader comments, authenticated by audience token
+        .merge(public_comment_routes(comment_state))
         // Caddy forward_auth endpoint
         .merge(domain_auth_route(domain_state))
         // Usage metering route (user-level, not namespace-scoped)
@@ -300,6 +589,11 @@ async fn main() {
         api = api.merge(apple);
     }
 
+    // Admin routes (only if configured)
+    if let Some(admin) = admin_router {
+        api = api.nest("/admin", admin);
+    }
+
     // Capabilities endpoint (returns server configuration for UI)
     let capabilities = {
         let site_base_url = config.site_base_url.clone();
@@ -328,6 +622,8 @@ async fn main() {
         .merge(site_routes(object_state.clone()))
         // ARK resolution (outside /api, no auth)
         .merge(ark_routes(object_state))
+        // WebFinger for ActivityPub actors (outside /api, no auth)
+        .merge(webfinger_routes(activitypub_state))
         // All API routes under /api
         .nest("/api", api);
 
@@ -0,0 +1,164 @@
+//! Offline maintenance commands and their scheduled counterparts.
+//!
+//! `diaryx_selfhosted reconcile-storage [--dry-run] [--grace-hours N]` runs
+//! one [`StorageReconcileService`] pass and prints the report as JSON. Setting
+//! `STORAGE_RECONCILE_INTERVAL_HOURS` runs the same pass in the background of
+//! a serving instance.
+//!
+//! [`spawn_audit_prune`] applies `AUDIT_LOG_RETENTION_DAYS` to the audit log
+//! once a day.
+
+use diaryx_server::ports::{
+    AuditLogStore, BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError,
+};
+use diaryx_server::use_cases::audit::AuditLogService;
+use diaryx_server::use_cases::storage::{
+    ReconcileOptions, ReconcileReport, StorageReconcileService,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// Name of the storage reconciliation subcommand.
+pub const RECONCILE_STORAGE_COMMAND: &str = "reconcile-storage";
+
+/// Arguments accepted by `reconcile-storage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconcileStorageArgs {
+    pub dry_run: bool,
+    pub grace_hours: u64,
+}
+
+impl ReconcileStorageArgs {
+    /// Parse the arguments following the subcommand name. `default_grace_hours`
+    /// applies when `--grace-hours` is not given.
+    pub fn parse<I, S>(args: I, default_grace_hours: u64) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parsed = Self {
+            dry_run: false,
+            grace_hours: default_grace_hours,
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--dry-run" => parsed.dry_run = true,
+                "--grace-hours" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| "--grace-hours needs a value".to_string())?;
+                    parsed.grace_hours = value
+                        .as_ref()
+                        .parse()
+                        .map_err(|_| format!("invalid --grace-hours: {}", value.as_ref()))?;
+                }
+                other => return Err(format!("unknown argument: {other}")),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Run one reconciliation pass against the current time.
+pub async fn reconcile_storage(
+    namespace_store: &dyn NamespaceStore,
+    object_meta_store: &dyn ObjectMetaStore,
+    blob_store: &dyn BlobStore,
+    dry_run: bool,
+    grace_hours: u64,
+) -> Result<ReconcileReport, ServerCoreError> {
+    let options = ReconcileOptions {
+        dry_run,
+        grace_secs: (grace_hours as i64).saturating_mul(3600),
+        now: chrono::Utc::now().timestamp(),
+    };
+    StorageReconcileService::new(namespace_store, object_meta_store, blob_store)
+        .reconcile(options)
+        .await
+}
+
+/// Run a reconciliation pass every `interval_hours` for the life of the
+/// process. The first pass happens one interval after startup.
+pub fn spawn_storage_reconcile(
+    namespace_store: Arc<dyn NamespaceStore>,
+    object_meta_store: Arc<dyn ObjectMetaStore>,
+    blob_store: Arc<dyn BlobStore>,
+    interval_hours: u64,
+    grace_hours: u64,
+) {
+    tokio::spawn(async move {
+        let period = tokio::time::Duration::from_secs(interval_hours.saturating_mul(3600));
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            match reconcile_storage(
+                namespace_store.as_ref(),
+                object_meta_store.as_ref(),
+                blob_store.as_ref(),
+                false,
+                grace_hours,
+            )
+            .await
+            {
+                Ok(report) => info!(
+                    "Storage reconcile: scanned {} blobs, removed {} ({} bytes), aborted {} uploads",
+                    report.blobs_scanned,
+                    report.deleted,
+                    report.orphaned_bytes,
+                    report.aborted_uploads.len()
+                ),
+                Err(e) => error!("Storage reconcile failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Drop audit events older than `retention_days`, at startup and then once a
+/// day. A retention of zero keeps everything, so nothing is spawned.
+pub fn spawn_audit_prune(
+    namespace_store: Arc<dyn NamespaceStore>,
+    audit_store: Arc<dyn AuditLogStore>,
+    retention_days: u32,
+) {
+    if retention_days == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(24 * 3600));
+        loop {
+            interval.tick().await;
+            let service = AuditLogService::new(namespace_store.as_ref(), audit_store.as_ref());
+            match service
+                .prune(retention_days, chrono::Utc::now().timestamp())
+                .await
+            {
+                Ok(0) => {}
+                Ok(pruned) => info!("Audit log: pruned {} expired events", pruned),
+                Err(e) => error!("Audit log prune failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReconcileStorageArgs;
+
+    #[test]
+    fn parses_reconcile_flags() {
+        let args = ReconcileStorageArgs::parse(["--dry-run", "--grace-hours", "6"], 24).unwrap();
+        assert_eq!(
+            args,
+            ReconcileStorageArgs {
+                dry_run: true,
+                grace_hours: 6
+            }
+        );
+        let args = ReconcileStorageArgs::parse(Vec::<String>::new(), 24).unwrap();
+        assert!(!args.dry_run);
+        assert_eq!(args.grace_hours, 24);
+        assert!(ReconcileStorageArgs::parse(["--grace-hours"], 24).is_err());
+        assert!(ReconcileStorageArgs::parse(["--force"], 24).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Postgres implementation of the ActivityPub port.
+
+use super::{db_error, pool_error};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{ActorKeyPair, FollowerInfo, OutboxItem};
+use diaryx_server::ports::{ActivityPubStore, ServerCoreError};
+use tokio_postgres::Row;
+
+const FOLLOWER_COLUMNS: &str = "namespace_id, actor, inbox, shared_inbox, created_at";
+const OUTBOX_COLUMNS: &str =
+    "id, namespace_id, audience, object_key, url, title, summary, published_at, updated_at";
+
+fn follower_from_row(row: &Row) -> FollowerInfo {
+    FollowerInfo {
+        namespace_id: row.get(0),
+        actor: row.get(1),
+        inbox: row.get(2),
+        shared_inbox: row.get(3),
+        created_at: row.get(4),
+    }
+}
+
+fn outbox_item_from_row(row: &Row) -> OutboxItem {
+    OutboxItem {
+        id: row.get(0),
+        namespace_id: row.get(1),
+        audience: row.get(2),
+        object_key: row.get(3),
+        url: row.get(4),
+        title: row.get(5),
+        summary: row.get(6),
+        published_at: row.get(7),
+        updated_at: row.get(8),
+    }
+}
+
+pub struct PgActivityPubStore {
+    pool: Pool,
+}
+
+impl PgActivityPubStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ActivityPubStore for PgActivityPubStore {
+    async fn get_actor_key(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<ActorKeyPair>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT namespace_id, public_key_pem, private_key_pem, created_at
+                 FROM activitypub_actor_keys WHERE namespace_id = $1",
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|row| ActorKeyPair {
+            namespace_id: row.get(0),
+            public_key_pem: row.get(1),
+            private_key_pem: row.get(2),
+            created_at: row.get(3),
+        }))
+    }
+
+    async fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO activitypub_actor_keys
+                    (namespace_id, public_key_pem, private_key_pem, created_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (namespace_id) DO NOTHING",
+                &[
+                    &key.namespace_id,
+                    &key.public_key_pem,
+                    &key.private_key_pem,
+                    &key.created_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO activitypub_followers ({FOLLOWER_COLUMNS})
+                     VALUES ($1, $2, $3, $4, $5)
+                     ON CONFLICT (namespace_id, actor) DO UPDATE SET
+                        inbox = EXCLUDED.inbox,
+                        shared_inbox = EXCLUDED.shared_inbox"
+                ),
+                &[
+                    &follower.namespace_id,
+                    &follower.actor,
+                    &follower.inbox,
+                    &follower.shared_inbox,
+                    &follower.created_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn delete_follower(
+        &self,
+        namespace_id: &str,
+        actor: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM activitypub_followers WHERE namespace_id = $1 AND actor = $2",
+                &[&namespace_id, &actor],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    async fn list_followers(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<FollowerInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {FOLLOWER_COLUMNS} FROM activitypub_followers
+                     WHERE namespace_id = $1
+                     ORDER BY created_at, actor"
+                ),
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(follower_from_row).collect())
+    }
+
+    async fn count_followers(&self, namespace_id: &str) -> Result<u64, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_one(
+                "SELECT COUNT(*) FROM activitypub_followers WHERE namespace_id = $1",
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.get::<_, i64>(0) as u64)
+    }
+
+    async fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO activitypub_outbox ({OUTBOX_COLUMNS})
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     ON CONFLICT (namespace_id, object_key) DO UPDATE SET
+                        audience = EXCLUDED.audience,
+                        url = EXCLUDED.url,
+                        title = EXCLUDED.title,
+                        summary = EXCLUDED.summary,
+                        updated_at = EXCLUDED.updated_at"
+                ),
+                &[
+                    &item.id,
+                    &item.namespace_id,
+                    &item.audience,
+                    &item.object_key,
+                    &item.url,
+                    &item.title,
+                    &item.summary,
+                    &item.published_at,
+                    &item.updated_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_outbox_items(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<OutboxItem>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {OUTBOX_COLUMNS} FROM activitypub_outbox
+                     WHERE namespace_id = $1
+                     ORDER BY published_at DESC, object_key"
+                ),
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(outbox_item_from_row).collect())
+    }
+
+    async fn delete_outbox_item(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM activitypub_outbox WHERE namespace_id = $1 AND object_key = $2",
+                &[&namespace_id, &object_key],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+}
@@ -0,0 +1,100 @@
+//! Postgres implementation of the audit log port.
+
+use super::{db_error, pool_error};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{AuditAction, AuditEvent, AuditScope};
+use diaryx_server::ports::{AuditLogStore, ServerCoreError};
+use tokio_postgres::Row;
+
+const AUDIT_COLUMNS: &str =
+    "id, action, actor_user_id, user_id, namespace_id, target, details, created_at";
+
+/// `None` for rows with an unknown action.
+fn audit_event_from_row(row: &Row) -> Option<AuditEvent> {
+    let details: Option<&str> = row.get(6);
+    Some(AuditEvent {
+        id: row.get(0),
+        action: AuditAction::parse(row.get(1))?,
+        actor_user_id: row.get(2),
+        user_id: row.get(3),
+        namespace_id: row.get(4),
+        target: row.get(5),
+        details: details
+            .and_then(|d| serde_json::from_str(d).ok())
+            .unwrap_or_default(),
+        created_at: row.get(7),
+    })
+}
+
+pub struct PgAuditLogStore {
+    pool: Pool,
+}
+
+impl PgAuditLogStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuditLogStore for PgAuditLogStore {
+    async fn append_audit_event(&self, event: &AuditEvent) -> Result<(), ServerCoreError> {
+        let details = (!event.details.is_null()).then(|| event.details.to_string());
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO audit_events ({AUDIT_COLUMNS})
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+                ),
+                &[
+                    &event.id,
+                    &event.action.as_str(),
+                    &event.actor_user_id,
+                    &event.user_id,
+                    &event.namespace_id,
+                    &event.target,
+                    &details,
+                    &event.created_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_audit_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        let (column, id) = match scope {
+            AuditScope::User(id) => ("user_id", id),
+            AuditScope::Namespace(id) => ("namespace_id", id),
+        };
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {AUDIT_COLUMNS} FROM audit_events
+                     WHERE {column} = $1 AND ($2::BIGINT IS NULL OR created_at < $2)
+                     ORDER BY created_at DESC, seq DESC
+                     LIMIT $3"
+                ),
+                &[id, &before, &i64::from(limit)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().filter_map(audit_event_from_row).collect())
+    }
+
+    async fn prune_audit_events(&self, cutoff: i64) -> Result<u64, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute("DELETE FROM audit_events WHERE created_at < $1", &[&cutoff])
+            .await
+            .map_err(db_error)
+    }
+}
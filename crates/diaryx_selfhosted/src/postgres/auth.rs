@@ -0,0 +1,601 @@
+//! Postgres implementations of the auth-side port traits.
+
+use super::{db_error, pool_error};
+use crate::db::{generate_secure_token, generate_verification_code};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{AccessTokenInfo, AuthSessionInfo, DeviceInfo, UserInfo, UserTier};
+use diaryx_server::ports::{
+    AccessTokenStore, AuthSessionStore, AuthStore, DeviceStore, MagicLinkStore, ServerCoreError,
+    UserStore,
+};
+use tokio_postgres::Row;
+
+fn timestamp_to_datetime(timestamp: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now)
+}
+
+fn user_from_row(row: &Row) -> UserInfo {
+    UserInfo {
+        id: row.get(0),
+        email: row.get(1),
+        created_at: timestamp_to_datetime(row.get(2)),
+        last_login_at: row.get::<_, Option<i64>>(3).map(timestamp_to_datetime),
+        attachment_limit_bytes: row.get::<_, Option<i64>>(4).map(|v| v as u64),
+        workspace_limit: row.get::<_, Option<i32>>(5).map(|v| v as u32),
+        tier: UserTier::from_str_lossy(row.get(6)),
+        published_site_limit: row.get::<_, Option<i32>>(7).map(|v| v as u32),
+    }
+}
+
+fn device_from_row(row: &Row) -> DeviceInfo {
+    DeviceInfo {
+        id: row.get(0),
+        user_id: row.get(1),
+        name: row.get(2),
+        user_agent: row.get(3),
+        created_at: timestamp_to_datetime(row.get(4)),
+        last_seen_at: timestamp_to_datetime(row.get(5)),
+    }
+}
+
+async fn list_user_devices(pool: &Pool, user_id: &str) -> Result<Vec<DeviceInfo>, ServerCoreError> {
+    let client = pool.get().await.map_err(pool_error)?;
+    let rows = client
+        .query(
+            "SELECT id, user_id, name, user_agent, created_at, last_seen_at
+             FROM devices WHERE user_id = $1 ORDER BY last_seen_at DESC",
+            &[&user_id],
+        )
+        .await
+        .map_err(db_error)?;
+    Ok(rows.iter().map(device_from_row).collect())
+}
+
+async fn delete_device(pool: &Pool, device_id: &str) -> Result<(), ServerCoreError> {
+    let mut client = pool.get().await.map_err(pool_error)?;
+    let tx = client.transaction().await.map_err(db_error)?;
+    tx.execute(
+        "DELETE FROM auth_sessions WHERE device_id = $1",
+        &[&device_id],
+    )
+    .await
+    .map_err(db_error)?;
+    tx.execute("DELETE FROM devices WHERE id = $1", &[&device_id])
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)
+}
+
+#[derive(Clone)]
+pub struct PgAuthStore {
+    pool: Pool,
+}
+
+impl PgAuthStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuthStore for PgAuthStore {
+    async fn get_user(&self, user_id: &str) -> Result<Option<UserInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT id, email, created_at, last_login_at, attachment_limit_bytes,
+                        workspace_limit, tier, published_site_limit
+                 FROM users WHERE id = $1",
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    async fn list_user_devices(&self, user_id: &str) -> Result<Vec<DeviceInfo>, ServerCoreError> {
+        list_user_devices(&self.pool, user_id).await
+    }
+
+    async fn rename_device(
+        &self,
+        device_id: &str,
+        new_name: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let updated = client
+            .execute(
+                "UPDATE devices SET name = $1 WHERE id = $2",
+                &[&new_name, &device_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(updated > 0)
+    }
+
+    async fn delete_device(&self, device_id: &str) -> Result<(), ServerCoreError> {
+        delete_device(&self.pool, device_id).await
+    }
+
+    async fn get_user_tier(&self, user_id: &str) -> Result<UserTier, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt("SELECT tier FROM users WHERE id = $1", &[&user_id])
+            .await
+            .map_err(db_error)?;
+        Ok(row
+            .map(|row| UserTier::from_str_lossy(row.get(0)))
+            .unwrap_or(UserTier::Free))
+    }
+}
+
+#[derive(Clone)]
+pub struct PgAuthSessionStore {
+    pool: Pool,
+}
+
+impl PgAuthSessionStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AuthSessionStore for PgAuthSessionStore {
+    async fn validate_session(
+        &self,
+        token: &str,
+    ) -> Result<Option<AuthSessionInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT token, user_id, device_id, expires_at, created_at
+                 FROM auth_sessions WHERE token = $1 AND expires_at > $2",
+                &[&token, &Utc::now().timestamp()],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|row| AuthSessionInfo {
+            token: row.get(0),
+            user_id: row.get(1),
+            device_id: row.get(2),
+            expires_at: timestamp_to_datetime(row.get(3)),
+            created_at: timestamp_to_datetime(row.get(4)),
+        }))
+    }
+
+    async fn create_auth_session(
+        &self,
+        user_id: &str,
+        device_id: &str,
+        expires_at_unix: i64,
+    ) -> Result<String, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let token = generate_secure_token();
+        client
+            .execute(
+                "INSERT INTO auth_sessions (token, user_id, device_id, expires_at, created_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &token,
+                    &user_id,
+                    &device_id,
+                    &expires_at_unix,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(token)
+    }
+
+    async fn delete_session(&self, token: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute("DELETE FROM auth_sessions WHERE token = $1", &[&token])
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn update_device_last_seen(&self, device_id: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE devices SET last_seen_at = $1 WHERE id = $2",
+                &[&Utc::now().timestamp(), &device_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct PgMagicLinkStore {
+    pool: Pool,
+}
+
+impl PgMagicLinkStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MagicLinkStore for PgMagicLinkStore {
+    async fn create_magic_token(
+        &self,
+        email: &str,
+        expires_at_unix: i64,
+    ) -> Result<(String, String), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let token = generate_secure_token();
+        let code = generate_verification_code();
+        client
+            .execute(
+                "INSERT INTO magic_tokens (token, email, code, expires_at, created_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &token,
+                    &email,
+                    &code,
+                    &expires_at_unix,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok((token, code))
+    }
+
+    async fn peek_magic_token(&self, token: &str) -> Result<Option<String>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT email FROM magic_tokens
+                 WHERE token = $1 AND NOT used AND expires_at > $2",
+                &[&token, &Utc::now().timestamp()],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn consume_magic_token(&self, token: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE magic_tokens SET used = TRUE WHERE token = $1",
+                &[&token],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn peek_magic_code(
+        &self,
+        code: &str,
+        email: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT token FROM magic_tokens
+                 WHERE code = $1 AND email = $2 AND NOT used AND expires_at > $3",
+                &[&code, &email, &Utc::now().timestamp()],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|_| email.to_string()))
+    }
+
+    async fn consume_magic_code(&self, code: &str, email: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE magic_tokens SET used = TRUE WHERE code = $1 AND email = $2",
+                &[&code, &email],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn count_recent_magic_tokens(
+        &self,
+        email: &str,
+        since_unix: i64,
+    ) -> Result<u64, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let count: i64 = client
+            .query_one(
+                "SELECT COUNT(*) FROM magic_tokens WHERE email = $1 AND created_at > $2",
+                &[&email, &since_unix],
+            )
+            .await
+            .map_err(db_error)?
+            .get(0);
+        Ok(count.max(0) as u64)
+    }
+}
+
+#[derive(Clone)]
+pub struct PgUserStore {
+    pool: Pool,
+}
+
+impl PgUserStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserStore for PgUserStore {
+    async fn get_or_create_user(&self, email: &str) -> Result<String, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        // Insert-or-nothing, then read back: two concurrent sign-ins for the
+        // same email must agree on one user id.
+        client
+            .execute(
+                "INSERT INTO users (id, email, created_at, tier) VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (email) DO NOTHING",
+                &[
+                    &uuid::Uuid::new_v4().to_string(),
+                    &email,
+                    &Utc::now().timestamp(),
+                    &UserTier::Free.as_str(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        let row = client
+            .query_one("SELECT id FROM users WHERE email = $1", &[&email])
+            .await
+            .map_err(db_error)?;
+        Ok(row.get(0))
+    }
+
+    async fn update_last_login(&self, user_id: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE users SET last_login_at = $1 WHERE id = $2",
+                &[&Utc::now().timestamp(), &user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute("DELETE FROM users WHERE id = $1", &[&user_id])
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_effective_device_limit(&self, user_id: &str) -> Result<u32, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT tier, device_limit FROM users WHERE id = $1",
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(match row {
+            Some(row) => match row.get::<_, Option<i32>>(1) {
+                Some(limit) => limit as u32,
+                None => UserTier::from_str_lossy(row.get(0)).defaults().device_limit,
+            },
+            None => UserTier::Free.defaults().device_limit,
+        })
+    }
+
+    async fn set_user_tier(&self, user_id: &str, tier: UserTier) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE users SET tier = $1 WHERE id = $2",
+                &[&tier.as_str(), &user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct PgDeviceStore {
+    pool: Pool,
+}
+
+impl PgDeviceStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DeviceStore for PgDeviceStore {
+    async fn create_device(
+        &self,
+        user_id: &str,
+        name: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> Result<String, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let device_id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().timestamp();
+        client
+            .execute(
+                "INSERT INTO devices (id, user_id, name, user_agent, created_at, last_seen_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[&device_id, &user_id, &name, &user_agent, &now, &now],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(device_id)
+    }
+
+    async fn count_user_devices(&self, user_id: &str) -> Result<u32, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let count: i64 = client
+            .query_one(
+                "SELECT COUNT(*) FROM devices WHERE user_id = $1",
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?
+            .get(0);
+        Ok(count as u32)
+    }
+
+    async fn list_user_devices(&self, user_id: &str) -> Result<Vec<DeviceInfo>, ServerCoreError> {
+        list_user_devices(&self.pool, user_id).await
+    }
+
+    async fn delete_device(&self, device_id: &str) -> Result<(), ServerCoreError> {
+        delete_device(&self.pool, device_id).await
+    }
+}
+
+/// Malformed JSON columns decode as empty lists, so a bad row grants nothing.
+fn access_token_from_row(row: &Row) -> AccessTokenInfo {
+    AccessTokenInfo {
+        id: row.get(0),
+        user_id: row.get(1),
+        name: row.get(2),
+        scopes: serde_json::from_str(row.get(3)).unwrap_or_default(),
+        namespace_ids: row
+            .get::<_, Option<&str>>(4)
+            .map(|ids| serde_json::from_str(ids).unwrap_or_default()),
+        created_at: row.get(5),
+        expires_at: row.get(6),
+        last_used_at: row.get(7),
+    }
+}
+
+#[derive(Clone)]
+pub struct PgAccessTokenStore {
+    pool: Pool,
+}
+
+impl PgAccessTokenStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AccessTokenStore for PgAccessTokenStore {
+    async fn create_access_token(
+        &self,
+        token: &AccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        let scopes = serde_json::to_string(&token.scopes)
+            .map_err(|e| ServerCoreError::internal(e.to_string()))?;
+        let namespace_ids = token
+            .namespace_ids
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| ServerCoreError::internal(e.to_string()))?;
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO access_tokens
+                     (id, user_id, name, token_hash, scopes, namespace_ids,
+                      created_at, expires_at, last_used_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                &[
+                    &token.id,
+                    &token.user_id,
+                    &token.name,
+                    &token_hash,
+                    &scopes,
+                    &namespace_ids,
+                    &token.created_at,
+                    &token.expires_at,
+                    &token.last_used_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AccessTokenInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at,
+                        last_used_at
+                 FROM access_tokens WHERE token_hash = $1",
+                &[&token_hash],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(access_token_from_row))
+    }
+
+    async fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT id, user_id, name, scopes, namespace_ids, created_at, expires_at,
+                        last_used_at
+                 FROM access_tokens WHERE user_id = $1 ORDER BY created_at DESC, id",
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(access_token_from_row).collect())
+    }
+
+    async fn delete_access_token(
+        &self,
+        user_id: &str,
+        token_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM access_tokens WHERE id = $1 AND user_id = $2",
+                &[&token_id, &user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    async fn touch_access_token(
+        &self,
+        token_id: &str,
+        used_at: i64,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE access_tokens SET last_used_at = $1 WHERE id = $2",
+                &[&used_at, &token_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
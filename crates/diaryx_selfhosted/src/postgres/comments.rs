@@ -0,0 +1,121 @@
+//! Postgres implementation of the comment port.
+
+use super::{db_error, pool_error};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{CommentInfo, CommentStatus};
+use diaryx_server::ports::{CommentStore, ServerCoreError};
+use tokio_postgres::Row;
+
+const COMMENT_COLUMNS: &str = "id, namespace_id, audience, object_key, source_key, author_name, \
+     body, status, token_id, created_at, updated_at";
+
+/// `None` for rows with an unknown status.
+fn comment_from_row(row: &Row) -> Option<CommentInfo> {
+    Some(CommentInfo {
+        id: row.get(0),
+        namespace_id: row.get(1),
+        audience: row.get(2),
+        object_key: row.get(3),
+        source_key: row.get(4),
+        author_name: row.get(5),
+        body: row.get(6),
+        status: CommentStatus::parse(row.get(7))?,
+        token_id: row.get(8),
+        created_at: row.get(9),
+        updated_at: row.get(10),
+    })
+}
+
+pub struct PgCommentStore {
+    pool: Pool,
+}
+
+impl PgCommentStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CommentStore for PgCommentStore {
+    async fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO comments ({COMMENT_COLUMNS})
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                     ON CONFLICT (id) DO UPDATE SET
+                        status = EXCLUDED.status,
+                        updated_at = EXCLUDED.updated_at"
+                ),
+                &[
+                    &comment.id,
+                    &comment.namespace_id,
+                    &comment.audience,
+                    &comment.object_key,
+                    &comment.source_key,
+                    &comment.author_name,
+                    &comment.body,
+                    &comment.status.as_str(),
+                    &comment.token_id,
+                    &comment.created_at,
+                    &comment.updated_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_comment(&self, id: &str) -> Result<Option<CommentInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {COMMENT_COLUMNS} FROM comments WHERE id = $1"),
+                &[&id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().and_then(comment_from_row))
+    }
+
+    async fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError> {
+        let status = status.map(|s| s.as_str());
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {COMMENT_COLUMNS} FROM comments
+                     WHERE namespace_id = $1
+                       AND ($2::TEXT IS NULL OR object_key = $2)
+                       AND ($3::TEXT IS NULL OR status = $3)
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $4"
+                ),
+                &[&namespace_id, &object_key, &status, &i64::from(limit)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().filter_map(comment_from_row).collect())
+    }
+
+    async fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM comments WHERE namespace_id = $1 AND id = $2",
+                &[&namespace_id, &id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+}
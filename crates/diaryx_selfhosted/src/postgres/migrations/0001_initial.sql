@@ -0,0 +1,193 @@
+-- Initial schema for the Postgres storage backend.
+--
+-- Mirrors the canonical SQLite schema in `diaryx_server::schema` as of its
+-- migration 0007 (ARK versions), folded into one step. Timestamps stay as
+-- Unix seconds (BIGINT) so rows map onto the same domain types as SQLite;
+-- flags use BOOLEAN. Legacy columns with no live readers are left out:
+-- `namespace_objects.data` (inline objects) and `namespace_audiences.access`
+-- (replaced by `gates`).
+
+-- Users
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY,
+    email TEXT UNIQUE NOT NULL,
+    created_at BIGINT NOT NULL,
+    last_login_at BIGINT,
+    attachment_limit_bytes BIGINT,
+    workspace_limit INTEGER,
+    tier TEXT NOT NULL DEFAULT 'free',
+    device_limit INTEGER,
+    published_site_limit INTEGER,
+    stripe_customer_id TEXT,
+    stripe_subscription_id TEXT,
+    apple_original_transaction_id TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_users_stripe_customer
+    ON users(stripe_customer_id) WHERE stripe_customer_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_users_apple_tx
+    ON users(apple_original_transaction_id) WHERE apple_original_transaction_id IS NOT NULL;
+
+-- Devices
+CREATE TABLE IF NOT EXISTS devices (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT,
+    user_agent TEXT,
+    created_at BIGINT NOT NULL,
+    last_seen_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_devices_user_id ON devices(user_id);
+
+-- Magic link tokens
+CREATE TABLE IF NOT EXISTS magic_tokens (
+    token TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    code TEXT,
+    expires_at BIGINT NOT NULL,
+    used BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_magic_tokens_email ON magic_tokens(email);
+CREATE INDEX IF NOT EXISTS idx_magic_tokens_expires ON magic_tokens(expires_at);
+CREATE INDEX IF NOT EXISTS idx_magic_tokens_code ON magic_tokens(code);
+
+-- Auth sessions
+CREATE TABLE IF NOT EXISTS auth_sessions (
+    token TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    device_id TEXT NOT NULL REFERENCES devices(id) ON DELETE CASCADE,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON auth_sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_expires ON auth_sessions(expires_at);
+
+-- AI usage counters
+CREATE TABLE IF NOT EXISTS user_ai_usage_monthly (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    period_utc TEXT NOT NULL,
+    request_count BIGINT NOT NULL DEFAULT 0,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, period_utc)
+);
+
+-- Namespaces
+CREATE TABLE IF NOT EXISTS namespaces (
+    id TEXT PRIMARY KEY,
+    owner_user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at BIGINT NOT NULL,
+    metadata TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespaces_owner ON namespaces(owner_user_id);
+
+-- Namespace objects
+CREATE TABLE IF NOT EXISTS namespace_objects (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    r2_key TEXT,
+    mime_type TEXT NOT NULL DEFAULT 'application/octet-stream',
+    size_bytes BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    audience TEXT,
+    content_hash TEXT,
+    PRIMARY KEY (namespace_id, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_objects_audience ON namespace_objects(namespace_id, audience);
+CREATE INDEX IF NOT EXISTS idx_namespace_objects_r2_key ON namespace_objects(namespace_id, r2_key);
+
+-- Namespace audiences. `gates` is a JSON array of gate records; `[]` is public.
+CREATE TABLE IF NOT EXISTS namespace_audiences (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience_name TEXT NOT NULL,
+    gates TEXT NOT NULL DEFAULT '[]',
+    PRIMARY KEY (namespace_id, audience_name)
+);
+
+-- Custom domains
+CREATE TABLE IF NOT EXISTS custom_domains (
+    domain TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience_name TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    verified BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS idx_custom_domains_ns ON custom_domains(namespace_id);
+
+-- Usage events
+CREATE TABLE IF NOT EXISTS usage_events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    namespace_id TEXT,
+    recorded_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_usage_events_user ON usage_events(user_id, event_type, recorded_at);
+CREATE INDEX IF NOT EXISTS idx_usage_events_recorded ON usage_events(recorded_at);
+
+-- Namespace sessions
+CREATE TABLE IF NOT EXISTS namespace_sessions (
+    code TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    owner_user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    read_only BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_sessions_owner ON namespace_sessions(owner_user_id);
+CREATE INDEX IF NOT EXISTS idx_namespace_sessions_ns ON namespace_sessions(namespace_id);
+
+-- Passkey credentials
+CREATE TABLE IF NOT EXISTS passkey_credentials (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    credential_json TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    last_used_at BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_passkey_credentials_user ON passkey_credentials(user_id);
+
+-- Passkey challenges (ephemeral)
+CREATE TABLE IF NOT EXISTS passkey_challenges (
+    challenge_id TEXT PRIMARY KEY,
+    user_id TEXT,
+    email TEXT NOT NULL,
+    challenge_type TEXT NOT NULL,
+    state_json TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_passkey_challenges_expires ON passkey_challenges(expires_at);
+
+-- ARK identity index: (workspace ARK, file ARK) -> object key, refreshed at publish.
+CREATE TABLE IF NOT EXISTS ark_index (
+    workspace_ark TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    file_ark TEXT NOT NULL,
+    object_key TEXT NOT NULL,
+    audience TEXT,
+    updated_at BIGINT NOT NULL,
+    source_key TEXT,
+    PRIMARY KEY (workspace_ark, file_ark)
+);
+
+-- Retained `.<FILE>` source versions per file ARK. Insert-only.
+CREATE TABLE IF NOT EXISTS ark_versions (
+    workspace_ark TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    file_ark TEXT NOT NULL,
+    version TEXT NOT NULL,
+    object_key TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (workspace_ark, file_ark, version)
+);
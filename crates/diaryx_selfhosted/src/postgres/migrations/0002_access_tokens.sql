@@ -0,0 +1,18 @@
+-- Personal access tokens. Mirrors the canonical SQLite migration
+-- `0008_access_tokens.sql`: only the SHA-256 hex digest of the secret is
+-- stored, and `scopes` / `namespace_ids` are JSON arrays stored as TEXT
+-- (`namespace_ids` NULL = every namespace the owner has).
+
+CREATE TABLE IF NOT EXISTS access_tokens (
+    id            TEXT PRIMARY KEY,
+    user_id       TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name          TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    scopes        TEXT NOT NULL,
+    namespace_ids TEXT,
+    created_at    BIGINT NOT NULL,
+    expires_at    BIGINT,
+    last_used_at  BIGINT
+);
+
+CREATE INDEX IF NOT EXISTS idx_access_tokens_user ON access_tokens(user_id);
@@ -0,0 +1,26 @@
+-- Namespace collaborators and pending invites. Mirrors the canonical SQLite
+-- migration `0009_namespace_members.sql`: the namespace owner is implicit,
+-- and only the SHA-256 hex digest of an invite secret is stored.
+
+CREATE TABLE IF NOT EXISTS namespace_members (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    user_id      TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role         TEXT NOT NULL,
+    created_at   BIGINT NOT NULL,
+    PRIMARY KEY (namespace_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_members_user ON namespace_members(user_id);
+
+CREATE TABLE IF NOT EXISTS namespace_invites (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    email        TEXT NOT NULL,
+    role         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    invited_by   TEXT NOT NULL,
+    created_at   BIGINT NOT NULL,
+    expires_at   BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_invites_namespace ON namespace_invites(namespace_id);
@@ -0,0 +1,33 @@
+-- Outbound webhooks and their delivery log. Mirrors the canonical SQLite
+-- migration `0010_webhooks.sql`: `events` is a JSON array of event names and
+-- `secret` is the plaintext HMAC signing key.
+
+CREATE TABLE IF NOT EXISTS namespace_webhooks (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    url          TEXT NOT NULL,
+    events       TEXT NOT NULL,
+    secret       TEXT NOT NULL,
+    created_by   TEXT NOT NULL,
+    created_at   BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_webhooks_namespace ON namespace_webhooks(namespace_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id              TEXT PRIMARY KEY,
+    webhook_id      TEXT NOT NULL REFERENCES namespace_webhooks(id) ON DELETE CASCADE,
+    namespace_id    TEXT NOT NULL,
+    event           TEXT NOT NULL,
+    payload         TEXT NOT NULL,
+    status          TEXT NOT NULL,
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at BIGINT,
+    response_status INTEGER,
+    last_error      TEXT,
+    created_at      BIGINT NOT NULL,
+    updated_at      BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_namespace ON webhook_deliveries(namespace_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
@@ -0,0 +1,19 @@
+-- Append-only audit log. Mirrors the canonical SQLite migration
+-- `0011_audit_log.sql`; `seq` stands in for SQLite's rowid so events from the
+-- same second list in the order they were appended.
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id            TEXT PRIMARY KEY,
+    seq           BIGSERIAL NOT NULL,
+    action        TEXT NOT NULL,
+    actor_user_id TEXT,
+    user_id       TEXT,
+    namespace_id  TEXT,
+    target        TEXT,
+    details       TEXT,
+    created_at    BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_user ON audit_events(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_namespace ON audit_events(namespace_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_created ON audit_events(created_at);
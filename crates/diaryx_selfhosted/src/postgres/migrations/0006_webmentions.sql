@@ -0,0 +1,17 @@
+-- Received Webmentions and their moderation state. Mirrors the canonical
+-- SQLite migration `0012_webmentions.sql`.
+
+CREATE TABLE IF NOT EXISTS webmentions (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    source       TEXT NOT NULL,
+    target       TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    status       TEXT NOT NULL,
+    source_title TEXT,
+    created_at   BIGINT NOT NULL,
+    updated_at   BIGINT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webmentions_pair ON webmentions(namespace_id, source, target);
+CREATE INDEX IF NOT EXISTS idx_webmentions_status ON webmentions(namespace_id, status, created_at);
@@ -0,0 +1,33 @@
+-- ActivityPub actor keys, followers and outboxes. Mirrors the canonical
+-- SQLite migration `0013_activitypub.sql`.
+
+CREATE TABLE IF NOT EXISTS activitypub_actor_keys (
+    namespace_id    TEXT PRIMARY KEY REFERENCES namespaces(id) ON DELETE CASCADE,
+    public_key_pem  TEXT NOT NULL,
+    private_key_pem TEXT NOT NULL,
+    created_at      BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS activitypub_followers (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    actor        TEXT NOT NULL,
+    inbox        TEXT NOT NULL,
+    shared_inbox TEXT,
+    created_at   BIGINT NOT NULL,
+    PRIMARY KEY (namespace_id, actor)
+);
+
+CREATE TABLE IF NOT EXISTS activitypub_outbox (
+    id           TEXT NOT NULL,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience     TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    url          TEXT NOT NULL,
+    title        TEXT NOT NULL,
+    summary      TEXT NOT NULL,
+    published_at BIGINT NOT NULL,
+    updated_at   BIGINT NOT NULL,
+    PRIMARY KEY (namespace_id, object_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activitypub_outbox_published ON activitypub_outbox(namespace_id, published_at);
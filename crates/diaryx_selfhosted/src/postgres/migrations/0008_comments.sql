@@ -0,0 +1,19 @@
+-- Reader comments on gated pages and their moderation state. Mirrors the
+-- canonical SQLite migration `0014_comments.sql`.
+
+CREATE TABLE IF NOT EXISTS comments (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience     TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    source_key   TEXT,
+    author_name  TEXT NOT NULL,
+    body         TEXT NOT NULL,
+    status       TEXT NOT NULL,
+    token_id     TEXT NOT NULL,
+    created_at   BIGINT NOT NULL,
+    updated_at   BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_page ON comments(namespace_id, object_key, status, created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_status ON comments(namespace_id, status, created_at);
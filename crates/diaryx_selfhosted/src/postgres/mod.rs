@@ -0,0 +1,95 @@
+//! Postgres implementations of the server port traits.
+//!
+//! Counterparts to the SQLite adapters in [`crate::adapters`], used by
+//! [`TestServer::start_postgres`](crate::testing::TestServer::start_postgres)
+//! and `tests/contract_postgres.rs`. Each store wraps a shared
+//! [`deadpool_postgres::Pool`] and implements one port trait from
+//! [`diaryx_server::ports`]:
+//!
+//! | Store | Trait |
+//! |---|---|
+//! | [`PgAuthStore`] | `AuthStore` |
+//! | [`PgAuthSessionStore`] | `AuthSessionStore` |
+//! | [`PgMagicLinkStore`] | `MagicLinkStore` |
+//! | [`PgUserStore`] | `UserStore` |
+//! | [`PgDeviceStore`] | `DeviceStore` |
+//! | [`PgAccessTokenStore`] | `AccessTokenStore` |
+//! | [`PgNamespaceStore`] | `NamespaceStore` |
+//! | [`PgNamespaceMemberStore`] | `NamespaceMemberStore` |
+//! | [`PgSessionStore`] | `SessionStore` |
+//! | [`PgObjectMetaStore`] | `ObjectMetaStore` |
+//! | [`PgArkIndexStore`] | `ArkIndexStore` |
+//! | [`PgWebhookStore`] | `WebhookStore` |
+//! | [`PgWebmentionStore`] | `WebmentionStore` |
+//! | [`PgCommentStore`] | `CommentStore` |
+//! | [`PgActivityPubStore`] | `ActivityPubStore` |
+//! | [`PgAuditLogStore`] | `AuditLogStore` |
+//!
+//! Passkeys, billing and AI usage counters have tables in the schema but no
+//! Postgres store yet; those features still need the SQLite `AuthRepo`. For
+//! that reason the server binary always runs on SQLite and has no option to
+//! select these stores.
+//!
+//! The schema has its own migration set under `postgres/migrations/` (see
+//! [`schema`]), applied by [`connect`]. It tracks the canonical SQLite
+//! migrations in `diaryx_server::schema` table-for-table, so a new canonical
+//! migration needs a matching Postgres one here.
+
+mod activitypub;
+mod audit;
+mod auth;
+mod comments;
+mod namespaces;
+pub mod schema;
+mod webmentions;
+
+pub use activitypub::PgActivityPubStore;
+pub use audit::PgAuditLogStore;
+pub use auth::{
+    PgAccessTokenStore, PgAuthSessionStore, PgAuthStore, PgDeviceStore, PgMagicLinkStore,
+    PgUserStore,
+};
+pub use comments::PgCommentStore;
+pub use namespaces::{
+    PgArkIndexStore, PgNamespaceMemberStore, PgNamespaceStore, PgObjectMetaStore, PgSessionStore,
+    PgWebhookStore,
+};
+pub use webmentions::PgWebmentionStore;
+
+use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
+use diaryx_server::ports::ServerCoreError;
+use tokio_postgres::NoTls;
+
+/// Default maximum number of pooled connections.
+const DEFAULT_POOL_SIZE: usize = 16;
+
+/// Open a connection pool for `database_url` (a `postgres://` URL or
+/// key-value connection string) and bring the schema up to date.
+pub async fn connect(database_url: &str) -> Result<Pool, ServerCoreError> {
+    let pg_config: tokio_postgres::Config = database_url
+        .parse()
+        .map_err(|e| ServerCoreError::invalid_input(format!("invalid Postgres URL: {e}")))?;
+    let manager = Manager::from_config(
+        pg_config,
+        NoTls,
+        ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        },
+    );
+    let pool = Pool::builder(manager)
+        .max_size(DEFAULT_POOL_SIZE)
+        .build()
+        .map_err(|e| ServerCoreError::internal(format!("Postgres pool: {e}")))?;
+
+    let mut client = pool.get().await.map_err(pool_error)?;
+    schema::migrate(&mut client).await?;
+    Ok(pool)
+}
+
+fn pool_error(e: deadpool_postgres::PoolError) -> ServerCoreError {
+    ServerCoreError::unavailable(format!("Postgres connection unavailable: {e}"))
+}
+
+fn db_error(e: tokio_postgres::Error) -> ServerCoreError {
+    ServerCoreError::internal(format!("Postgres error: {e}"))
+}
@@ -0,0 +1,1227 @@
+//! Postgres implementations of the namespace-side port traits.
+
+use super::{db_error, pool_error};
+use crate::db::generate_session_code;
+use async_trait::async_trait;
+use chrono::Utc;
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{
+    ArkIndexEntry, ArkVersionEntry, AudienceInfo, CustomDomainInfo, GateRecord, NamespaceInfo,
+    NamespaceInviteInfo, NamespaceMemberInfo, NamespaceRole, NamespaceSessionInfo, ObjectMeta,
+    UsageEvent, UsageTotals, WebhookDeliveryInfo, WebhookDeliveryStatus, WebhookEvent, WebhookInfo,
+};
+use diaryx_server::ports::{
+    ArkIndexStore, NamespaceMemberStore, NamespaceStore, ObjectMetaStore, ServerCoreError,
+    SessionStore, WebhookStore,
+};
+use tokio_postgres::Row;
+
+const OBJECT_COLUMNS: &str =
+    "namespace_id, key, r2_key, mime_type, size_bytes, updated_at, audience, content_hash";
+
+fn namespace_from_row(row: &Row) -> NamespaceInfo {
+    NamespaceInfo {
+        id: row.get(0),
+        owner_user_id: row.get(1),
+        created_at: row.get(2),
+        metadata: row.get(3),
+    }
+}
+
+fn audience_from_row(row: &Row) -> AudienceInfo {
+    let gates_json: &str = row.get(2);
+    AudienceInfo {
+        namespace_id: row.get(0),
+        audience_name: row.get(1),
+        gates: serde_json::from_str(gates_json).unwrap_or_default(),
+    }
+}
+
+fn custom_domain_from_row(row: &Row) -> CustomDomainInfo {
+    CustomDomainInfo {
+        domain: row.get(0),
+        namespace_id: row.get(1),
+        audience_name: row.get(2),
+        created_at: row.get(3),
+        verified: row.get(4),
+    }
+}
+
+/// Expects `namespace_id, user_id, email, role, created_at`. Unknown roles
+/// decode as viewer, the least privileged.
+fn member_from_row(row: &Row) -> NamespaceMemberInfo {
+    let role: &str = row.get(3);
+    NamespaceMemberInfo {
+        namespace_id: row.get(0),
+        user_id: row.get(1),
+        email: row.get(2),
+        role: NamespaceRole::parse(role).unwrap_or(NamespaceRole::Viewer),
+        created_at: row.get(4),
+    }
+}
+
+fn invite_from_row(row: &Row) -> NamespaceInviteInfo {
+    let role: &str = row.get(3);
+    NamespaceInviteInfo {
+        id: row.get(0),
+        namespace_id: row.get(1),
+        email: row.get(2),
+        role: NamespaceRole::parse(role).unwrap_or(NamespaceRole::Viewer),
+        invited_by: row.get(4),
+        created_at: row.get(5),
+        expires_at: row.get(6),
+    }
+}
+
+fn webhook_from_row(row: &Row) -> WebhookInfo {
+    let events: Vec<String> = serde_json::from_str(row.get(3)).unwrap_or_default();
+    WebhookInfo {
+        id: row.get(0),
+        namespace_id: row.get(1),
+        url: row.get(2),
+        events: events
+            .iter()
+            .filter_map(|e| WebhookEvent::parse(e))
+            .collect(),
+        created_by: row.get(4),
+        created_at: row.get(5),
+    }
+}
+
+/// `None` for rows with an unknown event or status.
+fn delivery_from_row(row: &Row) -> Option<WebhookDeliveryInfo> {
+    Some(WebhookDeliveryInfo {
+        id: row.get(0),
+        webhook_id: row.get(1),
+        namespace_id: row.get(2),
+        event: WebhookEvent::parse(row.get(3))?,
+        payload: row.get(4),
+        status: WebhookDeliveryStatus::parse(row.get(5))?,
+        attempts: row.get::<_, i32>(6).max(0) as u32,
+        next_attempt_at: row.get(7),
+        response_status: row.get::<_, Option<i32>>(8).map(|s| s as u16),
+        last_error: row.get(9),
+        created_at: row.get(10),
+        updated_at: row.get(11),
+    })
+}
+
+fn object_from_row(row: &Row) -> ObjectMeta {
+    ObjectMeta {
+        namespace_id: row.get(0),
+        key: row.get(1),
+        blob_key: row.get(2),
+        mime_type: row.get(3),
+        size_bytes: row.get::<_, i64>(4).max(0) as u64,
+        updated_at: row.get(5),
+        audience: row.get(6),
+        content_hash: row.get(7),
+    }
+}
+
+fn ark_entry_from_row(row: &Row) -> ArkIndexEntry {
+    ArkIndexEntry {
+        workspace_ark: row.get(0),
+        file_ark: row.get(1),
+        object_key: row.get(2),
+        audience: row.get(3),
+        source_key: row.get(4),
+        updated_at: row.get(5),
+    }
+}
+
+#[derive(Clone)]
+pub struct PgNamespaceStore {
+    pool: Pool,
+}
+
+impl PgNamespaceStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NamespaceStore for PgNamespaceStore {
+    async fn get_namespace(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<NamespaceInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT id, owner_user_id, created_at, metadata FROM namespaces WHERE id = $1",
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(namespace_from_row))
+    }
+
+    async fn list_namespaces(
+        &self,
+        owner_user_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<NamespaceInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT id, owner_user_id, created_at, metadata FROM namespaces
+                 WHERE owner_user_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+                &[&owner_user_id, &(limit as i64), &(offset as i64)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(namespace_from_row).collect())
+    }
+
+    async fn get_audience(
+        &self,
+        namespace_id: &str,
+        audience_name: &str,
+    ) -> Result<Option<AudienceInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT namespace_id, audience_name, gates FROM namespace_audiences
+                 WHERE namespace_id = $1 AND audience_name = $2",
+                &[&namespace_id, &audience_name],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(audience_from_row))
+    }
+
+    async fn get_custom_domain(
+        &self,
+        domain: &str,
+    ) -> Result<Option<CustomDomainInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT domain, namespace_id, audience_name, created_at, verified
+                 FROM custom_domains WHERE domain = $1",
+                &[&domain],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(custom_domain_from_row))
+    }
+
+    async fn list_custom_domains(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<CustomDomainInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT domain, namespace_id, audience_name, created_at, verified
+                 FROM custom_domains WHERE namespace_id = $1 ORDER BY domain",
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(custom_domain_from_row).collect())
+    }
+
+    async fn upsert_custom_domain(
+        &self,
+        domain: &str,
+        namespace_id: &str,
+        audience_name: &str,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO custom_domains (domain, namespace_id, audience_name, created_at, verified)
+                 VALUES ($1, $2, $3, $4, FALSE)
+                 ON CONFLICT (domain) DO UPDATE SET
+                   namespace_id = excluded.namespace_id,
+                   audience_name = excluded.audience_name",
+                &[
+                    &domain,
+                    &namespace_id,
+                    &audience_name,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn delete_custom_domain(&self, domain: &str) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute("DELETE FROM custom_domains WHERE domain = $1", &[&domain])
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    async fn create_namespace(
+        &self,
+        namespace_id: &str,
+        owner_user_id: &str,
+        metadata: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespaces (id, owner_user_id, created_at, metadata)
+                 VALUES ($1, $2, $3, $4)",
+                &[
+                    &namespace_id,
+                    &owner_user_id,
+                    &Utc::now().timestamp(),
+                    &metadata,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn update_namespace_metadata(
+        &self,
+        namespace_id: &str,
+        metadata: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE namespaces SET metadata = $1 WHERE id = $2",
+                &[&metadata, &namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn delete_namespace(&self, namespace_id: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute("DELETE FROM namespaces WHERE id = $1", &[&namespace_id])
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn upsert_audience(
+        &self,
+        namespace_id: &str,
+        audience_name: &str,
+        gates: &[GateRecord],
+    ) -> Result<(), ServerCoreError> {
+        let gates_json = serde_json::to_string(gates)
+            .map_err(|e| ServerCoreError::internal(format!("encode gates: {e}")))?;
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespace_audiences (namespace_id, audience_name, gates)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (namespace_id, audience_name) DO UPDATE SET
+                   gates = excluded.gates",
+                &[&namespace_id, &audience_name, &gates_json],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_audiences(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<AudienceInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT namespace_id, audience_name, gates FROM namespace_audiences
+                 WHERE namespace_id = $1 ORDER BY audience_name",
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(audience_from_row).collect())
+    }
+
+    async fn delete_audience(
+        &self,
+        namespace_id: &str,
+        audience_name: &str,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "DELETE FROM namespace_audiences WHERE namespace_id = $1 AND audience_name = $2",
+                &[&namespace_id, &audience_name],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn clear_objects_audience(
+        &self,
+        namespace_id: &str,
+        audience_name: &str,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "UPDATE namespace_objects SET audience = NULL
+                 WHERE namespace_id = $1 AND audience = $2",
+                &[&namespace_id, &audience_name],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct PgSessionStore {
+    pool: Pool,
+}
+
+impl PgSessionStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SessionStore for PgSessionStore {
+    async fn create_session(
+        &self,
+        namespace_id: &str,
+        owner_user_id: &str,
+        read_only: bool,
+        expires_at: Option<i64>,
+    ) -> Result<String, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let code = generate_session_code();
+        client
+            .execute(
+                "INSERT INTO namespace_sessions
+                   (code, namespace_id, owner_user_id, read_only, created_at, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &code,
+                    &namespace_id,
+                    &owner_user_id,
+                    &read_only,
+                    &Utc::now().timestamp(),
+                    &expires_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(code)
+    }
+
+    async fn get_session(
+        &self,
+        code: &str,
+    ) -> Result<Option<NamespaceSessionInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT code, namespace_id, owner_user_id, read_only, created_at, expires_at
+                 FROM namespace_sessions
+                 WHERE code = $1 AND (expires_at IS NULL OR expires_at > $2)",
+                &[&code, &Utc::now().timestamp()],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|row| NamespaceSessionInfo {
+            code: row.get(0),
+            namespace_id: row.get(1),
+            owner_user_id: row.get(2),
+            read_only: row.get(3),
+            created_at: row.get(4),
+            expires_at: row.get(5),
+        }))
+    }
+
+    async fn update_session_read_only(
+        &self,
+        code: &str,
+        read_only: bool,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let updated = client
+            .execute(
+                "UPDATE namespace_sessions SET read_only = $1 WHERE code = $2",
+                &[&read_only, &code],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(updated > 0)
+    }
+
+    async fn delete_session(&self, code: &str) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute("DELETE FROM namespace_sessions WHERE code = $1", &[&code])
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+}
+
+#[derive(Clone)]
+pub struct PgNamespaceMemberStore {
+    pool: Pool,
+}
+
+impl PgNamespaceMemberStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+const MEMBER_SELECT: &str = "SELECT m.namespace_id, m.user_id, u.email, m.role, m.created_at
+     FROM namespace_members m LEFT JOIN users u ON u.id = m.user_id";
+
+const INVITE_COLUMNS: &str = "id, namespace_id, email, role, invited_by, created_at, expires_at";
+
+#[async_trait]
+impl NamespaceMemberStore for PgNamespaceMemberStore {
+    async fn get_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<NamespaceMemberInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("{MEMBER_SELECT} WHERE m.namespace_id = $1 AND m.user_id = $2"),
+                &[&namespace_id, &user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(member_from_row))
+    }
+
+    async fn list_members(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "{MEMBER_SELECT} WHERE m.namespace_id = $1 ORDER BY m.created_at, m.user_id"
+                ),
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(member_from_row).collect())
+    }
+
+    async fn list_user_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "{MEMBER_SELECT} WHERE m.user_id = $1 ORDER BY m.created_at DESC, m.namespace_id"
+                ),
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(member_from_row).collect())
+    }
+
+    async fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespace_members (namespace_id, user_id, role, created_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (namespace_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+                &[
+                    &member.namespace_id,
+                    &member.user_id,
+                    &member.role.as_str(),
+                    &member.created_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn remove_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM namespace_members WHERE namespace_id = $1 AND user_id = $2",
+                &[&namespace_id, &user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    async fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespace_invites
+                   (id, namespace_id, email, role, token_hash, invited_by, created_at, expires_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &invite.id,
+                    &invite.namespace_id,
+                    &invite.email,
+                    &invite.role.as_str(),
+                    &token_hash,
+                    &invite.invited_by,
+                    &invite.created_at,
+                    &invite.expires_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_invite_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<NamespaceInviteInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {INVITE_COLUMNS} FROM namespace_invites WHERE token_hash = $1"),
+                &[&token_hash],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(invite_from_row))
+    }
+
+    async fn list_invites(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {INVITE_COLUMNS} FROM namespace_invites
+                     WHERE namespace_id = $1 ORDER BY created_at DESC, id"
+                ),
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(invite_from_row).collect())
+    }
+
+    async fn delete_invite(
+        &self,
+        namespace_id: &str,
+        invite_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM namespace_invites WHERE namespace_id = $1 AND id = $2",
+                &[&namespace_id, &invite_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+}
+
+#[derive(Clone)]
+pub struct PgWebhookStore {
+    pool: Pool,
+}
+
+impl PgWebhookStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+const WEBHOOK_COLUMNS: &str = "id, namespace_id, url, events, created_by, created_at";
+
+const DELIVERY_COLUMNS: &str = "id, webhook_id, namespace_id, event, payload, status, attempts,
+     next_attempt_at, response_status, last_error, created_at, updated_at";
+
+#[async_trait]
+impl WebhookStore for PgWebhookStore {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookInfo,
+        secret: &str,
+    ) -> Result<(), ServerCoreError> {
+        let events = serde_json::to_string(&webhook.events)
+            .map_err(|e| ServerCoreError::internal(format!("encode events: {e}")))?;
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespace_webhooks
+                   (id, namespace_id, url, events, secret, created_by, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    &webhook.id,
+                    &webhook.namespace_id,
+                    &webhook.url,
+                    &events,
+                    &secret,
+                    &webhook.created_by,
+                    &webhook.created_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_webhook(&self, webhook_id: &str) -> Result<Option<WebhookInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {WEBHOOK_COLUMNS} FROM namespace_webhooks WHERE id = $1"),
+                &[&webhook_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(webhook_from_row))
+    }
+
+    async fn get_webhook_secret(
+        &self,
+        webhook_id: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT secret FROM namespace_webhooks WHERE id = $1",
+                &[&webhook_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|r| r.get(0)))
+    }
+
+    async fn list_webhooks(&self, namespace_id: &str) -> Result<Vec<WebhookInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {WEBHOOK_COLUMNS} FROM namespace_webhooks
+                     WHERE namespace_id = $1 ORDER BY created_at, id"
+                ),
+                &[&namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(webhook_from_row).collect())
+    }
+
+    async fn delete_webhook(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        // Deliveries go with it (`ON DELETE CASCADE`).
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM namespace_webhooks WHERE namespace_id = $1 AND id = $2",
+                &[&namespace_id, &webhook_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    async fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO webhook_deliveries
+                   (id, webhook_id, namespace_id, event, payload, status, attempts,
+                    next_attempt_at, response_status, last_error, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                 ON CONFLICT (id) DO UPDATE SET
+                   status = EXCLUDED.status,
+                   attempts = EXCLUDED.attempts,
+                   next_attempt_at = EXCLUDED.next_attempt_at,
+                   response_status = EXCLUDED.response_status,
+                   last_error = EXCLUDED.last_error,
+                   updated_at = EXCLUDED.updated_at",
+                &[
+                    &delivery.id,
+                    &delivery.webhook_id,
+                    &delivery.namespace_id,
+                    &delivery.event.as_str(),
+                    &delivery.payload,
+                    &delivery.status.as_str(),
+                    &(delivery.attempts as i32),
+                    &delivery.next_attempt_at,
+                    &delivery.response_status.map(i32::from),
+                    &delivery.last_error,
+                    &delivery.created_at,
+                    &delivery.updated_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_delivery(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE id = $1"),
+                &[&delivery_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().and_then(delivery_from_row))
+    }
+
+    async fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+                     WHERE namespace_id = $1 AND ($2::TEXT IS NULL OR webhook_id = $2)
+                     ORDER BY created_at DESC, id DESC LIMIT $3"
+                ),
+                &[&namespace_id, &webhook_id, &(limit as i64)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().filter_map(delivery_from_row).collect())
+    }
+
+    async fn list_due_deliveries(
+        &self,
+        now: i64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+                     WHERE status = 'pending' AND next_attempt_at <= $1
+                     ORDER BY next_attempt_at, id LIMIT $2"
+                ),
+                &[&now, &(limit as i64)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().filter_map(delivery_from_row).collect())
+    }
+}
+
+#[derive(Clone)]
+pub struct PgObjectMetaStore {
+    pool: Pool,
+}
+
+impl PgObjectMetaStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    async fn usage_totals(
+        &self,
+        user_id: &str,
+        namespace_id: Option<&str>,
+    ) -> Result<UsageTotals, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT event_type, COALESCE(SUM(amount), 0)::BIGINT FROM usage_events
+                 WHERE user_id = $1 AND ($2::TEXT IS NULL OR namespace_id = $2)
+                 GROUP BY event_type",
+                &[&user_id, &namespace_id],
+            )
+            .await
+            .map_err(db_error)?;
+        let mut totals = UsageTotals::default();
+        for row in rows {
+            let amount = row.get::<_, i64>(1).max(0) as u64;
+            match row.get::<_, &str>(0) {
+                "bytes_in" => totals.bytes_in = amount,
+                "bytes_out" => totals.bytes_out = amount,
+                "relay_seconds" => totals.relay_seconds = amount,
+                _ => {}
+            }
+        }
+        Ok(totals)
+    }
+}
+
+#[async_trait]
+impl ObjectMetaStore for PgObjectMetaStore {
+    async fn upsert_object(
+        &self,
+        namespace_id: &str,
+        key: &str,
+        blob_key: &str,
+        mime_type: &str,
+        size_bytes: u64,
+        audience: Option<&str>,
+        content_hash: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO namespace_objects
+                   (namespace_id, key, r2_key, mime_type, size_bytes, updated_at, audience, content_hash)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (namespace_id, key) DO UPDATE SET
+                   r2_key = excluded.r2_key,
+                   mime_type = excluded.mime_type,
+                   size_bytes = excluded.size_bytes,
+                   updated_at = excluded.updated_at,
+                   audience = excluded.audience,
+                   content_hash = excluded.content_hash",
+                &[
+                    &namespace_id,
+                    &key,
+                    &blob_key,
+                    &mime_type,
+                    &(size_bytes as i64),
+                    &Utc::now().timestamp(),
+                    &audience,
+                    &content_hash,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_object_meta(
+        &self,
+        namespace_id: &str,
+        key: &str,
+    ) -> Result<Option<ObjectMeta>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!(
+                    "SELECT {OBJECT_COLUMNS} FROM namespace_objects
+                     WHERE namespace_id = $1 AND key = $2"
+                ),
+                &[&namespace_id, &key],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(object_from_row))
+    }
+
+    async fn get_objects_meta_batch(
+        &self,
+        namespace_id: &str,
+        keys: &[String],
+    ) -> Result<Vec<ObjectMeta>, ServerCoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {OBJECT_COLUMNS} FROM namespace_objects
+                     WHERE namespace_id = $1 AND key = ANY($2)"
+                ),
+                &[&namespace_id, &keys],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(object_from_row).collect())
+    }
+
+    async fn list_objects(
+        &self,
+        namespace_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<ObjectMeta>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {OBJECT_COLUMNS} FROM namespace_objects
+                     WHERE namespace_id = $1 ORDER BY key LIMIT $2 OFFSET $3"
+                ),
+                &[&namespace_id, &(limit as i64), &(offset as i64)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(object_from_row).collect())
+    }
+
+    async fn delete_object(&self, namespace_id: &str, key: &str) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "DELETE FROM namespace_objects WHERE namespace_id = $1 AND key = $2",
+                &[&namespace_id, &key],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn count_refs_to_blob(
+        &self,
+        namespace_id: &str,
+        blob_key: &str,
+    ) -> Result<u64, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let count: i64 = client
+            .query_one(
+                "SELECT COUNT(*) FROM namespace_objects WHERE namespace_id = $1 AND r2_key = $2",
+                &[&namespace_id, &blob_key],
+            )
+            .await
+            .map_err(db_error)?
+            .get(0);
+        Ok(count.max(0) as u64)
+    }
+
+    async fn record_usage(
+        &self,
+        user_id: &str,
+        event_type: &str,
+        amount: u64,
+        namespace_id: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO usage_events (user_id, event_type, amount, namespace_id, recorded_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &user_id,
+                    &event_type,
+                    &(amount as i64),
+                    &namespace_id,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_usage_totals(&self, user_id: &str) -> Result<UsageTotals, ServerCoreError> {
+        self.usage_totals(user_id, None).await
+    }
+
+    async fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT event_type, amount, namespace_id, recorded_at FROM usage_events
+                 WHERE user_id = $1 ORDER BY recorded_at, id",
+                &[&user_id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows
+            .iter()
+            .map(|row| UsageEvent {
+                event_type: row.get(0),
+                amount: row.get::<_, i64>(1).max(0) as u64,
+                namespace_id: row.get(2),
+                recorded_at: row.get(3),
+            })
+            .collect())
+    }
+
+    async fn get_namespace_usage_totals(
+        &self,
+        user_id: &str,
+        namespace_id: &str,
+    ) -> Result<UsageTotals, ServerCoreError> {
+        self.usage_totals(user_id, Some(namespace_id)).await
+    }
+}
+
+#[derive(Clone)]
+pub struct PgArkIndexStore {
+    pool: Pool,
+}
+
+impl PgArkIndexStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ArkIndexStore for PgArkIndexStore {
+    async fn upsert_ark(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        object_key: &str,
+        audience: Option<&str>,
+        source_key: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO ark_index
+                   (workspace_ark, file_ark, object_key, audience, source_key, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (workspace_ark, file_ark) DO UPDATE SET
+                   object_key = excluded.object_key,
+                   audience = excluded.audience,
+                   source_key = excluded.source_key,
+                   updated_at = excluded.updated_at",
+                &[
+                    &workspace_ark,
+                    &file_ark,
+                    &object_key,
+                    &audience,
+                    &source_key,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn resolve_ark(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Option<ArkIndexEntry>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT workspace_ark, file_ark, object_key, audience, source_key, updated_at
+                 FROM ark_index WHERE workspace_ark = $1 AND file_ark = $2",
+                &[&workspace_ark, &file_ark],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().map(ark_entry_from_row))
+    }
+
+    async fn get_ark_owner(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                "SELECT object_key FROM ark_index WHERE workspace_ark = $1 AND file_ark = $2",
+                &[&workspace_ark, &file_ark],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn list_ark_entries(
+        &self,
+        workspace_ark: &str,
+    ) -> Result<Vec<ArkIndexEntry>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT workspace_ark, file_ark, object_key, audience, source_key, updated_at
+                 FROM ark_index WHERE workspace_ark = $1",
+                &[&workspace_ark],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().map(ark_entry_from_row).collect())
+    }
+
+    async fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                "INSERT INTO ark_versions (workspace_ark, file_ark, version, object_key, created_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &workspace_ark,
+                    &file_ark,
+                    &version,
+                    &object_key,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<ArkVersionEntry>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                "SELECT workspace_ark, file_ark, version, object_key, created_at
+                 FROM ark_versions WHERE workspace_ark = $1 AND file_ark = $2
+                 ORDER BY created_at DESC, version",
+                &[&workspace_ark, &file_ark],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows
+            .iter()
+            .map(|row| ArkVersionEntry {
+                workspace_ark: row.get(0),
+                file_ark: row.get(1),
+                version: row.get(2),
+                object_key: row.get(3),
+                created_at: row.get(4),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,108 @@
+//! Postgres schema migrations.
+//!
+//! Applied versions are recorded in `schema_migrations`. Migration runs hold a
+//! transaction-scoped advisory lock, so several server instances starting
+//! against the same database apply each migration exactly once.
+
+use diaryx_server::ports::ServerCoreError;
+use diaryx_server::schema::Migration;
+
+use super::db_error;
+
+/// Ordered Postgres migrations. Applying them sequentially to an empty
+/// database produces the current target schema.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        sql: include_str!("migrations/0001_initial.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "access_tokens",
+        sql: include_str!("migrations/0002_access_tokens.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "namespace_members",
+        sql: include_str!("migrations/0003_namespace_members.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "webhooks",
+        sql: include_str!("migrations/0004_webhooks.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "audit_log",
+        sql: include_str!("migrations/0005_audit_log.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "webmentions",
+        sql: include_str!("migrations/0006_webmentions.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "activitypub",
+        sql: include_str!("migrations/0007_activitypub.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "comments",
+        sql: include_str!("migrations/0008_comments.sql"),
+    },
+];
+
+/// The version number of the latest Postgres migration.
+pub const CURRENT_VERSION: u32 = 8;
+
+/// Arbitrary key for `pg_advisory_xact_lock`, shared by every instance.
+const MIGRATION_LOCK_KEY: i64 = 0x6469_6172_7978; // "diaryx"
+
+/// Apply any migrations newer than the recorded schema version.
+pub async fn migrate(client: &mut tokio_postgres::Client) -> Result<(), ServerCoreError> {
+    let tx = client.transaction().await.map_err(db_error)?;
+    tx.execute("SELECT pg_advisory_xact_lock($1)", &[&MIGRATION_LOCK_KEY])
+        .await
+        .map_err(db_error)?;
+    tx.batch_execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at BIGINT NOT NULL
+        )",
+    )
+    .await
+    .map_err(db_error)?;
+
+    let applied: i32 = tx
+        .query_one(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            &[],
+        )
+        .await
+        .map_err(db_error)?
+        .get(0);
+
+    for m in MIGRATIONS {
+        if m.version as i32 <= applied {
+            continue;
+        }
+        tx.batch_execute(m.sql).await.map_err(|e| {
+            ServerCoreError::internal(format!("Postgres migration '{}' failed: {e}", m.name))
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES ($1, $2, $3)",
+            &[
+                &(m.version as i32),
+                &m.name,
+                &chrono::Utc::now().timestamp(),
+            ],
+        )
+        .await
+        .map_err(db_error)?;
+    }
+
+    tx.commit().await.map_err(db_error)
+}
@@ -0,0 +1,140 @@
+//! Postgres implementation of the Webmention port.
+
+use super::{db_error, pool_error};
+use async_trait::async_trait;
+use deadpool_postgres::Pool;
+use diaryx_server::domain::{WebmentionInfo, WebmentionStatus};
+use diaryx_server::ports::{ServerCoreError, WebmentionStore};
+use tokio_postgres::Row;
+
+const WEBMENTION_COLUMNS: &str =
+    "id, namespace_id, source, target, object_key, status, source_title, created_at, updated_at";
+
+/// `None` for rows with an unknown status.
+fn webmention_from_row(row: &Row) -> Option<WebmentionInfo> {
+    Some(WebmentionInfo {
+        id: row.get(0),
+        namespace_id: row.get(1),
+        source: row.get(2),
+        target: row.get(3),
+        object_key: row.get(4),
+        status: WebmentionStatus::parse(row.get(5))?,
+        source_title: row.get(6),
+        created_at: row.get(7),
+        updated_at: row.get(8),
+    })
+}
+
+pub struct PgWebmentionStore {
+    pool: Pool,
+}
+
+impl PgWebmentionStore {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WebmentionStore for PgWebmentionStore {
+    async fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO webmentions ({WEBMENTION_COLUMNS})
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     ON CONFLICT (id) DO UPDATE SET
+                        object_key = EXCLUDED.object_key,
+                        status = EXCLUDED.status,
+                        source_title = EXCLUDED.source_title,
+                        updated_at = EXCLUDED.updated_at"
+                ),
+                &[
+                    &mention.id,
+                    &mention.namespace_id,
+                    &mention.source,
+                    &mention.target,
+                    &mention.object_key,
+                    &mention.status.as_str(),
+                    &mention.source_title,
+                    &mention.created_at,
+                    &mention.updated_at,
+                ],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get_webmention(&self, id: &str) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!("SELECT {WEBMENTION_COLUMNS} FROM webmentions WHERE id = $1"),
+                &[&id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().and_then(webmention_from_row))
+    }
+
+    async fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let row = client
+            .query_opt(
+                &format!(
+                    "SELECT {WEBMENTION_COLUMNS} FROM webmentions
+                     WHERE namespace_id = $1 AND source = $2 AND target = $3"
+                ),
+                &[&namespace_id, &source, &target],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(row.as_ref().and_then(webmention_from_row))
+    }
+
+    async fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError> {
+        let status = status.map(|s| s.as_str());
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {WEBMENTION_COLUMNS} FROM webmentions
+                     WHERE namespace_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT $3"
+                ),
+                &[&namespace_id, &status, &i64::from(limit)],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(rows.iter().filter_map(webmention_from_row).collect())
+    }
+
+    async fn delete_webmention(
+        &self,
+        namespace_id: &str,
+        id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let client = self.pool.get().await.map_err(pool_error)?;
+        let deleted = client
+            .execute(
+                "DELETE FROM webmentions WHERE namespace_id = $1 AND id = $2",
+                &[&namespace_id, &id],
+            )
+            .await
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+}
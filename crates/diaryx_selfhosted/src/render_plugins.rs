@@ -0,0 +1,75 @@
+//! Plugins that expand editor syntax when a namespace is built.
+//!
+//! `RENDER_PLUGINS_DIR` holds installed plugins in the usual
+//! `{plugin_id}/plugin.wasm` layout. [`load_render_hooks`] loads them through
+//! `diaryx_extism` and keeps the hooks of those declaring the `render_html`
+//! capability, which every build passes to
+//! [`RenderService`](diaryx_server::use_cases::render::RenderService). This is
+//! where the pages `diaryx publish` and the apps upload get their math and
+//! drawings rendered.
+//!
+//! Render hooks must be pure, so the plugins run against an empty in-memory
+//! filesystem with every permission denied. Signatures are checked against
+//! `trusted-publishers.json` in the same directory, pinning each publisher key
+//! on first load.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use diaryx_core::fs::{InMemoryFileSystem, SyncToAsyncFs};
+use diaryx_core::plugin::PluginRegistry;
+use diaryx_extism::{HostContext, PluginTrustPolicy, load_plugins_from_dir};
+use diaryx_render::RenderHooks;
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+/// File in the render plugins directory pinning each plugin's publisher key.
+pub const RENDER_TRUST_STORE_FILE: &str = "trusted-publishers.json";
+
+/// Render hooks of the plugins in `config.render_plugins_dir`. Empty when no
+/// directory is configured or none of its plugins render HTML; plugins that
+/// fail to load are logged and skipped.
+pub fn load_render_hooks(config: &Config) -> RenderHooks {
+    let Some(dir) = config.render_plugins_dir.as_deref() else {
+        return RenderHooks::new();
+    };
+    let hooks = load_render_hooks_from_dir(dir, config.render_plugins_allow_unsigned);
+    let syntax: Vec<_> = hooks
+        .hooks()
+        .map(|hook| format!("{} ({})", hook.extension_id, hook.plugin_id))
+        .collect();
+    if syntax.is_empty() {
+        warn!(
+            "No render plugins loaded from {}; only built-in syntax will be rendered",
+            dir.display()
+        );
+    } else {
+        info!("Render hooks: {}", syntax.join(", "));
+    }
+    hooks
+}
+
+fn load_render_hooks_from_dir(dir: &Path, allow_unsigned: bool) -> RenderHooks {
+    let mut host_context =
+        HostContext::with_fs(Arc::new(SyncToAsyncFs::new(InMemoryFileSystem::new())));
+    host_context.plugin_trust = PluginTrustPolicy {
+        trust_store: Some(dir.join(RENDER_TRUST_STORE_FILE)),
+        allow_unsigned,
+        allow_rekeyed: false,
+    };
+
+    let adapters = match load_plugins_from_dir(dir, Arc::new(host_context)) {
+        Ok(adapters) => adapters,
+        Err(e) => {
+            warn!("Failed to read render plugins from {}: {e}", dir.display());
+            return RenderHooks::new();
+        }
+    };
+
+    let mut registry = PluginRegistry::new();
+    for adapter in adapters {
+        registry.register_workspace_plugin(Arc::new(adapter));
+    }
+    RenderHooks::from_registry(&registry)
+}
@@ -1,8 +1,9 @@
 //! Native-target test helpers for `diaryx_selfhosted`.
 //!
 //! Provides a real-TCP test server ([`TestServer`]) that spawns the full
-//! router on `127.0.0.1:0` with `:memory:` SQLite + [`InMemoryBlobStore`],
-//! plus a dev-mode sign-in helper. Used by
+//! router on `127.0.0.1:0` with `:memory:` SQLite + [`InMemoryBlobStore`]
+//! (or a Postgres database via [`TestServer::start_postgres`]), plus a
+//! dev-mode sign-in helper. Used by
 //! `crates/plugins/diaryx_sync_extism/tests/sync_e2e.rs` to drive two
 //! plugin instances against a real HTTP server.
 //!
@@ -26,24 +27,49 @@ use std::sync::Arc;
 
 use axum::Router;
 use axum::routing::get;
+use diaryx_server::ports::{
+    AccessTokenStore, ActivityPubStore, ArkIndexStore, AuditLogStore, AuthSessionStore, AuthStore,
+    CommentStore, DeviceStore, JobSink, MagicLinkStore, NamespaceMemberStore, NamespaceStore,
+    ObjectMetaStore, SessionStore, UserStore, WebhookStore, WebmentionStore,
+};
 use rusqlite::Connection;
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
 use crate::adapters::{
-    NativeArkIndexStore, NativeAuthSessionStore, NativeAuthStore, NativeNamespaceStore,
-    NativeObjectMetaStore, NativeSessionStore, NativeUserStore,
+    NativeAccessTokenStore, NativeActivityPubStore, NativeArkIndexStore, NativeAuditLogStore,
+    NativeAuthSessionStore, NativeAuthStore, NativeCommentStore, NativeDeviceStore,
+    NativeMagicLinkStore, NativeNamespaceMemberStore, NativeNamespaceStore, NativeObjectMetaStore,
+    NativePasskeyStore, NativeSessionStore, NativeUserStore, NativeWebhookStore,
+    NativeWebmentionStore,
 };
 use crate::auth::{MagicLinkService, PasskeyService};
 use crate::blob_store::{BlobStore, InMemoryBlobStore};
 use crate::config::{AppleIapConfig, Config, EmailConfig, ManagedAiConfig, R2Config, StripeConfig};
-use crate::db::{AuthRepo, NamespaceRepo, init_database};
+use crate::db::{
+    ActivityPubRepo, AuditRepo, AuthRepo, CommentRepo, NamespaceRepo, WebmentionRepo, init_database,
+};
 use crate::email::EmailService;
 use crate::handlers::{
-    AudienceState, NamespaceState, NsSessionState, ObjectState, audience_routes, auth_routes,
-    namespace_routes, ns_session_routes, object_routes, public_object_routes, usage_routes,
+    AccountState, ActivityPubState, ArchiveState, AudienceState, AuditState, CommentState,
+    MemberState, NamespaceState, NsSessionState, ObjectState, WebhookState, WebmentionState,
+    account_audit_routes, account_routes, activitypub_routes, archive_routes, audience_routes,
+    auth_routes, comment_routes, member_routes, membership_routes, namespace_audit_routes,
+    namespace_routes, ns_session_routes, object_routes, public_comment_routes,
+    public_object_routes, usage_routes, webfinger_routes, webhook_routes, webmention_routes,
+};
+use crate::jobs::{
+    MAX_OUTBOUND_REDIRECTS, OutboundClient, ReqwestActivityPubClient, ReqwestWebhookTransport,
+    ReqwestWebmentionFetcher, TokioJobSink,
+};
+use crate::postgres::{
+    PgAccessTokenStore, PgActivityPubStore, PgArkIndexStore, PgAuditLogStore, PgAuthSessionStore,
+    PgAuthStore, PgCommentStore, PgDeviceStore, PgMagicLinkStore, PgNamespaceMemberStore,
+    PgNamespaceStore, PgObjectMetaStore, PgSessionStore, PgUserStore, PgWebhookStore,
+    PgWebmentionStore,
 };
+use crate::render_plugins::load_render_hooks;
 
 // ---------------------------------------------------------------------------
 // Config
@@ -74,6 +100,8 @@ pub fn test_config() -> Config {
             endpoint: None,
             prefix: "test".to_string(),
         },
+        s3: None,
+        blob_store_backend: None,
         token_signing_key: vec![0u8; 32], // 32-byte zero key — test-only
         admin_secret: None,
         managed_ai: ManagedAiConfig {
@@ -92,6 +120,12 @@ pub fn test_config() -> Config {
         kv_namespace_id: None,
         site_base_url: "http://localhost:5174".to_string(),
         site_domain: None,
+        storage_reconcile_interval_hours: None,
+        storage_reconcile_grace_hours: 24,
+        audit_log_retention_days: 365,
+        outbound_allow_private: false,
+        render_plugins_dir: None,
+        render_plugins_allow_unsigned: false,
     }
 }
 
@@ -99,44 +133,165 @@ pub fn test_config() -> Config {
 // Router assembly
 // ---------------------------------------------------------------------------
 
+/// Port-trait stores behind the E2E router, so the same router runs against
+/// SQLite or Postgres.
+struct E2eStores {
+    auth_store: Arc<dyn AuthStore>,
+    auth_session_store: Arc<dyn AuthSessionStore>,
+    magic_link_store: Arc<dyn MagicLinkStore>,
+    user_store: Arc<dyn UserStore>,
+    device_store: Arc<dyn DeviceStore>,
+    access_token_store: Arc<dyn AccessTokenStore>,
+    namespace_store: Arc<dyn NamespaceStore>,
+    member_store: Arc<dyn NamespaceMemberStore>,
+    session_store: Arc<dyn SessionStore>,
+    object_meta_store: Arc<dyn ObjectMetaStore>,
+    ark_index_store: Arc<dyn ArkIndexStore>,
+    webhook_store: Arc<dyn WebhookStore>,
+    webmention_store: Arc<dyn WebmentionStore>,
+    comment_store: Arc<dyn CommentStore>,
+    activitypub_store: Arc<dyn ActivityPubStore>,
+    audit_store: Arc<dyn AuditLogStore>,
+}
+
+impl E2eStores {
+    fn sqlite(repo: &Arc<AuthRepo>) -> Self {
+        let ns_repo = Arc::new(NamespaceRepo::new(repo.connection()));
+        Self {
+            auth_store: Arc::new(NativeAuthStore::new(repo.clone())),
+            auth_session_store: Arc::new(NativeAuthSessionStore::new(repo.clone())),
+            magic_link_store: Arc::new(NativeMagicLinkStore::new(repo.clone())),
+            user_store: Arc::new(NativeUserStore::new(repo.clone())),
+            device_store: Arc::new(NativeDeviceStore::new(repo.clone())),
+            access_token_store: Arc::new(NativeAccessTokenStore::new(repo.clone())),
+            namespace_store: Arc::new(NativeNamespaceStore::new(ns_repo.clone())),
+            member_store: Arc::new(NativeNamespaceMemberStore::new(ns_repo.clone())),
+            session_store: Arc::new(NativeSessionStore::new(ns_repo.clone())),
+            object_meta_store: Arc::new(NativeObjectMetaStore::new(ns_repo.clone())),
+            ark_index_store: Arc::new(NativeArkIndexStore::new(ns_repo.clone())),
+            webhook_store: Arc::new(NativeWebhookStore::new(ns_repo)),
+            webmention_store: Arc::new(NativeWebmentionStore::new(Arc::new(WebmentionRepo::new(
+                repo.connection(),
+            )))),
+            comment_store: Arc::new(NativeCommentStore::new(Arc::new(CommentRepo::new(
+                repo.connection(),
+            )))),
+            activitypub_store: Arc::new(NativeActivityPubStore::new(Arc::new(
+                ActivityPubRepo::new(repo.connection()),
+            ))),
+            audit_store: Arc::new(NativeAuditLogStore::new(Arc::new(AuditRepo::new(
+                repo.connection(),
+            )))),
+        }
+    }
+
+    fn postgres(pool: &deadpool_postgres::Pool) -> Self {
+        Self {
+            auth_store: Arc::new(PgAuthStore::new(pool.clone())),
+            auth_session_store: Arc::new(PgAuthSessionStore::new(pool.clone())),
+            magic_link_store: Arc::new(PgMagicLinkStore::new(pool.clone())),
+            user_store: Arc::new(PgUserStore::new(pool.clone())),
+            device_store: Arc::new(PgDeviceStore::new(pool.clone())),
+            access_token_store: Arc::new(PgAccessTokenStore::new(pool.clone())),
+            namespace_store: Arc::new(PgNamespaceStore::new(pool.clone())),
+            member_store: Arc::new(PgNamespaceMemberStore::new(pool.clone())),
+            session_store: Arc::new(PgSessionStore::new(pool.clone())),
+            object_meta_store: Arc::new(PgObjectMetaStore::new(pool.clone())),
+            ark_index_store: Arc::new(PgArkIndexStore::new(pool.clone())),
+            webhook_store: Arc::new(PgWebhookStore::new(pool.clone())),
+            webmention_store: Arc::new(PgWebmentionStore::new(pool.clone())),
+            comment_store: Arc::new(PgCommentStore::new(pool.clone())),
+            activitypub_store: Arc::new(PgActivityPubStore::new(pool.clone())),
+            audit_store: Arc::new(PgAuditLogStore::new(pool.clone())),
+        }
+    }
+}
+
 /// Build the subset of the full router needed for plugin E2E scenarios:
-/// health + auth + namespace + object + audience + usage + sessions + public
-/// object access. Omits: sync-v2 websockets, AI proxy, Stripe, Apple IAP,
-/// domain management. Add them back by extending this function when a test
-/// needs them.
+/// health + auth + namespace + object + audience + member + webhook + webmention +
+/// comment + ActivityPub + audit + usage + sessions + public object access.
+/// Omits: sync-v2 websockets, AI proxy, Stripe, Apple IAP, domain management.
+/// Add them back by extending this function when a test needs them.
+///
+/// Passkeys have no port-trait store yet, so `passkey_repo` always backs
+/// [`PasskeyService`] regardless of which stores are in use.
 fn build_e2e_router(
     config: Arc<Config>,
-    repo: Arc<AuthRepo>,
+    stores: E2eStores,
+    passkey_repo: Arc<AuthRepo>,
 ) -> (Router, axum::Extension<crate::auth::AuthExtractor>) {
-    let ns_repo = Arc::new(NamespaceRepo::new(repo.connection()));
-    let magic_link_service = Arc::new(MagicLinkService::new(repo.clone(), config.clone()));
+    let E2eStores {
+        auth_store,
+        auth_session_store,
+        magic_link_store,
+        user_store,
+        device_store,
+        access_token_store,
+        namespace_store,
+        member_store,
+        session_store,
+        object_meta_store,
+        ark_index_store,
+        webhook_store,
+        webmention_store,
+        comment_store,
+        activitypub_store,
+        audit_store,
+    } = stores;
+    let magic_link_service = Arc::new(
+        MagicLinkService::with_stores(
+            magic_link_store,
+            user_store.clone(),
+            device_store,
+            auth_session_store.clone(),
+            &config,
+        )
+        .with_audit(audit_store.clone()),
+    );
     let email_service = Arc::new(EmailService::new(config.clone()));
+    let passkey_store = Arc::new(NativePasskeyStore::new(passkey_repo.clone()));
     let passkey_service = Arc::new(PasskeyService::new(
-        repo.clone(),
+        passkey_repo,
         config.clone(),
         magic_link_service.clone(),
     ));
-
-    let auth_session_store = Arc::new(NativeAuthSessionStore::new(repo.clone()));
-    let user_store = Arc::new(NativeUserStore::new(repo.clone()));
-    let auth_store = Arc::new(NativeAuthStore::new(repo.clone()));
-    let namespace_store = Arc::new(NativeNamespaceStore::new(ns_repo.clone()));
-    let session_store = Arc::new(NativeSessionStore::new(ns_repo.clone()));
-    let object_meta_store = Arc::new(NativeObjectMetaStore::new(ns_repo.clone()));
-    let ark_index_store = Arc::new(NativeArkIndexStore::new(ns_repo));
     let blob_store: Arc<dyn BlobStore> = Arc::new(InMemoryBlobStore::new("test"));
 
     let auth_extractor =
-        crate::auth::AuthExtractor::new(auth_store.clone(), auth_session_store.clone());
+        crate::auth::AuthExtractor::new(auth_store.clone(), auth_session_store.clone())
+            .with_access_tokens(access_token_store.clone());
 
+    let account_state = AccountState {
+        auth_store: auth_store.clone(),
+        user_store: user_store.clone(),
+        passkey_store,
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        domain_mapping_cache: None,
+        email_service: email_service.clone(),
+        app_base_url: config.app_base_url.clone(),
+        token_signing_key: config.token_signing_key.clone(),
+    };
+    let comment_state = CommentState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        comment_store,
+        auth_store: auth_store.clone(),
+        email_service: email_service.clone(),
+        token_signing_key: config.token_signing_key.clone(),
+    };
     let auth_state = crate::handlers::auth::AuthState {
         magic_link_service,
-        email_service,
+        email_service: email_service.clone(),
         auth_store,
         namespace_store: namespace_store.clone(),
         session_store: auth_session_store,
         user_store,
+        access_token_store,
         passkey_service,
+        audit_store: audit_store.clone(),
         session_expiry_days: config.session_expiry_days,
         secure_cookies: config.secure_cookies,
     };
@@ -144,17 +299,90 @@ fn build_e2e_router(
         namespace_store: namespace_store.clone(),
         domain_mapping_cache: None, // domains not wired in test router
     };
+    let archive_state = ArchiveState {
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        domain_mapping_cache: None,
+    };
+    let api_base_url = format!("{}/api", config.site_base_url);
+    let job_sink: Arc<dyn JobSink> = Arc::new(
+        TokioJobSink::new(
+            webhook_store.clone(),
+            Arc::new(ReqwestWebhookTransport::new(OutboundClient::new(
+                config.outbound_allow_private,
+                0,
+            ))),
+        )
+        .with_webmentions(
+            webmention_store.clone(),
+            Arc::new(ReqwestWebmentionFetcher::new(OutboundClient::new(
+                config.outbound_allow_private,
+                MAX_OUTBOUND_REDIRECTS,
+            ))),
+        )
+        .with_activitypub(
+            activitypub_store.clone(),
+            Arc::new(ReqwestActivityPubClient::new(OutboundClient::new(
+                config.outbound_allow_private,
+                MAX_OUTBOUND_REDIRECTS,
+            ))),
+            api_base_url.clone(),
+        ),
+    );
+    let webhook_state = WebhookState {
+        namespace_store: namespace_store.clone(),
+        webhook_store,
+        job_sink: job_sink.clone(),
+        allow_private_targets: config.outbound_allow_private,
+    };
+    let webmention_state = WebmentionState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        webmention_store,
+        job_sink: job_sink.clone(),
+        api_base_url: api_base_url.clone(),
+    };
+    let activitypub_state = ActivityPubState {
+        namespace_store: namespace_store.clone(),
+        activitypub_store,
+        client: Arc::new(ReqwestActivityPubClient::new(OutboundClient::new(
+            config.outbound_allow_private,
+            MAX_OUTBOUND_REDIRECTS,
+        ))),
+        job_sink,
+        api_base_url,
+    };
     let object_state = ObjectState {
         namespace_store: namespace_store.clone(),
         object_meta_store,
         blob_store: blob_store.clone(),
         ark_index_store,
         token_signing_key: config.token_signing_key.clone(),
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        webmentions: webmention_state.clone(),
+        activitypub: activitypub_state.clone(),
+        render_hooks: load_render_hooks(&config),
     };
     let audience_state = AudienceState {
         namespace_store: namespace_store.clone(),
         token_signing_key: config.token_signing_key.clone(),
         blob_store,
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        audit_store: audit_store.clone(),
+    };
+    let member_state = MemberState {
+        namespace_store: namespace_store.clone(),
+        member_store,
+        email_service,
+        app_base_url: config.app_base_url.clone(),
+    };
+    let audit_state = AuditState {
+        namespace_store: namespace_store.clone(),
+        audit_store,
     };
     let ns_session_state = NsSessionState {
         namespace_store,
@@ -164,14 +392,30 @@ fn build_e2e_router(
     let api = Router::new()
         .route("/health", get(|| async { "OK" }))
         .nest("/auth", auth_routes(auth_state))
+        .nest("/auth", account_routes(account_state))
+        .nest("/auth", account_audit_routes(audit_state.clone()))
         .nest("/namespaces", namespace_routes(namespace_state))
+        .nest("/namespaces", archive_routes(archive_state))
         .nest("/namespaces/{ns_id}", object_routes(object_state.clone()))
         .nest("/namespaces/{ns_id}", audience_routes(audience_state))
+        .nest("/namespaces/{ns_id}", member_routes(member_state.clone()))
+        .nest("/namespaces/{ns_id}", webhook_routes(webhook_state))
+        .nest("/namespaces/{ns_id}", webmention_routes(webmention_state))
+        .nest("/namespaces/{ns_id}", comment_routes(comment_state.clone()))
+        .nest(
+            "/namespaces/{ns_id}",
+            activitypub_routes(activitypub_state.clone()),
+        )
+        .nest("/namespaces/{ns_id}", namespace_audit_routes(audit_state))
+        .merge(membership_routes(member_state))
         .merge(public_object_routes(object_state.clone()))
+        .merge(public_comment_routes(comment_state))
         .nest("/usage", usage_routes(object_state))
         .nest("/sessions", ns_session_routes(ns_session_state));
 
-    let router = Router::new().nest("/api", api);
+    let router = Router::new()
+        .merge(webfinger_routes(activitypub_state))
+        .nest("/api", api);
     (router, axum::Extension(auth_extractor))
 }
 
@@ -209,11 +453,25 @@ impl TestServer {
     pub async fn start() -> Self {
         let config = Arc::new(test_config());
 
-        let conn = Connection::open_in_memory().expect("open :memory: sqlite");
-        init_database(&conn).expect("init schema");
-        let repo = Arc::new(AuthRepo::new(conn));
+        let repo = in_memory_repo();
+        Self::serve(config, E2eStores::sqlite(&repo), repo).await
+    }
 
-        let (router, auth_ext) = build_e2e_router(config.clone(), repo);
+    /// Like [`start`](Self::start), but with every port-trait store backed by
+    /// the Postgres database at `database_url`. Migrations are applied on
+    /// connect; callers own isolation (e.g. a fresh schema per test selected
+    /// with `options=-c search_path=...` in the URL). Passkeys still use an
+    /// in-memory SQLite repo.
+    pub async fn start_postgres(database_url: &str) -> Self {
+        let config = Arc::new(test_config());
+        let pool = crate::postgres::connect(database_url)
+            .await
+            .unwrap_or_else(|e| panic!("connect to Postgres: {e}"));
+        Self::serve(config, E2eStores::postgres(&pool), in_memory_repo()).await
+    }
+
+    async fn serve(config: Arc<Config>, stores: E2eStores, passkey_repo: Arc<AuthRepo>) -> Self {
+        let (router, auth_ext) = build_e2e_router(config.clone(), stores, passkey_repo);
         let app = router.layer(auth_ext);
 
         let listener = TcpListener::bind("127.0.0.1:0")
@@ -321,6 +579,12 @@ impl TestServer {
     }
 }
 
+fn in_memory_repo() -> Arc<AuthRepo> {
+    let conn = Connection::open_in_memory().expect("open :memory: sqlite");
+    init_database(&conn).expect("init schema");
+    Arc::new(AuthRepo::new(conn))
+}
+
 impl Drop for TestServer {
     fn drop(&mut self) {
         self.shutdown.cancel();
@@ -0,0 +1,165 @@
+//! Runs the shared [`diaryx_server::contract`] test suite against
+//! `diaryx_selfhosted` with every port-trait store on Postgres — the same
+//! scenarios as `tests/contract.rs`, so any SQLite/Postgres divergence shows
+//! up as a failure here.
+//!
+//! Needs a Postgres server. Point `DIARYX_TEST_POSTGRES_URL` at a throwaway
+//! database (URL form, e.g. `postgres://postgres@localhost/diaryx_test`);
+//! without it every test returns early:
+//!
+//!     DIARYX_TEST_POSTGRES_URL=postgres://postgres@localhost/diaryx_test \
+//!         cargo test -p diaryx_selfhosted --test contract_postgres
+//!
+//! Each test migrates into its own `diaryx_contract_*` schema, so tests run
+//! in parallel without sharing rows. Schemas are left behind afterwards.
+
+use diaryx_selfhosted::testing::TestServer;
+use diaryx_server::contract::{self, http::ReqwestDispatcher};
+
+const DATABASE_URL_ENV: &str = "DIARYX_TEST_POSTGRES_URL";
+
+/// Start a server on a fresh schema, or `None` when no database is configured.
+async fn fresh_dispatcher() -> Option<(TestServer, ReqwestDispatcher)> {
+    let Ok(base_url) = std::env::var(DATABASE_URL_ENV) else {
+        eprintln!("{DATABASE_URL_ENV} not set; skipping Postgres contract test");
+        return None;
+    };
+
+    let schema = format!("diaryx_contract_{}", uuid::Uuid::new_v4().simple());
+    let (client, connection) = tokio_postgres::connect(&base_url, tokio_postgres::NoTls)
+        .await
+        .unwrap_or_else(|e| panic!("connect to {DATABASE_URL_ENV}: {e}"));
+    tokio::spawn(connection);
+    client
+        .batch_execute(&format!("CREATE SCHEMA {schema}"))
+        .await
+        .expect("create test schema");
+
+    let separator = if base_url.contains('?') { '&' } else { '?' };
+    let url = format!("{base_url}{separator}options=-c%20search_path%3D{schema}");
+    let server = TestServer::start_postgres(&url).await;
+    let dispatcher = ReqwestDispatcher::new(server.base_url());
+    Some((server, dispatcher))
+}
+
+// -- Health + auth -----------------------------------------------------------
+
+#[tokio::test]
+async fn contract_health_endpoint() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_health_endpoint_returns_200_ok(&d).await;
+}
+
+#[tokio::test]
+async fn contract_magic_link_dev_mode() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_magic_link_dev_mode_returns_dev_credentials(&d).await;
+}
+
+#[tokio::test]
+async fn contract_magic_link_rejects_invalid_email() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_magic_link_rejects_invalid_email(&d).await;
+}
+
+// -- Auth round-trip ---------------------------------------------------------
+
+#[tokio::test]
+async fn contract_magic_link_verify_returns_session() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_magic_link_verify_returns_session(&d).await;
+}
+
+#[tokio::test]
+async fn contract_me_without_auth_returns_401() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_me_without_auth_returns_401(&d).await;
+}
+
+#[tokio::test]
+async fn contract_me_with_invalid_token_returns_401() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_me_with_invalid_token_returns_401(&d).await;
+}
+
+#[tokio::test]
+async fn contract_delete_current_device_returns_actionable_error() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_delete_current_device_returns_actionable_error(&d).await;
+}
+
+// -- Namespace lifecycle -----------------------------------------------------
+
+#[tokio::test]
+async fn contract_namespace_create_list_get_lifecycle() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_namespace_create_list_get_lifecycle(&d).await;
+}
+
+#[tokio::test]
+async fn contract_namespace_access_forbidden_for_other_users() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_namespace_access_forbidden_for_other_users(&d).await;
+}
+
+// -- Object CRUD + URL fuzz --------------------------------------------------
+
+#[tokio::test]
+async fn contract_object_put_get_delete_roundtrip() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_object_put_get_delete_roundtrip(&d).await;
+}
+
+#[tokio::test]
+async fn contract_object_list_returns_uploaded_keys() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_object_list_returns_uploaded_keys(&d).await;
+}
+
+#[tokio::test]
+async fn contract_url_corpus_keys_roundtrip_through_http() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_url_corpus_keys_roundtrip_through_http(&d).await;
+}
+
+// -- Batch -------------------------------------------------------------------
+
+#[tokio::test]
+async fn contract_batch_objects_json_returns_all_keys() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_batch_objects_json_returns_all_keys(&d).await;
+}
+
+#[tokio::test]
+async fn contract_batch_objects_multipart_returns_all_keys() {
+    let Some((_s, d)) = fresh_dispatcher().await else {
+        return;
+    };
+    contract::test_batch_objects_multipart_returns_all_keys(&d).await;
+}
@@ -0,0 +1,40 @@
+;; Test fixture for render hooks: a guest written directly in WAT so the
+;; build test doesn't need a wasm toolchain.
+;;
+;; `manifest` declares the `render_html` capability and an inline `$...$`
+;; editor extension, like the math plugin. `render_html` ignores its input
+;; and renders every occurrence as the MathML for `x`.
+(module
+  (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+  (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+  (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+
+  (memory (export "memory") 1)
+
+  ;; manifest
+  (data (i32.const 16) "{\"id\":\"test.render-math\",\"name\":\"Render Math\",\"version\":\"0.1.0\",\"description\":\"Renders inline $x$ math as MathML\",\"capabilities\":[\"editor_extension\",\"render_html\"],\"ui\":[{\"slot\":\"EditorExtension\",\"extension_id\":\"mathInline\",\"node_type\":\"InlineAtom\",\"markdown\":{\"level\":\"Inline\",\"open\":\"$\",\"close\":\"$\"},\"render_export\":null,\"edit_mode\":null,\"css\":null}]}")
+  ;; render_html output
+  (data (i32.const 400) "{\"html\":\"<math><mi>x</mi></math>\"}")
+
+  ;; Set the call's output to `len` bytes at `src` in this module's memory.
+  (func $output (param $src i32) (param $len i32)
+    (local $block i64)
+    (local $i i32)
+    (local.set $block (call $alloc (i64.extend_i32_u (local.get $len))))
+    (block $done
+      (loop $next
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (call $store_u8
+          (i64.add (local.get $block) (i64.extend_i32_u (local.get $i)))
+          (i32.load8_u (i32.add (local.get $src) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next)))
+    (call $output_set (local.get $block) (i64.extend_i32_u (local.get $len))))
+
+  (func (export "manifest") (result i32)
+    (call $output (i32.const 16) (i32.const 353))
+    (i32.const 0))
+
+  (func (export "render_html") (result i32)
+    (call $output (i32.const 400) (i32.const 34))
+    (i32.const 0)))
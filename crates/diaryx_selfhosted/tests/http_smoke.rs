@@ -11,7 +11,10 @@ use axum::body::Body;
 use axum::http::{Method, Request, StatusCode, header};
 use serde_json::json;
 
-use support::{TestApp, build_test_router, read_body, read_json, read_status_and_json};
+use support::{
+    TEST_ADMIN_SECRET, TestApp, build_test_router, build_test_router_with_config, read_body,
+    read_json, read_status_and_json, test_config,
+};
 
 async fn authed_put(
     app: &TestApp,
@@ -30,6 +33,25 @@ async fn authed_put(
     app.request(builder.body(Body::from(body)).unwrap()).await
 }
 
+async fn authed_json(
+    app: &TestApp,
+    token: &str,
+    method: Method,
+    path: &str,
+    body: serde_json::Value,
+) -> axum::http::Response<Body> {
+    app.request(
+        Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap(),
+    )
+    .await
+}
+
 async fn sign_in(app: &TestApp, email: &str) -> String {
     let response = app
         .post_json("/api/auth/magic-link", &json!({ "email": email }))
@@ -191,6 +213,87 @@ async fn server_build_renders_html_from_sources() {
     );
 }
 
+/// Render plugins: with `RENDER_PLUGINS_DIR` holding a plugin that declares
+/// `render_html` for inline `$...$`, a build expands the math in a published
+/// source into the plugin's MathML. The plugin is a WAT fixture standing in
+/// for `diaryx_math_extism`, loaded through `diaryx_extism` like any other.
+#[tokio::test]
+async fn server_build_expands_plugin_syntax_with_render_plugins() {
+    let plugins_dir = tempfile::tempdir().expect("plugins dir");
+    let plugin_dir = plugins_dir.path().join("test.render-math");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+    std::fs::copy(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/render_math.wat"
+        ),
+        plugin_dir.join("plugin.wasm"),
+    )
+    .unwrap();
+
+    let mut config = test_config();
+    config.render_plugins_dir = Some(plugins_dir.path().to_path_buf());
+    config.render_plugins_allow_unsigned = true;
+    let app = build_test_router_with_config(config);
+    let token = sign_in(&app, "math@example.com").await;
+
+    let resp = authed_json(&app, &token, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = authed_json(
+        &app,
+        &token,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/public"),
+        json!({ "gates": [] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let ark = "bcdfgm";
+    let resp = authed_put(
+        &app,
+        &token,
+        &format!("/api/namespaces/{ns}/objects/public/Math.md"),
+        &[
+            ("x-audience", "public"),
+            ("content-type", "text/markdown"),
+            ("x-diaryx-file-ark", ark),
+            ("x-diaryx-source-key", "public/Math.md"),
+            ("x-diaryx-object-key", "public/index.html"),
+            ("x-diaryx-is-index", "true"),
+        ],
+        "---\ntitle: Math\nid: bcdfgm\n---\nLet $x$ be free.\n",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = authed_json(
+        &app,
+        &token,
+        Method::POST,
+        &format!("/api/namespaces/{ns}/build"),
+        json!({}),
+    )
+    .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "build: {body}");
+    assert_eq!(body["pages_rendered"], 1, "build summary: {body}");
+
+    let resp = app.get(&format!("/ark/{ns}/{ark}")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = String::from_utf8_lossy(&read_body(resp).await).into_owned();
+    assert!(
+        html.contains(
+            "<span class=\"diaryx-render-hook\" data-extension=\"mathInline\"><math><mi>x</mi></math></span>"
+        ),
+        "math not rendered: {html}"
+    );
+    assert!(!html.contains("$x$"), "raw math left in page: {html}");
+}
+
 /// End-to-end ARK Layer 2: publish a source sibling + HTML rendition, then
 /// resolve the ARK with each inflection.
 #[tokio::test]
@@ -277,28 +380,58 @@ async fn ark_resolution_serves_html_content_and_info() {
     let body = read_body(resp).await;
     assert!(String::from_utf8_lossy(&body).contains("Body text"));
 
-    // ?info → frontmatter JSON.
-    let resp = app.get(&format!("/ark/{ns}/{file_ark}?info")).await;
+    // ?json → raw frontmatter plus body.
+    let resp = app.get(&format!("/ark/{ns}/{file_ark}?json")).await;
     let (status, body) = read_status_and_json(resp).await;
-    assert_eq!(status, StatusCode::OK, "info: {body}");
-    assert_eq!(body["title"], "Hello");
+    assert_eq!(status, StatusCode::OK, "json: {body}");
+    assert_eq!(body["frontmatter"]["title"], "Hello");
 
     // Sensitive internal frontmatter keys must never surface via resolution.
     // The publisher strips these before uploading the source sibling; assert
     // that contract holds end-to-end so a regression here can't leak publish
     // config (audiences, plugin settings) to anyone who can resolve the ARK.
+    let fm = &body["frontmatter"];
+    assert!(
+        fm.get("plugins").is_none(),
+        "?json leaked `plugins`: {body}"
+    );
     assert!(
-        body.get("plugins").is_none(),
-        "?info leaked `plugins`: {body}"
+        fm.get("audiences").is_none(),
+        "?json leaked `audiences`: {body}"
     );
     assert!(
-        body.get("audiences").is_none(),
-        "?info leaked `audiences`: {body}"
+        fm.get("audiences_migrated").is_none(),
+        "?json leaked `audiences_migrated`: {body}"
     );
+
+    // ?info → ERC kernel text; `??` is the same record.
+    let resp = app.get(&format!("/ark/{ns}/{file_ark}?info")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let erc = String::from_utf8(read_body(resp).await).unwrap();
+    assert!(erc.starts_with("erc:\n"), "info: {erc}");
+    assert!(erc.contains("what: Hello\n"), "info: {erc}");
     assert!(
-        body.get("audiences_migrated").is_none(),
-        "?info leaked `audiences_migrated`: {body}"
+        erc.contains(&format!("where: ark:{naan}/{ns}/{file_ark}\n")),
+        "info: {erc}"
     );
+    let resp = app.get(&format!("/ark/{ns}/{file_ark}??")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(String::from_utf8(read_body(resp).await).unwrap(), erc);
+
+    // ?info with a JSON Accept header → JSON-LD.
+    let resp = app
+        .request(
+            Request::builder()
+                .uri(format!("/ark/{ns}/{file_ark}?info"))
+                .header(header::ACCEPT, "application/ld+json")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "info json-ld: {body}");
+    assert_eq!(body["name"], "Hello");
+    assert_eq!(body["@id"], format!("ark:{naan}/{ns}/{file_ark}"));
 
     // ?meta=title → single field.
     let resp = app.get(&format!("/ark/{ns}/{file_ark}?meta=title")).await;
@@ -307,6 +440,1147 @@ async fn ark_resolution_serves_html_content_and_info() {
     assert_eq!(body, json!("Hello"));
 }
 
+/// Republishing a source retains the earlier revision as an addressable
+/// `.<FILE>` version variant.
+#[tokio::test]
+async fn ark_version_variants_survive_republish() {
+    let app = build_test_router();
+    let token = sign_in(&app, "versions@example.com").await;
+
+    let resp = app
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/namespaces")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&json!({})).unwrap()))
+                .unwrap(),
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = app
+        .request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/api/namespaces/{ns}/audiences/public"))
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&json!({ "gates": [] })).unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let file_ark = "bcdfgr";
+    for source_md in [
+        "---\ntitle: First\n---\n\nFirst draft\n",
+        "---\ntitle: Second\n---\n\nSecond draft\n",
+    ] {
+        let resp = authed_put(
+            &app,
+            &token,
+            &format!("/api/namespaces/{ns}/objects/public/note.md"),
+            &[
+                ("x-audience", "public"),
+                ("x-diaryx-file-ark", file_ark),
+                ("x-diaryx-source-key", "public/note.md"),
+                ("x-diaryx-object-key", "public/note.html"),
+                ("content-type", "text/markdown"),
+            ],
+            source_md,
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // ?versions lists both retained revisions.
+    let resp = app.get(&format!("/ark/{ns}/{file_ark}?versions")).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "versions: {body}");
+    let versions = body["versions"].as_array().expect("versions array");
+    assert_eq!(versions.len(), 2, "versions: {body}");
+
+    // Each short hash resolves to its own revision, even after the source
+    // key has been overwritten by the later publish.
+    let mut drafts = Vec::new();
+    for v in versions {
+        let hash = v["version"].as_str().expect("version hash");
+        let resp = app
+            .get(&format!("/ark/{ns}/{file_ark}.{}", &hash[..7]))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        drafts.push(String::from_utf8_lossy(&read_body(resp).await).into_owned());
+    }
+    assert!(drafts.iter().any(|d| d.contains("First draft")));
+    assert!(drafts.iter().any(|d| d.contains("Second draft")));
+
+    // A short prefix and the full hash name the same version, so ?info
+    // gives both the one canonical ARK, spelled with the full hash.
+    let hash = versions[0]["version"].as_str().expect("version hash");
+    let naan = diaryx_server::use_cases::ark::ARK_NAAN;
+    for spelling in [&hash[..7], hash] {
+        let resp = app
+            .get(&format!("/ark/{ns}/{file_ark}.{spelling}?info"))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let erc = String::from_utf8_lossy(&read_body(resp).await).into_owned();
+        assert!(
+            erc.contains(&format!("where: ark:{naan}/{ns}/{file_ark}.{hash}\n")),
+            "info for .{spelling}: {erc}"
+        );
+    }
+
+    // An unknown version is a 404, not the current revision.
+    let resp = app.get(&format!("/ark/{ns}/{file_ark}.0000000")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+/// A namespace exported from one server imports onto another under the same
+/// id, keeping its audiences, objects and ARKs.
+#[tokio::test]
+async fn namespace_export_imports_on_another_server() {
+    let source = build_test_router();
+    let token = sign_in(&source, "export@example.com").await;
+
+    let resp = source
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/namespaces")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::to_vec(&json!({})).unwrap()))
+                .unwrap(),
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = source
+        .request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/api/namespaces/{ns}/audiences/public"))
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&json!({ "gates": [] })).unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = authed_put(
+        &source,
+        &token,
+        &format!("/api/namespaces/{ns}/objects/public/note.md"),
+        &[
+            ("x-audience", "public"),
+            ("x-diaryx-file-ark", "bcdfgr"),
+            ("x-diaryx-source-key", "public/note.md"),
+            ("x-diaryx-object-key", "public/note.html"),
+            ("content-type", "text/markdown"),
+        ],
+        "---\ntitle: Moved\n---\n\nStill here\n",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = source
+        .request_with_bearer(Method::GET, &format!("/api/namespaces/{ns}/export"), &token)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let archive = read_body(resp).await;
+    assert!(String::from_utf8_lossy(&archive).contains(r#""type":"end""#));
+
+    // Import onto a fresh server as a different account.
+    let target = build_test_router();
+    let target_token = sign_in(&target, "import@example.com").await;
+    let import = |token: String, archive: Vec<u8>| {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/api/namespaces/import")
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(header::CONTENT_TYPE, "application/x-ndjson")
+            .body(Body::from(archive))
+            .unwrap()
+    };
+    let resp = target
+        .request(import(target_token.clone(), archive.clone()))
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "import: {body}");
+    assert_eq!(body["namespace_id"], ns.as_str());
+    assert_eq!(body["audiences"], 1);
+
+    let resp = target
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/namespaces/{ns}/objects/public/note.md"),
+            &target_token,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(String::from_utf8_lossy(&read_body(resp).await).contains("Still here"));
+
+    // ARKs keep resolving because the namespace id travelled with it.
+    let resp = target.get(&format!("/ark/{ns}/bcdfgr?content")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Importing the same archive again conflicts on the namespace id.
+    let resp = target.request(import(target_token, archive)).await;
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+/// A publish-scoped access token restricted to one namespace can upload into
+/// it, but can't touch other namespaces, manage the namespace, or reach the
+/// account API — and is refused outside the `Authorization` header.
+#[tokio::test]
+async fn scoped_access_token_only_reaches_its_namespace() {
+    let app = build_test_router();
+    let session = sign_in(&app, "ci@example.com").await;
+
+    let mut namespaces = Vec::new();
+    for _ in 0..2 {
+        let resp = app
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/namespaces")
+                    .header(header::AUTHORIZATION, format!("Bearer {session}"))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await;
+        let (status, body) = read_status_and_json(resp).await;
+        assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+        namespaces.push(body["id"].as_str().expect("namespace id").to_string());
+    }
+    let (ns, other) = (&namespaces[0], &namespaces[1]);
+
+    let resp = app
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/auth/tokens")
+                .header(header::AUTHORIZATION, format!("Bearer {session}"))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&json!({
+                        "name": "ci",
+                        "scopes": ["publish"],
+                        "namespace_ids": [ns],
+                        "expires_in_days": 30,
+                    }))
+                    .unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create token: {body}");
+    let pat = body["token"].as_str().expect("token secret").to_string();
+    let token_id = body["id"].as_str().expect("token id").to_string();
+    assert!(pat.starts_with("dxp_"));
+
+    let resp = authed_put(
+        &app,
+        &pat,
+        &format!("/api/namespaces/{ns}/objects/note.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = authed_put(
+        &app,
+        &pat,
+        &format!("/api/namespaces/{other}/objects/note.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    for (method, path) in [
+        (Method::DELETE, format!("/api/namespaces/{ns}")),
+        (Method::GET, "/api/auth/me".to_string()),
+        (Method::GET, "/api/auth/tokens".to_string()),
+    ] {
+        let resp = app.request_with_bearer(method, &path, &pat).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN, "{path}");
+    }
+
+    let resp = app
+        .request(
+            Request::builder()
+                .uri(format!("/api/namespaces/{ns}/objects"))
+                .header(header::COOKIE, format!("diaryx_session={pat}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/auth/tokens", &session)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "list tokens: {body}");
+    assert_eq!(body[0]["id"], token_id.as_str());
+    assert!(body[0]["last_used_at"].is_i64());
+    assert!(body[0].get("token").is_none());
+
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/auth/tokens/{token_id}"),
+            &session,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let resp = app
+        .request_with_bearer(Method::GET, &format!("/api/namespaces/{ns}/objects"), &pat)
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+/// Account export streams the caller's data; deletion only happens once the
+/// emailed (here: returned, dev mode) confirmation link is used.
+#[tokio::test]
+async fn account_export_and_confirmed_deletion() {
+    let app = build_test_router();
+    let session = sign_in(&app, "leaving@example.com").await;
+
+    let resp = authed_json(&app, &session, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+    let resp = authed_put(
+        &app,
+        &session,
+        &format!("/api/namespaces/{ns}/objects/note.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/auth/account/export", &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = read_body(resp).await;
+    let records: Vec<serde_json::Value> = body
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).expect("ndjson record"))
+        .collect();
+    assert_eq!(records[0]["type"], "header");
+    assert_eq!(records[1]["user"]["email"], "leaving@example.com");
+    assert!(records.iter().any(|r| r["type"] == "namespace_archive"
+        && r["namespace_id"] == ns.as_str()
+        && r["record"]["key"] == "note.md"));
+    assert_eq!(records.last().unwrap()["type"], "end");
+    assert_eq!(records.last().unwrap()["objects"], 1);
+
+    let resp = app
+        .request_with_bearer(Method::DELETE, "/api/auth/account", &session)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "request deletion: {body}");
+    assert_eq!(body["emailed"], false);
+    let confirm_url = body["confirm_url"].as_str().expect("dev-mode confirm_url");
+    let token = confirm_url
+        .split("delete_account=")
+        .nth(1)
+        .expect("confirm_url should carry the token");
+
+    // Nothing is deleted until confirmation.
+    let resp = app
+        .request_with_bearer(Method::GET, &format!("/api/namespaces/{ns}"), &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = authed_json(
+        &app,
+        &session,
+        Method::POST,
+        "/api/auth/account/confirm-deletion",
+        json!({ "token": "0.deadbeef" }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = authed_json(
+        &app,
+        &session,
+        Method::POST,
+        "/api/auth/account/confirm-deletion",
+        json!({ "token": token }),
+    )
+    .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "confirm deletion: {body}");
+    assert_eq!(body["namespaces"], 1);
+    assert!(body["blobs_deleted"].as_u64().unwrap() >= 1);
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/auth/me", &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn invited_editor_can_publish_but_not_manage_audiences() {
+    let app = build_test_router();
+    let owner = sign_in(&app, "parent1@example.com").await;
+    let editor = sign_in(&app, "parent2@example.com").await;
+
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    // Before the invite the second parent is a stranger.
+    let resp = authed_put(
+        &app,
+        &editor,
+        &format!("/api/namespaces/{ns}/objects/kids.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::POST,
+        &format!("/api/namespaces/{ns}/invites"),
+        json!({ "email": "Parent2@example.com", "role": "editor" }),
+    )
+    .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "invite: {body}");
+    assert_eq!(body["emailed"], false);
+    let accept_url = body["accept_url"]
+        .as_str()
+        .expect("accept_url is returned when email is not configured");
+    let invite_token = accept_url.split("invite=").nth(1).expect("invite token");
+
+    // Only the invited address can accept.
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::POST,
+        "/api/invites/accept",
+        json!({ "token": invite_token }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = authed_json(
+        &app,
+        &editor,
+        Method::POST,
+        "/api/invites/accept",
+        json!({ "token": invite_token }),
+    )
+    .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "accept: {body}");
+    assert_eq!(body["role"], "editor");
+
+    let resp = authed_put(
+        &app,
+        &editor,
+        &format!("/api/namespaces/{ns}/objects/kids.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = authed_json(
+        &app,
+        &editor,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/family"),
+        json!({ "gates": [] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/namespaces/{ns}/members"),
+            &editor,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "members: {body}");
+    assert_eq!(body[0]["role"], "owner");
+    assert_eq!(body[1]["role"], "editor");
+    assert_eq!(body[1]["email"], "parent2@example.com");
+    let editor_id = body[1]["user_id"].as_str().expect("member id").to_string();
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/memberships", &editor)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "memberships: {body}");
+    assert_eq!(body[0]["namespace_id"], ns.as_str());
+
+    // Downgraded to viewer: reads still work, writes don't.
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PATCH,
+        &format!("/api/namespaces/{ns}/members/{editor_id}"),
+        json!({ "role": "viewer" }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/namespaces/{ns}/objects/kids.md"),
+            &editor,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/namespaces/{ns}/objects/kids.md"),
+            &editor,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+/// An owner registers a webhook against a local receiver; deleting an object
+/// delivers a signed `object_deleted` event and the delivery log records it.
+#[tokio::test]
+async fn object_deletion_delivers_a_signed_webhook() {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(axum::http::HeaderMap, Vec<u8>)>();
+    let receiver = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(
+            move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                let tx = tx.clone();
+                async move {
+                    let _ = tx.send((headers, body.to_vec()));
+                    StatusCode::NO_CONTENT
+                }
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let receiver_addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+    let app = build_test_router();
+    let owner = sign_in(&app, "hooks@example.com").await;
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::POST,
+        &format!("/api/namespaces/{ns}/webhooks"),
+        json!({ "url": format!("http://{receiver_addr}/hook"), "events": ["object_deleted"] }),
+    )
+    .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create webhook: {body}");
+    let webhook_id = body["id"].as_str().expect("webhook id").to_string();
+    let secret = body["secret"].as_str().expect("secret").to_string();
+
+    let resp = authed_put(
+        &app,
+        &owner,
+        &format!("/api/namespaces/{ns}/objects/notes.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/namespaces/{ns}/objects/notes.md"),
+            &owner,
+        )
+        .await;
+    assert!(resp.status().is_success());
+
+    let (headers, body) = tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv())
+        .await
+        .expect("webhook delivered in time")
+        .expect("receiver open");
+    let header = |name: &str| headers[name].to_str().unwrap().to_string();
+    assert_eq!(header("x-diaryx-event"), "object_deleted");
+    assert_eq!(header("x-diaryx-namespace"), ns);
+    let timestamp: u64 = header("x-diaryx-timestamp").parse().unwrap();
+    assert!(diaryx_server::proxy::verify_proxy_signature(
+        secret.as_bytes(),
+        timestamp,
+        &ns,
+        &body,
+        &header("x-diaryx-signature"),
+    ));
+    let event: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(event["data"]["key"], "notes.md");
+
+    // The attempt is recorded once the receiver has answered.
+    let mut log = serde_json::Value::Null;
+    for _ in 0..50 {
+        let resp = app
+            .request_with_bearer(
+                Method::GET,
+                &format!("/api/namespaces/{ns}/webhooks/deliveries?webhook_id={webhook_id}"),
+                &owner,
+            )
+            .await;
+        let (status, body) = read_status_and_json(resp).await;
+        assert_eq!(status, StatusCode::OK, "deliveries: {body}");
+        log = body;
+        if log[0]["status"] == "succeeded" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(log[0]["status"], "succeeded", "log: {log}");
+    assert_eq!(log[0]["attempts"], 1);
+    assert_eq!(log[0]["response_status"], 204);
+}
+
+/// The operator admin API: listing, tier override, session revocation and a
+/// namespace takedown that purges the owner's blobs.
+#[tokio::test]
+async fn admin_api_manages_users_and_takes_down_namespaces() {
+    let app = build_test_router();
+    let session = sign_in(&app, "reported@example.com").await;
+
+    let resp = authed_json(&app, &session, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+    let resp = authed_put(
+        &app,
+        &session,
+        &format!("/api/namespaces/{ns}/objects/note.md"),
+        &[("content-type", "text/markdown")],
+        "hello",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // User sessions don't reach the admin API.
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/admin/health", &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/admin/health", TEST_ADMIN_SECRET)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "health: {body}");
+    assert_eq!(body["users"], 1);
+    assert_eq!(body["namespaces"], 1);
+    assert_eq!(body["blob_store"]["reachable"], true);
+
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            "/api/admin/users?q=REPORTED",
+            TEST_ADMIN_SECRET,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "list users: {body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["namespace_count"], 1);
+    let user_id = body[0]["id"].as_str().unwrap().to_string();
+
+    let resp = authed_json(
+        &app,
+        TEST_ADMIN_SECRET,
+        Method::PUT,
+        &format!("/api/admin/users/{user_id}/tier"),
+        json!({ "tier": "gold" }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = authed_json(
+        &app,
+        TEST_ADMIN_SECRET,
+        Method::PUT,
+        &format!("/api/admin/users/{user_id}/tier"),
+        json!({ "tier": "plus" }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/admin/users/{user_id}"),
+            TEST_ADMIN_SECRET,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "user detail: {body}");
+    assert_eq!(body["tier"], "plus");
+    assert_eq!(body["devices"].as_array().unwrap().len(), 1);
+    assert_eq!(body["namespaces"][0]["id"], ns.as_str());
+    assert_eq!(body["namespaces"][0]["stored_bytes"], 5);
+
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/admin/namespaces/{ns}?reason=abuse"),
+            TEST_ADMIN_SECRET,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "takedown: {body}");
+    assert!(body["blobs_deleted"].as_u64().unwrap() >= 1);
+    let resp = app
+        .request_with_bearer(Method::GET, &format!("/api/namespaces/{ns}"), &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/admin/users/{user_id}/sessions"),
+            TEST_ADMIN_SECRET,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "revoke sessions: {body}");
+    assert_eq!(body["revoked"], 1);
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/auth/me", &session)
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn audit_log_records_unlocks_and_is_owner_scoped() {
+    let app = build_test_router();
+    let owner = sign_in(&app, "audited@example.com").await;
+
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/family"),
+        json!({ "gates": [{ "kind": "password", "password": "hunter2" }] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    for (password, expected) in [
+        ("wrong", StatusCode::FORBIDDEN),
+        ("hunter2", StatusCode::OK),
+    ] {
+        let resp = app
+            .post_json(
+                &format!("/api/namespaces/{ns}/audiences/family/unlock"),
+                &json!({ "password": password }),
+            )
+            .await;
+        assert_eq!(resp.status(), expected);
+    }
+
+    let resp = app
+        .request_with_bearer(Method::GET, &format!("/api/namespaces/{ns}/audit"), &owner)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "namespace audit: {body}");
+    let actions: Vec<&str> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            "audience_unlocked",
+            "audience_unlock_failed",
+            "audience_updated"
+        ]
+    );
+    assert_eq!(body[0]["target"], "family");
+    assert_eq!(body[0]["details"]["password_version"], 1);
+    assert!(body[0]["actor_user_id"].is_null());
+
+    let resp = app
+        .request_with_bearer(Method::GET, "/api/auth/audit?limit=1", &owner)
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "account audit: {body}");
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["action"], "sign_in");
+    assert_eq!(body[0]["details"]["device_name"], "SmokeDevice");
+
+    // Another account can't read this namespace's log.
+    let stranger = sign_in(&app, "stranger@example.com").await;
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/namespaces/{ns}/audit"),
+            &stranger,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn webmentions_are_verified_and_moderated_by_the_owner() {
+    let app = build_test_router();
+    let owner = sign_in(&app, "mentioned@example.com").await;
+
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/public"),
+        json!({ "gates": [] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = authed_put(
+        &app,
+        &owner,
+        &format!("/api/namespaces/{ns}/objects/public/post.md"),
+        &[
+            ("x-audience", "public"),
+            ("content-type", "text/markdown"),
+            ("x-diaryx-file-ark", "bcdfgk"),
+            ("x-diaryx-source-key", "public/post.md"),
+            ("x-diaryx-object-key", "public/post.html"),
+        ],
+        "---\ntitle: Post\n---\nHello.\n",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let encode = |value: &str| -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{b:02X}"),
+            })
+            .collect()
+    };
+    let send = |source: String, target: String| {
+        let app = &app;
+        let ns = &ns;
+        let body = format!("source={}&target={}", encode(&source), encode(&target));
+        async move {
+            app.request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/namespaces/{ns}/webmention"))
+                    .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+        }
+    };
+
+    let target = format!("http://localhost:5174/sites/{ns}/post.html");
+    let source = format!("https://reply.example/reply?links={target}");
+    let resp = send(
+        source.clone(),
+        format!("http://localhost:5174/sites/{ns}/nope.html"),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = send("http://127.0.0.1:8080/admin".to_string(), target.clone()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = send(source.clone(), target.clone()).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    // Verification runs on a spawned task.
+    let list = format!("/api/namespaces/{ns}/webmentions");
+    let mut mention = json!(null);
+    for _ in 0..100 {
+        let resp = app.request_with_bearer(Method::GET, &list, &owner).await;
+        let (status, body) = read_status_and_json(resp).await;
+        assert_eq!(status, StatusCode::OK, "list webmentions: {body}");
+        if body[0]["status"] == "pending" {
+            mention = body[0].clone();
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(mention["status"], "pending", "mention never verified");
+    assert_eq!(mention["object_key"], "public/post.html");
+    assert_eq!(mention["source_title"], "Reply");
+    let id = mention["id"].as_str().unwrap().to_string();
+
+    let stranger = sign_in(&app, "bystander@example.com").await;
+    let resp = app
+        .request_with_bearer(
+            Method::POST,
+            &format!("/api/namespaces/{ns}/webmentions/{id}/approve"),
+            &stranger,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .request_with_bearer(
+            Method::POST,
+            &format!("/api/namespaces/{ns}/webmentions/{id}/approve"),
+            &owner,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "approve: {body}");
+    assert_eq!(body["status"], "approved");
+
+    let resp = app
+        .request_with_bearer(Method::GET, &format!("{list}?status=approved"), &owner)
+        .await;
+    let (_, body) = read_status_and_json(resp).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/namespaces/{ns}/webmentions/{id}"),
+            &owner,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn audience_readers_comment_and_the_owner_moderates() {
+    let app = build_test_router();
+    let owner = sign_in(&app, "commented@example.com").await;
+
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/family"),
+        json!({ "gates": [{ "kind": "link" }] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = authed_put(
+        &app,
+        &owner,
+        &format!("/api/namespaces/{ns}/objects/family/letter.md"),
+        &[
+            ("x-audience", "family"),
+            ("content-type", "text/markdown"),
+            ("x-diaryx-file-ark", "bcdfgk"),
+            ("x-diaryx-source-key", "family/letter.md"),
+            ("x-diaryx-object-key", "family/letter.html"),
+        ],
+        "---\ntitle: Letter\n---\nDear all.\n",
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app
+        .request_with_bearer(
+            Method::GET,
+            &format!("/api/namespaces/{ns}/audiences/family/token"),
+            &owner,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "link token: {body}");
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let page = format!("/api/public/{ns}/comments/family/letter.html");
+    let comment = json!({ "author_name": "Aunt May", "body": "Lovely news!" });
+    let resp = app.post_json(&page, &comment).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let resp = app
+        .post_json(
+            &format!("/api/public/{ns}/comments/family/missing.html?audience_token={token}"),
+            &comment,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let page = format!("{page}?audience_token={token}");
+    let resp = app.post_json(&page, &comment).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "post comment: {body}");
+    assert_eq!(body["status"], "pending");
+    let id = body["id"].as_str().unwrap().to_string();
+
+    // Pending comments stay hidden from readers.
+    let (status, body) = read_status_and_json(app.get(&page).await).await;
+    assert_eq!(status, StatusCode::OK, "list comments: {body}");
+    assert_eq!(body.as_array().unwrap().len(), 0);
+
+    let stranger = sign_in(&app, "nosy@example.com").await;
+    let resp = app
+        .request_with_bearer(
+            Method::POST,
+            &format!("/api/namespaces/{ns}/comments/{id}/approve"),
+            &stranger,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = app
+        .request_with_bearer(
+            Method::POST,
+            &format!("/api/namespaces/{ns}/comments/{id}/approve"),
+            &owner,
+        )
+        .await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "approve: {body}");
+    assert_eq!(body["status"], "approved");
+    assert_eq!(body["source_key"], "family/letter.md");
+
+    let (_, body) = read_status_and_json(app.get(&page).await).await;
+    assert_eq!(body.as_array().unwrap().len(), 1);
+    assert_eq!(body[0]["author_name"], "Aunt May");
+    assert_eq!(body[0]["body"], "Lovely news!");
+
+    let resp = app
+        .request_with_bearer(
+            Method::DELETE,
+            &format!("/api/namespaces/{ns}/comments/{id}"),
+            &owner,
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn activitypub_actor_exists_only_while_an_audience_is_public() {
+    let app = build_test_router();
+    let owner = sign_in(&app, "fediverse@example.com").await;
+
+    let resp = authed_json(&app, &owner, Method::POST, "/api/namespaces", json!({})).await;
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::CREATED, "create namespace: {body}");
+    let ns = body["id"].as_str().expect("namespace id").to_string();
+    let webfinger = format!("/.well-known/webfinger?resource=acct:{ns}@localhost:5174");
+    let actor = format!("/api/namespaces/{ns}/actor");
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/members"),
+        json!({ "gates": [{ "kind": "link" }] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(app.get(&webfinger).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(app.get(&actor).await.status(), StatusCode::NOT_FOUND);
+
+    let resp = authed_json(
+        &app,
+        &owner,
+        Method::PUT,
+        &format!("/api/namespaces/{ns}/audiences/public"),
+        json!({ "gates": [] }),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app.get(&webfinger).await;
+    assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/jrd+json");
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "webfinger: {body}");
+    let actor_id = format!("http://localhost:5174/api/namespaces/{ns}/actor");
+    assert_eq!(body["links"][0]["href"], actor_id);
+
+    let resp = app.get(&actor).await;
+    assert_eq!(
+        resp.headers()[header::CONTENT_TYPE],
+        "application/activity+json"
+    );
+    let (status, body) = read_status_and_json(resp).await;
+    assert_eq!(status, StatusCode::OK, "actor: {body}");
+    assert_eq!(body["id"], actor_id);
+    assert_eq!(body["type"], "Person");
+    assert!(
+        body["publicKey"]["publicKeyPem"]
+            .as_str()
+            .unwrap()
+            .starts_with("-----BEGIN PUBLIC KEY-----")
+    );
+
+    let (status, body) = read_status_and_json(app.get(&format!("{actor}/outbox")).await).await;
+    assert_eq!(status, StatusCode::OK, "outbox: {body}");
+    assert_eq!(body["totalItems"], 0);
+    let (_, body) = read_status_and_json(app.get(&format!("{actor}/followers")).await).await;
+    assert_eq!(body["totalItems"], 0);
+
+    let follow = json!({
+        "type": "Follow",
+        "actor": "https://social.example/users/someone",
+        "object": actor_id,
+    });
+    let resp = app
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("{actor}/inbox"))
+                .header(header::CONTENT_TYPE, "application/activity+json")
+                .body(Body::from(follow.to_string()))
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
 #[tokio::test]
 async fn health_endpoint_returns_200_ok() {
     let app: TestApp = build_test_router();
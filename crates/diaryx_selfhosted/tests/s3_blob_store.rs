@@ -0,0 +1,588 @@
+//! Exercises [`S3BlobStore`] end-to-end against a small in-process S3
+//! stand-in bound on `127.0.0.1:0`. The stand-in speaks just enough of the
+//! S3 REST API (object CRUD, ranged GET, multipart, ListObjectsV2,
+//! ListMultipartUploads, DeleteObjects) to drive the real AWS SDK client, and records every
+//! request so the tests can check path-style addressing and SigV4 headers.
+//!
+//! It does not recompute signatures — that is the SDK's job — but it does
+//! reject requests whose SigV4 credential scope names the wrong access key.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, Method, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+};
+use diaryx_selfhosted::blob_store::{BlobStore, MultipartCompletedPart, S3BlobStore};
+use diaryx_selfhosted::config::S3Config;
+
+const BUCKET: &str = "diaryx-test";
+const ACCESS_KEY: &str = "test-access-key";
+const SECRET_KEY: &str = "test-secret-key";
+/// Small page size so `list_by_prefix` has to follow continuation tokens.
+const LIST_PAGE_SIZE: usize = 2;
+
+// ---------------------------------------------------------------------------
+// S3 stand-in
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct StoredObject {
+    body: Vec<u8>,
+    content_type: Option<String>,
+    metadata: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct FakeS3 {
+    objects: Mutex<BTreeMap<String, StoredObject>>,
+    uploads: Mutex<HashMap<String, BTreeMap<u32, Vec<u8>>>>,
+    /// `upload_id -> key` for every multipart upload ever initiated.
+    upload_keys: Mutex<HashMap<String, String>>,
+    next_upload: Mutex<u32>,
+    /// `METHOD path?query` of every authenticated request.
+    requests: Mutex<Vec<String>>,
+}
+
+impl FakeS3 {
+    fn object(&self, key: &str) -> Option<StoredObject> {
+        self.objects.lock().unwrap().get(key).cloned()
+    }
+}
+
+struct TestS3 {
+    fake: Arc<FakeS3>,
+    endpoint: String,
+    _server: tokio::task::JoinHandle<()>,
+}
+
+impl TestS3 {
+    async fn start() -> Self {
+        let fake = Arc::new(FakeS3::default());
+        let app = Router::new().fallback(handle).with_state(fake.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Self {
+            fake,
+            endpoint: format!("http://{addr}"),
+            _server: server,
+        }
+    }
+
+    async fn store(&self) -> S3BlobStore {
+        self.store_with_key(ACCESS_KEY).await
+    }
+
+    async fn store_with_key(&self, access_key_id: &str) -> S3BlobStore {
+        S3BlobStore::new(&S3Config {
+            endpoint: self.endpoint.clone(),
+            region: "us-east-1".to_string(),
+            bucket: BUCKET.to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: SECRET_KEY.to_string(),
+            prefix: "diaryx-sync".to_string(),
+            force_path_style: true,
+        })
+        .await
+        .unwrap()
+    }
+}
+
+fn s3_error(status: StatusCode, code: &str) -> Response {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/xml")],
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?><Error><Code>{code}</Code><Message>{code}</Message></Error>"),
+    )
+        .into_response()
+}
+
+fn xml(body: String) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/xml")],
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>{body}"),
+    )
+        .into_response()
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).unwrap()
+}
+
+/// Text of every `<tag>…</tag>` in `body`, in order.
+fn xml_values(body: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else { break };
+        values.push(rest[..end].to_string());
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+/// Accept only SigV4 requests scoped to our access key and the `s3` service.
+fn authorized(headers: &HeaderMap) -> bool {
+    let Some(auth) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+    else {
+        return false;
+    };
+    auth.starts_with(&format!("AWS4-HMAC-SHA256 Credential={ACCESS_KEY}/"))
+        && auth.contains("/us-east-1/s3/aws4_request")
+        && auth.contains("SignedHeaders=")
+        && auth.contains("Signature=")
+        && headers.contains_key("x-amz-date")
+        && headers.contains_key("x-amz-content-sha256")
+}
+
+async fn handle(
+    State(s3): State<Arc<FakeS3>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !authorized(&headers) {
+        return s3_error(StatusCode::FORBIDDEN, "InvalidAccessKeyId");
+    }
+    s3.requests
+        .lock()
+        .unwrap()
+        .push(format!("{method} {}", uri.path_and_query().unwrap()));
+
+    let query: HashMap<String, String> =
+        url::form_urlencoded::parse(uri.query().unwrap_or("").as_bytes())
+            .into_owned()
+            .collect();
+
+    // Path-style only: /{bucket} or /{bucket}/{key}.
+    let path = uri.path().trim_start_matches('/');
+    let (bucket, key) = match path.split_once('/') {
+        Some((bucket, key)) => (bucket, percent_decode(key)),
+        None => (path, String::new()),
+    };
+    if bucket != BUCKET {
+        return s3_error(StatusCode::NOT_FOUND, "NoSuchBucket");
+    }
+
+    if key.is_empty() {
+        return match method {
+            Method::GET if query.get("list-type").map(String::as_str) == Some("2") => {
+                list_objects(&s3, &query)
+            }
+            Method::GET if query.contains_key("uploads") => list_multipart_uploads(&s3, &query),
+            Method::POST if query.contains_key("delete") => {
+                delete_objects(&s3, &String::from_utf8_lossy(&body))
+            }
+            _ => s3_error(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed"),
+        };
+    }
+
+    match method {
+        Method::PUT => {
+            if let (Some(upload_id), Some(part)) = (query.get("uploadId"), query.get("partNumber"))
+            {
+                let mut uploads = s3.uploads.lock().unwrap();
+                let Some(parts) = uploads.get_mut(upload_id) else {
+                    return s3_error(StatusCode::NOT_FOUND, "NoSuchUpload");
+                };
+                let part_no: u32 = part.parse().unwrap();
+                parts.insert(part_no, body.to_vec());
+                return (
+                    StatusCode::OK,
+                    [(header::ETAG, format!("\"part-{part_no}\""))],
+                )
+                    .into_response();
+            }
+            let metadata = headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    name.as_str()
+                        .strip_prefix("x-amz-meta-")
+                        .map(|k| (k.to_string(), value.to_str().unwrap().to_string()))
+                })
+                .collect();
+            let content_type = headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            s3.objects.lock().unwrap().insert(
+                key,
+                StoredObject {
+                    body: body.to_vec(),
+                    content_type,
+                    metadata,
+                },
+            );
+            (StatusCode::OK, [(header::ETAG, "\"object\"")]).into_response()
+        }
+        Method::POST if query.contains_key("uploads") => {
+            let mut next = s3.next_upload.lock().unwrap();
+            *next += 1;
+            let upload_id = format!("upload-{}", *next);
+            s3.uploads
+                .lock()
+                .unwrap()
+                .insert(upload_id.clone(), BTreeMap::new());
+            s3.upload_keys
+                .lock()
+                .unwrap()
+                .insert(upload_id.clone(), key.clone());
+            xml(format!(
+                "<InitiateMultipartUploadResult><Bucket>{BUCKET}</Bucket><Key>{key}</Key><UploadId>{upload_id}</UploadId></InitiateMultipartUploadResult>"
+            ))
+        }
+        Method::POST if query.contains_key("uploadId") => {
+            let Some(parts) = s3.uploads.lock().unwrap().remove(&query["uploadId"]) else {
+                return s3_error(StatusCode::NOT_FOUND, "NoSuchUpload");
+            };
+            let request = String::from_utf8_lossy(&body).to_string();
+            let mut assembled = Vec::new();
+            for part_no in xml_values(&request, "PartNumber") {
+                let Some(bytes) = parts.get(&part_no.parse().unwrap()) else {
+                    return s3_error(StatusCode::BAD_REQUEST, "InvalidPart");
+                };
+                assembled.extend_from_slice(bytes);
+            }
+            s3.objects.lock().unwrap().insert(
+                key.clone(),
+                StoredObject {
+                    body: assembled,
+                    content_type: None,
+                    metadata: BTreeMap::new(),
+                },
+            );
+            xml(format!(
+                "<CompleteMultipartUploadResult><Bucket>{BUCKET}</Bucket><Key>{key}</Key><ETag>\"multipart\"</ETag></CompleteMultipartUploadResult>"
+            ))
+        }
+        Method::DELETE => {
+            if let Some(upload_id) = query.get("uploadId") {
+                if s3.uploads.lock().unwrap().remove(upload_id).is_none() {
+                    return s3_error(StatusCode::NOT_FOUND, "NoSuchUpload");
+                }
+            } else {
+                s3.objects.lock().unwrap().remove(&key);
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Method::HEAD => match s3.object(&key) {
+            Some(object) => (
+                StatusCode::OK,
+                [(header::CONTENT_LENGTH, object.body.len().to_string())],
+            )
+                .into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        },
+        Method::GET => {
+            let Some(object) = s3.object(&key) else {
+                return s3_error(StatusCode::NOT_FOUND, "NoSuchKey");
+            };
+            let range = headers
+                .get(header::RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("bytes="))
+                .and_then(|v| v.split_once('-'));
+            match range {
+                Some((start, end)) => {
+                    let len = object.body.len();
+                    let start: usize = start.parse().unwrap();
+                    let end = end.parse::<usize>().unwrap().min(len - 1);
+                    (
+                        StatusCode::PARTIAL_CONTENT,
+                        [(header::CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))],
+                        object.body[start..=end].to_vec(),
+                    )
+                        .into_response()
+                }
+                None => (StatusCode::OK, object.body).into_response(),
+            }
+        }
+        _ => s3_error(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed"),
+    }
+}
+
+fn list_objects(s3: &FakeS3, query: &HashMap<String, String>) -> Response {
+    let prefix = query.get("prefix").cloned().unwrap_or_default();
+    let after = query.get("continuation-token").cloned();
+    let objects = s3.objects.lock().unwrap();
+    let matching: Vec<&String> = objects
+        .keys()
+        .filter(|k| k.starts_with(&prefix))
+        .filter(|k| after.as_ref().is_none_or(|after| *k > after))
+        .collect();
+    let page = &matching[..matching.len().min(LIST_PAGE_SIZE)];
+    let truncated = matching.len() > page.len();
+    let mut body = format!(
+        "<ListBucketResult xmlns=\"http://s3.amazonaws.com/doc/2006-03-01/\"><Name>{BUCKET}</Name><Prefix>{prefix}</Prefix><KeyCount>{}</KeyCount><MaxKeys>{LIST_PAGE_SIZE}</MaxKeys><IsTruncated>{truncated}</IsTruncated>",
+        page.len()
+    );
+    for key in page {
+        body.push_str(&format!(
+            "<Contents><Key>{key}</Key><Size>{}</Size></Contents>",
+            objects[*key].body.len()
+        ));
+    }
+    if truncated {
+        body.push_str(&format!(
+            "<NextContinuationToken>{}</NextContinuationToken>",
+            page.last().unwrap()
+        ));
+    }
+    body.push_str("</ListBucketResult>");
+    xml(body)
+}
+
+fn list_multipart_uploads(s3: &FakeS3, query: &HashMap<String, String>) -> Response {
+    let prefix = query.get("prefix").cloned().unwrap_or_default();
+    let uploads = s3.uploads.lock().unwrap();
+    let keys = s3.upload_keys.lock().unwrap();
+    let mut body = format!(
+        "<ListMultipartUploadsResult><Bucket>{BUCKET}</Bucket><Prefix>{prefix}</Prefix><IsTruncated>false</IsTruncated>"
+    );
+    for upload_id in uploads.keys() {
+        let key = &keys[upload_id];
+        if key.starts_with(&prefix) {
+            body.push_str(&format!(
+                "<Upload><Key>{key}</Key><UploadId>{upload_id}</UploadId><Initiated>2026-01-01T00:00:00.000Z</Initiated></Upload>"
+            ));
+        }
+    }
+    body.push_str("</ListMultipartUploadsResult>");
+    xml(body)
+}
+
+fn delete_objects(s3: &FakeS3, request: &str) -> Response {
+    let mut objects = s3.objects.lock().unwrap();
+    let mut body = String::from("<DeleteResult>");
+    for key in xml_values(request, "Key") {
+        objects.remove(&key);
+        body.push_str(&format!("<Deleted><Key>{key}</Key></Deleted>"));
+    }
+    body.push_str("</DeleteResult>");
+    xml(body)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn put_get_exists_delete_round_trip() {
+    let s3 = TestS3::start().await;
+    let store = s3.store().await;
+
+    let key = store.blob_key("user-1", "abc123");
+    assert_eq!(key, "diaryx-sync/u/user-1/blobs/abc123");
+
+    let metadata = HashMap::from([("owner".to_string(), "user-1".to_string())]);
+    store
+        .put(&key, b"hello s3", "text/markdown", Some(&metadata))
+        .await
+        .unwrap();
+
+    let stored = s3.fake.object(&key).unwrap();
+    assert_eq!(stored.content_type.as_deref(), Some("text/markdown"));
+    assert_eq!(
+        stored.metadata.get("owner").map(String::as_str),
+        Some("user-1")
+    );
+
+    assert_eq!(
+        store.get(&key).await.unwrap().as_deref(),
+        Some(&b"hello s3"[..])
+    );
+    assert!(store.exists(&key).await.unwrap());
+
+    store.delete(&key).await.unwrap();
+    assert!(store.get(&key).await.unwrap().is_none());
+    assert!(!store.exists(&key).await.unwrap());
+
+    // Every request addressed the bucket in the path, not the host.
+    let requests = s3.fake.requests.lock().unwrap();
+    assert!(!requests.is_empty());
+    for request in requests.iter() {
+        let path = request.split_once(' ').unwrap().1;
+        assert!(
+            path.starts_with(&format!("/{BUCKET}/")) || path.starts_with(&format!("/{BUCKET}?")),
+            "not path-style: {request}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn get_range_returns_inclusive_slice() {
+    let s3 = TestS3::start().await;
+    let store = s3.store().await;
+
+    store
+        .put("blob", b"0123456789", "application/octet-stream", None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store.get_range("blob", 2, 5).await.unwrap().as_deref(),
+        Some(&b"2345"[..])
+    );
+    assert_eq!(
+        store.get_range("blob", 8, 100).await.unwrap().as_deref(),
+        Some(&b"89"[..])
+    );
+    assert!(store.get_range("missing", 0, 1).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn multipart_upload_assembles_parts_in_order() {
+    let s3 = TestS3::start().await;
+    let store = s3.store().await;
+
+    let upload = store
+        .init_multipart("big", "application/octet-stream")
+        .await
+        .unwrap();
+    // Upload out of order; completion order decides the layout.
+    let etag2 = store
+        .upload_part("big", &upload, 2, b"world")
+        .await
+        .unwrap();
+    let etag1 = store
+        .upload_part("big", &upload, 1, b"hello ")
+        .await
+        .unwrap();
+    store
+        .complete_multipart(
+            "big",
+            &upload,
+            &[
+                MultipartCompletedPart {
+                    part_no: 1,
+                    etag: etag1,
+                },
+                MultipartCompletedPart {
+                    part_no: 2,
+                    etag: etag2,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get("big").await.unwrap().as_deref(),
+        Some(&b"hello world"[..])
+    );
+
+    let aborted = store
+        .init_multipart("abandoned", "application/octet-stream")
+        .await
+        .unwrap();
+    store
+        .upload_part("abandoned", &aborted, 1, b"partial")
+        .await
+        .unwrap();
+    store.abort_multipart("abandoned", &aborted).await.unwrap();
+    assert!(s3.fake.uploads.lock().unwrap().is_empty());
+    assert!(!store.exists("abandoned").await.unwrap());
+}
+
+#[tokio::test]
+async fn list_and_delete_by_prefix_follow_pagination() {
+    let s3 = TestS3::start().await;
+    let store = s3.store().await;
+
+    for key in [
+        "site/a/index.html",
+        "site/a/one.html",
+        "site/a/two.html",
+        "site/a/three.html",
+        "site/b/index.html",
+    ] {
+        store.put(key, b"x", "text/html", None).await.unwrap();
+    }
+
+    let mut listed = store.list_by_prefix("site/a/").await.unwrap();
+    listed.sort();
+    assert_eq!(
+        listed,
+        vec![
+            "site/a/index.html",
+            "site/a/one.html",
+            "site/a/three.html",
+            "site/a/two.html",
+        ]
+    );
+
+    assert_eq!(store.delete_by_prefix("site/a/").await.unwrap(), 4);
+    assert!(store.list_by_prefix("site/a/").await.unwrap().is_empty());
+    assert!(store.exists("site/b/index.html").await.unwrap());
+}
+
+#[tokio::test]
+async fn lists_entry_sizes_and_pending_multipart_uploads() {
+    let s3 = TestS3::start().await;
+    let store = s3.store().await;
+
+    store
+        .put("ns/a/blobs/one", b"hello", "text/plain", None)
+        .await
+        .unwrap();
+    let entries = store.list_entries_by_prefix("ns/a/").await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].size_bytes, Some(5));
+
+    let upload_id = store
+        .init_multipart("ns/a/blobs/big", "application/octet-stream")
+        .await
+        .unwrap();
+    store
+        .init_multipart("ns/b/blobs/other", "application/octet-stream")
+        .await
+        .unwrap();
+    let uploads = store.list_multipart_uploads("ns/a/").await.unwrap();
+    assert_eq!(uploads.len(), 1);
+    assert_eq!(uploads[0].key, "ns/a/blobs/big");
+    assert_eq!(uploads[0].upload_id, upload_id);
+    assert_eq!(uploads[0].initiated_at, Some(1_767_225_600));
+}
+
+#[tokio::test]
+async fn wrong_credentials_are_rejected() {
+    let s3 = TestS3::start().await;
+    let store = s3.store_with_key("someone-else").await;
+
+    let err = store
+        .put("blob", b"x", "text/plain", None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("S3 put failed"), "{err}");
+    assert!(s3.fake.objects.lock().unwrap().is_empty());
+}
@@ -27,26 +27,81 @@ use rusqlite::Connection;
 use tower::ServiceExt;
 
 use diaryx_selfhosted::adapters::{
-    NativeArkIndexStore, NativeAuthSessionStore, NativeAuthStore, NativeNamespaceStore,
-    NativeObjectMetaStore, NativeUserStore,
+    NativeAccessTokenStore, NativeActivityPubStore, NativeArkIndexStore, NativeAuditLogStore,
+    NativeAuthSessionStore, NativeAuthStore, NativeCommentStore, NativeNamespaceMemberStore,
+    NativeNamespaceStore, NativeObjectMetaStore, NativePasskeyStore, NativeUserStore,
+    NativeWebhookStore, NativeWebmentionStore,
 };
 use diaryx_selfhosted::auth::{AuthExtractor, MagicLinkService, PasskeyService};
 use diaryx_selfhosted::blob_store::InMemoryBlobStore;
 use diaryx_selfhosted::config::{
     AppleIapConfig, Config, EmailConfig, ManagedAiConfig, R2Config, StripeConfig,
 };
-use diaryx_selfhosted::db::{AuthRepo, NamespaceRepo, init_database};
+use diaryx_selfhosted::db::{
+    ActivityPubRepo, AuditRepo, AuthRepo, CommentRepo, NamespaceRepo, WebmentionRepo, init_database,
+};
 use diaryx_selfhosted::email::EmailService;
 use diaryx_selfhosted::handlers::auth::{AuthState, auth_routes};
 use diaryx_selfhosted::handlers::{
-    AudienceState, NamespaceState, ObjectState, ark_routes, audience_routes, namespace_routes,
-    object_routes,
+    AccountState, ActivityPubState, AdminState, ArchiveState, AudienceState, AuditState,
+    CommentState, MemberState, NamespaceState, ObjectState, WebhookState, WebmentionState,
+    account_audit_routes, account_routes, activitypub_routes, admin_routes, archive_routes,
+    ark_routes, audience_routes, comment_routes, member_routes, membership_routes,
+    namespace_audit_routes, namespace_routes, object_routes, public_comment_routes,
+    webfinger_routes, webhook_routes, webmention_routes,
 };
+use diaryx_selfhosted::jobs::{OutboundClient, ReqwestWebhookTransport, TokioJobSink};
+use diaryx_selfhosted::render_plugins::load_render_hooks;
+use diaryx_server::ports::{ActivityPubClient, ServerCoreError, WebmentionFetcher};
+
+/// Webmention sources the test router "fetches": every source is a page
+/// titled "Reply" that links to whatever follows `links=` in its URL, so a
+/// test controls verification without network access.
+pub struct StubWebmentionFetcher;
+
+#[async_trait::async_trait]
+impl WebmentionFetcher for StubWebmentionFetcher {
+    async fn fetch(&self, url: &str, _max_bytes: usize) -> Result<(u16, String), ServerCoreError> {
+        let links = url.split_once("links=").map_or("", |(_, target)| target);
+        Ok((
+            200,
+            format!("<html><title>Reply</title><a href=\"{links}\">post</a></html>"),
+        ))
+    }
+}
+
+/// The fediverse as the test router sees it: every document is gone and
+/// every inbox accepts, so nothing leaves the process.
+pub struct StubActivityPubClient;
+
+#[async_trait::async_trait]
+impl ActivityPubClient for StubActivityPubClient {
+    async fn get(
+        &self,
+        _url: &str,
+        _headers: &[(String, String)],
+        _max_bytes: usize,
+    ) -> Result<(u16, String), ServerCoreError> {
+        Ok((404, String::new()))
+    }
+
+    async fn post(
+        &self,
+        _url: &str,
+        _headers: &[(String, String)],
+        _body: &[u8],
+    ) -> Result<u16, ServerCoreError> {
+        Ok(202)
+    }
+}
 
 // ---------------------------------------------------------------------------
 // Config construction
 // ---------------------------------------------------------------------------
 
+/// `ADMIN_SECRET` of the test config; bearer token for `/api/admin/*`.
+pub const TEST_ADMIN_SECRET: &str = "test-admin-secret";
+
 /// Build a [`Config`] with safe defaults for tests: no email, no billing, no
 /// R2, plain-HTTP cookies. Callers can mutate the returned value before
 /// wrapping it in `Arc` if they need to exercise a specific config path.
@@ -72,9 +127,11 @@ pub fn test_config() -> Config {
             endpoint: None,
             prefix: "test".to_string(),
         },
+        s3: None,
+        blob_store_backend: None,
         // 32-byte zero key — fine for tests; don't ship this to prod.
         token_signing_key: vec![0u8; 32],
-        admin_secret: None,
+        admin_secret: Some(TEST_ADMIN_SECRET.to_string()),
         managed_ai: ManagedAiConfig {
             openrouter_api_key: String::new(),
             openrouter_endpoint: "https://example.invalid".to_string(),
@@ -91,6 +148,13 @@ pub fn test_config() -> Config {
         kv_namespace_id: None,
         site_base_url: "http://localhost:5174".to_string(),
         site_domain: None,
+        storage_reconcile_interval_hours: None,
+        storage_reconcile_grace_hours: 24,
+        audit_log_retention_days: 365,
+        // Webhook tests deliver to receivers bound on 127.0.0.1.
+        outbound_allow_private: true,
+        render_plugins_dir: None,
+        render_plugins_allow_unsigned: false,
     }
 }
 
@@ -160,14 +224,24 @@ impl TestApp {
 /// Build a minimal test router mounting only `/api/health` and `/api/auth/*`.
 /// Extend as additional handler states are needed.
 pub fn build_test_router() -> TestApp {
-    let config = Arc::new(test_config());
+    build_test_router_with_config(test_config())
+}
+
+/// [`build_test_router`] with a customised [`test_config`].
+pub fn build_test_router_with_config(config: Config) -> TestApp {
+    let config = Arc::new(config);
 
     let conn = Connection::open_in_memory().expect("open :memory: sqlite");
     init_database(&conn).expect("init schema");
     let repo = Arc::new(AuthRepo::new(conn));
 
     let ns_repo = Arc::new(NamespaceRepo::new(repo.connection()));
-    let magic_link_service = Arc::new(MagicLinkService::new(repo.clone(), config.clone()));
+    let audit_store = Arc::new(NativeAuditLogStore::new(Arc::new(AuditRepo::new(
+        repo.connection(),
+    ))));
+    let magic_link_service = Arc::new(
+        MagicLinkService::new(repo.clone(), config.clone()).with_audit(audit_store.clone()),
+    );
     let email_service = Arc::new(EmailService::new(config.clone()));
     let passkey_service = Arc::new(PasskeyService::new(
         repo.clone(),
@@ -181,27 +255,120 @@ pub fn build_test_router() -> TestApp {
     let namespace_store = Arc::new(NativeNamespaceStore::new(ns_repo.clone()));
     let object_meta_store = Arc::new(NativeObjectMetaStore::new(ns_repo.clone()));
     let ark_index_store = Arc::new(NativeArkIndexStore::new(ns_repo.clone()));
+    let member_store = Arc::new(NativeNamespaceMemberStore::new(ns_repo.clone()));
+    let webhook_store = Arc::new(NativeWebhookStore::new(ns_repo.clone()));
+    let webmention_store = Arc::new(NativeWebmentionStore::new(Arc::new(WebmentionRepo::new(
+        repo.connection(),
+    ))));
+    let comment_store = Arc::new(NativeCommentStore::new(Arc::new(CommentRepo::new(
+        repo.connection(),
+    ))));
+    let activitypub_store = Arc::new(NativeActivityPubStore::new(Arc::new(ActivityPubRepo::new(
+        repo.connection(),
+    ))));
     let blob_store = Arc::new(InMemoryBlobStore::new("test"));
-    let auth_extractor = AuthExtractor::new(auth_store.clone(), auth_session_store.clone());
+    let access_token_store = Arc::new(NativeAccessTokenStore::new(repo.clone()));
+    let auth_extractor = AuthExtractor::new(auth_store.clone(), auth_session_store.clone())
+        .with_access_tokens(access_token_store.clone());
+
+    let account_state = AccountState {
+        auth_store: auth_store.clone(),
+        user_store: user_store.clone(),
+        passkey_store: Arc::new(NativePasskeyStore::new(repo.clone())),
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        domain_mapping_cache: None,
+        email_service: email_service.clone(),
+        app_base_url: config.app_base_url.clone(),
+        token_signing_key: config.token_signing_key.clone(),
+    };
+
+    let comment_state = CommentState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        comment_store,
+        auth_store: auth_store.clone(),
+        email_service: email_service.clone(),
+        token_signing_key: config.token_signing_key.clone(),
+    };
 
     let auth_state = AuthState {
         magic_link_service,
-        email_service,
+        email_service: email_service.clone(),
         auth_store,
         namespace_store: namespace_store.clone(),
         session_store: auth_session_store,
         user_store,
+        access_token_store,
         passkey_service,
+        audit_store: audit_store.clone(),
         session_expiry_days: config.session_expiry_days,
         secure_cookies: config.secure_cookies,
     };
 
+    let admin_state = AdminState {
+        admin_secret: TEST_ADMIN_SECRET.to_string(),
+        auth_repo: repo.clone(),
+        ns_repo: ns_repo.clone(),
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        domain_mapping_cache: None,
+        config: config.clone(),
+        started_at: 0,
+    };
+
+    let archive_state = ArchiveState {
+        namespace_store: namespace_store.clone(),
+        object_meta_store: object_meta_store.clone(),
+        blob_store: blob_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        domain_mapping_cache: None,
+    };
+    let job_sink = Arc::new(
+        TokioJobSink::new(
+            webhook_store.clone(),
+            Arc::new(ReqwestWebhookTransport::new(OutboundClient::new(
+                config.outbound_allow_private,
+                0,
+            ))),
+        )
+        .with_webmentions(webmention_store.clone(), Arc::new(StubWebmentionFetcher)),
+    );
+    let api_base_url = format!("{}/api", config.site_base_url);
+    let webhook_state = WebhookState {
+        namespace_store: namespace_store.clone(),
+        webhook_store,
+        job_sink: job_sink.clone(),
+        allow_private_targets: config.outbound_allow_private,
+    };
+    let webmention_state = WebmentionState {
+        namespace_store: namespace_store.clone(),
+        ark_index_store: ark_index_store.clone(),
+        webmention_store,
+        job_sink: job_sink.clone(),
+        api_base_url: api_base_url.clone(),
+    };
+    let activitypub_state = ActivityPubState {
+        namespace_store: namespace_store.clone(),
+        activitypub_store,
+        client: Arc::new(StubActivityPubClient),
+        job_sink,
+        api_base_url,
+    };
     let object_state = ObjectState {
         namespace_store: namespace_store.clone(),
         object_meta_store,
         blob_store: blob_store.clone(),
         ark_index_store,
         token_signing_key: config.token_signing_key.clone(),
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        webmentions: webmention_state.clone(),
+        activitypub: activitypub_state.clone(),
+        render_hooks: load_render_hooks(&config),
     };
     let namespace_state = NamespaceState {
         namespace_store: namespace_store.clone(),
@@ -211,18 +378,47 @@ pub fn build_test_router() -> TestApp {
         namespace_store: namespace_store.clone(),
         token_signing_key: config.token_signing_key.clone(),
         blob_store: blob_store.clone(),
+        member_store: member_store.clone(),
+        webhooks: webhook_state.clone(),
+        audit_store: audit_store.clone(),
+    };
+    let member_state = MemberState {
+        namespace_store: namespace_store.clone(),
+        member_store,
+        email_service,
+        app_base_url: config.app_base_url.clone(),
+    };
+    let audit_state = AuditState {
+        namespace_store: namespace_store.clone(),
+        audit_store,
     };
 
     let api = Router::new()
         .route("/health", get(|| async { "OK" }))
         .nest("/auth", auth_routes(auth_state))
+        .nest("/auth", account_routes(account_state))
+        .nest("/auth", account_audit_routes(audit_state.clone()))
+        .nest("/admin", admin_routes(admin_state))
         .nest("/namespaces", namespace_routes(namespace_state))
+        .nest("/namespaces", archive_routes(archive_state))
         .nest("/namespaces/{ns_id}", object_routes(object_state.clone()))
-        .nest("/namespaces/{ns_id}", audience_routes(audience_state));
+        .nest("/namespaces/{ns_id}", audience_routes(audience_state))
+        .nest("/namespaces/{ns_id}", member_routes(member_state.clone()))
+        .nest("/namespaces/{ns_id}", webhook_routes(webhook_state))
+        .nest("/namespaces/{ns_id}", webmention_routes(webmention_state))
+        .nest("/namespaces/{ns_id}", comment_routes(comment_state.clone()))
+        .nest(
+            "/namespaces/{ns_id}",
+            activitypub_routes(activitypub_state.clone()),
+        )
+        .nest("/namespaces/{ns_id}", namespace_audit_routes(audit_state))
+        .merge(membership_routes(member_state))
+        .merge(public_comment_routes(comment_state));
 
     let router = Router::new()
         .nest("/api", api)
         .merge(ark_routes(object_state))
+        .merge(webfinger_routes(activitypub_state))
         .layer(Extension(auth_extractor));
 
     TestApp {
@@ -90,6 +90,81 @@ pub struct AuthSessionInfo {
 pub struct AuthContext {
     pub session: AuthSessionInfo,
     pub user: UserInfo,
+    /// The personal access token the request authenticated with, or `None`
+    /// for a device session. When set, `session` is synthesized from the
+    /// token and its `device_id` is empty.
+    pub access_token: Option<AccessTokenInfo>,
+}
+
+/// An operation class a personal access token can be granted.
+///
+/// Scopes are ordered and each implies the ones before it: `manage` can do
+/// everything `publish` can, which can do everything `read` can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// List and fetch namespaces, objects, audiences, and domains.
+    Read,
+    /// Upload and delete objects, sync audiences, trigger builds, and create
+    /// namespaces — everything `diaryx publish` needs.
+    Publish,
+    /// Namespace administration: metadata, deletion, import, domains.
+    Manage,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Publish => "publish",
+            TokenScope::Manage => "manage",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(TokenScope::Read),
+            "publish" => Some(TokenScope::Publish),
+            "manage" => Some(TokenScope::Manage),
+            _ => None,
+        }
+    }
+}
+
+/// Personal access token metadata. The secret itself is never stored; see
+/// [`AccessTokenStore`](crate::ports::AccessTokenStore).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessTokenInfo {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Namespaces the token is restricted to, or `None` for every namespace
+    /// the owner has (and account-wide routes such as namespace creation).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_ids: Option<Vec<String>>,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+impl AccessTokenInfo {
+    /// True if one of the token's scopes implies `scope`.
+    pub fn grants(&self, scope: TokenScope) -> bool {
+        self.scopes.iter().any(|s| *s >= scope)
+    }
+
+    /// True if the token may act on `namespace_id`.
+    pub fn covers_namespace(&self, namespace_id: &str) -> bool {
+        self.namespace_ids
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == namespace_id))
+    }
+
+    /// True if the token has an expiry at or before `now` (unix seconds).
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
 }
 
 /// Namespace metadata.
@@ -103,6 +178,434 @@ pub struct NamespaceInfo {
     pub metadata: Option<String>,
 }
 
+/// A collaborator's role on a namespace.
+///
+/// Roles are ordered and each implies the ones before it. The namespace's
+/// `owner_user_id` always has [`NamespaceRole::Owner`], whether or not it has a
+/// membership row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamespaceRole {
+    /// Read objects (including private audiences) and list audiences.
+    Viewer,
+    /// Everything a viewer can do, plus put/delete objects and trigger builds.
+    Editor,
+    /// Full control: audiences, domains, collaborators, and namespace metadata.
+    Owner,
+}
+
+impl NamespaceRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NamespaceRole::Viewer => "viewer",
+            NamespaceRole::Editor => "editor",
+            NamespaceRole::Owner => "owner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "viewer" => Some(NamespaceRole::Viewer),
+            "editor" => Some(NamespaceRole::Editor),
+            "owner" => Some(NamespaceRole::Owner),
+            _ => None,
+        }
+    }
+}
+
+/// A user's membership in a namespace they don't own outright.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceMemberInfo {
+    pub namespace_id: String,
+    pub user_id: String,
+    /// The member's email, when the store can join it from the users table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub role: NamespaceRole,
+    pub created_at: i64,
+}
+
+/// A pending invitation to collaborate on a namespace. The invite secret is
+/// never stored; see [`NamespaceMemberStore`](crate::ports::NamespaceMemberStore).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceInviteInfo {
+    pub id: String,
+    pub namespace_id: String,
+    /// Lowercased address the invite was sent to; only that account can accept.
+    pub email: String,
+    pub role: NamespaceRole,
+    pub invited_by: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+/// Something that happened in a namespace that webhooks can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A server-side build finished rendering the namespace's site.
+    BuildCompleted,
+    /// An audience was created, had its gates changed or its password
+    /// rotated, or was deleted.
+    AudienceChanged,
+    /// An object was deleted from the namespace.
+    ObjectDeleted,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::BuildCompleted,
+        WebhookEvent::AudienceChanged,
+        WebhookEvent::ObjectDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::BuildCompleted => "build_completed",
+            WebhookEvent::AudienceChanged => "audience_changed",
+            WebhookEvent::ObjectDeleted => "object_deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "build_completed" => Some(WebhookEvent::BuildCompleted),
+            "audience_changed" => Some(WebhookEvent::AudienceChanged),
+            "object_deleted" => Some(WebhookEvent::ObjectDeleted),
+            _ => None,
+        }
+    }
+}
+
+/// An owner-configured endpoint that receives signed event notifications for
+/// a namespace. The signing secret is never part of this record; see
+/// [`WebhookStore`](crate::ports::WebhookStore).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookInfo {
+    pub id: String,
+    pub namespace_id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_by: String,
+    pub created_at: i64,
+}
+
+impl WebhookInfo {
+    pub fn subscribes_to(&self, event: WebhookEvent) -> bool {
+        self.events.contains(&event)
+    }
+}
+
+/// Where a webhook delivery is in its lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Not yet delivered; another attempt is due at `next_attempt_at`.
+    Pending,
+    /// The endpoint answered with a 2xx status.
+    Succeeded,
+    /// Every attempt failed; no more will be made.
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Succeeded => "succeeded",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(WebhookDeliveryStatus::Pending),
+            "succeeded" => Some(WebhookDeliveryStatus::Succeeded),
+            "failed" => Some(WebhookDeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One event sent (or being sent) to one webhook — an entry in the
+/// namespace's delivery log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDeliveryInfo {
+    pub id: String,
+    pub webhook_id: String,
+    pub namespace_id: String,
+    pub event: WebhookEvent,
+    /// The exact JSON body that is (re)sent on every attempt.
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    /// When the next attempt is due; `None` once the delivery is settled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<i64>,
+    /// HTTP status of the most recent attempt, if the endpoint answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Where a received Webmention is in verification and moderation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebmentionStatus {
+    /// Received; the source hasn't been checked yet.
+    Unverified,
+    /// The source links to the target; waiting for the owner.
+    Pending,
+    /// Shown under the target page from the next build on.
+    Approved,
+    /// Hidden by the owner. Sending it again doesn't bring it back.
+    Rejected,
+    /// The source couldn't be fetched or no longer links to the target.
+    Invalid,
+}
+
+impl WebmentionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebmentionStatus::Unverified => "unverified",
+            WebmentionStatus::Pending => "pending",
+            WebmentionStatus::Approved => "approved",
+            WebmentionStatus::Rejected => "rejected",
+            WebmentionStatus::Invalid => "invalid",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "unverified" => Some(WebmentionStatus::Unverified),
+            "pending" => Some(WebmentionStatus::Pending),
+            "approved" => Some(WebmentionStatus::Approved),
+            "rejected" => Some(WebmentionStatus::Rejected),
+            "invalid" => Some(WebmentionStatus::Invalid),
+            _ => None,
+        }
+    }
+}
+
+/// A Webmention received for a published page: `source` says it links to
+/// `target`. One per `(namespace_id, source, target)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebmentionInfo {
+    pub id: String,
+    pub namespace_id: String,
+    pub source: String,
+    pub target: String,
+    /// Object key of the page `target` resolved to.
+    pub object_key: String,
+    pub status: WebmentionStatus,
+    /// `<title>` of the source page, captured during verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_title: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Where a reader's comment is in moderation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentStatus {
+    /// Posted; waiting for the owner.
+    Pending,
+    /// Visible to everyone with access to the page's audience.
+    Approved,
+    /// Hidden by the owner.
+    Rejected,
+}
+
+impl CommentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentStatus::Pending => "pending",
+            CommentStatus::Approved => "approved",
+            CommentStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(CommentStatus::Pending),
+            "approved" => Some(CommentStatus::Approved),
+            "rejected" => Some(CommentStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// A note a reader left on a page of a gated audience, authenticated by the
+/// audience token they opened it with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentInfo {
+    pub id: String,
+    pub namespace_id: String,
+    pub audience: String,
+    /// Object key of the rendered page the comment is on.
+    pub object_key: String,
+    /// Object key of the page's markdown source, when it was uploaded.
+    /// `diaryx publish` files the comment next to that entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_key: Option<String>,
+    pub author_name: String,
+    pub body: String,
+    pub status: CommentStatus,
+    /// Id of the audience token the comment was posted with.
+    pub token_id: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// The RSA key pair a namespace's ActivityPub actor signs requests with,
+/// PEM-encoded (SPKI public key, PKCS#8 private key). Created on first use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActorKeyPair {
+    pub namespace_id: String,
+    pub public_key_pem: String,
+    pub private_key_pem: String,
+    pub created_at: i64,
+}
+
+/// A fediverse account following a namespace's ActivityPub actor. One per
+/// `(namespace_id, actor)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowerInfo {
+    pub namespace_id: String,
+    /// The follower's actor id.
+    pub actor: String,
+    /// The follower's personal inbox.
+    pub inbox: String,
+    /// Its server's shared inbox, preferred for delivery when present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_inbox: Option<String>,
+    pub created_at: i64,
+}
+
+/// A public page listed in a namespace actor's outbox. One per
+/// `(namespace_id, object_key)`, kept in step with the pages each build
+/// writes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxItem {
+    /// Stable id of the page's `Note`, assigned when first published.
+    pub id: String,
+    pub namespace_id: String,
+    pub audience: String,
+    pub object_key: String,
+    /// Where the page is published.
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+    /// The page's `created` date, or when it was first built without one.
+    pub published_at: i64,
+    pub updated_at: i64,
+}
+
+/// A security-relevant action recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A device signed in (magic link, code or passkey).
+    SignIn,
+    /// A device was removed to make room for a new sign-in.
+    DeviceReplaced,
+    PasskeyRegistered,
+    PasskeyDeleted,
+    AccessTokenCreated,
+    AccessTokenRevoked,
+    /// An audience was created or had its gates changed.
+    AudienceUpdated,
+    AudienceDeleted,
+    AudiencePasswordRotated,
+    /// A reader unlocked a password audience.
+    AudienceUnlocked,
+    /// A reader supplied the wrong password for an audience.
+    AudienceUnlockFailed,
+    DomainRegistered,
+    DomainRemoved,
+    SubdomainClaimed,
+    SubdomainReleased,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::SignIn => "sign_in",
+            AuditAction::DeviceReplaced => "device_replaced",
+            AuditAction::PasskeyRegistered => "passkey_registered",
+            AuditAction::PasskeyDeleted => "passkey_deleted",
+            AuditAction::AccessTokenCreated => "access_token_created",
+            AuditAction::AccessTokenRevoked => "access_token_revoked",
+            AuditAction::AudienceUpdated => "audience_updated",
+            AuditAction::AudienceDeleted => "audience_deleted",
+            AuditAction::AudiencePasswordRotated => "audience_password_rotated",
+            AuditAction::AudienceUnlocked => "audience_unlocked",
+            AuditAction::AudienceUnlockFailed => "audience_unlock_failed",
+            AuditAction::DomainRegistered => "domain_registered",
+            AuditAction::DomainRemoved => "domain_removed",
+            AuditAction::SubdomainClaimed => "subdomain_claimed",
+            AuditAction::SubdomainReleased => "subdomain_released",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "sign_in" => Some(AuditAction::SignIn),
+            "device_replaced" => Some(AuditAction::DeviceReplaced),
+            "passkey_registered" => Some(AuditAction::PasskeyRegistered),
+            "passkey_deleted" => Some(AuditAction::PasskeyDeleted),
+            "access_token_created" => Some(AuditAction::AccessTokenCreated),
+            "access_token_revoked" => Some(AuditAction::AccessTokenRevoked),
+            "audience_updated" => Some(AuditAction::AudienceUpdated),
+            "audience_deleted" => Some(AuditAction::AudienceDeleted),
+            "audience_password_rotated" => Some(AuditAction::AudiencePasswordRotated),
+            "audience_unlocked" => Some(AuditAction::AudienceUnlocked),
+            "audience_unlock_failed" => Some(AuditAction::AudienceUnlockFailed),
+            "domain_registered" => Some(AuditAction::DomainRegistered),
+            "domain_removed" => Some(AuditAction::DomainRemoved),
+            "subdomain_claimed" => Some(AuditAction::SubdomainClaimed),
+            "subdomain_released" => Some(AuditAction::SubdomainReleased),
+            _ => None,
+        }
+    }
+}
+
+/// One entry in the append-only audit log. Account events carry `user_id`
+/// and show up in that user's log; namespace events carry `namespace_id` and
+/// show up in the namespace owner's log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: String,
+    pub action: AuditAction,
+    /// Who did it; `None` for anonymous readers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_user_id: Option<String>,
+    /// The account the event belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// The namespace the event belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_id: Option<String>,
+    /// What was acted on: an audience name, domain, passkey or token ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Extra context. Never holds secrets (passwords, hashes, tokens).
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
+    pub created_at: i64,
+}
+
+/// Whose audit log to read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditScope {
+    User(String),
+    Namespace(String),
+}
+
 /// A single stackable gate on a namespace audience.
 ///
 /// An audience with no gates is public. Multiple gates are evaluated with OR
@@ -230,6 +733,138 @@ pub struct ArkIndexEntry {
     pub updated_at: i64,
 }
 
+/// A retained version of a file ARK's markdown source, addressable as
+/// `ark:…/{workspace}/{file}.{version}`. Immutable once recorded: a version is
+/// the source's content hash, and the object it points at is never rewritten.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArkVersionEntry {
+    pub workspace_ark: String,
+    pub file_ark: String,
+    /// SHA-256 hex digest of the source content — the `.<FILE>` variant.
+    pub version: String,
+    /// Object key of the immutable version object holding the source bytes.
+    pub object_key: String,
+    /// When this version was first published (unix seconds).
+    pub created_at: i64,
+}
+
+/// One line of a namespace archive — the NDJSON stream produced by
+/// [`NamespaceArchiveService::export`](crate::use_cases::archive::NamespaceArchiveService::export)
+/// and consumed by its `import`.
+///
+/// Records arrive in dependency order: the header, audiences, domains, objects,
+/// ARK rows, retained versions, then the `end` trailer. A stream without the
+/// trailer is truncated and is rejected on import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArchiveRecord {
+    Header {
+        /// Always [`ARCHIVE_FORMAT`](crate::use_cases::archive::ARCHIVE_FORMAT).
+        format: String,
+        version: u32,
+        namespace_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        metadata: Option<String>,
+        exported_at: i64,
+    },
+    Audience {
+        name: String,
+        /// Includes password hashes, so an archive is as sensitive as the
+        /// database rows it came from.
+        gates: Vec<GateRecord>,
+    },
+    Domain {
+        domain: String,
+        audience_name: String,
+    },
+    Object {
+        key: String,
+        mime_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audience: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content_hash: Option<String>,
+        /// Base64 (standard alphabet) of the object bytes.
+        data: String,
+    },
+    Ark {
+        file_ark: String,
+        object_key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audience: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source_key: Option<String>,
+    },
+    ArkVersion {
+        file_ark: String,
+        version: String,
+        object_key: String,
+    },
+    End {
+        /// Number of `object` records in the archive.
+        objects: u64,
+    },
+}
+
+/// One line of an account export — the NDJSON stream produced by
+/// [`AccountExportService::export`](crate::use_cases::current_user::AccountExportService::export).
+///
+/// Records arrive as the header, the profile, devices, passkeys, then each
+/// owned namespace (its `namespace` record followed by that namespace's full
+/// archive as `namespace_archive` records), usage events, and the `end`
+/// trailer. The nested archive records are exactly what
+/// [`NamespaceArchiveService::import`](crate::use_cases::archive::NamespaceArchiveService::import)
+/// reads, so a namespace can be restored from an account export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccountExportRecord {
+    Header {
+        /// Always [`ACCOUNT_EXPORT_FORMAT`](crate::use_cases::current_user::ACCOUNT_EXPORT_FORMAT).
+        format: String,
+        version: u32,
+        user_id: String,
+        exported_at: i64,
+    },
+    Profile {
+        user: UserInfo,
+    },
+    Device {
+        device: DeviceInfo,
+    },
+    /// Passkey metadata only; the credential itself is never exported.
+    Passkey {
+        id: String,
+        name: String,
+        created_at: i64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        last_used_at: Option<i64>,
+    },
+    Namespace {
+        namespace: NamespaceInfo,
+    },
+    NamespaceArchive {
+        namespace_id: String,
+        record: ArchiveRecord,
+    },
+    UsageEvent {
+        event: UsageEvent,
+    },
+    End {
+        namespaces: u64,
+        objects: u64,
+    },
+}
+
+/// A single recorded usage event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageEvent {
+    pub event_type: String,
+    pub amount: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_id: Option<String>,
+    pub recorded_at: i64,
+}
+
 /// Aggregated usage totals for a user.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UsageTotals {
@@ -292,7 +927,7 @@ pub struct CurrentUserContext {
 
 #[cfg(test)]
 mod tests {
-    use super::{TierDefaults, UserTier};
+    use super::{AccessTokenInfo, NamespaceRole, TierDefaults, TokenScope, UserTier};
 
     #[test]
     fn user_tier_string_helpers_round_trip() {
@@ -332,4 +967,45 @@ mod tests {
             UserTier::Free
         );
     }
+
+    #[test]
+    fn namespace_roles_are_ordered_and_round_trip() {
+        assert!(NamespaceRole::Owner > NamespaceRole::Editor);
+        assert!(NamespaceRole::Editor > NamespaceRole::Viewer);
+        for role in [
+            NamespaceRole::Viewer,
+            NamespaceRole::Editor,
+            NamespaceRole::Owner,
+        ] {
+            assert_eq!(NamespaceRole::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(NamespaceRole::parse("admin"), None);
+        assert_eq!(
+            serde_json::to_string(&NamespaceRole::Editor).unwrap(),
+            "\"editor\""
+        );
+    }
+
+    #[test]
+    fn access_token_scopes_imply_weaker_scopes() {
+        let token = AccessTokenInfo {
+            id: "tok".to_string(),
+            user_id: "user".to_string(),
+            name: "ci".to_string(),
+            scopes: vec![TokenScope::Publish],
+            namespace_ids: Some(vec!["ns-a".to_string()]),
+            created_at: 0,
+            expires_at: Some(100),
+            last_used_at: None,
+        };
+        assert!(token.grants(TokenScope::Read));
+        assert!(token.grants(TokenScope::Publish));
+        assert!(!token.grants(TokenScope::Manage));
+        assert!(token.covers_namespace("ns-a"));
+        assert!(!token.covers_namespace("ns-b"));
+        assert!(!token.is_expired(99));
+        assert!(token.is_expired(100));
+        assert_eq!(TokenScope::parse("publish"), Some(TokenScope::Publish));
+        assert_eq!(TokenScope::parse("admin"), None);
+    }
 }
@@ -27,6 +27,7 @@ macro_rules! cfg_async_trait {
 pub mod api;
 pub mod audience_token;
 pub mod domain;
+pub mod outbound;
 pub mod ports;
 pub mod proxy;
 pub mod schema;
@@ -50,9 +51,9 @@ pub use domain::{
     UserTier,
 };
 pub use ports::{
-    AppleReceiptVerifier, AuthSessionStore, AuthStore, BillingProvider, BillingStore, BlobStore,
-    Clock, DeviceStore, DomainMappingCache, JobSink, MagicLinkStore, Mailer,
-    MultipartCompletedPart, NamespaceStore, ObjectMetaStore, PasskeyStore, ProxyConfigStore,
-    ProxySecretResolver, ProxyUsageStore, RateLimitStore, ServerCoreError, SessionStore,
-    TokenClaims, TokenSigner, UserStore,
+    AppleReceiptVerifier, AuthSessionStore, AuthStore, BillingProvider, BillingStore, BlobEntry,
+    BlobStore, Clock, DeviceStore, DomainMappingCache, JobSink, MagicLinkStore, Mailer,
+    MultipartCompletedPart, NamespaceStore, ObjectMetaStore, PasskeyStore, PendingMultipartUpload,
+    ProxyConfigStore, ProxySecretResolver, ProxyUsageStore, RateLimitStore, ServerCoreError,
+    SessionStore, TokenClaims, TokenSigner, UserStore,
 };
@@ -0,0 +1,121 @@
+//! Checks for requests the server makes to URLs its users supply: webhook
+//! endpoints, Webmention sources, and ActivityPub actors and inboxes.
+//!
+//! Use cases refuse URLs whose host names this machine or a private network
+//! with [`url_host`] and [`is_internal_host`]. A public name can still
+//! resolve to, or redirect to, an internal address, so adapters that make
+//! the request also check every address they connect to with
+//! [`is_internal_ip`] and re-check the host of every redirect they follow.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The host of an http(s) URL, without port or IPv6 brackets.
+pub fn url_host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    Some(match authority.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    })
+}
+
+/// Whether a host names this machine or a private network, either by a
+/// reserved name or as an internal IP literal.
+pub fn is_internal_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host == "localhost"
+        || [".localhost", ".local", ".internal"]
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+    {
+        return true;
+    }
+    host.parse::<IpAddr>().is_ok_and(is_internal_ip)
+}
+
+/// Whether an address is loopback, private, link-local (which includes
+/// cloud metadata endpoints), shared carrier-grade NAT space, multicast or
+/// unspecified.
+pub fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_internal_ipv4(ip),
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+                || ip.to_ipv4_mapped().is_some_and(is_internal_ipv4)
+                || is_ipv4_compatible(ip)
+        }
+    }
+}
+
+fn is_internal_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+}
+
+/// `::a.b.c.d`, which some stacks still route to the embedded IPv4 address.
+fn is_ipv4_compatible(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    segments[..6].iter().all(|s| *s == 0) && !ip.is_loopback() && !ip.is_unspecified()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_recognised() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.8",
+            "172.16.4.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "224.0.0.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+            "::7f00:1",
+        ] {
+            assert!(is_internal_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["93.184.216.34", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_internal_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn internal_hosts_are_recognised() {
+        for url in [
+            "http://localhost:3030/",
+            "http://LOCALHOST./",
+            "http://api.localhost/",
+            "http://metadata.google.internal/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[fe80::1]/",
+        ] {
+            assert!(url_host(url).is_some_and(is_internal_host), "{url}");
+        }
+        assert!(!url_host("https://example.org/hook").is_some_and(is_internal_host));
+        assert_eq!(url_host("ftp://example.org/"), None);
+    }
+}
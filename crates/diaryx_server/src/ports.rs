@@ -1,7 +1,10 @@
 use crate::domain::{
-    AudienceInfo, AuthSessionInfo, CustomDomainInfo, DeviceInfo, GateRecord, NamespaceInfo,
-    NamespaceSessionInfo, ObjectMeta, PasskeyChallengeInfo, PasskeyCredentialInfo, UsageTotals,
-    UserInfo, UserTier,
+    AccessTokenInfo, AccountExportRecord, ActorKeyPair, ArchiveRecord, AudienceInfo, AuditEvent,
+    AuditScope, AuthSessionInfo, CommentInfo, CommentStatus, CustomDomainInfo, DeviceInfo,
+    FollowerInfo, GateRecord, NamespaceInfo, NamespaceInviteInfo, NamespaceMemberInfo,
+    NamespaceRole, NamespaceSessionInfo, ObjectMeta, OutboxItem, PasskeyChallengeInfo,
+    PasskeyCredentialInfo, UsageEvent, UsageTotals, UserInfo, UserTier, WebhookDeliveryInfo,
+    WebhookInfo, WebmentionInfo, WebmentionStatus,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -74,6 +77,24 @@ pub struct MultipartCompletedPart {
     pub etag: String,
 }
 
+/// A blob as reported by a store listing. Size and last-modified time (Unix
+/// seconds) are `None` when the backend doesn't report them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobEntry {
+    pub key: String,
+    pub size_bytes: Option<u64>,
+    pub last_modified: Option<i64>,
+}
+
+/// A multipart upload that was started but never completed or aborted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingMultipartUpload {
+    pub key: String,
+    pub upload_id: String,
+    /// Unix seconds, when the backend reports it.
+    pub initiated_at: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenClaims {
     pub subject: String,
@@ -109,6 +130,37 @@ pub trait AuthSessionStore: Send + Sync {
     async fn update_device_last_seen(&self, device_id: &str) -> Result<(), ServerCoreError>;
 }
 
+/// Storage for personal access tokens. Only the SHA-256 hex digest of a
+/// token's secret is persisted; the plaintext is shown once at creation.
+pub trait AccessTokenStore: Send + Sync {
+    async fn create_access_token(
+        &self,
+        token: &AccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError>;
+    async fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AccessTokenInfo>, ServerCoreError>;
+    /// List a user's tokens, newest first.
+    async fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenInfo>, ServerCoreError>;
+    /// Delete a token owned by `user_id`. Returns `false` if there was none.
+    async fn delete_access_token(
+        &self,
+        user_id: &str,
+        token_id: &str,
+    ) -> Result<bool, ServerCoreError>;
+    /// Stamp `last_used_at` (unix seconds).
+    async fn touch_access_token(
+        &self,
+        token_id: &str,
+        used_at: i64,
+    ) -> Result<(), ServerCoreError>;
+}
+
 pub trait MagicLinkStore: Send + Sync {
     /// Create a magic token + 6-digit code for the given email.
     /// Returns (token, code).
@@ -255,6 +307,204 @@ pub trait NamespaceStore: Send + Sync {
     ) -> Result<(), ServerCoreError>;
 }
 
+/// Namespace collaborators and their pending invites. The namespace owner
+/// (`NamespaceInfo::owner_user_id`) is implicit and never stored here. Only
+/// the SHA-256 hex digest of an invite's secret is persisted.
+pub trait NamespaceMemberStore: Send + Sync {
+    async fn get_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<NamespaceMemberInfo>, ServerCoreError>;
+    /// List a namespace's members, oldest first.
+    async fn list_members(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError>;
+    /// List the memberships `user_id` holds across namespaces, newest first.
+    async fn list_user_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError>;
+    /// Insert a membership, or change the role of an existing one (keeping its
+    /// `created_at`).
+    async fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), ServerCoreError>;
+    /// Returns `false` if the user was not a member.
+    async fn remove_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, ServerCoreError>;
+    async fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError>;
+    async fn get_invite_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<NamespaceInviteInfo>, ServerCoreError>;
+    /// List a namespace's pending invites, newest first.
+    async fn list_invites(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError>;
+    /// Returns `false` if there was no such invite on the namespace.
+    async fn delete_invite(
+        &self,
+        namespace_id: &str,
+        invite_id: &str,
+    ) -> Result<bool, ServerCoreError>;
+}
+
+/// Outbound webhooks and their delivery log. The signing secret is stored
+/// alongside the webhook (it has to be, to sign deliveries) but only read
+/// back through [`get_webhook_secret`](Self::get_webhook_secret).
+pub trait WebhookStore: Send + Sync {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookInfo,
+        secret: &str,
+    ) -> Result<(), ServerCoreError>;
+    async fn get_webhook(&self, webhook_id: &str) -> Result<Option<WebhookInfo>, ServerCoreError>;
+    async fn get_webhook_secret(&self, webhook_id: &str) -> Result<Option<String>, ServerCoreError>;
+    /// List a namespace's webhooks, oldest first.
+    async fn list_webhooks(&self, namespace_id: &str) -> Result<Vec<WebhookInfo>, ServerCoreError>;
+    /// Delete a webhook and its deliveries. Returns `false` if there was no
+    /// such webhook on the namespace.
+    async fn delete_webhook(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+    ) -> Result<bool, ServerCoreError>;
+    /// Insert a delivery, or overwrite the mutable fields (`status`,
+    /// `attempts`, `next_attempt_at`, `response_status`, `last_error`,
+    /// `updated_at`) of an existing one.
+    async fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), ServerCoreError>;
+    async fn get_delivery(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError>;
+    /// List a namespace's deliveries, newest first, optionally only those of
+    /// one webhook.
+    async fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError>;
+    /// List pending deliveries whose `next_attempt_at` is at or before `now`,
+    /// oldest due first, across all namespaces.
+    async fn list_due_deliveries(
+        &self,
+        now: i64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError>;
+}
+
+/// Webmentions received for a namespace's published pages.
+pub trait WebmentionStore: Send + Sync {
+    /// Insert a mention, or overwrite the mutable fields (`object_key`,
+    /// `status`, `source_title`, `updated_at`) of an existing one.
+    async fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), ServerCoreError>;
+    async fn get_webmention(&self, id: &str) -> Result<Option<WebmentionInfo>, ServerCoreError>;
+    async fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<Option<WebmentionInfo>, ServerCoreError>;
+    /// List a namespace's mentions, newest first, optionally only those in
+    /// one status.
+    async fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError>;
+    /// Returns `false` if there was no such mention on the namespace.
+    async fn delete_webmention(
+        &self,
+        namespace_id: &str,
+        id: &str,
+    ) -> Result<bool, ServerCoreError>;
+}
+
+/// Reader comments on the pages of a namespace's gated audiences.
+pub trait CommentStore: Send + Sync {
+    /// Insert a comment, or overwrite the mutable fields (`status`,
+    /// `updated_at`) of an existing one.
+    async fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), ServerCoreError>;
+    async fn get_comment(&self, id: &str) -> Result<Option<CommentInfo>, ServerCoreError>;
+    /// List a namespace's comments, newest first, optionally only those on
+    /// one page and/or in one status.
+    async fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError>;
+    /// Returns `false` if there was no such comment on the namespace.
+    async fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, ServerCoreError>;
+}
+
+/// State behind each namespace's ActivityPub actor: its signing key, its
+/// followers and the pages in its outbox.
+pub trait ActivityPubStore: Send + Sync {
+    async fn get_actor_key(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<ActorKeyPair>, ServerCoreError>;
+    /// Store a key unless the namespace already has one; the existing key
+    /// wins.
+    async fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), ServerCoreError>;
+    /// Insert a follower, or overwrite the inboxes of an existing one.
+    async fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), ServerCoreError>;
+    /// Returns `false` if the actor wasn't following the namespace.
+    async fn delete_follower(
+        &self,
+        namespace_id: &str,
+        actor: &str,
+    ) -> Result<bool, ServerCoreError>;
+    /// List a namespace's followers, oldest first.
+    async fn list_followers(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<FollowerInfo>, ServerCoreError>;
+    async fn count_followers(&self, namespace_id: &str) -> Result<u64, ServerCoreError>;
+    /// Insert an item, or overwrite the mutable fields (`audience`, `url`,
+    /// `title`, `summary`, `updated_at`) of the one with its object key.
+    async fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), ServerCoreError>;
+    /// List a namespace's outbox, newest `published_at` first.
+    async fn list_outbox_items(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<OutboxItem>, ServerCoreError>;
+    /// Returns `false` if there was no item for the object key.
+    async fn delete_outbox_item(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+    ) -> Result<bool, ServerCoreError>;
+}
+
+/// Append-only log of security-relevant events. Entries are never updated;
+/// the only removal is retention pruning.
+pub trait AuditLogStore: Send + Sync {
+    async fn append_audit_event(&self, event: &AuditEvent) -> Result<(), ServerCoreError>;
+    /// List a scope's events, newest first, created strictly before `before`
+    /// when given.
+    async fn list_audit_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError>;
+    /// Delete every event created before `cutoff`. Returns how many went.
+    async fn prune_audit_events(&self, cutoff: i64) -> Result<u64, ServerCoreError>;
+}
+
 pub trait SessionStore: Send + Sync {
     async fn create_session(
         &self,
@@ -318,6 +568,8 @@ pub trait ObjectMetaStore: Send + Sync {
         namespace_id: Option<&str>,
     ) -> Result<(), ServerCoreError>;
     async fn get_usage_totals(&self, user_id: &str) -> Result<UsageTotals, ServerCoreError>;
+    /// Every usage event recorded for a user, oldest first.
+    async fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, ServerCoreError>;
     async fn get_namespace_usage_totals(
         &self,
         user_id: &str,
@@ -362,6 +614,31 @@ pub trait BlobStore: Send + Sync {
     ) -> Result<Option<Vec<u8>>, ServerCoreError>;
     async fn list_by_prefix(&self, prefix: &str) -> Result<Vec<String>, ServerCoreError>;
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError>;
+    /// Like [`list_by_prefix`](Self::list_by_prefix), with size and age where
+    /// the backend has them. The default reports bare keys.
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        Ok(self
+            .list_by_prefix(prefix)
+            .await?
+            .into_iter()
+            .map(|key| BlobEntry {
+                key,
+                size_bytes: None,
+                last_modified: None,
+            })
+            .collect())
+    }
+    /// Multipart uploads under `prefix` still waiting to be completed or
+    /// aborted. Backends that can't enumerate them report none.
+    async fn list_multipart_uploads(
+        &self,
+        _prefix: &str,
+    ) -> Result<Vec<PendingMultipartUpload>, ServerCoreError> {
+        Ok(Vec::new())
+    }
 }
 
 pub trait Mailer: Send + Sync {
@@ -371,6 +648,31 @@ pub trait Mailer: Send + Sync {
         magic_link_url: &str,
         verification_code: &str,
     ) -> Result<(), ServerCoreError>;
+    /// Invite `to_email` to collaborate on a namespace with `role`.
+    async fn send_namespace_invite(
+        &self,
+        to_email: &str,
+        inviter_email: &str,
+        namespace_name: &str,
+        role: NamespaceRole,
+        accept_url: &str,
+    ) -> Result<(), ServerCoreError>;
+    /// Ask the account holder to confirm deleting their account by opening
+    /// `confirm_url`.
+    async fn send_account_deletion_confirmation(
+        &self,
+        to_email: &str,
+        confirm_url: &str,
+    ) -> Result<(), ServerCoreError>;
+    /// Tell a namespace owner that `author_name` commented on `page`.
+    async fn send_comment_notification(
+        &self,
+        to_email: &str,
+        namespace_name: &str,
+        page: &str,
+        author_name: &str,
+        body: &str,
+    ) -> Result<(), ServerCoreError>;
 }
 
 pub trait RateLimitStore: Send + Sync {
@@ -488,6 +790,47 @@ pub trait JobSink: Send + Sync {
     async fn enqueue(&self, kind: &str, payload: Value) -> Result<(), ServerCoreError>;
 }
 
+/// Sends a webhook delivery to its endpoint.
+pub trait WebhookTransport: Send + Sync {
+    /// POST `body` to `url` and return the response status. Transport-level
+    /// failures (DNS, TLS, timeouts) are errors; any HTTP status is `Ok`.
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError>;
+}
+
+/// Fetches the source page of a Webmention to verify it.
+pub trait WebmentionFetcher: Send + Sync {
+    /// GET `url` and return the response status and at most `max_bytes` of
+    /// the body, decoded lossily as UTF-8. Transport-level failures are
+    /// errors; any HTTP status is `Ok`.
+    async fn fetch(&self, url: &str, max_bytes: usize) -> Result<(u16, String), ServerCoreError>;
+}
+
+/// HTTP client the ActivityPub actor reaches other fediverse servers with.
+/// Callers supply every header, including the request signature.
+pub trait ActivityPubClient: Send + Sync {
+    /// GET `url` and return the response status and at most `max_bytes` of
+    /// the body, decoded lossily as UTF-8. Transport-level failures are
+    /// errors; any HTTP status is `Ok`.
+    async fn get(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        max_bytes: usize,
+    ) -> Result<(u16, String), ServerCoreError>;
+    /// POST `body` to `url` and return the response status.
+    async fn post(
+        &self,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> Result<u16, ServerCoreError>;
+}
+
 /// Storage for the ARK identity index — the `(workspace ARK, file ARK)` →
 /// object key mapping, populated at publish time.
 pub trait ArkIndexStore: Send + Sync {
@@ -526,6 +869,43 @@ pub trait ArkIndexStore: Send + Sync {
         &self,
         workspace_ark: &str,
     ) -> Result<Vec<crate::domain::ArkIndexEntry>, ServerCoreError>;
+
+    /// Record a retained source version for a file ARK. Insert-only: if the
+    /// `(workspace_ark, file_ark, version)` row already exists it is left
+    /// untouched, so `created_at` keeps the first-publish time.
+    async fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError>;
+
+    /// List every retained version of a file ARK, newest first.
+    async fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<crate::domain::ArkVersionEntry>, ServerCoreError>;
+}
+
+/// Destination for a streamed namespace export, one record at a time.
+/// Adapters typically encode each record as an NDJSON line onto a response
+/// body (see [`encode_archive_record`](crate::use_cases::archive::encode_archive_record)).
+pub trait ArchiveSink: Send {
+    async fn write_record(&mut self, record: &ArchiveRecord) -> Result<(), ServerCoreError>;
+}
+
+/// Destination for a streamed account export (see [`ArchiveSink`]).
+pub trait AccountExportSink: Send {
+    async fn write_record(&mut self, record: &AccountExportRecord)
+    -> Result<(), ServerCoreError>;
+}
+
+/// Source of archive records for a namespace import. Returns `Ok(None)` at the
+/// end of the stream.
+pub trait ArchiveSource: Send {
+    async fn next_record(&mut self) -> Result<Option<ArchiveRecord>, ServerCoreError>;
 }
 
 } // cfg_async_trait!
@@ -0,0 +1,22 @@
+-- Personal access tokens: long-lived bearer credentials for automation (CI
+-- publishing, scripts) that don't occupy a device slot.
+--
+-- Only the SHA-256 hex digest of the secret is stored (`token_hash`); the
+-- plaintext is returned once at creation. `scopes` is a JSON array of scope
+-- names (`"read"`, `"publish"`, `"manage"`); `namespace_ids` is a JSON array
+-- restricting the token to those namespaces, or NULL for every namespace the
+-- owner has. `expires_at` is NULL for tokens that never expire.
+
+CREATE TABLE IF NOT EXISTS access_tokens (
+    id            TEXT PRIMARY KEY,
+    user_id       TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name          TEXT NOT NULL,
+    token_hash    TEXT NOT NULL UNIQUE,
+    scopes        TEXT NOT NULL,
+    namespace_ids TEXT,
+    created_at    INTEGER NOT NULL,
+    expires_at    INTEGER,
+    last_used_at  INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_access_tokens_user ON access_tokens(user_id);
@@ -0,0 +1,30 @@
+-- Namespace collaborators. The namespace owner (`namespaces.owner_user_id`)
+-- is implicit; this table only holds the other users with a role on the
+-- namespace. `role` is one of `"owner"`, `"editor"`, `"viewer"`.
+--
+-- Invites are pending memberships addressed to an email. Only the SHA-256
+-- hex digest of the invite secret is stored (`token_hash`); the plaintext
+-- travels in the emailed accept link.
+
+CREATE TABLE IF NOT EXISTS namespace_members (
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    user_id      TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role         TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    PRIMARY KEY (namespace_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_members_user ON namespace_members(user_id);
+
+CREATE TABLE IF NOT EXISTS namespace_invites (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    email        TEXT NOT NULL,
+    role         TEXT NOT NULL,
+    token_hash   TEXT NOT NULL UNIQUE,
+    invited_by   TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    expires_at   INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_invites_namespace ON namespace_invites(namespace_id);
@@ -0,0 +1,40 @@
+-- Outbound webhooks per namespace, and their delivery log.
+--
+-- `events` is a JSON array of event names (`"build_completed"`,
+-- `"audience_changed"`, `"object_deleted"`). `secret` is the HMAC key
+-- deliveries are signed with; unlike token secrets it has to be stored in
+-- plaintext, and is only returned to the owner once, at creation.
+--
+-- A delivery is one event sent to one webhook. `payload` is the exact JSON
+-- body sent on every attempt. `status` is `"pending"`, `"succeeded"` or
+-- `"failed"`; pending deliveries are retried once `next_attempt_at` passes.
+
+CREATE TABLE IF NOT EXISTS namespace_webhooks (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    url          TEXT NOT NULL,
+    events       TEXT NOT NULL,
+    secret       TEXT NOT NULL,
+    created_by   TEXT NOT NULL,
+    created_at   INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_namespace_webhooks_namespace ON namespace_webhooks(namespace_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id              TEXT PRIMARY KEY,
+    webhook_id      TEXT NOT NULL REFERENCES namespace_webhooks(id) ON DELETE CASCADE,
+    namespace_id    TEXT NOT NULL,
+    event           TEXT NOT NULL,
+    payload         TEXT NOT NULL,
+    status          TEXT NOT NULL,
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at INTEGER,
+    response_status INTEGER,
+    last_error      TEXT,
+    created_at      INTEGER NOT NULL,
+    updated_at      INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_namespace ON webhook_deliveries(namespace_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
@@ -0,0 +1,26 @@
+-- Append-only audit log of security-relevant events.
+--
+-- Account events (sign-ins, passkeys, access tokens) set `user_id`;
+-- namespace events (audience changes and unlocks, domains) set
+-- `namespace_id`. `actor_user_id` is who did it, NULL for anonymous readers
+-- unlocking a password audience. `target` names the thing acted on (a device,
+-- passkey, token, audience or domain) and `details` is a JSON object, or
+-- NULL. Rows are never updated; retention pruning is the only delete.
+--
+-- There are deliberately no foreign keys: the log outlives the namespaces
+-- and credentials it mentions.
+
+CREATE TABLE IF NOT EXISTS audit_events (
+    id            TEXT PRIMARY KEY,
+    action        TEXT NOT NULL,
+    actor_user_id TEXT,
+    user_id       TEXT,
+    namespace_id  TEXT,
+    target        TEXT,
+    details       TEXT,
+    created_at    INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_user ON audit_events(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_namespace ON audit_events(namespace_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_events_created ON audit_events(created_at);
@@ -0,0 +1,25 @@
+-- Webmentions received for pages in a namespace's public audiences.
+--
+-- `target` is the URL the sender mentioned and `object_key` the published
+-- page it resolved to. `status` is `"unverified"` until the source has been
+-- fetched, then `"pending"` (it links to the target) or `"invalid"`; the
+-- owner moves pending mentions to `"approved"` or `"rejected"`. Only
+-- approved mentions are rendered. `source_title` is the source page's
+-- `<title>`, captured on verification.
+--
+-- Re-sending the same source/target pair updates the existing row.
+
+CREATE TABLE IF NOT EXISTS webmentions (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    source       TEXT NOT NULL,
+    target       TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    status       TEXT NOT NULL,
+    source_title TEXT,
+    created_at   INTEGER NOT NULL,
+    updated_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webmentions_pair ON webmentions(namespace_id, source, target);
+CREATE INDEX IF NOT EXISTS idx_webmentions_status ON webmentions(namespace_id, status, created_at);
@@ -0,0 +1,24 @@
+-- Reader comments on the pages of a namespace's gated audiences.
+--
+-- Readers authenticate with the audience token they opened the audience
+-- with; `token_id` records which one. `object_key` is the rendered page and
+-- `source_key` its markdown source, which `diaryx publish` files approved
+-- comments next to. `status` starts `"pending"`; the owner moves comments
+-- to `"approved"` (shown to the audience) or `"rejected"`.
+
+CREATE TABLE IF NOT EXISTS comments (
+    id           TEXT PRIMARY KEY,
+    namespace_id TEXT NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+    audience     TEXT NOT NULL,
+    object_key   TEXT NOT NULL,
+    source_key   TEXT,
+    author_name  TEXT NOT NULL,
+    body         TEXT NOT NULL,
+    status       TEXT NOT NULL,
+    token_id     TEXT NOT NULL,
+    created_at   INTEGER NOT NULL,
+    updated_at   INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_page ON comments(namespace_id, object_key, status, created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_status ON comments(namespace_id, status, created_at);
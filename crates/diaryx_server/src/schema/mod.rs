@@ -48,10 +48,50 @@ pub const MIGRATIONS: &[Migration] = &[
         name: "ark_source_key",
         sql: include_str!("0006_ark_source_key.sql"),
     },
+    Migration {
+        version: 7,
+        name: "ark_versions",
+        sql: include_str!("0007_ark_versions.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "access_tokens",
+        sql: include_str!("0008_access_tokens.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "namespace_members",
+        sql: include_str!("0009_namespace_members.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "webhooks",
+        sql: include_str!("0010_webhooks.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "audit_log",
+        sql: include_str!("0011_audit_log.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "webmentions",
+        sql: include_str!("0012_webmentions.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "activitypub",
+        sql: include_str!("0013_activitypub.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "comments",
+        sql: include_str!("0014_comments.sql"),
+    },
 ];
 
 /// The version number of the latest migration.
-pub const CURRENT_VERSION: u32 = 6;
+pub const CURRENT_VERSION: u32 = 14;
 
 #[cfg(test)]
 mod tests {
@@ -82,20 +122,26 @@ mod tests {
         }
 
         let expected_tables = [
+            "access_tokens",
             "ark_index",
+            "ark_versions",
             "auth_sessions",
             "custom_domains",
             "devices",
             "magic_tokens",
             "namespace_audiences",
+            "namespace_invites",
+            "namespace_members",
             "namespace_objects",
             "namespace_sessions",
+            "namespace_webhooks",
             "namespaces",
             "passkey_challenges",
             "passkey_credentials",
             "usage_events",
             "user_ai_usage_monthly",
             "users",
+            "webhook_deliveries",
         ];
 
         let tables: Vec<String> = conn
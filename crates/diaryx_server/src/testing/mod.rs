@@ -10,7 +10,10 @@
 //! ## Scope
 //!
 //! - Supported: namespace + audience + object CRUD, blob put/get/exists/delete,
-//!   usage recording and totals.
+//!   usage recording and totals, the ARK index and its retained versions,
+//!   personal access tokens, namespace members and invites, webhooks and
+//!   their deliveries, webmentions, reader comments, ActivityPub actor keys, followers and
+//!   outboxes, the audit log.
 //! - Not yet supported: multipart uploads, range reads, listing by prefix,
 //!   custom domains. These `todo!()` rather than returning a stub, so tests
 //!   that depend on them fail loudly rather than silently passing.
@@ -38,10 +41,16 @@ use std::sync::Mutex;
 use async_trait::async_trait;
 
 use crate::domain::{
-    AudienceInfo, CustomDomainInfo, GateRecord, NamespaceInfo, ObjectMeta, UsageTotals,
+    AccessTokenInfo, ActorKeyPair, ArkIndexEntry, ArkVersionEntry, AudienceInfo, AuditEvent,
+    AuditScope, CommentInfo, CommentStatus, CustomDomainInfo, FollowerInfo, GateRecord,
+    NamespaceInfo, NamespaceInviteInfo, NamespaceMemberInfo, ObjectMeta, OutboxItem, UsageEvent,
+    UsageTotals, WebhookDeliveryInfo, WebhookDeliveryStatus, WebhookInfo, WebmentionInfo,
+    WebmentionStatus,
 };
 use crate::ports::{
-    BlobStore, MultipartCompletedPart, NamespaceStore, ObjectMetaStore, ServerCoreError,
+    AccessTokenStore, ActivityPubStore, ArkIndexStore, AuditLogStore, BlobEntry, BlobStore,
+    CommentStore, MultipartCompletedPart, NamespaceMemberStore, NamespaceStore, ObjectMetaStore,
+    ServerCoreError, WebhookStore, WebmentionStore,
 };
 
 // ---------------------------------------------------------------------------
@@ -264,6 +273,8 @@ pub struct InMemoryObjectMetaStore {
     usage: Mutex<HashMap<String, UsageTotals>>,
     /// `(user_id, namespace_id) -> totals`
     namespace_usage: Mutex<HashMap<(String, String), UsageTotals>>,
+    /// `(user_id, event)` in recording order. `recorded_at` is a counter.
+    events: Mutex<Vec<(String, UsageEvent)>>,
 }
 
 impl InMemoryObjectMetaStore {
@@ -376,6 +387,19 @@ impl ObjectMetaStore for InMemoryObjectMetaStore {
         amount: u64,
         namespace_id: Option<&str>,
     ) -> Result<(), ServerCoreError> {
+        {
+            let mut events = self.events.lock().unwrap();
+            let recorded_at = events.len() as i64;
+            events.push((
+                user_id.to_string(),
+                UsageEvent {
+                    event_type: event_type.to_string(),
+                    amount,
+                    namespace_id: namespace_id.map(str::to_string),
+                    recorded_at,
+                },
+            ));
+        }
         let field = match event_type {
             "bytes_in" => |t: &mut UsageTotals, a: u64| t.bytes_in += a,
             "bytes_out" => |t: &mut UsageTotals, a: u64| t.bytes_out += a,
@@ -403,6 +427,17 @@ impl ObjectMetaStore for InMemoryObjectMetaStore {
         Ok(())
     }
 
+    async fn list_usage_events(&self, user_id: &str) -> Result<Vec<UsageEvent>, ServerCoreError> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(owner, _)| owner == user_id)
+            .map(|(_, event)| event.clone())
+            .collect())
+    }
+
     async fn get_usage_totals(&self, user_id: &str) -> Result<UsageTotals, ServerCoreError> {
         Ok(self
             .usage
@@ -569,6 +604,26 @@ impl BlobStore for InMemoryBlobStore {
         Ok(keys)
     }
 
+    async fn list_entries_by_prefix(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<BlobEntry>, ServerCoreError> {
+        let mut entries: Vec<BlobEntry> = self
+            .blobs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(k, bytes)| BlobEntry {
+                key: k.clone(),
+                size_bytes: Some(bytes.len() as u64),
+                last_modified: None,
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(entries)
+    }
+
     async fn delete_by_prefix(&self, prefix: &str) -> Result<usize, ServerCoreError> {
         let mut map = self.blobs.lock().unwrap();
         let to_remove: Vec<String> = map
@@ -582,3 +637,877 @@ impl BlobStore for InMemoryBlobStore {
         Ok(to_remove.len())
     }
 }
+
+// ---------------------------------------------------------------------------
+// ArkIndexStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`ArkIndexStore`] implementation. `updated_at` and
+/// `created_at` are insertion counters rather than wall-clock times, so
+/// ordering is deterministic in tests.
+#[derive(Default)]
+pub struct InMemoryArkIndexStore {
+    /// `(workspace_ark, file_ark) -> entry`
+    entries: Mutex<HashMap<(String, String), ArkIndexEntry>>,
+    versions: Mutex<Vec<ArkVersionEntry>>,
+}
+
+impl InMemoryArkIndexStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ArkIndexStore for InMemoryArkIndexStore {
+    async fn upsert_ark(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        object_key: &str,
+        audience: Option<&str>,
+        source_key: Option<&str>,
+    ) -> Result<(), ServerCoreError> {
+        let mut map = self.entries.lock().unwrap();
+        let updated_at = map.len() as i64 + 1;
+        map.insert(
+            (workspace_ark.to_string(), file_ark.to_string()),
+            ArkIndexEntry {
+                workspace_ark: workspace_ark.to_string(),
+                file_ark: file_ark.to_string(),
+                object_key: object_key.to_string(),
+                audience: audience.map(str::to_string),
+                source_key: source_key.map(str::to_string),
+                updated_at,
+            },
+        );
+        Ok(())
+    }
+
+    async fn resolve_ark(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Option<ArkIndexEntry>, ServerCoreError> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&(workspace_ark.to_string(), file_ark.to_string()))
+            .cloned())
+    }
+
+    async fn get_ark_owner(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        Ok(self
+            .resolve_ark(workspace_ark, file_ark)
+            .await?
+            .map(|e| e.object_key))
+    }
+
+    async fn list_ark_entries(
+        &self,
+        workspace_ark: &str,
+    ) -> Result<Vec<ArkIndexEntry>, ServerCoreError> {
+        let mut entries: Vec<ArkIndexEntry> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| e.workspace_ark == workspace_ark)
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| a.file_ark.cmp(&b.file_ark));
+        Ok(entries)
+    }
+
+    async fn record_ark_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError> {
+        let mut versions = self.versions.lock().unwrap();
+        let exists = versions.iter().any(|v| {
+            v.workspace_ark == workspace_ark && v.file_ark == file_ark && v.version == version
+        });
+        if !exists {
+            let created_at = versions.len() as i64 + 1;
+            versions.push(ArkVersionEntry {
+                workspace_ark: workspace_ark.to_string(),
+                file_ark: file_ark.to_string(),
+                version: version.to_string(),
+                object_key: object_key.to_string(),
+                created_at,
+            });
+        }
+        Ok(())
+    }
+
+    async fn list_ark_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<ArkVersionEntry>, ServerCoreError> {
+        let mut versions: Vec<ArkVersionEntry> = self
+            .versions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|v| v.workspace_ark == workspace_ark && v.file_ark == file_ark)
+            .cloned()
+            .collect();
+        versions.sort_by_key(|v| std::cmp::Reverse(v.created_at));
+        Ok(versions)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AccessTokenStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`AccessTokenStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryAccessTokenStore {
+    /// `token_hash -> token`
+    tokens: Mutex<HashMap<String, AccessTokenInfo>>,
+}
+
+impl InMemoryAccessTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AccessTokenStore for InMemoryAccessTokenStore {
+    async fn create_access_token(
+        &self,
+        token: &AccessTokenInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        if tokens.contains_key(token_hash) {
+            return Err(ServerCoreError::conflict("Access token already exists"));
+        }
+        tokens.insert(token_hash.to_string(), token.clone());
+        Ok(())
+    }
+
+    async fn get_access_token_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<AccessTokenInfo>, ServerCoreError> {
+        Ok(self.tokens.lock().unwrap().get(token_hash).cloned())
+    }
+
+    async fn list_access_tokens(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<AccessTokenInfo>, ServerCoreError> {
+        let mut tokens: Vec<AccessTokenInfo> = self
+            .tokens
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|t| t.user_id == user_id)
+            .cloned()
+            .collect();
+        tokens.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+        Ok(tokens)
+    }
+
+    async fn delete_access_token(
+        &self,
+        user_id: &str,
+        token_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let before = tokens.len();
+        tokens.retain(|_, t| !(t.id == token_id && t.user_id == user_id));
+        Ok(tokens.len() != before)
+    }
+
+    async fn touch_access_token(
+        &self,
+        token_id: &str,
+        used_at: i64,
+    ) -> Result<(), ServerCoreError> {
+        if let Some(token) = self
+            .tokens
+            .lock()
+            .unwrap()
+            .values_mut()
+            .find(|t| t.id == token_id)
+        {
+            token.last_used_at = Some(used_at);
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// NamespaceMemberStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`NamespaceMemberStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryNamespaceMemberStore {
+    /// `(namespace_id, user_id) -> member`
+    members: Mutex<HashMap<(String, String), NamespaceMemberInfo>>,
+    /// `token_hash -> invite`
+    invites: Mutex<HashMap<String, NamespaceInviteInfo>>,
+}
+
+impl InMemoryNamespaceMemberStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl NamespaceMemberStore for InMemoryNamespaceMemberStore {
+    async fn get_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<Option<NamespaceMemberInfo>, ServerCoreError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .get(&(namespace_id.to_string(), user_id.to_string()))
+            .cloned())
+    }
+
+    async fn list_members(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let mut members: Vec<NamespaceMemberInfo> = self
+            .members
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|m| m.namespace_id == namespace_id)
+            .cloned()
+            .collect();
+        members.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then(a.user_id.cmp(&b.user_id))
+        });
+        Ok(members)
+    }
+
+    async fn list_user_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let mut members: Vec<NamespaceMemberInfo> = self
+            .members
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|m| m.user_id == user_id)
+            .cloned()
+            .collect();
+        members.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then(a.namespace_id.cmp(&b.namespace_id))
+        });
+        Ok(members)
+    }
+
+    async fn upsert_member(&self, member: &NamespaceMemberInfo) -> Result<(), ServerCoreError> {
+        let mut members = self.members.lock().unwrap();
+        let key = (member.namespace_id.clone(), member.user_id.clone());
+        let created_at = members
+            .get(&key)
+            .map(|m| m.created_at)
+            .unwrap_or(member.created_at);
+        members.insert(
+            key,
+            NamespaceMemberInfo {
+                created_at,
+                ..member.clone()
+            },
+        );
+        Ok(())
+    }
+
+    async fn remove_member(
+        &self,
+        namespace_id: &str,
+        user_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .remove(&(namespace_id.to_string(), user_id.to_string()))
+            .is_some())
+    }
+
+    async fn create_invite(
+        &self,
+        invite: &NamespaceInviteInfo,
+        token_hash: &str,
+    ) -> Result<(), ServerCoreError> {
+        let mut invites = self.invites.lock().unwrap();
+        if invites.contains_key(token_hash) {
+            return Err(ServerCoreError::conflict("Invite already exists"));
+        }
+        invites.insert(token_hash.to_string(), invite.clone());
+        Ok(())
+    }
+
+    async fn get_invite_by_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<NamespaceInviteInfo>, ServerCoreError> {
+        Ok(self.invites.lock().unwrap().get(token_hash).cloned())
+    }
+
+    async fn list_invites(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError> {
+        let mut invites: Vec<NamespaceInviteInfo> = self
+            .invites
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| i.namespace_id == namespace_id)
+            .cloned()
+            .collect();
+        invites.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+        Ok(invites)
+    }
+
+    async fn delete_invite(
+        &self,
+        namespace_id: &str,
+        invite_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let mut invites = self.invites.lock().unwrap();
+        let before = invites.len();
+        invites.retain(|_, i| !(i.id == invite_id && i.namespace_id == namespace_id));
+        Ok(invites.len() != before)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WebhookStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`WebhookStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryWebhookStore {
+    /// `webhook_id -> (webhook, secret)`
+    webhooks: Mutex<HashMap<String, (WebhookInfo, String)>>,
+    /// `delivery_id -> delivery`
+    deliveries: Mutex<HashMap<String, WebhookDeliveryInfo>>,
+}
+
+impl InMemoryWebhookStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WebhookStore for InMemoryWebhookStore {
+    async fn create_webhook(
+        &self,
+        webhook: &WebhookInfo,
+        secret: &str,
+    ) -> Result<(), ServerCoreError> {
+        let mut webhooks = self.webhooks.lock().unwrap();
+        if webhooks.contains_key(&webhook.id) {
+            return Err(ServerCoreError::conflict("Webhook already exists"));
+        }
+        webhooks.insert(webhook.id.clone(), (webhook.clone(), secret.to_string()));
+        Ok(())
+    }
+
+    async fn get_webhook(&self, webhook_id: &str) -> Result<Option<WebhookInfo>, ServerCoreError> {
+        Ok(self
+            .webhooks
+            .lock()
+            .unwrap()
+            .get(webhook_id)
+            .map(|(webhook, _)| webhook.clone()))
+    }
+
+    async fn get_webhook_secret(
+        &self,
+        webhook_id: &str,
+    ) -> Result<Option<String>, ServerCoreError> {
+        Ok(self
+            .webhooks
+            .lock()
+            .unwrap()
+            .get(webhook_id)
+            .map(|(_, secret)| secret.clone()))
+    }
+
+    async fn list_webhooks(&self, namespace_id: &str) -> Result<Vec<WebhookInfo>, ServerCoreError> {
+        let mut webhooks: Vec<WebhookInfo> = self
+            .webhooks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(w, _)| w.namespace_id == namespace_id)
+            .map(|(w, _)| w.clone())
+            .collect();
+        webhooks.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(webhooks)
+    }
+
+    async fn delete_webhook(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let mut webhooks = self.webhooks.lock().unwrap();
+        let removed = match webhooks.get(webhook_id) {
+            Some((w, _)) if w.namespace_id == namespace_id => {
+                webhooks.remove(webhook_id);
+                true
+            }
+            _ => false,
+        };
+        if removed {
+            self.deliveries
+                .lock()
+                .unwrap()
+                .retain(|_, d| d.webhook_id != webhook_id);
+        }
+        Ok(removed)
+    }
+
+    async fn upsert_delivery(&self, delivery: &WebhookDeliveryInfo) -> Result<(), ServerCoreError> {
+        self.deliveries
+            .lock()
+            .unwrap()
+            .insert(delivery.id.clone(), delivery.clone());
+        Ok(())
+    }
+
+    async fn get_delivery(
+        &self,
+        delivery_id: &str,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError> {
+        Ok(self.deliveries.lock().unwrap().get(delivery_id).cloned())
+    }
+
+    async fn list_deliveries(
+        &self,
+        namespace_id: &str,
+        webhook_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let mut deliveries: Vec<WebhookDeliveryInfo> = self
+            .deliveries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|d| d.namespace_id == namespace_id)
+            .filter(|d| webhook_id.is_none_or(|id| d.webhook_id == id))
+            .cloned()
+            .collect();
+        deliveries.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        deliveries.truncate(limit as usize);
+        Ok(deliveries)
+    }
+
+    async fn list_due_deliveries(
+        &self,
+        now: i64,
+        limit: u32,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        let mut due: Vec<WebhookDeliveryInfo> = self
+            .deliveries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|d| d.status == WebhookDeliveryStatus::Pending)
+            .filter(|d| d.next_attempt_at.is_some_and(|at| at <= now))
+            .cloned()
+            .collect();
+        due.sort_by(|a, b| {
+            a.next_attempt_at
+                .cmp(&b.next_attempt_at)
+                .then(a.id.cmp(&b.id))
+        });
+        due.truncate(limit as usize);
+        Ok(due)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// WebmentionStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`WebmentionStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryWebmentionStore {
+    /// Keyed by mention id.
+    mentions: Mutex<HashMap<String, WebmentionInfo>>,
+}
+
+impl InMemoryWebmentionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl WebmentionStore for InMemoryWebmentionStore {
+    async fn upsert_webmention(&self, mention: &WebmentionInfo) -> Result<(), ServerCoreError> {
+        let mut mentions = self.mentions.lock().unwrap();
+        match mentions.get_mut(&mention.id) {
+            Some(existing) => {
+                existing.object_key = mention.object_key.clone();
+                existing.status = mention.status;
+                existing.source_title = mention.source_title.clone();
+                existing.updated_at = mention.updated_at;
+            }
+            None => {
+                mentions.insert(mention.id.clone(), mention.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_webmention(&self, id: &str) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        Ok(self.mentions.lock().unwrap().get(id).cloned())
+    }
+
+    async fn find_webmention(
+        &self,
+        namespace_id: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<Option<WebmentionInfo>, ServerCoreError> {
+        Ok(self
+            .mentions
+            .lock()
+            .unwrap()
+            .values()
+            .find(|m| m.namespace_id == namespace_id && m.source == source && m.target == target)
+            .cloned())
+    }
+
+    async fn list_webmentions(
+        &self,
+        namespace_id: &str,
+        status: Option<WebmentionStatus>,
+        limit: u32,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError> {
+        let mut mentions: Vec<WebmentionInfo> = self
+            .mentions
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|m| m.namespace_id == namespace_id)
+            .filter(|m| status.is_none_or(|s| m.status == s))
+            .cloned()
+            .collect();
+        mentions.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        mentions.truncate(limit as usize);
+        Ok(mentions)
+    }
+
+    async fn delete_webmention(
+        &self,
+        namespace_id: &str,
+        id: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let mut mentions = self.mentions.lock().unwrap();
+        if mentions
+            .get(id)
+            .is_some_and(|m| m.namespace_id == namespace_id)
+        {
+            mentions.remove(id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CommentStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`CommentStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryCommentStore {
+    /// Keyed by comment id.
+    comments: Mutex<HashMap<String, CommentInfo>>,
+}
+
+impl InMemoryCommentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CommentStore for InMemoryCommentStore {
+    async fn upsert_comment(&self, comment: &CommentInfo) -> Result<(), ServerCoreError> {
+        let mut comments = self.comments.lock().unwrap();
+        match comments.get_mut(&comment.id) {
+            Some(existing) => {
+                existing.status = comment.status;
+                existing.updated_at = comment.updated_at;
+            }
+            None => {
+                comments.insert(comment.id.clone(), comment.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_comment(&self, id: &str) -> Result<Option<CommentInfo>, ServerCoreError> {
+        Ok(self.comments.lock().unwrap().get(id).cloned())
+    }
+
+    async fn list_comments(
+        &self,
+        namespace_id: &str,
+        object_key: Option<&str>,
+        status: Option<CommentStatus>,
+        limit: u32,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError> {
+        let mut comments: Vec<CommentInfo> = self
+            .comments
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|c| c.namespace_id == namespace_id)
+            .filter(|c| object_key.is_none_or(|k| c.object_key == k))
+            .filter(|c| status.is_none_or(|s| c.status == s))
+            .cloned()
+            .collect();
+        comments.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        comments.truncate(limit as usize);
+        Ok(comments)
+    }
+
+    async fn delete_comment(&self, namespace_id: &str, id: &str) -> Result<bool, ServerCoreError> {
+        let mut comments = self.comments.lock().unwrap();
+        if comments
+            .get(id)
+            .is_some_and(|c| c.namespace_id == namespace_id)
+        {
+            comments.remove(id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ActivityPubStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`ActivityPubStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryActivityPubStore {
+    /// Keyed by namespace id.
+    keys: Mutex<HashMap<String, ActorKeyPair>>,
+    /// In follow order.
+    followers: Mutex<Vec<FollowerInfo>>,
+    /// Keyed by `(namespace_id, object_key)`.
+    outbox: Mutex<HashMap<(String, String), OutboxItem>>,
+}
+
+impl InMemoryActivityPubStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ActivityPubStore for InMemoryActivityPubStore {
+    async fn get_actor_key(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Option<ActorKeyPair>, ServerCoreError> {
+        Ok(self.keys.lock().unwrap().get(namespace_id).cloned())
+    }
+
+    async fn insert_actor_key(&self, key: &ActorKeyPair) -> Result<(), ServerCoreError> {
+        self.keys
+            .lock()
+            .unwrap()
+            .entry(key.namespace_id.clone())
+            .or_insert_with(|| key.clone());
+        Ok(())
+    }
+
+    async fn upsert_follower(&self, follower: &FollowerInfo) -> Result<(), ServerCoreError> {
+        let mut followers = self.followers.lock().unwrap();
+        match followers
+            .iter_mut()
+            .find(|f| f.namespace_id == follower.namespace_id && f.actor == follower.actor)
+        {
+            Some(existing) => {
+                existing.inbox = follower.inbox.clone();
+                existing.shared_inbox = follower.shared_inbox.clone();
+            }
+            None => followers.push(follower.clone()),
+        }
+        Ok(())
+    }
+
+    async fn delete_follower(
+        &self,
+        namespace_id: &str,
+        actor: &str,
+    ) -> Result<bool, ServerCoreError> {
+        let mut followers = self.followers.lock().unwrap();
+        let before = followers.len();
+        followers.retain(|f| !(f.namespace_id == namespace_id && f.actor == actor));
+        Ok(followers.len() != before)
+    }
+
+    async fn list_followers(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<FollowerInfo>, ServerCoreError> {
+        Ok(self
+            .followers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|f| f.namespace_id == namespace_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn count_followers(&self, namespace_id: &str) -> Result<u64, ServerCoreError> {
+        Ok(self
+            .followers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|f| f.namespace_id == namespace_id)
+            .count() as u64)
+    }
+
+    async fn upsert_outbox_item(&self, item: &OutboxItem) -> Result<(), ServerCoreError> {
+        let mut outbox = self.outbox.lock().unwrap();
+        let key = (item.namespace_id.clone(), item.object_key.clone());
+        match outbox.get_mut(&key) {
+            Some(existing) => {
+                existing.audience = item.audience.clone();
+                existing.url = item.url.clone();
+                existing.title = item.title.clone();
+                existing.summary = item.summary.clone();
+                existing.updated_at = item.updated_at;
+            }
+            None => {
+                outbox.insert(key, item.clone());
+            }
+        }
+        Ok(())
+    }
+
+    async fn list_outbox_items(
+        &self,
+        namespace_id: &str,
+    ) -> Result<Vec<OutboxItem>, ServerCoreError> {
+        let mut items: Vec<OutboxItem> = self
+            .outbox
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|i| i.namespace_id == namespace_id)
+            .cloned()
+            .collect();
+        items.sort_by(|a, b| {
+            b.published_at
+                .cmp(&a.published_at)
+                .then(a.object_key.cmp(&b.object_key))
+        });
+        Ok(items)
+    }
+
+    async fn delete_outbox_item(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+    ) -> Result<bool, ServerCoreError> {
+        Ok(self
+            .outbox
+            .lock()
+            .unwrap()
+            .remove(&(namespace_id.to_string(), object_key.to_string()))
+            .is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuditLogStore
+// ---------------------------------------------------------------------------
+
+/// Thread-safe, in-memory [`AuditLogStore`] implementation.
+#[derive(Default)]
+pub struct InMemoryAuditLogStore {
+    /// Events in append order.
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+impl InMemoryAuditLogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl AuditLogStore for InMemoryAuditLogStore {
+    async fn append_audit_event(&self, event: &AuditEvent) -> Result<(), ServerCoreError> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+
+    async fn list_audit_events(
+        &self,
+        scope: &AuditScope,
+        before: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        let mut events: Vec<AuditEvent> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|e| match scope {
+                AuditScope::User(id) => e.user_id.as_deref() == Some(id.as_str()),
+                AuditScope::Namespace(id) => e.namespace_id.as_deref() == Some(id.as_str()),
+            })
+            .filter(|e| before.is_none_or(|b| e.created_at < b))
+            .cloned()
+            .collect();
+        // Stable, so same-second events stay newest-appended first.
+        events.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+        events.truncate(limit as usize);
+        Ok(events)
+    }
+
+    async fn prune_audit_events(&self, cutoff: i64) -> Result<u64, ServerCoreError> {
+        let mut events = self.events.lock().unwrap();
+        let before = events.len();
+        events.retain(|e| e.created_at >= cutoff);
+        Ok((before - events.len()) as u64)
+    }
+}
@@ -0,0 +1,559 @@
+//! Personal access tokens: long-lived, scoped bearer credentials for
+//! automation (CI publishing, scripts).
+//!
+//! A device session is tied to a device slot and can do anything its user
+//! can. An access token instead carries a set of [`TokenScope`]s, optionally
+//! a list of namespaces, and an optional expiry. Tokens are recognised by the
+//! [`ACCESS_TOKEN_PREFIX`] and validated by
+//! [`SessionValidationService`](crate::use_cases::auth::SessionValidationService)
+//! when it is given an [`AccessTokenStore`]; the HTTP layer then checks each
+//! request against [`authorize_request`].
+
+use crate::domain::{AccessTokenInfo, AuditAction, TokenScope};
+use crate::ports::{AccessTokenStore, NamespaceStore, ServerCoreError};
+use crate::use_cases::audit::AuditRecorder;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Prefix every access token secret starts with. Lets the auth layer tell
+/// tokens from device sessions without a lookup, and makes leaked tokens
+/// easy to spot with secret scanners.
+pub const ACCESS_TOKEN_PREFIX: &str = "dxp_";
+
+/// Maximum number of access tokens a user can hold at once.
+pub const MAX_ACCESS_TOKENS_PER_USER: usize = 25;
+
+/// Maximum lifetime that can be requested for a token, in days.
+pub const MAX_ACCESS_TOKEN_EXPIRY_DAYS: u32 = 365;
+
+const MAX_NAME_LEN: usize = 64;
+
+/// True if `token` has the shape of an access token (rather than a device
+/// session token).
+pub fn is_access_token(token: &str) -> bool {
+    token.starts_with(ACCESS_TOKEN_PREFIX)
+}
+
+/// SHA-256 hex digest of a token secret — the value stores key tokens by.
+pub fn hash_access_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn generate_access_token() -> String {
+    format!(
+        "{ACCESS_TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateAccessTokenRequest {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    /// Restrict the token to these namespaces. Omit for every namespace.
+    #[serde(default)]
+    pub namespace_ids: Option<Vec<String>>,
+    /// Days until the token expires. Omit for a token that never expires.
+    #[serde(default)]
+    pub expires_in_days: Option<u32>,
+}
+
+/// A freshly created token. `token` is the only time the secret is revealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedAccessToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: AccessTokenInfo,
+}
+
+pub struct AccessTokenService<'a> {
+    token_store: &'a dyn AccessTokenStore,
+    namespace_store: &'a dyn NamespaceStore,
+    audit: Option<AuditRecorder<'a>>,
+}
+
+impl<'a> AccessTokenService<'a> {
+    pub fn new(
+        token_store: &'a dyn AccessTokenStore,
+        namespace_store: &'a dyn NamespaceStore,
+    ) -> Self {
+        Self {
+            token_store,
+            namespace_store,
+            audit: None,
+        }
+    }
+
+    /// Record token creation and revocation in the user's audit log.
+    pub fn with_audit(mut self, audit: AuditRecorder<'a>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Create a token for `user_id`. Every namespace the token is restricted
+    /// to must exist and be owned by the user.
+    pub async fn create(
+        &self,
+        user_id: &str,
+        request: CreateAccessTokenRequest,
+    ) -> Result<CreatedAccessToken, ServerCoreError> {
+        let name = request.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(ServerCoreError::invalid_input(format!(
+                "Token name must be 1-{MAX_NAME_LEN} characters"
+            )));
+        }
+
+        let mut scopes = request.scopes;
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(ServerCoreError::invalid_input(
+                "A token needs at least one scope",
+            ));
+        }
+
+        let namespace_ids = match request.namespace_ids {
+            Some(mut ids) => {
+                ids.sort();
+                ids.dedup();
+                if ids.is_empty() {
+                    return Err(ServerCoreError::invalid_input(
+                        "namespace_ids must not be empty; omit it to allow every namespace",
+                    ));
+                }
+                for id in &ids {
+                    let owned = self
+                        .namespace_store
+                        .get_namespace(id)
+                        .await?
+                        .is_some_and(|ns| ns.owner_user_id == user_id);
+                    if !owned {
+                        return Err(ServerCoreError::not_found(format!(
+                            "Namespace not found: {id}"
+                        )));
+                    }
+                }
+                Some(ids)
+            }
+            None => None,
+        };
+
+        if let Some(days) = request.expires_in_days
+            && !(1..=MAX_ACCESS_TOKEN_EXPIRY_DAYS).contains(&days)
+        {
+            return Err(ServerCoreError::invalid_input(format!(
+                "expires_in_days must be between 1 and {MAX_ACCESS_TOKEN_EXPIRY_DAYS}"
+            )));
+        }
+
+        let existing = self.token_store.list_access_tokens(user_id).await?;
+        if existing.len() >= MAX_ACCESS_TOKENS_PER_USER {
+            return Err(ServerCoreError::conflict(format!(
+                "Access token limit reached ({MAX_ACCESS_TOKENS_PER_USER}); revoke one first"
+            )));
+        }
+
+        let now = Utc::now().timestamp();
+        let info = AccessTokenInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+            scopes,
+            namespace_ids,
+            created_at: now,
+            expires_at: request
+                .expires_in_days
+                .map(|days| now + i64::from(days) * 86_400),
+            last_used_at: None,
+        };
+        let token = generate_access_token();
+        self.token_store
+            .create_access_token(&info, &hash_access_token(&token))
+            .await?;
+        if let Some(audit) = &self.audit {
+            audit
+                .account(
+                    AuditAction::AccessTokenCreated,
+                    user_id,
+                    Some(&info.id),
+                    serde_json::json!({
+                        "name": info.name,
+                        "scopes": info.scopes,
+                        "namespace_ids": info.namespace_ids,
+                    }),
+                )
+                .await;
+        }
+
+        Ok(CreatedAccessToken { token, info })
+    }
+
+    pub async fn list(&self, user_id: &str) -> Result<Vec<AccessTokenInfo>, ServerCoreError> {
+        self.token_store.list_access_tokens(user_id).await
+    }
+
+    pub async fn revoke(&self, user_id: &str, token_id: &str) -> Result<(), ServerCoreError> {
+        if !self
+            .token_store
+            .delete_access_token(user_id, token_id)
+            .await?
+        {
+            return Err(ServerCoreError::not_found("Access token not found"));
+        }
+        if let Some(audit) = &self.audit {
+            audit
+                .account(
+                    AuditAction::AccessTokenRevoked,
+                    user_id,
+                    Some(token_id),
+                    serde_json::Value::Null,
+                )
+                .await;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Route policy
+// ---------------------------------------------------------------------------
+
+/// What a request needs from an access token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredAccess {
+    pub scope: TokenScope,
+    /// The namespace the request acts on, or `None` for account-wide routes
+    /// (listing, creating, importing), which only unrestricted tokens reach.
+    pub namespace_id: Option<String>,
+}
+
+/// Map a request to the access it needs from an access token, or `None` if
+/// the route is never available to access tokens.
+///
+/// Only the namespace API (`/namespaces/...`, with or without the `/api`
+/// mount prefix) is reachable. Account routes — `/auth/*` (including token
+/// and device management), billing, AI, share sessions — need a device
+/// session. This is a pure function so every adapter applies the same policy.
+pub fn required_access(method: &str, path: &str) -> Option<RequiredAccess> {
+    let path = match path.strip_prefix("/api") {
+        Some(rest) if rest.starts_with('/') => rest,
+        _ => path,
+    };
+    let rest = path.strip_prefix("/namespaces")?;
+    if !(rest.is_empty() || rest.starts_with('/')) {
+        return None;
+    }
+    let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+    let read = matches!(method, "GET" | "HEAD");
+
+    let (scope, namespace) = match segments.as_slice() {
+        // List (read) or create (publish — `diaryx publish` mints namespaces).
+        [] => (
+            if read {
+                TokenScope::Read
+            } else {
+                TokenScope::Publish
+            },
+            None,
+        ),
+        ["import"] if !read => (TokenScope::Manage, None),
+        // Namespace metadata and deletion.
+        [ns] => (
+            if read {
+                TokenScope::Read
+            } else {
+                TokenScope::Manage
+            },
+            Some(*ns),
+        ),
+        // Batch fetches are POSTs but only read.
+        [ns, "batch", ..] => (TokenScope::Read, Some(*ns)),
+        // These are GETs too, but expose more than published content: the
+        // audit log says who unlocked what, an export carries gate password
+        // hashes, and the rest list subscriber emails, webhook endpoints
+        // and collaborators. An audience token is a non-expiring link into
+        // a gated audience, so minting one is a grant, not a read.
+        [
+            ns,
+            "audit" | "export" | "webhooks" | "members" | "invites",
+            ..,
+        ]
+        | [ns, "audiences", _, "subscribers" | "token", ..] => (TokenScope::Manage, Some(*ns)),
+        _ if read => (TokenScope::Read, Some(segments[0])),
+        [ns, "objects" | "audiences" | "build", ..] => (TokenScope::Publish, Some(*ns)),
+        [ns, ..] => (TokenScope::Manage, Some(*ns)),
+    };
+
+    Some(RequiredAccess {
+        scope,
+        namespace_id: match namespace {
+            Some(segment) => Some(percent_decode(segment)?),
+            None => None,
+        },
+    })
+}
+
+/// Check a request against an access token's scopes and namespace list.
+pub fn authorize_request(
+    token: &AccessTokenInfo,
+    method: &str,
+    path: &str,
+) -> Result<(), ServerCoreError> {
+    let required = required_access(method, path).ok_or_else(|| {
+        ServerCoreError::permission_denied("This endpoint requires a device session")
+    })?;
+    if !token.grants(required.scope) {
+        return Err(ServerCoreError::permission_denied(format!(
+            "Access token lacks the '{}' scope",
+            required.scope.as_str()
+        )));
+    }
+    let namespace_ok = match &required.namespace_id {
+        Some(id) => token.covers_namespace(id),
+        None => token.namespace_ids.is_none(),
+    };
+    if !namespace_ok {
+        return Err(ServerCoreError::permission_denied(
+            "Access token is not valid for this namespace",
+        ));
+    }
+    Ok(())
+}
+
+/// Decode `%XX` escapes in a path segment. `None` on malformed escapes or
+/// non-UTF-8 output.
+fn percent_decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AccessTokenService, CreateAccessTokenRequest, RequiredAccess, authorize_request,
+        hash_access_token, is_access_token, required_access,
+    };
+    use crate::domain::{AccessTokenInfo, TokenScope};
+    use crate::ports::{AccessTokenStore, NamespaceStore, ServerCoreError};
+    use crate::testing::{InMemoryAccessTokenStore, InMemoryNamespaceStore};
+
+    fn request(
+        scopes: Vec<TokenScope>,
+        namespace_ids: Option<Vec<&str>>,
+    ) -> CreateAccessTokenRequest {
+        CreateAccessTokenRequest {
+            name: "ci".to_string(),
+            scopes,
+            namespace_ids: namespace_ids.map(|ids| ids.into_iter().map(str::to_string).collect()),
+            expires_in_days: Some(30),
+        }
+    }
+
+    fn token(scopes: Vec<TokenScope>, namespace_ids: Option<Vec<&str>>) -> AccessTokenInfo {
+        AccessTokenInfo {
+            id: "tok".to_string(),
+            user_id: "user".to_string(),
+            name: "ci".to_string(),
+            scopes,
+            namespace_ids: namespace_ids.map(|ids| ids.into_iter().map(str::to_string).collect()),
+            created_at: 0,
+            expires_at: None,
+            last_used_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn create_stores_only_the_hash() {
+        let tokens = InMemoryAccessTokenStore::new();
+        let namespaces = InMemoryNamespaceStore::new();
+        namespaces
+            .create_namespace("ns", "user", None)
+            .await
+            .unwrap();
+        let service = AccessTokenService::new(&tokens, &namespaces);
+
+        let created = service
+            .create("user", request(vec![TokenScope::Publish], Some(vec!["ns"])))
+            .await
+            .unwrap();
+        assert!(is_access_token(&created.token));
+        assert!(created.info.expires_at.is_some());
+
+        let stored = tokens
+            .get_access_token_by_hash(&hash_access_token(&created.token))
+            .await
+            .unwrap()
+            .expect("token stored under its hash");
+        assert_eq!(stored.id, created.info.id);
+        assert!(
+            tokens
+                .get_access_token_by_hash(&created.token)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn create_rejects_namespaces_the_user_does_not_own() {
+        let tokens = InMemoryAccessTokenStore::new();
+        let namespaces = InMemoryNamespaceStore::new();
+        namespaces
+            .create_namespace("theirs", "other", None)
+            .await
+            .unwrap();
+        let service = AccessTokenService::new(&tokens, &namespaces);
+
+        let err = service
+            .create(
+                "user",
+                request(vec![TokenScope::Read], Some(vec!["theirs"])),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn create_validates_scopes_and_expiry() {
+        let tokens = InMemoryAccessTokenStore::new();
+        let namespaces = InMemoryNamespaceStore::new();
+        let service = AccessTokenService::new(&tokens, &namespaces);
+
+        let err = service
+            .create("user", request(vec![], None))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        let mut zero_days = request(vec![TokenScope::Read], None);
+        zero_days.expires_in_days = Some(0);
+        let err = service.create("user", zero_days).await.unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn revoke_only_deletes_own_tokens() {
+        let tokens = InMemoryAccessTokenStore::new();
+        let namespaces = InMemoryNamespaceStore::new();
+        let service = AccessTokenService::new(&tokens, &namespaces);
+        let created = service
+            .create("user", request(vec![TokenScope::Read], None))
+            .await
+            .unwrap();
+
+        let err = service.revoke("other", &created.info.id).await.unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+
+        service.revoke("user", &created.info.id).await.unwrap();
+        assert!(service.list("user").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn required_access_maps_publish_routes() {
+        let publish = |ns: &str| {
+            Some(RequiredAccess {
+                scope: TokenScope::Publish,
+                namespace_id: Some(ns.to_string()),
+            })
+        };
+        assert_eq!(
+            required_access("PUT", "/api/namespaces/ns/objects/a/b.html"),
+            publish("ns")
+        );
+        assert_eq!(
+            required_access("DELETE", "/namespaces/ns/audiences/public"),
+            publish("ns")
+        );
+        assert_eq!(
+            required_access("POST", "/namespaces/ns/build"),
+            publish("ns")
+        );
+        assert_eq!(
+            required_access("POST", "/namespaces/a%2Fb/build"),
+            publish("a/b")
+        );
+        assert_eq!(
+            required_access("POST", "/namespaces/ns/batch/objects"),
+            Some(RequiredAccess {
+                scope: TokenScope::Read,
+                namespace_id: Some("ns".to_string()),
+            })
+        );
+        assert_eq!(
+            required_access("POST", "/api/namespaces"),
+            Some(RequiredAccess {
+                scope: TokenScope::Publish,
+                namespace_id: None,
+            })
+        );
+    }
+
+    #[test]
+    fn required_access_raises_sensitive_reads_to_manage() {
+        let manage = Some(RequiredAccess {
+            scope: TokenScope::Manage,
+            namespace_id: Some("ns".to_string()),
+        });
+        for path in [
+            "/namespaces/ns/audit",
+            "/api/namespaces/ns/export",
+            "/namespaces/ns/audiences/members/subscribers",
+            "/namespaces/ns/audiences/members/subscribers/sub-1",
+            "/api/namespaces/ns/audiences/members/token",
+            "/namespaces/ns/webhooks",
+            "/namespaces/ns/webhooks/deliveries",
+            "/namespaces/ns/members",
+            "/namespaces/ns/invites",
+        ] {
+            assert_eq!(required_access("GET", path), manage, "{path}");
+        }
+        assert_eq!(
+            required_access("GET", "/namespaces/ns/objects"),
+            Some(RequiredAccess {
+                scope: TokenScope::Read,
+                namespace_id: Some("ns".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn required_access_denies_account_routes() {
+        assert_eq!(required_access("GET", "/api/auth/me"), None);
+        assert_eq!(required_access("POST", "/api/auth/tokens"), None);
+        assert_eq!(required_access("GET", "/api/usage"), None);
+        assert_eq!(required_access("GET", "/api/namespacesx"), None);
+        assert_eq!(required_access("GET", "/apinamespaces"), None);
+    }
+
+    #[test]
+    fn authorize_request_enforces_scope_and_namespace() {
+        let publish = token(vec![TokenScope::Publish], Some(vec!["ns"]));
+        assert!(authorize_request(&publish, "PUT", "/api/namespaces/ns/objects/k").is_ok());
+        assert!(authorize_request(&publish, "GET", "/api/namespaces/ns").is_ok());
+        assert!(authorize_request(&publish, "PUT", "/api/namespaces/other/objects/k").is_err());
+        assert!(authorize_request(&publish, "DELETE", "/api/namespaces/ns").is_err());
+        // Namespace-restricted tokens can't reach account-wide routes.
+        assert!(authorize_request(&publish, "GET", "/api/namespaces").is_err());
+        assert!(authorize_request(&publish, "GET", "/api/auth/tokens").is_err());
+
+        let read = token(vec![TokenScope::Read], None);
+        assert!(authorize_request(&read, "GET", "/api/namespaces").is_ok());
+        assert!(authorize_request(&read, "POST", "/api/namespaces").is_err());
+    }
+}
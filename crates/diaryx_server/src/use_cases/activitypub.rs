@@ -0,0 +1,1414 @@
+//! ActivityPub publishing: every namespace with a public audience is an actor
+//! that Mastodon and other fediverse accounts can follow.
+//!
+//! The actor lives at `{api}/namespaces/{id}/actor` (see [`actor_url`]) and is
+//! found through WebFinger as `acct:{id}@{api host}`. Its outbox lists the
+//! pages `render_site` puts in the feeds of the namespace's public audiences:
+//! `RenderService::with_activitypub` hands them to [`ActivityPubPublisher`]
+//! after each build, which keeps the outbox in the [`ActivityPubStore`] and
+//! enqueues an [`ACTIVITYPUB_DELIVERY_JOB`] on the adapter's [`JobSink`] for
+//! every new page and follower inbox. Running the job calls
+//! [`ActivityPubDelivery::deliver`]. Gated audiences are never exposed, and an
+//! audience that gains a gate drops out of the outbox straight away.
+//!
+//! Requests both ways carry HTTP Signatures (`rsa-sha256` over
+//! `(request-target) host date digest`, which is what Mastodon expects); an
+//! actor's key pair is generated the first time it's needed.
+//! [`ActivityPubInbox`] only acts on activities signed by the actor they
+//! claim to be from. It understands `Follow`, `Undo` of a follow and account
+//! `Delete`, and ignores everything else. Deliveries are attempted once — a
+//! follower whose server is down misses that post, as with a feed reader that
+//! was offline.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::domain::{ActorKeyPair, FollowerInfo, OutboxItem};
+use crate::outbound::{is_internal_host, url_host};
+use crate::ports::{ActivityPubClient, ActivityPubStore, JobSink, NamespaceStore, ServerCoreError};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, NaiveDate, Utc};
+use diaryx_render::page::html_escape;
+use diaryx_render::types::FeedEntry;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{
+    DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding,
+};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+/// [`JobSink`] kind for sending one activity to one inbox. The payload is an
+/// [`ActivityPubDeliveryJob`].
+pub const ACTIVITYPUB_DELIVERY_JOB: &str = "activitypub.deliver";
+
+/// Content type of ActivityPub documents.
+pub const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Content type of WebFinger responses.
+pub const JRD_JSON: &str = "application/jrd+json";
+
+const ACTIVITY_STREAMS: &str = "https://www.w3.org/ns/activitystreams";
+const SECURITY_V1: &str = "https://w3id.org/security/v1";
+const PUBLIC_COLLECTION: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+const ACTOR_KEY_BITS: usize = 2048;
+
+/// Headers every signature must cover.
+const REQUIRED_SIGNED_HEADERS: [&str; 4] = ["(request-target)", "host", "date", "digest"];
+
+/// How far a signed request's `Date` may be from our clock.
+const MAX_CLOCK_SKEW_SECS: i64 = 3600;
+
+/// Most of a remote actor or key document read.
+const MAX_DOCUMENT_BYTES: usize = 256 * 1024;
+
+/// Outbox items served, newest first.
+const OUTBOX_PAGE_SIZE: usize = 50;
+
+/// New pages announced to followers per build. A first build of a large
+/// public audience only announces the newest ones.
+const MAX_ANNOUNCED_PER_BUILD: usize = 20;
+
+const MAX_URL_LEN: usize = 2048;
+
+/// The actor id of `namespace_id`, under the API base URL (e.g.
+/// `https://sync.example.com/api`).
+pub fn actor_url(api_base_url: &str, namespace_id: &str) -> String {
+    format!(
+        "{}/namespaces/{}/actor",
+        api_base_url.trim_end_matches('/'),
+        namespace_id
+    )
+}
+
+/// Payload of an [`ACTIVITYPUB_DELIVERY_JOB`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ActivityPubDeliveryJob {
+    pub namespace_id: String,
+    pub inbox: String,
+    pub activity: Value,
+}
+
+impl ActivityPubDeliveryJob {
+    pub fn from_payload(payload: &Value) -> Result<Self, ServerCoreError> {
+        serde_json::from_value(payload.clone())
+            .map_err(|e| ServerCoreError::invalid_input(format!("Bad ActivityPub job: {e}")))
+    }
+}
+
+/// Query string of the WebFinger endpoint.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebFingerQuery {
+    pub resource: String,
+}
+
+/// A request to an actor's inbox, as the adapter received it.
+pub struct InboxRequest<'r> {
+    /// Path and query the sender requested, e.g.
+    /// `/api/namespaces/n1/actor/inbox`.
+    pub path: &'r str,
+    /// Request headers. Names are matched case-insensitively.
+    pub headers: &'r [(String, String)],
+    pub body: &'r [u8],
+}
+
+impl InboxRequest<'_> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HTTP Signatures
+// ---------------------------------------------------------------------------
+
+/// Split an http(s) URL into its authority (`host[:port]`) and its path and
+/// query.
+fn split_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let rest = rest.split('#').next().unwrap_or_default();
+    Some(match rest.find(['/', '?']) {
+        Some(i) if rest[i..].starts_with('/') => (&rest[..i], &rest[i..]),
+        Some(i) => (&rest[..i], "/"),
+        None => (rest, "/"),
+    })
+}
+
+/// A remote URL the server may fetch or post to: http(s), reasonably short,
+/// and not on this machine or a private network.
+fn validate_remote_url(url: &str) -> Result<(), ServerCoreError> {
+    let host = url_host(url).unwrap_or_default();
+    if host.is_empty()
+        || is_internal_host(host)
+        || url.len() > MAX_URL_LEN
+        || url.chars().any(char::is_whitespace)
+    {
+        return Err(ServerCoreError::invalid_input(format!(
+            "Not a public URL: {url}"
+        )));
+    }
+    Ok(())
+}
+
+/// `Date` header value for a unix timestamp.
+fn http_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+fn body_digest(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+fn generate_actor_key(namespace_id: &str, now: i64) -> Result<ActorKeyPair, ServerCoreError> {
+    let private_key = RsaPrivateKey::new(&mut rand::rngs::OsRng, ACTOR_KEY_BITS)
+        .map_err(|e| ServerCoreError::internal(format!("Actor key generation failed: {e}")))?;
+    let private_key_pem = private_key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| ServerCoreError::internal(e.to_string()))?
+        .to_string();
+    let public_key_pem = private_key
+        .to_public_key()
+        .to_public_key_pem(LineEnding::LF)
+        .map_err(|e| ServerCoreError::internal(e.to_string()))?;
+    Ok(ActorKeyPair {
+        namespace_id: namespace_id.to_string(),
+        public_key_pem,
+        private_key_pem,
+        created_at: now,
+    })
+}
+
+/// The namespace's key pair, generated and stored on first use.
+async fn actor_key(
+    store: &dyn ActivityPubStore,
+    namespace_id: &str,
+) -> Result<ActorKeyPair, ServerCoreError> {
+    if let Some(key) = store.get_actor_key(namespace_id).await? {
+        return Ok(key);
+    }
+    let key = generate_actor_key(namespace_id, Utc::now().timestamp())?;
+    store.insert_actor_key(&key).await?;
+    // Another request may have stored its key first; use whichever won.
+    store
+        .get_actor_key(namespace_id)
+        .await?
+        .ok_or_else(|| ServerCoreError::internal("Actor key vanished after insert"))
+}
+
+/// Headers for a request to `url` signed with `key`: `Host`, `Date`, and for
+/// requests with a body `Digest` and `Content-Type`, plus `Signature`.
+fn signed_headers(
+    key: &ActorKeyPair,
+    key_id: &str,
+    method: &str,
+    url: &str,
+    body: Option<&[u8]>,
+    now: i64,
+) -> Result<Vec<(String, String)>, ServerCoreError> {
+    let (host, path) = split_url(url)
+        .ok_or_else(|| ServerCoreError::invalid_input(format!("Not an http(s) URL: {url}")))?;
+    let mut headers = vec![
+        ("Host".to_string(), host.to_string()),
+        ("Date".to_string(), http_date(now)),
+    ];
+    let mut signed = format!(
+        "(request-target): {} {path}\nhost: {host}\ndate: {}",
+        method.to_ascii_lowercase(),
+        http_date(now)
+    );
+    let mut covered = "(request-target) host date";
+    if let Some(body) = body {
+        let digest = body_digest(body);
+        signed.push_str(&format!("\ndigest: {digest}"));
+        covered = "(request-target) host date digest";
+        headers.push(("Digest".to_string(), digest));
+        headers.push(("Content-Type".to_string(), ACTIVITY_JSON.to_string()));
+    }
+
+    let private_key = RsaPrivateKey::from_pkcs8_pem(&key.private_key_pem)
+        .map_err(|e| ServerCoreError::internal(format!("Bad actor key: {e}")))?;
+    let signature = SigningKey::<Sha256>::new(private_key).sign(signed.as_bytes());
+    headers.push((
+        "Signature".to_string(),
+        format!(
+            "keyId=\"{key_id}\",algorithm=\"rsa-sha256\",headers=\"{covered}\",signature=\"{}\"",
+            BASE64.encode(signature.to_bytes())
+        ),
+    ));
+    Ok(headers)
+}
+
+/// The parts of a `Signature` header we use.
+struct SignatureParams {
+    key_id: String,
+    headers: Vec<String>,
+    signature: Vec<u8>,
+}
+
+/// Parse `keyId="…",headers="…",signature="…"` (the `Signature` header, or an
+/// `Authorization: Signature …` value without its scheme).
+fn parse_signature(value: &str) -> Option<SignatureParams> {
+    let mut params = HashMap::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (name, after) = rest.split_once('=')?;
+        let after = after.strip_prefix('"')?;
+        let (val, after) = after.split_once('"')?;
+        params.insert(name.trim().to_ascii_lowercase(), val);
+        rest = after.trim_start_matches([',', ' ']);
+    }
+    Some(SignatureParams {
+        key_id: params.get("keyid")?.to_string(),
+        headers: params
+            .get("headers")
+            .unwrap_or(&"date")
+            .split_whitespace()
+            .map(|h| h.to_ascii_lowercase())
+            .collect(),
+        signature: BASE64.decode(params.get("signature")?).ok()?,
+    })
+}
+
+fn parse_public_key(pem: &str) -> Option<RsaPublicKey> {
+    RsaPublicKey::from_public_key_pem(pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        .ok()
+}
+
+/// Check an inbox request's signature against the sender's public key.
+fn verify_signature(
+    request: &InboxRequest<'_>,
+    params: &SignatureParams,
+    public_key_pem: &str,
+    now: i64,
+) -> Result<(), ServerCoreError> {
+    if let Some(missing) = REQUIRED_SIGNED_HEADERS
+        .iter()
+        .find(|h| !params.headers.iter().any(|p| p == *h))
+    {
+        return Err(ServerCoreError::permission_denied(format!(
+            "Signature must cover {missing}"
+        )));
+    }
+    let date = request
+        .header("date")
+        .and_then(|d| DateTime::parse_from_rfc2822(d).ok())
+        .ok_or_else(|| ServerCoreError::permission_denied("Missing or malformed Date header"))?;
+    if (date.timestamp() - now).abs() > MAX_CLOCK_SKEW_SECS {
+        return Err(ServerCoreError::permission_denied("Signature has expired"));
+    }
+    let digest_matches = request
+        .header("digest")
+        .and_then(|d| d.split_once('='))
+        .zip(body_digest(request.body).split_once('='))
+        .is_some_and(|((alg, value), (want_alg, want))| {
+            alg.eq_ignore_ascii_case(want_alg) && value == want
+        });
+    if !digest_matches {
+        return Err(ServerCoreError::permission_denied(
+            "Digest does not match the body",
+        ));
+    }
+
+    let mut lines = Vec::with_capacity(params.headers.len());
+    for name in &params.headers {
+        if name == "(request-target)" {
+            lines.push(format!("(request-target): post {}", request.path));
+        } else {
+            let value = request.header(name).ok_or_else(|| {
+                ServerCoreError::permission_denied(format!("Signed header {name} is missing"))
+            })?;
+            lines.push(format!("{name}: {}", value.trim()));
+        }
+    }
+
+    let public_key = parse_public_key(public_key_pem)
+        .ok_or_else(|| ServerCoreError::permission_denied("Unusable signing key"))?;
+    let signature = Signature::try_from(params.signature.as_slice())
+        .map_err(|_| ServerCoreError::permission_denied("Bad signature"))?;
+    VerifyingKey::<Sha256>::new(public_key)
+        .verify(lines.join("\n").as_bytes(), &signature)
+        .map_err(|_| ServerCoreError::permission_denied("Bad signature"))
+}
+
+// ---------------------------------------------------------------------------
+// Documents
+// ---------------------------------------------------------------------------
+
+/// `id` of an embedded object, or the object itself when it's a bare id.
+fn object_id(value: &Value) -> Option<&str> {
+    value
+        .as_str()
+        .or_else(|| value.get("id").and_then(Value::as_str))
+}
+
+/// Parse a frontmatter date: RFC 3339, or a bare `YYYY-MM-DD`.
+fn parse_published(date: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|d| d.timestamp())
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| d.and_utc().timestamp())
+        })
+}
+
+fn note(actor: &str, item: &OutboxItem) -> Value {
+    let mut content = format!("<p><strong>{}</strong></p>", html_escape(&item.title));
+    if !item.summary.is_empty() {
+        content.push_str(&format!("<p>{}</p>", html_escape(&item.summary)));
+    }
+    content.push_str(&format!(
+        "<p><a href=\"{url}\">{url}</a></p>",
+        url = html_escape(&item.url)
+    ));
+    json!({
+        "id": format!("{actor}/notes/{}", item.id),
+        "type": "Note",
+        "attributedTo": actor,
+        "to": [PUBLIC_COLLECTION],
+        "cc": [format!("{actor}/followers")],
+        "published": rfc3339(item.published_at),
+        "url": item.url,
+        "content": content,
+    })
+}
+
+fn create_activity(actor: &str, item: &OutboxItem) -> Value {
+    json!({
+        "@context": ACTIVITY_STREAMS,
+        "id": format!("{actor}/notes/{}/activity", item.id),
+        "type": "Create",
+        "actor": actor,
+        "published": rfc3339(item.published_at),
+        "to": [PUBLIC_COLLECTION],
+        "cc": [format!("{actor}/followers")],
+        "object": note(actor, item),
+    })
+}
+
+/// Names of the namespace's public audiences. `NotFound` when there are none:
+/// such a namespace has no actor.
+async fn require_public_audiences(
+    namespace_store: &dyn NamespaceStore,
+    namespace_id: &str,
+) -> Result<HashSet<String>, ServerCoreError> {
+    let public: HashSet<String> = namespace_store
+        .list_audiences(namespace_id)
+        .await?
+        .into_iter()
+        .filter(|a| a.is_public())
+        .map(|a| a.audience_name)
+        .collect();
+    if public.is_empty() {
+        return Err(ServerCoreError::not_found("Actor not found"));
+    }
+    Ok(public)
+}
+
+// ---------------------------------------------------------------------------
+// Discovery (public)
+// ---------------------------------------------------------------------------
+
+/// Serves a namespace's WebFinger record, actor document, outbox and
+/// followers collection.
+pub struct ActivityPubService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    activitypub_store: &'a dyn ActivityPubStore,
+    api_base_url: &'a str,
+}
+
+impl<'a> ActivityPubService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        activitypub_store: &'a dyn ActivityPubStore,
+        api_base_url: &'a str,
+    ) -> Self {
+        Self {
+            namespace_store,
+            activitypub_store,
+            api_base_url,
+        }
+    }
+
+    /// Resolve `acct:{namespace}@{api host}`, or the actor URL itself, to the
+    /// actor.
+    pub async fn webfinger(&self, resource: &str) -> Result<Value, ServerCoreError> {
+        let host = split_url(self.api_base_url)
+            .map(|(host, _)| host)
+            .unwrap_or_default();
+        let acct = resource.strip_prefix("acct:").unwrap_or(resource);
+        let namespace_id = match acct.rsplit_once('@') {
+            Some((user, domain)) if domain.eq_ignore_ascii_case(host) => user.to_string(),
+            _ => {
+                let prefix = format!("{}/namespaces/", self.api_base_url.trim_end_matches('/'));
+                resource
+                    .strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix("/actor"))
+                    .filter(|id| !id.is_empty() && !id.contains('/'))
+                    .ok_or_else(|| ServerCoreError::not_found("Unknown resource"))?
+                    .to_string()
+            }
+        };
+        self.require_actor(&namespace_id).await?;
+
+        let actor = actor_url(self.api_base_url, &namespace_id);
+        Ok(json!({
+            "subject": format!("acct:{namespace_id}@{host}"),
+            "aliases": [actor],
+            "links": [{ "rel": "self", "type": ACTIVITY_JSON, "href": actor }],
+        }))
+    }
+
+    /// The actor document, with the public key followers verify its
+    /// deliveries against.
+    pub async fn actor(&self, namespace_id: &str) -> Result<Value, ServerCoreError> {
+        let metadata = self.require_actor(namespace_id).await?;
+        let key = actor_key(self.activitypub_store, namespace_id).await?;
+        let name = metadata
+            .as_deref()
+            .and_then(|m| serde_json::from_str::<Value>(m).ok())
+            .and_then(|m| m.get("name").and_then(Value::as_str).map(String::from))
+            .unwrap_or_else(|| namespace_id.to_string());
+
+        let actor = actor_url(self.api_base_url, namespace_id);
+        Ok(json!({
+            "@context": [ACTIVITY_STREAMS, SECURITY_V1],
+            "id": actor,
+            "type": "Person",
+            "preferredUsername": namespace_id,
+            "name": name,
+            "inbox": format!("{actor}/inbox"),
+            "outbox": format!("{actor}/outbox"),
+            "followers": format!("{actor}/followers"),
+            "manuallyApprovesFollowers": false,
+            "discoverable": true,
+            "publicKey": {
+                "id": format!("{actor}#main-key"),
+                "owner": actor,
+                "publicKeyPem": key.public_key_pem,
+            },
+        }))
+    }
+
+    /// The newest pages of the namespace's currently public audiences, as
+    /// `Create` activities.
+    pub async fn outbox(&self, namespace_id: &str) -> Result<Value, ServerCoreError> {
+        self.require_actor(namespace_id).await?;
+        let public = require_public_audiences(self.namespace_store, namespace_id).await?;
+        let items: Vec<OutboxItem> = self
+            .activitypub_store
+            .list_outbox_items(namespace_id)
+            .await?
+            .into_iter()
+            .filter(|item| public.contains(&item.audience))
+            .collect();
+
+        let actor = actor_url(self.api_base_url, namespace_id);
+        let activities: Vec<Value> = items
+            .iter()
+            .take(OUTBOX_PAGE_SIZE)
+            .map(|item| create_activity(&actor, item))
+            .collect();
+        Ok(json!({
+            "@context": ACTIVITY_STREAMS,
+            "id": format!("{actor}/outbox"),
+            "type": "OrderedCollection",
+            "totalItems": items.len(),
+            "orderedItems": activities,
+        }))
+    }
+
+    /// How many accounts follow the namespace. Who they are isn't published.
+    pub async fn followers(&self, namespace_id: &str) -> Result<Value, ServerCoreError> {
+        self.require_actor(namespace_id).await?;
+        let total = self.activitypub_store.count_followers(namespace_id).await?;
+        let actor = actor_url(self.api_base_url, namespace_id);
+        Ok(json!({
+            "@context": ACTIVITY_STREAMS,
+            "id": format!("{actor}/followers"),
+            "type": "OrderedCollection",
+            "totalItems": total,
+        }))
+    }
+
+    /// The namespace's metadata, once it's known to have an actor.
+    async fn require_actor(&self, namespace_id: &str) -> Result<Option<String>, ServerCoreError> {
+        let namespace = self
+            .namespace_store
+            .get_namespace(namespace_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Actor not found"))?;
+        require_public_audiences(self.namespace_store, namespace_id).await?;
+        Ok(namespace.metadata)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Inbox (public, signed)
+// ---------------------------------------------------------------------------
+
+/// The sender of a verified request.
+struct RemoteActor {
+    id: String,
+    inbox: String,
+    shared_inbox: Option<String>,
+}
+
+/// Handles activities posted to a namespace actor's inbox.
+pub struct ActivityPubInbox<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    activitypub_store: &'a dyn ActivityPubStore,
+    client: &'a dyn ActivityPubClient,
+    job_sink: &'a dyn JobSink,
+    api_base_url: &'a str,
+}
+
+impl<'a> ActivityPubInbox<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        activitypub_store: &'a dyn ActivityPubStore,
+        client: &'a dyn ActivityPubClient,
+        job_sink: &'a dyn JobSink,
+        api_base_url: &'a str,
+    ) -> Self {
+        Self {
+            namespace_store,
+            activitypub_store,
+            client,
+            job_sink,
+            api_base_url,
+        }
+    }
+
+    /// Act on a posted activity. Fails with `PermissionDenied` when it isn't
+    /// signed by its actor.
+    pub async fn receive(
+        &self,
+        namespace_id: &str,
+        request: &InboxRequest<'_>,
+        now: i64,
+    ) -> Result<(), ServerCoreError> {
+        self.namespace_store
+            .get_namespace(namespace_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Actor not found"))?;
+        require_public_audiences(self.namespace_store, namespace_id).await?;
+
+        let activity: Value = serde_json::from_slice(request.body)
+            .map_err(|e| ServerCoreError::invalid_input(format!("Bad activity: {e}")))?;
+        let claimed = activity
+            .get("actor")
+            .and_then(object_id)
+            .ok_or_else(|| ServerCoreError::invalid_input("Activity has no actor"))?
+            .to_string();
+        let kind = activity.get("type").and_then(Value::as_str).unwrap_or("");
+        let object = activity.get("object").unwrap_or(&Value::Null);
+
+        // Servers announce account deletions to everyone they know; only the
+        // ones from followers matter, and their keys are usually gone.
+        let deletes_account = kind == "Delete" && object_id(object) == Some(claimed.as_str());
+        if deletes_account && !self.is_follower(namespace_id, &claimed).await? {
+            return Ok(());
+        }
+
+        let signer = match self.verify(namespace_id, request, now).await? {
+            Some(signer) => signer,
+            // The signer's actor answered 404/410: gone, so at most it unfollows.
+            None if deletes_account => {
+                self.activitypub_store
+                    .delete_follower(namespace_id, &claimed)
+                    .await?;
+                return Ok(());
+            }
+            None => {
+                return Err(ServerCoreError::permission_denied(
+                    "Signing key could not be found",
+                ));
+            }
+        };
+        if signer.id != claimed {
+            return Err(ServerCoreError::permission_denied(
+                "Activity is not signed by its actor",
+            ));
+        }
+
+        let actor = actor_url(self.api_base_url, namespace_id);
+        match kind {
+            "Follow" => {
+                if object_id(object) != Some(actor.as_str()) {
+                    return Err(ServerCoreError::invalid_input(
+                        "Follow is for another actor",
+                    ));
+                }
+                validate_remote_url(&signer.inbox)?;
+                let shared_inbox = signer
+                    .shared_inbox
+                    .filter(|url| validate_remote_url(url).is_ok());
+                self.activitypub_store
+                    .upsert_follower(&FollowerInfo {
+                        namespace_id: namespace_id.to_string(),
+                        actor: signer.id.clone(),
+                        inbox: signer.inbox.clone(),
+                        shared_inbox,
+                        created_at: now,
+                    })
+                    .await?;
+                let accept = json!({
+                    "@context": ACTIVITY_STREAMS,
+                    "id": format!("{actor}#accepts/{}", Uuid::new_v4()),
+                    "type": "Accept",
+                    "actor": actor,
+                    "object": activity,
+                });
+                self.enqueue(namespace_id, &signer.inbox, accept).await?;
+            }
+            "Undo" if object.get("type").and_then(Value::as_str) == Some("Follow") => {
+                self.activitypub_store
+                    .delete_follower(namespace_id, &signer.id)
+                    .await?;
+            }
+            "Delete" if deletes_account => {
+                self.activitypub_store
+                    .delete_follower(namespace_id, &signer.id)
+                    .await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn is_follower(&self, namespace_id: &str, actor: &str) -> Result<bool, ServerCoreError> {
+        Ok(self
+            .activitypub_store
+            .list_followers(namespace_id)
+            .await?
+            .iter()
+            .any(|f| f.actor == actor))
+    }
+
+    /// Verify the request's signature and return who made it. `None` when
+    /// the signing key's document is gone.
+    async fn verify(
+        &self,
+        namespace_id: &str,
+        request: &InboxRequest<'_>,
+        now: i64,
+    ) -> Result<Option<RemoteActor>, ServerCoreError> {
+        let header = request
+            .header("signature")
+            .or_else(|| {
+                request
+                    .header("authorization")
+                    .and_then(|v| v.strip_prefix("Signature "))
+            })
+            .ok_or_else(|| ServerCoreError::permission_denied("Request is not signed"))?;
+        let params = parse_signature(header)
+            .ok_or_else(|| ServerCoreError::permission_denied("Malformed Signature header"))?;
+
+        let our_key = actor_key(self.activitypub_store, namespace_id).await?;
+        let our_key_id = format!("{}#main-key", actor_url(self.api_base_url, namespace_id));
+
+        let key_doc_url = params.key_id.split('#').next().unwrap_or_default();
+        let Some(doc) = self.fetch(&our_key, &our_key_id, key_doc_url, now).await? else {
+            return Ok(None);
+        };
+        // keyId names either a standalone key document or the actor itself.
+        let (actor_doc, key) = if doc.get("publicKeyPem").is_some() {
+            let owner = doc.get("owner").and_then(Value::as_str).unwrap_or_default();
+            let Some(actor_doc) = self.fetch(&our_key, &our_key_id, owner, now).await? else {
+                return Ok(None);
+            };
+            (actor_doc, doc)
+        } else {
+            let key = match doc.get("publicKey") {
+                Some(Value::Array(keys)) => keys
+                    .iter()
+                    .find(|k| k.get("id").and_then(Value::as_str) == Some(&params.key_id))
+                    .cloned(),
+                Some(key) => Some(key.clone()),
+                None => None,
+            }
+            .ok_or_else(|| ServerCoreError::permission_denied("Signing key not found"))?;
+            (doc, key)
+        };
+
+        let actor_id = actor_doc
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ServerCoreError::permission_denied("Signer has no id"))?;
+        let owner = key.get("owner").and_then(Value::as_str);
+        if owner.is_some_and(|o| o != actor_id)
+            || !url_host(actor_id)
+                .unwrap_or_default()
+                .eq_ignore_ascii_case(url_host(&params.key_id).unwrap_or_default())
+        {
+            return Err(ServerCoreError::permission_denied(
+                "Signing key does not belong to its actor",
+            ));
+        }
+        let pem = key
+            .get("publicKeyPem")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ServerCoreError::permission_denied("Signing key has no PEM"))?;
+        verify_signature(request, &params, pem, now)?;
+
+        let inbox = actor_doc
+            .get("inbox")
+            .and_then(Value::as_str)
+            .ok_or_else(|| ServerCoreError::invalid_input("Actor has no inbox"))?;
+        let shared_inbox = actor_doc
+            .get("endpoints")
+            .and_then(|e| e.get("sharedInbox"))
+            .and_then(Value::as_str);
+        Ok(Some(RemoteActor {
+            id: actor_id.to_string(),
+            inbox: inbox.to_string(),
+            shared_inbox: shared_inbox.map(String::from),
+        }))
+    }
+
+    /// GET an ActivityPub document, signed so servers that require
+    /// authorized fetch answer. `None` on 404/410.
+    async fn fetch(
+        &self,
+        our_key: &ActorKeyPair,
+        our_key_id: &str,
+        url: &str,
+        now: i64,
+    ) -> Result<Option<Value>, ServerCoreError> {
+        validate_remote_url(url)?;
+        let mut headers = signed_headers(our_key, our_key_id, "GET", url, None, now)?;
+        headers.push(("Accept".to_string(), ACTIVITY_JSON.to_string()));
+        let (status, body) = self.client.get(url, &headers, MAX_DOCUMENT_BYTES).await?;
+        match status {
+            404 | 410 => Ok(None),
+            200..=299 => serde_json::from_str(&body)
+                .map(Some)
+                .map_err(|e| ServerCoreError::invalid_input(format!("Bad document at {url}: {e}"))),
+            other => Err(ServerCoreError::unavailable(format!(
+                "{url} answered {other}"
+            ))),
+        }
+    }
+
+    async fn enqueue(
+        &self,
+        namespace_id: &str,
+        inbox: &str,
+        activity: Value,
+    ) -> Result<(), ServerCoreError> {
+        enqueue_delivery(self.job_sink, namespace_id, inbox, activity).await
+    }
+}
+
+async fn enqueue_delivery(
+    job_sink: &dyn JobSink,
+    namespace_id: &str,
+    inbox: &str,
+    activity: Value,
+) -> Result<(), ServerCoreError> {
+    let job = ActivityPubDeliveryJob {
+        namespace_id: namespace_id.to_string(),
+        inbox: inbox.to_string(),
+        activity,
+    };
+    job_sink
+        .enqueue(
+            ACTIVITYPUB_DELIVERY_JOB,
+            serde_json::to_value(job).map_err(|e| ServerCoreError::internal(e.to_string()))?,
+        )
+        .await
+}
+
+// ---------------------------------------------------------------------------
+// Publishing (after a build)
+// ---------------------------------------------------------------------------
+
+/// Keeps a namespace's outbox in step with its public pages and announces
+/// new ones to followers.
+pub struct ActivityPubPublisher<'a> {
+    activitypub_store: &'a dyn ActivityPubStore,
+    job_sink: &'a dyn JobSink,
+    api_base_url: &'a str,
+}
+
+impl<'a> ActivityPubPublisher<'a> {
+    pub fn new(
+        activitypub_store: &'a dyn ActivityPubStore,
+        job_sink: &'a dyn JobSink,
+        api_base_url: &'a str,
+    ) -> Self {
+        Self {
+            activitypub_store,
+            job_sink,
+            api_base_url,
+        }
+    }
+
+    /// Replace the outbox with `audiences` — each public audience's feed
+    /// entries, published under `base_url` — and queue a `Create` for every
+    /// page that wasn't in it before.
+    ///
+    /// Best-effort — errors are logged but do not fail the build, whose pages
+    /// are already written. The next build catches the outbox up.
+    pub async fn publish(
+        &self,
+        namespace_id: &str,
+        base_url: &str,
+        audiences: &[(String, Vec<FeedEntry>)],
+    ) {
+        if let Err(e) = self.try_publish(namespace_id, base_url, audiences).await {
+            warn!(
+                "Failed to update the ActivityPub outbox ({}): {}",
+                namespace_id, e
+            );
+        }
+    }
+
+    async fn try_publish(
+        &self,
+        namespace_id: &str,
+        base_url: &str,
+        audiences: &[(String, Vec<FeedEntry>)],
+    ) -> Result<(), ServerCoreError> {
+        let now = Utc::now().timestamp();
+        let base = base_url.trim_end_matches('/');
+        let mut existing: HashMap<String, OutboxItem> = self
+            .activitypub_store
+            .list_outbox_items(namespace_id)
+            .await?
+            .into_iter()
+            .map(|item| (item.object_key.clone(), item))
+            .collect();
+
+        let mut added = Vec::new();
+        for (audience, entries) in audiences {
+            for entry in entries {
+                let object_key = format!("{audience}/{}", entry.dest_filename);
+                let url = format!("{base}/{}", entry.dest_filename);
+                let item = match existing.remove(&object_key) {
+                    Some(old) => {
+                        if old.audience == *audience
+                            && old.url == url
+                            && old.title == entry.title
+                            && old.summary == entry.summary
+                        {
+                            continue;
+                        }
+                        OutboxItem {
+                            audience: audience.clone(),
+                            url,
+                            title: entry.title.clone(),
+                            summary: entry.summary.clone(),
+                            updated_at: now,
+                            ..old
+                        }
+                    }
+                    None => {
+                        let item = OutboxItem {
+                            id: Uuid::new_v4().to_string(),
+                            namespace_id: namespace_id.to_string(),
+                            audience: audience.clone(),
+                            object_key,
+                            url,
+                            title: entry.title.clone(),
+                            summary: entry.summary.clone(),
+                            published_at: entry
+                                .published
+                                .as_deref()
+                                .and_then(parse_published)
+                                .unwrap_or(now),
+                            updated_at: now,
+                        };
+                        added.push(item.clone());
+                        item
+                    }
+                };
+                self.activitypub_store.upsert_outbox_item(&item).await?;
+            }
+        }
+        // Whatever is left is no longer a public feed page.
+        for object_key in existing.keys() {
+            self.activitypub_store
+                .delete_outbox_item(namespace_id, object_key)
+                .await?;
+        }
+
+        if added.is_empty() {
+            return Ok(());
+        }
+        let inboxes: BTreeSet<String> = self
+            .activitypub_store
+            .list_followers(namespace_id)
+            .await?
+            .into_iter()
+            .map(|f| f.shared_inbox.unwrap_or(f.inbox))
+            .collect();
+        added.sort_by_key(|item| std::cmp::Reverse(item.published_at));
+        added.truncate(MAX_ANNOUNCED_PER_BUILD);
+
+        let actor = actor_url(self.api_base_url, namespace_id);
+        // Oldest first, so followers' timelines end up in order.
+        for item in added.iter().rev() {
+            let activity = create_activity(&actor, item);
+            for inbox in &inboxes {
+                if let Err(e) =
+                    enqueue_delivery(self.job_sink, namespace_id, inbox, activity.clone()).await
+                {
+                    warn!("Failed to enqueue ActivityPub delivery to {}: {}", inbox, e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Delivery (background)
+// ---------------------------------------------------------------------------
+
+/// Sends queued activities, signed with the namespace actor's key.
+pub struct ActivityPubDelivery<'a> {
+    activitypub_store: &'a dyn ActivityPubStore,
+    client: &'a dyn ActivityPubClient,
+    api_base_url: &'a str,
+}
+
+impl<'a> ActivityPubDelivery<'a> {
+    pub fn new(
+        activitypub_store: &'a dyn ActivityPubStore,
+        client: &'a dyn ActivityPubClient,
+        api_base_url: &'a str,
+    ) -> Self {
+        Self {
+            activitypub_store,
+            client,
+            api_base_url,
+        }
+    }
+
+    /// POST the job's activity to its inbox. Fails with `Unavailable` when
+    /// the inbox doesn't accept it.
+    pub async fn deliver(
+        &self,
+        job: &ActivityPubDeliveryJob,
+        now: i64,
+    ) -> Result<(), ServerCoreError> {
+        validate_remote_url(&job.inbox)?;
+        let key = actor_key(self.activitypub_store, &job.namespace_id).await?;
+        let key_id = format!(
+            "{}#main-key",
+            actor_url(self.api_base_url, &job.namespace_id)
+        );
+        let body = job.activity.to_string();
+        let headers = signed_headers(
+            &key,
+            &key_id,
+            "POST",
+            &job.inbox,
+            Some(body.as_bytes()),
+            now,
+        )?;
+        let status = self
+            .client
+            .post(&job.inbox, &headers, body.as_bytes())
+            .await?;
+        if !(200..300).contains(&status) {
+            return Err(ServerCoreError::unavailable(format!(
+                "{} answered {}",
+                job.inbox, status
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::GateRecord;
+    use crate::testing::{InMemoryActivityPubStore, InMemoryNamespaceStore};
+    use std::sync::{Mutex, OnceLock};
+
+    const API: &str = "https://sync.example.com/api";
+    const ALICE: &str = "https://social.example/users/alice";
+    const NOW: i64 = 1_800_000_000;
+
+    #[derive(Default)]
+    struct RecordingJobSink {
+        jobs: Mutex<Vec<ActivityPubDeliveryJob>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl JobSink for RecordingJobSink {
+        async fn enqueue(&self, kind: &str, payload: Value) -> Result<(), ServerCoreError> {
+            assert_eq!(kind, ACTIVITYPUB_DELIVERY_JOB);
+            self.jobs.lock().unwrap().push(ActivityPubDeliveryJob::from_payload(&payload)?);
+            Ok(())
+        }
+    }
+    }
+
+    /// A recorded POST: url, headers and body.
+    type Post = (String, Vec<(String, String)>, Vec<u8>);
+
+    /// Serves fixed documents by URL and records posts.
+    #[derive(Default)]
+    struct StubClient {
+        documents: HashMap<String, Value>,
+        posts: Mutex<Vec<Post>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl ActivityPubClient for StubClient {
+        async fn get(
+            &self,
+            url: &str,
+            _headers: &[(String, String)],
+            _max_bytes: usize,
+        ) -> Result<(u16, String), ServerCoreError> {
+            Ok(match self.documents.get(url) {
+                Some(doc) => (200, doc.to_string()),
+                None => (410, String::new()),
+            })
+        }
+
+        async fn post(
+            &self,
+            url: &str,
+            headers: &[(String, String)],
+            body: &[u8],
+        ) -> Result<u16, ServerCoreError> {
+            self.posts.lock().unwrap().push((url.to_string(), headers.to_vec(), body.to_vec()));
+            Ok(202)
+        }
+    }
+    }
+
+    /// Alice's key, generated once for the whole test run.
+    fn alice_key() -> &'static ActorKeyPair {
+        static KEY: OnceLock<ActorKeyPair> = OnceLock::new();
+        KEY.get_or_init(|| generate_actor_key("alice", NOW).unwrap())
+    }
+
+    fn alice_client() -> StubClient {
+        let mut client = StubClient::default();
+        client.documents.insert(
+            ALICE.to_string(),
+            json!({
+                "id": ALICE,
+                "type": "Person",
+                "inbox": format!("{ALICE}/inbox"),
+                "endpoints": { "sharedInbox": "https://social.example/inbox" },
+                "publicKey": {
+                    "id": format!("{ALICE}#main-key"),
+                    "owner": ALICE,
+                    "publicKeyPem": alice_key().public_key_pem,
+                },
+            }),
+        );
+        client
+    }
+
+    /// A request to n1's inbox signed by Alice.
+    fn signed_by_alice(activity: &Value) -> (String, Vec<(String, String)>, Vec<u8>) {
+        let body = activity.to_string().into_bytes();
+        let inbox = format!("{}/inbox", actor_url(API, "n1"));
+        let headers = signed_headers(
+            alice_key(),
+            &format!("{ALICE}#main-key"),
+            "POST",
+            &inbox,
+            Some(&body),
+            NOW,
+        )
+        .unwrap();
+        let (_, path) = split_url(&inbox).unwrap();
+        (path.to_string(), headers, body)
+    }
+
+    async fn namespace_with(gates: &[GateRecord]) -> InMemoryNamespaceStore {
+        let ns = InMemoryNamespaceStore::new();
+        ns.create_namespace("n1", "owner", Some(r#"{"name":"Field Notes"}"#))
+            .await
+            .unwrap();
+        ns.upsert_audience("n1", "public", gates).await.unwrap();
+        ns
+    }
+
+    fn entry(dest: &str, title: &str, published: &str) -> FeedEntry {
+        FeedEntry {
+            dest_filename: dest.to_string(),
+            title: title.to_string(),
+            summary: format!("About {title}"),
+            published: Some(published.to_string()),
+            updated: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_namespaces_with_a_public_audience_have_an_actor() {
+        let store = InMemoryActivityPubStore::new();
+        let gated = namespace_with(&[GateRecord::Link]).await;
+        let service = ActivityPubService::new(&gated, &store, API);
+        assert!(matches!(
+            service.actor("n1").await,
+            Err(ServerCoreError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.webfinger("acct:n1@sync.example.com").await,
+            Err(ServerCoreError::NotFound(_))
+        ));
+
+        let public = namespace_with(&[]).await;
+        let service = ActivityPubService::new(&public, &store, API);
+        let jrd = service.webfinger("acct:n1@sync.example.com").await.unwrap();
+        assert_eq!(jrd["links"][0]["href"], actor_url(API, "n1"));
+        assert!(
+            service
+                .webfinger("acct:n1@elsewhere.example")
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            service.webfinger(&actor_url(API, "n1")).await.unwrap()["subject"],
+            "acct:n1@sync.example.com"
+        );
+
+        let actor = service.actor("n1").await.unwrap();
+        assert_eq!(actor["name"], "Field Notes");
+        assert_eq!(actor["inbox"], format!("{}/inbox", actor_url(API, "n1")));
+        let pem = actor["publicKey"]["publicKeyPem"].as_str().unwrap();
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----"));
+        // The key is generated once and kept.
+        let again = service.actor("n1").await.unwrap();
+        assert_eq!(again["publicKey"]["publicKeyPem"], pem);
+    }
+
+    #[tokio::test]
+    async fn publishing_announces_new_pages_once_per_inbox() {
+        let ns = namespace_with(&[]).await;
+        let store = InMemoryActivityPubStore::new();
+        let jobs = RecordingJobSink::default();
+        for (actor, shared) in [
+            ("a", Some("https://social.example/inbox")),
+            ("b", Some("https://social.example/inbox")),
+            ("c", None),
+        ] {
+            store
+                .upsert_follower(&FollowerInfo {
+                    namespace_id: "n1".to_string(),
+                    actor: format!("https://social.example/users/{actor}"),
+                    inbox: format!("https://social.example/users/{actor}/inbox"),
+                    shared_inbox: shared.map(String::from),
+                    created_at: NOW,
+                })
+                .await
+                .unwrap();
+        }
+        let publisher = ActivityPubPublisher::new(&store, &jobs, API);
+
+        let first = vec![entry("a.html", "A", "2024-01-01")];
+        publisher
+            .publish(
+                "n1",
+                "https://notes.example/",
+                &[("public".to_string(), first.clone())],
+            )
+            .await;
+        let delivered = jobs.jobs.lock().unwrap().clone();
+        let inboxes: Vec<_> = delivered.iter().map(|j| j.inbox.as_str()).collect();
+        assert_eq!(
+            inboxes,
+            [
+                "https://social.example/inbox",
+                "https://social.example/users/c/inbox"
+            ]
+        );
+        let note = &delivered[0].activity["object"];
+        assert_eq!(delivered[0].activity["type"], "Create");
+        assert_eq!(note["url"], "https://notes.example/a.html");
+        assert_eq!(note["published"], "2024-01-01T00:00:00Z");
+
+        // Rebuilding with one page added announces only that page, and a
+        // dropped page leaves the outbox.
+        jobs.jobs.lock().unwrap().clear();
+        let second = vec![entry("b.html", "B", "2024-02-01")];
+        publisher
+            .publish(
+                "n1",
+                "https://notes.example",
+                &[("public".to_string(), second)],
+            )
+            .await;
+        let delivered = jobs.jobs.lock().unwrap().clone();
+        assert_eq!(delivered.len(), 2);
+        assert!(
+            delivered
+                .iter()
+                .all(|j| j.activity["object"]["url"] == "https://notes.example/b.html")
+        );
+
+        let service = ActivityPubService::new(&ns, &store, API);
+        let outbox = service.outbox("n1").await.unwrap();
+        assert_eq!(outbox["totalItems"], 1);
+        assert_eq!(
+            outbox["orderedItems"][0]["object"]["url"],
+            "https://notes.example/b.html"
+        );
+
+        // Gating the audience hides its pages without a rebuild.
+        ns.upsert_audience("n1", "public", &[GateRecord::Link])
+            .await
+            .unwrap();
+        ns.upsert_audience("n1", "open", &[]).await.unwrap();
+        assert_eq!(service.outbox("n1").await.unwrap()["totalItems"], 0);
+    }
+
+    #[tokio::test]
+    async fn signed_follow_and_undo_manage_followers() {
+        let ns = namespace_with(&[]).await;
+        let store = InMemoryActivityPubStore::new();
+        let jobs = RecordingJobSink::default();
+        let client = alice_client();
+        let inbox = ActivityPubInbox::new(&ns, &store, &client, &jobs, API);
+
+        let follow = json!({
+            "id": format!("{ALICE}#follows/1"),
+            "type": "Follow",
+            "actor": ALICE,
+            "object": actor_url(API, "n1"),
+        });
+        let (path, headers, body) = signed_by_alice(&follow);
+        let request = InboxRequest {
+            path: &path,
+            headers: &headers,
+            body: &body,
+        };
+        inbox.receive("n1", &request, NOW).await.unwrap();
+
+        let followers = store.list_followers("n1").await.unwrap();
+        assert_eq!(followers.len(), 1);
+        assert_eq!(followers[0].actor, ALICE);
+        assert_eq!(
+            followers[0].shared_inbox.as_deref(),
+            Some("https://social.example/inbox")
+        );
+        let accept = jobs.jobs.lock().unwrap()[0].clone();
+        assert_eq!(accept.inbox, format!("{ALICE}/inbox"));
+        assert_eq!(accept.activity["type"], "Accept");
+        assert_eq!(accept.activity["object"]["id"], follow["id"]);
+
+        // A tampered body, a stale date and a forged actor are all refused.
+        let tampered = json!({ "type": "Undo", "actor": ALICE, "object": follow }).to_string();
+        let request = InboxRequest {
+            path: &path,
+            headers: &headers,
+            body: tampered.as_bytes(),
+        };
+        assert!(matches!(
+            inbox.receive("n1", &request, NOW).await,
+            Err(ServerCoreError::PermissionDenied(_))
+        ));
+        let request = InboxRequest {
+            path: &path,
+            headers: &headers,
+            body: &body,
+        };
+        assert!(matches!(
+            inbox
+                .receive("n1", &request, NOW + 2 * MAX_CLOCK_SKEW_SECS)
+                .await,
+            Err(ServerCoreError::PermissionDenied(_))
+        ));
+        let forged = json!({ "type": "Follow", "actor": "https://social.example/users/bob", "object": actor_url(API, "n1") });
+        let (path, headers, body) = signed_by_alice(&forged);
+        let request = InboxRequest {
+            path: &path,
+            headers: &headers,
+            body: &body,
+        };
+        assert!(matches!(
+            inbox.receive("n1", &request, NOW).await,
+            Err(ServerCoreError::PermissionDenied(_))
+        ));
+
+        let undo = json!({ "type": "Undo", "actor": ALICE, "object": follow });
+        let (path, headers, body) = signed_by_alice(&undo);
+        let request = InboxRequest {
+            path: &path,
+            headers: &headers,
+            body: &body,
+        };
+        inbox.receive("n1", &request, NOW).await.unwrap();
+        assert!(store.list_followers("n1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn deliveries_are_signed_with_the_actor_key() {
+        let ns = namespace_with(&[]).await;
+        let store = InMemoryActivityPubStore::new();
+        let client = StubClient::default();
+        let job = ActivityPubDeliveryJob {
+            namespace_id: "n1".to_string(),
+            inbox: "https://social.example/inbox".to_string(),
+            activity: json!({ "type": "Create", "actor": actor_url(API, "n1") }),
+        };
+        ActivityPubDelivery::new(&store, &client, API)
+            .deliver(&job, NOW)
+            .await
+            .unwrap();
+
+        let (url, headers, body) = client.posts.lock().unwrap()[0].clone();
+        assert_eq!(url, "https://social.example/inbox");
+        let request = InboxRequest {
+            path: "/inbox",
+            headers: &headers,
+            body: &body,
+        };
+        let params = parse_signature(request.header("signature").unwrap()).unwrap();
+        assert_eq!(params.key_id, format!("{}#main-key", actor_url(API, "n1")));
+        let actor = ActivityPubService::new(&ns, &store, API)
+            .actor("n1")
+            .await
+            .unwrap();
+        let pem = actor["publicKey"]["publicKeyPem"].as_str().unwrap();
+        verify_signature(&request, &params, pem, NOW).unwrap();
+
+        // Internal inboxes are never posted to.
+        let internal = ActivityPubDeliveryJob {
+            inbox: "http://127.0.0.1/inbox".to_string(),
+            ..job
+        };
+        assert!(
+            ActivityPubDelivery::new(&store, &client, API)
+                .deliver(&internal, NOW)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,1045 @@
+//! Namespace export and import, for moving a namespace between servers.
+//!
+//! An archive is a stream of [`ArchiveRecord`]s — NDJSON on the wire — that
+//! carries everything needed to recreate a namespace elsewhere: audiences with
+//! their gates, custom domains, every object's bytes, and the ARK index. The
+//! namespace id travels with it, so ARKs (whose workspace blade *is* the
+//! namespace id) keep resolving after the move. An id that is not a valid
+//! workspace blade is replaced with a freshly minted one on import.
+//!
+//! Export streams through an [`ArchiveSink`] so adapters never buffer the
+//! whole namespace; import reads from an [`ArchiveSource`] and rolls back
+//! everything it created if the stream turns out to be invalid or truncated.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use sha2::{Digest, Sha256};
+
+use crate::domain::ArchiveRecord;
+use crate::ports::{
+    ArchiveSink, ArchiveSource, ArkIndexStore, BlobStore, DomainMappingCache, NamespaceStore,
+    ObjectMetaStore, ServerCoreError,
+};
+use crate::use_cases::domains::{DIARYX_SUBDOMAIN_SUFFIX, validate_domain_mapping};
+use crate::use_cases::namespaces::NamespaceService;
+use crate::use_cases::objects::{content_blob_key, object_blob_key};
+
+/// Value of the header's `format` field.
+pub const ARCHIVE_FORMAT: &str = "diaryx-namespace-archive";
+
+/// Archive layout version written by this build. Import rejects newer ones.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Objects are listed from the meta store in pages of this size.
+const EXPORT_PAGE_SIZE: u32 = 500;
+
+/// Longest line [`ArchiveDecoder`] buffers. An object travels base64-encoded
+/// on a single line, so this also bounds the largest object an archive holds.
+pub const MAX_ARCHIVE_LINE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest archive [`ArchiveDecoder`] reads in total.
+pub const MAX_ARCHIVE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Counts of what an export wrote or an import recreated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveSummary {
+    pub namespace_id: String,
+    pub audiences: u64,
+    pub domains: u64,
+    pub objects: u64,
+    pub bytes: u64,
+    pub arks: u64,
+    pub versions: u64,
+    /// Domain records an import left out because they failed the checks a
+    /// live registration would apply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_domains: Vec<SkippedDomain>,
+}
+
+/// A domain record an import did not recreate, and why.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SkippedDomain {
+    pub domain: String,
+    pub audience_name: String,
+    pub reason: String,
+}
+
+/// Encode one record as an NDJSON line (trailing `\n` included).
+pub fn encode_archive_record(record: &ArchiveRecord) -> Result<Vec<u8>, ServerCoreError> {
+    let mut line = serde_json::to_vec(record)
+        .map_err(|e| ServerCoreError::internal(format!("archive encode: {e}")))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Incremental NDJSON decoder for archive streams. Feed it body chunks as they
+/// arrive and drain complete records; blank lines are skipped. A line longer
+/// than [`MAX_ARCHIVE_LINE_BYTES`] or a stream longer than
+/// [`MAX_ARCHIVE_BYTES`] is refused rather than buffered.
+#[derive(Debug)]
+pub struct ArchiveDecoder {
+    buf: Vec<u8>,
+    total: u64,
+    max_line: usize,
+    max_total: u64,
+}
+
+impl Default for ArchiveDecoder {
+    fn default() -> Self {
+        Self::with_limits(MAX_ARCHIVE_LINE_BYTES, MAX_ARCHIVE_BYTES)
+    }
+}
+
+impl ArchiveDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A decoder with its own line and total size limits.
+    pub fn with_limits(max_line: usize, max_total: u64) -> Self {
+        Self {
+            buf: Vec::new(),
+            total: 0,
+            max_line,
+            max_total,
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), ServerCoreError> {
+        self.total += chunk.len() as u64;
+        if self.total > self.max_total {
+            return Err(ServerCoreError::invalid_input(format!(
+                "archive is larger than {} bytes",
+                self.max_total
+            )));
+        }
+        self.buf.extend_from_slice(chunk);
+        let line_start = self
+            .buf
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |pos| pos + 1);
+        if self.buf.len() - line_start > self.max_line {
+            return Err(ServerCoreError::invalid_input(format!(
+                "archive line is longer than {} bytes",
+                self.max_line
+            )));
+        }
+        Ok(())
+    }
+
+    /// Pop the next complete line as a record, or `None` if no full line is
+    /// buffered yet.
+    pub fn next_record(&mut self) -> Result<Option<ArchiveRecord>, ServerCoreError> {
+        while let Some(pos) = self.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=pos).collect();
+            if let Some(record) = decode_line(&line)? {
+                return Ok(Some(record));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decode whatever is left once the stream has ended (a final line without
+    /// a trailing newline).
+    pub fn finish(&mut self) -> Result<Option<ArchiveRecord>, ServerCoreError> {
+        if let Some(record) = self.next_record()? {
+            return Ok(Some(record));
+        }
+        let rest = std::mem::take(&mut self.buf);
+        decode_line(&rest)
+    }
+}
+
+fn decode_line(line: &[u8]) -> Result<Option<ArchiveRecord>, ServerCoreError> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    serde_json::from_slice(line)
+        .map(Some)
+        .map_err(|e| ServerCoreError::invalid_input(format!("malformed archive record: {e}")))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut s, b| {
+            use std::fmt::Write;
+            let _ = write!(s, "{:02x}", b);
+            s
+        })
+}
+
+pub struct NamespaceArchiveService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    object_meta_store: &'a dyn ObjectMetaStore,
+    blob_store: &'a dyn BlobStore,
+    ark_index: &'a dyn ArkIndexStore,
+}
+
+impl<'a> NamespaceArchiveService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        object_meta_store: &'a dyn ObjectMetaStore,
+        blob_store: &'a dyn BlobStore,
+        ark_index: &'a dyn ArkIndexStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            object_meta_store,
+            blob_store,
+            ark_index,
+        }
+    }
+
+    /// Stream a namespace the caller owns into `sink`.
+    pub async fn export(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        sink: &mut dyn ArchiveSink,
+    ) -> Result<ArchiveSummary, ServerCoreError> {
+        let ns = self
+            .namespace_store
+            .get_namespace(namespace_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
+        if ns.owner_user_id != caller_user_id {
+            return Err(ServerCoreError::permission_denied(
+                "You do not own this namespace",
+            ));
+        }
+
+        let mut summary = ArchiveSummary {
+            namespace_id: namespace_id.to_string(),
+            ..Default::default()
+        };
+
+        sink.write_record(&ArchiveRecord::Header {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            namespace_id: namespace_id.to_string(),
+            metadata: ns.metadata,
+            exported_at: chrono::Utc::now().timestamp(),
+        })
+        .await?;
+
+        for audience in self.namespace_store.list_audiences(namespace_id).await? {
+            sink.write_record(&ArchiveRecord::Audience {
+                name: audience.audience_name,
+                gates: audience.gates,
+            })
+            .await?;
+            summary.audiences += 1;
+        }
+
+        for domain in self
+            .namespace_store
+            .list_custom_domains(namespace_id)
+            .await?
+        {
+            sink.write_record(&ArchiveRecord::Domain {
+                domain: domain.domain,
+                audience_name: domain.audience_name,
+            })
+            .await?;
+            summary.domains += 1;
+        }
+
+        let mut offset = 0;
+        loop {
+            let page = self
+                .object_meta_store
+                .list_objects(namespace_id, EXPORT_PAGE_SIZE, offset)
+                .await?;
+            let page_len = page.len() as u32;
+            for meta in page {
+                let blob_key = meta
+                    .blob_key
+                    .unwrap_or_else(|| object_blob_key(namespace_id, &meta.key));
+                let bytes = self.blob_store.get(&blob_key).await?.ok_or_else(|| {
+                    ServerCoreError::internal(format!("blob missing for object '{}'", meta.key))
+                })?;
+                summary.bytes += bytes.len() as u64;
+                sink.write_record(&ArchiveRecord::Object {
+                    key: meta.key,
+                    mime_type: meta.mime_type,
+                    audience: meta.audience,
+                    content_hash: meta.content_hash,
+                    data: BASE64.encode(&bytes),
+                })
+                .await?;
+                summary.objects += 1;
+            }
+            if page_len < EXPORT_PAGE_SIZE {
+                break;
+            }
+            offset += page_len;
+        }
+
+        for entry in self.ark_index.list_ark_entries(namespace_id).await? {
+            sink.write_record(&ArchiveRecord::Ark {
+                file_ark: entry.file_ark.clone(),
+                object_key: entry.object_key,
+                audience: entry.audience,
+                source_key: entry.source_key,
+            })
+            .await?;
+            summary.arks += 1;
+
+            // Oldest first, so an importer that stamps `created_at` on insert
+            // keeps the original order.
+            let mut versions = self
+                .ark_index
+                .list_ark_versions(namespace_id, &entry.file_ark)
+                .await?;
+            versions.sort_by_key(|v| v.created_at);
+            for v in versions {
+                sink.write_record(&ArchiveRecord::ArkVersion {
+                    file_ark: v.file_ark,
+                    version: v.version,
+                    object_key: v.object_key,
+                })
+                .await?;
+                summary.versions += 1;
+            }
+        }
+
+        sink.write_record(&ArchiveRecord::End {
+            objects: summary.objects,
+        })
+        .await?;
+
+        Ok(summary)
+    }
+
+    /// Recreate an exported namespace, owned by the caller, under its original
+    /// id — or a newly minted one when the archive's id is not a valid
+    /// workspace blade — through the same path as
+    /// [`NamespaceService::create`]. Conflicts — the namespace id or a custom
+    /// domain already taken on this server — fail with
+    /// [`ServerCoreError::Conflict`]; a malformed or truncated archive with
+    /// [`ServerCoreError::InvalidInput`]. On any failure after the namespace
+    /// was created, everything imported so far is removed.
+    pub async fn import(
+        &self,
+        caller_user_id: &str,
+        source: &mut dyn ArchiveSource,
+        domain_cache: Option<&dyn DomainMappingCache>,
+    ) -> Result<ArchiveSummary, ServerCoreError> {
+        let (namespace_id, metadata) = match source.next_record().await? {
+            Some(ArchiveRecord::Header {
+                format,
+                version,
+                namespace_id,
+                metadata,
+                ..
+            }) => {
+                if format != ARCHIVE_FORMAT {
+                    return Err(ServerCoreError::invalid_input(format!(
+                        "not a namespace archive (format '{format}')"
+                    )));
+                }
+                if version > ARCHIVE_VERSION {
+                    return Err(ServerCoreError::invalid_input(format!(
+                        "archive version {version} is newer than supported ({ARCHIVE_VERSION})"
+                    )));
+                }
+                (namespace_id, metadata)
+            }
+            _ => {
+                return Err(ServerCoreError::invalid_input(
+                    "archive must start with a header record",
+                ));
+            }
+        };
+
+        // Only a well-formed workspace blade is kept; anything else (a legacy
+        // id, or whatever a hand-edited archive claims) gets a fresh one.
+        let requested_id = diaryx_ark::validate_workspace_blade(&namespace_id)
+            .is_ok()
+            .then_some(namespace_id.as_str());
+        if let Some(id) = requested_id
+            && self.namespace_store.get_namespace(id).await?.is_some()
+        {
+            return Err(ServerCoreError::conflict(format!(
+                "Namespace '{id}' already exists"
+            )));
+        }
+        let namespace_id = NamespaceService::new(self.namespace_store)
+            .create(caller_user_id, requested_id, metadata.as_deref())
+            .await?
+            .id;
+
+        let mut imported = ImportState {
+            summary: ArchiveSummary {
+                namespace_id: namespace_id.clone(),
+                ..Default::default()
+            },
+            blob_keys: Vec::new(),
+            domains: Vec::new(),
+        };
+        match self
+            .import_records(&namespace_id, source, &mut imported)
+            .await
+        {
+            Ok(()) => {
+                if let Some(cache) = domain_cache {
+                    for (domain, audience_name) in &imported.domains {
+                        cache_domain(cache, domain, &namespace_id, audience_name).await?;
+                    }
+                }
+                let _ = self
+                    .object_meta_store
+                    .record_usage(
+                        caller_user_id,
+                        "bytes_in",
+                        imported.summary.bytes,
+                        Some(&namespace_id),
+                    )
+                    .await;
+                Ok(imported.summary)
+            }
+            Err(e) => {
+                self.rollback(&namespace_id, &imported).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn import_records(
+        &self,
+        namespace_id: &str,
+        source: &mut dyn ArchiveSource,
+        imported: &mut ImportState,
+    ) -> Result<(), ServerCoreError> {
+        let summary = &mut imported.summary;
+        loop {
+            let record = source.next_record().await?.ok_or_else(|| {
+                ServerCoreError::invalid_input("archive is truncated (no end record)")
+            })?;
+            match record {
+                ArchiveRecord::Header { .. } => {
+                    return Err(ServerCoreError::invalid_input(
+                        "archive contains more than one header",
+                    ));
+                }
+                ArchiveRecord::Audience { name, gates } => {
+                    self.namespace_store
+                        .upsert_audience(namespace_id, &name, &gates)
+                        .await?;
+                    summary.audiences += 1;
+                }
+                ArchiveRecord::Domain {
+                    domain,
+                    audience_name,
+                } => {
+                    // Audiences precede domains in an archive, so the
+                    // audience check sees everything this import created.
+                    match validate_domain_mapping(
+                        self.namespace_store,
+                        namespace_id,
+                        &domain,
+                        &audience_name,
+                    )
+                    .await
+                    {
+                        Ok(()) => {}
+                        Err(
+                            e @ (ServerCoreError::InvalidInput(_) | ServerCoreError::Conflict(_)),
+                        ) => {
+                            summary.skipped_domains.push(SkippedDomain {
+                                domain,
+                                audience_name,
+                                reason: e.to_string(),
+                            });
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                    if let Some(existing) = self.namespace_store.get_custom_domain(&domain).await?
+                        && existing.namespace_id != namespace_id
+                    {
+                        return Err(ServerCoreError::conflict(format!(
+                            "Domain '{domain}' is already registered on this server"
+                        )));
+                    }
+                    self.namespace_store
+                        .upsert_custom_domain(&domain, namespace_id, &audience_name)
+                        .await?;
+                    imported.domains.push((domain, audience_name));
+                    summary.domains += 1;
+                }
+                ArchiveRecord::Object {
+                    key,
+                    mime_type,
+                    audience,
+                    content_hash,
+                    data,
+                } => {
+                    let bytes = BASE64.decode(data.as_bytes()).map_err(|e| {
+                        ServerCoreError::invalid_input(format!("object '{key}': bad base64: {e}"))
+                    })?;
+                    let hash = sha256_hex(&bytes);
+                    if content_hash.as_deref().is_some_and(|h| h != hash) {
+                        return Err(ServerCoreError::invalid_input(format!(
+                            "object '{key}': content does not match its hash"
+                        )));
+                    }
+                    if let Some(aud) = audience.as_deref()
+                        && self
+                            .namespace_store
+                            .get_audience(namespace_id, aud)
+                            .await?
+                            .is_none()
+                    {
+                        return Err(ServerCoreError::invalid_input(format!(
+                            "object '{key}': audience '{aud}' is not in the archive"
+                        )));
+                    }
+
+                    let blob_key = content_blob_key(namespace_id, &hash);
+                    if !self.blob_store.exists(&blob_key).await? {
+                        self.blob_store
+                            .put(&blob_key, &bytes, &mime_type, None)
+                            .await?;
+                        imported.blob_keys.push(blob_key.clone());
+                    }
+                    self.object_meta_store
+                        .upsert_object(
+                            namespace_id,
+                            &key,
+                            &blob_key,
+                            &mime_type,
+                            bytes.len() as u64,
+                            audience.as_deref(),
+                            Some(&hash),
+                        )
+                        .await?;
+                    summary.objects += 1;
+                    summary.bytes += bytes.len() as u64;
+                }
+                ArchiveRecord::Ark {
+                    file_ark,
+                    object_key,
+                    audience,
+                    source_key,
+                } => {
+                    self.ark_index
+                        .upsert_ark(
+                            namespace_id,
+                            &file_ark,
+                            &object_key,
+                            audience.as_deref(),
+                            source_key.as_deref(),
+                        )
+                        .await?;
+                    summary.arks += 1;
+                }
+                ArchiveRecord::ArkVersion {
+                    file_ark,
+                    version,
+                    object_key,
+                } => {
+                    self.ark_index
+                        .record_ark_version(namespace_id, &file_ark, &version, &object_key)
+                        .await?;
+                    summary.versions += 1;
+                }
+                ArchiveRecord::End { objects } => {
+                    if objects != summary.objects {
+                        return Err(ServerCoreError::invalid_input(format!(
+                            "archive declares {objects} objects but contains {}",
+                            summary.objects
+                        )));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Best-effort removal of a partially imported namespace. Failures are
+    /// ignored: the original error is what the caller needs to see.
+    async fn rollback(&self, namespace_id: &str, imported: &ImportState) {
+        for (domain, _) in &imported.domains {
+            let _ = self.namespace_store.delete_custom_domain(domain).await;
+        }
+        let mut keys = Vec::new();
+        let mut offset = 0;
+        while let Ok(page) = self
+            .object_meta_store
+            .list_objects(namespace_id, EXPORT_PAGE_SIZE, offset)
+            .await
+        {
+            let page_len = page.len() as u32;
+            keys.extend(page.into_iter().map(|m| m.key));
+            if page_len < EXPORT_PAGE_SIZE {
+                break;
+            }
+            offset += page_len;
+        }
+        for key in &keys {
+            let _ = self
+                .object_meta_store
+                .delete_object(namespace_id, key)
+                .await;
+        }
+        for blob_key in &imported.blob_keys {
+            let _ = self.blob_store.delete(blob_key).await;
+        }
+        let _ = self.namespace_store.delete_namespace(namespace_id).await;
+    }
+}
+
+struct ImportState {
+    summary: ArchiveSummary,
+    /// Blobs this import wrote (and must remove on rollback).
+    blob_keys: Vec<String>,
+    /// `(domain, audience_name)` rows created, for the edge cache and rollback.
+    domains: Vec<(String, String)>,
+}
+
+async fn cache_domain(
+    cache: &dyn DomainMappingCache,
+    domain: &str,
+    namespace_id: &str,
+    audience_name: &str,
+) -> Result<(), ServerCoreError> {
+    match domain.strip_suffix(DIARYX_SUBDOMAIN_SUFFIX) {
+        Some(subdomain) => cache.put_subdomain(subdomain, namespace_id, None).await,
+        None => cache.put_domain(domain, namespace_id, audience_name).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::GateRecord;
+    use crate::testing::{
+        InMemoryArkIndexStore, InMemoryBlobStore, InMemoryNamespaceStore, InMemoryObjectMetaStore,
+    };
+    use crate::use_cases::domains::SUBDOMAIN_AUDIENCE_NAME;
+    use crate::use_cases::objects::ObjectService;
+
+    #[derive(Default)]
+    struct Stores {
+        ns: InMemoryNamespaceStore,
+        meta: InMemoryObjectMetaStore,
+        blob: InMemoryBlobStore,
+        ark: InMemoryArkIndexStore,
+    }
+
+    impl Stores {
+        fn archive(&self) -> NamespaceArchiveService<'_> {
+            NamespaceArchiveService::new(&self.ns, &self.meta, &self.blob, &self.ark)
+        }
+    }
+
+    #[derive(Default)]
+    struct VecArchive {
+        records: Vec<ArchiveRecord>,
+        pos: usize,
+    }
+
+    crate::cfg_async_trait! {
+    impl ArchiveSink for VecArchive {
+        async fn write_record(&mut self, record: &ArchiveRecord) -> Result<(), ServerCoreError> {
+            self.records.push(record.clone());
+            Ok(())
+        }
+    }
+
+    impl ArchiveSource for VecArchive {
+        async fn next_record(&mut self) -> Result<Option<ArchiveRecord>, ServerCoreError> {
+            let record = self.records.get(self.pos).cloned();
+            self.pos += 1;
+            Ok(record)
+        }
+    }
+    }
+
+    async fn seeded() -> Stores {
+        let s = Stores::default();
+        s.ns.create_namespace("dxbcdfgh6", "alice", Some(r#"{"name":"Notes"}"#))
+            .await
+            .unwrap();
+        s.ns.upsert_audience(
+            "dxbcdfgh6",
+            "friends",
+            &[GateRecord::Password {
+                hash: Some("$argon2id$stub".to_string()),
+                version: 2,
+            }],
+        )
+        .await
+        .unwrap();
+        s.ns.upsert_custom_domain("notes.example.com", "dxbcdfgh6", "friends")
+            .await
+            .unwrap();
+        let objects = ObjectService::new(&s.ns, &s.meta, &s.blob);
+        objects
+            .put(
+                "dxbcdfgh6",
+                "friends/note.md",
+                "text/markdown",
+                b"# Hello",
+                Some("friends"),
+                "alice",
+            )
+            .await
+            .unwrap();
+        objects
+            .put(
+                "dxbcdfgh6",
+                "draft.md",
+                "text/markdown",
+                b"wip",
+                None,
+                "alice",
+            )
+            .await
+            .unwrap();
+        s.ark
+            .upsert_ark(
+                "dxbcdfgh6",
+                "bcdfgr",
+                "friends/note.html",
+                Some("friends"),
+                Some("friends/note.md"),
+            )
+            .await
+            .unwrap();
+        s.ark
+            .record_ark_version("dxbcdfgh6", "bcdfgr", "aaaa", "_versions/bcdfgr/aaaa.md")
+            .await
+            .unwrap();
+        s.ark
+            .record_ark_version("dxbcdfgh6", "bcdfgr", "bbbb", "_versions/bcdfgr/bbbb.md")
+            .await
+            .unwrap();
+        s
+    }
+
+    #[tokio::test]
+    async fn export_then_import_recreates_namespace() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        let exported = src
+            .archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+        assert_eq!(exported.objects, 2);
+        assert_eq!(exported.versions, 2);
+        assert!(matches!(archive.records[0], ArchiveRecord::Header { .. }));
+        assert_eq!(
+            archive.records.last(),
+            Some(&ArchiveRecord::End { objects: 2 })
+        );
+
+        let dst = Stores::default();
+        let imported = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap();
+        assert_eq!(imported, exported);
+
+        let ns = dst.ns.get_namespace("dxbcdfgh6").await.unwrap().unwrap();
+        assert_eq!(ns.owner_user_id, "bob");
+        assert_eq!(ns.metadata.as_deref(), Some(r#"{"name":"Notes"}"#));
+
+        let audience = dst
+            .ns
+            .get_audience("dxbcdfgh6", "friends")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(audience.password_gate().is_some());
+        let domain = dst
+            .ns
+            .get_custom_domain("notes.example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(domain.audience_name, "friends");
+
+        let objects = ObjectService::new(&dst.ns, &dst.meta, &dst.blob);
+        let note = objects
+            .get("dxbcdfgh6", "friends/note.md", "bob")
+            .await
+            .unwrap();
+        assert_eq!(note.bytes, b"# Hello");
+
+        let entry = dst
+            .ark
+            .resolve_ark("dxbcdfgh6", "bcdfgr")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.source_key.as_deref(), Some("friends/note.md"));
+        let versions = dst
+            .ark
+            .list_ark_versions("dxbcdfgh6", "bcdfgr")
+            .await
+            .unwrap();
+        let order: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(order, ["bbbb", "aaaa"], "newest-first order survives");
+    }
+
+    #[tokio::test]
+    async fn export_requires_owner() {
+        let src = seeded().await;
+        let err = src
+            .archive()
+            .export("dxbcdfgh6", "mallory", &mut VecArchive::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+    }
+
+    #[tokio::test]
+    async fn import_conflicts_on_existing_namespace_or_domain() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        src.archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+
+        // Same server: the namespace id is taken.
+        let err = src
+            .archive()
+            .import("alice", &mut archive, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::Conflict(_)));
+
+        // Another server where the custom domain already belongs elsewhere.
+        let dst = Stores::default();
+        dst.ns
+            .upsert_custom_domain("notes.example.com", "dxother00", "public")
+            .await
+            .unwrap();
+        archive.pos = 0;
+        let err = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::Conflict(_)));
+        assert!(dst.ns.get_namespace("dxbcdfgh6").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn truncated_import_rolls_back() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        src.archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+        archive.records.pop(); // drop the `end` trailer
+
+        let dst = Stores::default();
+        let err = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+        assert!(dst.ns.get_namespace("dxbcdfgh6").await.unwrap().is_none());
+        assert!(
+            dst.ns
+                .get_custom_domain("notes.example.com")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            dst.meta
+                .list_objects("dxbcdfgh6", 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(dst.blob.raw_blobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_rejects_tampered_object() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        src.archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+        for record in &mut archive.records {
+            if let ArchiveRecord::Object { data, .. } = record {
+                *data = BASE64.encode(b"tampered");
+            }
+        }
+
+        let dst = Stores::default();
+        let err = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn decoder_reassembles_split_lines() {
+        let mut bytes = encode_archive_record(&ArchiveRecord::Domain {
+            domain: "a.example".to_string(),
+            audience_name: "public".to_string(),
+        })
+        .unwrap();
+        bytes.extend(b"\n");
+        bytes.extend(br#"{"type":"end","objects":0}"#);
+
+        let mut decoder = ArchiveDecoder::new();
+        let (head, tail) = bytes.split_at(7);
+        decoder.push(head).unwrap();
+        assert_eq!(decoder.next_record().unwrap(), None);
+        decoder.push(tail).unwrap();
+        assert!(matches!(
+            decoder.next_record().unwrap(),
+            Some(ArchiveRecord::Domain { .. })
+        ));
+        assert_eq!(decoder.next_record().unwrap(), None);
+        assert_eq!(
+            decoder.finish().unwrap(),
+            Some(ArchiveRecord::End { objects: 0 })
+        );
+
+        let mut bad = ArchiveDecoder::new();
+        bad.push(b"not json\n").unwrap();
+        assert!(matches!(
+            bad.next_record(),
+            Err(ServerCoreError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn decoder_refuses_overlong_lines_and_archives() {
+        // A line that never ends is refused once it outgrows the limit,
+        // however it is split across chunks.
+        let mut decoder = ArchiveDecoder::with_limits(16, 1024);
+        decoder.push(b"{\"type\":\"end\",").unwrap();
+        assert!(matches!(
+            decoder.push(b"\"objects\":0"),
+            Err(ServerCoreError::InvalidInput(_))
+        ));
+
+        // Complete lines don't count towards the line limit.
+        let mut decoder = ArchiveDecoder::with_limits(32, 1024);
+        for _ in 0..8 {
+            decoder.push(b"{\"type\":\"end\",\"objects\":0}\n").unwrap();
+        }
+
+        let mut decoder = ArchiveDecoder::with_limits(1024, 40);
+        decoder.push(b"{\"type\":\"end\",\"objects\":0}\n").unwrap();
+        assert!(matches!(
+            decoder.push(b"{\"type\":\"end\",\"objects\":0}\n"),
+            Err(ServerCoreError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn import_mints_a_new_id_for_a_malformed_namespace_id() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        src.archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+        if let ArchiveRecord::Header { namespace_id, .. } = &mut archive.records[0] {
+            *namespace_id = "../../victim".to_string();
+        }
+
+        let dst = Stores::default();
+        let imported = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap();
+        assert_ne!(imported.namespace_id, "../../victim");
+        diaryx_ark::validate_workspace_blade(&imported.namespace_id).unwrap();
+        let ns = dst
+            .ns
+            .get_namespace(&imported.namespace_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ns.owner_user_id, "bob");
+        assert!(
+            dst.ark
+                .resolve_ark(&imported.namespace_id, "bcdfgr")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn import_skips_domains_a_registration_would_refuse() {
+        let src = seeded().await;
+        let mut archive = VecArchive::default();
+        src.archive()
+            .export("dxbcdfgh6", "alice", &mut archive)
+            .await
+            .unwrap();
+        let end = archive.records.len() - 1;
+        let extra = [
+            ("notes.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("app.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("bad_name.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("open.diaryx.org", "friends"),
+            ("ghost.example.com", "ghost"),
+        ];
+        archive.records.splice(
+            end..end,
+            extra
+                .iter()
+                .map(|(domain, audience_name)| ArchiveRecord::Domain {
+                    domain: domain.to_string(),
+                    audience_name: audience_name.to_string(),
+                }),
+        );
+
+        let dst = Stores::default();
+        let imported = dst
+            .archive()
+            .import("bob", &mut archive, None)
+            .await
+            .unwrap();
+        assert_eq!(imported.domains, 2);
+        let skipped: Vec<&str> = imported
+            .skipped_domains
+            .iter()
+            .map(|s| s.domain.as_str())
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                "app.diaryx.org",
+                "bad_name.diaryx.org",
+                "open.diaryx.org",
+                "ghost.example.com"
+            ]
+        );
+        for domain in skipped {
+            assert!(dst.ns.get_custom_domain(domain).await.unwrap().is_none());
+        }
+        assert!(
+            dst.ns
+                .get_custom_domain("notes.diaryx.org")
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+}
@@ -4,7 +4,7 @@
 //! object PUT — so ownership is already enforced by the caller before this
 //! service runs. The service's job is the collision check and the upsert.
 
-use crate::domain::ArkIndexEntry;
+use crate::domain::{ArkIndexEntry, ArkVersionEntry};
 use crate::ports::{ArkIndexStore, ServerCoreError};
 
 /// Reserved file-blade sentinel for a workspace's front-page (index) pointer.
@@ -20,6 +20,11 @@ pub const ARK_WORKSPACE_INDEX: &str = "index";
 /// NAAN, so swapping in a real NAAN here is a one-line, link-preserving change.
 pub const ARK_NAAN: &str = "99999";
 
+/// Shortest `.<FILE>` variant accepted as a content-hash prefix. Versions are
+/// full SHA-256 hex digests; like git, a unique prefix of at least this many
+/// characters is enough to address one.
+pub const ARK_VERSION_MIN_PREFIX: usize = 7;
+
 /// A resolution inflection — what representation of a file an ARK request wants.
 /// Parsed from the URL query string (the part after `?`).
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,25 +33,30 @@ pub enum Inflection {
     Default,
     /// `?content`: the raw markdown source.
     Content,
-    /// `?json`: `{ "frontmatter": {...}, "body": "..." }`.
+    /// `?json`: `{ "frontmatter": {...}, "body": "..." }` — the raw frontmatter.
     Json,
-    /// `?info`: the frontmatter map only.
+    /// `?info` (or the legacy `??`): ERC kernel metadata — who/what/when/where —
+    /// as ERC text, or JSON-LD when the client asks for JSON.
     Info,
     /// `?meta=<key>` or `?.<key>`: a single literal frontmatter field.
     Meta(String),
+    /// `?versions`: the retained `.<FILE>` versions of the file.
+    Versions,
 }
 
 impl Inflection {
     /// Parse the raw query string. Reserved/unknown queries fall back to
     /// [`Inflection::Default`]. `meta=<key>` and a leading-dot `.<key>` both
     /// address a literal frontmatter key (so reserved names like `info` stay
-    /// reachable).
+    /// reachable). A `??` URL leaves `?` as its raw query, the older N2T
+    /// spelling of `?info`.
     pub fn parse(query: &str) -> Self {
         match query {
             "" => Inflection::Default,
             "content" => Inflection::Content,
             "json" => Inflection::Json,
-            "info" => Inflection::Info,
+            "info" | "?" => Inflection::Info,
+            "versions" => Inflection::Versions,
             q => {
                 if let Some(key) = q.strip_prefix("meta=") {
                     Inflection::Meta(key.to_string())
@@ -60,29 +70,183 @@ impl Inflection {
     }
 }
 
-/// Build the JSON body for a `?json` / `?info` / `?meta=` inflection from the
-/// stored markdown source. Parses frontmatter server-side via `diaryx_core`.
-pub fn inflection_json(
+/// Split a resolver path's file segment into its blade and `.<FILE>` variant,
+/// validating the variant's shape. The blade itself is not validated here —
+/// resolution simply misses on an unknown blade.
+pub fn split_file_variant(segment: &str) -> Result<(&str, Option<&str>), ServerCoreError> {
+    let (file, variant) = diaryx_ark::split_variant(segment);
+    if let Some(v) = variant {
+        diaryx_ark::validate_variant(v)
+            .map_err(|e| ServerCoreError::invalid_input(format!("ARK version: {e}")))?;
+    }
+    Ok((file, variant))
+}
+
+/// The canonical `ark:{NAAN}/{ws}[/{file}][.{version}]` form of an ARK. The
+/// workspace front page ([`ARK_WORKSPACE_INDEX`]) is the bare workspace ARK.
+pub fn canonical_ark(workspace_ark: &str, file_ark: &str, variant: Option<&str>) -> String {
+    let mut ark = format!("ark:{ARK_NAAN}/{workspace_ark}");
+    if file_ark != ARK_WORKSPACE_INDEX {
+        ark.push('/');
+        ark.push_str(file_ark);
+    }
+    if let Some(v) = variant {
+        ark.push('.');
+        ark.push_str(v);
+    }
+    ark
+}
+
+fn parse_frontmatter(
     source_markdown: &str,
-    inflection: &Inflection,
-) -> Result<serde_json::Value, ServerCoreError> {
+) -> Result<(serde_json::Value, String), ServerCoreError> {
     let parsed = diaryx_core::frontmatter::parse_or_empty(source_markdown)
         .map_err(|e| ServerCoreError::internal(format!("frontmatter parse: {e}")))?;
     let fm = serde_json::to_value(&parsed.frontmatter)
         .map_err(|e| ServerCoreError::internal(format!("frontmatter to json: {e}")))?;
+    Ok((fm, parsed.body))
+}
+
+/// Build the JSON body for a `?json` / `?meta=` inflection from the stored
+/// markdown source. Parses frontmatter server-side via `diaryx_core`.
+pub fn inflection_json(
+    source_markdown: &str,
+    inflection: &Inflection,
+) -> Result<serde_json::Value, ServerCoreError> {
+    let (fm, body) = parse_frontmatter(source_markdown)?;
     match inflection {
-        Inflection::Json => Ok(serde_json::json!({ "frontmatter": fm, "body": parsed.body })),
-        Inflection::Info => Ok(fm),
+        Inflection::Json => Ok(serde_json::json!({ "frontmatter": fm, "body": body })),
         Inflection::Meta(key) => fm
             .get(key)
             .cloned()
             .ok_or_else(|| ServerCoreError::not_found("metadata key not found")),
-        Inflection::Default | Inflection::Content => Err(ServerCoreError::internal(
-            "inflection_json called for a non-JSON inflection",
-        )),
+        Inflection::Default | Inflection::Content | Inflection::Info | Inflection::Versions => Err(
+            ServerCoreError::internal("inflection_json called for a non-JSON inflection"),
+        ),
+    }
+}
+
+/// ERC code for a kernel element with no value, per the ERC spec.
+const ERC_UNKNOWN: &str = "(:unkn)";
+
+/// ERC kernel metadata for a resolved ARK — the answer to `?info` / `??`.
+///
+/// Mapped from frontmatter: `author` → who, `title` → what, `created` → when;
+/// `where` is the canonical `ark:` URL. Nothing else from the frontmatter is
+/// exposed, so publish-internal keys can't leak through this inflection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErcKernel {
+    pub who: Vec<String>,
+    pub what: Option<String>,
+    pub when: Option<String>,
+    pub r#where: String,
+}
+
+impl ErcKernel {
+    /// Extract the kernel from a markdown source resolved at `ark`.
+    pub fn from_source(source_markdown: &str, ark: &str) -> Result<Self, ServerCoreError> {
+        let (fm, _) = parse_frontmatter(source_markdown)?;
+        let who = match fm.get("author") {
+            Some(serde_json::Value::Array(items)) => {
+                items.iter().filter_map(scalar_string).collect()
+            }
+            Some(v) => scalar_string(v).into_iter().collect(),
+            None => Vec::new(),
+        };
+        Ok(Self {
+            who,
+            what: fm.get("title").and_then(scalar_string),
+            when: fm.get("created").and_then(scalar_string),
+            r#where: ark.to_string(),
+        })
+    }
+
+    /// Render as an ERC record (`text/plain`), the format N2T and other ARK
+    /// resolvers expect. Multiple authors are joined with `; `.
+    pub fn to_erc(&self) -> String {
+        let who = if self.who.is_empty() {
+            ERC_UNKNOWN.to_string()
+        } else {
+            self.who.join("; ")
+        };
+        let field = |v: &Option<String>| v.as_deref().unwrap_or(ERC_UNKNOWN).to_string();
+        format!(
+            "erc:\nwho: {}\nwhat: {}\nwhen: {}\nwhere: {}\n",
+            erc_escape(&who),
+            erc_escape(&field(&self.what)),
+            erc_escape(&field(&self.when)),
+            erc_escape(&self.r#where),
+        )
+    }
+
+    /// Render as schema.org JSON-LD. Unknown elements are omitted rather than
+    /// coded.
+    pub fn to_json_ld(&self) -> serde_json::Value {
+        let mut doc = serde_json::json!({
+            "@context": "https://schema.org",
+            "@type": "CreativeWork",
+            "@id": self.r#where,
+            "identifier": self.r#where,
+            "url": self.r#where,
+        });
+        if !self.who.is_empty() {
+            let authors: Vec<serde_json::Value> = self
+                .who
+                .iter()
+                .map(|name| serde_json::json!({ "@type": "Person", "name": name }))
+                .collect();
+            doc["author"] = serde_json::Value::Array(authors);
+        }
+        if let Some(what) = &self.what {
+            doc["name"] = what.clone().into();
+        }
+        if let Some(when) = &self.when {
+            doc["dateCreated"] = when.clone().into();
+        }
+        doc
+    }
+}
+
+/// Whether an `Accept` header asks for the JSON-LD rendering of `?info`
+/// rather than ERC text.
+pub fn info_wants_json_ld(accept: Option<&str>) -> bool {
+    accept.is_some_and(|a| a.contains("application/ld+json") || a.contains("application/json"))
+}
+
+fn scalar_string(v: &serde_json::Value) -> Option<String> {
+    match v {
+        serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
     }
 }
 
+/// ERC values are single-line; `%` and line breaks are percent-encoded so a
+/// value can't forge a following element.
+fn erc_escape(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Build the JSON body for a `?versions` inflection: the file's retained
+/// versions, newest first, each with its `.<FILE>` ARK.
+pub fn versions_json(versions: &[ArkVersionEntry]) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = versions
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "version": v.version,
+                "ark": canonical_ark(&v.workspace_ark, &v.file_ark, Some(&v.version)),
+                "created_at": v.created_at,
+            })
+        })
+        .collect();
+    serde_json::json!({ "versions": items })
+}
+
 pub struct ArkService<'a> {
     ark_index: &'a dyn ArkIndexStore,
 }
@@ -155,6 +319,71 @@ impl<'a> ArkService<'a> {
             .await?
             .ok_or_else(|| ServerCoreError::not_found("ARK not found"))
     }
+
+    /// Record a retained source version (see
+    /// [`ObjectService::retain_version`](crate::use_cases::objects::ObjectService::retain_version)).
+    /// Idempotent: republishing identical content keeps the original row.
+    pub async fn record_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        version: &str,
+        object_key: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.ark_index
+            .record_ark_version(workspace_ark, file_ark, version, object_key)
+            .await
+    }
+
+    /// List a file ARK's retained versions, newest first.
+    pub async fn list_versions(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+    ) -> Result<Vec<ArkVersionEntry>, ServerCoreError> {
+        let mut versions = self
+            .ark_index
+            .list_ark_versions(workspace_ark, file_ark)
+            .await?;
+        versions.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.version.cmp(&b.version))
+        });
+        Ok(versions)
+    }
+
+    /// Resolve a `.<FILE>` variant to one retained version. The variant is a
+    /// content-hash prefix of at least [`ARK_VERSION_MIN_PREFIX`] characters
+    /// (case-insensitive); a prefix matching more than one version is rejected
+    /// rather than guessed.
+    pub async fn resolve_version(
+        &self,
+        workspace_ark: &str,
+        file_ark: &str,
+        variant: &str,
+    ) -> Result<ArkVersionEntry, ServerCoreError> {
+        if variant.len() < ARK_VERSION_MIN_PREFIX {
+            return Err(ServerCoreError::invalid_input(format!(
+                "version must be at least {ARK_VERSION_MIN_PREFIX} characters"
+            )));
+        }
+        let variant = variant.to_ascii_lowercase();
+        let mut matches: Vec<ArkVersionEntry> = self
+            .ark_index
+            .list_ark_versions(workspace_ark, file_ark)
+            .await?
+            .into_iter()
+            .filter(|v| v.version.starts_with(&variant))
+            .collect();
+        match matches.len() {
+            0 => Err(ServerCoreError::not_found("ARK version not found")),
+            1 => Ok(matches.remove(0)),
+            _ => Err(ServerCoreError::invalid_input(
+                "ambiguous version prefix; use more characters",
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -169,6 +398,7 @@ mod tests {
     #[derive(Default)]
     struct TestArkIndexStore {
         rows: Mutex<ArkRows>,
+        versions: Mutex<Vec<ArkVersionEntry>>,
     }
 
     crate::cfg_async_trait! {
@@ -242,6 +472,43 @@ mod tests {
                 })
                 .collect())
         }
+        async fn record_ark_version(
+            &self,
+            workspace_ark: &str,
+            file_ark: &str,
+            version: &str,
+            object_key: &str,
+        ) -> Result<(), ServerCoreError> {
+            let mut versions = self.versions.lock().unwrap();
+            let exists = versions.iter().any(|v| {
+                v.workspace_ark == workspace_ark && v.file_ark == file_ark && v.version == version
+            });
+            if !exists {
+                let created_at = versions.len() as i64 + 1;
+                versions.push(ArkVersionEntry {
+                    workspace_ark: workspace_ark.to_string(),
+                    file_ark: file_ark.to_string(),
+                    version: version.to_string(),
+                    object_key: object_key.to_string(),
+                    created_at,
+                });
+            }
+            Ok(())
+        }
+        async fn list_ark_versions(
+            &self,
+            workspace_ark: &str,
+            file_ark: &str,
+        ) -> Result<Vec<ArkVersionEntry>, ServerCoreError> {
+            Ok(self
+                .versions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|v| v.workspace_ark == workspace_ark && v.file_ark == file_ark)
+                .cloned()
+                .collect())
+        }
     }
     }
 
@@ -273,6 +540,9 @@ mod tests {
         assert_eq!(Inflection::parse("content"), Inflection::Content);
         assert_eq!(Inflection::parse("json"), Inflection::Json);
         assert_eq!(Inflection::parse("info"), Inflection::Info);
+        // `??` (raw query `?`) is the legacy N2T spelling of `?info`.
+        assert_eq!(Inflection::parse("?"), Inflection::Info);
+        assert_eq!(Inflection::parse("versions"), Inflection::Versions);
         assert_eq!(
             Inflection::parse("meta=title"),
             Inflection::Meta("title".to_string())
@@ -283,7 +553,20 @@ mod tests {
             Inflection::Meta("info".to_string())
         );
         // Reserved/unknown falls back to default.
-        assert_eq!(Inflection::parse("?"), Inflection::Default);
+        assert_eq!(Inflection::parse("??"), Inflection::Default);
+    }
+
+    #[test]
+    fn file_segment_variant_split() {
+        assert_eq!(split_file_variant("bcdfgr").unwrap(), ("bcdfgr", None));
+        assert_eq!(
+            split_file_variant("bcdfgr.3f9a0c1").unwrap(),
+            ("bcdfgr", Some("3f9a0c1"))
+        );
+        assert!(matches!(
+            split_file_variant("bcdfgr."),
+            Err(ServerCoreError::InvalidInput(_))
+        ));
     }
 
     #[test]
@@ -294,15 +577,50 @@ mod tests {
         assert_eq!(json["frontmatter"]["title"], "Hello");
         assert_eq!(json["body"].as_str().unwrap().trim(), "Body text");
 
-        let info = inflection_json(src, &Inflection::Info).unwrap();
-        assert_eq!(info["title"], "Hello");
-        assert_eq!(info["id"], "bcdfgr");
-
         let meta = inflection_json(src, &Inflection::Meta("title".to_string())).unwrap();
         assert_eq!(meta, serde_json::json!("Hello"));
 
         let missing = inflection_json(src, &Inflection::Meta("nope".to_string()));
         assert!(matches!(missing, Err(ServerCoreError::NotFound(_))));
+
+        // `?info` is ERC, not raw frontmatter.
+        assert!(inflection_json(src, &Inflection::Info).is_err());
+    }
+
+    #[test]
+    fn erc_kernel_maps_frontmatter() {
+        let src = "---\ntitle: Hello\nauthor:\n- Ada\n- Grace\ncreated: 2025-12-05\nplugins: {}\n---\n\nBody\n";
+        let ark = canonical_ark("dxbcdfgh6", "bcdfgr", None);
+        assert_eq!(ark, "ark:99999/dxbcdfgh6/bcdfgr");
+
+        let erc = ErcKernel::from_source(src, &ark).unwrap();
+        assert_eq!(
+            erc.to_erc(),
+            "erc:\nwho: Ada; Grace\nwhat: Hello\nwhen: 2025-12-05\nwhere: ark:99999/dxbcdfgh6/bcdfgr\n"
+        );
+
+        let ld = erc.to_json_ld();
+        assert_eq!(ld["@id"], "ark:99999/dxbcdfgh6/bcdfgr");
+        assert_eq!(ld["name"], "Hello");
+        assert_eq!(ld["dateCreated"], "2025-12-05");
+        assert_eq!(ld["author"][1]["name"], "Grace");
+        assert!(ld.get("plugins").is_none());
+    }
+
+    #[test]
+    fn erc_kernel_codes_missing_elements_and_escapes() {
+        let src = "---\ntitle: \"Two%\\nlines\"\n---\n";
+        let ark = canonical_ark("dxbcdfgh6", ARK_WORKSPACE_INDEX, None);
+        assert_eq!(ark, "ark:99999/dxbcdfgh6");
+
+        let erc = ErcKernel::from_source(src, &ark).unwrap().to_erc();
+        assert!(erc.contains("who: (:unkn)\n"));
+        assert!(erc.contains("what: Two%25%0Alines\n"));
+        assert!(erc.contains("when: (:unkn)\n"));
+
+        assert!(info_wants_json_ld(Some("application/ld+json")));
+        assert!(!info_wants_json_ld(Some("text/plain")));
+        assert!(!info_wants_json_ld(None));
     }
 
     #[tokio::test]
@@ -398,4 +716,77 @@ mod tests {
             .unwrap_err();
         assert!(matches!(err, ServerCoreError::Conflict(_)));
     }
+
+    #[tokio::test]
+    async fn versions_are_recorded_once_and_listed_newest_first() {
+        let store = TestArkIndexStore::default();
+        let service = ArkService::new(&store);
+        let v1 = "3f9a0c1d".repeat(8);
+        let v2 = "77aa01bc".repeat(8);
+
+        service
+            .record_version("dxbcdfgh6", "bcdfgr", &v1, "_versions/bcdfgr/v1")
+            .await
+            .unwrap();
+        service
+            .record_version("dxbcdfgh6", "bcdfgr", &v2, "_versions/bcdfgr/v2")
+            .await
+            .unwrap();
+        // Republishing identical content must not add (or re-date) a version.
+        service
+            .record_version("dxbcdfgh6", "bcdfgr", &v1, "_versions/bcdfgr/v1")
+            .await
+            .unwrap();
+
+        let versions = service.list_versions("dxbcdfgh6", "bcdfgr").await.unwrap();
+        let ids: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(ids, vec![v2.as_str(), v1.as_str()]);
+
+        let json = versions_json(&versions);
+        assert_eq!(
+            json["versions"][1]["ark"],
+            format!("ark:{ARK_NAAN}/dxbcdfgh6/bcdfgr.{v1}")
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_version_matches_unique_prefix() {
+        let store = TestArkIndexStore::default();
+        let service = ArkService::new(&store);
+        let v1 = format!("3f9a0c1d{}", "0".repeat(56));
+        let v2 = format!("3f9a0c1e{}", "0".repeat(56));
+        for (v, key) in [(&v1, "k1"), (&v2, "k2")] {
+            service
+                .record_version("dxbcdfgh6", "bcdfgr", v, key)
+                .await
+                .unwrap();
+        }
+
+        // Unique prefix, matched case-insensitively.
+        let hit = service
+            .resolve_version("dxbcdfgh6", "bcdfgr", "3F9A0C1D")
+            .await
+            .unwrap();
+        assert_eq!(hit.object_key, "k1");
+
+        // Shared prefix: rejected rather than guessed.
+        let err = service
+            .resolve_version("dxbcdfgh6", "bcdfgr", "3f9a0c1")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        // Too short to be a version selector at all.
+        let err = service
+            .resolve_version("dxbcdfgh6", "bcdfgr", "3f9a0c")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        let err = service
+            .resolve_version("dxbcdfgh6", "bcdfgr", "3f9a0c0")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+    }
 }
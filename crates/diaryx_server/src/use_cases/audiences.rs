@@ -1,6 +1,11 @@
 use crate::audience_token::{AudienceTokenClaims, GateKind, create_audience_token};
-use crate::domain::{AudienceInfo, GateInput, GateRecord};
-use crate::ports::{BlobStore, NamespaceStore, ServerCoreError};
+use crate::domain::{
+    AudienceInfo, AuditAction, GateInput, GateRecord, NamespaceRole, WebhookEvent,
+};
+use crate::ports::{BlobStore, NamespaceMemberStore, NamespaceStore, ServerCoreError};
+use crate::use_cases::audit::AuditRecorder;
+use crate::use_cases::members::require_namespace_role;
+use crate::use_cases::webhooks::WebhookDispatcher;
 use argon2::Argon2;
 use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
 use serde::{Deserialize, Serialize};
@@ -80,6 +85,9 @@ pub struct TokenResponse {
 pub struct AudienceService<'a> {
     namespace_store: &'a dyn NamespaceStore,
     blob_store: &'a dyn BlobStore,
+    member_store: Option<&'a dyn NamespaceMemberStore>,
+    webhooks: Option<WebhookDispatcher<'a>>,
+    audit: Option<AuditRecorder<'a>>,
 }
 
 /// Result of a successful password verification: the password gate's current
@@ -94,33 +102,49 @@ impl<'a> AudienceService<'a> {
         Self {
             namespace_store,
             blob_store,
+            member_store: None,
+            webhooks: None,
+            audit: None,
         }
     }
 
-    fn require_owner<'b>(
-        &self,
-        ns: &'b crate::domain::NamespaceInfo,
-        caller_user_id: &str,
-    ) -> Result<(), ServerCoreError> {
-        if ns.owner_user_id != caller_user_id {
-            return Err(ServerCoreError::permission_denied(
-                "You do not own this namespace",
-            ));
-        }
-        Ok(())
+    /// Let namespace collaborators in: viewers can list audiences; changing
+    /// them stays owner-only. Without a member store only the owner has
+    /// access.
+    pub fn with_members(mut self, member_store: &'a dyn NamespaceMemberStore) -> Self {
+        self.member_store = Some(member_store);
+        self
+    }
+
+    /// Notify the namespace's webhooks when an audience is set, deleted, or
+    /// has its password rotated.
+    pub fn with_webhooks(mut self, webhooks: WebhookDispatcher<'a>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Record audience changes, password rotations, and password unlocks
+    /// (successful or not) in the namespace's audit log.
+    pub fn with_audit(mut self, audit: AuditRecorder<'a>) -> Self {
+        self.audit = Some(audit);
+        self
     }
 
-    async fn require_namespace_owner(
+    async fn require_role(
         &self,
         namespace_id: &str,
         caller_user_id: &str,
+        role: NamespaceRole,
     ) -> Result<(), ServerCoreError> {
-        let ns = self
-            .namespace_store
-            .get_namespace(namespace_id)
-            .await?
-            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
-        self.require_owner(&ns, caller_user_id)
+        require_namespace_role(
+            self.namespace_store,
+            self.member_store,
+            namespace_id,
+            caller_user_id,
+            role,
+        )
+        .await
+        .map(|_| ())
     }
 
     /// Upsert an audience with the given gate set.
@@ -143,7 +167,7 @@ impl<'a> AudienceService<'a> {
         inputs: Vec<GateInput>,
         caller_user_id: &str,
     ) -> Result<AudienceInfo, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
             .await?;
 
         validate_no_duplicate_kinds(&inputs)?;
@@ -190,6 +214,16 @@ impl<'a> AudienceService<'a> {
             .ok_or_else(|| ServerCoreError::internal("Audience missing after upsert"))?;
 
         self.write_audiences_meta(namespace_id).await;
+        self.emit_audience_changed(namespace_id, audience_name, "updated", &info.gates)
+            .await;
+        self.audit(
+            AuditAction::AudienceUpdated,
+            namespace_id,
+            Some(caller_user_id),
+            audience_name,
+            serde_json::Value::Null,
+        )
+        .await;
         Ok(info)
     }
 
@@ -198,7 +232,7 @@ impl<'a> AudienceService<'a> {
         namespace_id: &str,
         caller_user_id: &str,
     ) -> Result<Vec<AudienceInfo>, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
             .await?;
         self.namespace_store.list_audiences(namespace_id).await
     }
@@ -209,7 +243,7 @@ impl<'a> AudienceService<'a> {
         audience_name: &str,
         caller_user_id: &str,
     ) -> Result<(), ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
             .await?;
 
         if self
@@ -228,6 +262,16 @@ impl<'a> AudienceService<'a> {
             .delete_audience(namespace_id, audience_name)
             .await?;
         self.write_audiences_meta(namespace_id).await;
+        self.emit_audience_changed(namespace_id, audience_name, "deleted", &[])
+            .await;
+        self.audit(
+            AuditAction::AudienceDeleted,
+            namespace_id,
+            Some(caller_user_id),
+            audience_name,
+            serde_json::Value::Null,
+        )
+        .await;
         Ok(())
     }
 
@@ -239,7 +283,7 @@ impl<'a> AudienceService<'a> {
         audience_name: &str,
         caller_user_id: &str,
     ) -> Result<AudienceInfo, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
             .await?;
 
         let audience = self
@@ -291,9 +335,33 @@ impl<'a> AudienceService<'a> {
         audience_name: &str,
         password: &str,
     ) -> Result<TokenResponse, ServerCoreError> {
-        let verified = self
+        let verified = match self
             .verify_password(namespace_id, audience_name, password)
-            .await?;
+            .await
+        {
+            Ok(verified) => verified,
+            Err(e) => {
+                if matches!(e, ServerCoreError::PermissionDenied(_)) {
+                    self.audit(
+                        AuditAction::AudienceUnlockFailed,
+                        namespace_id,
+                        None,
+                        audience_name,
+                        serde_json::Value::Null,
+                    )
+                    .await;
+                }
+                return Err(e);
+            }
+        };
+        self.audit(
+            AuditAction::AudienceUnlocked,
+            namespace_id,
+            None,
+            audience_name,
+            serde_json::json!({ "password_version": verified.version }),
+        )
+        .await;
         let claims = AudienceTokenClaims {
             slug: namespace_id.to_string(),
             audience: audience_name.to_string(),
@@ -383,7 +451,7 @@ impl<'a> AudienceService<'a> {
         new_password: &str,
         caller_user_id: &str,
     ) -> Result<u32, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
             .await?;
 
         let audience = self
@@ -416,9 +484,74 @@ impl<'a> AudienceService<'a> {
             .await?;
 
         self.write_audiences_meta(namespace_id).await;
+        self.emit_audience_changed(namespace_id, audience_name, "password_rotated", &gates)
+            .await;
+        self.audit(
+            AuditAction::AudiencePasswordRotated,
+            namespace_id,
+            Some(caller_user_id),
+            audience_name,
+            serde_json::json!({ "password_version": new_version }),
+        )
+        .await;
         Ok(new_version)
     }
 
+    async fn audit(
+        &self,
+        action: AuditAction,
+        namespace_id: &str,
+        actor_user_id: Option<&str>,
+        audience_name: &str,
+        details: serde_json::Value,
+    ) {
+        if let Some(audit) = &self.audit {
+            audit
+                .namespace(
+                    action,
+                    namespace_id,
+                    actor_user_id,
+                    Some(audience_name),
+                    details,
+                )
+                .await;
+        }
+    }
+
+    /// Queue an `audience_changed` webhook event. Like the metadata blob, the
+    /// payload only names the gate kinds (and password version), never hashes.
+    async fn emit_audience_changed(
+        &self,
+        namespace_id: &str,
+        audience_name: &str,
+        change: &str,
+        gates: &[GateRecord],
+    ) {
+        let Some(webhooks) = &self.webhooks else {
+            return;
+        };
+        let gates: Vec<serde_json::Value> = gates
+            .iter()
+            .map(|g| match g {
+                GateRecord::Link => serde_json::json!({ "kind": "link" }),
+                GateRecord::Password { version, .. } => {
+                    serde_json::json!({ "kind": "password", "version": version })
+                }
+            })
+            .collect();
+        webhooks
+            .emit(
+                namespace_id,
+                WebhookEvent::AudienceChanged,
+                serde_json::json!({
+                    "audience": audience_name,
+                    "change": change,
+                    "gates": gates,
+                }),
+            )
+            .await;
+    }
+
     /// Write `ns/{ns_id}/_audiences.json` to the blob store.
     /// The file contains a map of audience name → `{ gates: [...] }` so the
     /// site-proxy can evaluate gates without additional round-trips. Password
@@ -976,6 +1109,63 @@ mod tests {
         assert_eq!(verified.version, 2);
     }
 
+    #[tokio::test]
+    async fn audit_records_rotations_and_unlock_attempts() {
+        use crate::domain::{AuditAction, AuditScope};
+        use crate::ports::AuditLogStore;
+        use crate::testing::InMemoryAuditLogStore;
+        use crate::use_cases::audit::AuditRecorder;
+
+        let store = make_store_with_namespace("user1", "ns1");
+        let blob_store = TestBlobStore::default();
+        let audit = InMemoryAuditLogStore::new();
+        let service =
+            AudienceService::new(&store, &blob_store).with_audit(AuditRecorder::new(&audit));
+
+        service
+            .set(
+                "ns1",
+                "inner",
+                vec![GateInput::Password {
+                    password: Some("old".to_string()),
+                }],
+                "user1",
+            )
+            .await
+            .unwrap();
+        service
+            .rotate_password("ns1", "inner", "new", "user1")
+            .await
+            .unwrap();
+        service
+            .unlock_with_password(b"key", "ns1", "inner", "old")
+            .await
+            .unwrap_err();
+        service
+            .unlock_with_password(b"key", "ns1", "inner", "new")
+            .await
+            .unwrap();
+
+        let events = audit
+            .list_audit_events(&AuditScope::Namespace("ns1".to_string()), None, 10)
+            .await
+            .unwrap();
+        let actions: Vec<AuditAction> = events.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::AudienceUnlocked,
+                AuditAction::AudienceUnlockFailed,
+                AuditAction::AudiencePasswordRotated,
+                AuditAction::AudienceUpdated,
+            ]
+        );
+        assert_eq!(events[0].details["password_version"], 2);
+        assert!(events[0].actor_user_id.is_none());
+        assert_eq!(events[2].actor_user_id.as_deref(), Some("user1"));
+        assert!(events.iter().all(|e| e.target.as_deref() == Some("inner")));
+    }
+
     #[tokio::test]
     async fn require_link_eligible_rejects_audience_without_link_gate() {
         let store = make_store_with_namespace("user1", "ns1");
@@ -0,0 +1,299 @@
+//! Audit log of security-relevant events: sign-ins, passkeys, access tokens,
+//! audience gates and passwords, and domains.
+//!
+//! Services that perform these actions are given an [`AuditRecorder`]
+//! through their `with_audit` builder and append an [`AuditEvent`] to the
+//! [`AuditLogStore`] once the action has happened. Recording is best-effort:
+//! a failed append is logged and never undoes or fails the action.
+//!
+//! [`AuditLogService`] serves the reads, which are owner-scoped: a user sees
+//! their own account's events and the events of namespaces they own. Entries
+//! are never edited; [`AuditLogService::prune`] drops those older than the
+//! adapter's retention period.
+
+use crate::domain::{AuditAction, AuditEvent, AuditScope, NamespaceRole};
+use crate::ports::{AuditLogStore, NamespaceStore, ServerCoreError};
+use crate::use_cases::members::require_namespace_role;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Days an audit event is kept when the adapter doesn't configure it.
+pub const DEFAULT_AUDIT_RETENTION_DAYS: u32 = 365;
+
+/// Events returned by a log read when no limit is given.
+pub const DEFAULT_AUDIT_LOG_LIMIT: u32 = 50;
+const MAX_AUDIT_LOG_LIMIT: u32 = 200;
+
+/// Query string of the audit log endpoints. `before` pages back from the
+/// `created_at` of the oldest event already seen.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AuditLogQuery {
+    #[serde(default)]
+    pub before: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// Recording
+// ---------------------------------------------------------------------------
+
+/// Appends events on behalf of the services that raise them.
+#[derive(Clone, Copy)]
+pub struct AuditRecorder<'a> {
+    audit_store: &'a dyn AuditLogStore,
+}
+
+impl<'a> AuditRecorder<'a> {
+    pub fn new(audit_store: &'a dyn AuditLogStore) -> Self {
+        Self { audit_store }
+    }
+
+    /// Record an event on `user_id`'s account, performed by that user.
+    pub async fn account(
+        &self,
+        action: AuditAction,
+        user_id: &str,
+        target: Option<&str>,
+        details: Value,
+    ) {
+        self.record(AuditEvent {
+            id: Uuid::new_v4().to_string(),
+            action,
+            actor_user_id: Some(user_id.to_string()),
+            user_id: Some(user_id.to_string()),
+            namespace_id: None,
+            target: target.map(str::to_string),
+            details,
+            created_at: Utc::now().timestamp(),
+        })
+        .await;
+    }
+
+    /// Record an event on a namespace. `actor_user_id` is `None` for
+    /// anonymous readers.
+    pub async fn namespace(
+        &self,
+        action: AuditAction,
+        namespace_id: &str,
+        actor_user_id: Option<&str>,
+        target: Option<&str>,
+        details: Value,
+    ) {
+        self.record(AuditEvent {
+            id: Uuid::new_v4().to_string(),
+            action,
+            actor_user_id: actor_user_id.map(str::to_string),
+            user_id: None,
+            namespace_id: Some(namespace_id.to_string()),
+            target: target.map(str::to_string),
+            details,
+            created_at: Utc::now().timestamp(),
+        })
+        .await;
+    }
+
+    /// Append `event`. Best-effort — errors are logged, not returned.
+    pub async fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.audit_store.append_audit_event(&event).await {
+            warn!(
+                "Failed to record {} audit event: {}",
+                event.action.as_str(),
+                e
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reading and retention
+// ---------------------------------------------------------------------------
+
+/// Owner-scoped reads of the audit log, and retention pruning.
+pub struct AuditLogService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    audit_store: &'a dyn AuditLogStore,
+}
+
+impl<'a> AuditLogService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        audit_store: &'a dyn AuditLogStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            audit_store,
+        }
+    }
+
+    /// Events on the caller's own account, newest first.
+    pub async fn list_for_user(
+        &self,
+        user_id: &str,
+        query: &AuditLogQuery,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        self.list(AuditScope::User(user_id.to_string()), query)
+            .await
+    }
+
+    /// Events on a namespace, newest first. Owner-only: collaborators don't
+    /// see who unlocked or reconfigured what.
+    pub async fn list_for_namespace(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        query: &AuditLogQuery,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            None,
+            namespace_id,
+            caller_user_id,
+            NamespaceRole::Owner,
+        )
+        .await?;
+        self.list(AuditScope::Namespace(namespace_id.to_string()), query)
+            .await
+    }
+
+    async fn list(
+        &self,
+        scope: AuditScope,
+        query: &AuditLogQuery,
+    ) -> Result<Vec<AuditEvent>, ServerCoreError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_AUDIT_LOG_LIMIT)
+            .clamp(1, MAX_AUDIT_LOG_LIMIT);
+        self.audit_store
+            .list_audit_events(&scope, query.before, limit)
+            .await
+    }
+
+    /// Drop events older than `retention_days` before `now`. A retention of
+    /// zero keeps everything.
+    pub async fn prune(&self, retention_days: u32, now: i64) -> Result<u64, ServerCoreError> {
+        if retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = now - i64::from(retention_days) * 86_400;
+        self.audit_store.prune_audit_events(cutoff).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditLogQuery, AuditLogService, AuditRecorder};
+    use crate::domain::{AuditAction, AuditEvent};
+    use crate::ports::{AuditLogStore, NamespaceStore, ServerCoreError};
+    use crate::testing::{InMemoryAuditLogStore, InMemoryNamespaceStore};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn reads_are_scoped_to_the_owner() {
+        let ns_store = InMemoryNamespaceStore::new();
+        ns_store
+            .create_namespace("ns1", "owner", None)
+            .await
+            .unwrap();
+        let audit = InMemoryAuditLogStore::new();
+        let recorder = AuditRecorder::new(&audit);
+
+        recorder
+            .account(
+                AuditAction::SignIn,
+                "owner",
+                None,
+                json!({ "device_id": "d1" }),
+            )
+            .await;
+        recorder
+            .account(
+                AuditAction::PasskeyRegistered,
+                "someone-else",
+                Some("pk1"),
+                json!(null),
+            )
+            .await;
+        recorder
+            .namespace(
+                AuditAction::AudienceUnlocked,
+                "ns1",
+                None,
+                Some("family"),
+                json!(null),
+            )
+            .await;
+
+        let service = AuditLogService::new(&ns_store, &audit);
+        let mine = service
+            .list_for_user("owner", &AuditLogQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(mine.len(), 1);
+        assert_eq!(mine[0].action, AuditAction::SignIn);
+        assert_eq!(mine[0].details["device_id"], "d1");
+
+        let ns_events = service
+            .list_for_namespace("ns1", "owner", &AuditLogQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(ns_events.len(), 1);
+        assert_eq!(ns_events[0].target.as_deref(), Some("family"));
+        assert!(ns_events[0].actor_user_id.is_none());
+
+        let err = service
+            .list_for_namespace("ns1", "someone-else", &AuditLogQuery::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+    }
+
+    #[tokio::test]
+    async fn prune_applies_retention_and_paging_goes_back_in_time() {
+        let ns_store = InMemoryNamespaceStore::new();
+        let audit = InMemoryAuditLogStore::new();
+        for (id, created_at) in [("old", 100), ("mid", 200), ("new", 300)] {
+            audit
+                .append_audit_event(&AuditEvent {
+                    id: id.to_string(),
+                    action: AuditAction::SignIn,
+                    actor_user_id: Some("u1".to_string()),
+                    user_id: Some("u1".to_string()),
+                    namespace_id: None,
+                    target: None,
+                    details: json!(null),
+                    created_at,
+                })
+                .await
+                .unwrap();
+        }
+        let service = AuditLogService::new(&ns_store, &audit);
+
+        let page = service
+            .list_for_user(
+                "u1",
+                &AuditLogQuery {
+                    before: Some(300),
+                    limit: Some(1),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, "mid");
+
+        assert_eq!(service.prune(0, 1_000_000).await.unwrap(), 0);
+        // One day of retention at t = 86_400 + 150 drops only the event at 100.
+        assert_eq!(service.prune(1, 86_550).await.unwrap(), 1);
+        let left = service
+            .list_for_user("u1", &AuditLogQuery::default())
+            .await
+            .unwrap();
+        let ids: Vec<&str> = left.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["new", "mid"]);
+    }
+}
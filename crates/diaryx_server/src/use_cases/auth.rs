@@ -1,8 +1,11 @@
-use crate::domain::{AuthContext, DeviceInfo};
+use crate::domain::{AuditAction, AuthContext, AuthSessionInfo, DeviceInfo};
 use crate::ports::{
-    AuthSessionStore, AuthStore, DeviceStore, MagicLinkStore, ServerCoreError, UserStore,
+    AccessTokenStore, AuthSessionStore, AuthStore, DeviceStore, MagicLinkStore, ServerCoreError,
+    UserStore,
 };
-use chrono::{Duration, Utc};
+use crate::use_cases::access_tokens::{hash_access_token, is_access_token};
+use crate::use_cases::audit::AuditRecorder;
+use chrono::{DateTime, Duration, Utc};
 use serde::Serialize;
 
 // ---------------------------------------------------------------------------
@@ -114,6 +117,7 @@ pub struct AuthenticationService<'a> {
     device_store: &'a dyn DeviceStore,
     session_store: &'a dyn AuthSessionStore,
     config: &'a AuthConfig,
+    audit: Option<AuditRecorder<'a>>,
 }
 
 impl<'a> AuthenticationService<'a> {
@@ -130,9 +134,17 @@ impl<'a> AuthenticationService<'a> {
             device_store,
             session_store,
             config,
+            audit: None,
         }
     }
 
+    /// Record sign-ins, and devices evicted to make room for them, in the
+    /// user's audit log.
+    pub fn with_audit(mut self, audit: AuditRecorder<'a>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
     /// Request a magic link for the given email.
     /// Returns (token, verification_code).
     pub async fn request_magic_link(&self, email: &str) -> Result<(String, String), AuthError> {
@@ -238,6 +250,16 @@ impl<'a> AuthenticationService<'a> {
                     return Err(AuthError::InvalidReplaceDevice);
                 }
                 self.device_store.delete_device(replace_id).await?;
+                if let Some(audit) = &self.audit {
+                    audit
+                        .account(
+                            AuditAction::DeviceReplaced,
+                            &user_id,
+                            Some(replace_id),
+                            serde_json::Value::Null,
+                        )
+                        .await;
+                }
             } else {
                 let devices = self.device_store.list_user_devices(&user_id).await?;
                 return Err(AuthError::DeviceLimitReached {
@@ -260,6 +282,17 @@ impl<'a> AuthenticationService<'a> {
             .create_auth_session(&user_id, &device_id, expires_at)
             .await?;
 
+        if let Some(audit) = &self.audit {
+            audit
+                .account(
+                    AuditAction::SignIn,
+                    &user_id,
+                    Some(&device_id),
+                    serde_json::json!({ "device_name": device_name, "user_agent": user_agent }),
+                )
+                .await;
+        }
+
         Ok(VerifyResult {
             session_token,
             user_id,
@@ -272,6 +305,7 @@ impl<'a> AuthenticationService<'a> {
 pub struct SessionValidationService<'a> {
     auth_store: &'a dyn AuthStore,
     session_store: &'a dyn AuthSessionStore,
+    access_token_store: Option<&'a dyn AccessTokenStore>,
 }
 
 impl<'a> SessionValidationService<'a> {
@@ -279,16 +313,35 @@ impl<'a> SessionValidationService<'a> {
         Self {
             auth_store,
             session_store,
+            access_token_store: None,
         }
     }
 
+    /// Also accept personal access tokens (`dxp_…`). Without this, access
+    /// tokens are looked up as sessions and never match.
+    pub fn with_access_tokens(mut self, access_token_store: &'a dyn AccessTokenStore) -> Self {
+        self.access_token_store = Some(access_token_store);
+        self
+    }
+
     /// Validate a session token and return the full auth context.
     ///
     /// This is the core of authentication middleware: given a token string
     /// (extracted from a header, cookie, or query parameter by the HTTP layer),
     /// validate it against the session store, update the device heartbeat,
     /// and load the user info.
+    ///
+    /// Access tokens are validated against the access token store instead and
+    /// have their `last_used_at` stamped. The caller must still check the
+    /// request against the token with
+    /// [`authorize_request`](crate::use_cases::access_tokens::authorize_request).
     pub async fn validate(&self, token: &str) -> Result<AuthContext, ServerCoreError> {
+        if let Some(access_token_store) = self.access_token_store
+            && is_access_token(token)
+        {
+            return self.validate_access_token(access_token_store, token).await;
+        }
+
         let session = self
             .session_store
             .validate_session(token)
@@ -307,7 +360,51 @@ impl<'a> SessionValidationService<'a> {
             .await?
             .ok_or_else(|| ServerCoreError::not_found("User not found"))?;
 
-        Ok(AuthContext { session, user })
+        Ok(AuthContext {
+            session,
+            user,
+            access_token: None,
+        })
+    }
+
+    async fn validate_access_token(
+        &self,
+        access_token_store: &dyn AccessTokenStore,
+        token: &str,
+    ) -> Result<AuthContext, ServerCoreError> {
+        let now = Utc::now().timestamp();
+        let access_token = access_token_store
+            .get_access_token_by_hash(&hash_access_token(token))
+            .await?
+            .filter(|t| !t.is_expired(now))
+            .ok_or_else(|| ServerCoreError::not_found("Invalid or expired access token"))?;
+
+        // Best-effort, like the device heartbeat above.
+        let _ = access_token_store
+            .touch_access_token(&access_token.id, now)
+            .await;
+
+        let user = self
+            .auth_store
+            .get_user(&access_token.user_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("User not found"))?;
+
+        let session = AuthSessionInfo {
+            token: token.to_string(),
+            user_id: access_token.user_id.clone(),
+            device_id: String::new(),
+            expires_at: access_token
+                .expires_at
+                .and_then(|at| DateTime::from_timestamp(at, 0))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            created_at: DateTime::from_timestamp(access_token.created_at, 0).unwrap_or_default(),
+        };
+        Ok(AuthContext {
+            session,
+            user,
+            access_token: Some(access_token),
+        })
     }
 
     /// Delete a session (logout).
@@ -321,6 +418,10 @@ impl<'a> SessionValidationService<'a> {
 /// 2. `Cookie: diaryx_session=<token>` cookie
 /// 3. `?token=<token>` query parameter
 ///
+/// Personal access tokens are only honoured in the `Authorization` header:
+/// a `dxp_…` value in a cookie or query string is ignored, so a token can't
+/// be replayed from a browser cookie jar or leak through a logged URL.
+///
 /// This is a pure function — no I/O. The HTTP layer provides the raw values;
 /// a CF Worker and Axum extract them differently but both call this.
 pub fn extract_token(
@@ -343,6 +444,7 @@ pub fn extract_token(
         .map(|c| c.trim())
         .find(|c| c.starts_with("diaryx_session="))
         .and_then(|c| c.strip_prefix("diaryx_session="))
+        .filter(|s| !is_access_token(s))
         .map(|s| s.to_string())
     {
         return Some(token);
@@ -354,14 +456,19 @@ pub fn extract_token(
         .flat_map(|q| q.split('&'))
         .find(|p| p.starts_with("token="))
         .and_then(|p| p.strip_prefix("token="))
+        .filter(|s| !is_access_token(s))
         .map(|s| s.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{SessionValidationService, extract_token};
-    use crate::domain::{AuthSessionInfo, DeviceInfo, UserInfo, UserTier};
-    use crate::ports::{AuthSessionStore, AuthStore, ServerCoreError};
+    use crate::domain::{
+        AccessTokenInfo, AuthSessionInfo, DeviceInfo, TokenScope, UserInfo, UserTier,
+    };
+    use crate::ports::{AccessTokenStore, AuthSessionStore, AuthStore, ServerCoreError};
+    use crate::testing::InMemoryAccessTokenStore;
+    use crate::use_cases::access_tokens::hash_access_token;
     use chrono::{TimeZone, Utc};
     use std::collections::HashMap;
     use std::sync::Mutex;
@@ -482,6 +589,73 @@ mod tests {
         assert!(matches!(err, ServerCoreError::NotFound(_)));
     }
 
+    #[tokio::test]
+    async fn validate_accepts_access_tokens_when_configured() {
+        let (auth_store, session_store) = make_stores();
+        let tokens = InMemoryAccessTokenStore::new();
+        let info = AccessTokenInfo {
+            id: "tok1".to_string(),
+            user_id: "user1".to_string(),
+            name: "ci".to_string(),
+            scopes: vec![TokenScope::Publish],
+            namespace_ids: None,
+            created_at: 1,
+            expires_at: None,
+            last_used_at: None,
+        };
+        tokens
+            .create_access_token(&info, &hash_access_token("dxp_secret"))
+            .await
+            .unwrap();
+
+        // Without the store an access token is just an unknown session.
+        let plain = SessionValidationService::new(&auth_store, &session_store);
+        assert!(plain.validate("dxp_secret").await.is_err());
+
+        let service =
+            SessionValidationService::new(&auth_store, &session_store).with_access_tokens(&tokens);
+        let ctx = service.validate("dxp_secret").await.unwrap();
+        assert_eq!(ctx.user.id, "user1");
+        assert_eq!(
+            ctx.access_token.as_ref().map(|t| t.id.as_str()),
+            Some("tok1")
+        );
+        assert!(ctx.session.device_id.is_empty());
+        assert!(session_store.last_seen.lock().unwrap().is_empty());
+
+        let listed = tokens.list_access_tokens("user1").await.unwrap();
+        assert!(listed[0].last_used_at.is_some());
+
+        // Device sessions still validate through the same service.
+        let ctx = service.validate("valid-token").await.unwrap();
+        assert!(ctx.access_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn validate_rejects_expired_access_tokens() {
+        let (auth_store, session_store) = make_stores();
+        let tokens = InMemoryAccessTokenStore::new();
+        let info = AccessTokenInfo {
+            id: "tok1".to_string(),
+            user_id: "user1".to_string(),
+            name: "ci".to_string(),
+            scopes: vec![TokenScope::Read],
+            namespace_ids: None,
+            created_at: 1,
+            expires_at: Some(2),
+            last_used_at: None,
+        };
+        tokens
+            .create_access_token(&info, &hash_access_token("dxp_old"))
+            .await
+            .unwrap();
+
+        let service =
+            SessionValidationService::new(&auth_store, &session_store).with_access_tokens(&tokens);
+        let err = service.validate("dxp_old").await.unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+    }
+
     #[test]
     fn extract_token_from_bearer() {
         let token = extract_token(Some("Bearer abc123"), None, None);
@@ -520,6 +694,14 @@ mod tests {
         assert_eq!(token.as_deref(), Some("from-cookie"));
     }
 
+    #[test]
+    fn extract_token_accepts_access_tokens_only_from_bearer() {
+        let token = extract_token(Some("Bearer dxp_abc"), None, None);
+        assert_eq!(token.as_deref(), Some("dxp_abc"));
+        assert!(extract_token(None, Some("diaryx_session=dxp_abc"), None).is_none());
+        assert!(extract_token(None, None, Some("token=dxp_abc")).is_none());
+    }
+
     #[test]
     fn extract_token_returns_none_when_empty() {
         assert!(extract_token(None, None, None).is_none());
@@ -0,0 +1,672 @@
+//! Reader comments on the pages of gated audiences.
+//!
+//! Family and friends who open a password- or link-gated audience already
+//! hold an audience token for it; that token is what lets them comment.
+//! Adapters validate the token's signature (see
+//! [`crate::audience_token::validate_audience_token`]) and hand the claims
+//! to [`CommentReader`], which checks them against the audience's current
+//! gates the same way object access does — so rotating a password or
+//! dropping the link gate also closes its comments. Public audiences have no
+//! tokens and take no comments.
+//!
+//! New comments wait for the owner, who is emailed through the [`Mailer`]
+//! and approves, rejects or deletes them through [`CommentService`].
+//! Approved comments are shown to everyone with access to the audience, and
+//! `diaryx publish` pulls them into the workspace next to their entry.
+
+use crate::audience_token::{AudienceTokenClaims, GateKind};
+use crate::domain::{ArkIndexEntry, CommentInfo, CommentStatus, GateRecord, NamespaceRole};
+use crate::ports::{
+    ArkIndexStore, AuthStore, CommentStore, Mailer, NamespaceStore, ServerCoreError,
+};
+use crate::use_cases::ark::ARK_WORKSPACE_INDEX;
+use crate::use_cases::members::{namespace_display_name, require_namespace_role};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Comments a namespace can hold awaiting moderation. New ones are refused
+/// past this until the owner works through the queue.
+pub const MAX_PENDING_COMMENTS: usize = 500;
+
+/// Longest author name and body accepted, in characters.
+pub const MAX_AUTHOR_NAME_CHARS: usize = 80;
+pub const MAX_COMMENT_BODY_CHARS: usize = 5_000;
+
+/// Comments returned by the moderation list when no limit is given.
+pub const DEFAULT_COMMENT_LIST_LIMIT: u32 = 50;
+const MAX_COMMENT_LIST_LIMIT: u32 = 500;
+
+/// Approved comments shown under one page.
+const MAX_PAGE_COMMENTS: u32 = 1_000;
+
+/// Body of a reader's new comment.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PostCommentRequest {
+    pub author_name: String,
+    pub body: String,
+}
+
+/// Query string of the moderation list. `status=approved` is what
+/// `diaryx publish` asks for.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CommentListQuery {
+    #[serde(default)]
+    pub status: Option<CommentStatus>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// A comment as readers see it: no token id or moderation state.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ReaderComment {
+    pub id: String,
+    pub author_name: String,
+    pub body: String,
+    pub created_at: i64,
+}
+
+impl From<CommentInfo> for ReaderComment {
+    fn from(comment: CommentInfo) -> Self {
+        Self {
+            id: comment.id,
+            author_name: comment.author_name,
+            body: comment.body,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+/// Whether `claims` satisfy one of `gates` on `audience` of namespace
+/// `slug`. An ungated (public) audience grants nothing: there's no token to
+/// authenticate a commenter with.
+fn claims_grant_access(
+    gates: &[GateRecord],
+    audience: &str,
+    slug: &str,
+    claims: &AudienceTokenClaims,
+) -> bool {
+    if claims.slug != slug || claims.audience != audience {
+        return false;
+    }
+    gates.iter().any(|gate| match gate {
+        GateRecord::Link => claims.gate == GateKind::Link,
+        GateRecord::Password { version, .. } => {
+            claims.gate == GateKind::Unlock && claims.password_version == Some(*version)
+        }
+    })
+}
+
+fn trimmed_text(value: &str, field: &str, max_chars: usize) -> Result<String, ServerCoreError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(ServerCoreError::invalid_input(format!(
+            "{field} is required"
+        )));
+    }
+    if value.chars().count() > max_chars {
+        return Err(ServerCoreError::invalid_input(format!(
+            "{field} is longer than {max_chars} characters"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Readers (audience token)
+// ---------------------------------------------------------------------------
+
+/// Posts and lists comments on behalf of a reader holding an audience token.
+pub struct CommentReader<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    ark_index: &'a dyn ArkIndexStore,
+    comment_store: &'a dyn CommentStore,
+    notify: Option<(&'a dyn AuthStore, &'a dyn Mailer)>,
+}
+
+impl<'a> CommentReader<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        ark_index: &'a dyn ArkIndexStore,
+        comment_store: &'a dyn CommentStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            ark_index,
+            comment_store,
+            notify: None,
+        }
+    }
+
+    /// Email the namespace owner about each new comment through `mailer`.
+    /// Without one, comments still wait in the moderation queue.
+    pub fn with_mailer(mut self, auth_store: &'a dyn AuthStore, mailer: &'a dyn Mailer) -> Self {
+        self.notify = Some((auth_store, mailer));
+        self
+    }
+
+    /// The page `object_key` names, once `claims` are shown to grant access
+    /// to its audience. Anything else is `PermissionDenied`, so a reader
+    /// can't tell missing pages from ones they can't see.
+    async fn authorized_page(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+        claims: &AudienceTokenClaims,
+    ) -> Result<ArkIndexEntry, ServerCoreError> {
+        let denied = || ServerCoreError::permission_denied("Audience token does not grant access");
+        let page = self
+            .ark_index
+            .list_ark_entries(namespace_id)
+            .await?
+            .into_iter()
+            .find(|row| row.file_ark != ARK_WORKSPACE_INDEX && row.object_key == object_key)
+            .ok_or_else(denied)?;
+        let audience = page.audience.as_deref().ok_or_else(denied)?;
+        let info = self
+            .namespace_store
+            .get_audience(namespace_id, audience)
+            .await?
+            .ok_or_else(denied)?;
+        if !claims_grant_access(&info.gates, audience, namespace_id, claims) {
+            return Err(denied());
+        }
+        Ok(page)
+    }
+
+    /// Approved comments on a page, oldest first.
+    pub async fn list(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+        claims: &AudienceTokenClaims,
+    ) -> Result<Vec<ReaderComment>, ServerCoreError> {
+        self.authorized_page(namespace_id, object_key, claims)
+            .await?;
+        let approved = self
+            .comment_store
+            .list_comments(
+                namespace_id,
+                Some(object_key),
+                Some(CommentStatus::Approved),
+                MAX_PAGE_COMMENTS,
+            )
+            .await?;
+        Ok(approved
+            .into_iter()
+            .rev()
+            .map(ReaderComment::from)
+            .collect())
+    }
+
+    /// Leave a comment on a page. It waits for the owner's approval, and the
+    /// owner is emailed when a mailer is configured.
+    pub async fn post(
+        &self,
+        namespace_id: &str,
+        object_key: &str,
+        claims: &AudienceTokenClaims,
+        request: &PostCommentRequest,
+    ) -> Result<CommentInfo, ServerCoreError> {
+        let author_name = trimmed_text(&request.author_name, "author_name", MAX_AUTHOR_NAME_CHARS)?;
+        let body = trimmed_text(&request.body, "body", MAX_COMMENT_BODY_CHARS)?;
+        let page = self
+            .authorized_page(namespace_id, object_key, claims)
+            .await?;
+
+        let pending = self
+            .comment_store
+            .list_comments(
+                namespace_id,
+                None,
+                Some(CommentStatus::Pending),
+                MAX_PENDING_COMMENTS as u32,
+            )
+            .await?
+            .len();
+        if pending >= MAX_PENDING_COMMENTS {
+            return Err(ServerCoreError::rate_limited(
+                "Too many comments awaiting moderation",
+            ));
+        }
+
+        let now = Utc::now().timestamp();
+        let comment = CommentInfo {
+            id: Uuid::new_v4().to_string(),
+            namespace_id: namespace_id.to_string(),
+            audience: claims.audience.clone(),
+            object_key: page.object_key,
+            source_key: page.source_key,
+            author_name,
+            body,
+            status: CommentStatus::Pending,
+            token_id: claims.token_id.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+        self.comment_store.upsert_comment(&comment).await?;
+        self.notify_owner(&comment).await;
+        Ok(comment)
+    }
+
+    /// Best-effort: a failed email never loses the comment.
+    async fn notify_owner(&self, comment: &CommentInfo) {
+        let Some((auth_store, mailer)) = self.notify else {
+            return;
+        };
+        let owner = match self
+            .namespace_store
+            .get_namespace(&comment.namespace_id)
+            .await
+        {
+            Ok(Some(ns)) => match auth_store.get_user(&ns.owner_user_id).await {
+                Ok(Some(user)) => Some((namespace_display_name(&ns), user.email)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((namespace_name, email)) = owner else {
+            return;
+        };
+        let page = comment
+            .object_key
+            .strip_prefix(&format!("{}/", comment.audience))
+            .unwrap_or(&comment.object_key);
+        if let Err(e) = mailer
+            .send_comment_notification(
+                &email,
+                &namespace_name,
+                page,
+                &comment.author_name,
+                &comment.body,
+            )
+            .await
+        {
+            warn!(
+                "Failed to email the owner about comment {}: {}",
+                comment.id, e
+            );
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Moderation (owner-only)
+// ---------------------------------------------------------------------------
+
+/// List, approve, reject and delete a namespace's comments. Only the owner
+/// decides what their readers see.
+pub struct CommentService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    comment_store: &'a dyn CommentStore,
+}
+
+impl<'a> CommentService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        comment_store: &'a dyn CommentStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            comment_store,
+        }
+    }
+
+    async fn require_owner(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            None,
+            namespace_id,
+            caller_user_id,
+            NamespaceRole::Owner,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// The namespace's comments, newest first.
+    pub async fn list(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        query: &CommentListQuery,
+    ) -> Result<Vec<CommentInfo>, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_COMMENT_LIST_LIMIT)
+            .clamp(1, MAX_COMMENT_LIST_LIMIT);
+        self.comment_store
+            .list_comments(namespace_id, None, query.status, limit)
+            .await
+    }
+
+    /// Approve or reject a comment. Either decision can be reversed later.
+    pub async fn moderate(
+        &self,
+        namespace_id: &str,
+        comment_id: &str,
+        caller_user_id: &str,
+        approve: bool,
+    ) -> Result<CommentInfo, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        let mut comment = self
+            .comment_store
+            .get_comment(comment_id)
+            .await?
+            .filter(|c| c.namespace_id == namespace_id)
+            .ok_or_else(|| ServerCoreError::not_found("Comment not found"))?;
+        comment.status = if approve {
+            CommentStatus::Approved
+        } else {
+            CommentStatus::Rejected
+        };
+        comment.updated_at = Utc::now().timestamp();
+        self.comment_store.upsert_comment(&comment).await?;
+        Ok(comment)
+    }
+
+    pub async fn delete(
+        &self,
+        namespace_id: &str,
+        comment_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        if self
+            .comment_store
+            .delete_comment(namespace_id, comment_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(ServerCoreError::not_found("Comment not found"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DeviceInfo, NamespaceRole, UserInfo, UserTier};
+    use crate::testing::{InMemoryArkIndexStore, InMemoryCommentStore, InMemoryNamespaceStore};
+    use std::sync::Mutex;
+
+    /// Knows only the namespace owner.
+    struct OwnerAuthStore;
+
+    crate::cfg_async_trait! {
+    impl AuthStore for OwnerAuthStore {
+        async fn get_user(&self, user_id: &str) -> Result<Option<UserInfo>, ServerCoreError> {
+            Ok((user_id == "owner").then(|| UserInfo {
+                id: "owner".to_string(),
+                email: "owner@example.com".to_string(),
+                created_at: Utc::now(),
+                last_login_at: None,
+                attachment_limit_bytes: None,
+                workspace_limit: None,
+                tier: UserTier::Free,
+                published_site_limit: None,
+            }))
+        }
+        async fn list_user_devices(&self, _: &str) -> Result<Vec<DeviceInfo>, ServerCoreError> {
+            Ok(vec![])
+        }
+        async fn rename_device(&self, _: &str, _: &str) -> Result<bool, ServerCoreError> {
+            Ok(true)
+        }
+        async fn delete_device(&self, _: &str) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+        async fn get_user_tier(&self, _: &str) -> Result<UserTier, ServerCoreError> {
+            Ok(UserTier::Free)
+        }
+    }
+    }
+
+    #[derive(Default)]
+    struct RecordingMailer {
+        sent: Mutex<Vec<(String, String, String)>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl Mailer for RecordingMailer {
+        async fn send_magic_link(&self, _: &str, _: &str, _: &str) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+
+        async fn send_namespace_invite(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: NamespaceRole,
+            _: &str,
+        ) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+
+        async fn send_account_deletion_confirmation(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+
+        async fn send_comment_notification(
+            &self,
+            to_email: &str,
+            _namespace_name: &str,
+            page: &str,
+            author_name: &str,
+            _body: &str,
+        ) -> Result<(), ServerCoreError> {
+            self.sent.lock().unwrap().push((
+                to_email.to_string(),
+                page.to_string(),
+                author_name.to_string(),
+            ));
+            Ok(())
+        }
+    }
+    }
+
+    fn claims(
+        audience: &str,
+        gate: GateKind,
+        password_version: Option<u32>,
+    ) -> AudienceTokenClaims {
+        AudienceTokenClaims {
+            slug: "ns1".to_string(),
+            audience: audience.to_string(),
+            token_id: "tok1".to_string(),
+            gate,
+            password_version,
+            expires_at: None,
+        }
+    }
+
+    fn request(body: &str) -> PostCommentRequest {
+        PostCommentRequest {
+            author_name: " Grandma ".to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn claims_must_match_a_current_gate() {
+        let password = [GateRecord::Password {
+            hash: Some("h".to_string()),
+            version: 2,
+        }];
+        let link = [GateRecord::Link];
+        assert!(claims_grant_access(
+            &password,
+            "family",
+            "ns1",
+            &claims("family", GateKind::Unlock, Some(2))
+        ));
+        // Rotated password, other audience, other namespace, public audience.
+        assert!(!claims_grant_access(
+            &password,
+            "family",
+            "ns1",
+            &claims("family", GateKind::Unlock, Some(1))
+        ));
+        assert!(!claims_grant_access(
+            &link,
+            "family",
+            "ns1",
+            &claims("friends", GateKind::Link, None)
+        ));
+        assert!(!claims_grant_access(
+            &link,
+            "family",
+            "ns2",
+            &claims("family", GateKind::Link, None)
+        ));
+        assert!(!claims_grant_access(
+            &[],
+            "family",
+            "ns1",
+            &claims("family", GateKind::Link, None)
+        ));
+    }
+
+    #[tokio::test]
+    async fn readers_comment_and_the_owner_moderates() {
+        let ns_store = InMemoryNamespaceStore::new();
+        let owner = "owner".to_string();
+        ns_store
+            .create_namespace("ns1", &owner, None)
+            .await
+            .unwrap();
+        ns_store
+            .upsert_audience("ns1", "family", &[GateRecord::Link])
+            .await
+            .unwrap();
+        ns_store
+            .upsert_audience("ns1", "public", &[])
+            .await
+            .unwrap();
+        let ark = InMemoryArkIndexStore::new();
+        ark.upsert_ark(
+            "ns1",
+            "a",
+            "family/post.html",
+            Some("family"),
+            Some("family/post.md"),
+        )
+        .await
+        .unwrap();
+        ark.upsert_ark("ns1", "b", "public/open.html", Some("public"), None)
+            .await
+            .unwrap();
+        let store = InMemoryCommentStore::new();
+        let mailer = RecordingMailer::default();
+        let reader =
+            CommentReader::new(&ns_store, &ark, &store).with_mailer(&OwnerAuthStore, &mailer);
+        let family = claims("family", GateKind::Link, None);
+
+        let err = reader
+            .post(
+                "ns1",
+                "public/open.html",
+                &claims("public", GateKind::Link, None),
+                &request("hi"),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+        let err = reader
+            .post("ns1", "family/post.html", &family, &request("  "))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        let comment = reader
+            .post(
+                "ns1",
+                "family/post.html",
+                &family,
+                &request("Lovely photos"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(comment.status, CommentStatus::Pending);
+        assert_eq!(comment.author_name, "Grandma");
+        assert_eq!(comment.source_key.as_deref(), Some("family/post.md"));
+        assert_eq!(
+            *mailer.sent.lock().unwrap(),
+            [(
+                "owner@example.com".to_string(),
+                "post.html".to_string(),
+                "Grandma".to_string()
+            )]
+        );
+        assert!(
+            reader
+                .list("ns1", "family/post.html", &family)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let service = CommentService::new(&ns_store, &store);
+        let err = service
+            .moderate("ns1", &comment.id, "stranger", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+        service
+            .moderate("ns1", &comment.id, &owner, true)
+            .await
+            .unwrap();
+
+        let shown = reader
+            .list("ns1", "family/post.html", &family)
+            .await
+            .unwrap();
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].body, "Lovely photos");
+        let approved = service
+            .list(
+                "ns1",
+                &owner,
+                &CommentListQuery {
+                    status: Some(CommentStatus::Approved),
+                    limit: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(approved[0].id, comment.id);
+
+        // Dropping the link gate closes the audience's comments.
+        ns_store
+            .upsert_audience(
+                "ns1",
+                "family",
+                &[GateRecord::Password {
+                    hash: Some("h".to_string()),
+                    version: 1,
+                }],
+            )
+            .await
+            .unwrap();
+        let err = reader
+            .list("ns1", "family/post.html", &family)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+
+        service.delete("ns1", &comment.id, &owner).await.unwrap();
+        assert!(store.get_comment(&comment.id).await.unwrap().is_none());
+    }
+}
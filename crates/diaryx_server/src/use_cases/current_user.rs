@@ -1,5 +1,37 @@
-use crate::domain::{CurrentUserContext, TierDefaults};
-use crate::ports::{AuthStore, NamespaceStore, ServerCoreError};
+//! The signed-in account: the `/auth/me` aggregate, a full data export, and
+//! account deletion.
+//!
+//! Deletion is two steps. [`AccountDeletionService::request`] emails a signed,
+//! short-lived confirmation link; [`AccountDeletionService::confirm`] checks it
+//! and purges every namespace the user owns — blobs, edge-cache domain
+//! entries, rows — before deleting the user, whose own rows then cascade.
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::domain::{
+    AccountExportRecord, ArchiveRecord, CurrentUserContext, NamespaceInfo, TierDefaults,
+};
+use crate::ports::{
+    AccountExportSink, ArchiveSink, ArkIndexStore, AuthStore, BlobStore, DomainMappingCache,
+    Mailer, NamespaceStore, ObjectMetaStore, PasskeyStore, ServerCoreError, UserStore,
+};
+use crate::use_cases::archive::NamespaceArchiveService;
+use crate::use_cases::namespaces::NamespaceService;
+
+/// Value of the account export header's `format` field.
+pub const ACCOUNT_EXPORT_FORMAT: &str = "diaryx-account-export";
+
+/// Account export layout version written by this build.
+pub const ACCOUNT_EXPORT_VERSION: u32 = 1;
+
+/// How long an account deletion confirmation link stays valid.
+pub const ACCOUNT_DELETION_EXPIRY_MINUTES: i64 = 30;
+
+/// Owned namespaces are listed in pages of this size.
+const NAMESPACE_PAGE_SIZE: u32 = 100;
 
 pub struct CurrentUserService<'a> {
     auth_store: &'a dyn AuthStore,
@@ -55,12 +87,369 @@ impl<'a> CurrentUserService<'a> {
     }
 }
 
+/// Every namespace `user_id` owns.
+async fn owned_namespaces(
+    namespace_store: &dyn NamespaceStore,
+    user_id: &str,
+) -> Result<Vec<NamespaceInfo>, ServerCoreError> {
+    let mut namespaces = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = namespace_store
+            .list_namespaces(user_id, NAMESPACE_PAGE_SIZE, offset)
+            .await?;
+        let page_len = page.len() as u32;
+        namespaces.extend(page);
+        if page_len < NAMESPACE_PAGE_SIZE {
+            return Ok(namespaces);
+        }
+        offset += page_len;
+    }
+}
+
+/// Counts of what an account export wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountExportSummary {
+    pub namespaces: u64,
+    pub objects: u64,
+    pub bytes: u64,
+    pub usage_events: u64,
+}
+
+/// Encode one record as an NDJSON line (trailing `\n` included).
+pub fn encode_account_export_record(
+    record: &AccountExportRecord,
+) -> Result<Vec<u8>, ServerCoreError> {
+    let mut line = serde_json::to_vec(record)
+        .map_err(|e| ServerCoreError::internal(format!("account export encode: {e}")))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Wraps each namespace archive record for the account export stream.
+struct NamespaceArchiveSink<'s> {
+    namespace_id: &'s str,
+    inner: &'s mut dyn AccountExportSink,
+}
+
+crate::cfg_async_trait! {
+impl ArchiveSink for NamespaceArchiveSink<'_> {
+    async fn write_record(&mut self, record: &ArchiveRecord) -> Result<(), ServerCoreError> {
+        self.inner
+            .write_record(&AccountExportRecord::NamespaceArchive {
+                namespace_id: self.namespace_id.to_string(),
+                record: record.clone(),
+            })
+            .await
+    }
+}
+}
+
+/// Streams everything the server holds about an account.
+pub struct AccountExportService<'a> {
+    auth_store: &'a dyn AuthStore,
+    namespace_store: &'a dyn NamespaceStore,
+    object_meta_store: &'a dyn ObjectMetaStore,
+    blob_store: &'a dyn BlobStore,
+    ark_index: &'a dyn ArkIndexStore,
+    passkey_store: Option<&'a dyn PasskeyStore>,
+}
+
+impl<'a> AccountExportService<'a> {
+    pub fn new(
+        auth_store: &'a dyn AuthStore,
+        namespace_store: &'a dyn NamespaceStore,
+        object_meta_store: &'a dyn ObjectMetaStore,
+        blob_store: &'a dyn BlobStore,
+        ark_index: &'a dyn ArkIndexStore,
+    ) -> Self {
+        Self {
+            auth_store,
+            namespace_store,
+            object_meta_store,
+            blob_store,
+            ark_index,
+            passkey_store: None,
+        }
+    }
+
+    /// Include passkey metadata. Without a store no `passkey` records are
+    /// written.
+    pub fn with_passkeys(mut self, passkey_store: &'a dyn PasskeyStore) -> Self {
+        self.passkey_store = Some(passkey_store);
+        self
+    }
+
+    /// Stream the caller's account into `sink`.
+    pub async fn export(
+        &self,
+        user_id: &str,
+        sink: &mut dyn AccountExportSink,
+    ) -> Result<AccountExportSummary, ServerCoreError> {
+        let user =
+            self.auth_store.get_user(user_id).await?.ok_or_else(|| {
+                ServerCoreError::not_found(format!("User '{}' not found", user_id))
+            })?;
+        let mut summary = AccountExportSummary::default();
+
+        sink.write_record(&AccountExportRecord::Header {
+            format: ACCOUNT_EXPORT_FORMAT.to_string(),
+            version: ACCOUNT_EXPORT_VERSION,
+            user_id: user_id.to_string(),
+            exported_at: Utc::now().timestamp(),
+        })
+        .await?;
+        sink.write_record(&AccountExportRecord::Profile { user })
+            .await?;
+
+        for device in self.auth_store.list_user_devices(user_id).await? {
+            sink.write_record(&AccountExportRecord::Device { device })
+                .await?;
+        }
+
+        if let Some(passkey_store) = self.passkey_store {
+            for passkey in passkey_store.get_credentials(user_id).await? {
+                sink.write_record(&AccountExportRecord::Passkey {
+                    id: passkey.id,
+                    name: passkey.name,
+                    created_at: passkey.created_at,
+                    last_used_at: passkey.last_used_at,
+                })
+                .await?;
+            }
+        }
+
+        let archive = NamespaceArchiveService::new(
+            self.namespace_store,
+            self.object_meta_store,
+            self.blob_store,
+            self.ark_index,
+        );
+        for namespace in owned_namespaces(self.namespace_store, user_id).await? {
+            let namespace_id = namespace.id.clone();
+            sink.write_record(&AccountExportRecord::Namespace { namespace })
+                .await?;
+            let mut namespace_sink = NamespaceArchiveSink {
+                namespace_id: &namespace_id,
+                inner: &mut *sink,
+            };
+            let archived = archive
+                .export(&namespace_id, user_id, &mut namespace_sink)
+                .await?;
+            summary.namespaces += 1;
+            summary.objects += archived.objects;
+            summary.bytes += archived.bytes;
+        }
+
+        for event in self.object_meta_store.list_usage_events(user_id).await? {
+            sink.write_record(&AccountExportRecord::UsageEvent { event })
+                .await?;
+            summary.usage_events += 1;
+        }
+
+        sink.write_record(&AccountExportRecord::End {
+            namespaces: summary.namespaces,
+            objects: summary.objects,
+        })
+        .await?;
+
+        Ok(summary)
+    }
+}
+
+/// Wire shape of a started account deletion. The confirmation link is only
+/// included when it wasn't emailed (dev mode).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountDeletionRequested {
+    pub expires_at: i64,
+    pub emailed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConfirmAccountDeletionRequest {
+    /// The token from the confirmation link.
+    pub token: String,
+}
+
+/// What [`AccountDeletionService::confirm`] removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDeletionSummary {
+    pub namespaces: u64,
+    pub blobs_deleted: u64,
+}
+
+/// Emailed confirmation and purge of an account.
+pub struct AccountDeletionService<'a> {
+    user_store: &'a dyn UserStore,
+    namespace_store: &'a dyn NamespaceStore,
+    blob_store: &'a dyn BlobStore,
+    signing_key: &'a [u8],
+    mailer: Option<&'a dyn Mailer>,
+    domain_cache: Option<&'a dyn DomainMappingCache>,
+}
+
+impl<'a> AccountDeletionService<'a> {
+    /// `signing_key` signs confirmation tokens; the adapters pass their
+    /// audience-token key.
+    pub fn new(
+        user_store: &'a dyn UserStore,
+        namespace_store: &'a dyn NamespaceStore,
+        blob_store: &'a dyn BlobStore,
+        signing_key: &'a [u8],
+    ) -> Self {
+        Self {
+            user_store,
+            namespace_store,
+            blob_store,
+            signing_key,
+            mailer: None,
+            domain_cache: None,
+        }
+    }
+
+    /// Email confirmation links through `mailer`. Without one the link is
+    /// returned to the caller instead.
+    pub fn with_mailer(mut self, mailer: &'a dyn Mailer) -> Self {
+        self.mailer = Some(mailer);
+        self
+    }
+
+    /// Remove the deleted namespaces' domains and subdomains from the edge
+    /// cache.
+    pub fn with_domain_cache(mut self, domain_cache: &'a dyn DomainMappingCache) -> Self {
+        self.domain_cache = Some(domain_cache);
+        self
+    }
+
+    /// Start deleting the caller's account. The confirmation link is built as
+    /// `{confirm_url_base}?delete_account=<token>` and sent to `email`.
+    pub async fn request(
+        &self,
+        user_id: &str,
+        email: &str,
+        confirm_url_base: &str,
+    ) -> Result<AccountDeletionRequested, ServerCoreError> {
+        let expires_at = Utc::now().timestamp() + ACCOUNT_DELETION_EXPIRY_MINUTES * 60;
+        let token = deletion_token(self.signing_key, user_id, expires_at)?;
+        let confirm_url = format!("{confirm_url_base}?delete_account={token}");
+
+        let emailed = match self.mailer {
+            Some(mailer) => {
+                mailer
+                    .send_account_deletion_confirmation(email, &confirm_url)
+                    .await?;
+                true
+            }
+            None => false,
+        };
+
+        Ok(AccountDeletionRequested {
+            expires_at,
+            emailed,
+            confirm_url: (!emailed).then_some(confirm_url),
+        })
+    }
+
+    /// Delete the caller's account if `token` is a live confirmation token
+    /// issued to them. Owned namespaces are purged first, blobs included;
+    /// memberships, devices, sessions and the rest cascade with the user row.
+    pub async fn confirm(
+        &self,
+        user_id: &str,
+        token: &str,
+    ) -> Result<AccountDeletionSummary, ServerCoreError> {
+        if !verify_deletion_token(self.signing_key, user_id, token, Utc::now().timestamp()) {
+            return Err(ServerCoreError::permission_denied(
+                "Invalid or expired confirmation link",
+            ));
+        }
+
+        let mut summary = AccountDeletionSummary::default();
+        let namespaces = NamespaceService::new(self.namespace_store);
+        for namespace in owned_namespaces(self.namespace_store, user_id).await? {
+            summary.blobs_deleted += self
+                .blob_store
+                .delete_by_prefix(&format!("ns/{}/", namespace.id))
+                .await? as u64;
+            namespaces
+                .delete_with_cache(&namespace.id, user_id, self.domain_cache)
+                .await?;
+            summary.namespaces += 1;
+        }
+
+        // Per-user blobs (`blob_key` with an empty hash is the user's prefix).
+        let user_prefix = self.blob_store.blob_key(user_id, "");
+        summary.blobs_deleted += self.blob_store.delete_by_prefix(&user_prefix).await? as u64;
+
+        self.user_store.delete_user(user_id).await?;
+        Ok(summary)
+    }
+}
+
+fn deletion_mac(
+    signing_key: &[u8],
+    user_id: &str,
+    expires_at: i64,
+) -> Result<Hmac<Sha256>, ServerCoreError> {
+    if signing_key.is_empty() {
+        return Err(ServerCoreError::unavailable(
+            "Token signing key is not configured",
+        ));
+    }
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_key)
+        .map_err(|e| ServerCoreError::internal(format!("invalid signing key: {e}")))?;
+    mac.update(format!("account-delete\n{user_id}\n{expires_at}").as_bytes());
+    Ok(mac)
+}
+
+/// `{expires_at}.{hex HMAC}` binding the token to one user and expiry.
+fn deletion_token(
+    signing_key: &[u8],
+    user_id: &str,
+    expires_at: i64,
+) -> Result<String, ServerCoreError> {
+    let mac = deletion_mac(signing_key, user_id, expires_at)?;
+    Ok(format!(
+        "{expires_at}.{}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+fn verify_deletion_token(signing_key: &[u8], user_id: &str, token: &str, now: i64) -> bool {
+    let Some((expires_at, signature)) = token.split_once('.') else {
+        return false;
+    };
+    let (Ok(expires_at), Ok(signature)) = (expires_at.parse::<i64>(), hex::decode(signature))
+    else {
+        return false;
+    };
+    if expires_at < now {
+        return false;
+    }
+    deletion_mac(signing_key, user_id, expires_at)
+        .is_ok_and(|mac| mac.verify_slice(&signature).is_ok())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::CurrentUserService;
-    use crate::domain::{DeviceInfo, NamespaceInfo, UserInfo, UserTier};
-    use crate::ports::{AuthStore, NamespaceStore, ServerCoreError};
+    use super::{
+        ACCOUNT_EXPORT_FORMAT, AccountDeletionService, AccountExportService, CurrentUserService,
+        deletion_token, verify_deletion_token,
+    };
+    use crate::domain::{
+        AccountExportRecord, ArchiveRecord, DeviceInfo, NamespaceInfo, UserInfo, UserTier,
+    };
+    use crate::ports::{
+        AccountExportSink, AuthStore, BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError,
+        UserStore,
+    };
+    use crate::testing::{
+        InMemoryArkIndexStore, InMemoryBlobStore, InMemoryNamespaceStore, InMemoryObjectMetaStore,
+    };
     use chrono::{TimeZone, Utc};
+    use std::sync::Mutex;
 
     struct TestAuthStore;
     struct TestNamespaceStore;
@@ -214,4 +603,191 @@ mod tests {
         assert_eq!(result.limits.workspace_limit, 4);
         assert_eq!(result.limits.device_limit, 10);
     }
+
+    #[derive(Default)]
+    struct CollectingSink(Vec<AccountExportRecord>);
+
+    crate::cfg_async_trait! {
+    impl AccountExportSink for CollectingSink {
+        async fn write_record(
+            &mut self,
+            record: &AccountExportRecord,
+        ) -> Result<(), ServerCoreError> {
+            self.0.push(record.clone());
+            Ok(())
+        }
+    }
+    }
+
+    #[derive(Default)]
+    struct TestUserStore {
+        deleted: Mutex<Vec<String>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl UserStore for TestUserStore {
+        async fn get_or_create_user(&self, _: &str) -> Result<String, ServerCoreError> {
+            Ok("u1".to_string())
+        }
+        async fn update_last_login(&self, _: &str) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+        async fn delete_user(&self, user_id: &str) -> Result<(), ServerCoreError> {
+            self.deleted.lock().unwrap().push(user_id.to_string());
+            Ok(())
+        }
+        async fn get_effective_device_limit(&self, _: &str) -> Result<u32, ServerCoreError> {
+            Ok(10)
+        }
+        async fn set_user_tier(&self, _: &str, _: UserTier) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+    }
+    }
+
+    #[tokio::test]
+    async fn account_export_nests_namespace_archives() {
+        let auth_store = TestAuthStore;
+        let namespaces = InMemoryNamespaceStore::new();
+        let meta = InMemoryObjectMetaStore::new();
+        let blobs = InMemoryBlobStore::new();
+        let arks = InMemoryArkIndexStore::new();
+
+        namespaces
+            .create_namespace("mine", "u1", None)
+            .await
+            .unwrap();
+        namespaces
+            .create_namespace("theirs", "u2", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/mine/blobs/aa", b"hello", "text/plain", None)
+            .await
+            .unwrap();
+        meta.upsert_object(
+            "mine",
+            "a.md",
+            "ns/mine/blobs/aa",
+            "text/plain",
+            5,
+            None,
+            Some("aa"),
+        )
+        .await
+        .unwrap();
+        meta.record_usage("u1", "bytes_in", 5, Some("mine"))
+            .await
+            .unwrap();
+        meta.record_usage("u2", "bytes_in", 9, None).await.unwrap();
+
+        let service = AccountExportService::new(&auth_store, &namespaces, &meta, &blobs, &arks);
+        let mut sink = CollectingSink::default();
+        let summary = service.export("u1", &mut sink).await.unwrap();
+        assert_eq!(
+            (summary.namespaces, summary.objects, summary.bytes),
+            (1, 1, 5)
+        );
+        assert_eq!(summary.usage_events, 1);
+
+        let records = sink.0;
+        assert!(matches!(
+            &records[0],
+            AccountExportRecord::Header { format, user_id, .. }
+                if format == ACCOUNT_EXPORT_FORMAT && user_id == "u1"
+        ));
+        assert!(matches!(records[1], AccountExportRecord::Profile { .. }));
+        assert!(matches!(records[2], AccountExportRecord::Device { .. }));
+        let namespace_ids: Vec<&str> = records
+            .iter()
+            .filter_map(|r| match r {
+                AccountExportRecord::Namespace { namespace } => Some(namespace.id.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(namespace_ids, ["mine"]);
+        assert!(records.iter().any(|r| matches!(
+            r,
+            AccountExportRecord::NamespaceArchive {
+                record: ArchiveRecord::Object { key, .. },
+                ..
+            } if key == "a.md"
+        )));
+        assert!(matches!(
+            records.last(),
+            Some(AccountExportRecord::End {
+                namespaces: 1,
+                objects: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn deletion_tokens_are_bound_to_user_and_expiry() {
+        let key = [3u8; 32];
+        let token = deletion_token(&key, "u1", 1_000).unwrap();
+        assert!(verify_deletion_token(&key, "u1", &token, 999));
+        assert!(!verify_deletion_token(&key, "u1", &token, 1_001));
+        assert!(!verify_deletion_token(&key, "u2", &token, 999));
+        assert!(!verify_deletion_token(&[4u8; 32], "u1", &token, 999));
+        let forged = token.replacen("1000", "9000", 1);
+        assert!(!verify_deletion_token(&key, "u1", &forged, 999));
+        assert!(!verify_deletion_token(&key, "u1", "garbage", 999));
+    }
+
+    #[tokio::test]
+    async fn confirmed_deletion_purges_owned_namespaces_and_blobs() {
+        let users = TestUserStore::default();
+        let namespaces = InMemoryNamespaceStore::new();
+        let blobs = InMemoryBlobStore::new();
+        let key = [9u8; 32];
+
+        namespaces
+            .create_namespace("mine", "u1", None)
+            .await
+            .unwrap();
+        namespaces
+            .create_namespace("theirs", "u2", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/mine/blobs/aa", b"x", "text/plain", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/mine/_audiences.json", b"{}", "application/json", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/theirs/blobs/bb", b"y", "text/plain", None)
+            .await
+            .unwrap();
+        let user_blob = blobs.blob_key("u1", "cc");
+        blobs
+            .put(&user_blob, b"z", "text/plain", None)
+            .await
+            .unwrap();
+
+        let service = AccountDeletionService::new(&users, &namespaces, &blobs, &key);
+        let requested = service
+            .request("u1", "u1@example.com", "https://app.example.com/account")
+            .await
+            .unwrap();
+        assert!(!requested.emailed);
+        let confirm_url = requested.confirm_url.unwrap();
+        let token = confirm_url.split_once("delete_account=").unwrap().1;
+
+        let err = service.confirm("u2", token).await.unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+        assert!(users.deleted.lock().unwrap().is_empty());
+
+        let summary = service.confirm("u1", token).await.unwrap();
+        assert_eq!(summary.namespaces, 1);
+        assert_eq!(summary.blobs_deleted, 3);
+        assert_eq!(*users.deleted.lock().unwrap(), ["u1"]);
+        assert!(namespaces.get_namespace("mine").await.unwrap().is_none());
+        assert!(namespaces.get_namespace("theirs").await.unwrap().is_some());
+        assert!(blobs.exists("ns/theirs/blobs/bb").await.unwrap());
+        assert!(!blobs.exists(&user_blob).await.unwrap());
+    }
 }
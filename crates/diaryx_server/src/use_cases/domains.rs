@@ -1,5 +1,7 @@
-use crate::domain::CustomDomainInfo;
-use crate::ports::{DomainMappingCache, NamespaceStore, ServerCoreError};
+use crate::domain::{AuditAction, CustomDomainInfo, NamespaceRole};
+use crate::ports::{DomainMappingCache, NamespaceMemberStore, NamespaceStore, ServerCoreError};
+use crate::use_cases::audit::AuditRecorder;
+use crate::use_cases::members::require_namespace_role;
 use serde::{Deserialize, Serialize};
 
 pub const RESERVED_SUBDOMAINS: &[&str] = &[
@@ -26,6 +28,8 @@ pub struct ReleasedSubdomain {
 pub struct DomainService<'a> {
     namespace_store: &'a dyn NamespaceStore,
     domain_mapping_cache: &'a dyn DomainMappingCache,
+    member_store: Option<&'a dyn NamespaceMemberStore>,
+    audit: Option<AuditRecorder<'a>>,
 }
 
 impl<'a> DomainService<'a> {
@@ -36,26 +40,82 @@ impl<'a> DomainService<'a> {
         Self {
             namespace_store,
             domain_mapping_cache,
+            member_store: None,
+            audit: None,
         }
     }
 
+    /// Let namespace members see the domain list. Registering and releasing
+    /// domains stays with the owner either way.
+    pub fn with_members(mut self, member_store: &'a dyn NamespaceMemberStore) -> Self {
+        self.member_store = Some(member_store);
+        self
+    }
+
+    /// Record domain and subdomain changes in the namespace's audit log.
+    pub fn with_audit(mut self, audit: AuditRecorder<'a>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    async fn audit(
+        &self,
+        action: AuditAction,
+        namespace_id: &str,
+        caller_user_id: &str,
+        domain: &str,
+        details: serde_json::Value,
+    ) {
+        if let Some(audit) = &self.audit {
+            audit
+                .namespace(
+                    action,
+                    namespace_id,
+                    Some(caller_user_id),
+                    Some(domain),
+                    details,
+                )
+                .await;
+        }
+    }
+
+    async fn require_role(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        required: NamespaceRole,
+    ) -> Result<(), ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            self.member_store,
+            namespace_id,
+            caller_user_id,
+            required,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn list_domains(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<Vec<CustomDomainInfo>, ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
+            .await?;
+        self.namespace_store.list_custom_domains(namespace_id).await
+    }
+
     pub async fn register_domain(
         &self,
         namespace_id: &str,
         domain: &str,
         audience_name: &str,
+        caller_user_id: &str,
     ) -> Result<CustomDomainInfo, ServerCoreError> {
-        if self
-            .namespace_store
-            .get_audience(namespace_id, audience_name)
-            .await?
-            .is_none()
-        {
-            return Err(ServerCoreError::invalid_input(format!(
-                "audience '{}' does not exist",
-                audience_name
-            )));
-        }
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
+        validate_domain_mapping(self.namespace_store, namespace_id, domain, audience_name).await?;
 
         self.namespace_store
             .upsert_custom_domain(domain, namespace_id, audience_name)
@@ -80,6 +140,14 @@ impl<'a> DomainService<'a> {
             )
             .await?;
 
+        self.audit(
+            AuditAction::DomainRegistered,
+            namespace_id,
+            caller_user_id,
+            &domain_info.domain,
+            serde_json::json!({ "audience": domain_info.audience_name }),
+        )
+        .await;
         Ok(domain_info)
     }
 
@@ -87,7 +155,10 @@ impl<'a> DomainService<'a> {
         &self,
         namespace_id: &str,
         domain: &str,
+        caller_user_id: &str,
     ) -> Result<(), ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
         let existing = self
             .namespace_store
             .get_custom_domain(domain)
@@ -110,6 +181,14 @@ impl<'a> DomainService<'a> {
         }
 
         self.domain_mapping_cache.delete_domain(domain).await?;
+        self.audit(
+            AuditAction::DomainRemoved,
+            namespace_id,
+            caller_user_id,
+            domain,
+            serde_json::Value::Null,
+        )
+        .await;
         Ok(())
     }
 
@@ -118,7 +197,10 @@ impl<'a> DomainService<'a> {
         namespace_id: &str,
         requested_subdomain: &str,
         default_audience: Option<&str>,
+        caller_user_id: &str,
     ) -> Result<ClaimedSubdomain, ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
         let subdomain = validate_subdomain_label(requested_subdomain)?;
         let domain = format!("{}{}", subdomain, DIARYX_SUBDOMAIN_SUFFIX);
 
@@ -136,6 +218,14 @@ impl<'a> DomainService<'a> {
         self.domain_mapping_cache
             .put_subdomain(&subdomain, namespace_id, default_audience)
             .await?;
+        self.audit(
+            AuditAction::SubdomainClaimed,
+            namespace_id,
+            caller_user_id,
+            &domain,
+            serde_json::json!({ "default_audience": default_audience }),
+        )
+        .await;
 
         Ok(ClaimedSubdomain {
             subdomain,
@@ -148,7 +238,10 @@ impl<'a> DomainService<'a> {
     pub async fn release_subdomain(
         &self,
         namespace_id: &str,
+        caller_user_id: &str,
     ) -> Result<ReleasedSubdomain, ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
         let domain_info = self
             .namespace_store
             .list_custom_domains(namespace_id)
@@ -181,6 +274,14 @@ impl<'a> DomainService<'a> {
         self.domain_mapping_cache
             .delete_subdomain(&subdomain)
             .await?;
+        self.audit(
+            AuditAction::SubdomainReleased,
+            namespace_id,
+            caller_user_id,
+            &domain_info.domain,
+            serde_json::Value::Null,
+        )
+        .await;
 
         Ok(ReleasedSubdomain {
             subdomain,
@@ -190,6 +291,46 @@ impl<'a> DomainService<'a> {
     }
 }
 
+/// Check that `domain` may map to `audience_name` in `namespace_id`. A
+/// `*.diaryx.org` name must carry a valid, unreserved label and map to the
+/// whole namespace, as [`DomainService::claim_subdomain`] would have made it;
+/// any other domain must name an existing audience. Ownership conflicts are
+/// left to the caller.
+pub async fn validate_domain_mapping(
+    namespace_store: &dyn NamespaceStore,
+    namespace_id: &str,
+    domain: &str,
+    audience_name: &str,
+) -> Result<(), ServerCoreError> {
+    if let Some(label) = domain.strip_suffix(DIARYX_SUBDOMAIN_SUFFIX) {
+        if validate_subdomain_label(label)? != label {
+            return Err(ServerCoreError::invalid_input(format!(
+                "Subdomain '{}' is not normalized",
+                domain
+            )));
+        }
+        if audience_name != SUBDOMAIN_AUDIENCE_NAME {
+            return Err(ServerCoreError::invalid_input(format!(
+                "Subdomain '{}' must map to the whole namespace",
+                domain
+            )));
+        }
+        return Ok(());
+    }
+
+    if namespace_store
+        .get_audience(namespace_id, audience_name)
+        .await?
+        .is_none()
+    {
+        return Err(ServerCoreError::invalid_input(format!(
+            "audience '{}' does not exist",
+            audience_name
+        )));
+    }
+    Ok(())
+}
+
 pub fn normalize_subdomain_label(input: &str) -> String {
     input.trim().to_lowercase()
 }
@@ -238,9 +379,14 @@ mod tests {
     impl NamespaceStore for TestNamespaceStore {
         async fn get_namespace(
             &self,
-            _namespace_id: &str,
+            namespace_id: &str,
         ) -> Result<Option<NamespaceInfo>, ServerCoreError> {
-            Ok(None)
+            Ok(Some(NamespaceInfo {
+                id: namespace_id.to_string(),
+                owner_user_id: "owner".to_string(),
+                created_at: 0,
+                metadata: None,
+            }))
         }
 
         async fn list_namespaces(
@@ -436,7 +582,7 @@ mod tests {
         let service = DomainService::new(&store, &cache);
 
         let domain = service
-            .register_domain("ns_123", "blog.example.com", "public")
+            .register_domain("ns_123", "blog.example.com", "public", "owner")
             .await
             .expect("domain should register");
 
@@ -447,6 +593,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn register_domain_cannot_bypass_subdomain_rules() {
+        let store = TestNamespaceStore::default();
+        let cache = TestDomainMappingCache::default();
+        let service = DomainService::new(&store, &cache);
+
+        for (domain, audience) in [
+            ("app.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("bad_name.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("Notes.diaryx.org", SUBDOMAIN_AUDIENCE_NAME),
+            ("notes.diaryx.org", "public"),
+        ] {
+            assert!(
+                service
+                    .register_domain("ns_123", domain, audience, "owner")
+                    .await
+                    .is_err(),
+                "{domain} -> {audience}"
+            );
+        }
+        assert!(store.domains.lock().expect("domains lock").is_empty());
+        assert!(cache.puts.lock().expect("puts lock").is_empty());
+    }
+
     #[tokio::test]
     async fn claim_and_release_subdomain_round_trips_through_shared_service() {
         let store = TestNamespaceStore::default();
@@ -454,7 +624,7 @@ mod tests {
         let service = DomainService::new(&store, &cache);
 
         let claimed = service
-            .claim_subdomain("ns_123", "Notes-App", Some("public"))
+            .claim_subdomain("ns_123", "Notes-App", Some("public"), "owner")
             .await
             .expect("subdomain should be claimed");
         assert_eq!(
@@ -477,7 +647,7 @@ mod tests {
         assert_eq!(stored.audience_name, SUBDOMAIN_AUDIENCE_NAME);
 
         let released = service
-            .release_subdomain("ns_123")
+            .release_subdomain("ns_123", "owner")
             .await
             .expect("subdomain should release");
         assert_eq!(released.subdomain, "notes-app");
@@ -0,0 +1,703 @@
+//! Namespace collaborators: roles, invites, and the role checks the other
+//! namespace services share.
+//!
+//! A namespace has one `owner_user_id`, who always holds
+//! [`NamespaceRole::Owner`]. Other users gain a role by accepting an emailed
+//! invite; their memberships live in a [`NamespaceMemberStore`]. Services that
+//! gate on the caller's relationship to a namespace call
+//! [`require_namespace_role`] — without a member store (adapters that haven't
+//! opted in) only the owner passes, exactly as before collaborators existed.
+
+use crate::domain::{NamespaceInfo, NamespaceInviteInfo, NamespaceMemberInfo, NamespaceRole};
+use crate::ports::{Mailer, NamespaceMemberStore, NamespaceStore, ServerCoreError};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// How long an invite can be accepted for.
+pub const INVITE_EXPIRY_DAYS: i64 = 7;
+
+/// Maximum number of pending invites a namespace can have at once.
+pub const MAX_PENDING_INVITES: usize = 50;
+
+/// Resolve `user_id`'s role on a namespace: [`NamespaceRole::Owner`] for the
+/// namespace owner, the stored membership role otherwise, or `None`.
+pub async fn namespace_role(
+    namespace_store: &dyn NamespaceStore,
+    member_store: Option<&dyn NamespaceMemberStore>,
+    namespace_id: &str,
+    user_id: &str,
+) -> Result<(NamespaceInfo, Option<NamespaceRole>), ServerCoreError> {
+    let ns = namespace_store
+        .get_namespace(namespace_id)
+        .await?
+        .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
+    if ns.owner_user_id == user_id {
+        return Ok((ns, Some(NamespaceRole::Owner)));
+    }
+    let role = match member_store {
+        Some(store) => store
+            .get_member(namespace_id, user_id)
+            .await?
+            .map(|member| member.role),
+        None => None,
+    };
+    Ok((ns, role))
+}
+
+/// Require that `user_id` holds at least `required` on a namespace. Callers
+/// with no role get the same error a non-owner always has.
+pub async fn require_namespace_role(
+    namespace_store: &dyn NamespaceStore,
+    member_store: Option<&dyn NamespaceMemberStore>,
+    namespace_id: &str,
+    user_id: &str,
+    required: NamespaceRole,
+) -> Result<NamespaceInfo, ServerCoreError> {
+    let (ns, role) = namespace_role(namespace_store, member_store, namespace_id, user_id).await?;
+    match role {
+        Some(role) if role >= required => Ok(ns),
+        Some(_) => Err(ServerCoreError::permission_denied(format!(
+            "This requires the {} role on the namespace",
+            required.as_str()
+        ))),
+        None => Err(ServerCoreError::permission_denied(
+            "You do not own this namespace",
+        )),
+    }
+}
+
+/// SHA-256 hex digest of an invite secret — the value stores key invites by.
+pub fn hash_invite_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct InviteMemberRequest {
+    pub email: String,
+    pub role: NamespaceRole,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateMemberRoleRequest {
+    pub role: NamespaceRole,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AcceptInviteRequest {
+    /// The secret from the invite's accept link.
+    pub token: String,
+}
+
+/// Result of [`NamespaceMemberService::invite`].
+#[derive(Debug, Clone)]
+pub struct CreatedInvite {
+    pub invite: NamespaceInviteInfo,
+    /// Link that accepts the invite. Contains the secret; only hand it back
+    /// to the inviter when no email was sent (dev mode).
+    pub accept_url: String,
+    /// Whether the invite was emailed through the configured [`Mailer`].
+    pub emailed: bool,
+}
+
+/// Wire shape of a created invite. The accept link is only included when the
+/// invite wasn't emailed, so the inviter can pass it on themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteResponse {
+    #[serde(flatten)]
+    pub invite: NamespaceInviteInfo,
+    pub emailed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accept_url: Option<String>,
+}
+
+impl From<CreatedInvite> for InviteResponse {
+    fn from(created: CreatedInvite) -> Self {
+        Self {
+            accept_url: (!created.emailed).then_some(created.accept_url),
+            invite: created.invite,
+            emailed: created.emailed,
+        }
+    }
+}
+
+pub struct NamespaceMemberService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    member_store: &'a dyn NamespaceMemberStore,
+    mailer: Option<&'a dyn Mailer>,
+}
+
+impl<'a> NamespaceMemberService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        member_store: &'a dyn NamespaceMemberStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            member_store,
+            mailer: None,
+        }
+    }
+
+    /// Email invites through `mailer`. Without one, invites are still created
+    /// and their accept link is returned to the caller instead.
+    pub fn with_mailer(mut self, mailer: &'a dyn Mailer) -> Self {
+        self.mailer = Some(mailer);
+        self
+    }
+
+    async fn require_role(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        required: NamespaceRole,
+    ) -> Result<NamespaceInfo, ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            Some(self.member_store),
+            namespace_id,
+            caller_user_id,
+            required,
+        )
+        .await
+    }
+
+    /// List everyone with a role on the namespace, starting with the owner.
+    /// Any member can see who else has access.
+    pub async fn list_members(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
+            .await?;
+        let mut members = vec![NamespaceMemberInfo {
+            namespace_id: ns.id.clone(),
+            user_id: ns.owner_user_id.clone(),
+            email: None,
+            role: NamespaceRole::Owner,
+            created_at: ns.created_at,
+        }];
+        members.extend(
+            self.member_store
+                .list_members(namespace_id)
+                .await?
+                .into_iter()
+                .filter(|m| m.user_id != ns.owner_user_id),
+        );
+        Ok(members)
+    }
+
+    /// Memberships the caller holds on namespaces owned by someone else.
+    pub async fn list_memberships(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<NamespaceMemberInfo>, ServerCoreError> {
+        self.member_store.list_user_memberships(user_id).await
+    }
+
+    /// Invite `request.email` to the namespace. Owner-only. The invite secret
+    /// goes into an accept link built as `{accept_url_base}?invite=<secret>`.
+    pub async fn invite(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        caller_email: &str,
+        request: InviteMemberRequest,
+        accept_url_base: &str,
+    ) -> Result<CreatedInvite, ServerCoreError> {
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
+
+        let email = request.email.trim().to_lowercase();
+        if !email.contains('@') || email.len() < 5 {
+            return Err(ServerCoreError::invalid_input("Invalid email address"));
+        }
+        if email == caller_email.trim().to_lowercase() {
+            return Err(ServerCoreError::invalid_input(
+                "You already have access to this namespace",
+            ));
+        }
+
+        let pending = self.member_store.list_invites(namespace_id).await?;
+        let now = Utc::now().timestamp();
+        if pending
+            .iter()
+            .filter(|invite| invite.expires_at > now)
+            .count()
+            >= MAX_PENDING_INVITES
+        {
+            return Err(ServerCoreError::conflict(format!(
+                "A namespace can have at most {MAX_PENDING_INVITES} pending invites"
+            )));
+        }
+        // Re-inviting an address replaces its earlier invite.
+        for invite in pending.iter().filter(|invite| invite.email == email) {
+            self.member_store
+                .delete_invite(namespace_id, &invite.id)
+                .await?;
+        }
+
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let invite = NamespaceInviteInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            namespace_id: namespace_id.to_string(),
+            email,
+            role: request.role,
+            invited_by: caller_user_id.to_string(),
+            created_at: now,
+            expires_at: now + INVITE_EXPIRY_DAYS * 24 * 60 * 60,
+        };
+        self.member_store
+            .create_invite(&invite, &hash_invite_token(&token))
+            .await?;
+
+        let accept_url = format!("{accept_url_base}?invite={token}");
+        let emailed = match self.mailer {
+            Some(mailer) => {
+                mailer
+                    .send_namespace_invite(
+                        &invite.email,
+                        caller_email,
+                        &namespace_display_name(&ns),
+                        invite.role,
+                        &accept_url,
+                    )
+                    .await?;
+                true
+            }
+            None => false,
+        };
+
+        Ok(CreatedInvite {
+            invite,
+            accept_url,
+            emailed,
+        })
+    }
+
+    /// Pending invites on the namespace. Owner-only.
+    pub async fn list_invites(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<Vec<NamespaceInviteInfo>, ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
+        self.member_store.list_invites(namespace_id).await
+    }
+
+    /// Withdraw a pending invite. Owner-only.
+    pub async fn revoke_invite(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        invite_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
+        if !self
+            .member_store
+            .delete_invite(namespace_id, invite_id)
+            .await?
+        {
+            return Err(ServerCoreError::not_found("Invite not found"));
+        }
+        Ok(())
+    }
+
+    /// Accept an invite as the signed-in user. The invite is single-use and
+    /// only the address it was sent to can accept it.
+    pub async fn accept(
+        &self,
+        token: &str,
+        caller_user_id: &str,
+        caller_email: &str,
+    ) -> Result<NamespaceMemberInfo, ServerCoreError> {
+        let now = Utc::now().timestamp();
+        let invite = self
+            .member_store
+            .get_invite_by_hash(&hash_invite_token(token))
+            .await?
+            .filter(|invite| invite.expires_at > now)
+            .ok_or_else(|| ServerCoreError::not_found("Invalid or expired invite"))?;
+        if invite.email != caller_email.trim().to_lowercase() {
+            return Err(ServerCoreError::permission_denied(
+                "This invite was sent to a different email address",
+            ));
+        }
+
+        let ns = self
+            .namespace_store
+            .get_namespace(&invite.namespace_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
+        self.member_store
+            .delete_invite(&invite.namespace_id, &invite.id)
+            .await?;
+        if ns.owner_user_id == caller_user_id {
+            return Err(ServerCoreError::conflict("You already own this namespace"));
+        }
+
+        let member = NamespaceMemberInfo {
+            namespace_id: invite.namespace_id,
+            user_id: caller_user_id.to_string(),
+            email: Some(invite.email),
+            role: invite.role,
+            created_at: now,
+        };
+        self.member_store.upsert_member(&member).await?;
+        Ok(member)
+    }
+
+    /// Change a member's role. Owner-only; the namespace owner's own role is
+    /// fixed.
+    pub async fn update_role(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        user_id: &str,
+        role: NamespaceRole,
+    ) -> Result<NamespaceMemberInfo, ServerCoreError> {
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Owner)
+            .await?;
+        if user_id == ns.owner_user_id {
+            return Err(ServerCoreError::invalid_input(
+                "The namespace owner's role cannot be changed",
+            ));
+        }
+        let mut member = self
+            .member_store
+            .get_member(namespace_id, user_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Member not found"))?;
+        member.role = role;
+        self.member_store.upsert_member(&member).await?;
+        Ok(member)
+    }
+
+    /// Remove a member. Owners can remove anyone but the namespace owner;
+    /// any member can remove themselves.
+    pub async fn remove_member(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        let required = if user_id == caller_user_id {
+            NamespaceRole::Viewer
+        } else {
+            NamespaceRole::Owner
+        };
+        let ns = self
+            .require_role(namespace_id, caller_user_id, required)
+            .await?;
+        if user_id == ns.owner_user_id {
+            return Err(ServerCoreError::invalid_input(
+                "The namespace owner cannot be removed",
+            ));
+        }
+        if !self
+            .member_store
+            .remove_member(namespace_id, user_id)
+            .await?
+        {
+            return Err(ServerCoreError::not_found("Member not found"));
+        }
+        Ok(())
+    }
+}
+
+/// The name to show for a namespace in emails: its `name` metadata field
+/// when set, otherwise the ID.
+pub(crate) fn namespace_display_name(ns: &NamespaceInfo) -> String {
+    ns.metadata
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+        .and_then(|v| v.get("name").and_then(|n| n.as_str()).map(str::to_string))
+        .unwrap_or_else(|| ns.id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InviteMemberRequest, NamespaceMemberService, require_namespace_role};
+    use crate::domain::NamespaceRole;
+    use crate::ports::{Mailer, NamespaceMemberStore, NamespaceStore, ServerCoreError};
+    use crate::testing::{InMemoryNamespaceMemberStore, InMemoryNamespaceStore};
+    use std::sync::Mutex;
+
+    const ACCEPT_BASE: &str = "https://app.example.com/invite";
+
+    #[derive(Default)]
+    struct RecordingMailer {
+        sent: Mutex<Vec<(String, String, String)>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl Mailer for RecordingMailer {
+        async fn send_magic_link(&self, _: &str, _: &str, _: &str) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+
+        async fn send_namespace_invite(
+            &self,
+            to_email: &str,
+            _inviter_email: &str,
+            namespace_name: &str,
+            _role: NamespaceRole,
+            accept_url: &str,
+        ) -> Result<(), ServerCoreError> {
+            self.sent.lock().unwrap().push((
+                to_email.to_string(),
+                namespace_name.to_string(),
+                accept_url.to_string(),
+            ));
+            Ok(())
+        }
+
+        async fn send_account_deletion_confirmation(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+
+        async fn send_comment_notification(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<(), ServerCoreError> {
+            Ok(())
+        }
+    }
+    }
+
+    fn invite_request(email: &str, role: NamespaceRole) -> InviteMemberRequest {
+        InviteMemberRequest {
+            email: email.to_string(),
+            role,
+        }
+    }
+
+    fn token_from(accept_url: &str) -> &str {
+        accept_url
+            .split_once("?invite=")
+            .map(|(_, token)| token)
+            .expect("accept url carries the invite token")
+    }
+
+    async fn namespace_store() -> InMemoryNamespaceStore {
+        let namespaces = InMemoryNamespaceStore::new();
+        namespaces
+            .create_namespace("family", "alice", Some(r#"{"name":"Family Blog"}"#))
+            .await
+            .unwrap();
+        namespaces
+    }
+
+    #[tokio::test]
+    async fn invite_is_emailed_and_accepted_by_the_invitee() {
+        let namespaces = namespace_store().await;
+        let members = InMemoryNamespaceMemberStore::new();
+        let mailer = RecordingMailer::default();
+        let service = NamespaceMemberService::new(&namespaces, &members).with_mailer(&mailer);
+
+        let created = service
+            .invite(
+                "family",
+                "alice",
+                "alice@example.com",
+                invite_request("Bob@Example.com", NamespaceRole::Editor),
+                ACCEPT_BASE,
+            )
+            .await
+            .unwrap();
+        assert!(created.emailed);
+        assert_eq!(created.invite.email, "bob@example.com");
+        {
+            let sent = mailer.sent.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].0, "bob@example.com");
+            assert_eq!(sent[0].1, "Family Blog");
+            assert_eq!(sent[0].2, created.accept_url);
+        }
+
+        let token = token_from(&created.accept_url);
+        let member = service
+            .accept(token, "bob", "bob@example.com")
+            .await
+            .unwrap();
+        assert_eq!(member.role, NamespaceRole::Editor);
+        assert!(
+            service
+                .list_invites("family", "alice")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Invites are single-use.
+        let err = service
+            .accept(token, "bob", "bob@example.com")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+
+        let listed = service.list_members("family", "bob").await.unwrap();
+        let roles: Vec<_> = listed
+            .iter()
+            .map(|m| (m.user_id.as_str(), m.role))
+            .collect();
+        assert_eq!(
+            roles,
+            vec![
+                ("alice", NamespaceRole::Owner),
+                ("bob", NamespaceRole::Editor)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn invite_cannot_be_accepted_by_another_address() {
+        let namespaces = namespace_store().await;
+        let members = InMemoryNamespaceMemberStore::new();
+        let service = NamespaceMemberService::new(&namespaces, &members);
+
+        let created = service
+            .invite(
+                "family",
+                "alice",
+                "alice@example.com",
+                invite_request("bob@example.com", NamespaceRole::Viewer),
+                ACCEPT_BASE,
+            )
+            .await
+            .unwrap();
+        assert!(!created.emailed);
+
+        let err = service
+            .accept(
+                token_from(&created.accept_url),
+                "mallory",
+                "mallory@example.com",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+        assert!(
+            members
+                .get_member("family", "mallory")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn only_owners_manage_members() {
+        let namespaces = namespace_store().await;
+        let members = InMemoryNamespaceMemberStore::new();
+        let service = NamespaceMemberService::new(&namespaces, &members);
+        let created = service
+            .invite(
+                "family",
+                "alice",
+                "alice@example.com",
+                invite_request("bob@example.com", NamespaceRole::Editor),
+                ACCEPT_BASE,
+            )
+            .await
+            .unwrap();
+        service
+            .accept(token_from(&created.accept_url), "bob", "bob@example.com")
+            .await
+            .unwrap();
+
+        let err = service
+            .invite(
+                "family",
+                "bob",
+                "bob@example.com",
+                invite_request("carol@example.com", NamespaceRole::Viewer),
+                ACCEPT_BASE,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+
+        let err = service
+            .remove_member("family", "bob", "alice")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+
+        service
+            .update_role("family", "alice", "bob", NamespaceRole::Viewer)
+            .await
+            .unwrap();
+        let err = service
+            .update_role("family", "alice", "alice", NamespaceRole::Viewer)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        // Members can always leave.
+        service.remove_member("family", "bob", "bob").await.unwrap();
+        assert!(members.get_member("family", "bob").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn role_checks_respect_the_role_order() {
+        let namespaces = namespace_store().await;
+        let members = InMemoryNamespaceMemberStore::new();
+        members
+            .upsert_member(&crate::domain::NamespaceMemberInfo {
+                namespace_id: "family".to_string(),
+                user_id: "grandma".to_string(),
+                email: None,
+                role: NamespaceRole::Viewer,
+                created_at: 0,
+            })
+            .await
+            .unwrap();
+
+        let check = |user: &'static str, role: NamespaceRole, with_members: bool| {
+            let namespaces = &namespaces;
+            let members = &members;
+            async move {
+                let store: Option<&dyn NamespaceMemberStore> =
+                    if with_members { Some(members) } else { None };
+                require_namespace_role(namespaces, store, "family", user, role).await
+            }
+        };
+
+        assert!(check("grandma", NamespaceRole::Viewer, true).await.is_ok());
+        assert!(matches!(
+            check("grandma", NamespaceRole::Editor, true).await,
+            Err(ServerCoreError::PermissionDenied(_))
+        ));
+        assert!(check("alice", NamespaceRole::Owner, true).await.is_ok());
+        // Without a member store only the owner has a role.
+        assert!(matches!(
+            check("grandma", NamespaceRole::Viewer, false).await,
+            Err(ServerCoreError::PermissionDenied(_))
+        ));
+        assert!(check("alice", NamespaceRole::Viewer, true).await.is_ok());
+        assert!(matches!(
+            require_namespace_role(&namespaces, None, "missing", "alice", NamespaceRole::Viewer)
+                .await,
+            Err(ServerCoreError::NotFound(_))
+        ));
+    }
+}
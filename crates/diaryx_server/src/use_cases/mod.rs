@@ -1,12 +1,21 @@
+pub mod access_tokens;
+pub mod activitypub;
+pub mod archive;
 pub mod ark;
 pub mod audiences;
+pub mod audit;
 pub mod auth;
 pub mod billing;
+pub mod comments;
 pub mod current_user;
 pub mod domains;
+pub mod members;
 pub mod namespaces;
 pub mod objects;
 pub mod passkeys;
 pub mod proxy;
 pub mod render;
 pub mod sessions;
+pub mod storage;
+pub mod webhooks;
+pub mod webmentions;
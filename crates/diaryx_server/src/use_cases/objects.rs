@@ -1,5 +1,11 @@
-use crate::domain::{ObjectMeta, PublicObjectAccess, UsageTotals};
-use crate::ports::{BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError};
+use crate::domain::{
+    NamespaceInfo, NamespaceRole, ObjectMeta, PublicObjectAccess, UsageTotals, WebhookEvent,
+};
+use crate::ports::{
+    BlobStore, NamespaceMemberStore, NamespaceStore, ObjectMetaStore, ServerCoreError,
+};
+use crate::use_cases::members::require_namespace_role;
+use crate::use_cases::webhooks::WebhookDispatcher;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 
@@ -17,6 +23,15 @@ pub struct GetObjectResult {
     pub bytes: Vec<u8>,
 }
 
+/// A source version retained by [`ObjectService::retain_version`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainedVersion {
+    /// SHA-256 hex digest of the source — the ARK `.<FILE>` variant.
+    pub content_hash: String,
+    /// Key of the immutable version object.
+    pub key: String,
+}
+
 /// Result of a batch object get.
 #[derive(Debug)]
 pub struct BatchGetResult {
@@ -32,6 +47,8 @@ pub struct ObjectService<'a> {
     namespace_store: &'a dyn NamespaceStore,
     object_meta_store: &'a dyn ObjectMetaStore,
     blob_store: &'a dyn BlobStore,
+    member_store: Option<&'a dyn NamespaceMemberStore>,
+    webhooks: Option<WebhookDispatcher<'a>>,
 }
 
 impl<'a> ObjectService<'a> {
@@ -44,25 +61,40 @@ impl<'a> ObjectService<'a> {
             namespace_store,
             object_meta_store,
             blob_store,
+            member_store: None,
+            webhooks: None,
         }
     }
 
-    async fn require_namespace_owner(
+    /// Let namespace collaborators in: viewers can read objects, editors can
+    /// also write them. Without a member store only the owner has access.
+    pub fn with_members(mut self, member_store: &'a dyn NamespaceMemberStore) -> Self {
+        self.member_store = Some(member_store);
+        self
+    }
+
+    /// Notify the namespace's webhooks when an object is deleted.
+    pub fn with_webhooks(mut self, webhooks: WebhookDispatcher<'a>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Require `role` on the namespace. Usage is charged to the returned
+    /// namespace's owner, whoever makes the request.
+    async fn require_role(
         &self,
         namespace_id: &str,
         caller_user_id: &str,
-    ) -> Result<(), ServerCoreError> {
-        let ns = self
-            .namespace_store
-            .get_namespace(namespace_id)
-            .await?
-            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
-        if ns.owner_user_id != caller_user_id {
-            return Err(ServerCoreError::permission_denied(
-                "You do not own this namespace",
-            ));
-        }
-        Ok(())
+        role: NamespaceRole,
+    ) -> Result<NamespaceInfo, ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            self.member_store,
+            namespace_id,
+            caller_user_id,
+            role,
+        )
+        .await
     }
 
     pub async fn put(
@@ -74,7 +106,8 @@ impl<'a> ObjectService<'a> {
         audience: Option<&str>,
         caller_user_id: &str,
     ) -> Result<PutObjectResult, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Editor)
             .await?;
 
         // Validate audience exists if specified.
@@ -166,7 +199,7 @@ impl<'a> ObjectService<'a> {
         // Record bytes_in usage (fire-and-forget; errors are non-fatal).
         let _ = self
             .object_meta_store
-            .record_usage(caller_user_id, "bytes_in", size, Some(namespace_id))
+            .record_usage(&ns.owner_user_id, "bytes_in", size, Some(namespace_id))
             .await;
 
         Ok(PutObjectResult {
@@ -181,7 +214,8 @@ impl<'a> ObjectService<'a> {
         key: &str,
         caller_user_id: &str,
     ) -> Result<GetObjectResult, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
             .await?;
 
         let meta = self
@@ -203,7 +237,7 @@ impl<'a> ObjectService<'a> {
         let size = bytes.len() as u64;
         let _ = self
             .object_meta_store
-            .record_usage(caller_user_id, "bytes_out", size, Some(namespace_id))
+            .record_usage(&ns.owner_user_id, "bytes_out", size, Some(namespace_id))
             .await;
 
         Ok(GetObjectResult {
@@ -229,7 +263,8 @@ impl<'a> ObjectService<'a> {
             )));
         }
 
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
             .await?;
 
         let mut objects = HashMap::new();
@@ -296,7 +331,7 @@ impl<'a> ObjectService<'a> {
             let _ = self
                 .object_meta_store
                 .record_usage(
-                    caller_user_id,
+                    &ns.owner_user_id,
                     "bytes_out",
                     total_bytes_out,
                     Some(namespace_id),
@@ -317,7 +352,7 @@ impl<'a> ObjectService<'a> {
         key: &str,
         caller_user_id: &str,
     ) -> Result<(), ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Editor)
             .await?;
 
         let meta = self
@@ -343,6 +378,15 @@ impl<'a> ObjectService<'a> {
             self.blob_store.delete(&blob_key).await?;
         }
 
+        if let Some(webhooks) = &self.webhooks {
+            webhooks
+                .emit(
+                    namespace_id,
+                    WebhookEvent::ObjectDeleted,
+                    serde_json::json!({ "key": key, "deleted_by": caller_user_id }),
+                )
+                .await;
+        }
         Ok(())
     }
 
@@ -353,7 +397,7 @@ impl<'a> ObjectService<'a> {
         offset: u32,
         caller_user_id: &str,
     ) -> Result<Vec<ObjectMeta>, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
             .await?;
 
         let limit = limit.min(500);
@@ -371,11 +415,12 @@ impl<'a> ObjectService<'a> {
         namespace_id: &str,
         caller_user_id: &str,
     ) -> Result<UsageTotals, ServerCoreError> {
-        self.require_namespace_owner(namespace_id, caller_user_id)
+        let ns = self
+            .require_role(namespace_id, caller_user_id, NamespaceRole::Viewer)
             .await?;
 
         self.object_meta_store
-            .get_namespace_usage_totals(caller_user_id, namespace_id)
+            .get_namespace_usage_totals(&ns.owner_user_id, namespace_id)
             .await
     }
 
@@ -412,6 +457,86 @@ impl<'a> ObjectService<'a> {
         })
     }
 
+    /// Retain the current content of `source_key` as an immutable version
+    /// object for `file_ark`, keyed by content hash (see [`ark_version_key`]).
+    ///
+    /// The version object is owner-only (no audience) and points at the same
+    /// content-addressed blob as the source, so no bytes are copied and the
+    /// blob outlives later republishes that replace the source. Returns
+    /// `Ok(None)` when the source is missing or predates content hashing.
+    /// Idempotent: an existing version object is never rewritten.
+    pub async fn retain_version(
+        &self,
+        namespace_id: &str,
+        source_key: &str,
+        file_ark: &str,
+        caller_user_id: &str,
+    ) -> Result<Option<RetainedVersion>, ServerCoreError> {
+        self.require_role(namespace_id, caller_user_id, NamespaceRole::Editor)
+            .await?;
+
+        let Some(meta) = self
+            .object_meta_store
+            .get_object_meta(namespace_id, source_key)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let (Some(blob_key), Some(content_hash)) = (meta.blob_key, meta.content_hash) else {
+            return Ok(None);
+        };
+
+        let key = ark_version_key(file_ark, &content_hash);
+        if self
+            .object_meta_store
+            .get_object_meta(namespace_id, &key)
+            .await?
+            .is_none()
+        {
+            self.object_meta_store
+                .upsert_object(
+                    namespace_id,
+                    &key,
+                    &blob_key,
+                    &meta.mime_type,
+                    meta.size_bytes,
+                    None,
+                    Some(&content_hash),
+                )
+                .await?;
+        }
+
+        Ok(Some(RetainedVersion { content_hash, key }))
+    }
+
+    /// Fetch an object's bytes by key, following its recorded blob key.
+    ///
+    /// Performs no access check: callers resolve and gate access first (e.g.
+    /// ARK version resolution, which gates on the file's canonical rendition).
+    pub async fn fetch_object(
+        &self,
+        namespace_id: &str,
+        key: &str,
+    ) -> Result<GetObjectResult, ServerCoreError> {
+        let meta = self
+            .object_meta_store
+            .get_object_meta(namespace_id, key)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Object not found"))?;
+        let blob_key = meta
+            .blob_key
+            .unwrap_or_else(|| object_blob_key(namespace_id, key));
+        let bytes = self
+            .blob_store
+            .get(&blob_key)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Object not found"))?;
+        Ok(GetObjectResult {
+            mime_type: meta.mime_type,
+            bytes,
+        })
+    }
+
     /// Fetch bytes from the blob store for a resolved public object.
     pub async fn fetch_blob(
         &self,
@@ -447,8 +572,14 @@ pub fn object_blob_key(namespace_id: &str, key: &str) -> String {
     format!("ns/{}/{}", namespace_id, key)
 }
 
+/// Derive the object key of a retained ARK source version. Lives outside every
+/// audience prefix, so publish diffs and the site build never touch it.
+pub fn ark_version_key(file_ark: &str, content_hash: &str) -> String {
+    format!("_versions/{}/{}.md", file_ark, content_hash)
+}
+
 /// Derive the content-addressed blob key for a namespace object.
-fn content_blob_key(namespace_id: &str, content_hash: &str) -> String {
+pub(crate) fn content_blob_key(namespace_id: &str, content_hash: &str) -> String {
     format!("ns/{}/blobs/{}", namespace_id, content_hash)
 }
 
@@ -668,6 +799,12 @@ mod tests {
         async fn get_usage_totals(&self, _user_id: &str) -> Result<UsageTotals, ServerCoreError> {
             Ok(UsageTotals::default())
         }
+        async fn list_usage_events(
+            &self,
+            _user_id: &str,
+        ) -> Result<Vec<crate::domain::UsageEvent>, ServerCoreError> {
+            Ok(vec![])
+        }
         async fn get_namespace_usage_totals(
             &self,
             _user_id: &str,
@@ -1081,4 +1218,54 @@ mod tests {
         service.delete("ns1", "legacy.txt", "user1").await.unwrap();
         assert!(blob_store.blobs.lock().unwrap().is_empty());
     }
+
+    #[tokio::test]
+    async fn retained_version_outlives_source_overwrite() {
+        let (ns_store, obj_store, blob_store) = make_stores();
+        let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+
+        service
+            .put("ns1", "note.md", "text/markdown", b"# v1", None, "user1")
+            .await
+            .unwrap();
+        let v1 = service
+            .retain_version("ns1", "note.md", "bcdfgr", "user1")
+            .await
+            .unwrap()
+            .expect("source has a content hash");
+        assert_eq!(v1.key, super::ark_version_key("bcdfgr", &v1.content_hash));
+
+        // Retaining identical content again is a no-op returning the same key.
+        let again = service
+            .retain_version("ns1", "note.md", "bcdfgr", "user1")
+            .await
+            .unwrap();
+        assert_eq!(again.as_ref(), Some(&v1));
+
+        // Overwriting the source must not drop the v1 blob: the version
+        // object still references it.
+        service
+            .put("ns1", "note.md", "text/markdown", b"# v2", None, "user1")
+            .await
+            .unwrap();
+        assert_eq!(blob_store.blobs.lock().unwrap().len(), 2);
+
+        let old = service.fetch_object("ns1", &v1.key).await.unwrap();
+        assert_eq!(old.bytes, b"# v1");
+        assert_eq!(old.mime_type, "text/markdown");
+
+        // Version objects are owner-only.
+        assert!(service.resolve_public_access("ns1", &v1.key).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn retain_version_skips_missing_source() {
+        let (ns_store, obj_store, blob_store) = make_stores();
+        let service = ObjectService::new(&ns_store, &obj_store, &blob_store);
+        let retained = service
+            .retain_version("ns1", "missing.md", "bcdfgr", "user1")
+            .await
+            .unwrap();
+        assert!(retained.is_none());
+    }
 }
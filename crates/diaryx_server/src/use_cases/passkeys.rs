@@ -3,8 +3,9 @@
 //! Uses `webauthn_rp` for server-side ceremony verification. Both native
 //! (Axum/SQLite) and Cloudflare Workers adapters share this implementation.
 
-use crate::domain::PasskeyInfo;
+use crate::domain::{AuditAction, PasskeyInfo};
 use crate::ports::{PasskeyStore, ServerCoreError};
+use crate::use_cases::audit::AuditRecorder;
 use base64::Engine;
 use tracing::info;
 use webauthn_rp::bin::{Decode, Encode};
@@ -35,6 +36,7 @@ fn dec_err(e: impl std::fmt::Display) -> ServerCoreError {
 pub struct PasskeyService<'a> {
     store: &'a dyn PasskeyStore,
     rp_id: String,
+    audit: Option<AuditRecorder<'a>>,
 }
 
 /// Stored credential data (serialized via webauthn_rp's binary encoding).
@@ -68,9 +70,16 @@ impl<'a> PasskeyService<'a> {
         Self {
             store,
             rp_id: rp_id.to_string(),
+            audit: None,
         }
     }
 
+    /// Record passkey registrations and deletions in the user's audit log.
+    pub fn with_audit(mut self, audit: AuditRecorder<'a>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
     fn rp_id(&self) -> Result<RpId, ServerCoreError> {
         let domain = AsciiDomain::try_from(self.rp_id.clone())
             .map_err(|_| ServerCoreError::internal(format!("Invalid RP ID: {}", self.rp_id)))?;
@@ -236,6 +245,16 @@ impl<'a> PasskeyService<'a> {
             .await?;
 
         info!("Passkey '{}' registered for user {}", name, user_id);
+        if let Some(audit) = &self.audit {
+            audit
+                .account(
+                    AuditAction::PasskeyRegistered,
+                    user_id,
+                    Some(&id),
+                    serde_json::json!({ "name": name }),
+                )
+                .await;
+        }
         Ok(id)
     }
 
@@ -442,7 +461,18 @@ impl<'a> PasskeyService<'a> {
 
     /// Delete a passkey.
     pub async fn delete_passkey(&self, id: &str, user_id: &str) -> Result<bool, ServerCoreError> {
-        self.store.delete_credential(id, user_id).await
+        let deleted = self.store.delete_credential(id, user_id).await?;
+        if deleted && let Some(audit) = &self.audit {
+            audit
+                .account(
+                    AuditAction::PasskeyDeleted,
+                    user_id,
+                    Some(id),
+                    serde_json::Value::Null,
+                )
+                .await;
+        }
+        Ok(deleted)
     }
 }
 
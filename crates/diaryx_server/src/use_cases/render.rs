@@ -8,17 +8,26 @@
 //!
 //! Sources are keyed by their (sanitized) workspace-relative path; the
 //! per-audience root is the page whose dest is `index.html`. Rendering itself
-//! lives in the portable `diaryx_render` engine.
+//! lives in the portable `diaryx_render` engine; plugin syntax (math,
+//! drawings, ...) is expanded by whatever [`RenderHooks`] the host supplies.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
-use diaryx_render::SiteStyle;
 use diaryx_render::site::{SiteOptions, SourceDoc, render_site};
+use diaryx_render::types::FeedEntry;
+use diaryx_render::{RenderHooks, SiteStyle};
 
-use crate::domain::ArkIndexEntry;
-use crate::ports::{ArkIndexStore, BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError};
+use crate::domain::{ArkIndexEntry, NamespaceRole, WebhookEvent};
+use crate::ports::{
+    ArkIndexStore, BlobStore, NamespaceMemberStore, NamespaceStore, ObjectMetaStore,
+    ServerCoreError, WebmentionStore,
+};
+use crate::use_cases::activitypub::ActivityPubPublisher;
 use crate::use_cases::ark::ARK_WORKSPACE_INDEX;
+use crate::use_cases::members::require_namespace_role;
 use crate::use_cases::objects::ObjectService;
+use crate::use_cases::webhooks::WebhookDispatcher;
+use crate::use_cases::webmentions::{approved_mentions_by_page, webmention_endpoint};
 
 /// Summary of a build run.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -37,6 +46,11 @@ pub struct RenderService<'a> {
     object_meta_store: &'a dyn ObjectMetaStore,
     blob_store: &'a dyn BlobStore,
     ark_index: &'a dyn ArkIndexStore,
+    member_store: Option<&'a dyn NamespaceMemberStore>,
+    webhooks: Option<WebhookDispatcher<'a>>,
+    webmentions: Option<(&'a dyn WebmentionStore, &'a str)>,
+    activitypub: Option<ActivityPubPublisher<'a>>,
+    render_hooks: RenderHooks,
 }
 
 impl<'a> RenderService<'a> {
@@ -51,28 +65,83 @@ impl<'a> RenderService<'a> {
             object_meta_store,
             blob_store,
             ark_index,
+            member_store: None,
+            webhooks: None,
+            webmentions: None,
+            activitypub: None,
+            render_hooks: RenderHooks::new(),
+        }
+    }
+
+    /// Let namespace editors trigger builds. Without a member store only the
+    /// owner can.
+    pub fn with_members(mut self, member_store: &'a dyn NamespaceMemberStore) -> Self {
+        self.member_store = Some(member_store);
+        self
+    }
+
+    /// Notify the namespace's webhooks when a build completes.
+    pub fn with_webhooks(mut self, webhooks: WebhookDispatcher<'a>) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Advertise the Webmention endpoint under `api_base_url` on pages of
+    /// public audiences and list each page's approved mentions.
+    pub fn with_webmentions(
+        mut self,
+        webmention_store: &'a dyn WebmentionStore,
+        api_base_url: &'a str,
+    ) -> Self {
+        self.webmentions = Some((webmention_store, api_base_url));
+        self
+    }
+
+    /// Keep the namespace's ActivityPub outbox in step with the feed pages
+    /// of its public audiences and announce new ones to followers. Needs a
+    /// `base_url` on the build to link the pages.
+    pub fn with_activitypub(mut self, publisher: ActivityPubPublisher<'a>) -> Self {
+        self.activitypub = Some(publisher);
+        self
+    }
+
+    /// Expand plugin editor syntax through `hooks`. Without hooks only the
+    /// built-in syntax (highlights, spoilers, HTML embeds) is rendered.
+    pub fn with_render_hooks(mut self, hooks: RenderHooks) -> Self {
+        self.render_hooks = hooks;
+        self
+    }
+
+    fn object_service(&self) -> ObjectService<'a> {
+        let service = ObjectService::new(
+            self.namespace_store,
+            self.object_meta_store,
+            self.blob_store,
+        );
+        match self.member_store {
+            Some(member_store) => service.with_members(member_store),
+            None => service,
         }
     }
 
     /// Render every page in the namespace (grouped by audience) from its stored
-    /// markdown source and write the resulting HTML + assets. Owner-gated.
+    /// markdown source and write the resulting HTML + assets. Requires the
+    /// editor role.
     pub async fn build_namespace(
         &self,
         namespace_id: &str,
         caller_user_id: &str,
         base_url: Option<&str>,
     ) -> Result<BuildSummary, ServerCoreError> {
-        // Ownership check (the build mutates the namespace's objects).
-        let ns = self
-            .namespace_store
-            .get_namespace(namespace_id)
-            .await?
-            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
-        if ns.owner_user_id != caller_user_id {
-            return Err(ServerCoreError::permission_denied(
-                "You do not own this namespace",
-            ));
-        }
+        // Role check (the build mutates the namespace's objects).
+        require_namespace_role(
+            self.namespace_store,
+            self.member_store,
+            namespace_id,
+            caller_user_id,
+            NamespaceRole::Editor,
+        )
+        .await?;
 
         let rows = self.ark_index.list_ark_entries(namespace_id).await?;
 
@@ -90,13 +159,14 @@ impl<'a> RenderService<'a> {
             by_audience.entry(aud).or_default().push(row);
         }
 
-        let object_service = ObjectService::new(
-            self.namespace_store,
-            self.object_meta_store,
-            self.blob_store,
-        );
+        let object_service = self.object_service();
+        let mentions = match self.webmentions {
+            Some((store, _)) => approved_mentions_by_page(store, namespace_id).await?,
+            None => HashMap::new(),
+        };
 
         let mut summary = BuildSummary::default();
+        let mut public_entries: Vec<(String, Vec<FeedEntry>)> = Vec::new();
 
         for (audience, page_rows) in by_audience {
             let aud_prefix = format!("{}/", audience);
@@ -130,6 +200,26 @@ impl<'a> RenderService<'a> {
                 continue;
             }
 
+            // Only public pages take mentions or federate; gated ones aren't
+            // linkable.
+            let is_public = self.is_public(namespace_id, &audience).await?;
+            let webmention_endpoint = match self.webmentions {
+                Some((_, api_base_url)) if is_public => {
+                    Some(webmention_endpoint(api_base_url, namespace_id))
+                }
+                _ => None,
+            };
+            let page_mentions = if webmention_endpoint.is_some() {
+                mentions
+                    .iter()
+                    .filter_map(|(key, list)| {
+                        Some((key.strip_prefix(&aud_prefix)?.to_string(), list.clone()))
+                    })
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
             let opts = SiteOptions {
                 audience: if audience.is_empty() {
                     None
@@ -141,6 +231,9 @@ impl<'a> RenderService<'a> {
                 generate_seo: true,
                 generate_feeds: true,
                 style: SiteStyle::default(),
+                webmention_endpoint,
+                mentions: page_mentions,
+                render_hooks: self.render_hooks.clone(),
             };
             let rendered = render_site(&sources, &opts);
 
@@ -182,9 +275,33 @@ impl<'a> RenderService<'a> {
                 summary.assets_written += 1;
             }
 
+            if is_public {
+                public_entries.push((audience, rendered.entries));
+            }
             summary.audiences += 1;
         }
 
+        if let (Some(publisher), Some(base_url)) = (&self.activitypub, base_url) {
+            publisher
+                .publish(namespace_id, base_url, &public_entries)
+                .await;
+        }
+
+        if let Some(webhooks) = &self.webhooks {
+            webhooks
+                .emit(
+                    namespace_id,
+                    WebhookEvent::BuildCompleted,
+                    serde_json::json!({
+                        "audiences": summary.audiences,
+                        "pages_rendered": summary.pages_rendered,
+                        "assets_written": summary.assets_written,
+                        "triggered_by": caller_user_id,
+                    }),
+                )
+                .await;
+        }
+
         Ok(summary)
     }
 
@@ -195,17 +312,24 @@ impl<'a> RenderService<'a> {
         key: &str,
         caller_user_id: &str,
     ) -> Result<Option<Vec<u8>>, ServerCoreError> {
-        let object_service = ObjectService::new(
-            self.namespace_store,
-            self.object_meta_store,
-            self.blob_store,
-        );
+        let object_service = self.object_service();
         match object_service.get(namespace_id, key, caller_user_id).await {
             Ok(res) => Ok(Some(res.bytes)),
             Err(ServerCoreError::NotFound(_)) => Ok(None),
             Err(e) => Err(e),
         }
     }
+
+    async fn is_public(&self, namespace_id: &str, audience: &str) -> Result<bool, ServerCoreError> {
+        if audience.is_empty() {
+            return Ok(false);
+        }
+        Ok(self
+            .namespace_store
+            .get_audience(namespace_id, audience)
+            .await?
+            .is_some_and(|info| info.is_public()))
+    }
 }
 
 /// Join an audience prefix and a name, omitting the prefix for the default
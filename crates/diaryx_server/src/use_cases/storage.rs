@@ -0,0 +1,381 @@
+//! Blob store reconciliation.
+//!
+//! [`ObjectService`](crate::use_cases::objects::ObjectService) drops a content
+//! blob only when the last metadata row pointing at it goes away, so anything
+//! that dies between the blob write and the metadata upsert — a crashed
+//! upload, an abandoned multipart, a failed build — leaves bytes behind that
+//! nothing references. [`StorageReconcileService`] walks the blob store
+//! against the metadata store, removes what is unreferenced, aborts stale
+//! multipart uploads, and recomputes per-namespace storage from the rows that
+//! remain.
+//!
+//! Only content blobs (`ns/{ns}/blobs/{hash}`) are candidates; other keys in
+//! a namespace prefix (audience manifests, legacy per-key objects) are left
+//! alone. Blobs younger than the grace period are skipped because an upload
+//! may still be about to write their metadata row.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::ports::{BlobEntry, BlobStore, NamespaceStore, ObjectMetaStore, ServerCoreError};
+
+/// Default age a blob or multipart upload must reach before it is collected.
+pub const DEFAULT_RECONCILE_GRACE_SECS: i64 = 24 * 60 * 60;
+
+/// Objects are listed from the meta store in pages of this size.
+const LIST_PAGE_SIZE: u32 = 500;
+
+/// How a reconciliation run should behave.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconcileOptions {
+    /// Report what would be removed without deleting or aborting anything.
+    pub dry_run: bool,
+    /// Minimum age, in seconds, before an unreferenced blob or a pending
+    /// multipart upload is considered abandoned.
+    pub grace_secs: i64,
+    /// Current Unix time, used to age blobs and uploads.
+    pub now: i64,
+}
+
+/// Why a blob was judged unreferenced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// No object metadata row points at the blob.
+    Unreferenced,
+    /// The namespace the blob was written under no longer exists.
+    NamespaceDeleted,
+}
+
+/// A blob that no metadata row accounts for.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct OrphanedBlob {
+    pub key: String,
+    pub namespace_id: String,
+    pub size_bytes: Option<u64>,
+    pub reason: OrphanReason,
+}
+
+/// A multipart upload that was aborted (or would be, on a dry run).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AbortedUpload {
+    pub key: String,
+    pub upload_id: String,
+    pub initiated_at: Option<i64>,
+}
+
+/// Storage held by one namespace, recomputed from its metadata rows. Each
+/// distinct blob counts once, however many keys point at it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NamespaceStorage {
+    pub namespace_id: String,
+    pub owner_user_id: String,
+    pub objects: u64,
+    pub blobs: u64,
+    pub stored_bytes: u64,
+}
+
+/// Outcome of a reconciliation run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ReconcileReport {
+    pub dry_run: bool,
+    pub blobs_scanned: u64,
+    pub orphaned: Vec<OrphanedBlob>,
+    /// Sum of the orphans' sizes, where the backend reported them.
+    pub orphaned_bytes: u64,
+    /// Orphans actually removed. Always zero on a dry run.
+    pub deleted: u64,
+    /// Unreferenced blobs left in place because they are inside the grace
+    /// period.
+    pub skipped_recent: u64,
+    pub aborted_uploads: Vec<AbortedUpload>,
+    pub namespaces: Vec<NamespaceStorage>,
+    /// `owner_user_id -> stored_bytes` summed across their namespaces.
+    pub user_stored_bytes: BTreeMap<String, u64>,
+}
+
+/// Finds and removes blobs and multipart uploads nothing references.
+pub struct StorageReconcileService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    object_meta_store: &'a dyn ObjectMetaStore,
+    blob_store: &'a dyn BlobStore,
+}
+
+impl<'a> StorageReconcileService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        object_meta_store: &'a dyn ObjectMetaStore,
+        blob_store: &'a dyn BlobStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            object_meta_store,
+            blob_store,
+        }
+    }
+
+    /// Run one reconciliation pass over every namespace in the blob store.
+    pub async fn reconcile(
+        &self,
+        options: ReconcileOptions,
+    ) -> Result<ReconcileReport, ServerCoreError> {
+        let mut report = ReconcileReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        let cutoff = options.now - options.grace_secs;
+
+        // namespace_id -> owner, or None once the namespace is known to be gone.
+        let mut owners: HashMap<String, Option<String>> = HashMap::new();
+
+        for entry in self.blob_store.list_entries_by_prefix("ns/").await? {
+            let Some(namespace_id) = content_blob_namespace(&entry.key) else {
+                continue;
+            };
+            report.blobs_scanned += 1;
+
+            if !owners.contains_key(namespace_id) {
+                let owner = self
+                    .namespace_store
+                    .get_namespace(namespace_id)
+                    .await?
+                    .map(|ns| ns.owner_user_id);
+                owners.insert(namespace_id.to_string(), owner);
+            }
+
+            let reason = if owners[namespace_id].is_none() {
+                OrphanReason::NamespaceDeleted
+            } else if self.unreferenced(namespace_id, &entry.key).await? {
+                if is_recent(&entry, cutoff) {
+                    report.skipped_recent += 1;
+                    continue;
+                }
+                OrphanReason::Unreferenced
+            } else {
+                continue;
+            };
+
+            if !options.dry_run {
+                // An upload may have written its metadata row since the
+                // first check; only delete if the blob is still unclaimed.
+                if reason == OrphanReason::NamespaceDeleted
+                    || self.unreferenced(namespace_id, &entry.key).await?
+                {
+                    self.blob_store.delete(&entry.key).await?;
+                    report.deleted += 1;
+                } else {
+                    continue;
+                }
+            }
+
+            report.orphaned_bytes += entry.size_bytes.unwrap_or(0);
+            report.orphaned.push(OrphanedBlob {
+                namespace_id: namespace_id.to_string(),
+                key: entry.key,
+                size_bytes: entry.size_bytes,
+                reason,
+            });
+        }
+
+        for upload in self.blob_store.list_multipart_uploads("ns/").await? {
+            // Uploads of unknown age are left alone; the backend can't tell
+            // an abandoned one from one in flight.
+            let Some(initiated_at) = upload.initiated_at else {
+                continue;
+            };
+            if initiated_at > cutoff {
+                continue;
+            }
+            if !options.dry_run {
+                self.blob_store
+                    .abort_multipart(&upload.key, &upload.upload_id)
+                    .await?;
+            }
+            report.aborted_uploads.push(AbortedUpload {
+                key: upload.key,
+                upload_id: upload.upload_id,
+                initiated_at: upload.initiated_at,
+            });
+        }
+
+        let mut live: Vec<(String, String)> = owners
+            .into_iter()
+            .filter_map(|(ns, owner)| owner.map(|owner| (ns, owner)))
+            .collect();
+        live.sort();
+        for (namespace_id, owner_user_id) in live {
+            let storage = self.namespace_storage(&namespace_id, owner_user_id).await?;
+            *report
+                .user_stored_bytes
+                .entry(storage.owner_user_id.clone())
+                .or_default() += storage.stored_bytes;
+            report.namespaces.push(storage);
+        }
+
+        Ok(report)
+    }
+
+    async fn unreferenced(
+        &self,
+        namespace_id: &str,
+        blob_key: &str,
+    ) -> Result<bool, ServerCoreError> {
+        Ok(self
+            .object_meta_store
+            .count_refs_to_blob(namespace_id, blob_key)
+            .await?
+            == 0)
+    }
+
+    async fn namespace_storage(
+        &self,
+        namespace_id: &str,
+        owner_user_id: String,
+    ) -> Result<NamespaceStorage, ServerCoreError> {
+        let mut storage = NamespaceStorage {
+            namespace_id: namespace_id.to_string(),
+            owner_user_id,
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+        let mut offset = 0;
+        loop {
+            let page = self
+                .object_meta_store
+                .list_objects(namespace_id, LIST_PAGE_SIZE, offset)
+                .await?;
+            for meta in &page {
+                storage.objects += 1;
+                let counted = match &meta.blob_key {
+                    Some(blob_key) => seen.insert(blob_key.clone()),
+                    None => true,
+                };
+                if counted {
+                    storage.blobs += 1;
+                    storage.stored_bytes += meta.size_bytes;
+                }
+            }
+            if page.len() < LIST_PAGE_SIZE as usize {
+                break;
+            }
+            offset += LIST_PAGE_SIZE;
+        }
+        Ok(storage)
+    }
+}
+
+/// The namespace id of a content blob key (`ns/{ns}/blobs/{hash}`), or `None`
+/// for any other key.
+fn content_blob_namespace(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix("ns/")?;
+    let (namespace_id, rest) = rest.split_once('/')?;
+    let hash = rest.strip_prefix("blobs/")?;
+    if namespace_id.is_empty() || hash.is_empty() || hash.contains('/') {
+        return None;
+    }
+    Some(namespace_id)
+}
+
+/// Blobs of unknown age are treated as old: backends that can't report a
+/// modification time are in-process stores with no uploads in flight.
+fn is_recent(entry: &BlobEntry, cutoff: i64) -> bool {
+    entry.last_modified.is_some_and(|t| t > cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OrphanReason, ReconcileOptions, StorageReconcileService, content_blob_namespace};
+    use crate::ports::{BlobStore, NamespaceStore, ObjectMetaStore};
+    use crate::testing::{InMemoryBlobStore, InMemoryNamespaceStore, InMemoryObjectMetaStore};
+
+    const NOW: i64 = 1_800_000_000;
+
+    #[test]
+    fn only_content_blob_keys_are_candidates() {
+        assert_eq!(content_blob_namespace("ns/abc/blobs/ff00"), Some("abc"));
+        assert_eq!(content_blob_namespace("ns/abc/_audiences.json"), None);
+        assert_eq!(content_blob_namespace("ns/abc/_versions/x/y.md"), None);
+        assert_eq!(content_blob_namespace("ns/abc/blobs/"), None);
+        assert_eq!(content_blob_namespace("other/abc/blobs/ff00"), None);
+    }
+
+    #[tokio::test]
+    async fn removes_unreferenced_blobs_and_recomputes_storage() {
+        let namespaces = InMemoryNamespaceStore::new();
+        let meta = InMemoryObjectMetaStore::new();
+        let blobs = InMemoryBlobStore::new();
+
+        namespaces
+            .create_namespace("live", "alice", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/live/blobs/aa", b"hello", "text/plain", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/live/blobs/bb", b"stray", "text/plain", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/live/_audiences.json", b"{}", "application/json", None)
+            .await
+            .unwrap();
+        blobs
+            .put("ns/gone/blobs/cc", b"old", "text/plain", None)
+            .await
+            .unwrap();
+        // Two keys sharing one blob count its bytes once.
+        for key in ["a.md", "copy-of-a.md"] {
+            meta.upsert_object(
+                "live",
+                key,
+                "ns/live/blobs/aa",
+                "text/plain",
+                5,
+                None,
+                Some("aa"),
+            )
+            .await
+            .unwrap();
+        }
+
+        let service = StorageReconcileService::new(&namespaces, &meta, &blobs);
+        let options = ReconcileOptions {
+            dry_run: true,
+            grace_secs: 3600,
+            now: NOW,
+        };
+
+        let report = service.reconcile(options).await.unwrap();
+        assert_eq!(report.blobs_scanned, 3);
+        assert_eq!(report.orphaned.len(), 2);
+        assert_eq!(report.orphaned_bytes, 8);
+        assert_eq!(report.deleted, 0);
+        assert!(blobs.exists("ns/live/blobs/bb").await.unwrap());
+
+        let report = service
+            .reconcile(ReconcileOptions {
+                dry_run: false,
+                ..options
+            })
+            .await
+            .unwrap();
+        assert_eq!(report.deleted, 2);
+        let reasons: Vec<_> = report
+            .orphaned
+            .iter()
+            .map(|o| (o.key.as_str(), o.reason))
+            .collect();
+        assert!(reasons.contains(&("ns/live/blobs/bb", OrphanReason::Unreferenced)));
+        assert!(reasons.contains(&("ns/gone/blobs/cc", OrphanReason::NamespaceDeleted)));
+        assert!(!blobs.exists("ns/live/blobs/bb").await.unwrap());
+        assert!(!blobs.exists("ns/gone/blobs/cc").await.unwrap());
+        assert!(blobs.exists("ns/live/blobs/aa").await.unwrap());
+        assert!(blobs.exists("ns/live/_audiences.json").await.unwrap());
+
+        assert_eq!(report.namespaces.len(), 1);
+        let live = &report.namespaces[0];
+        assert_eq!((live.objects, live.blobs, live.stored_bytes), (2, 1, 5));
+        assert_eq!(report.user_stored_bytes.get("alice"), Some(&5));
+    }
+}
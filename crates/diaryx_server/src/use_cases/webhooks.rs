@@ -0,0 +1,807 @@
+//! Outbound webhooks: owner-configured endpoints that are notified when a
+//! namespace's site is rebuilt, an audience's gates change, or an object is
+//! deleted.
+//!
+//! Services that raise events are given a [`WebhookDispatcher`] through their
+//! `with_webhooks` builder. For every webhook subscribed to the event it
+//! records a pending delivery in the [`WebhookStore`] and enqueues a
+//! [`WEBHOOK_DELIVERY_JOB`] on the adapter's [`JobSink`]; running that job
+//! calls [`WebhookDeliveryService::deliver`]. Failed attempts are rescheduled
+//! with backoff and picked up again by [`WebhookDispatcher::requeue_due`],
+//! which adapters run periodically. Everything a delivery went through is
+//! visible in the namespace's delivery log.
+//!
+//! Deliveries are signed with the proxy HMAC scheme
+//! ([`sign_proxy_request`]), keyed by the webhook's secret, with the
+//! namespace id in place of the user id. Receivers check `X-Diaryx-Signature`
+//! against `X-Diaryx-Timestamp`, `X-Diaryx-Namespace` and the raw body with
+//! [`verify_proxy_signature`](crate::proxy::verify_proxy_signature).
+
+use crate::domain::{
+    NamespaceRole, WebhookDeliveryInfo, WebhookDeliveryStatus, WebhookEvent, WebhookInfo,
+};
+use crate::outbound::{is_internal_host, url_host};
+use crate::ports::{JobSink, NamespaceStore, ServerCoreError, WebhookStore, WebhookTransport};
+use crate::proxy::sign_proxy_request;
+use crate::use_cases::members::require_namespace_role;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+
+/// [`JobSink`] kind for a webhook delivery attempt. The payload is a
+/// [`WebhookDeliveryJob`].
+pub const WEBHOOK_DELIVERY_JOB: &str = "webhook.deliver";
+
+/// Maximum number of webhooks a namespace can have at once.
+pub const MAX_WEBHOOKS_PER_NAMESPACE: usize = 10;
+
+/// Attempts made before a delivery is marked failed.
+pub const MAX_DELIVERY_ATTEMPTS: u32 = RETRY_BACKOFF_SECS.len() as u32 + 1;
+
+/// Delay before each retry, indexed by the number of attempts already made
+/// minus one: 1 minute, 5 minutes, 30 minutes, 2 hours, 6 hours.
+const RETRY_BACKOFF_SECS: [i64; 5] = [60, 300, 1_800, 7_200, 21_600];
+
+/// How long an attempt in progress holds its delivery. The attempt pushes
+/// `next_attempt_at` this far out before sending, so a retry sweep running
+/// at the same time doesn't send it twice.
+const DELIVERY_LEASE_SECS: i64 = 120;
+
+/// Deliveries returned by the delivery log when no limit is given.
+pub const DEFAULT_DELIVERY_LOG_LIMIT: u32 = 50;
+const MAX_DELIVERY_LOG_LIMIT: u32 = 200;
+
+/// Due deliveries re-enqueued per [`WebhookDispatcher::requeue_due`] call.
+const REQUEUE_BATCH: u32 = 100;
+
+const MAX_URL_LEN: usize = 2048;
+const MAX_ERROR_LEN: usize = 500;
+
+fn generate_webhook_secret() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn validate_webhook_url(url: &str, allow_private: bool) -> Result<(), ServerCoreError> {
+    let host = url_host(url)
+        .ok_or_else(|| ServerCoreError::invalid_input("Webhook URL must be http(s)"))?;
+    if host.is_empty() || url.len() > MAX_URL_LEN || url.chars().any(char::is_whitespace) {
+        return Err(ServerCoreError::invalid_input("Invalid webhook URL"));
+    }
+    if !allow_private && is_internal_host(host) {
+        return Err(ServerCoreError::invalid_input(
+            "Webhook URL must not point at this server or a private network",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Events to deliver. Omit to subscribe to every event.
+    #[serde(default)]
+    pub events: Option<Vec<WebhookEvent>>,
+}
+
+/// A newly created webhook. The signing secret is only ever returned here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedWebhook {
+    #[serde(flatten)]
+    pub webhook: WebhookInfo,
+    pub secret: String,
+}
+
+/// Query string of the delivery log endpoint.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DeliveryLogQuery {
+    #[serde(default)]
+    pub webhook_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Payload of a [`WEBHOOK_DELIVERY_JOB`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookDeliveryJob {
+    pub delivery_id: String,
+}
+
+impl WebhookDeliveryJob {
+    pub fn from_payload(payload: &Value) -> Result<Self, ServerCoreError> {
+        serde_json::from_value(payload.clone())
+            .map_err(|e| ServerCoreError::invalid_input(format!("Bad webhook job: {e}")))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Management (owner-only)
+// ---------------------------------------------------------------------------
+
+/// Create, list and delete a namespace's webhooks, and read its delivery log.
+/// Webhooks can carry namespace events off-site, so only the owner manages
+/// them. URLs on this machine or a private network are refused unless
+/// [`with_private_targets`](Self::with_private_targets) allows them.
+pub struct WebhookService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    webhook_store: &'a dyn WebhookStore,
+    allow_private_targets: bool,
+}
+
+impl<'a> WebhookService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        webhook_store: &'a dyn WebhookStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            webhook_store,
+            allow_private_targets: false,
+        }
+    }
+
+    /// Accept webhook URLs on loopback and private networks, for servers
+    /// whose owners deliberately notify services on their own network.
+    pub fn with_private_targets(mut self, allowed: bool) -> Self {
+        self.allow_private_targets = allowed;
+        self
+    }
+
+    async fn require_owner(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            None,
+            namespace_id,
+            caller_user_id,
+            NamespaceRole::Owner,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn create(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        request: CreateWebhookRequest,
+    ) -> Result<CreatedWebhook, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+
+        let url = request.url.trim().to_string();
+        validate_webhook_url(&url, self.allow_private_targets)?;
+
+        let mut events = request.events.unwrap_or_else(|| WebhookEvent::ALL.to_vec());
+        events.sort();
+        events.dedup();
+        if events.is_empty() {
+            return Err(ServerCoreError::invalid_input(
+                "A webhook needs at least one event",
+            ));
+        }
+
+        let existing = self.webhook_store.list_webhooks(namespace_id).await?;
+        if existing.len() >= MAX_WEBHOOKS_PER_NAMESPACE {
+            return Err(ServerCoreError::conflict(format!(
+                "A namespace can have at most {MAX_WEBHOOKS_PER_NAMESPACE} webhooks"
+            )));
+        }
+
+        let webhook = WebhookInfo {
+            id: Uuid::new_v4().to_string(),
+            namespace_id: namespace_id.to_string(),
+            url,
+            events,
+            created_by: caller_user_id.to_string(),
+            created_at: Utc::now().timestamp(),
+        };
+        let secret = generate_webhook_secret();
+        self.webhook_store.create_webhook(&webhook, &secret).await?;
+        Ok(CreatedWebhook { webhook, secret })
+    }
+
+    /// List the namespace's webhooks, oldest first.
+    pub async fn list(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<Vec<WebhookInfo>, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        self.webhook_store.list_webhooks(namespace_id).await
+    }
+
+    /// Delete a webhook along with its delivery log. Pending deliveries are
+    /// dropped.
+    pub async fn delete(
+        &self,
+        namespace_id: &str,
+        webhook_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        if !self
+            .webhook_store
+            .delete_webhook(namespace_id, webhook_id)
+            .await?
+        {
+            return Err(ServerCoreError::not_found("Webhook not found"));
+        }
+        Ok(())
+    }
+
+    /// The namespace's delivery log, newest first.
+    pub async fn deliveries(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        query: &DeliveryLogQuery,
+    ) -> Result<Vec<WebhookDeliveryInfo>, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_DELIVERY_LOG_LIMIT)
+            .clamp(1, MAX_DELIVERY_LOG_LIMIT);
+        self.webhook_store
+            .list_deliveries(namespace_id, query.webhook_id.as_deref(), limit)
+            .await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Dispatch
+// ---------------------------------------------------------------------------
+
+/// Turns namespace events into queued deliveries. Cheap to copy; services
+/// hold one when the adapter has opted in to webhooks.
+#[derive(Clone, Copy)]
+pub struct WebhookDispatcher<'a> {
+    webhook_store: &'a dyn WebhookStore,
+    job_sink: &'a dyn JobSink,
+}
+
+impl<'a> WebhookDispatcher<'a> {
+    pub fn new(webhook_store: &'a dyn WebhookStore, job_sink: &'a dyn JobSink) -> Self {
+        Self {
+            webhook_store,
+            job_sink,
+        }
+    }
+
+    /// Queue a delivery of `event` to every webhook on the namespace that
+    /// subscribes to it. `data` becomes the payload's `data` field.
+    ///
+    /// Best-effort — errors are logged but do not fail the caller, whose
+    /// change has already been made.
+    pub async fn emit(&self, namespace_id: &str, event: WebhookEvent, data: Value) {
+        if let Err(e) = self.try_emit(namespace_id, event, data).await {
+            warn!(
+                "Failed to queue {} webhooks ({}): {}",
+                event.as_str(),
+                namespace_id,
+                e
+            );
+        }
+    }
+
+    async fn try_emit(
+        &self,
+        namespace_id: &str,
+        event: WebhookEvent,
+        data: Value,
+    ) -> Result<(), ServerCoreError> {
+        let webhooks = self.webhook_store.list_webhooks(namespace_id).await?;
+        let now = Utc::now().timestamp();
+        for webhook in webhooks.iter().filter(|w| w.subscribes_to(event)) {
+            let id = Uuid::new_v4().to_string();
+            let payload = serde_json::json!({
+                "id": id,
+                "event": event,
+                "namespace_id": namespace_id,
+                "created_at": now,
+                "data": data,
+            });
+            let delivery = WebhookDeliveryInfo {
+                id,
+                webhook_id: webhook.id.clone(),
+                namespace_id: namespace_id.to_string(),
+                event,
+                payload: payload.to_string(),
+                status: WebhookDeliveryStatus::Pending,
+                attempts: 0,
+                next_attempt_at: Some(now),
+                response_status: None,
+                last_error: None,
+                created_at: now,
+                updated_at: now,
+            };
+            self.webhook_store.upsert_delivery(&delivery).await?;
+            // The delivery is recorded, so a failed enqueue only delays it
+            // until the next `requeue_due` sweep.
+            if let Err(e) = self.enqueue(&delivery.id).await {
+                warn!("Failed to enqueue webhook delivery {}: {}", delivery.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn enqueue(&self, delivery_id: &str) -> Result<(), ServerCoreError> {
+        let payload = serde_json::to_value(WebhookDeliveryJob {
+            delivery_id: delivery_id.to_string(),
+        })
+        .map_err(|e| ServerCoreError::internal(e.to_string()))?;
+        self.job_sink.enqueue(WEBHOOK_DELIVERY_JOB, payload).await
+    }
+
+    /// Enqueue pending deliveries whose next attempt is due at `now`: retries,
+    /// and first attempts whose enqueue failed. Returns how many were queued.
+    pub async fn requeue_due(&self, now: i64) -> Result<usize, ServerCoreError> {
+        let due = self
+            .webhook_store
+            .list_due_deliveries(now, REQUEUE_BATCH)
+            .await?;
+        for delivery in &due {
+            self.enqueue(&delivery.id).await?;
+        }
+        Ok(due.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Delivery
+// ---------------------------------------------------------------------------
+
+/// Runs delivery attempts; adapters call [`deliver`](Self::deliver) from the
+/// job a [`WebhookDispatcher`] enqueued.
+pub struct WebhookDeliveryService<'a> {
+    webhook_store: &'a dyn WebhookStore,
+    transport: &'a dyn WebhookTransport,
+}
+
+impl<'a> WebhookDeliveryService<'a> {
+    pub fn new(webhook_store: &'a dyn WebhookStore, transport: &'a dyn WebhookTransport) -> Self {
+        Self {
+            webhook_store,
+            transport,
+        }
+    }
+
+    /// Attempt a delivery if it is pending and due at `now`, and record the
+    /// outcome: success on a 2xx response, otherwise a retry after backoff,
+    /// or failure once [`MAX_DELIVERY_ATTEMPTS`] have been made.
+    ///
+    /// Returns the delivery as it now stands (unchanged if it wasn't due), or
+    /// `None` if it or its webhook has been deleted.
+    pub async fn deliver(
+        &self,
+        delivery_id: &str,
+        now: i64,
+    ) -> Result<Option<WebhookDeliveryInfo>, ServerCoreError> {
+        let Some(mut delivery) = self.webhook_store.get_delivery(delivery_id).await? else {
+            return Ok(None);
+        };
+        if delivery.status != WebhookDeliveryStatus::Pending
+            || delivery.next_attempt_at.is_some_and(|at| at > now)
+        {
+            return Ok(Some(delivery));
+        }
+        let webhook = self.webhook_store.get_webhook(&delivery.webhook_id).await?;
+        let secret = self
+            .webhook_store
+            .get_webhook_secret(&delivery.webhook_id)
+            .await?;
+        let (Some(webhook), Some(secret)) = (webhook, secret) else {
+            return Ok(None);
+        };
+
+        delivery.next_attempt_at = Some(now + DELIVERY_LEASE_SECS);
+        delivery.updated_at = now;
+        self.webhook_store.upsert_delivery(&delivery).await?;
+
+        let headers = signed_headers(&delivery, secret.as_bytes(), now);
+        let result = self
+            .transport
+            .post(&webhook.url, &headers, delivery.payload.as_bytes())
+            .await;
+
+        delivery.attempts += 1;
+        match result {
+            Ok(status) if (200..300).contains(&status) => {
+                delivery.status = WebhookDeliveryStatus::Succeeded;
+                delivery.next_attempt_at = None;
+                delivery.response_status = Some(status);
+                delivery.last_error = None;
+            }
+            Ok(status) => {
+                delivery.response_status = Some(status);
+                delivery.last_error = Some(format!("Endpoint responded with HTTP {status}"));
+                schedule_retry(&mut delivery, now);
+            }
+            Err(e) => {
+                delivery.response_status = None;
+                delivery.last_error = Some(e.to_string().chars().take(MAX_ERROR_LEN).collect());
+                schedule_retry(&mut delivery, now);
+            }
+        }
+        delivery.updated_at = now;
+        self.webhook_store.upsert_delivery(&delivery).await?;
+        Ok(Some(delivery))
+    }
+}
+
+/// After a failed attempt: schedule the next one, or give up.
+fn schedule_retry(delivery: &mut WebhookDeliveryInfo, now: i64) {
+    match RETRY_BACKOFF_SECS.get(delivery.attempts.saturating_sub(1) as usize) {
+        Some(delay) => delivery.next_attempt_at = Some(now + delay),
+        None => {
+            delivery.status = WebhookDeliveryStatus::Failed;
+            delivery.next_attempt_at = None;
+        }
+    }
+}
+
+/// Headers sent with every attempt of a delivery.
+fn signed_headers(
+    delivery: &WebhookDeliveryInfo,
+    secret: &[u8],
+    timestamp: i64,
+) -> Vec<(String, String)> {
+    let signature = sign_proxy_request(
+        secret,
+        timestamp as u64,
+        &delivery.namespace_id,
+        delivery.payload.as_bytes(),
+    );
+    vec![
+        ("Content-Type".into(), "application/json".into()),
+        ("X-Diaryx-Event".into(), delivery.event.as_str().into()),
+        ("X-Diaryx-Delivery".into(), delivery.id.clone()),
+        ("X-Diaryx-Namespace".into(), delivery.namespace_id.clone()),
+        ("X-Diaryx-Timestamp".into(), timestamp.to_string()),
+        ("X-Diaryx-Signature".into(), signature),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CreateWebhookRequest, DeliveryLogQuery, MAX_DELIVERY_ATTEMPTS, WEBHOOK_DELIVERY_JOB,
+        WebhookDeliveryJob, WebhookDeliveryService, WebhookDispatcher, WebhookService,
+    };
+    use crate::domain::{WebhookDeliveryStatus, WebhookEvent};
+    use crate::ports::{JobSink, NamespaceStore, ServerCoreError, WebhookStore, WebhookTransport};
+    use crate::proxy::verify_proxy_signature;
+    use crate::testing::{
+        InMemoryBlobStore, InMemoryNamespaceStore, InMemoryObjectMetaStore, InMemoryWebhookStore,
+    };
+    use crate::use_cases::objects::ObjectService;
+    use serde_json::Value;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingJobSink {
+        jobs: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl RecordingJobSink {
+        fn delivery_ids(&self) -> Vec<String> {
+            self.jobs
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(kind, payload)| {
+                    assert_eq!(kind, WEBHOOK_DELIVERY_JOB);
+                    WebhookDeliveryJob::from_payload(payload)
+                        .unwrap()
+                        .delivery_id
+                })
+                .collect()
+        }
+    }
+
+    crate::cfg_async_trait! {
+    impl JobSink for RecordingJobSink {
+        async fn enqueue(&self, kind: &str, payload: Value) -> Result<(), ServerCoreError> {
+            self.jobs.lock().unwrap().push((kind.to_string(), payload));
+            Ok(())
+        }
+    }
+    }
+
+    /// URL, headers and body of one request.
+    type RecordedRequest = (String, Vec<(String, String)>, Vec<u8>);
+
+    /// Answers every request with `status`, or fails if it is `None`.
+    struct RecordingTransport {
+        status: Mutex<Option<u16>>,
+        requests: Mutex<Vec<RecordedRequest>>,
+    }
+
+    impl RecordingTransport {
+        fn answering(status: Option<u16>) -> Self {
+            Self {
+                status: Mutex::new(status),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    crate::cfg_async_trait! {
+    impl WebhookTransport for RecordingTransport {
+        async fn post(
+            &self,
+            url: &str,
+            headers: &[(String, String)],
+            body: &[u8],
+        ) -> Result<u16, ServerCoreError> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((url.to_string(), headers.to_vec(), body.to_vec()));
+            let status = *self.status.lock().unwrap();
+            status.ok_or_else(|| ServerCoreError::unavailable("connection refused"))
+        }
+    }
+    }
+
+    fn header<'h>(headers: &'h [(String, String)], name: &str) -> &'h str {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_else(|| panic!("missing header {name}"))
+    }
+
+    async fn namespace_store() -> InMemoryNamespaceStore {
+        let namespaces = InMemoryNamespaceStore::new();
+        namespaces
+            .create_namespace("family", "alice", None)
+            .await
+            .unwrap();
+        namespaces
+    }
+
+    fn hook(url: &str, events: Option<Vec<WebhookEvent>>) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            url: url.to_string(),
+            events,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_manages_webhooks_and_urls_are_validated() {
+        let namespaces = namespace_store().await;
+        let webhooks = InMemoryWebhookStore::new();
+        let service = WebhookService::new(&namespaces, &webhooks);
+
+        let err = service
+            .create("family", "bob", hook("https://bot.example.com/hook", None))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+
+        for bad in ["ftp://example.com", "https://", "https://exa mple.com/"] {
+            let err = service
+                .create("family", "alice", hook(bad, None))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ServerCoreError::InvalidInput(_)), "{bad}");
+        }
+        let err = service
+            .create(
+                "family",
+                "alice",
+                hook("https://bot.example.com/hook", Some(vec![])),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        let created = service
+            .create(
+                "family",
+                "alice",
+                hook("https://bot.example.com/hook", None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.webhook.events, WebhookEvent::ALL.to_vec());
+        assert_eq!(created.secret.len(), 64);
+        assert_eq!(
+            webhooks
+                .get_webhook_secret(&created.webhook.id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(created.secret.as_str())
+        );
+        assert_eq!(
+            service.list("family", "alice").await.unwrap(),
+            vec![created.webhook.clone()]
+        );
+
+        service
+            .delete("family", &created.webhook.id, "alice")
+            .await
+            .unwrap();
+        let err = service
+            .delete("family", &created.webhook.id, "alice")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn internal_webhook_urls_are_refused_unless_allowed() {
+        let namespaces = namespace_store().await;
+        let webhooks = InMemoryWebhookStore::new();
+        let service = WebhookService::new(&namespaces, &webhooks);
+
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "https://10.0.0.5/hook",
+            "http://[::1]/hook",
+            "http://user@192.168.1.1/hook",
+            "http://metadata.google.internal/",
+        ] {
+            let err = service
+                .create("family", "alice", hook(url, None))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ServerCoreError::InvalidInput(_)), "{url}");
+        }
+        assert!(webhooks.list_webhooks("family").await.unwrap().is_empty());
+
+        let lan = WebhookService::new(&namespaces, &webhooks).with_private_targets(true);
+        lan.create("family", "alice", hook("http://192.168.1.20/hook", None))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn object_deletion_is_delivered_signed_to_subscribed_webhooks() {
+        let namespaces = namespace_store().await;
+        let meta = InMemoryObjectMetaStore::new();
+        let blobs = InMemoryBlobStore::new();
+        let webhooks = InMemoryWebhookStore::new();
+        let jobs = RecordingJobSink::default();
+        let service = WebhookService::new(&namespaces, &webhooks);
+
+        let subscribed = service
+            .create(
+                "family",
+                "alice",
+                hook(
+                    "https://bot.example.com/hook",
+                    Some(vec![WebhookEvent::ObjectDeleted]),
+                ),
+            )
+            .await
+            .unwrap();
+        service
+            .create(
+                "family",
+                "alice",
+                hook(
+                    "https://backup.example.com/hook",
+                    Some(vec![WebhookEvent::BuildCompleted]),
+                ),
+            )
+            .await
+            .unwrap();
+
+        let objects = ObjectService::new(&namespaces, &meta, &blobs)
+            .with_webhooks(WebhookDispatcher::new(&webhooks, &jobs));
+        objects
+            .put("family", "notes.md", "text/markdown", b"hi", None, "alice")
+            .await
+            .unwrap();
+        objects.delete("family", "notes.md", "alice").await.unwrap();
+
+        let queued = jobs.delivery_ids();
+        assert_eq!(queued.len(), 1);
+
+        let now = chrono::Utc::now().timestamp();
+        let transport = RecordingTransport::answering(Some(204));
+        let delivered = WebhookDeliveryService::new(&webhooks, &transport)
+            .deliver(&queued[0], now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(delivered.status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(delivered.attempts, 1);
+        assert_eq!(delivered.response_status, Some(204));
+        assert_eq!(delivered.webhook_id, subscribed.webhook.id);
+
+        let requests = transport.requests.lock().unwrap().clone();
+        let (url, headers, body) = &requests[0];
+        assert_eq!(url, "https://bot.example.com/hook");
+        assert_eq!(header(headers, "X-Diaryx-Event"), "object_deleted");
+        assert_eq!(header(headers, "X-Diaryx-Timestamp"), now.to_string());
+        assert!(verify_proxy_signature(
+            subscribed.secret.as_bytes(),
+            now as u64,
+            header(headers, "X-Diaryx-Namespace"),
+            body,
+            header(headers, "X-Diaryx-Signature"),
+        ));
+        let payload: Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(payload["event"], "object_deleted");
+        assert_eq!(payload["data"]["key"], "notes.md");
+        assert_eq!(payload["data"]["deleted_by"], "alice");
+
+        let log = service
+            .deliveries("family", "alice", &DeliveryLogQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(log, vec![delivered]);
+    }
+
+    #[tokio::test]
+    async fn failed_deliveries_back_off_and_eventually_give_up() {
+        let namespaces = namespace_store().await;
+        let webhooks = InMemoryWebhookStore::new();
+        let jobs = RecordingJobSink::default();
+        WebhookService::new(&namespaces, &webhooks)
+            .create(
+                "family",
+                "alice",
+                hook("https://bot.example.com/hook", None),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = WebhookDispatcher::new(&webhooks, &jobs);
+        dispatcher
+            .emit(
+                "family",
+                WebhookEvent::BuildCompleted,
+                serde_json::json!({ "pages_rendered": 3 }),
+            )
+            .await;
+        let id = jobs.delivery_ids().remove(0);
+
+        let transport = RecordingTransport::answering(Some(500));
+        let deliveries = WebhookDeliveryService::new(&webhooks, &transport);
+        let mut now = chrono::Utc::now().timestamp();
+
+        let first = deliveries.deliver(&id, now).await.unwrap().unwrap();
+        assert_eq!(first.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(first.response_status, Some(500));
+        assert_eq!(first.next_attempt_at, Some(now + 60));
+
+        // Not due yet: neither an early job nor the sweep sends it again.
+        assert_eq!(
+            deliveries.deliver(&id, now + 59).await.unwrap(),
+            Some(first)
+        );
+        assert_eq!(dispatcher.requeue_due(now + 59).await.unwrap(), 0);
+        assert_eq!(transport.requests.lock().unwrap().len(), 1);
+
+        *transport.status.lock().unwrap() = None;
+        let mut last = None;
+        for _ in 1..MAX_DELIVERY_ATTEMPTS {
+            now = webhooks
+                .get_delivery(&id)
+                .await
+                .unwrap()
+                .unwrap()
+                .next_attempt_at
+                .unwrap();
+            assert_eq!(dispatcher.requeue_due(now).await.unwrap(), 1);
+            last = deliveries.deliver(&id, now).await.unwrap();
+        }
+        let last = last.unwrap();
+        assert_eq!(last.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(last.attempts, MAX_DELIVERY_ATTEMPTS);
+        assert_eq!(last.next_attempt_at, None);
+        assert_eq!(last.response_status, None);
+        assert_eq!(last.last_error.as_deref(), Some("connection refused"));
+        assert_eq!(dispatcher.requeue_due(i64::MAX).await.unwrap(), 0);
+    }
+}
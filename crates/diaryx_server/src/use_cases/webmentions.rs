@@ -0,0 +1,727 @@
+//! Webmention receiving, verification and moderation for published pages.
+//!
+//! Pages in public audiences advertise `{api}/namespaces/{id}/webmention`
+//! as their endpoint (see [`webmention_endpoint`]). [`WebmentionReceiver`]
+//! accepts a `source`/`target` pair there once `target` resolves to one of
+//! those pages, records it as unverified and enqueues a
+//! [`WEBMENTION_VERIFY_JOB`] on the adapter's [`JobSink`]. Running the job
+//! calls [`WebmentionVerifier::verify`], which fetches the source and checks
+//! that it really links to the target. Verified mentions wait for the owner
+//! to approve or reject them through [`WebmentionService`];
+//! `RenderService` shows approved ones under their page on the next build.
+//!
+//! Sending the same pair again re-runs verification but keeps the owner's
+//! decision, so an edited reply stays approved and a rejected one stays
+//! hidden. A source that answers `410 Gone` removes its mention.
+
+use std::collections::HashMap;
+
+use crate::domain::{ArkIndexEntry, NamespaceRole, WebmentionInfo, WebmentionStatus};
+use crate::outbound::{is_internal_host, url_host};
+use crate::ports::{
+    ArkIndexStore, JobSink, NamespaceStore, ServerCoreError, WebmentionFetcher, WebmentionStore,
+};
+use crate::use_cases::ark::ARK_WORKSPACE_INDEX;
+use crate::use_cases::members::require_namespace_role;
+use chrono::Utc;
+use diaryx_render::percent_decode;
+use diaryx_render::types::PageMention;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+
+/// [`JobSink`] kind for verifying a received mention. The payload is a
+/// [`WebmentionVerifyJob`].
+pub const WEBMENTION_VERIFY_JOB: &str = "webmention.verify";
+
+/// Mentions a namespace can hold awaiting verification or moderation. New
+/// ones are refused past this until the owner works through the queue.
+pub const MAX_QUEUED_WEBMENTIONS: usize = 500;
+
+/// Most of a source page read when verifying it.
+pub const MAX_SOURCE_BYTES: usize = 1024 * 1024;
+
+/// Mentions returned by the moderation list when no limit is given.
+pub const DEFAULT_WEBMENTION_LIST_LIMIT: u32 = 50;
+const MAX_WEBMENTION_LIST_LIMIT: u32 = 200;
+
+/// Approved mentions read per namespace build.
+const MAX_RENDERED_MENTIONS: u32 = 10_000;
+
+const MAX_URL_LEN: usize = 2048;
+const MAX_TITLE_CHARS: usize = 200;
+
+/// The endpoint pages of `namespace_id` advertise, under the API base URL
+/// (e.g. `https://sync.example.com/api`).
+pub fn webmention_endpoint(api_base_url: &str, namespace_id: &str) -> String {
+    format!(
+        "{}/namespaces/{}/webmention",
+        api_base_url.trim_end_matches('/'),
+        namespace_id
+    )
+}
+
+/// Payload of a [`WEBMENTION_VERIFY_JOB`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebmentionVerifyJob {
+    pub webmention_id: String,
+}
+
+impl WebmentionVerifyJob {
+    pub fn from_payload(payload: &Value) -> Result<Self, ServerCoreError> {
+        serde_json::from_value(payload.clone())
+            .map_err(|e| ServerCoreError::invalid_input(format!("Bad webmention job: {e}")))
+    }
+}
+
+/// Form body of the receiving endpoint.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReceiveWebmentionRequest {
+    pub source: String,
+    pub target: String,
+}
+
+/// Query string of the moderation list.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct WebmentionListQuery {
+    #[serde(default)]
+    pub status: Option<WebmentionStatus>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+fn validate_mention_url(url: &str, field: &str) -> Result<(), ServerCoreError> {
+    let host = url_host(url)
+        .ok_or_else(|| ServerCoreError::invalid_input(format!("{field} must be an http(s) URL")))?;
+    if host.is_empty() || url.len() > MAX_URL_LEN || url.chars().any(char::is_whitespace) {
+        return Err(ServerCoreError::invalid_input(format!(
+            "Invalid {field} URL"
+        )));
+    }
+    Ok(())
+}
+
+/// The decoded path of an http(s) URL, without the leading slash. A
+/// directory path gets `index.html` appended.
+fn target_path(url: &str) -> String {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+    let path = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .split_once('/')
+        .map(|(_, path)| path)
+        .unwrap_or_default();
+    let mut path = percent_decode(path);
+    if path.is_empty() || path.ends_with('/') {
+        path.push_str("index.html");
+    }
+    path
+}
+
+/// Whether `path` is `key` or ends in `/{key}`.
+fn path_names(path: &str, key: &str) -> bool {
+    path == key
+        || path
+            .strip_suffix(key)
+            .is_some_and(|prefix| prefix.ends_with('/'))
+}
+
+/// Pick the page a target URL points at. Published pages are reachable at
+/// several URLs (custom domain, subdomain, `/sites/{id}/`, the public object
+/// route), so the target's path is matched by suffix: first against full
+/// object keys (`{audience}/{page}.html`), then against keys within their
+/// audience; the longest match wins.
+fn resolve_target<'r>(target: &str, pages: &[&'r ArkIndexEntry]) -> Option<&'r ArkIndexEntry> {
+    let path = target_path(target);
+    let by_key = pages
+        .iter()
+        .filter(|row| path_names(&path, &row.object_key))
+        .max_by_key(|row| row.object_key.len());
+    if let Some(row) = by_key {
+        return Some(row);
+    }
+    pages
+        .iter()
+        .filter_map(|row| {
+            let audience = row.audience.as_deref()?;
+            let dest = row.object_key.strip_prefix(&format!("{audience}/"))?;
+            path_names(&path, dest).then_some((dest.len(), *row))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, row)| row)
+}
+
+/// The source page's `<title>`, trimmed and capped.
+fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title: String = html[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    (!title.is_empty()).then_some(title)
+}
+
+/// Whether the source body links to `target`, as an `href` or, for
+/// non-HTML sources, anywhere in the text.
+fn source_links_to(body: &str, target: &str) -> bool {
+    body.contains(target) || body.contains(&target.replace('&', "&amp;"))
+}
+
+// ---------------------------------------------------------------------------
+// Receiving (public)
+// ---------------------------------------------------------------------------
+
+/// Accepts mentions for pages in a namespace's public audiences.
+pub struct WebmentionReceiver<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    ark_index: &'a dyn ArkIndexStore,
+    webmention_store: &'a dyn WebmentionStore,
+    job_sink: &'a dyn JobSink,
+}
+
+impl<'a> WebmentionReceiver<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        ark_index: &'a dyn ArkIndexStore,
+        webmention_store: &'a dyn WebmentionStore,
+        job_sink: &'a dyn JobSink,
+    ) -> Self {
+        Self {
+            namespace_store,
+            ark_index,
+            webmention_store,
+            job_sink,
+        }
+    }
+
+    /// Record a mention of `target` by `source` and queue its verification.
+    /// Fails with `InvalidInput` when the target isn't a public page of the
+    /// namespace.
+    pub async fn receive(
+        &self,
+        namespace_id: &str,
+        request: &ReceiveWebmentionRequest,
+    ) -> Result<WebmentionInfo, ServerCoreError> {
+        let source = request.source.trim();
+        let target = request.target.trim();
+        validate_mention_url(source, "source")?;
+        validate_mention_url(target, "target")?;
+        if url_host(source).is_some_and(is_internal_host) {
+            return Err(ServerCoreError::invalid_input(
+                "source must be a public URL",
+            ));
+        }
+        if source == target {
+            return Err(ServerCoreError::invalid_input(
+                "source and target must differ",
+            ));
+        }
+        self.namespace_store
+            .get_namespace(namespace_id)
+            .await?
+            .ok_or_else(|| ServerCoreError::not_found("Namespace not found"))?;
+
+        let object_key = self.resolve_public_page(namespace_id, target).await?;
+        let now = Utc::now().timestamp();
+
+        let mention = match self
+            .webmention_store
+            .find_webmention(namespace_id, source, target)
+            .await?
+        {
+            Some(existing) => WebmentionInfo {
+                object_key,
+                updated_at: now,
+                ..existing
+            },
+            None => {
+                let mut queued = self
+                    .webmention_store
+                    .list_webmentions(
+                        namespace_id,
+                        Some(WebmentionStatus::Unverified),
+                        MAX_QUEUED_WEBMENTIONS as u32,
+                    )
+                    .await?
+                    .len();
+                queued += self
+                    .webmention_store
+                    .list_webmentions(
+                        namespace_id,
+                        Some(WebmentionStatus::Pending),
+                        MAX_QUEUED_WEBMENTIONS as u32,
+                    )
+                    .await?
+                    .len();
+                if queued >= MAX_QUEUED_WEBMENTIONS {
+                    return Err(ServerCoreError::rate_limited(
+                        "Too many mentions awaiting moderation",
+                    ));
+                }
+                WebmentionInfo {
+                    id: Uuid::new_v4().to_string(),
+                    namespace_id: namespace_id.to_string(),
+                    source: source.to_string(),
+                    target: target.to_string(),
+                    object_key,
+                    status: WebmentionStatus::Unverified,
+                    source_title: None,
+                    created_at: now,
+                    updated_at: now,
+                }
+            }
+        };
+        self.webmention_store.upsert_webmention(&mention).await?;
+
+        let job = serde_json::json!(WebmentionVerifyJob {
+            webmention_id: mention.id.clone(),
+        });
+        if let Err(e) = self.job_sink.enqueue(WEBMENTION_VERIFY_JOB, job).await {
+            warn!(
+                "Failed to enqueue verification of webmention {}: {}",
+                mention.id, e
+            );
+        }
+        Ok(mention)
+    }
+
+    async fn resolve_public_page(
+        &self,
+        namespace_id: &str,
+        target: &str,
+    ) -> Result<String, ServerCoreError> {
+        let rows = self.ark_index.list_ark_entries(namespace_id).await?;
+        let mut public: HashMap<String, bool> = HashMap::new();
+        let mut pages = Vec::new();
+        for row in &rows {
+            if row.file_ark == ARK_WORKSPACE_INDEX {
+                continue;
+            }
+            let Some(audience) = row.audience.as_deref() else {
+                continue;
+            };
+            if !public.contains_key(audience) {
+                let is_public = self
+                    .namespace_store
+                    .get_audience(namespace_id, audience)
+                    .await?
+                    .is_some_and(|info| info.is_public());
+                public.insert(audience.to_string(), is_public);
+            }
+            if public[audience] {
+                pages.push(row);
+            }
+        }
+        resolve_target(target, &pages)
+            .map(|row| row.object_key.clone())
+            .ok_or_else(|| {
+                ServerCoreError::invalid_input("target is not a public page of this namespace")
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Verification (job)
+// ---------------------------------------------------------------------------
+
+/// Checks that a mention's source links to its target.
+pub struct WebmentionVerifier<'a> {
+    webmention_store: &'a dyn WebmentionStore,
+    fetcher: &'a dyn WebmentionFetcher,
+}
+
+impl<'a> WebmentionVerifier<'a> {
+    pub fn new(
+        webmention_store: &'a dyn WebmentionStore,
+        fetcher: &'a dyn WebmentionFetcher,
+    ) -> Self {
+        Self {
+            webmention_store,
+            fetcher,
+        }
+    }
+
+    /// Fetch the source and settle the mention: pending moderation when it
+    /// links to the target (approved and rejected ones keep their status),
+    /// invalid when it doesn't or can't be fetched, deleted on `410 Gone`.
+    pub async fn verify(&self, webmention_id: &str, now: i64) -> Result<(), ServerCoreError> {
+        let Some(mut mention) = self.webmention_store.get_webmention(webmention_id).await? else {
+            return Ok(());
+        };
+
+        let linked = match self.fetcher.fetch(&mention.source, MAX_SOURCE_BYTES).await {
+            Ok((410, _)) => {
+                self.webmention_store
+                    .delete_webmention(&mention.namespace_id, &mention.id)
+                    .await?;
+                return Ok(());
+            }
+            Ok((status, body)) if (200..300).contains(&status) => {
+                if source_links_to(&body, &mention.target) {
+                    mention.source_title = extract_title(&body);
+                    true
+                } else {
+                    false
+                }
+            }
+            Ok(_) => false,
+            Err(e) => {
+                warn!(
+                    "Fetching webmention source {} failed: {}",
+                    mention.source, e
+                );
+                false
+            }
+        };
+
+        mention.status = match (linked, mention.status) {
+            (true, WebmentionStatus::Unverified | WebmentionStatus::Invalid) => {
+                WebmentionStatus::Pending
+            }
+            (true, status) => status,
+            (false, WebmentionStatus::Rejected) => WebmentionStatus::Rejected,
+            (false, _) => WebmentionStatus::Invalid,
+        };
+        mention.updated_at = now;
+        self.webmention_store.upsert_webmention(&mention).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Moderation (owner-only)
+// ---------------------------------------------------------------------------
+
+/// List, approve, reject and delete a namespace's mentions. What appears on
+/// the published site is the owner's call, so only the owner moderates.
+pub struct WebmentionService<'a> {
+    namespace_store: &'a dyn NamespaceStore,
+    webmention_store: &'a dyn WebmentionStore,
+}
+
+impl<'a> WebmentionService<'a> {
+    pub fn new(
+        namespace_store: &'a dyn NamespaceStore,
+        webmention_store: &'a dyn WebmentionStore,
+    ) -> Self {
+        Self {
+            namespace_store,
+            webmention_store,
+        }
+    }
+
+    async fn require_owner(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        require_namespace_role(
+            self.namespace_store,
+            None,
+            namespace_id,
+            caller_user_id,
+            NamespaceRole::Owner,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// The namespace's mentions, newest first.
+    pub async fn list(
+        &self,
+        namespace_id: &str,
+        caller_user_id: &str,
+        query: &WebmentionListQuery,
+    ) -> Result<Vec<WebmentionInfo>, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_WEBMENTION_LIST_LIMIT)
+            .clamp(1, MAX_WEBMENTION_LIST_LIMIT);
+        self.webmention_store
+            .list_webmentions(namespace_id, query.status, limit)
+            .await
+    }
+
+    /// Approve or reject a verified mention. Unverified and invalid mentions
+    /// can't be approved — there's no link to show.
+    pub async fn moderate(
+        &self,
+        namespace_id: &str,
+        webmention_id: &str,
+        caller_user_id: &str,
+        approve: bool,
+    ) -> Result<WebmentionInfo, ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        let mut mention = self
+            .webmention_store
+            .get_webmention(webmention_id)
+            .await?
+            .filter(|m| m.namespace_id == namespace_id)
+            .ok_or_else(|| ServerCoreError::not_found("Webmention not found"))?;
+        if approve
+            && matches!(
+                mention.status,
+                WebmentionStatus::Unverified | WebmentionStatus::Invalid
+            )
+        {
+            return Err(ServerCoreError::conflict(
+                "Only verified mentions can be approved",
+            ));
+        }
+        mention.status = if approve {
+            WebmentionStatus::Approved
+        } else {
+            WebmentionStatus::Rejected
+        };
+        mention.updated_at = Utc::now().timestamp();
+        self.webmention_store.upsert_webmention(&mention).await?;
+        Ok(mention)
+    }
+
+    pub async fn delete(
+        &self,
+        namespace_id: &str,
+        webmention_id: &str,
+        caller_user_id: &str,
+    ) -> Result<(), ServerCoreError> {
+        self.require_owner(namespace_id, caller_user_id).await?;
+        if self
+            .webmention_store
+            .delete_webmention(namespace_id, webmention_id)
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(ServerCoreError::not_found("Webmention not found"))
+        }
+    }
+}
+
+/// Approved mentions of a namespace grouped by page object key, oldest
+/// first, as the renderer shows them.
+pub async fn approved_mentions_by_page(
+    webmention_store: &dyn WebmentionStore,
+    namespace_id: &str,
+) -> Result<HashMap<String, Vec<PageMention>>, ServerCoreError> {
+    let approved = webmention_store
+        .list_webmentions(
+            namespace_id,
+            Some(WebmentionStatus::Approved),
+            MAX_RENDERED_MENTIONS,
+        )
+        .await?;
+    let mut by_page: HashMap<String, Vec<PageMention>> = HashMap::new();
+    for mention in approved.into_iter().rev() {
+        by_page
+            .entry(mention.object_key)
+            .or_default()
+            .push(PageMention {
+                source: mention.source,
+                title: mention.source_title,
+            });
+    }
+    Ok(by_page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::GateRecord;
+    use crate::testing::{InMemoryArkIndexStore, InMemoryNamespaceStore, InMemoryWebmentionStore};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingJobSink {
+        kinds: Mutex<Vec<String>>,
+    }
+
+    crate::cfg_async_trait! {
+    impl JobSink for RecordingJobSink {
+        async fn enqueue(&self, kind: &str, _payload: Value) -> Result<(), ServerCoreError> {
+            self.kinds.lock().unwrap().push(kind.to_string());
+            Ok(())
+        }
+    }
+    }
+
+    /// Answers every fetch with the same status and body.
+    struct StaticFetcher(u16, &'static str);
+
+    crate::cfg_async_trait! {
+    impl WebmentionFetcher for StaticFetcher {
+        async fn fetch(&self, _url: &str, _max_bytes: usize) -> Result<(u16, String), ServerCoreError> {
+            Ok((self.0, self.1.to_string()))
+        }
+    }
+    }
+
+    fn row(object_key: &str, audience: Option<&str>) -> ArkIndexEntry {
+        ArkIndexEntry {
+            workspace_ark: "ns1".to_string(),
+            file_ark: object_key.to_string(),
+            object_key: object_key.to_string(),
+            audience: audience.map(str::to_string),
+            source_key: None,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn targets_resolve_by_path_suffix() {
+        let rows = [
+            row("public/index.html", Some("public")),
+            row("public/notes/My Note.html", Some("public")),
+            row("other/index.html", Some("other")),
+        ];
+        let pages: Vec<&ArkIndexEntry> = rows.iter().collect();
+        let resolve = |url: &str| resolve_target(url, &pages).map(|r| r.object_key.as_str());
+
+        assert_eq!(
+            resolve("https://me.example/notes/My%20Note.html"),
+            Some("public/notes/My Note.html")
+        );
+        assert_eq!(
+            resolve("https://sync.example/api/public/ns1/objects/other/index.html?x=1"),
+            Some("other/index.html")
+        );
+        assert_eq!(
+            resolve("https://sync.example/sites/ns1/public/"),
+            Some("public/index.html")
+        );
+        assert_eq!(resolve("https://me.example/Note.html"), None);
+        assert_eq!(resolve("https://me.example/missing.html"), None);
+    }
+
+    #[test]
+    fn title_and_link_detection() {
+        let html = "<html><head><TITLE>\n  A   reply </title></head>\
+                    <a href=\"https://me.example/post.html?a=1&amp;b=2\">x</a>";
+        assert_eq!(extract_title(html).as_deref(), Some("A reply"));
+        assert!(source_links_to(
+            html,
+            "https://me.example/post.html?a=1&b=2"
+        ));
+        assert!(!source_links_to(html, "https://me.example/other.html"));
+        assert_eq!(extract_title("<p>no title</p>"), None);
+    }
+
+    #[test]
+    fn internal_sources_are_refused() {
+        for url in [
+            "http://localhost:3030/",
+            "http://127.0.0.1/x",
+            "https://10.1.2.3/",
+            "http://[::1]:8080/",
+            "http://user@192.168.0.1/",
+            "http://printer.local/",
+        ] {
+            assert!(url_host(url).is_some_and(is_internal_host), "{url}");
+        }
+        assert!(!url_host("https://elsewhere.example/reply").is_some_and(is_internal_host));
+        assert!(!url_host("https://93.184.216.34/").is_some_and(is_internal_host));
+    }
+
+    #[tokio::test]
+    async fn mentions_are_verified_then_moderated_by_the_owner() {
+        let ns_store = InMemoryNamespaceStore::new();
+        ns_store
+            .create_namespace("ns1", "owner", None)
+            .await
+            .unwrap();
+        ns_store
+            .upsert_audience("ns1", "public", &[])
+            .await
+            .unwrap();
+        ns_store
+            .upsert_audience("ns1", "family", &[GateRecord::Link])
+            .await
+            .unwrap();
+        let ark = InMemoryArkIndexStore::new();
+        ark.upsert_ark("ns1", "a", "public/post.html", Some("public"), None)
+            .await
+            .unwrap();
+        ark.upsert_ark("ns1", "b", "family/secret.html", Some("family"), None)
+            .await
+            .unwrap();
+        let store = InMemoryWebmentionStore::new();
+        let jobs = RecordingJobSink::default();
+        let receiver = WebmentionReceiver::new(&ns_store, &ark, &store, &jobs);
+
+        let request = |target: &str| ReceiveWebmentionRequest {
+            source: "https://elsewhere.example/reply".to_string(),
+            target: target.to_string(),
+        };
+        let err = receiver
+            .receive("ns1", &request("https://me.example/secret.html"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::InvalidInput(_)));
+
+        let mention = receiver
+            .receive("ns1", &request("https://me.example/post.html"))
+            .await
+            .unwrap();
+        assert_eq!(mention.status, WebmentionStatus::Unverified);
+        assert_eq!(mention.object_key, "public/post.html");
+        assert_eq!(*jobs.kinds.lock().unwrap(), [WEBMENTION_VERIFY_JOB]);
+
+        let service = WebmentionService::new(&ns_store, &store);
+        let err = service
+            .moderate("ns1", &mention.id, "owner", true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::Conflict(_)));
+
+        let fetcher = StaticFetcher(
+            200,
+            "<title>Reply</title><a href=\"https://me.example/post.html\">post</a>",
+        );
+        WebmentionVerifier::new(&store, &fetcher)
+            .verify(&mention.id, 10)
+            .await
+            .unwrap();
+        let listed = service
+            .list("ns1", "owner", &WebmentionListQuery::default())
+            .await
+            .unwrap();
+        assert_eq!(listed[0].status, WebmentionStatus::Pending);
+        assert_eq!(listed[0].source_title.as_deref(), Some("Reply"));
+
+        let err = service
+            .list("ns1", "stranger", &WebmentionListQuery::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServerCoreError::PermissionDenied(_)));
+
+        service
+            .moderate("ns1", &mention.id, "owner", true)
+            .await
+            .unwrap();
+        let shown = approved_mentions_by_page(&store, "ns1").await.unwrap();
+        assert_eq!(shown["public/post.html"][0].title.as_deref(), Some("Reply"));
+
+        // Re-sending keeps the approval; a source that's gone removes it.
+        let again = receiver
+            .receive("ns1", &request("https://me.example/post.html"))
+            .await
+            .unwrap();
+        assert_eq!(again.id, mention.id);
+        assert_eq!(again.status, WebmentionStatus::Approved);
+        WebmentionVerifier::new(&store, &StaticFetcher(410, ""))
+            .verify(&mention.id, 20)
+            .await
+            .unwrap();
+        assert!(store.get_webmention(&mention.id).await.unwrap().is_none());
+    }
+}
@@ -33,31 +33,37 @@ pub fn manifest(_input: String) -> FnResult<String> {
         },
         "host_capabilities": ["audio_capture"],
     })])
-    .commands(vec!["get_component_html".into()])
+    .command_set::<Commands>()
     .min_app_version("1.4.0");
     Ok(serde_json::to_string(&manifest)?)
 }
 
+#[derive(serde::Deserialize, CommandArgs)]
+struct ComponentArgs {
+    /// Iframe component to load
+    component_id: String,
+}
+
+struct Commands;
+
+#[commands]
+impl Commands {
+    /// HTML of an iframe component.
+    #[command(name = "get_component_html")]
+    fn get_component_html(args: ComponentArgs) -> Result<serde_json::Value, CommandError> {
+        match args.component_id.as_str() {
+            "audio-recorder" => Ok(serde_json::json!({ "html": AUDIO_RECORDER_HTML })),
+            other => Err(CommandError::not_found(format!(
+                "Unknown component: {other}"
+            ))),
+        }
+    }
+}
+
 #[plugin_fn]
 pub fn handle_command(input: String) -> FnResult<String> {
     let req: CommandRequest = serde_json::from_str(&input)?;
-    let resp = match req.command.as_str() {
-        "get_component_html" => {
-            let component_id = req
-                .params
-                .get("component_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            match component_id {
-                "audio-recorder" => {
-                    CommandResponse::ok(serde_json::json!({ "html": AUDIO_RECORDER_HTML }))
-                }
-                _ => CommandResponse::err(format!("Unknown component: {component_id}")),
-            }
-        }
-        _ => CommandResponse::err(format!("Unknown command: {}", req.command)),
-    };
-    Ok(serde_json::to_string(&resp)?)
+    Ok(serde_json::to_string(&Commands::handle(req))?)
 }
 
 #[plugin_fn]
@@ -10,7 +10,7 @@ pub fn manifest(_input: String) -> FnResult<String> {
         "Drawing",
         env!("CARGO_PKG_VERSION"),
         "Freehand drawing blocks with pen, eraser, colors, and undo/redo.",
-        vec!["editor_extension".into()],
+        vec!["editor_extension".into(), "render_html".into()],
     )
     .ui(vec![
         // Editor extension: iframe-based drawing block
@@ -27,7 +27,7 @@ pub fn manifest(_input: String) -> FnResult<String> {
             "render_export": null,
             "edit_mode": "Iframe",
             "iframe_component_id": "drawing-canvas",
-            "css": null,
+            "css": "img.diaryx-drawing { max-width: 100%; height: auto; }",
             "insert_command": {
                 "label": "Drawing",
                 "icon": "pencil",
@@ -35,31 +35,63 @@ pub fn manifest(_input: String) -> FnResult<String> {
             },
         }),
     ])
-    .commands(vec!["get_component_html".into()])
+    .command_set::<Commands>()
     .min_app_version("1.4.0");
     Ok(serde_json::to_string(&manifest)?)
 }
 
+#[derive(serde::Deserialize, CommandArgs)]
+struct ComponentArgs {
+    /// Iframe component to load
+    component_id: String,
+}
+
+struct Commands;
+
+#[commands]
+impl Commands {
+    /// HTML of an iframe component.
+    #[command(name = "get_component_html")]
+    fn get_component_html(args: ComponentArgs) -> Result<serde_json::Value, CommandError> {
+        match args.component_id.as_str() {
+            "drawing-canvas" => Ok(serde_json::json!({ "html": DRAWING_CANVAS_HTML })),
+            other => Err(CommandError::not_found(format!(
+                "Unknown component: {other}"
+            ))),
+        }
+    }
+}
+
 #[plugin_fn]
 pub fn handle_command(input: String) -> FnResult<String> {
     let req: CommandRequest = serde_json::from_str(&input)?;
-    let resp = match req.command.as_str() {
-        "get_component_html" => {
-            let component_id = req
-                .params
-                .get("component_id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-            match component_id {
-                "drawing-canvas" => {
-                    CommandResponse::ok(serde_json::json!({ "html": DRAWING_CANVAS_HTML }))
-                }
-                _ => CommandResponse::err(format!("Unknown component: {component_id}")),
-            }
-        }
-        _ => CommandResponse::err(format!("Unknown command: {}", req.command)),
+    Ok(serde_json::to_string(&Commands::handle(req))?)
+}
+
+/// Render a drawing block as an image for published sites.
+///
+/// The source is everything between `![drawing:` and the closing `)`, i.e.
+/// `<id>](<path>`. A block without an attachment renders as nothing.
+#[plugin_fn]
+pub fn render_html(input: String) -> FnResult<String> {
+    let input: RenderHtmlInput = serde_json::from_str(&input)?;
+    let output = match input.source.split_once("](") {
+        Some((_, path)) if path.trim().is_empty() => RenderHtmlOutput::ok(""),
+        Some((id, path)) => RenderHtmlOutput::ok(format!(
+            r#"<img src="{}" alt="{}" class="diaryx-drawing" loading="lazy">"#,
+            attr_escape(path.trim()),
+            attr_escape(id),
+        )),
+        None => RenderHtmlOutput::err("Malformed drawing block"),
     };
-    Ok(serde_json::to_string(&resp)?)
+    Ok(serde_json::to_string(&output)?)
+}
+
+fn attr_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 #[plugin_fn]
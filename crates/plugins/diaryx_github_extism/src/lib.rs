@@ -779,19 +779,7 @@ pub fn manifest(_input: String) -> FnResult<String> {
             "description": "Store workspace snapshots in a GitHub repository"
         })
     ])
-    .commands(vec![
-        "BeginOAuth".into(),
-        "CompleteOAuth".into(),
-        "Disconnect".into(),
-        "GetProviderStatus".into(),
-        "ListRemoteWorkspaces".into(),
-        "LinkWorkspace".into(),
-        "UnlinkWorkspace".into(),
-        "DownloadWorkspace".into(),
-        "SyncWorkspace".into(),
-        "GetCommitHistory".into(),
-        "get_component_html".into(),
-    ])
+    .command_set::<Commands>()
     .requested_permissions(GuestRequestedPermissions {
         defaults: json!({
             "plugin_storage": { "include": ["all"], "exclude": [] },
@@ -858,128 +846,158 @@ pub fn on_event(_input: String) -> FnResult<String> {
 #[plugin_fn]
 pub fn handle_command(input: String) -> FnResult<String> {
     let req: CommandRequest = serde_json::from_str(&input)?;
-    let response = dispatch_command(&req.command, &req.params);
-    Ok(serde_json::to_string(&response)?)
+    Ok(serde_json::to_string(&Commands::handle(req))?)
 }
 
-fn dispatch_command(command: &str, params: &JsonValue) -> CommandResponse {
-    let result = match command {
-        "get_component_html" => params
-            .get("component_id")
-            .and_then(|value| value.as_str())
-            .ok_or_else(|| "Missing component_id".to_string())
-            .and_then(get_component_html)
-            .map(JsonValue::String),
-        "BeginOAuth" => {
-            let config = current_config_or_default();
-            let client_id = params
-                .get("client_id")
-                .and_then(|value| value.as_str())
-                .filter(|value| !value.trim().is_empty())
-                .map(str::to_string)
-                .or_else(|| {
-                    let fallback = config.client_id.trim();
-                    if fallback.is_empty() {
-                        None
-                    } else {
-                        Some(fallback.to_string())
-                    }
-                })
-                .ok_or_else(|| {
-                    "GitHub OAuth is not configured for this build. Set a host-managed client ID or use a personal access token.".to_string()
-                });
+fn required(value: Option<String>, name: &str) -> Result<String, CommandError> {
+    value.ok_or_else(|| CommandError::invalid_params(format!("Missing {name}")))
+}
 
-            client_id.and_then(|client_id| {
-                let redirect_uri = params
-                    .get("redirect_uri")
-                    .and_then(|value| value.as_str())
-                    .ok_or_else(|| "Missing redirect_uri".to_string())?;
-                let redirect_uri_prefix = params
-                    .get("redirect_uri_prefix")
-                    .and_then(|value| value.as_str())
-                    .unwrap_or(redirect_uri);
-                let code_challenge = params
-                    .get("code_challenge")
-                    .and_then(|value| value.as_str())
-                    .ok_or_else(|| "Missing code_challenge".to_string())?;
-                Ok(json!({
-                    "host_action": {
-                        "type": "open-oauth",
-                        "payload": {
-                            "url": format!(
-                                "https://github.com/login/oauth/authorize?client_id={}&redirect_uri={}&scope={}&code_challenge={}&code_challenge_method=S256",
-                                uri_encode(&client_id),
-                                uri_encode(redirect_uri),
-                                uri_encode("repo"),
-                                uri_encode(code_challenge),
-                            ),
-                            "redirect_uri_prefix": redirect_uri_prefix,
-                        }
-                    },
-                    "follow_up": {
-                        "command": "CompleteOAuth",
-                        "params": {
-                            "client_id": client_id,
-                            "code_verifier": params.get("code_verifier").cloned().unwrap_or(JsonValue::Null),
-                        }
-                    }
-                }))
-            })
-        }
-        "CompleteOAuth" => {
-            let code = params
-                .get("code")
-                .and_then(|value| value.as_str())
-                .ok_or_else(|| "Missing code".to_string());
-            let redirect_uri = params
-                .get("redirect_uri")
-                .and_then(|value| value.as_str())
-                .ok_or_else(|| "Missing redirect_uri".to_string());
-            let client_id = params
-                .get("client_id")
-                .and_then(|value| value.as_str())
-                .map(str::to_string)
-                .or_else(|| {
-                    let existing = current_config_or_default().client_id;
-                    if existing.trim().is_empty() {
-                        None
-                    } else {
-                        Some(existing)
-                    }
-                })
-                .ok_or_else(|| "Missing client_id".to_string());
-            let code_verifier = params
-                .get("code_verifier")
-                .and_then(|value| value.as_str())
-                .ok_or_else(|| "Missing code_verifier".to_string());
-
-            code.and_then(|code| {
-                redirect_uri.and_then(|redirect_uri| {
-                    client_id.and_then(|client_id| {
-                        code_verifier.and_then(|code_verifier| {
-                            let token =
-                                exchange_token(&client_id, code, redirect_uri, code_verifier)?;
-                            let mut config = current_config_or_default();
-                            config.client_id = client_id;
-                            config.access_token = token.clone();
-                            config.connected_login = fetch_current_user_login(&config, &token)?;
-                            persist_config(&config)?;
-                            set_runtime_config(config.clone());
-                            Ok(json!({
-                                "message": format!("Connected as {}.", config.connected_login),
-                            }))
-                        })
-                    })
-                })
+/// Remote workspace, falling back to the one linked to the current workspace.
+fn linked_remote_id(remote_id: Option<String>) -> Result<String, String> {
+    remote_id
+        .or_else(|| current_workspace_context("diaryx.github").and_then(|value| value.remote_id))
+        .ok_or_else(|| "No GitHub remote workspace is linked.".to_string())
+}
+
+#[derive(Deserialize, CommandArgs)]
+struct ComponentArgs {
+    /// Iframe component to load
+    component_id: Option<String>,
+}
+
+#[derive(Deserialize, CommandArgs)]
+struct BeginOAuthArgs {
+    /// OAuth app client ID (default: the configured one)
+    client_id: Option<String>,
+    /// Redirect URI registered with the OAuth app
+    redirect_uri: Option<String>,
+    /// Prefix the host watches for the redirect (default: `redirect_uri`)
+    redirect_uri_prefix: Option<String>,
+    /// PKCE code challenge
+    code_challenge: Option<String>,
+    /// PKCE code verifier, passed on to `CompleteOAuth`
+    code_verifier: Option<String>,
+}
+
+#[derive(Deserialize, CommandArgs)]
+struct CompleteOAuthArgs {
+    /// Authorization code from the redirect
+    code: Option<String>,
+    /// Redirect URI used to start the flow
+    redirect_uri: Option<String>,
+    /// OAuth app client ID (default: the configured one)
+    client_id: Option<String>,
+    /// PKCE code verifier
+    code_verifier: Option<String>,
+}
+
+#[derive(Deserialize, CommandArgs)]
+struct LinkWorkspaceArgs {
+    /// Local workspace ID
+    local_workspace_id: Option<String>,
+    /// Workspace name
+    name: Option<String>,
+    /// Remote workspace ID (default: derived from the local ID)
+    remote_id: Option<String>,
+}
+
+#[derive(Deserialize, CommandArgs)]
+struct RemoteArgs {
+    /// Remote workspace ID (default: the linked one)
+    remote_id: Option<String>,
+}
+
+struct Commands;
+
+#[commands]
+impl Commands {
+    /// Start the GitHub OAuth flow.
+    #[command(name = "BeginOAuth")]
+    fn begin_oauth(args: BeginOAuthArgs) -> Result<JsonValue, CommandError> {
+        let client_id = args
+            .client_id
+            .filter(|value| !value.trim().is_empty())
+            .or_else(|| {
+                let config = current_config_or_default();
+                let fallback = config.client_id.trim();
+                (!fallback.is_empty()).then(|| fallback.to_string())
             })
-        }
-        "Disconnect" => with_config_mut(|config| {
+            .ok_or_else(|| {
+                CommandError::failed(
+                    "GitHub OAuth is not configured for this build. Set a host-managed client ID or use a personal access token.",
+                )
+            })?;
+        let redirect_uri = required(args.redirect_uri, "redirect_uri")?;
+        let redirect_uri_prefix = args
+            .redirect_uri_prefix
+            .unwrap_or_else(|| redirect_uri.clone());
+        let code_challenge = required(args.code_challenge, "code_challenge")?;
+        Ok(json!({
+            "host_action": {
+                "type": "open-oauth",
+                "payload": {
+                    "url": format!(
+                        "https://github.com/login/oauth/authorize?client_id={}&redirect_uri={}&scope={}&code_challenge={}&code_challenge_method=S256",
+                        uri_encode(&client_id),
+                        uri_encode(&redirect_uri),
+                        uri_encode("repo"),
+                        uri_encode(&code_challenge),
+                    ),
+                    "redirect_uri_prefix": redirect_uri_prefix,
+                }
+            },
+            "follow_up": {
+                "command": "CompleteOAuth",
+                "params": {
+                    "client_id": client_id,
+                    "code_verifier": args.code_verifier,
+                }
+            }
+        }))
+    }
+
+    /// Exchange the OAuth code for a token and store it.
+    #[command(name = "CompleteOAuth")]
+    fn complete_oauth(args: CompleteOAuthArgs) -> Result<JsonValue, CommandError> {
+        let code = required(args.code, "code")?;
+        let redirect_uri = required(args.redirect_uri, "redirect_uri")?;
+        let client_id = required(
+            args.client_id.or_else(|| {
+                let existing = current_config_or_default().client_id;
+                (!existing.trim().is_empty()).then_some(existing)
+            }),
+            "client_id",
+        )?;
+        let code_verifier = required(args.code_verifier, "code_verifier")?;
+
+        let token = exchange_token(&client_id, &code, &redirect_uri, &code_verifier)?;
+        let mut config = current_config_or_default();
+        config.client_id = client_id;
+        config.access_token = token.clone();
+        config.connected_login = fetch_current_user_login(&config, &token)?;
+        persist_config(&config)?;
+        set_runtime_config(config.clone());
+        Ok(json!({
+            "message": format!("Connected as {}.", config.connected_login),
+        }))
+    }
+
+    /// Forget the stored token.
+    #[command(name = "Disconnect")]
+    fn disconnect() -> Result<JsonValue, String> {
+        with_config_mut(|config| {
             config.access_token.clear();
             config.connected_login.clear();
             persist_config(config)?;
             Ok(json!({ "message": "Disconnected from GitHub." }))
-        }),
-        "GetProviderStatus" => with_config(|config| {
+        })
+    }
+
+    /// Whether the repository is configured and reachable.
+    #[command(name = "GetProviderStatus")]
+    fn get_provider_status() -> Result<JsonValue, String> {
+        with_config(|config| {
             if config.repo_owner.trim().is_empty() || config.repo_name.trim().is_empty() {
                 return Ok(json!({
                     "ready": false,
@@ -1005,28 +1023,28 @@ fn dispatch_command(command: &str, params: &JsonValue) -> CommandResponse {
                     "message": format!("GitHub repo check failed ({}).", resp.status),
                 }))
             }
-        }),
-        "ListRemoteWorkspaces" => with_config(|config| {
+        })
+    }
+
+    /// Workspace snapshots in the repository.
+    #[command(name = "ListRemoteWorkspaces")]
+    fn list_remote_workspaces() -> Result<JsonValue, String> {
+        with_config(|config| {
             validate_repo_config(config)?;
             Ok(json!({
                 "workspaces": list_remote_workspaces(config)?,
             }))
-        }),
-        "LinkWorkspace" => with_config(|config| {
+        })
+    }
+
+    /// Upload a first snapshot of a local workspace.
+    #[command(name = "LinkWorkspace")]
+    fn link_workspace(args: LinkWorkspaceArgs) -> Result<JsonValue, String> {
+        with_config(|config| {
             validate_repo_config(config)?;
-            let local_id = params
-                .get("local_workspace_id")
-                .and_then(|value| value.as_str())
-                .unwrap_or("workspace");
-            let name = params
-                .get("name")
-                .and_then(|value| value.as_str())
-                .unwrap_or("Workspace");
-            let remote_id = params
-                .get("remote_id")
-                .and_then(|value| value.as_str())
-                .map(sanitize_remote_id)
-                .unwrap_or_else(|| sanitize_remote_id(local_id));
+            let local_id = args.local_workspace_id.as_deref().unwrap_or("workspace");
+            let name = args.name.as_deref().unwrap_or("Workspace");
+            let remote_id = sanitize_remote_id(args.remote_id.as_deref().unwrap_or(local_id));
             let snapshot = build_workspace_snapshot(local_id, name)?;
             let created_remote = upload_snapshot(
                 config,
@@ -1039,31 +1057,38 @@ fn dispatch_command(command: &str, params: &JsonValue) -> CommandResponse {
                 "created_remote": created_remote,
                 "snapshot_uploaded": true,
             }))
-        }),
-        "UnlinkWorkspace" => Ok(json!({ "message": "Workspace unlinked." })),
-        "DownloadWorkspace" => with_config(|config| {
+        })
+    }
+
+    /// Unlink the current workspace. Snapshots stay in the repository.
+    #[command(name = "UnlinkWorkspace")]
+    fn unlink_workspace() -> Result<JsonValue, String> {
+        Ok(json!({ "message": "Workspace unlinked." }))
+    }
+
+    /// Restore a snapshot into the current workspace.
+    #[command(name = "DownloadWorkspace")]
+    fn download_workspace(args: RemoteArgs) -> Result<JsonValue, String> {
+        with_config(|config| {
             validate_repo_config(config)?;
-            let runtime = current_workspace_context("diaryx.github");
-            let remote_id = params
-                .get("remote_id")
-                .and_then(|value| value.as_str())
-                .map(str::to_string)
-                .or_else(|| runtime.and_then(|value| value.remote_id))
-                .ok_or_else(|| "No GitHub remote workspace is linked.".to_string())?;
+            let remote_id = linked_remote_id(args.remote_id)?;
             let snapshot = fetch_snapshot(config, &remote_id)?;
             let files_imported = restore_snapshot(&snapshot)?;
             Ok(json!({
                 "files_imported": files_imported,
             }))
-        }),
-        "SyncWorkspace" => with_config(|config| {
+        })
+    }
+
+    /// Upload a snapshot of the current workspace.
+    #[command(name = "SyncWorkspace")]
+    fn sync_workspace(args: RemoteArgs) -> Result<JsonValue, String> {
+        with_config(|config| {
             validate_repo_config(config)?;
             let runtime = current_workspace_context("diaryx.github")
                 .ok_or_else(|| "No active workspace context is available.".to_string())?;
-            let remote_id = params
-                .get("remote_id")
-                .and_then(|value| value.as_str())
-                .map(str::to_string)
+            let remote_id = args
+                .remote_id
                 .or(runtime.remote_id)
                 .unwrap_or_else(|| sanitize_remote_id(&runtime.local_id));
             let snapshot = build_workspace_snapshot(&runtime.local_id, &runtime.name)?;
@@ -1077,27 +1102,29 @@ fn dispatch_command(command: &str, params: &JsonValue) -> CommandResponse {
                 "message": format!("Synced {} to GitHub.", runtime.name),
                 "remote_id": remote_id,
             }))
-        }),
-        "GetCommitHistory" => with_config(|config| {
+        })
+    }
+
+    /// Recent commits touching a workspace snapshot.
+    #[command(name = "GetCommitHistory")]
+    fn get_commit_history(args: RemoteArgs) -> Result<JsonValue, String> {
+        with_config(|config| {
             validate_repo_config(config)?;
-            let runtime = current_workspace_context("diaryx.github");
-            let remote_id = params
-                .get("remote_id")
-                .and_then(|value| value.as_str())
-                .map(str::to_string)
-                .or_else(|| runtime.and_then(|value| value.remote_id))
-                .ok_or_else(|| "No GitHub remote workspace is linked.".to_string())?;
+            let remote_id = linked_remote_id(args.remote_id)?;
             Ok(json!({
                 "remote_id": remote_id,
                 "repo": format!("{}/{}", config.repo_owner, config.repo_name),
                 "commits": get_commit_history(config, &remote_id)?,
             }))
-        }),
-        _ => Err(format!("Unknown command: {command}")),
-    };
+        })
+    }
 
-    match result {
-        Ok(data) => CommandResponse::ok(data),
-        Err(error) => CommandResponse::err(error),
+    /// HTML of an iframe component.
+    #[command(name = "get_component_html")]
+    fn get_component_html(args: ComponentArgs) -> Result<JsonValue, CommandError> {
+        let component_id = required(args.component_id, "component_id")?;
+        get_component_html(&component_id)
+            .map(JsonValue::String)
+            .map_err(CommandError::not_found)
     }
 }
@@ -140,28 +140,6 @@ fn optional_trimmed(value: &str) -> Option<String> {
 // ============================================================================
 
 fn build_manifest() -> GuestManifest {
-    #[allow(unused_mut)]
-    let mut commands = vec![
-        "ChooseDayOneParent".to_string(),
-        "SaveDayOneParentSelection".to_string(),
-        "ResetDayOneParent".to_string(),
-        "StartDayOneImport".to_string(),
-        "ParseDayOne".to_string(),
-        "ImportDayOne".to_string(),
-        "StartMarkdownFolderImport".to_string(),
-        "StartMarkdownZipImport".to_string(),
-        "PrepareMarkdownImport".to_string(),
-        "FinalizeMarkdownImport".to_string(),
-        "ImportEntries".to_string(),
-        "ImportDirectoryInPlace".to_string(),
-    ];
-
-    #[cfg(feature = "markdown-import")]
-    commands.push("ParseMarkdownFile".to_string());
-
-    #[cfg(feature = "email-import")]
-    commands.push("ParseEml".to_string());
-
     #[allow(unused_mut)]
     let mut subcommands = vec![
         serde_json::json!({
@@ -386,7 +364,7 @@ fn build_manifest() -> GuestManifest {
         ],
         "component": serde_json::Value::Null
     })])
-    .commands(commands)
+    .command_set::<Commands>()
     .cli(vec![serde_json::json!({
         "name": "import",
         "about": "Import entries from external formats",
@@ -483,230 +461,326 @@ pub fn set_config(input: String) -> FnResult<String> {
 // Command handlers
 // ============================================================================
 
-fn success_response(data: JsonValue) -> CommandResponse {
-    CommandResponse::ok(data)
+#[derive(serde::Deserialize, CommandArgs)]
+struct SaveDayOneParentArgs {
+    /// Selected parent entry (empty for the workspace root)
+    path: Option<String>,
+    /// Display name of the selected entry
+    name: Option<String>,
 }
 
-fn failure_response(message: impl Into<String>) -> CommandResponse {
-    CommandResponse::err(message)
+#[derive(serde::Deserialize, CommandArgs)]
+struct PrepareMarkdownImportArgs {
+    /// Host token for the picked folder or ZIP
+    selection_token: Option<String>,
 }
 
-fn handle_choose_dayone_parent(_params: JsonValue) -> CommandResponse {
-    success_response(serde_json::json!({
-        "host_action": {
-            "type": "pick-workspace-entry",
-            "payload": {
-                "title": "Choose Parent Entry",
-                "description": "Choose the entry to import Day One content under, or cancel to keep the workspace root.",
-                "placeholder": "Search entries...",
-                "allow_root": true
-            }
-        },
-        "follow_up": {
-            "command": "SaveDayOneParentSelection"
-        }
-    }))
+#[derive(serde::Deserialize, CommandArgs)]
+struct FinalizeMarkdownImportArgs {
+    /// Workspace folder the host copied the files into
+    path: Option<String>,
+    /// Files the host copied
+    files_imported: Option<u64>,
+    /// Files the host skipped
+    files_skipped: Option<u64>,
+    /// Errors from the host copy
+    errors: Option<Vec<JsonValue>>,
 }
 
-fn handle_save_dayone_parent_selection(params: JsonValue) -> CommandResponse {
-    let path = params.get("path").and_then(|v| v.as_str()).unwrap_or("");
-    let selected_name = params
-        .get("name")
-        .and_then(|v| v.as_str())
-        .unwrap_or("selected entry");
-    let message = if path.trim().is_empty() {
-        "Day One import will use the workspace root"
-    } else {
-        return success_response(serde_json::json!({
-            "message": format!("Day One import will go under {selected_name}"),
-            "config_patch": {
-                "dayone_parent_path": path.trim()
-            }
-        }));
-    };
+#[derive(serde::Deserialize, CommandArgs)]
+struct InputArgs {
+    /// Key of a host-provided file
+    file_key: Option<String>,
+    /// Base64-encoded input bytes, when there is no `file_key`
+    data: Option<String>,
+}
 
-    success_response(serde_json::json!({
-        "message": message,
-        "config_patch": {
-            "dayone_parent_path": path.trim()
-        }
-    }))
+#[derive(serde::Deserialize, CommandArgs)]
+struct ImportDayOneArgs {
+    /// Key of a host-provided file
+    file_key: Option<String>,
+    /// Base64-encoded input bytes, when there is no `file_key`
+    data: Option<String>,
+    /// Base folder for imported entries
+    folder: Option<String>,
+    /// Entry to import under (default: the workspace root)
+    parent_path: Option<String>,
 }
 
-fn handle_reset_dayone_parent(_params: JsonValue) -> CommandResponse {
-    success_response(serde_json::json!({
-        "message": "Day One import will use the workspace root",
-        "config_patch": {
-            "dayone_parent_path": ""
-        }
-    }))
+#[cfg(feature = "markdown-import")]
+#[derive(serde::Deserialize, CommandArgs)]
+struct ParseMarkdownArgs {
+    /// Base64-encoded markdown
+    data: Option<String>,
+    /// Original filename, used for the fallback title
+    filename: Option<String>,
 }
 
-fn handle_start_dayone_import(_params: JsonValue) -> CommandResponse {
-    let config = load_import_config();
-    let folder = non_empty_or(&config.dayone_folder, "journal");
-    let parent_path = optional_trimmed(&config.dayone_parent_path);
+#[derive(serde::Deserialize, CommandArgs)]
+struct ImportEntriesArgs {
+    /// JSON array of parsed entries
+    entries_json: Option<String>,
+    /// Base folder for imported entries
+    folder: Option<String>,
+    /// Entry to import under (default: the workspace root)
+    parent_path: Option<String>,
+}
 
-    success_response(serde_json::json!({
-        "host_action": {
-            "type": "pick-local-file",
-            "payload": {
-                "accept": ".json,.zip"
-            }
-        },
-        "follow_up": {
-            "command": "ImportDayOne",
-            "params": {
-                "folder": folder,
-                "parent_path": parent_path
-            }
-        }
-    }))
+#[derive(serde::Deserialize, CommandArgs)]
+struct ImportDirectoryArgs {
+    /// Workspace folder to import in place
+    path: Option<String>,
 }
 
-fn handle_start_markdown_folder_import(_params: JsonValue) -> CommandResponse {
-    success_response(serde_json::json!({
-        "host_action": {
-            "type": "pick-local-directory"
-        },
-        "follow_up": {
-            "command": "PrepareMarkdownImport"
-        }
-    }))
+#[derive(serde::Serialize)]
+struct ParseDayOneResult {
+    entries: Vec<ImportedEntry>,
+    errors: Vec<String>,
+    journal_name: Option<String>,
 }
 
-fn handle_start_markdown_zip_import(_params: JsonValue) -> CommandResponse {
-    success_response(serde_json::json!({
-        "host_action": {
-            "type": "pick-local-file",
-            "payload": {
-                "accept": ".zip"
+struct Commands;
+
+#[commands]
+impl Commands {
+    /// Ask the host to pick the entry Day One imports go under.
+    #[command(name = "ChooseDayOneParent")]
+    fn choose_dayone_parent() -> Result<JsonValue, CommandError> {
+        Ok(serde_json::json!({
+            "host_action": {
+                "type": "pick-workspace-entry",
+                "payload": {
+                    "title": "Choose Parent Entry",
+                    "description": "Choose the entry to import Day One content under, or cancel to keep the workspace root.",
+                    "placeholder": "Search entries...",
+                    "allow_root": true
+                }
+            },
+            "follow_up": {
+                "command": "SaveDayOneParentSelection"
             }
-        },
-        "follow_up": {
-            "command": "PrepareMarkdownImport"
-        }
-    }))
-}
+        }))
+    }
 
-fn handle_prepare_markdown_import(params: JsonValue) -> CommandResponse {
-    let selection_token = match params.get("selection_token").and_then(|v| v.as_str()) {
-        Some(value) if !value.trim().is_empty() => value,
-        _ => return failure_response("Missing selected local source for markdown import"),
-    };
-    let config = load_import_config();
-    let destination_prefix = if config.markdown_destination == "root" {
-        String::new()
-    } else {
-        non_empty_or(&config.markdown_subfolder, "imported")
-    };
+    /// Store the entry picked by `ChooseDayOneParent`.
+    #[command(name = "SaveDayOneParentSelection")]
+    fn save_dayone_parent_selection(args: SaveDayOneParentArgs) -> Result<JsonValue, CommandError> {
+        let path = args.path.as_deref().unwrap_or("").trim();
+        let message = if path.is_empty() {
+            "Day One import will use the workspace root".to_string()
+        } else {
+            let selected_name = args.name.as_deref().unwrap_or("selected entry");
+            format!("Day One import will go under {selected_name}")
+        };
+        Ok(serde_json::json!({
+            "message": message,
+            "config_patch": {
+                "dayone_parent_path": path
+            }
+        }))
+    }
 
-    success_response(serde_json::json!({
-        "host_action": {
-            "type": "import-local-selection-to-workspace",
-            "payload": {
-                "selection_token": selection_token,
-                "destination_prefix": destination_prefix
+    /// Import Day One entries at the workspace root again.
+    #[command(name = "ResetDayOneParent")]
+    fn reset_dayone_parent() -> Result<JsonValue, CommandError> {
+        Ok(serde_json::json!({
+            "message": "Day One import will use the workspace root",
+            "config_patch": {
+                "dayone_parent_path": ""
             }
-        },
-        "follow_up": {
-            "command": "FinalizeMarkdownImport"
-        }
-    }))
-}
+        }))
+    }
 
-fn handle_finalize_markdown_import(params: JsonValue) -> CommandResponse {
-    let imported = params
-        .get("files_imported")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0) as usize;
-    let skipped = params
-        .get("files_skipped")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0) as usize;
-    let mut errors: Vec<String> = params
-        .get("errors")
-        .and_then(|v| v.as_array())
-        .map(|values| {
-            values
-                .iter()
-                .filter_map(|value| value.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-    if imported == 0 {
-        return failure_response(
-            errors
-                .first()
-                .cloned()
-                .unwrap_or_else(|| "No files found to import".to_string()),
-        );
+    /// Ask the host for a Day One export, then run `ImportDayOne`.
+    #[command(name = "StartDayOneImport")]
+    fn start_dayone_import() -> Result<JsonValue, CommandError> {
+        let config = load_import_config();
+        let folder = non_empty_or(&config.dayone_folder, "journal");
+        let parent_path = optional_trimmed(&config.dayone_parent_path);
+
+        Ok(serde_json::json!({
+            "host_action": {
+                "type": "pick-local-file",
+                "payload": {
+                    "accept": ".json,.zip"
+                }
+            },
+            "follow_up": {
+                "command": "ImportDayOne",
+                "params": {
+                    "folder": folder,
+                    "parent_path": parent_path
+                }
+            }
+        }))
     }
 
-    let path = params.get("path").and_then(|v| v.as_str()).unwrap_or("");
-    match directory::import_directory_in_place(path) {
-        Ok(result) => {
-            errors.extend(result.errors);
-            success_response(serde_json::json!({
-                "imported": imported,
-                "skipped": skipped + result.skipped,
-                "errors": errors,
-                "attachment_count": result.attachment_count,
-                "message": format!("Imported {} files", imported)
-            }))
-        }
-        Err(error) => failure_response(error),
+    /// Parse a Day One export without writing anything.
+    #[command(name = "ParseDayOne")]
+    fn parse_dayone(args: InputArgs) -> Result<ParseDayOneResult, CommandError> {
+        let bytes = resolve_input_bytes(args.file_key.as_deref(), args.data.as_deref())?;
+        let parsed = parse_dayone_entries(&bytes);
+        Ok(ParseDayOneResult {
+            entries: parsed.entries,
+            errors: parsed.errors,
+            journal_name: parsed.journal_name,
+        })
     }
-}
 
-fn handle_parse_dayone(params: JsonValue) -> CommandResponse {
-    let bytes = match resolve_input_bytes(&params) {
-        Ok(b) => b,
-        Err(error) => {
-            return CommandResponse::err(error);
-        }
-    };
+    /// Parse a Day One export and write its entries.
+    #[command(name = "ImportDayOne")]
+    fn import_dayone(args: ImportDayOneArgs) -> Result<ImportResult, CommandError> {
+        let bytes = resolve_input_bytes(args.file_key.as_deref(), args.data.as_deref())?;
+        let folder = args
+            .folder
+            .ok_or_else(|| CommandError::invalid_params("Missing 'folder' parameter"))?;
+        Ok(import_dayone_direct(
+            &bytes,
+            &folder,
+            args.parent_path.as_deref(),
+        ))
+    }
 
-    let parsed = parse_dayone_entries(&bytes);
+    /// Ask the host for a markdown folder.
+    #[command(name = "StartMarkdownFolderImport")]
+    fn start_markdown_folder_import() -> Result<JsonValue, CommandError> {
+        Ok(serde_json::json!({
+            "host_action": {
+                "type": "pick-local-directory"
+            },
+            "follow_up": {
+                "command": "PrepareMarkdownImport"
+            }
+        }))
+    }
 
-    #[derive(serde::Serialize)]
-    struct ParseResult {
-        entries: Vec<ImportedEntry>,
-        errors: Vec<String>,
-        journal_name: Option<String>,
+    /// Ask the host for a markdown ZIP.
+    #[command(name = "StartMarkdownZipImport")]
+    fn start_markdown_zip_import() -> Result<JsonValue, CommandError> {
+        Ok(serde_json::json!({
+            "host_action": {
+                "type": "pick-local-file",
+                "payload": {
+                    "accept": ".zip"
+                }
+            },
+            "follow_up": {
+                "command": "PrepareMarkdownImport"
+            }
+        }))
     }
 
-    match serde_json::to_value(&ParseResult {
-        entries: parsed.entries,
-        errors: parsed.errors,
-        journal_name: parsed.journal_name,
-    }) {
-        Ok(data) => CommandResponse::ok(data),
-        Err(e) => CommandResponse::err(format!("Failed to serialize: {e}")),
+    /// Ask the host to copy the picked markdown into the workspace.
+    #[command(name = "PrepareMarkdownImport")]
+    fn prepare_markdown_import(args: PrepareMarkdownImportArgs) -> Result<JsonValue, CommandError> {
+        let selection_token = args
+            .selection_token
+            .filter(|value| !value.trim().is_empty())
+            .ok_or_else(|| {
+                CommandError::invalid_params("Missing selected local source for markdown import")
+            })?;
+        let config = load_import_config();
+        let destination_prefix = if config.markdown_destination == "root" {
+            String::new()
+        } else {
+            non_empty_or(&config.markdown_subfolder, "imported")
+        };
+
+        Ok(serde_json::json!({
+            "host_action": {
+                "type": "import-local-selection-to-workspace",
+                "payload": {
+                    "selection_token": selection_token,
+                    "destination_prefix": destination_prefix
+                }
+            },
+            "follow_up": {
+                "command": "FinalizeMarkdownImport"
+            }
+        }))
     }
-}
 
-fn handle_import_dayone(params: JsonValue) -> CommandResponse {
-    let bytes = match resolve_input_bytes(&params) {
-        Ok(b) => b,
-        Err(error) => {
-            return CommandResponse::err(error);
+    /// Build hierarchy metadata for markdown the host copied in.
+    #[command(name = "FinalizeMarkdownImport")]
+    fn finalize_markdown_import(
+        args: FinalizeMarkdownImportArgs,
+    ) -> Result<JsonValue, CommandError> {
+        let imported = args.files_imported.unwrap_or(0) as usize;
+        let skipped = args.files_skipped.unwrap_or(0) as usize;
+        let mut errors: Vec<String> = args
+            .errors
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|value| value.as_str().map(|s| s.to_string()))
+            .collect();
+        if imported == 0 {
+            return Err(CommandError::failed(
+                errors
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "No files found to import".to_string()),
+            ));
         }
-    };
 
-    let folder = match params.get("folder").and_then(|v| v.as_str()) {
-        Some(folder) => folder,
-        None => {
-            return CommandResponse::err("Missing 'folder' parameter");
-        }
-    };
+        let result = directory::import_directory_in_place(args.path.as_deref().unwrap_or(""))?;
+        errors.extend(result.errors);
+        Ok(serde_json::json!({
+            "imported": imported,
+            "skipped": skipped + result.skipped,
+            "errors": errors,
+            "attachment_count": result.attachment_count,
+            "message": format!("Imported {} files", imported)
+        }))
+    }
+
+    /// Write already-parsed entries.
+    #[command(name = "ImportEntries")]
+    fn import_entries(args: ImportEntriesArgs) -> Result<ImportResult, CommandError> {
+        let entries_json = args
+            .entries_json
+            .ok_or_else(|| CommandError::invalid_params("Missing 'entries_json' parameter"))?;
+        let folder = args
+            .folder
+            .ok_or_else(|| CommandError::invalid_params("Missing 'folder' parameter"))?;
+        let entries: Vec<ImportedEntry> = serde_json::from_str(&entries_json)
+            .map_err(|e| CommandError::invalid_params(format!("Invalid entries JSON: {e}")))?;
+        Ok(orchestrate::write_entries(
+            &folder,
+            &entries,
+            args.parent_path.as_deref(),
+        ))
+    }
 
-    let parent_path = params.get("parent_path").and_then(|v| v.as_str());
-    let result = import_dayone_direct(&bytes, folder, parent_path);
+    /// Add hierarchy metadata to markdown already in the workspace.
+    #[command(name = "ImportDirectoryInPlace")]
+    fn import_directory_in_place(args: ImportDirectoryArgs) -> Result<ImportResult, CommandError> {
+        Ok(directory::import_directory_in_place(
+            args.path.as_deref().unwrap_or(""),
+        )?)
+    }
 
-    match serde_json::to_value(&result) {
-        Ok(data) => CommandResponse::ok(data),
-        Err(e) => CommandResponse::err(format!("Failed to serialize result: {e}")),
+    /// Parse one markdown file.
+    #[cfg(feature = "markdown-import")]
+    #[command(name = "ParseMarkdownFile")]
+    fn parse_markdown(args: ParseMarkdownArgs) -> Result<ImportedEntry, CommandError> {
+        let data_b64 = args.data.ok_or_else(|| {
+            CommandError::invalid_params("Missing 'data' parameter (base64-encoded bytes)")
+        })?;
+        let bytes = BASE64
+            .decode(data_b64)
+            .map_err(|e| CommandError::invalid_params(format!("Failed to decode base64: {e}")))?;
+        Ok(parse_markdown_file(
+            &bytes,
+            args.filename.as_deref().unwrap_or("unknown.md"),
+        )?)
+    }
+
+    /// Parse one `.eml` message.
+    #[cfg(feature = "email-import")]
+    #[command(name = "ParseEml")]
+    fn parse_eml(args: InputArgs) -> Result<ImportedEntry, CommandError> {
+        let bytes = resolve_input_bytes(args.file_key.as_deref(), args.data.as_deref())?;
+        Ok(email::parse_eml(&bytes)?)
     }
 }
 
@@ -764,104 +838,41 @@ fn parse_dayone_entries(bytes: &[u8]) -> ParsedDayOneEntries {
     }
 }
 
-fn resolve_input_bytes(params: &JsonValue) -> Result<Vec<u8>, String> {
-    if let Some(file_key) = params.get("file_key").and_then(|v| v.as_str()) {
+fn resolve_input_bytes(
+    file_key: Option<&str>,
+    data: Option<&str>,
+) -> Result<Vec<u8>, CommandError> {
+    if let Some(file_key) = file_key {
         let bytes = host::files::request(file_key)?;
         if bytes.is_empty() {
-            return Err(format!("Requested file not available for key '{file_key}'"));
+            return Err(CommandError::not_found(format!(
+                "Requested file not available for key '{file_key}'"
+            )));
         }
         return Ok(bytes);
     }
 
-    let data_b64 = params.get("data").and_then(|v| v.as_str()).ok_or_else(|| {
-        "Missing 'data' parameter (base64-encoded bytes) or 'file_key' parameter".to_string()
+    let data_b64 = data.ok_or_else(|| {
+        CommandError::invalid_params(
+            "Missing 'data' parameter (base64-encoded bytes) or 'file_key' parameter",
+        )
     })?;
 
     BASE64
         .decode(data_b64)
-        .map_err(|e| format!("Failed to decode base64: {e}"))
-}
-
-#[cfg(not(feature = "markdown-import"))]
-fn markdown_import_disabled_response() -> CommandResponse {
-    CommandResponse::err(MARKDOWN_IMPORT_DISABLED_ERROR)
-}
-
-#[cfg(not(feature = "email-import"))]
-fn email_import_disabled_response() -> CommandResponse {
-    CommandResponse::err(EMAIL_IMPORT_DISABLED_ERROR)
-}
-
-#[cfg(feature = "email-import")]
-fn handle_parse_eml(params: JsonValue) -> CommandResponse {
-    let bytes = match resolve_input_bytes(&params) {
-        Ok(b) => b,
-        Err(error) => return CommandResponse::err(error),
-    };
-
-    match email::parse_eml(&bytes) {
-        Ok(entry) => match serde_json::to_value(&entry) {
-            Ok(data) => CommandResponse::ok(data),
-            Err(e) => CommandResponse::err(format!("Failed to serialize: {e}")),
-        },
-        Err(e) => CommandResponse::err(e),
-    }
-}
-
-#[cfg(feature = "markdown-import")]
-fn handle_parse_markdown(params: JsonValue) -> CommandResponse {
-    let data_b64 = match params.get("data").and_then(|v| v.as_str()) {
-        Some(d) => d,
-        None => {
-            return CommandResponse::err("Missing 'data' parameter (base64-encoded bytes)");
-        }
-    };
-
-    let filename = params
-        .get("filename")
-        .and_then(|v| v.as_str())
-        .unwrap_or("unknown.md");
-
-    let bytes = match BASE64.decode(data_b64) {
-        Ok(b) => b,
-        Err(e) => {
-            return CommandResponse::err(format!("Failed to decode base64: {e}"));
-        }
-    };
-
-    match parse_markdown_file(&bytes, filename) {
-        Ok(entry) => match serde_json::to_value(&entry) {
-            Ok(data) => CommandResponse::ok(data),
-            Err(e) => CommandResponse::err(format!("Failed to serialize: {e}")),
-        },
-        Err(e) => CommandResponse::err(e),
-    }
+        .map_err(|e| CommandError::invalid_params(format!("Failed to decode base64: {e}")))
 }
 
 fn dispatch_command(req: CommandRequest) -> CommandResponse {
-    match req.command.as_str() {
-        "ChooseDayOneParent" => handle_choose_dayone_parent(req.params),
-        "SaveDayOneParentSelection" => handle_save_dayone_parent_selection(req.params),
-        "ResetDayOneParent" => handle_reset_dayone_parent(req.params),
-        "StartDayOneImport" => handle_start_dayone_import(req.params),
-        "ParseDayOne" => handle_parse_dayone(req.params),
-        "ImportDayOne" => handle_import_dayone(req.params),
-        "StartMarkdownFolderImport" => handle_start_markdown_folder_import(req.params),
-        "StartMarkdownZipImport" => handle_start_markdown_zip_import(req.params),
-        "PrepareMarkdownImport" => handle_prepare_markdown_import(req.params),
-        "FinalizeMarkdownImport" => handle_finalize_markdown_import(req.params),
-        #[cfg(feature = "markdown-import")]
-        "ParseMarkdownFile" => handle_parse_markdown(req.params),
-        #[cfg(not(feature = "markdown-import"))]
-        "ParseMarkdownFile" => markdown_import_disabled_response(),
-        #[cfg(feature = "email-import")]
-        "ParseEml" => handle_parse_eml(req.params),
-        #[cfg(not(feature = "email-import"))]
-        "ParseEml" => email_import_disabled_response(),
-        "ImportEntries" => handle_import_entries(req.params),
-        "ImportDirectoryInPlace" => handle_import_directory_in_place(req.params),
-        _ => CommandResponse::err(format!("Unknown command: {}", req.command)),
+    #[cfg(not(feature = "markdown-import"))]
+    if req.command == "ParseMarkdownFile" {
+        return CommandResponse::err(MARKDOWN_IMPORT_DISABLED_ERROR);
+    }
+    #[cfg(not(feature = "email-import"))]
+    if req.command == "ParseEml" {
+        return CommandResponse::err(EMAIL_IMPORT_DISABLED_ERROR);
     }
+    Commands::handle(req)
 }
 
 fn dispatch_typed_command(command: &str, params: JsonValue) -> Result<Option<JsonValue>, String> {
@@ -1085,6 +1096,46 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn manifest_lists_every_command() {
+        #[allow(unused_mut)]
+        let mut expected = vec![
+            "ChooseDayOneParent",
+            "SaveDayOneParentSelection",
+            "ResetDayOneParent",
+            "StartDayOneImport",
+            "ParseDayOne",
+            "ImportDayOne",
+            "StartMarkdownFolderImport",
+            "StartMarkdownZipImport",
+            "PrepareMarkdownImport",
+            "FinalizeMarkdownImport",
+            "ImportEntries",
+            "ImportDirectoryInPlace",
+        ];
+        #[cfg(feature = "markdown-import")]
+        expected.push("ParseMarkdownFile");
+        #[cfg(feature = "email-import")]
+        expected.push("ParseEml");
+
+        assert_eq!(parsed_manifest().commands, expected);
+    }
+
+    #[test]
+    fn missing_params_are_invalid_params() {
+        let response = dispatch_command(CommandRequest {
+            command: "ImportEntries".to_string(),
+            params: serde_json::json!({ "folder": "journal" }),
+        });
+
+        assert!(!response.success);
+        assert_eq!(response.error_code.as_deref(), Some("invalid_params"));
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Missing 'entries_json' parameter")
+        );
+    }
+
     #[test]
     fn get_config_returns_defaults() {
         test_helpers::clear_test_storage();
@@ -1530,47 +1581,3 @@ mod tests {
         assert!(error.to_string().contains("Missing 'data' parameter"));
     }
 }
-
-fn handle_import_entries(params: JsonValue) -> CommandResponse {
-    let entries_json = match params.get("entries_json").and_then(|v| v.as_str()) {
-        Some(j) => j,
-        None => {
-            return CommandResponse::err("Missing 'entries_json' parameter");
-        }
-    };
-
-    let folder = match params.get("folder").and_then(|v| v.as_str()) {
-        Some(f) => f,
-        None => {
-            return CommandResponse::err("Missing 'folder' parameter");
-        }
-    };
-
-    let parent_path = params.get("parent_path").and_then(|v| v.as_str());
-
-    let entries: Vec<ImportedEntry> = match serde_json::from_str(entries_json) {
-        Ok(e) => e,
-        Err(e) => {
-            return CommandResponse::err(format!("Invalid entries JSON: {e}"));
-        }
-    };
-
-    let result = orchestrate::write_entries(folder, &entries, parent_path);
-
-    match serde_json::to_value(&result) {
-        Ok(data) => CommandResponse::ok(data),
-        Err(e) => CommandResponse::err(format!("Failed to serialize result: {e}")),
-    }
-}
-
-fn handle_import_directory_in_place(params: JsonValue) -> CommandResponse {
-    let path = params.get("path").and_then(|v| v.as_str()).unwrap_or("");
-
-    match directory::import_directory_in_place(path) {
-        Ok(result) => match serde_json::to_value(&result) {
-            Ok(data) => CommandResponse::ok(data),
-            Err(e) => CommandResponse::err(format!("Failed to serialize result: {e}")),
-        },
-        Err(e) => CommandResponse::err(e),
-    }
-}
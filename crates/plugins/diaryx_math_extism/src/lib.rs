@@ -80,7 +80,7 @@ pub fn manifest(_input: String) -> FnResult<String> {
         "Math",
         env!("CARGO_PKG_VERSION"),
         "LaTeX math rendering with inline ($...$) and block ($$...$$) support",
-        vec!["editor_extension".into()],
+        vec!["editor_extension".into(), "render_html".into()],
     )
     .ui(vec![
         // Inline math: $...$
@@ -139,6 +139,26 @@ pub fn render_content(input: String) -> FnResult<String> {
     Ok(serde_json::to_string(&output)?)
 }
 
+/// Render math as static MathML for published sites.
+///
+/// Input: [`RenderHtmlInput`] for `mathInline` or `mathBlock`.
+/// Output: [`RenderHtmlOutput`].
+#[plugin_fn]
+pub fn render_html(input: String) -> FnResult<String> {
+    let input: RenderHtmlInput = serde_json::from_str(&input).map_err(extism_pdk::Error::msg)?;
+
+    let output = match render_latex(&input.source, input.display_mode) {
+        RenderOutput {
+            html: Some(html), ..
+        } => RenderHtmlOutput::ok(html),
+        RenderOutput { error, .. } => {
+            RenderHtmlOutput::err(error.unwrap_or_else(|| "Failed to render math".into()))
+        }
+    };
+
+    Ok(serde_json::to_string(&output)?)
+}
+
 /// Handle commands dispatched by the host (none for this plugin).
 #[plugin_fn]
 pub fn handle_command(input: String) -> FnResult<String> {
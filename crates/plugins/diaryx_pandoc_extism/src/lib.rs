@@ -64,10 +64,24 @@ pub fn manifest(_input: String) -> FnResult<String> {
     .min_app_version("1.4.1")
     .ui(pm.ui.iter().map(|u| core_to_json_value(u)).collect())
     .commands(all_commands())
+    .provides(GuestServiceProvision::new("export", "1.0.0").commands([
+        "PlanExport",
+        "ExportToMemory",
+        "ExportBinaryAttachments",
+        "GetExportFormats",
+        "ConvertFormat",
+        "ConvertToPdf",
+    ]))
+    // Without templating, bodies are exported with `{{...}}` left as written.
+    .requires(GuestServiceRequirement::new("templating", "^1").optional())
     .requested_permissions(GuestRequestedPermissions {
         defaults: serde_json::json!({
             "read_files": { "include": ["all"], "exclude": [] },
             "http_requests": { "include": ["unpkg.com"], "exclude": [] },
+            "execute_commands": {
+                "include": ["diaryx.templating:RenderBody", "diaryx.templating:HasTemplates"],
+                "exclude": []
+            },
             // The pandoc WASM module is ~58 MB; request 100 MiB so the
             // download fits with headroom. The host caps this at its hard
             // ceiling regardless.
@@ -86,6 +100,10 @@ pub fn manifest(_input: String) -> FnResult<String> {
                 "plugin_storage".into(),
                 "Cache the ~58 MB pandoc WASM module between runs.".into(),
             ),
+            (
+                "execute_commands".into(),
+                "Render body templates with the Templating plugin before export.".into(),
+            ),
         ]
         .into_iter()
         .collect(),
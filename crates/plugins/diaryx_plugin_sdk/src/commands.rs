@@ -0,0 +1,446 @@
+//! Typed plugin commands.
+//!
+//! Instead of matching on `CommandRequest::command` and picking fields out of
+//! a `serde_json::Value` by hand, put typed handlers in an inherent `impl`
+//! and mark it `#[commands]` (feature `macros`). The macro generates a
+//! [`CommandSet`]: the dispatch table, the command names for the
+//! `custom_commands` capability, and [`CliCommandSchema`] entries whose
+//! argument schemas come from `#[derive(CommandArgs)]` on the params structs.
+//! Bad params and handler failures come back as [`CommandError`]s, which set
+//! `CommandResponse::error_code`.
+//!
+//! ```rust,ignore
+//! use diaryx_plugin_sdk::prelude::*;
+//! use extism_pdk::*;
+//!
+//! #[derive(serde::Deserialize, CommandArgs)]
+//! struct WordCountArgs {
+//!     /// Entry to count
+//!     path: String,
+//!     /// Skip the frontmatter
+//!     #[serde(default)]
+//!     body_only: bool,
+//! }
+//!
+//! struct Commands;
+//!
+//! #[commands]
+//! impl Commands {
+//!     /// Count the words in an entry.
+//!     #[command(cli = "word-count")]
+//!     fn word_count(args: WordCountArgs) -> Result<usize, CommandError> {
+//!         let text = host::fs::read_file(&args.path)?;
+//!         Ok(text.split_whitespace().count())
+//!     }
+//! }
+//!
+//! #[plugin_fn]
+//! pub fn manifest(_input: String) -> FnResult<String> {
+//!     let manifest = GuestManifest::new("acme.words", "Words", "0.1.0", "Word counts", vec![])
+//!         .command_set::<Commands>();
+//!     Ok(serde_json::to_string(&manifest)?)
+//! }
+//!
+//! #[plugin_fn]
+//! pub fn handle_command(input: String) -> FnResult<String> {
+//!     let request: CommandRequest = serde_json::from_str(&input)?;
+//!     Ok(serde_json::to_string(&Commands::handle(request))?)
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::{CommandRequest, CommandResponse};
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// What kind of failure a [`CommandError`] is. Reported to the host as
+/// `CommandResponse::error_code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// The params didn't match the command's argument struct.
+    InvalidParams,
+    /// Something the command needed doesn't exist.
+    NotFound,
+    /// The host denied an operation the command needed.
+    PermissionDenied,
+    /// Any other failure.
+    Failed,
+}
+
+impl CommandErrorKind {
+    /// The `error_code` string sent to the host.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::InvalidParams => "invalid_params",
+            Self::NotFound => "not_found",
+            Self::PermissionDenied => "permission_denied",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// A typed command failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    /// Kind of failure.
+    pub kind: CommandErrorKind,
+    /// Human-readable message.
+    pub message: String,
+}
+
+impl CommandError {
+    /// An error of the given kind.
+    pub fn new(kind: CommandErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// The params didn't match the command's arguments.
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::InvalidParams, message)
+    }
+
+    /// Something the command needed doesn't exist.
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::NotFound, message)
+    }
+
+    /// The host denied an operation.
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::PermissionDenied, message)
+    }
+
+    /// Any other failure.
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self::new(CommandErrorKind::Failed, message)
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::failed(message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::failed(message)
+    }
+}
+
+impl From<CommandError> for CommandResponse {
+    fn from(error: CommandError) -> Self {
+        let mut response = CommandResponse::err(error.message);
+        response.error_code = Some(error.kind.code().to_string());
+        response
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CLI schema
+// ---------------------------------------------------------------------------
+
+/// Value type of a CLI argument. Mirrors
+/// `diaryx_core::plugin::CliArgType`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CliArgType {
+    /// String value.
+    #[default]
+    String,
+    /// Integer value.
+    Integer,
+    /// Floating-point value.
+    Float,
+    /// Boolean value.
+    Boolean,
+    /// Filesystem path.
+    Path,
+}
+
+/// A CLI argument. Mirrors `diaryx_core::plugin::CliArg`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliArgSchema {
+    /// Argument name; also the key it is sent under in the params.
+    pub name: String,
+    /// Help text.
+    pub help: String,
+    /// Value type.
+    #[serde(default)]
+    pub value_type: CliArgType,
+    /// Whether the argument must be given.
+    #[serde(default)]
+    pub required: bool,
+    /// Default value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+    /// Short flag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short: Option<char>,
+    /// Long flag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long: Option<String>,
+    /// Whether this is a boolean flag.
+    #[serde(default)]
+    pub is_flag: bool,
+}
+
+/// A CLI subcommand. Mirrors `diaryx_core::plugin::CliCommand`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CliCommandSchema {
+    /// Subcommand name.
+    pub name: String,
+    /// Short help text.
+    pub about: String,
+    /// Longer help text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_about: Option<String>,
+    /// Alternative names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Arguments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<CliArgSchema>,
+    /// Nested subcommands.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subcommands: Vec<CliCommandSchema>,
+    /// Command sent to `handle_command`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_name: Option<String>,
+    /// Whether the CLI resolves a workspace first.
+    pub requires_workspace: bool,
+}
+
+impl Default for CliCommandSchema {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            about: String::new(),
+            long_about: None,
+            aliases: Vec::new(),
+            args: Vec::new(),
+            subcommands: Vec::new(),
+            command_name: None,
+            requires_workspace: true,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Traits
+// ---------------------------------------------------------------------------
+
+/// A command's params struct, described as CLI arguments. Derive it with
+/// `#[derive(CommandArgs)]`.
+pub trait CommandArgs: DeserializeOwned {
+    /// The struct's fields as CLI arguments.
+    fn cli_args() -> Vec<CliArgSchema>;
+}
+
+/// A set of typed commands. Generated by `#[commands]`.
+pub trait CommandSet {
+    /// Names of the commands, for the `custom_commands` capability.
+    fn command_names() -> Vec<String>;
+
+    /// CLI subcommands exposing the commands.
+    fn cli_commands() -> Vec<CliCommandSchema>;
+
+    /// Run `command`, or return `None` if it isn't in the set.
+    fn dispatch(
+        command: &str,
+        params: serde_json::Value,
+    ) -> Option<Result<serde_json::Value, CommandError>>;
+
+    /// Run a `handle_command` request. Unknown commands get the usual
+    /// `Unknown command: <name>` error.
+    fn handle(request: CommandRequest) -> CommandResponse {
+        match Self::dispatch(&request.command, request.params) {
+            Some(Ok(data)) => CommandResponse::ok(data),
+            Some(Err(error)) => error.into(),
+            None => CommandResponse::err(format!("Unknown command: {}", request.command)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Macro support
+// ---------------------------------------------------------------------------
+
+/// Deserialize command params into `A`. Missing params count as `{}`.
+pub fn parse_args<A: DeserializeOwned>(params: serde_json::Value) -> Result<A, CommandError> {
+    let params = if params.is_null() {
+        serde_json::Value::Object(Default::default())
+    } else {
+        params
+    };
+    serde_json::from_value(params).map_err(|e| CommandError::invalid_params(e.to_string()))
+}
+
+/// Serialize a handler's output as response data.
+pub fn to_data<T: Serialize>(output: T) -> Result<serde_json::Value, CommandError> {
+    serde_json::to_value(output)
+        .map_err(|e| CommandError::failed(format!("Failed to serialize command result: {e}")))
+}
+
+/// Run a handler that takes a params struct.
+pub fn run<A, T, E>(
+    params: serde_json::Value,
+    handler: impl FnOnce(A) -> Result<T, E>,
+) -> Result<serde_json::Value, CommandError>
+where
+    A: DeserializeOwned,
+    T: Serialize,
+    E: Into<CommandError>,
+{
+    to_data(handler(parse_args(params)?).map_err(Into::into)?)
+}
+
+/// Run a handler that takes no params.
+pub fn run_without_args<T, E>(
+    handler: impl FnOnce() -> Result<T, E>,
+) -> Result<serde_json::Value, CommandError>
+where
+    T: Serialize,
+    E: Into<CommandError>,
+{
+    to_data(handler().map_err(Into::into)?)
+}
+
+#[cfg(all(test, feature = "macros"))]
+mod tests {
+    use super::*;
+    use crate::protocol::GuestManifest;
+    use crate::{CommandArgs, commands};
+
+    #[derive(Deserialize, CommandArgs)]
+    struct GreetArgs {
+        /// Who to greet
+        name: String,
+        /// How many times
+        #[arg(short = 't', long = "times", default = "1")]
+        #[serde(default = "one")]
+        times: u32,
+        /// Shout it
+        #[serde(default)]
+        loud: bool,
+        /// Where to log the greeting
+        log: Option<std::path::PathBuf>,
+    }
+
+    fn one() -> u32 {
+        1
+    }
+
+    struct Commands;
+
+    #[commands(cli = "greet", about = "Greetings", alias = "hi")]
+    impl Commands {
+        /// Greet someone.
+        #[command(cli)]
+        fn say_hello(args: GreetArgs) -> Result<String, CommandError> {
+            if args.name.is_empty() {
+                return Err(CommandError::not_found("nobody to greet"));
+            }
+            let greeting = format!("hello {}", args.name).repeat(args.times as usize);
+            let greeting = if args.loud {
+                greeting.to_uppercase()
+            } else {
+                greeting
+            };
+            Ok(match args.log {
+                Some(log) => format!("{greeting} > {}", log.display()),
+                None => greeting,
+            })
+        }
+
+        #[command(name = "ping")]
+        fn ping() -> Result<bool, String> {
+            Err("no pong".into())
+        }
+    }
+
+    fn request(command: &str, params: serde_json::Value) -> CommandResponse {
+        Commands::handle(CommandRequest {
+            command: command.into(),
+            params,
+        })
+    }
+
+    #[test]
+    fn dispatches_typed_handlers() {
+        let response = request(
+            "SayHello",
+            serde_json::json!({ "name": "ada", "loud": true }),
+        );
+        assert_eq!(response.data, Some(serde_json::json!("HELLO ADA")));
+
+        let response = request("SayHello", serde_json::json!({ "times": 2 }));
+        assert_eq!(response.error_code.as_deref(), Some("invalid_params"));
+
+        let response = request("SayHello", serde_json::json!({ "name": "" }));
+        assert_eq!(response.error_code.as_deref(), Some("not_found"));
+
+        let response = request("ping", serde_json::Value::Null);
+        assert_eq!(
+            (response.error.as_deref(), response.error_code.as_deref()),
+            (Some("no pong"), Some("failed"))
+        );
+
+        let response = request("Nope", serde_json::Value::Null);
+        assert_eq!(response.error.as_deref(), Some("Unknown command: Nope"));
+    }
+
+    #[test]
+    fn generates_manifest_entries() {
+        assert_eq!(Commands::command_names(), ["SayHello", "ping"]);
+
+        let manifest = GuestManifest::new("t", "T", "1.0", "d", vec![]).command_set::<Commands>();
+        assert_eq!(manifest.capabilities, ["custom_commands"]);
+        assert_eq!(manifest.commands, ["SayHello", "ping"]);
+
+        let group: CliCommandSchema = serde_json::from_value(manifest.cli[0].clone()).unwrap();
+        assert_eq!(
+            (group.name.as_str(), group.aliases.as_slice()),
+            ("greet", &["hi".to_string()][..])
+        );
+        let hello = &group.subcommands[0];
+        assert_eq!(hello.name, "say-hello");
+        assert_eq!(hello.about, "Greet someone");
+        assert_eq!(hello.command_name.as_deref(), Some("SayHello"));
+
+        let args = &hello.args;
+        assert_eq!(args.len(), 4);
+        assert!(args[0].required && args[0].long.is_none());
+        assert_eq!(args[0].help, "Who to greet");
+        assert_eq!(
+            (
+                args[1].short,
+                args[1].default_value.as_deref(),
+                args[1].value_type
+            ),
+            (Some('t'), Some("1"), CliArgType::Integer)
+        );
+        assert!(!args[1].required);
+        assert!(args[2].is_flag);
+        assert_eq!(args[2].long.as_deref(), Some("loud"));
+        assert_eq!(
+            (args[3].value_type, args[3].required),
+            (CliArgType::Path, false)
+        );
+    }
+}
@@ -83,8 +83,13 @@
 //! | `context`        | Runtime context queries                      |
 //! | `wasi`           | WASI module execution                        |
 //! | `files`          | User-provided file requests                  |
+//! | `macros` (default) | `#[commands]` / `#[derive(CommandArgs)]`, see [`commands`] |
 //! | `full`           | All of the above                             |
 
+// Lets the macros' `::diaryx_plugin_sdk::` paths resolve inside this crate.
+extern crate self as diaryx_plugin_sdk;
+
+pub mod commands;
 pub mod config;
 #[cfg(feature = "getrandom-shim")]
 pub mod getrandom_shim;
@@ -92,12 +97,23 @@ pub mod host;
 pub mod protocol;
 pub mod state;
 
+#[cfg(feature = "macros")]
+pub use diaryx_plugin_sdk_macros::{CommandArgs, commands};
+#[doc(hidden)]
+pub use serde_json;
+
 /// Convenience re-exports for the most commonly used types.
 pub mod prelude {
+    pub use crate::commands::{CommandError, CommandSet};
     pub use crate::host;
     pub use crate::protocol::{
         CURRENT_PROTOCOL_VERSION, CommandRequest, CommandResponse, ConfigReconcile, GuestEvent,
-        GuestManifest, GuestRequestedPermissions, LegacyMigration, PermissionRequest,
+        GuestManifest, GuestRequestedPermissions, GuestSchedule, GuestServiceProvision,
+        GuestServiceRequirement, GuestValidationIssue, GuestValidationRule, LegacyMigration,
+        PermissionRequest, RenderHtmlInput, RenderHtmlOutput, ResourceLimits, ScheduledPayload,
+        ValidationFixResponse, ValidationRequest, ValidationResponse,
     };
     pub use crate::state::PluginState;
+    #[cfg(feature = "macros")]
+    pub use crate::{CommandArgs, commands};
 }
@@ -33,6 +33,28 @@ pub struct GuestRequestedPermissions {
     pub reasons: HashMap<String, String>,
 }
 
+// ---------------------------------------------------------------------------
+// Resource limits
+// ---------------------------------------------------------------------------
+
+/// Resources this plugin needs per call. The workspace may override these
+/// under `plugins.<id>.limits`, and the host caps them at its own policy.
+///
+/// Mirrors `diaryx_core::plugin::limits::PluginResourceLimits`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Maximum linear memory, in 64 KiB WebAssembly pages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_pages: Option<u32>,
+    /// Wall-clock budget for a single call, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Fuel (roughly, executed instructions) for a single call. Setting this
+    /// turns on fuel metering.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<u64>,
+}
+
 // ---------------------------------------------------------------------------
 // Config reconciliation
 // ---------------------------------------------------------------------------
@@ -119,7 +141,7 @@ pub struct GuestManifest {
     /// Capability strings this plugin requests.
     ///
     /// Known values: `"file_events"`, `"workspace_events"`, `"custom_commands"`,
-    /// `"editor_extension"`, `"media_transcoder"`, `"command"`, `"lifecycle"`.
+    /// `"editor_extension"`, `"media_transcoder"`, `"scheduled"`, `"validation_rules"`, `"render_html"`, `"services"`, `"command"`, `"lifecycle"`.
     pub capabilities: Vec<String>,
     /// Serialized UI contribution values.
     ///
@@ -152,6 +174,22 @@ pub struct GuestManifest {
     /// calls them. Used for documentation, validation, and future marketplace tooling.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub server_functions: Vec<ServerFunctionDecl>,
+    /// Memory, time and fuel this plugin needs per call. `None` takes the
+    /// host's defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_limits: Option<ResourceLimits>,
+    /// Cron-like schedules for the `"scheduled"` capability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<GuestSchedule>,
+    /// Lint rules for the `"validation_rules"` capability.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validation_rules: Vec<GuestValidationRule>,
+    /// Service interfaces provided to other plugins (`"services"` capability).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provides: Vec<GuestServiceProvision>,
+    /// Service interfaces called on other plugins (`"services"` capability).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<GuestServiceRequirement>,
 }
 
 impl GuestManifest {
@@ -178,6 +216,11 @@ impl GuestManifest {
             conversions: vec![],
             min_app_version: None,
             server_functions: vec![],
+            resource_limits: None,
+            schedules: vec![],
+            validation_rules: vec![],
+            provides: vec![],
+            requires: vec![],
         }
     }
 
@@ -199,6 +242,20 @@ impl GuestManifest {
         self
     }
 
+    /// Declare the commands of a [`CommandSet`](crate::commands::CommandSet):
+    /// adds their names, their CLI subcommands, and the `custom_commands`
+    /// capability.
+    pub fn command_set<C: crate::commands::CommandSet>(mut self) -> Self {
+        self.commands.extend(C::command_names());
+        self.cli.extend(
+            C::cli_commands()
+                .into_iter()
+                .filter_map(|command| serde_json::to_value(command).ok()),
+        );
+        self.add_capability("custom_commands");
+        self
+    }
+
     /// Set requested permissions.
     pub fn requested_permissions(mut self, perms: GuestRequestedPermissions) -> Self {
         self.requested_permissions = Some(perms);
@@ -222,6 +279,342 @@ impl GuestManifest {
         self.server_functions = fns;
         self
     }
+
+    /// Declare the memory, time and fuel this plugin needs per call.
+    pub fn resource_limits(mut self, limits: ResourceLimits) -> Self {
+        self.resource_limits = Some(limits);
+        self
+    }
+
+    /// Declare schedules. Also add `"scheduled"` to the capabilities so the
+    /// host delivers them.
+    pub fn schedules(mut self, schedules: Vec<GuestSchedule>) -> Self {
+        self.schedules = schedules;
+        self
+    }
+
+    /// Declare lint rules. Also add `"validation_rules"` to the capabilities
+    /// and export `validate` (and `fix_validation_issue` if any issue carries
+    /// a fix).
+    pub fn validation_rules(mut self, rules: Vec<GuestValidationRule>) -> Self {
+        self.validation_rules = rules;
+        self
+    }
+
+    /// Declare a service interface this plugin implements for other plugins,
+    /// adding the `"services"` capability.
+    pub fn provides(mut self, provision: GuestServiceProvision) -> Self {
+        self.provides.push(provision);
+        self.add_capability("services");
+        self
+    }
+
+    /// Declare a service interface this plugin calls on another plugin,
+    /// adding the `"services"` capability. The host orders plugins so
+    /// providers load first and offers to install missing ones.
+    pub fn requires(mut self, requirement: GuestServiceRequirement) -> Self {
+        self.requires.push(requirement);
+        self.add_capability("services");
+        self
+    }
+
+    fn add_capability(&mut self, capability: &str) {
+        if !self.capabilities.iter().any(|c| c == capability) {
+            self.capabilities.push(capability.into());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Services
+// ---------------------------------------------------------------------------
+
+/// A versioned service interface a plugin implements, e.g. `templating`.
+///
+/// Mirrors `diaryx_core::plugin::services::ServiceProvision`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestServiceProvision {
+    /// Interface name.
+    pub interface: String,
+    /// Version of the interface implemented (`major.minor.patch`).
+    pub version: String,
+    /// Commands that make up the interface. Empty means callers may invoke
+    /// any command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+}
+
+impl GuestServiceProvision {
+    /// Provide `version` of `interface`.
+    pub fn new(interface: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            interface: interface.into(),
+            version: version.into(),
+            commands: vec![],
+        }
+    }
+
+    /// Set the commands that make up the interface.
+    pub fn commands<I, S>(mut self, commands: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.commands = commands.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// A service interface a plugin calls on another plugin.
+///
+/// Mirrors `diaryx_core::plugin::services::ServiceRequirement`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestServiceRequirement {
+    /// Interface name.
+    pub interface: String,
+    /// Accepted versions, as a Cargo-style range (`^1.2`, `>=1, <3`).
+    pub version: String,
+    /// Whether the plugin still works, with reduced functionality, without a
+    /// provider.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+impl GuestServiceRequirement {
+    /// Require a version of `interface` in `range`.
+    pub fn new(interface: impl Into<String>, range: impl Into<String>) -> Self {
+        Self {
+            interface: interface.into(),
+            version: range.into(),
+            optional: false,
+        }
+    }
+
+    /// Mark the requirement optional.
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schedules
+// ---------------------------------------------------------------------------
+
+/// A cron-like schedule. The host sends a `"scheduled"` event to `on_event`
+/// each time it comes due.
+///
+/// Mirrors `diaryx_core::plugin::schedule::PluginSchedule`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestSchedule {
+    /// Identifier passed back in [`ScheduledPayload::schedule_id`].
+    pub id: String,
+    /// Five-field cron expression (`minute hour day-of-month month
+    /// day-of-week`, local time) or `@hourly`/`@daily`/`@weekly`/`@monthly`/`@yearly`.
+    pub cron: String,
+    /// Human-readable description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl GuestSchedule {
+    /// Create a schedule with an ID and cron expression.
+    pub fn new(id: impl Into<String>, cron: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            cron: cron.into(),
+            description: None,
+        }
+    }
+
+    /// Set the human-readable description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+/// Payload of a `"scheduled"` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledPayload {
+    /// ID of the schedule that came due.
+    pub schedule_id: String,
+    /// Local time of the occurrence (`YYYY-MM-DDTHH:MM`).
+    pub scheduled_at: String,
+    /// Earlier occurrences the host coalesced into this one because it
+    /// wasn't running when they came due.
+    #[serde(default)]
+    pub missed: u32,
+    /// Poll with `host::cancellation::is_cancelled` during long runs.
+    #[serde(default)]
+    pub cancel_token: String,
+}
+
+// ---------------------------------------------------------------------------
+// Validation rules
+// ---------------------------------------------------------------------------
+
+/// A workspace lint rule. The host calls the guest's `validate` export
+/// whenever the workspace or a file is validated.
+///
+/// Mirrors `diaryx_core::plugin::validation::ValidationRule`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestValidationRule {
+    /// Rule identifier, reported back in [`GuestValidationIssue::rule`].
+    pub id: String,
+    /// Human-readable summary of what the rule checks.
+    pub description: String,
+}
+
+impl GuestValidationRule {
+    /// Create a rule with an ID and description.
+    pub fn new(id: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Input to the guest's `validate` export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationRequest {
+    /// Workspace root directory.
+    pub workspace_root: String,
+    /// Workspace-relative root index, for a whole-workspace validation.
+    #[serde(default)]
+    pub root_index: Option<String>,
+    /// Workspace-relative file, when a single file is validated.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+/// Output of the guest's `validate` export.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationResponse {
+    /// Issues found by the guest's rules.
+    #[serde(default)]
+    pub issues: Vec<GuestValidationIssue>,
+}
+
+/// An issue raised by a lint rule. Issues with a `fix` payload are
+/// auto-fixable: the host passes the issue back, unchanged, to the guest's
+/// `fix_validation_issue` export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuestValidationIssue {
+    /// ID of the rule that raised the issue.
+    pub rule: String,
+    /// Workspace-relative path of the offending file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// Plugin-defined fix payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<serde_json::Value>,
+}
+
+impl GuestValidationIssue {
+    /// Create an issue for a rule.
+    pub fn new(rule: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            file: None,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    /// Attach the issue to a workspace-relative file.
+    pub fn file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Make the issue auto-fixable with a payload describing the fix.
+    pub fn fix(mut self, fix: serde_json::Value) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+}
+
+/// Output of the guest's `fix_validation_issue` export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationFixResponse {
+    /// Whether the fix was applied.
+    pub success: bool,
+    /// What was done, or why it failed.
+    #[serde(default)]
+    pub message: String,
+}
+
+impl ValidationFixResponse {
+    /// A fix that was applied.
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+        }
+    }
+
+    /// A fix that could not be applied.
+    pub fn err(message: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            message: message.into(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Render hooks
+// ---------------------------------------------------------------------------
+
+/// Input to the guest's `render_html` export.
+///
+/// Guests with the `"render_html"` capability are asked to expand each
+/// occurrence of their `EditorExtension` syntax into static HTML when a site
+/// is published. The export must be pure: no host calls, same output for the
+/// same input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderHtmlInput {
+    /// Editor extension whose syntax matched.
+    pub extension_id: String,
+    /// Text between the extension's delimiters.
+    pub source: String,
+    /// Whether the syntax matched as a block.
+    #[serde(default)]
+    pub display_mode: bool,
+}
+
+/// Output of the guest's `render_html` export. With neither field set, the
+/// syntax is left as written.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderHtmlOutput {
+    /// Static HTML replacing the syntax.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// Why the syntax couldn't be rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RenderHtmlOutput {
+    /// Rendered HTML.
+    pub fn ok(html: impl Into<String>) -> Self {
+        Self {
+            html: Some(html.into()),
+            error: None,
+        }
+    }
+
+    /// The syntax could not be rendered.
+    pub fn err(message: impl Into<String>) -> Self {
+        Self {
+            html: None,
+            error: Some(message.into()),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -257,11 +650,22 @@ pub struct GuestEvent {
     /// Known values:
     /// - `"workspace_opened"`, `"workspace_closed"`, `"workspace_changed"`, `"workspace_committed"`
     /// - `"file_saved"`, `"file_created"`, `"file_deleted"`, `"file_moved"`
+    /// - `"scheduled"` (see [`ScheduledPayload`])
     pub event_type: String,
     /// Event-specific payload (varies by event type).
     pub payload: serde_json::Value,
 }
 
+impl GuestEvent {
+    /// The payload of a `"scheduled"` event, or `None` for other events.
+    pub fn scheduled(&self) -> Option<ScheduledPayload> {
+        if self.event_type != "scheduled" {
+            return None;
+        }
+        serde_json::from_value(self.payload.clone()).ok()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Commands
 // ---------------------------------------------------------------------------
@@ -412,6 +816,88 @@ mod tests {
         assert_eq!(parsed.server_functions[0].name, "sync_ws");
     }
 
+    #[test]
+    fn scheduled_event_payload() {
+        let manifest = GuestManifest::new(
+            "diaryx.daily",
+            "Daily",
+            "1.0.0",
+            "Daily reminders",
+            vec!["scheduled".into()],
+        )
+        .schedules(vec![GuestSchedule::new("morning", "0 9 * * *")]);
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""schedules":[{"id":"morning","cron":"0 9 * * *"}]"#));
+
+        let event: GuestEvent = serde_json::from_str(
+            r#"{"event_type":"scheduled","payload":{"schedule_id":"morning","scheduled_at":"2026-03-02T09:00","missed":2,"cancel_token":"schedule:morning"}}"#,
+        )
+        .unwrap();
+        let payload = event.scheduled().unwrap();
+        assert_eq!(payload.schedule_id, "morning");
+        assert_eq!(payload.missed, 2);
+    }
+
+    #[test]
+    fn validation_rules_roundtrip() {
+        let manifest = GuestManifest::new(
+            "diaryx.lint",
+            "Lint",
+            "1.0.0",
+            "Journal lint rules",
+            vec!["validation_rules".into()],
+        )
+        .validation_rules(vec![GuestValidationRule::new(
+            "daily-mood",
+            "Daily entries must have a mood",
+        )]);
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""validation_rules":[{"id":"daily-mood""#));
+
+        let response = ValidationResponse {
+            issues: vec![
+                GuestValidationIssue::new("daily-mood", "missing mood")
+                    .file("journal/today.md")
+                    .fix(serde_json::json!({ "mood": "neutral" })),
+            ],
+        };
+        let parsed: ValidationResponse =
+            serde_json::from_str(&serde_json::to_string(&response).unwrap()).unwrap();
+        assert_eq!(parsed.issues, response.issues);
+    }
+
+    #[test]
+    fn services_add_capability_once() {
+        let manifest = GuestManifest::new("diaryx.export", "Export", "1.0.0", "Export", vec![])
+            .provides(GuestServiceProvision::new("export", "1.0.0").commands(["Export"]))
+            .requires(GuestServiceRequirement::new("templating", "^1").optional());
+        assert_eq!(manifest.capabilities, vec!["services"]);
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(
+            r#""provides":[{"interface":"export","version":"1.0.0","commands":["Export"]}]"#
+        ));
+        assert!(
+            json.contains(
+                r#""requires":[{"interface":"templating","version":"^1","optional":true}]"#
+            )
+        );
+    }
+
+    #[test]
+    fn render_html_roundtrip() {
+        let input: RenderHtmlInput =
+            serde_json::from_str(r#"{"extension_id":"mathInline","source":"x^2"}"#).unwrap();
+        assert!(!input.display_mode);
+        assert_eq!(
+            serde_json::to_string(&RenderHtmlOutput::ok("<math></math>")).unwrap(),
+            r#"{"html":"<math></math>"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&RenderHtmlOutput::default()).unwrap(),
+            "{}"
+        );
+    }
+
     #[test]
     fn manifest_server_functions_defaults_empty() {
         let json =
@@ -0,0 +1,708 @@
+//! Procedural macros for typed plugin commands.
+//!
+//! Re-exported by `diaryx_plugin_sdk` (feature `macros`); see
+//! `diaryx_plugin_sdk::commands` for the runtime side and full docs.
+//!
+//! - `#[derive(CommandArgs)]` describes a params struct as CLI arguments.
+//! - `#[commands]` on an inherent `impl` turns its `#[command]` functions
+//!   into a `CommandSet`: the dispatch table plus the `commands` and `cli`
+//!   manifest entries.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, FnArg, GenericArgument, ImplItem,
+    ItemImpl, Lit, LitChar, LitStr, Meta, PathArguments, Result, Type, parse_macro_input,
+};
+
+/// Derive `diaryx_plugin_sdk::commands::CommandArgs` for a struct with named
+/// fields.
+///
+/// Each field becomes a CLI argument named after the field, with its doc
+/// comment as help text. `bool` fields are flags, `Option<_>` fields are
+/// optional, and everything else is a required positional argument unless it
+/// gets a flag or a default. Field options, in `#[arg(...)]`:
+///
+/// - `short = 'f'`, `long = "folder"` — make the argument a named option
+/// - `default = "emails"` — default value (the argument becomes optional)
+/// - `skip` — leave the field out of the CLI (it must deserialize without it)
+#[proc_macro_derive(CommandArgs, attributes(arg))]
+pub fn derive_command_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_command_args(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Turn the `#[command]` functions of an inherent `impl` into a
+/// `diaryx_plugin_sdk::commands::CommandSet`.
+///
+/// Handlers take no arguments or a single `CommandArgs` struct and return
+/// `Result<T, E>` where `T: Serialize` and `E: Into<CommandError>`. Function
+/// options, in `#[command(...)]`:
+///
+/// - `name = "ParseDayOne"` — command name (default: PascalCase of the fn)
+/// - `cli` or `cli = "parse-dayone"` — also expose the command on the CLI
+///   (default name: kebab-case of the fn)
+/// - `about = "..."` — CLI help (default: first line of the doc comment)
+/// - `alias = "..."` — CLI alias, repeatable
+/// - `no_workspace` — don't resolve a workspace before running
+///
+/// A `#[cfg(...)]` on a handler also applies to its dispatch arm, its name
+/// and its CLI entry.
+///
+/// `#[commands(cli = "import", about = "...", alias = "imp")]` nests the CLI
+/// commands under one parent subcommand.
+#[proc_macro_attribute]
+pub fn commands(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut group = CliGroup::default();
+    let parser = syn::meta::parser(|meta| group.parse(meta));
+    parse_macro_input!(attr with parser);
+    let item = parse_macro_input!(item as ItemImpl);
+    expand_commands(group, item)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// ============================================================================
+// #[derive(CommandArgs)]
+// ============================================================================
+
+#[derive(Default)]
+struct ArgOptions {
+    short: Option<LitChar>,
+    long: Option<LitStr>,
+    default: Option<LitStr>,
+    skip: bool,
+}
+
+impl ArgOptions {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("arg")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("short") {
+                    options.short = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("long") {
+                    options.long = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    options.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else {
+                    return Err(meta.error("expected `short`, `long`, `default` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+enum ValueKind {
+    Flag,
+    String,
+    Path,
+    Integer,
+    Float,
+    Boolean,
+}
+
+fn expand_command_args(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "CommandArgs needs named fields")),
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                input,
+                "CommandArgs can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut args = Vec::new();
+    for field in fields {
+        let options = ArgOptions::parse(&field.attrs)?;
+        if options.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        let help = doc_string(&field.attrs).unwrap_or_default();
+        let (ty, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner, true),
+            None => (&field.ty, false),
+        };
+        let kind = match value_kind(ty) {
+            ValueKind::Flag if optional => ValueKind::Boolean,
+            kind => kind,
+        };
+        let is_flag = matches!(kind, ValueKind::Flag);
+        let value_type = match kind {
+            ValueKind::Flag | ValueKind::Boolean => quote!(Boolean),
+            ValueKind::String => quote!(String),
+            ValueKind::Path => quote!(Path),
+            ValueKind::Integer => quote!(Integer),
+            ValueKind::Float => quote!(Float),
+        };
+        let long = match (&options.long, &options.short) {
+            (Some(long), _) => quote!(::std::option::Option::Some(#long.into())),
+            (None, None) if is_flag => {
+                let long = name.replace('_', "-");
+                quote!(::std::option::Option::Some(#long.into()))
+            }
+            (None, _) => quote!(::std::option::Option::None),
+        };
+        let short = match &options.short {
+            Some(short) => quote!(::std::option::Option::Some(#short)),
+            None => quote!(::std::option::Option::None),
+        };
+        let required = !optional && !is_flag && options.default.is_none();
+        let default_value = match &options.default {
+            Some(default) => quote!(::std::option::Option::Some(#default.into())),
+            None => quote!(::std::option::Option::None),
+        };
+        args.push(quote! {
+            ::diaryx_plugin_sdk::commands::CliArgSchema {
+                name: #name.into(),
+                help: #help.into(),
+                value_type: ::diaryx_plugin_sdk::commands::CliArgType::#value_type,
+                required: #required,
+                default_value: #default_value,
+                short: #short,
+                long: #long,
+                is_flag: #is_flag,
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::diaryx_plugin_sdk::commands::CommandArgs for #ident #ty_generics
+        #where_clause
+        {
+            fn cli_args() -> ::std::vec::Vec<::diaryx_plugin_sdk::commands::CliArgSchema> {
+                ::std::vec![#(#args),*]
+            }
+        }
+    })
+}
+
+/// `T` if `ty` is `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+fn value_kind(ty: &Type) -> ValueKind {
+    let ident = match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        Type::Reference(reference) => return value_kind(&reference.elem),
+        _ => None,
+    };
+    match ident.as_deref() {
+        Some("bool") => ValueKind::Flag,
+        Some("PathBuf" | "Path") => ValueKind::Path,
+        Some(
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "usize",
+        ) => ValueKind::Integer,
+        Some("f32" | "f64") => ValueKind::Float,
+        _ => ValueKind::String,
+    }
+}
+
+// ============================================================================
+// #[commands]
+// ============================================================================
+
+#[derive(Default)]
+struct CliGroup {
+    name: Option<LitStr>,
+    about: Option<LitStr>,
+    aliases: Vec<LitStr>,
+}
+
+impl CliGroup {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> Result<()> {
+        if meta.path.is_ident("cli") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("about") {
+            self.about = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("alias") {
+            self.aliases.push(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `cli`, `about` or `alias`"));
+        }
+        Ok(())
+    }
+}
+
+struct CommandOptions {
+    name: String,
+    cli: Option<String>,
+    about: Option<String>,
+    aliases: Vec<LitStr>,
+    requires_workspace: bool,
+}
+
+impl CommandOptions {
+    fn parse(attr: &Attribute, fn_name: &str) -> Result<Self> {
+        let mut options = Self {
+            name: to_pascal_case(fn_name),
+            cli: None,
+            about: None,
+            aliases: Vec::new(),
+            requires_workspace: true,
+        };
+        if matches!(attr.meta, Meta::Path(_)) {
+            return Ok(options);
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                options.name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("cli") {
+                options.cli = Some(if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<LitStr>()?.value()
+                } else {
+                    fn_name.replace('_', "-")
+                });
+            } else if meta.path.is_ident("about") {
+                options.about = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("alias") {
+                options.aliases.push(meta.value()?.parse()?);
+            } else if meta.path.is_ident("no_workspace") {
+                options.requires_workspace = false;
+            } else {
+                return Err(
+                    meta.error("expected `name`, `cli`, `about`, `alias` or `no_workspace`")
+                );
+            }
+            Ok(())
+        })?;
+        Ok(options)
+    }
+}
+
+fn expand_commands(group: CliGroup, mut item: ItemImpl) -> Result<TokenStream2> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new_spanned(
+            path,
+            "#[commands] goes on an inherent impl block",
+        ));
+    }
+
+    let mut names = Vec::new();
+    let mut cli = Vec::new();
+    let mut arms = Vec::new();
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(function) = impl_item else {
+            continue;
+        };
+        let Some(position) = function
+            .attrs
+            .iter()
+            .position(|a| a.path().is_ident("command"))
+        else {
+            continue;
+        };
+        let attr = function.attrs.remove(position);
+        let fn_ident = function.sig.ident.clone();
+        let cfgs: Vec<Attribute> = function
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("cfg"))
+            .cloned()
+            .collect();
+        let options = CommandOptions::parse(&attr, &fn_ident.to_string())?;
+
+        let args_ty = match function.sig.inputs.len() {
+            0 => None,
+            1 => match function.sig.inputs.first() {
+                Some(FnArg::Typed(arg)) => Some((*arg.ty).clone()),
+                _ => {
+                    return Err(Error::new_spanned(
+                        &function.sig.inputs,
+                        "command handlers don't take `self`",
+                    ));
+                }
+            },
+            _ => {
+                return Err(Error::new_spanned(
+                    &function.sig.inputs,
+                    "command handlers take at most one `CommandArgs` argument",
+                ));
+            }
+        };
+
+        let name = &options.name;
+        names.push(quote!(#(#cfgs)* names.push(#name.to_string());));
+
+        let run = match &args_ty {
+            Some(_) => quote!(::diaryx_plugin_sdk::commands::run(params, Self::#fn_ident)),
+            None => quote!(::diaryx_plugin_sdk::commands::run_without_args(Self::#fn_ident)),
+        };
+        arms.push(quote!(#(#cfgs)* #name => ::std::option::Option::Some(#run),));
+
+        if let Some(cli_name) = &options.cli {
+            let about = options
+                .about
+                .clone()
+                .or_else(|| doc_string(&function.attrs).map(|doc| first_line(&doc)))
+                .unwrap_or_default();
+            let aliases = &options.aliases;
+            let args = match &args_ty {
+                Some(ty) => {
+                    quote!(<#ty as ::diaryx_plugin_sdk::commands::CommandArgs>::cli_args())
+                }
+                None => quote!(::std::vec::Vec::new()),
+            };
+            let requires_workspace = options.requires_workspace;
+            cli.push(quote! {
+                #(#cfgs)*
+                cli.push(::diaryx_plugin_sdk::commands::CliCommandSchema {
+                    name: #cli_name.into(),
+                    about: #about.into(),
+                    aliases: ::std::vec![#(#aliases.into()),*],
+                    args: #args,
+                    command_name: ::std::option::Option::Some(#name.into()),
+                    requires_workspace: #requires_workspace,
+                    ..::std::default::Default::default()
+                });
+            });
+        }
+    }
+
+    let cli = quote! {{
+        #[allow(unused_mut)]
+        let mut cli = ::std::vec::Vec::new();
+        #(#cli)*
+        cli
+    }};
+    let cli_commands = match &group.name {
+        Some(group_name) => {
+            let about = group.about.as_ref().map(LitStr::value).unwrap_or_default();
+            let aliases = &group.aliases;
+            quote! {
+                ::std::vec![::diaryx_plugin_sdk::commands::CliCommandSchema {
+                    name: #group_name.into(),
+                    about: #about.into(),
+                    aliases: ::std::vec![#(#aliases.into()),*],
+                    subcommands: #cli,
+                    ..::std::default::Default::default()
+                }]
+            }
+        }
+        None => cli,
+    };
+
+    let self_ty = &item.self_ty;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::diaryx_plugin_sdk::commands::CommandSet for #self_ty #where_clause {
+            fn command_names() -> ::std::vec::Vec<::std::string::String> {
+                #[allow(unused_mut)]
+                let mut names = ::std::vec::Vec::new();
+                #(#names)*
+                names
+            }
+
+            fn cli_commands() -> ::std::vec::Vec<::diaryx_plugin_sdk::commands::CliCommandSchema> {
+                #cli_commands
+            }
+
+            fn dispatch(
+                command: &str,
+                params: ::diaryx_plugin_sdk::serde_json::Value,
+            ) -> ::std::option::Option<
+                ::std::result::Result<
+                    ::diaryx_plugin_sdk::serde_json::Value,
+                    ::diaryx_plugin_sdk::commands::CommandError,
+                >,
+            > {
+                let _ = &params;
+                match command {
+                    #(#arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// The `///` doc comment on an item, lines joined with `\n`.
+fn doc_string(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+fn first_line(doc: &str) -> String {
+    doc.lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn to_pascal_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn commands(group: CliGroup, item: ItemImpl) -> Result<String> {
+        expand_commands(group, item).map(|tokens| tokens.to_string())
+    }
+
+    fn error(result: Result<String>) -> String {
+        result.expect_err("expected a compile error").to_string()
+    }
+
+    #[test]
+    fn derives_cli_args_from_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Args {
+                /// Source folder
+                #[arg(short = 'f', long = "folder")]
+                folder: PathBuf,
+                /// Dry run
+                dry_run: bool,
+                limit: Option<u32>,
+                #[arg(default = "emails")]
+                target: String,
+                #[arg(skip)]
+                token: String,
+            }
+        };
+        let expanded = expand_command_args(&input).unwrap().to_string();
+
+        assert!(expanded.contains("impl :: diaryx_plugin_sdk :: commands :: CommandArgs for Args"));
+        for name in ["\"folder\"", "\"dry_run\"", "\"limit\"", "\"target\""] {
+            assert!(expanded.contains(name), "{name} missing from {expanded}");
+        }
+        assert!(!expanded.contains("\"token\""));
+        assert!(expanded.contains("\"Source folder\""));
+        assert!(expanded.contains("CliArgType :: Path"));
+        assert!(expanded.contains("'f'"));
+        // Flags get a long name from the field; optional values aren't
+        // required.
+        assert!(expanded.contains("\"dry-run\""));
+        assert!(expanded.contains("CliArgType :: Integer , required : false"));
+        assert!(expanded.contains("\"emails\""));
+    }
+
+    #[test]
+    fn rejects_unsupported_args_types() {
+        let tuple: DeriveInput = parse_quote!(
+            struct Args(String);
+        );
+        assert_eq!(
+            expand_command_args(&tuple).unwrap_err().to_string(),
+            "CommandArgs needs named fields"
+        );
+
+        let enumeration: DeriveInput = parse_quote!(
+            enum Args {
+                A,
+            }
+        );
+        assert_eq!(
+            expand_command_args(&enumeration).unwrap_err().to_string(),
+            "CommandArgs can only be derived for structs"
+        );
+
+        let bad_option: DeriveInput = parse_quote! {
+            struct Args {
+                #[arg(hidden)]
+                name: String,
+            }
+        };
+        assert_eq!(
+            expand_command_args(&bad_option).unwrap_err().to_string(),
+            "expected `short`, `long`, `default` or `skip`"
+        );
+    }
+
+    #[test]
+    fn expands_command_set() {
+        let item: ItemImpl = parse_quote! {
+            impl Commands {
+                /// Parse a Day One export.
+                ///
+                /// Longer help.
+                #[command(cli, alias = "dayone")]
+                fn parse_day_one(args: ParseArgs) -> Result<Entries, String> {
+                    todo!()
+                }
+
+                #[command(name = "ping", no_workspace)]
+                fn ping() -> Result<bool, String> {
+                    Ok(true)
+                }
+
+                fn helper() {}
+            }
+        };
+        let group = CliGroup {
+            name: Some(parse_quote!("import")),
+            ..Default::default()
+        };
+        let expanded = commands(group, item).unwrap();
+
+        // The `#[command]` attributes are consumed; the impl is kept.
+        assert!(!expanded.contains("# [command"));
+        assert!(expanded.contains("fn helper ()"));
+        assert!(expanded.contains(
+            "names . push (\"ParseDayOne\" . to_string ()) ; names . push (\"ping\" . to_string ()) ;"
+        ));
+        assert!(expanded.contains(
+            "\"ParseDayOne\" => :: std :: option :: Option :: Some (:: diaryx_plugin_sdk :: commands :: run (params , Self :: parse_day_one))"
+        ));
+        assert!(expanded.contains("run_without_args (Self :: ping)"));
+        // Only `parse_day_one` is on the CLI, nested under `import`.
+        assert!(expanded.contains("name : \"import\" . into ()"));
+        assert!(expanded.contains("\"parse-day-one\""));
+        assert!(expanded.contains("\"Parse a Day One export\""));
+        assert!(expanded.contains(
+            "< ParseArgs as :: diaryx_plugin_sdk :: commands :: CommandArgs > :: cli_args ()"
+        ));
+        assert!(!expanded.contains("\"ping\" . into ()"));
+    }
+
+    #[test]
+    fn cfg_on_a_handler_gates_its_entries() {
+        let item: ItemImpl = parse_quote! {
+            impl Commands {
+                #[cfg(feature = "eml")]
+                #[command(cli)]
+                fn parse_eml() -> Result<(), String> { Ok(()) }
+            }
+        };
+        let expanded = commands(CliGroup::default(), item).unwrap();
+
+        let cfg = "# [cfg (feature = \"eml\")]";
+        assert!(expanded.contains(&format!("{cfg} names . push (\"ParseEml\"")));
+        assert!(expanded.contains(&format!("{cfg} \"ParseEml\" =>")));
+        assert!(expanded.contains(&format!("{cfg} cli . push (")));
+    }
+
+    #[test]
+    fn rejects_bad_handler_signatures() {
+        let receiver: ItemImpl = parse_quote! {
+            impl Commands {
+                #[command]
+                fn run(&self) -> Result<(), String> { Ok(()) }
+            }
+        };
+        assert_eq!(
+            error(commands(CliGroup::default(), receiver)),
+            "command handlers don't take `self`"
+        );
+
+        let two_args: ItemImpl = parse_quote! {
+            impl Commands {
+                #[command]
+                fn run(a: A, b: B) -> Result<(), String> { Ok(()) }
+            }
+        };
+        assert_eq!(
+            error(commands(CliGroup::default(), two_args)),
+            "command handlers take at most one `CommandArgs` argument"
+        );
+
+        let trait_impl: ItemImpl = parse_quote! {
+            impl Runner for Commands {
+                #[command]
+                fn run() -> Result<(), String> { Ok(()) }
+            }
+        };
+        assert_eq!(
+            error(commands(CliGroup::default(), trait_impl)),
+            "#[commands] goes on an inherent impl block"
+        );
+
+        let unknown_option: ItemImpl = parse_quote! {
+            impl Commands {
+                #[command(hidden)]
+                fn run() -> Result<(), String> { Ok(()) }
+            }
+        };
+        assert_eq!(
+            error(commands(CliGroup::default(), unknown_option)),
+            "expected `name`, `cli`, `about`, `alias` or `no_workspace`"
+        );
+    }
+
+    #[test]
+    fn names_and_docs() {
+        assert_eq!(to_pascal_case("parse_day_one"), "ParseDayOne");
+        assert_eq!(to_pascal_case("_sync__now"), "SyncNow");
+        assert_eq!(first_line("Import notes.\nMore."), "Import notes");
+
+        let item: syn::ItemFn = parse_quote! {
+            /// First line.
+            ///
+            /// Second paragraph.
+            fn f() {}
+        };
+        assert_eq!(
+            doc_string(&item.attrs).as_deref(),
+            Some("First line.\n\nSecond paragraph.")
+        );
+        let ty: Type = parse_quote!(Option<std::path::PathBuf>);
+        assert!(matches!(
+            value_kind(option_inner(&ty).unwrap()),
+            ValueKind::Path
+        ));
+        assert!(option_inner(&parse_quote!(Vec<String>)).is_none());
+    }
+}
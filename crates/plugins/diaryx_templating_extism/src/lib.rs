@@ -440,6 +440,9 @@ pub fn manifest(_input: String) -> FnResult<String> {
         }),
     ])
     .commands(all_commands())
+    .provides(
+        GuestServiceProvision::new("templating", "1.0.0").commands(["RenderBody", "HasTemplates"]),
+    )
     .requested_permissions(GuestRequestedPermissions {
         defaults: serde_json::json!({
             "read_files": { "include": ["all"], "exclude": [] },